@@ -8,6 +8,7 @@
 use libfuzzer_sys::fuzz_target;
 
 use arbitrary::Arbitrary;
+use kenken_core::limits::INLINE_CAGE_CELLS;
 use kenken_core::puzzle::{Cage, CellId, Puzzle};
 use kenken_core::rules::{Op, Ruleset};
 use kenken_solver::{count_solutions_up_to_with_deductions, solve_one_with_deductions, DeductionTier};
@@ -24,7 +25,7 @@ struct FuzzPuzzle {
 struct FuzzCage {
     cells: Vec<u16>,
     op: u8,
-    target: i32,
+    target: i64,
 }
 
 impl FuzzPuzzle {
@@ -44,12 +45,14 @@ impl FuzzPuzzle {
 
                 // Convert cells, clamping to valid range
                 let max_cell = (n as u16) * (n as u16);
-                let cells: SmallVec<[CellId; 6]> = fc
+                let mut cells: SmallVec<[CellId; INLINE_CAGE_CELLS]> = fc
                     .cells
                     .iter()
                     .take(6) // Max 6 cells per cage
                     .map(|&c| CellId(c % max_cell))
                     .collect();
+                // Validation rejects unsorted cages; sort so they still reach the solver
+                cells.sort_unstable();
 
                 if cells.is_empty() {
                     return None;
@@ -0,0 +1,135 @@
+//! `benchmark --export-json`: results as a JSON array of `{"name", "unit", "value"}` entries,
+//! the format github-action-benchmark's custom tools read.
+//!
+//! Entries are written sorted by name, so a run's file lines up with the last one whatever
+//! order the benchmark measured in. JSON has no NaN or infinity, so a non-finite value is
+//! dropped with a warning rather than written as something a dashboard would plot.
+
+use serde_json::{Value, json};
+
+/// One measurement, named by `/`-separated path segments such as
+/// `solve/n=6/tier=hard/puzzles_per_sec`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BenchEntry {
+    pub name: String,
+    pub unit: String,
+    pub value: f64,
+}
+
+#[derive(Debug, Default)]
+pub struct BenchReport {
+    entries: Vec<BenchEntry>,
+}
+
+impl BenchReport {
+    /// Record `value` under `name`. A NaN or infinite value is dropped, with a warning on
+    /// stderr, and `false` is returned.
+    pub fn push(&mut self, name: impl Into<String>, unit: &str, value: f64) -> bool {
+        let name = name.into();
+        if !value.is_finite() {
+            eprintln!("warning: benchmark entry {name} is {value}; omitted from the export");
+            return false;
+        }
+        self.entries.push(BenchEntry {
+            name,
+            unit: unit.to_string(),
+            value,
+        });
+        true
+    }
+
+    /// Entries sorted by name; entries sharing a name keep the order they were pushed in.
+    pub fn entries(&self) -> Vec<&BenchEntry> {
+        let mut sorted: Vec<&BenchEntry> = self.entries.iter().collect();
+        sorted.sort_by(|a, b| a.name.cmp(&b.name));
+        sorted
+    }
+
+    /// The report as a pretty-printed JSON array, in [`BenchReport::entries`] order.
+    pub fn to_json(&self) -> String {
+        let entries: Vec<Value> = self
+            .entries()
+            .into_iter()
+            .map(|entry| json!({"name": entry.name, "unit": entry.unit, "value": entry.value}))
+            .collect();
+        serde_json::to_string_pretty(&Value::Array(entries)).expect("JSON values serialize")
+    }
+
+    /// Write [`BenchReport::to_json`] to `path`.
+    pub fn write(&self, path: &str) -> Result<(), String> {
+        std::fs::write(path, self.to_json() + "\n")
+            .map_err(|e| format!("failed to write --export-json {path}: {e}"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(report: &BenchReport) -> Vec<Value> {
+        match serde_json::from_str(&report.to_json()).unwrap() {
+            Value::Array(entries) => entries,
+            other => panic!("not an array: {other}"),
+        }
+    }
+
+    #[test]
+    fn entries_serialize_as_name_unit_value_objects() {
+        let mut report = BenchReport::default();
+        assert!(report.push("solve/n=4/tier=normal/puzzles_per_sec", "puzzles/s", 1250.5));
+        let entries = parse(&report);
+        assert_eq!(
+            entries,
+            vec![json!({
+                "name": "solve/n=4/tier=normal/puzzles_per_sec",
+                "unit": "puzzles/s",
+                "value": 1250.5
+            })]
+        );
+        let keys: Vec<&String> = entries[0].as_object().unwrap().keys().collect();
+        assert_eq!(keys, ["name", "unit", "value"]);
+    }
+
+    #[test]
+    fn whole_and_tiny_values_stay_numbers() {
+        let mut report = BenchReport::default();
+        report.push("a", "nodes", 12.0);
+        report.push("b", "nodes", 0.0);
+        report.push("c", "s", 1.5e-9);
+        let values: Vec<f64> = parse(&report)
+            .iter()
+            .map(|entry| entry["value"].as_f64().unwrap())
+            .collect();
+        assert_eq!(values, [12.0, 0.0, 1.5e-9]);
+        assert!(report.to_json().contains("\"value\": 12.0"));
+    }
+
+    #[test]
+    fn non_finite_values_are_omitted() {
+        let mut report = BenchReport::default();
+        assert!(!report.push("nan", "nodes", f64::NAN));
+        assert!(!report.push("inf", "puzzles/s", f64::INFINITY));
+        assert!(!report.push("neg_inf", "puzzles/s", f64::NEG_INFINITY));
+        assert!(report.push("finite", "nodes", 3.0));
+        let entries = parse(&report);
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0]["name"], "finite");
+        assert!(!report.to_json().contains("null"));
+    }
+
+    #[test]
+    fn entries_are_ordered_by_name_whatever_the_push_order() {
+        let names = ["solve/b", "gen/z", "solve/a", "gen/a"];
+        let mut forward = BenchReport::default();
+        let mut backward = BenchReport::default();
+        for (i, name) in names.iter().enumerate() {
+            forward.push(*name, "nodes", i as f64);
+        }
+        for (i, name) in names.iter().enumerate().rev() {
+            backward.push(*name, "nodes", i as f64);
+        }
+        assert_eq!(forward.to_json(), backward.to_json());
+        let sorted: Vec<&str> = forward.entries().iter().map(|e| e.name.as_str()).collect();
+        assert_eq!(sorted, ["gen/a", "gen/z", "solve/a", "solve/b"]);
+    }
+}
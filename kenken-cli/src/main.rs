@@ -2,14 +2,22 @@
 #[global_allocator]
 static GLOBAL: mimalloc::MiMalloc = mimalloc::MiMalloc;
 
-use kenken_core::format::sgt_desc::parse_keen_desc;
-use kenken_core::puzzle::{Cage, CellId, Puzzle};
-use kenken_core::rules::{Op, Ruleset};
+mod bench_json;
+#[cfg(feature = "pack")]
+mod pack;
+
+use bench_json::BenchReport;
+use kenken_core::format::sgt_desc::{infer_keen_desc_size, parse_keen_desc};
+use kenken_core::puzzle::Puzzle;
+use kenken_core::render::render_ascii;
+use kenken_core::rules::Ruleset;
+use kenken_solver::debug::{InspectAction, SearchInspector};
 use kenken_solver::{
-    DeductionTier, count_solutions_up_to_with_deductions, solve_one_with_deductions,
+    DeductionTier, SearchOptions, StatsSummary, count_solutions_up_to,
+    count_solutions_up_to_with_deductions, solve_one_with_options,
 };
-use smallvec::SmallVec;
-use std::time::Instant;
+use std::process::ExitCode;
+use std::time::{Duration, Instant};
 
 #[cfg(feature = "telemetry-subscriber")]
 fn init_tracing() {
@@ -29,24 +37,25 @@ fn usage() -> &'static str {
     "kenken-cli\n\
 \n\
 USAGE:\n\
-  kenken-cli solve --n <N> --desc <DESC> [--tier <none|easy|normal|hard>]\n\
+  kenken-cli solve --n <N> --desc <DESC> [--tier <none|easy|normal|hard>] [--pretty] [--inspect-at <NODES,...>]\n\
+    (--inspect-at dumps the search state to stderr when it reaches each node count)\n\
+  kenken-cli show --n <N> --desc <DESC>\n\
   kenken-cli count --n <N> --desc <DESC> [--tier <none|easy|normal|hard>] [--limit <L>]\n\
-  kenken-cli benchmark --n <N> --count <C> [--tier <none|easy|normal|hard>]\n\
+  kenken-cli benchmark --n <N> --count <C> [--tier <none|easy|normal|hard>] [--generated [--seed <S>]] [--export-json <PATH>]\n\
+    (--generated solves C generated puzzles and reports nodes per difficulty)\n\
+  kenken-cli verify --n <N> --desc <DESC> (--solution <D,D,...> | --solution-file <PATH>) [--unique]\n\
+  kenken-cli pack --out <PATH> --spec <N:DIFFICULTY:COUNT,...> [--seed <S>] [--minimize] [--dedupe] [--max-attempts <A>]\n\
+    (exits 3 after writing a partial pack when a bucket exhausts its attempts)\n\
 \n\
 EXAMPLES:\n\
   kenken-cli solve --n 2 --desc b__,a3a3 --tier normal\n\
+  kenken-cli solve --n 6 --desc <DESC> --tier none --inspect-at 1000000,5000000\n\
+  kenken-cli show --n 2 --desc b__,a3a3\n\
   kenken-cli count --n 2 --desc b__,a3a3 --limit 2\n\
-  kenken-cli benchmark --n 4 --count 10 --tier normal\n"
-}
-
-fn parse_tier(s: &str) -> Option<DeductionTier> {
-    match s {
-        "none" => Some(DeductionTier::None),
-        "easy" => Some(DeductionTier::Easy),
-        "normal" => Some(DeductionTier::Normal),
-        "hard" => Some(DeductionTier::Hard),
-        _ => None,
-    }
+  kenken-cli benchmark --n 4 --count 10 --tier normal\n\
+  kenken-cli benchmark --n 5 --count 20 --generated --export-json bench.json\n\
+  kenken-cli verify --n 2 --desc b__,a3a3 --solution 1,2,2,1 --unique\n\
+  kenken-cli pack --out pack.keen --spec 4:easy:200,5:normal:200,6:hard:100 --seed 42 --dedupe\n"
 }
 
 fn parse_arg_value(args: &[String], i: &mut usize) -> Result<String, String> {
@@ -56,15 +65,18 @@ fn parse_arg_value(args: &[String], i: &mut usize) -> Result<String, String> {
         .ok_or_else(|| "missing value".to_string())
 }
 
-fn main() {
+fn main() -> ExitCode {
     init_tracing();
-    if let Err(err) = run() {
-        eprintln!("{err}\n\n{}", usage());
-        std::process::exit(2);
+    match run() {
+        Ok(code) => code,
+        Err(err) => {
+            eprintln!("{err}\n\n{}", usage());
+            ExitCode::from(2)
+        }
     }
 }
 
-fn run() -> Result<(), String> {
+fn run() -> Result<ExitCode, String> {
     let args: Vec<String> = std::env::args().collect();
     if args.len() < 2 {
         return Err("missing command".to_string());
@@ -76,6 +88,19 @@ fn run() -> Result<(), String> {
     let mut tier: DeductionTier = DeductionTier::Normal;
     let mut limit: u32 = 2;
     let mut count: u32 = 1;
+    let mut pretty = false;
+    let mut solution: Option<String> = None;
+    let mut solution_file: Option<String> = None;
+    let mut unique = false;
+    let mut out: Option<String> = None;
+    let mut spec: Option<String> = None;
+    let mut seed: u64 = 0;
+    let mut minimize = false;
+    let mut dedupe = false;
+    let mut max_attempts: Option<u32> = None;
+    let mut generated = false;
+    let mut export_json: Option<String> = None;
+    let mut inspect_at: Option<Vec<u64>> = None;
 
     let mut i = 2usize;
     while i < args.len() {
@@ -89,7 +114,7 @@ fn run() -> Result<(), String> {
             }
             "--tier" => {
                 let v = parse_arg_value(&args, &mut i)?;
-                tier = parse_tier(&v).ok_or_else(|| "invalid --tier".to_string())?;
+                tier = v.parse().map_err(|e| format!("invalid --tier: {e}"))?;
             }
             "--limit" => {
                 let v = parse_arg_value(&args, &mut i)?;
@@ -103,9 +128,54 @@ fn run() -> Result<(), String> {
                     .parse::<u32>()
                     .map_err(|_| "invalid --count".to_string())?;
             }
+            "--pretty" => {
+                pretty = true;
+            }
+            "--solution" => {
+                solution = Some(parse_arg_value(&args, &mut i)?);
+            }
+            "--solution-file" => {
+                solution_file = Some(parse_arg_value(&args, &mut i)?);
+            }
+            "--unique" => {
+                unique = true;
+            }
+            "--out" => {
+                out = Some(parse_arg_value(&args, &mut i)?);
+            }
+            "--spec" => {
+                spec = Some(parse_arg_value(&args, &mut i)?);
+            }
+            "--seed" => {
+                let v = parse_arg_value(&args, &mut i)?;
+                seed = v.parse::<u64>().map_err(|_| "invalid --seed".to_string())?;
+            }
+            "--minimize" => {
+                minimize = true;
+            }
+            "--dedupe" => {
+                dedupe = true;
+            }
+            "--max-attempts" => {
+                let v = parse_arg_value(&args, &mut i)?;
+                max_attempts = Some(
+                    v.parse::<u32>()
+                        .map_err(|_| "invalid --max-attempts".to_string())?,
+                );
+            }
+            "--generated" => {
+                generated = true;
+            }
+            "--export-json" => {
+                export_json = Some(parse_arg_value(&args, &mut i)?);
+            }
+            "--inspect-at" => {
+                let v = parse_arg_value(&args, &mut i)?;
+                inspect_at = Some(parse_node_counts(&v)?);
+            }
             "--help" | "-h" => {
                 println!("{}", usage());
-                return Ok(());
+                return Ok(ExitCode::SUCCESS);
             }
             other => {
                 return Err(format!("unknown arg: {other}"));
@@ -114,6 +184,13 @@ fn run() -> Result<(), String> {
         i += 1;
     }
 
+    if cmd == "pack" {
+        let (Some(out), Some(spec)) = (out, spec) else {
+            return Err("'pack' requires --out and --spec".to_string());
+        };
+        return run_pack(out, &spec, seed, minimize, dedupe, max_attempts);
+    }
+
     let Some(n) = n else {
         return Err("missing required flag: --n".to_string());
     };
@@ -125,19 +202,33 @@ fn run() -> Result<(), String> {
             let Some(desc) = desc else {
                 return Err("'solve' requires --desc".to_string());
             };
-            let Ok(puzzle) = parse_keen_desc(n, &desc) else {
-                return Err("failed to parse --desc".to_string());
+            let puzzle = parse_desc(n, &desc)?;
+
+            let inspector = inspect_at.map(|nodes| {
+                SearchInspector::new(nodes, |view| {
+                    eprintln!("{view}");
+                    InspectAction::Continue
+                })
+            });
+            let options = SearchOptions {
+                inspector,
+                ..SearchOptions::default()
             };
-
-            let sol = solve_one_with_deductions(&puzzle, rules, tier).unwrap_or(None);
+            let sol = solve_one_with_options(&puzzle, rules, tier, options)
+                .map(|(sol, _)| sol)
+                .unwrap_or(None);
             let Some(sol) = sol else {
                 println!("no-solution");
-                return Ok(());
+                return Ok(ExitCode::SUCCESS);
             };
+            if pretty {
+                print!("{}", render_ascii(&puzzle, Some(&sol.grid)));
+                return Ok(ExitCode::SUCCESS);
+            }
             println!("n={}", sol.n);
             for r in 0..(sol.n as usize) {
-                let row = &sol.grid[r * (sol.n as usize)..(r + 1) * (sol.n as usize)];
-                let line = row
+                let line = sol
+                    .row(r)
                     .iter()
                     .map(|v| v.to_string())
                     .collect::<Vec<_>>()
@@ -145,30 +236,187 @@ fn run() -> Result<(), String> {
                 println!("{line}");
             }
         }
+        "show" => {
+            let Some(desc) = desc else {
+                return Err("'show' requires --desc".to_string());
+            };
+            let puzzle = parse_desc(n, &desc)?;
+            print!("{}", render_ascii(&puzzle, None));
+        }
         "count" => {
             let Some(desc) = desc else {
                 return Err("'count' requires --desc".to_string());
             };
-            let Ok(puzzle) = parse_keen_desc(n, &desc) else {
-                return Err("failed to parse --desc".to_string());
-            };
+            let puzzle = parse_desc(n, &desc)?;
 
             let cnt =
                 count_solutions_up_to_with_deductions(&puzzle, rules, tier, limit).unwrap_or(0);
             println!("{cnt}");
         }
         "benchmark" => {
-            benchmark_puzzles(n, count, tier, rules)?;
+            let mut report = BenchReport::default();
+            if generated {
+                benchmark_generated(n, count, tier, seed, &mut report)?;
+            } else {
+                benchmark_puzzles(n, count, tier, rules, &mut report)?;
+            }
+            if let Some(path) = export_json {
+                report.write(&path)?;
+            }
+        }
+        "verify" => {
+            let Some(desc) = desc else {
+                return Err("'verify' requires --desc".to_string());
+            };
+            let puzzle = parse_desc(n, &desc)?;
+            let grid = match (solution, solution_file) {
+                (Some(digits), None) => parse_solution(&digits)?,
+                (None, Some(path)) => {
+                    let text = std::fs::read_to_string(&path)
+                        .map_err(|e| format!("failed to read --solution-file {path}: {e}"))?;
+                    parse_solution(&text)?
+                }
+                _ => {
+                    return Err(
+                        "'verify' requires exactly one of --solution or --solution-file"
+                            .to_string(),
+                    );
+                }
+            };
+            return verify_solution(&puzzle, rules, &grid, unique);
         }
         _ => {
             return Err(format!("unknown command: {cmd}"));
         }
     }
 
-    Ok(())
+    Ok(ExitCode::SUCCESS)
+}
+
+#[cfg(feature = "pack")]
+fn run_pack(
+    out: String,
+    spec: &str,
+    seed: u64,
+    minimize: bool,
+    dedupe: bool,
+    max_attempts: Option<u32>,
+) -> Result<ExitCode, String> {
+    pack::run_pack(&pack::PackOptions {
+        out,
+        buckets: pack::parse_spec(spec)?,
+        seed,
+        minimize,
+        dedupe,
+        max_attempts,
+    })
+}
+
+#[cfg(not(feature = "pack"))]
+fn run_pack(
+    _out: String,
+    _spec: &str,
+    _seed: u64,
+    _minimize: bool,
+    _dedupe: bool,
+    _max_attempts: Option<u32>,
+) -> Result<ExitCode, String> {
+    Err("'pack' requires kenken-cli's `pack` feature".to_string())
+}
+
+/// `--desc` at `--n`; when it fails to parse but its block structure fits another size, the
+/// error names that size instead of the parse failure.
+fn parse_desc(n: u8, desc: &str) -> Result<Puzzle, String> {
+    parse_keen_desc(n, desc).map_err(|e| match infer_keen_desc_size(desc) {
+        Some(m) if m != n => format!("desc appears to encode a {m}x{m} grid but --n {n} was given"),
+        _ => format!("failed to parse --desc: {e}"),
+    })
+}
+
+/// `--inspect-at`: comma-separated node counts.
+fn parse_node_counts(text: &str) -> Result<Vec<u64>, String> {
+    text.split(',')
+        .map(|count| {
+            count
+                .trim()
+                .parse::<u64>()
+                .map_err(|_| format!("invalid --inspect-at node count {count:?}"))
+        })
+        .collect()
 }
 
-fn benchmark_puzzles(n: u8, count: u32, tier: DeductionTier, rules: Ruleset) -> Result<(), String> {
+/// Digits separated by commas and/or whitespace, so a `--solution-file` can hold one row
+/// per line.
+fn parse_solution(text: &str) -> Result<Vec<u8>, String> {
+    text.split(|c: char| c == ',' || c.is_whitespace())
+        .filter(|tok| !tok.is_empty())
+        .map(|tok| {
+            tok.parse::<u8>()
+                .map_err(|_| format!("invalid solution digit: {tok}"))
+        })
+        .collect()
+}
+
+/// Print `valid` or `invalid: <violation>`, then with `unique` whether any other grid solves
+/// the puzzle. Exits 1 on an invalid or non-unique solution.
+fn verify_solution(
+    puzzle: &Puzzle,
+    rules: Ruleset,
+    grid: &[u8],
+    unique: bool,
+) -> Result<ExitCode, String> {
+    puzzle
+        .validate(rules)
+        .map_err(|e| format!("invalid puzzle: {e}"))?;
+
+    let verdict = puzzle
+        .check_solution_with_rules(grid, rules)
+        .map_err(|e| e.to_string());
+    #[cfg(feature = "verify")]
+    if let Err(msg) = cross_check(puzzle, grid, &verdict) {
+        eprintln!("{msg}");
+        return Ok(ExitCode::FAILURE);
+    }
+    if let Err(violation) = verdict {
+        println!("invalid: {violation}");
+        return Ok(ExitCode::FAILURE);
+    }
+    println!("valid");
+
+    if unique {
+        let count =
+            count_solutions_up_to(puzzle, rules, 2).map_err(|e| format!("count failed: {e}"))?;
+        if count > 1 {
+            println!("not unique: another solution exists");
+            return Ok(ExitCode::FAILURE);
+        }
+        println!("unique");
+    }
+    Ok(ExitCode::SUCCESS)
+}
+
+/// Re-check with kenken-verify's extracted checker and fail loudly if it disagrees with the
+/// core check.
+#[cfg(feature = "verify")]
+fn cross_check(puzzle: &Puzzle, grid: &[u8], core: &Result<(), String>) -> Result<(), String> {
+    match (core, kenken_verify::verify_solution(puzzle, grid)) {
+        (Ok(()), Err(theirs)) => Err(format!(
+            "checkers disagree: core accepts, kenken-verify rejects ({theirs})"
+        )),
+        (Err(ours), Ok(())) => Err(format!(
+            "checkers disagree: core rejects ({ours}), kenken-verify accepts"
+        )),
+        _ => Ok(()),
+    }
+}
+
+fn benchmark_puzzles(
+    n: u8,
+    count: u32,
+    tier: DeductionTier,
+    rules: Ruleset,
+    report: &mut BenchReport,
+) -> Result<(), String> {
     // Generate benchmark puzzle using cyclic Latin square pattern
     // For sizes 2-16: Uses SGT format
     // For sizes 17-32: Creates Puzzle objects directly
@@ -181,79 +429,139 @@ fn benchmark_puzzles(n: u8, count: u32, tier: DeductionTier, rules: Ruleset) ->
 
     let start = Instant::now();
     let mut solved = 0u32;
+    let mut runs = Vec::with_capacity(count as usize);
 
     for _ in 0..count {
-        if solve_one_with_deductions(&puzzle, rules, tier)
-            .unwrap_or(None)
-            .is_some()
+        if let Ok((solution, stats)) =
+            solve_one_with_options(&puzzle, rules, tier, SearchOptions::default())
         {
-            solved += 1;
+            solved += u32::from(solution.is_some());
+            runs.push(stats);
         }
     }
 
-    let elapsed = start.elapsed().as_secs_f64();
-    let rate = if elapsed > 0.0 {
-        solved as f64 / elapsed
-    } else {
-        0.0
-    };
-
+    let rate = solve_rate(solved, start.elapsed());
     println!("Puzzles/second: {:.3}", rate);
+    let summary: StatsSummary = runs.into_iter().collect();
+    for (label, dist) in [
+        ("Nodes", summary.nodes),
+        ("Assignments", summary.assignments),
+    ] {
+        println!(
+            "{label}: mean {:.1}, median {}, p95 {}",
+            dist.mean, dist.median, dist.p95
+        );
+    }
 
+    let prefix = format!("solve/n={n}/tier={tier}");
+    report.push(format!("{prefix}/puzzles_per_sec"), "puzzles/s", rate);
+    push_summary(report, &prefix, &summary);
     Ok(())
 }
 
-fn get_benchmark_puzzle(n: u8) -> Result<Puzzle, String> {
-    // Return all-singleton benchmark puzzles using cyclic Latin square pattern.
-    // Each cell is its own 1-cell cage with value: ((row + col) % n) + 1
-    //
-    // For sizes 2-16: Use SGT format (string-based)
-    // For sizes 17-32: Create Puzzle objects directly (avoids SGT format 16-cell limit)
-
-    if n <= 16 {
-        // Use SGT format for sizes 2-16
-        let total_positions = 2 * (n as usize) * ((n as usize) - 1) + 1;
-        let block_struct = format!("_{}", total_positions);
-
-        // Generate clues: cyclic pattern a1 a2 ... an a2 a3 ... a1 etc.
-        // For cyclic Latin square: cell at row i, col j has value ((i+j) % n) + 1
-        let mut clues = String::new();
-        for cell_idx in 0..(n as usize * n as usize) {
-            let row = cell_idx / (n as usize);
-            let col = cell_idx % (n as usize);
-            let value = ((row + col) % (n as usize)) + 1;
-            clues.push('a');
-            clues.push_str(&value.to_string());
-        }
+/// `benchmark --generated`: generate `count` `n`x`n` puzzles from seeds `seed`, `seed + 1`, ...,
+/// then solve each at `tier`, timing the solves only, and report nodes and assignments per
+/// difficulty.
+#[cfg(feature = "pack")]
+fn benchmark_generated(
+    n: u8,
+    count: u32,
+    tier: DeductionTier,
+    seed: u64,
+    report: &mut BenchReport,
+) -> Result<(), String> {
+    use kenken_gen::{GenerateConfig, generate_with_stats};
+    use kenken_solver::DifficultyTier;
 
-        let desc = format!("{},{}", block_struct, clues);
-        parse_keen_desc(n, &desc)
-            .map_err(|e| format!("Failed to parse SGT format for n={}: {}", n, e))
-    } else if n <= 32 {
-        // Create Puzzle object directly for sizes 17-32
-        let mut cages = Vec::new();
-
-        // Each cell is a 1-cell cage with value ((row + col) % n) + 1
-        for cell_idx in 0..(n as usize * n as usize) {
-            let row = cell_idx / (n as usize);
-            let col = cell_idx % (n as usize);
-            let value = ((row + col) % (n as usize)) + 1;
-
-            let cells: SmallVec<[CellId; 6]> = vec![CellId(cell_idx as u16)].into_iter().collect();
-            let cage = Cage {
-                cells,
-                op: Op::Eq,
-                target: value as i32,
-            };
-            cages.push(cage);
+    let rules = Ruleset::keen_baseline();
+    let mut runs: Vec<Vec<kenken_solver::SolveStats>> = vec![Vec::new(); DifficultyTier::ALL.len()];
+    let mut solving = Duration::ZERO;
+    let mut solved = 0u32;
+    for offset in 0..count {
+        let seed = seed.wrapping_add(u64::from(offset));
+        let generated = generate_with_stats(GenerateConfig::keen_baseline(n, seed))
+            .map_err(|e| format!("generation failed for seed {seed}: {e}"))?;
+        let start = Instant::now();
+        let (solution, stats) =
+            solve_one_with_options(&generated.puzzle, rules, tier, SearchOptions::default())
+                .map_err(|e| format!("solve failed for seed {seed}: {e}"))?;
+        solving += start.elapsed();
+        solved += u32::from(solution.is_some());
+        let slot = DifficultyTier::ALL
+            .iter()
+            .position(|&difficulty| difficulty == generated.difficulty)
+            .expect("every difficulty is in ALL");
+        runs[slot].push(stats);
+    }
+
+    let rate = solve_rate(solved, solving);
+    println!("Puzzles/second: {:.3}", rate);
+    let prefix = format!("generated/n={n}/tier={tier}");
+    report.push(format!("{prefix}/puzzles_per_sec"), "puzzles/s", rate);
+    for (difficulty, runs) in DifficultyTier::ALL.into_iter().zip(runs) {
+        if runs.is_empty() {
+            continue;
         }
+        let summary: StatsSummary = runs.into_iter().collect();
+        println!(
+            "{difficulty}: {} puzzles, nodes mean {:.1}, median {}, p95 {}",
+            summary.count, summary.nodes.mean, summary.nodes.median, summary.nodes.p95
+        );
+        let prefix = format!("{prefix}/difficulty={difficulty}");
+        report.push(format!("{prefix}/count"), "puzzles", summary.count as f64);
+        push_summary(report, &prefix, &summary);
+    }
+    Ok(())
+}
+
+#[cfg(not(feature = "pack"))]
+fn benchmark_generated(
+    _n: u8,
+    _count: u32,
+    _tier: DeductionTier,
+    _seed: u64,
+    _report: &mut BenchReport,
+) -> Result<(), String> {
+    Err("'benchmark --generated' requires kenken-cli's `pack` feature".to_string())
+}
 
-        Ok(Puzzle { n, cages })
+/// Solved puzzles per second over `elapsed`; 0 when no time was measured.
+fn solve_rate(solved: u32, elapsed: Duration) -> f64 {
+    let elapsed = elapsed.as_secs_f64();
+    if elapsed > 0.0 {
+        solved as f64 / elapsed
     } else {
-        Err(format!("Grid size {} not supported. Max: 32x32", n))
+        0.0
+    }
+}
+
+/// `<prefix>/nodes_{mean,median,p95}` and the same for assignments.
+fn push_summary(report: &mut BenchReport, prefix: &str, summary: &StatsSummary) {
+    for (counter, dist) in [
+        ("nodes", summary.nodes),
+        ("assignments", summary.assignments),
+    ] {
+        report.push(format!("{prefix}/{counter}_mean"), counter, dist.mean);
+        report.push(
+            format!("{prefix}/{counter}_median"),
+            counter,
+            dist.median as f64,
+        );
+        report.push(format!("{prefix}/{counter}_p95"), counter, dist.p95 as f64);
     }
 }
 
+fn get_benchmark_puzzle(n: u8) -> Result<Puzzle, String> {
+    // All-singleton benchmark puzzles over the cyclic Latin square ((row + col) % n) + 1.
+    if !(2..=32).contains(&n) {
+        return Err(format!("Grid size {} not supported. Max: 32x32", n));
+    }
+
+    kenken_gen::adversarial::singleton_grid(n)
+        .map(|adversarial| adversarial.puzzle)
+        .map_err(|e| format!("Failed to build benchmark puzzle for n={}: {}", n, e))
+}
+
 #[cfg(test)]
 mod bench_puzzle_tests {
     use super::*;
@@ -0,0 +1,288 @@
+//! `pack`: generate a puzzle bank for the game's content pipeline.
+//!
+//! Buckets are filled in spec order, each from one [`generate_stream`] whose puzzles are
+//! written to the bank as soon as they are delivered, so memory stays bounded by the dedupe
+//! keys. Every bucket's stream seed derives from `--seed` and the bucket, and the stream
+//! derives each draw's from that, so a run replays exactly.
+
+use std::collections::HashSet;
+use std::fs::File;
+use std::io::BufWriter;
+use std::ops::ControlFlow;
+use std::process::ExitCode;
+
+use kenken_core::rules::Ruleset;
+use kenken_gen::{
+    DifficultyGoal, GenerateConfig, GeneratedPuzzleWithStats, MinimizeConfig, StreamEnd,
+    generate_stream, minimize_puzzle,
+};
+use kenken_io::rkyv_bank::{BankRecord, BankWriter};
+use kenken_solver::{DifficultyTier, capabilities};
+
+/// Exit code of a run that wrote a partial pack because some bucket exhausted its attempts.
+pub const PARTIAL_PACK_EXIT: u8 = 3;
+
+/// Consecutive duplicate draws after which a `--dedupe` bucket counts as exhausted.
+const MAX_DUPLICATE_STREAK: u32 = 64;
+
+/// One `n:difficulty:count` entry of `--spec`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PackBucket {
+    pub n: u8,
+    pub difficulty: DifficultyTier,
+    pub count: u32,
+}
+
+#[derive(Debug, Clone)]
+pub struct PackOptions {
+    pub out: String,
+    pub buckets: Vec<PackBucket>,
+    pub seed: u64,
+    pub minimize: bool,
+    pub dedupe: bool,
+    /// Per-puzzle generation attempts; `None` keeps the generator's default.
+    pub max_attempts: Option<u32>,
+}
+
+/// Parse `n:difficulty:count[,n:difficulty:count...]`.
+pub fn parse_spec(spec: &str) -> Result<Vec<PackBucket>, String> {
+    spec.split(',')
+        .map(str::trim)
+        .filter(|entry| !entry.is_empty())
+        .map(|entry| {
+            let invalid = |why: String| format!("invalid --spec entry '{entry}': {why}");
+            let [n, difficulty, count] = entry.split(':').collect::<Vec<_>>()[..] else {
+                return Err(invalid("expected n:difficulty:count".to_string()));
+            };
+            Ok(PackBucket {
+                n: n.parse().map_err(|_| invalid(format!("bad size '{n}'")))?,
+                difficulty: difficulty.parse().map_err(|e| invalid(format!("{e}")))?,
+                count: count
+                    .parse()
+                    .map_err(|_| invalid(format!("bad count '{count}'")))?,
+            })
+        })
+        .collect::<Result<Vec<_>, _>>()
+        .and_then(|buckets| {
+            if buckets.is_empty() {
+                Err("--spec names no buckets".to_string())
+            } else {
+                Ok(buckets)
+            }
+        })
+}
+
+#[derive(Debug, Default)]
+struct BucketReport {
+    written: u32,
+    duplicates: u32,
+    attempts: u64,
+    exhausted: bool,
+}
+
+/// Build the pack, print a per-bucket summary, and exit 0, or [`PARTIAL_PACK_EXIT`] when a
+/// bucket came up short.
+pub fn run_pack(options: &PackOptions) -> Result<ExitCode, String> {
+    let rules = Ruleset::keen_baseline();
+    let file = File::create(&options.out)
+        .map_err(|e| format!("failed to create --out {}: {e}", options.out))?;
+    let mut writer = BankWriter::new(BufWriter::new(file))
+        .map_err(|e| format!("failed to write {}: {e}", options.out))?;
+    let mut seen = HashSet::new();
+
+    let mut reports = Vec::with_capacity(options.buckets.len());
+    for (index, &bucket) in options.buckets.iter().enumerate() {
+        let report = fill_bucket(options, index, bucket, rules, &mut seen, &mut writer)?;
+        if report.exhausted {
+            eprintln!(
+                "warning: {}x{} {} exhausted its attempts after {} of {} puzzles",
+                bucket.n, bucket.n, bucket.difficulty, report.written, bucket.count
+            );
+        }
+        reports.push(report);
+    }
+    writer
+        .finish()
+        .map_err(|e| format!("failed to write {}: {e}", options.out))?;
+
+    println!(
+        "{:<16} {:>9} {:>8} {:>10} {:>9}",
+        "bucket", "requested", "written", "duplicates", "attempts"
+    );
+    for (bucket, report) in options.buckets.iter().zip(&reports) {
+        println!(
+            "{:<16} {:>9} {:>8} {:>10} {:>9}{}",
+            format!("{}x{} {}", bucket.n, bucket.n, bucket.difficulty),
+            bucket.count,
+            report.written,
+            report.duplicates,
+            report.attempts,
+            if report.exhausted { "  exhausted" } else { "" }
+        );
+    }
+    let written: u32 = reports.iter().map(|r| r.written).sum();
+    let attempts: u64 = reports.iter().map(|r| r.attempts).sum();
+    println!(
+        "total: {written} puzzles, {attempts} attempts -> {}",
+        options.out
+    );
+
+    Ok(if reports.iter().any(|r| r.exhausted) {
+        ExitCode::from(PARTIAL_PACK_EXIT)
+    } else {
+        ExitCode::SUCCESS
+    })
+}
+
+fn fill_bucket(
+    options: &PackOptions,
+    index: usize,
+    bucket: PackBucket,
+    rules: Ruleset,
+    seen: &mut HashSet<u128>,
+    writer: &mut BankWriter<BufWriter<File>>,
+) -> Result<BucketReport, String> {
+    let mut report = BucketReport::default();
+    if bucket.count == 0 {
+        return Ok(report);
+    }
+    let base = GenerateConfig::with_difficulty(
+        bucket.n,
+        bucket_seed(options.seed, index),
+        bucket.difficulty,
+    );
+    let config = GenerateConfig {
+        max_attempts: options.max_attempts.unwrap_or(base.max_attempts),
+        ..base
+    };
+    // Duplicates are skipped, so a deduping bucket may need more draws than it keeps.
+    let draws = if options.dedupe {
+        u32::MAX
+    } else {
+        bucket.count
+    };
+    let mut duplicate_streak = 0;
+    let mut failure = None;
+    let summary = generate_stream(config, draws, |generated| {
+        match write_puzzle(options, bucket, rules, generated, seen, writer) {
+            Ok(true) => {
+                duplicate_streak = 0;
+                report.written += 1;
+            }
+            Ok(false) => {
+                report.duplicates += 1;
+                duplicate_streak += 1;
+                if duplicate_streak == MAX_DUPLICATE_STREAK {
+                    report.exhausted = true;
+                    return ControlFlow::Break(());
+                }
+            }
+            Err(e) => {
+                failure = Some(e);
+                return ControlFlow::Break(());
+            }
+        }
+        if report.written == bucket.count {
+            ControlFlow::Break(())
+        } else {
+            ControlFlow::Continue(())
+        }
+    })
+    .map_err(|e| format!("generation failed: {e}"))?;
+    if let Some(failure) = failure {
+        return Err(failure);
+    }
+
+    report.attempts = summary.attempts.iter().copied().map(u64::from).sum();
+    if let StreamEnd::Exhausted { attempts, .. } = summary.end {
+        report.attempts += u64::from(attempts);
+        report.exhausted = true;
+    }
+    Ok(report)
+}
+
+/// Minimize `generated` if asked and write it, or return `false` for a `--dedupe` duplicate.
+fn write_puzzle(
+    options: &PackOptions,
+    bucket: PackBucket,
+    rules: Ruleset,
+    generated: GeneratedPuzzleWithStats,
+    seen: &mut HashSet<u128>,
+    writer: &mut BankWriter<BufWriter<File>>,
+) -> Result<bool, String> {
+    let (puzzle, difficulty) = if options.minimize {
+        let minimized = minimize_puzzle(
+            generated.puzzle,
+            &generated.solution,
+            MinimizeConfig {
+                target_difficulty: Some((bucket.difficulty, DifficultyGoal::Exactly)),
+                ..MinimizeConfig::keen_baseline()
+            },
+        )
+        .map_err(|e| format!("minimization failed: {e}"))?;
+        let difficulty = minimized
+            .final_difficulty
+            .expect("a difficulty goal always reports the final difficulty");
+        (minimized.puzzle, difficulty)
+    } else {
+        (generated.puzzle, generated.difficulty)
+    };
+
+    if options.dedupe && !seen.insert(puzzle.content_hash128()) {
+        return Ok(false);
+    }
+    writer
+        .push(&BankRecord {
+            puzzle,
+            rules,
+            solution: generated.solution,
+            difficulty,
+            classified_with_version: Some(capabilities().version.to_string()),
+        })
+        .map_err(|e| format!("failed to write {}: {e}", options.out))?;
+    Ok(true)
+}
+
+/// Stream seed of bucket `index` (SplitMix64 finalizer over both).
+fn bucket_seed(seed: u64, index: usize) -> u64 {
+    let mut z = seed ^ (index as u64).wrapping_mul(0x9E37_79B9_7F4A_7C15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^ (z >> 31)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn spec_parses_buckets_in_order() {
+        assert_eq!(
+            parse_spec("4:easy:200, 5:normal:200,6:hard:100").unwrap(),
+            vec![
+                PackBucket {
+                    n: 4,
+                    difficulty: DifficultyTier::Easy,
+                    count: 200
+                },
+                PackBucket {
+                    n: 5,
+                    difficulty: DifficultyTier::Normal,
+                    count: 200
+                },
+                PackBucket {
+                    n: 6,
+                    difficulty: DifficultyTier::Hard,
+                    count: 100
+                },
+            ]
+        );
+        assert!(
+            parse_spec("4:easy")
+                .unwrap_err()
+                .contains("n:difficulty:count")
+        );
+        assert!(parse_spec("4:brutal:3").unwrap_err().contains("brutal"));
+        assert!(parse_spec("").unwrap_err().contains("no buckets"));
+    }
+}
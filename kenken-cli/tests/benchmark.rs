@@ -0,0 +1,95 @@
+//! `kenken-cli benchmark --export-json`: the written file parses and names every measurement.
+
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output};
+
+use serde_json::Value;
+
+fn benchmark(export: &Path, args: &[&str]) -> Output {
+    // The default trace filter logs solver spans to stdout.
+    Command::new(env!("CARGO_BIN_EXE_kenken-cli"))
+        .env("RUST_LOG", "off")
+        .arg("benchmark")
+        .args(args)
+        .arg("--export-json")
+        .arg(export)
+        .output()
+        .unwrap()
+}
+
+fn out_path(name: &str) -> PathBuf {
+    Path::new(env!("CARGO_TARGET_TMPDIR")).join(name)
+}
+
+/// The exported entries' names, checking each is a `{name, unit, value}` object with a
+/// numeric value.
+fn exported_names(path: &Path) -> Vec<String> {
+    let json: Value = serde_json::from_str(&std::fs::read_to_string(path).unwrap()).unwrap();
+    let entries = json.as_array().expect("a JSON array");
+    entries
+        .iter()
+        .map(|entry| {
+            let object = entry.as_object().expect("an object per entry");
+            assert_eq!(object.len(), 3, "{entry}");
+            assert!(object["unit"].is_string(), "{entry}");
+            assert!(object["value"].is_f64(), "{entry}");
+            object["name"].as_str().unwrap().to_string()
+        })
+        .collect()
+}
+
+#[test]
+fn solve_benchmark_exports_rate_and_distributions() {
+    let path = out_path("bench_solve.json");
+    let out = benchmark(&path, &["--n", "6", "--count", "3", "--tier", "hard"]);
+    assert!(
+        out.status.success(),
+        "{}",
+        String::from_utf8_lossy(&out.stderr)
+    );
+
+    let names = exported_names(&path);
+    let mut sorted = names.clone();
+    sorted.sort();
+    assert_eq!(names, sorted);
+    for key in [
+        "puzzles_per_sec",
+        "nodes_mean",
+        "nodes_median",
+        "nodes_p95",
+        "assignments_mean",
+        "assignments_median",
+        "assignments_p95",
+    ] {
+        let name = format!("solve/n=6/tier=hard/{key}");
+        assert!(names.contains(&name), "{name} missing from {names:?}");
+    }
+    assert_eq!(names.len(), 7);
+}
+
+#[cfg(feature = "pack")]
+#[test]
+fn generated_benchmark_exports_entries_per_difficulty() {
+    let path = out_path("bench_generated.json");
+    let out = benchmark(
+        &path,
+        &["--n", "4", "--count", "4", "--generated", "--seed", "7"],
+    );
+    assert!(
+        out.status.success(),
+        "{}",
+        String::from_utf8_lossy(&out.stderr)
+    );
+
+    let names = exported_names(&path);
+    assert!(names.contains(&"generated/n=4/tier=normal/puzzles_per_sec".to_string()));
+    let counts: Vec<&String> = names.iter().filter(|n| n.ends_with("/count")).collect();
+    assert!(!counts.is_empty(), "{names:?}");
+    for count in counts {
+        let prefix = count.trim_end_matches("/count");
+        assert!(prefix.starts_with("generated/n=4/tier=normal/difficulty="));
+        for key in ["nodes_median", "nodes_p95", "assignments_p95"] {
+            assert!(names.contains(&format!("{prefix}/{key}")), "{prefix}/{key}");
+        }
+    }
+}
@@ -0,0 +1,55 @@
+//! `kenken-cli solve --inspect-at`: state dumps on stderr, the solution unchanged on stdout.
+
+use std::process::{Command, Output};
+
+/// A generated Extreme 7x7, slow enough without deductions to stop in.
+const DESC: &str = "_aa__a_aa_a_5a_5b_4a_a_a_a__aa_3bb_b_4ab__ba_a,\
+                    s4a8m48a19s3a17m48a8s2a6m8m6s5a10s2m35s2a7d3s3a8";
+
+fn solve(extra: &[&str]) -> Output {
+    // The default trace filter logs solver spans to stdout.
+    Command::new(env!("CARGO_BIN_EXE_kenken-cli"))
+        .env("RUST_LOG", "off")
+        .args(["solve", "--n", "7", "--desc", DESC, "--tier", "none"])
+        .args(extra)
+        .output()
+        .unwrap()
+}
+
+#[test]
+fn dumps_go_to_stderr_at_each_node_count() {
+    let plain = solve(&[]);
+    let inspected = solve(&["--inspect-at", "500,1,20"]);
+    assert_eq!(inspected.status.code(), Some(0));
+    assert_eq!(inspected.stdout, plain.stdout);
+
+    let dumps = String::from_utf8_lossy(&inspected.stderr);
+    let headers: Vec<&str> = dumps.lines().filter(|l| l.starts_with("node ")).collect();
+    assert_eq!(headers.len(), 3, "{dumps}");
+    assert!(
+        headers[0].starts_with("node 1 at depth 0:"),
+        "{}",
+        headers[0]
+    );
+    assert!(
+        headers[1].starts_with("node 20 at depth "),
+        "{}",
+        headers[1]
+    );
+    assert!(
+        headers[2].starts_with("node 500 at depth "),
+        "{}",
+        headers[2]
+    );
+    assert_eq!(dumps.matches("decisions:").count(), 3);
+    assert_eq!(dumps.matches("row masks:").count(), 3);
+    // The pretty renderer's frame, once per dump.
+    assert_eq!(dumps.matches('┏').count(), 3);
+}
+
+#[test]
+fn bad_node_counts_are_rejected() {
+    let out = solve(&["--inspect-at", "10,x"]);
+    assert_eq!(out.status.code(), Some(2));
+    assert!(String::from_utf8_lossy(&out.stderr).contains("invalid --inspect-at node count \"x\""));
+}
@@ -0,0 +1,117 @@
+//! End-to-end `pack`: generate a small bank, reopen it, and check every entry.
+#![cfg(feature = "pack")]
+
+use std::fs::File;
+use std::io::BufReader;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output};
+
+use kenken_io::rkyv_bank::{BankReader, BankRecord};
+use kenken_solver::{
+    DeductionTier, DifficultyTier, classify_difficulty_from_tier, classify_tier_required,
+    count_solutions_up_to_with_deductions,
+};
+
+fn pack(out: &Path, args: &[&str]) -> Output {
+    // The default trace filter logs solver spans to stdout.
+    Command::new(env!("CARGO_BIN_EXE_kenken-cli"))
+        .env("RUST_LOG", "off")
+        .args(["pack", "--out"])
+        .arg(out)
+        .args(args)
+        .output()
+        .unwrap()
+}
+
+fn out_path(name: &str) -> PathBuf {
+    Path::new(env!("CARGO_TARGET_TMPDIR")).join(name)
+}
+
+fn read_bank(path: &Path) -> Vec<BankRecord> {
+    BankReader::new(BufReader::new(File::open(path).unwrap()))
+        .unwrap()
+        .collect::<Result<_, _>>()
+        .unwrap()
+}
+
+#[test]
+fn tiny_pack_reopens_with_solved_and_graded_entries() {
+    let path = out_path("tiny.keen");
+    let args = [
+        "--spec",
+        "4:easy:5,4:normal:5",
+        "--seed",
+        "42",
+        "--minimize",
+        "--dedupe",
+    ];
+    let out = pack(&path, &args);
+    let stdout = String::from_utf8_lossy(&out.stdout);
+    assert_eq!(out.status.code(), Some(0), "{stdout}");
+    assert!(stdout.contains("total: 10 puzzles"), "{stdout}");
+
+    let records = read_bank(&path);
+    assert_eq!(records.len(), 10);
+    for (i, record) in records.iter().enumerate() {
+        let expected = if i < 5 {
+            DifficultyTier::Easy
+        } else {
+            DifficultyTier::Normal
+        };
+        assert_eq!(record.difficulty, expected, "entry {i}");
+        assert_eq!(record.puzzle.n, 4);
+        record.puzzle.check_solution(&record.solution).unwrap();
+        assert_eq!(
+            count_solutions_up_to_with_deductions(
+                &record.puzzle,
+                record.rules,
+                DeductionTier::Hard,
+                2
+            )
+            .unwrap(),
+            1,
+            "entry {i}"
+        );
+        let tier = classify_tier_required(&record.puzzle, record.rules).unwrap();
+        assert_eq!(classify_difficulty_from_tier(tier), record.difficulty);
+    }
+
+    // The whole pack replays from the seed.
+    let again = out_path("tiny-again.keen");
+    assert_eq!(pack(&again, &args).status.code(), Some(0));
+    assert_eq!(
+        std::fs::read(&path).unwrap(),
+        std::fs::read(&again).unwrap()
+    );
+}
+
+#[test]
+fn exhausted_bucket_writes_a_partial_pack_and_exits_3() {
+    let path = out_path("partial.keen");
+    let out = pack(
+        &path,
+        &["--spec", "4:easy:2,5:extreme:1", "--max-attempts", "1"],
+    );
+    let stderr = String::from_utf8_lossy(&out.stderr);
+    assert_eq!(out.status.code(), Some(3), "{stderr}");
+    assert!(stderr.contains("exhausted its attempts"), "{stderr}");
+
+    let records = read_bank(&path);
+    assert!(records.len() < 3);
+    assert!(
+        records
+            .iter()
+            .all(|r| r.puzzle.check_solution(&r.solution).is_ok())
+    );
+}
+
+#[test]
+fn pack_requires_out_and_spec() {
+    let out = Command::new(env!("CARGO_BIN_EXE_kenken-cli"))
+        .env("RUST_LOG", "off")
+        .args(["pack", "--spec", "4:easy:1"])
+        .output()
+        .unwrap();
+    assert_eq!(out.status.code(), Some(2));
+    assert!(String::from_utf8_lossy(&out.stderr).contains("'pack' requires --out and --spec"));
+}
@@ -0,0 +1,99 @@
+//! `kenken-cli verify` end to end: exit codes and the reported violation.
+
+use std::process::{Command, Output};
+
+/// "4x4 singleton grid A" from kenken-solver/tests/corpus/golden.jsonl.
+const DESC: &str = "_25,a1a2a3a4a2a1a4a3a3a4a1a2a4a3a2a1";
+const SOLUTION: [u8; 16] = [1, 2, 3, 4, 2, 1, 4, 3, 3, 4, 1, 2, 4, 3, 2, 1];
+
+fn verify(n: &str, desc: &str, extra: &[&str]) -> Output {
+    // The default trace filter logs solver spans to stdout.
+    Command::new(env!("CARGO_BIN_EXE_kenken-cli"))
+        .env("RUST_LOG", "off")
+        .args(["verify", "--n", n, "--desc", desc])
+        .args(extra)
+        .output()
+        .unwrap()
+}
+
+fn digits(grid: &[u8]) -> String {
+    grid.iter().map(u8::to_string).collect::<Vec<_>>().join(",")
+}
+
+fn stdout(out: &Output) -> String {
+    String::from_utf8_lossy(&out.stdout).into_owned()
+}
+
+#[test]
+fn golden_solution_is_valid_and_unique() {
+    let out = verify("4", DESC, &["--solution", &digits(&SOLUTION), "--unique"]);
+    assert_eq!(stdout(&out), "valid\nunique\n");
+    assert_eq!(out.status.code(), Some(0));
+}
+
+#[test]
+fn corrupted_digit_reports_the_latin_violation() {
+    let mut grid = SOLUTION;
+    grid[6] = 3; // row 1 becomes 2 1 3 3
+    let out = verify("4", DESC, &["--solution", &digits(&grid)]);
+    assert_eq!(stdout(&out), "invalid: row 1 repeats 3 at cols 2 and 3\n");
+    assert_eq!(out.status.code(), Some(1));
+}
+
+#[test]
+fn swapped_intercalate_reports_the_cage_violation() {
+    // Swapping the top-left 1 2 / 2 1 block keeps the grid Latin but breaks its givens.
+    let mut grid = SOLUTION;
+    grid.swap(0, 1);
+    grid.swap(4, 5);
+    let out = verify("4", DESC, &["--solution", &digits(&grid)]);
+    assert_eq!(
+        stdout(&out),
+        "invalid: cage 0 (Eq 1, anchored at row 0, col 0) is not satisfied\n"
+    );
+    assert_eq!(out.status.code(), Some(1));
+}
+
+#[test]
+fn unique_flags_the_two_solution_2x2() {
+    let out = verify("2", "b__,a3a3", &["--solution", "1,2,2,1", "--unique"]);
+    assert_eq!(stdout(&out), "valid\nnot unique: another solution exists\n");
+    assert_eq!(out.status.code(), Some(1));
+}
+
+#[test]
+fn solution_file_accepts_one_row_per_line() {
+    let path = std::env::temp_dir().join(format!("kenken-cli-verify-{}.txt", std::process::id()));
+    let rows: Vec<String> = SOLUTION.chunks(4).map(digits).collect();
+    std::fs::write(&path, rows.join("\n")).unwrap();
+    let out = verify("4", DESC, &["--solution-file", path.to_str().unwrap()]);
+    std::fs::remove_file(&path).unwrap();
+    assert_eq!(stdout(&out), "valid\n");
+    assert_eq!(out.status.code(), Some(0));
+}
+
+#[test]
+fn missing_or_conflicting_solution_is_a_usage_error() {
+    assert_eq!(verify("4", DESC, &[]).status.code(), Some(2));
+    let out = verify(
+        "4",
+        DESC,
+        &["--solution", &digits(&SOLUTION), "--solution-file", "x"],
+    );
+    assert_eq!(out.status.code(), Some(2));
+}
+
+#[test]
+fn wrong_size_names_the_size_the_desc_encodes() {
+    let out = verify("5", DESC, &["--solution", &digits(&SOLUTION)]);
+    assert_eq!(out.status.code(), Some(2));
+    let stderr = String::from_utf8_lossy(&out.stderr);
+    assert!(
+        stderr.starts_with("desc appears to encode a 4x4 grid but --n 5 was given\n"),
+        "{stderr}"
+    );
+
+    let out = verify("4", "_25,a1", &["--solution", &digits(&SOLUTION)]);
+    let stderr = String::from_utf8_lossy(&out.stderr);
+    assert!(stderr.starts_with("failed to parse --desc: "), "{stderr}");
+}
@@ -0,0 +1,349 @@
+//! Cage arithmetic: what each [`Op`]'s clue means, defined in one place.
+//!
+//! Validation, tuple enumeration, the solver's propagators, the SAT allowlists and the
+//! verifier all evaluate clues through these functions, so extending the ruleset touches only
+//! this module. For the values of a full cage:
+//!
+//! - `Add`: their sum; `Mul`: their product.
+//! - `Sub`: the largest value minus the sum of the rest (`|a - b|` for two cells).
+//! - `Div`: the largest value divided by the product of the rest, if that divides it exactly
+//!   (`max / min` for two cells); otherwise no value.
+//! - `Eq`: the common value, if every value is equal.
+//!
+//! An empty cage has no value, and neither does one whose arithmetic overflows `i64`, so
+//! neither meets any target. Generators clue cages from a known solution with [`target_for`]
+//! and [`applicable_ops`], which also rule out clues no puzzle may show.
+
+use smallvec::SmallVec;
+
+use crate::rules::{Op, Ruleset};
+
+/// Value of a full cage assignment under `op`, or `None` if it has none (see the
+/// [module docs](self)).
+pub fn op_value<V: Copy + Into<i64>>(op: Op, values: &[V]) -> Option<i64> {
+    let mut values = values.iter().map(|&v| v.into());
+    let first = values.next()?;
+    match op {
+        Op::Add => values.try_fold(first, i64::checked_add),
+        Op::Mul => values.try_fold(first, i64::checked_mul),
+        Op::Eq => values.all(|v| v == first).then_some(first),
+        Op::Sub => {
+            let (max, sum) = values.try_fold((first, first), |(max, sum), v| {
+                Some((max.max(v), sum.checked_add(v)?))
+            })?;
+            max.checked_mul(2)?.checked_sub(sum)
+        }
+        Op::Div => {
+            // Exact successive division by the rest is the same as their product dividing the
+            // largest value; only one copy of a repeated maximum is the dividend.
+            let (max, product) = values.try_fold((first, 1i64), |(max, product), v| {
+                let (max, divisor) = if v > max { (v, max) } else { (max, v) };
+                Some((max, product.checked_mul(divisor)?))
+            })?;
+            (product != 0 && max % product == 0).then(|| max / product)
+        }
+    }
+}
+
+/// True if a full cage assignment `values` meets `target` under `op`.
+pub fn op_satisfied<V: Copy + Into<i64>>(op: Op, target: i64, values: &[V]) -> bool {
+    op_value(op, values) == Some(target)
+}
+
+/// [`op_satisfied`] for a two-cell cage holding `a` and `b`, without building a slice; for
+/// propagators that test many candidate pairs.
+pub fn op_pair_satisfied(op: Op, target: i64, a: u8, b: u8) -> bool {
+    let (a, b) = (i64::from(a), i64::from(b));
+    match op {
+        Op::Add => a + b == target,
+        Op::Mul => a * b == target,
+        Op::Sub => (a - b).abs() == target,
+        Op::Div => {
+            let (hi, lo) = (a.max(b), a.min(b));
+            lo != 0 && hi % lo == 0 && hi / lo == target
+        }
+        Op::Eq => a == target && b == target,
+    }
+}
+
+/// Target of an `op` clue over a cage holding `values`, or `None` when `op` cannot clue them:
+/// `Eq` on anything but one cell, `Sub` or `Div` on fewer than two, a `Sub` that is not
+/// positive, or a `Div` that is not exact.
+pub fn target_for(op: Op, values: &[u8]) -> Option<i64> {
+    match (op, values.len()) {
+        (_, 0) | (Op::Eq, 2..) | (Op::Sub | Op::Div, 1) => None,
+        _ => op_value(op, values).filter(|&target| target > 0),
+    }
+}
+
+/// Ops that can clue a cage holding `values` under `rules`, in [`Op::ALL`] order: `Eq` alone
+/// for one cell; otherwise `Add` and `Mul`, plus `Sub` and `Div` where [`target_for`] gives
+/// them a target and `rules` allows them at this size.
+pub fn applicable_ops(values: &[u8], rules: Ruleset) -> SmallVec<[Op; 5]> {
+    Op::ALL
+        .into_iter()
+        .filter(|&op| match (op, values.len()) {
+            (_, 0) => false,
+            (Op::Eq, len) => len == 1,
+            (_, 1) => false,
+            (Op::Sub | Op::Div, len) if rules.sub_div_two_cell_only && len != 2 => false,
+            _ => target_for(op, values).is_some(),
+        })
+        .collect()
+}
+
+/// Smallest and largest targets an `op` cage of `len` cells can have in an `n` x `n` grid;
+/// `one_unit` is whether every cell lies in one row or column, which forces distinct digits.
+/// See [`Cage::target_bounds`](crate::Cage::target_bounds) for the ranges.
+pub fn target_bounds(op: Op, len: usize, n: u8, one_unit: bool) -> (i64, i64) {
+    let len = len as i64;
+    let n = i64::from(n);
+    match op {
+        Op::Add if one_unit => (len * (len + 1) / 2, len * n - len * (len - 1) / 2),
+        Op::Add => (len, len * n),
+        Op::Mul => (1, (0..len).fold(1i64, |p, _| p.saturating_mul(n))),
+        // One cell: the digit itself, as under every op.
+        Op::Sub if len == 1 => (1, n),
+        Op::Sub if len == 2 => (1, n - 1),
+        Op::Sub => (n * (2 - len), n - 1),
+        Op::Div if len == 2 && one_unit => (2, n),
+        Op::Div => (1, n),
+        Op::Eq => (1, n),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The clue definitions written out directly. A sum or product that overflows `i64`
+    /// meets no target.
+    fn meets(op: Op, target: i64, values: &[i64]) -> bool {
+        let max = *values.iter().max().unwrap();
+        let at = values.iter().position(|&v| v == max).unwrap();
+        let rest: Vec<i64> = (0..values.len())
+            .filter(|&i| i != at)
+            .map(|i| values[i])
+            .collect();
+        let sum = |values: &[i64]| values.iter().try_fold(0i64, |sum, &v| sum.checked_add(v));
+        let product = |values: &[i64]| values.iter().try_fold(1i64, |p, &v| p.checked_mul(v));
+        match op {
+            Op::Add => sum(values) == Some(target),
+            Op::Mul => product(values) == Some(target),
+            Op::Sub => sum(&rest).and_then(|rest| max.checked_sub(rest)) == Some(target),
+            Op::Div => product(&rest).and_then(|rest| rest.checked_mul(target)) == Some(max),
+            Op::Eq => values.iter().all(|&v| v == target),
+        }
+    }
+
+    #[test]
+    fn pairs_agree_with_the_definitions_up_to_twelve() {
+        for op in Op::ALL {
+            for a in 1..=12u8 {
+                for b in 1..=12u8 {
+                    for target in -2..=150i64 {
+                        let expected = meets(op, target, &[i64::from(a), i64::from(b)]);
+                        let context = format!("{op} {target} on ({a}, {b})");
+                        assert_eq!(op_pair_satisfied(op, target, a, b), expected, "{context}");
+                        assert_eq!(op_satisfied(op, target, &[a, b]), expected, "{context}");
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn triples_agree_with_the_definitions_up_to_six() {
+        for op in Op::ALL {
+            for a in 1..=6i64 {
+                for b in 1..=6i64 {
+                    for c in 1..=6i64 {
+                        for target in -6..=220i64 {
+                            assert_eq!(
+                                op_satisfied(op, target, &[a, b, c]),
+                                meets(op, target, &[a, b, c]),
+                                "{op} {target} on ({a}, {b}, {c})"
+                            );
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn large_cages_agree_with_the_definitions_past_overflow() {
+        // Products of 36 or more cycling digits overflow `i64`; 24 do not.
+        for len in [12, 24, 36, 64] {
+            let values: Vec<i64> = (0..len).map(|i| 1 + i % 12).collect();
+            assert_eq!(op_value(Op::Mul, &values).is_none(), len >= 36);
+            for op in Op::ALL {
+                let exact = op_value(op, &values);
+                for target in [-1, 0, 1, 12, i64::MAX, exact.unwrap_or(2)] {
+                    assert_eq!(
+                        op_satisfied(op, target, &values),
+                        meets(op, target, &values),
+                        "{op} {target} on {len} cells"
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn empty_and_overflowing_cages_have_no_value() {
+        for op in Op::ALL {
+            assert_eq!(op_value::<u8>(op, &[]), None);
+        }
+        assert_eq!(op_value(Op::Mul, &[i64::MAX, 2]), None);
+        assert_eq!(op_value(Op::Add, &[i64::MAX, 1]), None);
+        assert_eq!(op_value(Op::Sub, &[i64::MAX, 1]), None);
+        assert_eq!(op_value(Op::Div, &[i64::MAX, 1 << 32, 1 << 32]), None);
+        assert!(!op_satisfied(Op::Mul, i64::MAX, &[i64::MAX, 2]));
+        // A zero divisor divides nothing.
+        assert_eq!(op_value(Op::Div, &[4i64, 0]), None);
+        assert!(!op_pair_satisfied(Op::Div, 0, 0, 0));
+    }
+
+    /// Every tuple of length 1..=4 over `1..=6`, shortest first.
+    fn tuples_up_to_four() -> Vec<Vec<u8>> {
+        let mut out: Vec<Vec<u8>> = vec![Vec::new()];
+        let mut all = Vec::new();
+        for _ in 0..4 {
+            out = out
+                .iter()
+                .flat_map(|t| {
+                    (1..=6).map(move |v| {
+                        let mut t = t.clone();
+                        t.push(v);
+                        t
+                    })
+                })
+                .collect();
+            all.extend(out.iter().cloned());
+        }
+        all
+    }
+
+    /// The clue a generator may show for `values`, computed from the definitions directly.
+    fn expected_target(op: Op, values: &[u8]) -> Option<i64> {
+        let values: Vec<i64> = values.iter().map(|&v| i64::from(v)).collect();
+        let max = *values.iter().max()?;
+        let sum: i64 = values.iter().sum();
+        let product: i64 = values.iter().product();
+        match op {
+            Op::Add => Some(sum),
+            Op::Mul => Some(product),
+            Op::Eq => (values.len() == 1).then_some(max),
+            Op::Sub if values.len() < 2 => None,
+            Op::Sub => Some(2 * max - sum).filter(|&t| t > 0),
+            Op::Div if values.len() < 2 => None,
+            Op::Div => {
+                let rest = product / max;
+                (max % rest == 0).then(|| max / rest)
+            }
+        }
+    }
+
+    #[test]
+    fn targets_agree_with_the_definitions_up_to_four_cells() {
+        for values in tuples_up_to_four() {
+            for op in Op::ALL {
+                assert_eq!(
+                    target_for(op, &values),
+                    expected_target(op, &values),
+                    "{op} on {values:?}"
+                );
+            }
+        }
+        for op in Op::ALL {
+            assert_eq!(target_for(op, &[]), None);
+        }
+        // Equal pairs have no positive difference; 4 / 3 is not exact.
+        assert_eq!(target_for(Op::Sub, &[3, 3]), None);
+        assert_eq!(target_for(Op::Div, &[4, 3]), None);
+        assert_eq!(target_for(Op::Div, &[6, 2]), Some(3));
+    }
+
+    #[test]
+    fn applicable_ops_follow_targets_and_rules() {
+        let baseline = Ruleset::keen_baseline();
+        let n_ary = Ruleset {
+            sub_div_two_cell_only: false,
+            ..baseline
+        };
+        for values in tuples_up_to_four() {
+            for rules in [baseline, n_ary] {
+                let expected: Vec<Op> = Op::ALL
+                    .into_iter()
+                    .filter(|&op| {
+                        let len = values.len();
+                        let shape_ok = match op {
+                            Op::Eq => len == 1,
+                            Op::Add | Op::Mul => len >= 2,
+                            Op::Sub | Op::Div => {
+                                len >= 2 && (!rules.sub_div_two_cell_only || len == 2)
+                            }
+                        };
+                        shape_ok && expected_target(op, &values).is_some()
+                    })
+                    .collect();
+                assert_eq!(
+                    applicable_ops(&values, rules).as_slice(),
+                    expected,
+                    "{values:?} under {rules:?}"
+                );
+            }
+        }
+        assert_eq!(applicable_ops(&[4], baseline).as_slice(), [Op::Eq]);
+        assert_eq!(
+            applicable_ops(&[2, 4], baseline).as_slice(),
+            [Op::Add, Op::Mul, Op::Sub, Op::Div]
+        );
+        assert_eq!(
+            applicable_ops(&[1, 2, 5], baseline).as_slice(),
+            [Op::Add, Op::Mul]
+        );
+        // 5 - 1 - 2 is positive, but 5 / (1 * 2) is not exact.
+        assert_eq!(
+            applicable_ops(&[1, 2, 5], n_ary).as_slice(),
+            [Op::Add, Op::Mul, Op::Sub]
+        );
+    }
+
+    #[test]
+    fn every_assignment_lies_within_its_bounds() {
+        for n in 2..=6u8 {
+            for len in 1..=3usize {
+                let mut values = vec![1u8; len];
+                loop {
+                    let distinct = values
+                        .iter()
+                        .enumerate()
+                        .all(|(i, v)| !values[..i].contains(v));
+                    for op in Op::ALL {
+                        // Targets are positive, so the bounds need not cover a zero difference.
+                        let Some(value) = op_value(op, &values).filter(|&v| v != 0) else {
+                            continue;
+                        };
+                        for one_unit in [false, true] {
+                            if one_unit && !distinct {
+                                continue;
+                            }
+                            let (min, max) = target_bounds(op, len, n, one_unit);
+                            assert!(
+                                (min..=max).contains(&value),
+                                "{op} {values:?} = {value} outside {min}..={max} (n={n})"
+                            );
+                        }
+                    }
+                    let Some(pos) = values.iter().position(|&v| v < n) else {
+                        break;
+                    };
+                    values[pos] += 1;
+                    values[..pos].fill(1);
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,206 @@
+//! Coordinate-based `Puzzle` construction.
+//!
+//! Tests and editors describe cages as `(row, col)` pairs rather than raw row-major
+//! `CellId`s. Coordinates are bounds-checked as each cage is added; coverage, duplication
+//! and rule checks run once in [`PuzzleBuilder::build`] and report the same `CoreError`s as
+//! [`Puzzle::validate`].
+
+use smallvec::SmallVec;
+
+use crate::error::CoreError;
+use crate::limits::INLINE_CAGE_CELLS;
+use crate::puzzle::{Cage, CellId, Coord, Puzzle, cell_id};
+use crate::rules::{Op, Ruleset};
+
+/// Incremental builder for a [`Puzzle`].
+///
+/// ```
+/// use kenken_core::PuzzleBuilder;
+/// use kenken_core::rules::{Op, Ruleset};
+///
+/// let puzzle = PuzzleBuilder::new(2)
+///     .cage(Op::Add, 3, &[(0, 0), (0, 1)])?
+///     .cage(Op::Add, 3, &[(1, 0), (1, 1)])?
+///     .build(Ruleset::keen_baseline())?;
+/// assert_eq!(puzzle.cages.len(), 2);
+/// # Ok::<(), kenken_core::CoreError>(())
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PuzzleBuilder {
+    n: u8,
+    cages: Vec<Cage>,
+}
+
+impl PuzzleBuilder {
+    /// Start an `n`×`n` puzzle with no cages.
+    pub fn new(n: u8) -> Self {
+        Self {
+            n,
+            cages: Vec::new(),
+        }
+    }
+
+    /// Append a cage over the given `(row, col)` cells, in any order (the cage stores them
+    /// sorted).
+    ///
+    /// Fails with `CellOutOfRange` if any coordinate lies outside the grid.
+    pub fn cage(mut self, op: Op, target: i64, cells: &[(u8, u8)]) -> Result<Self, CoreError> {
+        let mut cells = cells
+            .iter()
+            .map(|&(row, col)| cell_id(self.n, Coord { row, col }))
+            .collect::<Result<SmallVec<[CellId; INLINE_CAGE_CELLS]>, _>>()?;
+        cells.sort_unstable();
+        self.cages.push(Cage { cells, op, target });
+        Ok(self)
+    }
+
+    /// Cover every cell not yet in a cage with an `Eq` cage holding its value from
+    /// `solution` (row-major, `n * n` entries).
+    ///
+    /// New cages are appended in row-major order; cages added afterwards may still overlap
+    /// them, which `build` reports as `CellDuplicated`.
+    pub fn auto_singletons(mut self, solution: &[u8]) -> Result<Self, CoreError> {
+        let a = (self.n as usize) * (self.n as usize);
+        if solution.len() != a {
+            return Err(CoreError::SolutionLengthMismatch {
+                expected: a,
+                got: solution.len(),
+            });
+        }
+
+        let mut covered = vec![false; a];
+        for cage in &self.cages {
+            for &cell in &cage.cells {
+                covered[cell.0 as usize] = true;
+            }
+        }
+        for (idx, &value) in solution.iter().enumerate() {
+            if !covered[idx] {
+                self.cages.push(Cage {
+                    cells: SmallVec::from_slice(&[CellId(idx as u16)]),
+                    op: Op::Eq,
+                    target: value as i64,
+                });
+            }
+        }
+        Ok(self)
+    }
+
+    /// Finish the puzzle, running [`Puzzle::validate`] under `rules`.
+    pub fn build(self, rules: Ruleset) -> Result<Puzzle, CoreError> {
+        let puzzle = Puzzle {
+            n: self.n,
+            cages: self.cages,
+        };
+        puzzle.validate(rules)?;
+        Ok(puzzle)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rules() -> Ruleset {
+        Ruleset::keen_baseline()
+    }
+
+    #[test]
+    fn builds_cages_from_coordinates() {
+        // Listed bottom cell first; the cage stores its cells sorted.
+        let puzzle = PuzzleBuilder::new(3)
+            .cage(Op::Add, 4, &[(1, 0), (0, 0)])
+            .unwrap()
+            .auto_singletons(&[1, 2, 3, 3, 1, 2, 2, 3, 1])
+            .unwrap()
+            .build(rules())
+            .unwrap();
+
+        assert_eq!(puzzle.n, 3);
+        assert_eq!(puzzle.cages.len(), 8);
+        assert_eq!(puzzle.cages[0].cells.as_slice(), &[CellId(0), CellId(3)]);
+        assert_eq!(puzzle.cages[0].op, Op::Add);
+        // Singletons follow in row-major order, skipping the covered cells 0 and 3.
+        assert_eq!(puzzle.cages[1].cells.as_slice(), &[CellId(1)]);
+        assert_eq!(puzzle.cages[1].target, 2);
+        assert_eq!(puzzle.cages[3].cells.as_slice(), &[CellId(4)]);
+        assert_eq!(puzzle.cages[3].target, 1);
+    }
+
+    #[test]
+    fn rejects_out_of_range_coordinate_eagerly() {
+        let err = PuzzleBuilder::new(3)
+            .cage(Op::Add, 4, &[(0, 0), (0, 3)])
+            .unwrap_err();
+        assert!(matches!(err, CoreError::CellOutOfRange { n: 3, .. }));
+    }
+
+    #[test]
+    fn build_reports_uncovered_cell() {
+        let err = PuzzleBuilder::new(2)
+            .cage(Op::Add, 3, &[(0, 0), (0, 1)])
+            .unwrap()
+            .cage(Op::Eq, 2, &[(1, 0)])
+            .unwrap()
+            .build(rules())
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            CoreError::CellUncovered {
+                cell: CellId(3),
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn build_reports_duplicated_cell() {
+        let err = PuzzleBuilder::new(2)
+            .auto_singletons(&[1, 2, 2, 1])
+            .unwrap()
+            .cage(Op::Add, 3, &[(1, 0), (1, 1)])
+            .unwrap()
+            .build(rules())
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            CoreError::CellDuplicated {
+                cell: CellId(2),
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn build_applies_ruleset_checks() {
+        let err = PuzzleBuilder::new(2)
+            .cage(Op::Add, 3, &[(0, 0), (1, 1)])
+            .unwrap()
+            .auto_singletons(&[1, 2, 2, 1])
+            .unwrap()
+            .build(rules())
+            .unwrap_err();
+        assert!(matches!(err, CoreError::CageNotConnected { .. }));
+
+        let err = PuzzleBuilder::new(2)
+            .auto_singletons(&[1, 2, 3, 1])
+            .unwrap()
+            .build(rules())
+            .unwrap_err();
+        assert!(matches!(err, CoreError::EqTargetOutOfRange));
+    }
+
+    #[test]
+    fn auto_singletons_rejects_wrong_solution_length() {
+        let err = PuzzleBuilder::new(3)
+            .auto_singletons(&[1, 2, 3])
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            CoreError::SolutionLengthMismatch {
+                expected: 9,
+                got: 3
+            }
+        ));
+    }
+}
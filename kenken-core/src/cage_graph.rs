@@ -0,0 +1,263 @@
+//! Cage adjacency graph and per-cage region summaries.
+//!
+//! Built in a single pass over the grid so callers (minimizer, editors, deduction passes that
+//! want span information) don't have to rescan orthogonal neighbors themselves.
+
+use crate::puzzle::{CellId, Coord, Puzzle};
+
+/// Orthogonal adjacency between cages, plus bounding boxes.
+///
+/// Cells that are out of range or not covered by any cage are ignored, so building a graph
+/// for an unvalidated puzzle never panics.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CageGraph {
+    n: u8,
+    cage_of_cell: Vec<usize>,
+    /// Sorted neighbor cage indices per cage.
+    neighbors: Vec<Vec<usize>>,
+    /// Shared edge count, parallel to `neighbors`.
+    border_lens: Vec<Vec<usize>>,
+    /// Inclusive `(min, max)` corners per cage.
+    bounds: Vec<(Coord, Coord)>,
+}
+
+impl CageGraph {
+    /// Build the graph in one O(n²) pass over the grid.
+    pub fn build(puzzle: &Puzzle) -> Self {
+        let n = puzzle.n as usize;
+        let a = n * n;
+        let cages = puzzle.cages.len();
+
+        let mut cage_of_cell = vec![usize::MAX; a];
+        let mut bounds = vec![
+            (
+                Coord {
+                    row: u8::MAX,
+                    col: u8::MAX,
+                },
+                Coord { row: 0, col: 0 },
+            );
+            cages
+        ];
+        for (cage_idx, cage) in puzzle.cages.iter().enumerate() {
+            for &cell in &cage.cells {
+                let idx = cell.0 as usize;
+                if idx >= a {
+                    continue;
+                }
+                cage_of_cell[idx] = cage_idx;
+
+                let Coord { row, col } = puzzle.coord_of(cell);
+                let (min, max) = &mut bounds[cage_idx];
+                min.row = min.row.min(row);
+                min.col = min.col.min(col);
+                max.row = max.row.max(row);
+                max.col = max.col.max(col);
+            }
+        }
+        for (min, max) in &mut bounds {
+            if min.row > max.row {
+                // Empty cage (or only out-of-range cells).
+                *min = Coord { row: 0, col: 0 };
+                *max = Coord { row: 0, col: 0 };
+            }
+        }
+
+        // Accumulate shared edges by looking right and down from every cell.
+        let mut edges: Vec<Vec<(usize, usize)>> = vec![Vec::new(); cages];
+        let mut add_edge = |x: usize, y: usize| {
+            if x == usize::MAX || y == usize::MAX || x == y {
+                return;
+            }
+            for (from, to) in [(x, y), (y, x)] {
+                match edges[from].iter_mut().find(|(b, _)| *b == to) {
+                    Some((_, len)) => *len += 1,
+                    None => edges[from].push((to, 1)),
+                }
+            }
+        };
+        for row in 0..n {
+            for col in 0..n {
+                let here = cage_of_cell[row * n + col];
+                if col + 1 < n {
+                    add_edge(here, cage_of_cell[row * n + col + 1]);
+                }
+                if row + 1 < n {
+                    add_edge(here, cage_of_cell[(row + 1) * n + col]);
+                }
+            }
+        }
+
+        let mut neighbors = Vec::with_capacity(cages);
+        let mut border_lens = Vec::with_capacity(cages);
+        for mut list in edges {
+            list.sort_unstable();
+            neighbors.push(list.iter().map(|&(b, _)| b).collect());
+            border_lens.push(list.iter().map(|&(_, len)| len).collect());
+        }
+
+        Self {
+            n: puzzle.n,
+            cage_of_cell,
+            neighbors,
+            border_lens,
+            bounds,
+        }
+    }
+
+    /// Index of the cage containing `cell`, if any.
+    pub fn cage_of(&self, cell: CellId) -> Option<usize> {
+        self.cage_of_cell
+            .get(cell.0 as usize)
+            .copied()
+            .filter(|&c| c != usize::MAX)
+    }
+
+    /// Cages sharing at least one orthogonal edge with `cage_idx`, in ascending order.
+    pub fn neighbors(&self, cage_idx: usize) -> &[usize] {
+        &self.neighbors[cage_idx]
+    }
+
+    /// Number of unit edges shared by cages `a` and `b` (0 if not adjacent).
+    pub fn shared_border_len(&self, a: usize, b: usize) -> usize {
+        match self.neighbors[a].binary_search(&b) {
+            Ok(pos) => self.border_lens[a][pos],
+            Err(_) => 0,
+        }
+    }
+
+    /// Inclusive `(top_left, bottom_right)` corners of the cage's cells.
+    ///
+    /// Empty cages report `((0, 0), (0, 0))`.
+    pub fn bounding_box(&self, cage_idx: usize) -> (Coord, Coord) {
+        self.bounds[cage_idx]
+    }
+
+    /// True if every cell of the cage lies in one row.
+    pub fn spans_single_row(&self, cage_idx: usize) -> bool {
+        let (min, max) = self.bounds[cage_idx];
+        min.row == max.row
+    }
+
+    /// True if every cell of the cage lies in one column.
+    pub fn spans_single_col(&self, cage_idx: usize) -> bool {
+        let (min, max) = self.bounds[cage_idx];
+        min.col == max.col
+    }
+
+    /// Grid size the graph was built for.
+    pub fn n(&self) -> u8 {
+        self.n
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::puzzle::Cage;
+    use crate::rules::Op;
+
+    /// Build a puzzle from a row-major grid of cage labels (`labels[idx]` = cage index).
+    fn from_labels(n: u8, labels: &[usize]) -> Puzzle {
+        let cages = labels.iter().copied().max().unwrap() + 1;
+        let mut out: Vec<Cage> = (0..cages)
+            .map(|_| Cage {
+                cells: Default::default(),
+                op: Op::Add,
+                target: 1,
+            })
+            .collect();
+        for (idx, &label) in labels.iter().enumerate() {
+            out[label].cells.push(CellId(idx as u16));
+        }
+        Puzzle { n, cages: out }
+    }
+
+    fn coord(row: u8, col: u8) -> Coord {
+        Coord { row, col }
+    }
+
+    #[test]
+    fn mixed_partition_adjacency_and_borders() {
+        #[rustfmt::skip]
+        let labels = [
+            0, 0, 1, 1,
+            2, 0, 1, 3,
+            2, 4, 4, 3,
+            5, 5, 4, 3,
+        ];
+        let g = CageGraph::build(&from_labels(4, &labels));
+
+        assert_eq!(g.neighbors(0), &[1, 2, 4]);
+        assert_eq!(g.neighbors(1), &[0, 3, 4]);
+        assert_eq!(g.neighbors(2), &[0, 4, 5]);
+        assert_eq!(g.neighbors(3), &[1, 4]);
+        assert_eq!(g.neighbors(4), &[0, 1, 2, 3, 5]);
+        assert_eq!(g.neighbors(5), &[2, 4]);
+
+        assert_eq!(g.shared_border_len(0, 1), 2);
+        assert_eq!(g.shared_border_len(1, 0), 2);
+        assert_eq!(g.shared_border_len(0, 2), 2);
+        assert_eq!(g.shared_border_len(0, 4), 1);
+        assert_eq!(g.shared_border_len(3, 4), 2);
+        assert_eq!(g.shared_border_len(4, 5), 2);
+        assert_eq!(g.shared_border_len(0, 3), 0);
+
+        assert_eq!(g.bounding_box(0), (coord(0, 0), coord(1, 1)));
+        assert_eq!(g.bounding_box(3), (coord(1, 3), coord(3, 3)));
+        assert_eq!(g.bounding_box(4), (coord(2, 1), coord(3, 2)));
+
+        assert!(g.spans_single_col(2) && !g.spans_single_row(2));
+        assert!(g.spans_single_col(3));
+        assert!(g.spans_single_row(5) && !g.spans_single_col(5));
+        assert!(!g.spans_single_row(0) && !g.spans_single_col(0));
+
+        assert_eq!(g.cage_of(CellId(6)), Some(1));
+        assert_eq!(g.cage_of(CellId(16)), None);
+    }
+
+    #[test]
+    fn row_stripes_form_a_path() {
+        #[rustfmt::skip]
+        let labels = [
+            0, 0, 0, 0,
+            1, 1, 1, 1,
+            2, 2, 2, 2,
+            3, 3, 3, 3,
+        ];
+        let g = CageGraph::build(&from_labels(4, &labels));
+
+        assert_eq!(g.neighbors(0), &[1]);
+        assert_eq!(g.neighbors(1), &[0, 2]);
+        assert_eq!(g.neighbors(3), &[2]);
+        assert_eq!(g.shared_border_len(1, 2), 4);
+        assert_eq!(g.shared_border_len(0, 2), 0);
+        for cage in 0..4 {
+            assert!(g.spans_single_row(cage));
+            assert!(!g.spans_single_col(cage));
+        }
+    }
+
+    #[test]
+    fn uncovered_cells_are_ignored() {
+        let puzzle = Puzzle {
+            n: 2,
+            cages: vec![
+                Cage {
+                    cells: [CellId(0)].into_iter().collect(),
+                    op: Op::Eq,
+                    target: 1,
+                },
+                Cage {
+                    cells: [CellId(3), CellId(99)].into_iter().collect(),
+                    op: Op::Eq,
+                    target: 1,
+                },
+            ],
+        };
+        let g = CageGraph::build(&puzzle);
+        assert!(g.neighbors(0).is_empty());
+        assert!(g.neighbors(1).is_empty());
+        assert_eq!(g.bounding_box(1), (coord(1, 1), coord(1, 1)));
+    }
+}
@@ -0,0 +1,324 @@
+//! Where each cage's clue goes and how it reads, for rendering clients.
+//!
+//! Like upstream sgt-puzzles, a clue sits in its cage's top-left-most cell: the one with the
+//! smallest [`CellId`], so the first row the cage touches, leftmost within it.
+
+use crate::arith::{applicable_ops, target_for};
+use crate::error::CoreError;
+use crate::puzzle::{
+    CellId, Coord, Puzzle, cage_not_connected, cell_index, coord_unchecked,
+    is_orthogonally_connected,
+};
+use crate::rules::{Op, Ruleset};
+
+/// Glyphs written after a clue's target; singleton (`Eq`) clues are the bare target.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ClueStyle {
+    pub add: char,
+    pub sub: char,
+    pub mul: char,
+    pub div: char,
+}
+
+impl ClueStyle {
+    /// `+ − × ÷`, with a true minus sign (U+2212).
+    pub const UNICODE: Self = Self {
+        add: '+',
+        sub: '−',
+        mul: '×',
+        div: '÷',
+    };
+
+    /// `+ - x /`, for plain-text output.
+    pub const ASCII: Self = Self {
+        add: '+',
+        sub: '-',
+        mul: 'x',
+        div: '/',
+    };
+
+    /// Glyph for `op`, or `None` for `Eq`.
+    pub fn glyph(&self, op: Op) -> Option<char> {
+        match op {
+            Op::Add => Some(self.add),
+            Op::Sub => Some(self.sub),
+            Op::Mul => Some(self.mul),
+            Op::Div => Some(self.div),
+            Op::Eq => None,
+        }
+    }
+
+    /// Clue text for `target` under `op`, e.g. `12×` or `4`.
+    pub fn format(&self, op: Op, target: i64) -> String {
+        match self.glyph(op) {
+            Some(glyph) => format!("{target}{glyph}"),
+            None => target.to_string(),
+        }
+    }
+
+    /// Inverse of [`ClueStyle::format`]: the op and target `text` spells in this style.
+    pub fn parse(&self, text: &str) -> Option<(Op, i64)> {
+        let (op, digits) = match text.chars().next_back() {
+            Some(last) if !last.is_ascii_digit() => {
+                let op = Op::ALL
+                    .into_iter()
+                    .find(|&op| self.glyph(op) == Some(last))?;
+                (op, &text[..text.len() - last.len_utf8()])
+            }
+            _ => (Op::Eq, text),
+        };
+        if digits.is_empty() || !digits.bytes().all(|b| b.is_ascii_digit()) {
+            return None;
+        }
+        Some((op, digits.parse().ok()?))
+    }
+}
+
+impl Default for ClueStyle {
+    fn default() -> Self {
+        Self::UNICODE
+    }
+}
+
+/// One cage's clue, as a renderer draws it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CluePlacement {
+    /// Index of the cage in [`Puzzle::cages`].
+    pub cage: usize,
+    /// Cell the clue is drawn in.
+    pub anchor: Coord,
+    pub text: String,
+}
+
+impl Puzzle {
+    /// Every cage's clue with its anchor cell, ordered by anchor (row-major). Cages without
+    /// cells, which [`Puzzle::validate`] rejects, are skipped.
+    pub fn clue_placements(&self, style: &ClueStyle) -> Vec<CluePlacement> {
+        let mut placements: Vec<(CellId, CluePlacement)> = self
+            .cages
+            .iter()
+            .enumerate()
+            .filter(|(_, cage)| !cage.cells.is_empty())
+            .map(|(cage_idx, cage)| {
+                let anchor = cage.anchor();
+                let placement = CluePlacement {
+                    cage: cage_idx,
+                    anchor: self.coord_of(anchor),
+                    text: style.format(cage.op, cage.target),
+                };
+                (anchor, placement)
+            })
+            .collect();
+        placements.sort_by_key(|&(anchor, ref placement)| (anchor, placement.cage));
+        placements.into_iter().map(|(_, p)| p).collect()
+    }
+}
+
+/// Every clue that fits `cells` as a new cage over the row-major `solution` of an `n` x `n`
+/// grid, in [`Op::ALL`] order as [`applicable_ops`] gives them: the bare digit for one cell,
+/// otherwise the sum and product, then the difference and quotient where they exist and
+/// `rules` allows them.
+///
+/// The selection must be a cage `rules` would accept: non-empty, in range, without repeats,
+/// within [`Ruleset::max_cage_size`], and orthogonally connected when the ruleset asks for it.
+/// The digits under it must lie in `1..=n`; the rest of `solution` is not checked.
+pub fn clue_options(
+    cells: &[CellId],
+    solution: &[u8],
+    n: u8,
+    rules: Ruleset,
+) -> Result<Vec<(Op, i64)>, CoreError> {
+    let a = n as usize * n as usize;
+    if solution.len() != a {
+        return Err(CoreError::SolutionLengthMismatch {
+            expected: a,
+            got: solution.len(),
+        });
+    }
+    if cells.is_empty() {
+        return Err(CoreError::EmptyCage);
+    }
+    let mut seen = vec![false; a];
+    let mut values = Vec::with_capacity(cells.len());
+    for &cell in cells {
+        let idx = cell_index(n, cell)?;
+        if std::mem::replace(&mut seen[idx], true) {
+            return Err(CoreError::CellDuplicated {
+                cell,
+                coord: coord_unchecked(n, cell),
+            });
+        }
+        let value = solution[idx];
+        if !(1..=n).contains(&value) {
+            return Err(CoreError::DigitOutOfRange {
+                row: (idx / n as usize) as u8,
+                col: (idx % n as usize) as u8,
+                value,
+                n,
+            });
+        }
+        values.push(value);
+    }
+    if cells.len() > rules.max_cage_size as usize {
+        return Err(CoreError::CageTooLarge {
+            len: cells.len(),
+            max: rules.max_cage_size,
+        });
+    }
+    if rules.require_orthogonal_cage_connectivity && !is_orthogonally_connected(n, cells) {
+        return Err(cage_not_connected(n, cells));
+    }
+    Ok(applicable_ops(&values, rules)
+        .into_iter()
+        .filter_map(|op| Some((op, target_for(op, &values)?)))
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::puzzle::Cage;
+
+    fn cage(cells: &[u16], op: Op, target: i64) -> Cage {
+        Cage {
+            cells: cells.iter().copied().map(CellId).collect(),
+            op,
+            target,
+        }
+    }
+
+    #[test]
+    fn placements_anchor_on_the_smallest_cell_in_anchor_order() {
+        // 1 2 3 4 / 2 3 4 1 / 3 4 1 2 / 4 1 2 3, cages listed out of anchor order.
+        let puzzle = Puzzle {
+            n: 4,
+            cages: vec![
+                cage(&[10, 11, 14, 15], Op::Mul, 12),
+                cage(&[4, 0, 1], Op::Add, 5),
+                cage(&[9, 5], Op::Sub, 1),
+                cage(&[7, 6, 3, 2], Op::Mul, 48),
+                cage(&[8, 12], Op::Div, 2),
+                cage(&[13], Op::Eq, 1),
+            ],
+        };
+        let placements = puzzle.clue_placements(&ClueStyle::UNICODE);
+        let summary: Vec<_> = placements
+            .iter()
+            .map(|p| (p.cage, p.anchor.row, p.anchor.col, p.text.as_str()))
+            .collect();
+        assert_eq!(
+            summary,
+            [
+                (1, 0, 0, "5+"),
+                (3, 0, 2, "48×"),
+                (2, 1, 1, "1−"),
+                (4, 2, 0, "2÷"),
+                (0, 2, 2, "12×"),
+                (5, 3, 1, "1"),
+            ]
+        );
+
+        let ascii: Vec<_> = puzzle
+            .clue_placements(&ClueStyle::ASCII)
+            .into_iter()
+            .map(|p| p.text)
+            .collect();
+        assert_eq!(ascii, ["5+", "48x", "1-", "2/", "12x", "1"]);
+    }
+
+    #[test]
+    fn formatted_clues_parse_back_in_both_styles() {
+        for style in [ClueStyle::UNICODE, ClueStyle::ASCII] {
+            for op in Op::ALL {
+                for target in [1, 7, 12, 3_628_800] {
+                    let text = style.format(op, target);
+                    assert_eq!(style.parse(&text), Some((op, target)), "{text}");
+                }
+            }
+        }
+        assert_eq!(ClueStyle::UNICODE.parse("3-"), None);
+        assert_eq!(ClueStyle::ASCII.parse("3−"), None);
+        for bad in ["", "+", "x12", "1 2+", "-3"] {
+            assert_eq!(ClueStyle::ASCII.parse(bad), None, "{bad:?}");
+        }
+    }
+
+    /// 1 2 3 4 / 2 3 4 1 / 3 4 1 2 / 4 1 2 3.
+    const SOLUTION: [u8; 16] = [1, 2, 3, 4, 2, 3, 4, 1, 3, 4, 1, 2, 4, 1, 2, 3];
+
+    fn options(cells: &[u16], rules: Ruleset) -> Result<Vec<(Op, i64)>, CoreError> {
+        let cells: Vec<CellId> = cells.iter().copied().map(CellId).collect();
+        clue_options(&cells, &SOLUTION, 4, rules)
+    }
+
+    #[test]
+    fn clue_options_follow_the_selected_digits() {
+        let rules = Ruleset::keen_baseline();
+        assert_eq!(options(&[6], rules).unwrap(), [(Op::Eq, 4)]);
+        // 4 over 1 divides exactly; 2 and 3 do not.
+        assert_eq!(
+            options(&[3, 7], rules).unwrap(),
+            [(Op::Add, 5), (Op::Mul, 4), (Op::Sub, 3), (Op::Div, 4)]
+        );
+        assert_eq!(
+            options(&[1, 2], rules).unwrap(),
+            [(Op::Add, 5), (Op::Mul, 6), (Op::Sub, 1)]
+        );
+        // Sub and Div are two-cell only in the baseline.
+        assert_eq!(
+            options(&[10, 11, 14, 15], rules).unwrap(),
+            [(Op::Add, 8), (Op::Mul, 12)]
+        );
+    }
+
+    #[test]
+    fn clue_options_reject_selections_that_are_not_cages() {
+        let rules = Ruleset::keen_baseline();
+        assert!(matches!(options(&[], rules), Err(CoreError::EmptyCage)));
+        assert!(matches!(
+            options(&[0, 16], rules),
+            Err(CoreError::CellOutOfRange {
+                n: 4,
+                cell: CellId(16)
+            })
+        ));
+        assert!(matches!(
+            options(&[0, 1, 0], rules),
+            Err(CoreError::CellDuplicated {
+                cell: CellId(0),
+                ..
+            })
+        ));
+        assert!(matches!(
+            options(&[0, 5], rules),
+            Err(CoreError::CageNotConnected { .. })
+        ));
+        let loose = Ruleset {
+            require_orthogonal_cage_connectivity: false,
+            ..rules
+        };
+        assert_eq!(
+            options(&[0, 5], loose).unwrap(),
+            [(Op::Add, 4), (Op::Mul, 3), (Op::Sub, 2), (Op::Div, 3)]
+        );
+        let small = Ruleset {
+            max_cage_size: 3,
+            ..rules
+        };
+        assert!(matches!(
+            options(&[10, 11, 14, 15], small),
+            Err(CoreError::CageTooLarge { len: 4, max: 3 })
+        ));
+        let mut blank = SOLUTION;
+        blank[5] = 0;
+        assert!(matches!(
+            clue_options(&[CellId(4), CellId(5)], &blank, 4, rules),
+            Err(CoreError::DigitOutOfRange {
+                row: 1,
+                col: 1,
+                value: 0,
+                n: 4
+            })
+        ));
+    }
+}
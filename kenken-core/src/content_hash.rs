@@ -0,0 +1,243 @@
+//! Stable content hashes of puzzles, for caches and dedup keyed on what a puzzle says rather
+//! than how its cage list happens to be ordered.
+//!
+//! # Format (stable across releases)
+//!
+//! [`Puzzle::content_hash`] and [`Puzzle::content_hash128`] run SipHash-2-4 (64- and 128-bit
+//! outputs, as published by Aumasson and Bernstein) keyed with [`CONTENT_HASH_KEY`] over this
+//! byte encoding of the puzzle in [`Puzzle::normalize`]d order:
+//!
+//! - `n` as one byte, then the cage count as a little-endian `u32`;
+//! - per cage: the op as one byte (`Add` 0, `Mul` 1, `Sub` 2, `Div` 3, `Eq` 4), the target as
+//!   a little-endian `i64`, the cell count as a little-endian `u16`, then each cell id as a
+//!   little-endian `u16`.
+//!
+//! The 128-bit hash is the 16 output bytes read as a little-endian `u128`. Changing any of
+//! this changes every stored hash, so it must not happen; the golden corpus pins the hash of
+//! each of its puzzles.
+
+use crate::puzzle::Puzzle;
+use crate::rules::Op;
+
+/// SipHash key of the content hashes: the ASCII bytes of `"rustykeen/puzzle"`.
+pub const CONTENT_HASH_KEY: [u8; 16] = *b"rustykeen/puzzle";
+
+impl Puzzle {
+    /// Sort each cage's cells ascending, then the cages by their cells (so by smallest cell
+    /// first for a valid puzzle). Puzzles that differ only in cage or cell order normalize to
+    /// the same value, which is what [`content_hash`](Self::content_hash) hashes.
+    pub fn normalize(&mut self) {
+        for cage in &mut self.cages {
+            cage.cells.sort_unstable();
+        }
+        self.cages.sort_by(|a, b| {
+            a.cells
+                .cmp(&b.cells)
+                .then_with(|| op_code(a.op).cmp(&op_code(b.op)))
+                .then_with(|| a.target.cmp(&b.target))
+        });
+    }
+
+    /// 64-bit stable hash of `n` and the cage list, independent of cage and cell order; see the
+    /// [module docs](crate::content_hash) for the exact format.
+    pub fn content_hash(&self) -> u64 {
+        let (v0, v1, v2, v3) = SipState::new(false)
+            .absorb(&self.canonical_bytes())
+            .finish(0xff);
+        v0 ^ v1 ^ v2 ^ v3
+    }
+
+    /// 128-bit variant of [`content_hash`](Self::content_hash), for keys shared across many
+    /// puzzles where 64-bit collisions start to matter.
+    pub fn content_hash128(&self) -> u128 {
+        let mut state = SipState::new(true).absorb(&self.canonical_bytes());
+        let low = state.finish(0xee);
+        state.v1 ^= 0xdd;
+        let high = state.finish(0);
+        let fold = |(v0, v1, v2, v3): (u64, u64, u64, u64)| v0 ^ v1 ^ v2 ^ v3;
+        (u128::from(fold(high)) << 64) | u128::from(fold(low))
+    }
+
+    fn canonical_bytes(&self) -> Vec<u8> {
+        let mut canonical = self.clone();
+        canonical.normalize();
+        let cells: usize = canonical.cages.iter().map(|c| c.cells.len()).sum();
+        let mut out = Vec::with_capacity(5 + canonical.cages.len() * 11 + cells * 2);
+        out.push(canonical.n);
+        out.extend_from_slice(&(canonical.cages.len() as u32).to_le_bytes());
+        for cage in &canonical.cages {
+            out.push(op_code(cage.op));
+            out.extend_from_slice(&cage.target.to_le_bytes());
+            out.extend_from_slice(&(cage.cells.len() as u16).to_le_bytes());
+            for cell in &cage.cells {
+                out.extend_from_slice(&cell.0.to_le_bytes());
+            }
+        }
+        out
+    }
+}
+
+/// Op byte of the hash format; fixed independently of `Op`'s declaration order.
+fn op_code(op: Op) -> u8 {
+    match op {
+        Op::Add => 0,
+        Op::Mul => 1,
+        Op::Sub => 2,
+        Op::Div => 3,
+        Op::Eq => 4,
+    }
+}
+
+/// SipHash-2-4 over a whole message at once.
+struct SipState {
+    v0: u64,
+    v1: u64,
+    v2: u64,
+    v3: u64,
+}
+
+impl SipState {
+    fn new(wide: bool) -> Self {
+        let k0 = u64::from_le_bytes(CONTENT_HASH_KEY[..8].try_into().unwrap());
+        let k1 = u64::from_le_bytes(CONTENT_HASH_KEY[8..].try_into().unwrap());
+        Self::with_key(k0, k1, wide)
+    }
+
+    fn with_key(k0: u64, k1: u64, wide: bool) -> Self {
+        Self {
+            v0: k0 ^ 0x736f_6d65_7073_6575,
+            v1: k1 ^ 0x646f_7261_6e64_6f6d ^ if wide { 0xee } else { 0 },
+            v2: k0 ^ 0x6c79_6765_6e65_7261,
+            v3: k1 ^ 0x7465_6462_7974_6573,
+        }
+    }
+
+    fn round(&mut self) {
+        self.v0 = self.v0.wrapping_add(self.v1);
+        self.v1 = self.v1.rotate_left(13) ^ self.v0;
+        self.v0 = self.v0.rotate_left(32);
+        self.v2 = self.v2.wrapping_add(self.v3);
+        self.v3 = self.v3.rotate_left(16) ^ self.v2;
+        self.v0 = self.v0.wrapping_add(self.v3);
+        self.v3 = self.v3.rotate_left(21) ^ self.v0;
+        self.v2 = self.v2.wrapping_add(self.v1);
+        self.v1 = self.v1.rotate_left(17) ^ self.v2;
+        self.v2 = self.v2.rotate_left(32);
+    }
+
+    fn compress(&mut self, word: u64) {
+        self.v3 ^= word;
+        self.round();
+        self.round();
+        self.v0 ^= word;
+    }
+
+    /// Every word of `message`, then the final length-tagged word.
+    fn absorb(mut self, message: &[u8]) -> Self {
+        let mut words = message.chunks_exact(8);
+        for word in &mut words {
+            self.compress(u64::from_le_bytes(word.try_into().unwrap()));
+        }
+        let mut last = [0u8; 8];
+        last[..words.remainder().len()].copy_from_slice(words.remainder());
+        last[7] = message.len() as u8;
+        self.compress(u64::from_le_bytes(last));
+        self
+    }
+
+    /// Finalization rounds after `v2 ^= tag`, returning the state to fold.
+    fn finish(&mut self, tag: u64) -> (u64, u64, u64, u64) {
+        self.v2 ^= tag;
+        for _ in 0..4 {
+            self.round();
+        }
+        (self.v0, self.v1, self.v2, self.v3)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use smallvec::smallvec;
+
+    use super::*;
+    use crate::puzzle::{Cage, CellId};
+
+    /// The reference vectors' key (bytes 0..16) over the message `0..len`.
+    fn reference(len: u8, wide: bool) -> SipState {
+        let k0 = u64::from_le_bytes([0, 1, 2, 3, 4, 5, 6, 7]);
+        let k1 = u64::from_le_bytes([8, 9, 10, 11, 12, 13, 14, 15]);
+        let message: Vec<u8> = (0..len).collect();
+        SipState::with_key(k0, k1, wide).absorb(&message)
+    }
+
+    #[test]
+    fn siphash_matches_reference_vectors() {
+        let (v0, v1, v2, v3) = reference(0, false).finish(0xff);
+        assert_eq!(v0 ^ v1 ^ v2 ^ v3, 0x726f_db47_dd0e_0e31);
+        let (v0, v1, v2, v3) = reference(15, false).finish(0xff);
+        assert_eq!(v0 ^ v1 ^ v2 ^ v3, 0xa129_ca61_49be_45e5);
+
+        let mut state = reference(0, true);
+        let (v0, v1, v2, v3) = state.finish(0xee);
+        assert_eq!(
+            (v0 ^ v1 ^ v2 ^ v3).to_le_bytes(),
+            [0xa3, 0x81, 0x7f, 0x04, 0xba, 0x25, 0xa8, 0xe6]
+        );
+        state.v1 ^= 0xdd;
+        let (v0, v1, v2, v3) = state.finish(0);
+        assert_eq!(
+            (v0 ^ v1 ^ v2 ^ v3).to_le_bytes(),
+            [0x6d, 0xf6, 0x72, 0x14, 0xc7, 0x55, 0x02, 0x93]
+        );
+    }
+
+    fn two_by_two() -> Puzzle {
+        Puzzle {
+            n: 2,
+            cages: vec![
+                Cage {
+                    cells: smallvec![CellId(0), CellId(1)],
+                    op: Op::Add,
+                    target: 3,
+                },
+                Cage {
+                    cells: smallvec![CellId(2), CellId(3)],
+                    op: Op::Sub,
+                    target: 1,
+                },
+            ],
+        }
+    }
+
+    #[test]
+    fn hash_ignores_cage_and_cell_order() {
+        let puzzle = two_by_two();
+        let mut shuffled = puzzle.clone();
+        shuffled.cages.reverse();
+        shuffled.cages[0].cells.reverse();
+        assert_ne!(shuffled, puzzle);
+        assert_eq!(shuffled.content_hash(), puzzle.content_hash());
+        assert_eq!(shuffled.content_hash128(), puzzle.content_hash128());
+
+        shuffled.normalize();
+        assert_eq!(shuffled, puzzle);
+    }
+
+    #[test]
+    fn hash_changes_with_any_clue() {
+        let puzzle = two_by_two();
+        let mut retargeted = puzzle.clone();
+        retargeted.cages[1].target = 2;
+        assert_ne!(retargeted.content_hash(), puzzle.content_hash());
+        assert_ne!(retargeted.content_hash128(), puzzle.content_hash128());
+
+        let mut reop = puzzle.clone();
+        reop.cages[0].op = Op::Mul;
+        reop.cages[0].target = 2;
+        assert_ne!(reop.content_hash(), puzzle.content_hash());
+
+        let mut regrown = puzzle.clone();
+        regrown.n = 3;
+        assert_ne!(regrown.content_hash(), puzzle.content_hash());
+    }
+}
@@ -0,0 +1,355 @@
+//! Incremental, validated edits on a `Puzzle`.
+//!
+//! Every edit builds the changed cages on the side, checks them with
+//! [`Cage::validate_shape`] under the editor's ruleset, and only then writes them back, so a
+//! failed edit leaves the puzzle exactly as it was. Edits never change which cells are covered,
+//! which means a puzzle that passed [`Puzzle::validate`] when the editor was created still
+//! passes after any successful edit.
+//!
+//! Cage cell lists are kept in ascending `CellId` order, matching what the sgt-desc parser
+//! produces; that makes an edit followed by its inverse reproduce the original puzzle exactly.
+
+use smallvec::SmallVec;
+
+use crate::error::CoreError;
+use crate::limits::INLINE_CAGE_CELLS;
+use crate::puzzle::{Cage, CellId, Puzzle, coord};
+use crate::rules::{Op, Ruleset};
+
+/// A validated puzzle plus the ruleset its edits are checked against.
+///
+/// ```
+/// use kenken_core::format::sgt_desc::parse_keen_desc;
+/// use kenken_core::rules::{Op, Ruleset};
+/// use kenken_core::{CellId, PuzzleEditor};
+///
+/// let puzzle = parse_keen_desc(2, "b__,a3a3")?;
+/// let mut editor = PuzzleEditor::new(puzzle.clone(), Ruleset::keen_baseline())?;
+///
+/// // Split the top `3+` pair into two givens, then merge it back.
+/// let top = editor.cage_of(CellId(0)).unwrap();
+/// let new = editor.split_cage(top, &[CellId(1)], Op::Eq, 2)?;
+/// editor.set_clue(top, Op::Eq, 1)?;
+/// editor.merge_cages(top, new, Op::Add, 3)?;
+/// assert_eq!(editor.into_puzzle(), puzzle);
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PuzzleEditor {
+    puzzle: Puzzle,
+    rules: Ruleset,
+}
+
+impl PuzzleEditor {
+    /// Wrap `puzzle` for editing. Fails if it does not already pass `validate(rules)`.
+    pub fn new(puzzle: Puzzle, rules: Ruleset) -> Result<Self, CoreError> {
+        puzzle.validate(rules)?;
+        Ok(Self { puzzle, rules })
+    }
+
+    pub fn puzzle(&self) -> &Puzzle {
+        &self.puzzle
+    }
+
+    pub fn rules(&self) -> Ruleset {
+        self.rules
+    }
+
+    pub fn into_puzzle(self) -> Puzzle {
+        self.puzzle
+    }
+
+    /// Index of the cage containing `cell`, if the cell is on the grid.
+    pub fn cage_of(&self, cell: CellId) -> Option<usize> {
+        self.puzzle
+            .cages
+            .iter()
+            .position(|cage| cage.cells.contains(&cell))
+    }
+
+    /// Move `cells_to_extract` out of cage `cage_idx` into a new cage with `new_op` and
+    /// `new_target`, appended at the end of the cage list. Returns the new cage's index.
+    ///
+    /// The source cage keeps its clue; both cages must still pass `validate_shape`, so
+    /// extracting nothing or everything fails with `EmptyCage`.
+    pub fn split_cage(
+        &mut self,
+        cage_idx: usize,
+        cells_to_extract: &[CellId],
+        new_op: Op,
+        new_target: i64,
+    ) -> Result<usize, CoreError> {
+        let source = self.cage(cage_idx)?;
+        for (i, &cell) in cells_to_extract.iter().enumerate() {
+            if !source.cells.contains(&cell) {
+                return Err(CoreError::CellNotInCage {
+                    cell,
+                    cage: cage_idx,
+                });
+            }
+            if cells_to_extract[..i].contains(&cell) {
+                return Err(CoreError::CellDuplicated {
+                    cell,
+                    coord: self.puzzle.coord_of(cell),
+                });
+            }
+        }
+
+        let (extracted, kept): (
+            SmallVec<[CellId; INLINE_CAGE_CELLS]>,
+            SmallVec<[CellId; INLINE_CAGE_CELLS]>,
+        ) = source
+            .cells
+            .iter()
+            .copied()
+            .partition(|cell| cells_to_extract.contains(cell));
+        let kept = Cage {
+            cells: kept,
+            ..source.clone()
+        };
+        let new = Cage {
+            cells: extracted,
+            op: new_op,
+            target: new_target,
+        };
+        self.check(&kept, cage_idx)?;
+        self.check(&new, self.puzzle.cages.len())?;
+
+        self.puzzle.cages[cage_idx] = kept;
+        self.puzzle.cages.push(new);
+        Ok(self.puzzle.cages.len() - 1)
+    }
+
+    /// Merge cage `absorb` into cage `keep` under the clue `op`/`target`, removing `absorb`
+    /// from the cage list (later indices shift down by one). The inverse of
+    /// [`split_cage`](Self::split_cage) when `absorb` is the cage it returned.
+    ///
+    /// Merging a cage with itself fails with `CellDuplicated`.
+    pub fn merge_cages(
+        &mut self,
+        keep: usize,
+        absorb: usize,
+        op: Op,
+        target: i64,
+    ) -> Result<(), CoreError> {
+        let kept = self.cage(keep)?;
+        let absorbed = self.cage(absorb)?;
+        if keep == absorb {
+            let cell = kept.cells[0];
+            return Err(CoreError::CellDuplicated {
+                cell,
+                coord: self.puzzle.coord_of(cell),
+            });
+        }
+
+        let mut cells: SmallVec<[CellId; INLINE_CAGE_CELLS]> =
+            kept.cells.iter().chain(&absorbed.cells).copied().collect();
+        cells.sort_unstable();
+        let merged = Cage { cells, op, target };
+        self.check(&merged, keep)?;
+
+        self.puzzle.cages[keep] = merged;
+        self.puzzle.cages.remove(absorb);
+        Ok(())
+    }
+
+    /// Move `cell` from its current cage into cage `into_cage`. Both clues are unchanged.
+    ///
+    /// Fails with `EmptyCage` if `cell` is the last cell of its cage, and with whatever
+    /// `validate_shape` reports (e.g. `CageNotConnected`) if either cage becomes invalid under
+    /// the ruleset. Moving a cell into the cage it is already in is a no-op.
+    pub fn move_cell(&mut self, cell: CellId, into_cage: usize) -> Result<(), CoreError> {
+        coord(self.puzzle.n, cell)?;
+        let target = self.cage(into_cage)?;
+        let from = self.cage_of(cell).ok_or(CoreError::CellUncovered {
+            cell,
+            coord: self.puzzle.coord_of(cell),
+        })?;
+        if from == into_cage {
+            return Ok(());
+        }
+
+        let mut grown = target.clone();
+        let at = grown.cells.partition_point(|&c| c < cell);
+        grown.cells.insert(at, cell);
+        let mut shrunk = self.puzzle.cages[from].clone();
+        shrunk.cells.retain(|&mut c| c != cell);
+        self.check(&shrunk, from)?;
+        self.check(&grown, into_cage)?;
+
+        self.puzzle.cages[from] = shrunk;
+        self.puzzle.cages[into_cage] = grown;
+        Ok(())
+    }
+
+    /// Replace the operation and target of cage `cage_idx`.
+    pub fn set_clue(&mut self, cage_idx: usize, op: Op, target: i64) -> Result<(), CoreError> {
+        let cage = Cage {
+            op,
+            target,
+            ..self.cage(cage_idx)?.clone()
+        };
+        self.check(&cage, cage_idx)?;
+
+        self.puzzle.cages[cage_idx] = cage;
+        Ok(())
+    }
+
+    fn cage(&self, idx: usize) -> Result<&Cage, CoreError> {
+        self.puzzle
+            .cages
+            .get(idx)
+            .ok_or(CoreError::CageIndexOutOfRange {
+                index: idx,
+                len: self.puzzle.cages.len(),
+            })
+    }
+
+    /// `cage` as the cage at `idx`; the index goes into any error.
+    fn check(&self, cage: &Cage, idx: usize) -> Result<(), CoreError> {
+        cage.validate_shape(self.puzzle.n, self.rules)
+            .map_err(|e| e.in_cage(idx))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::PuzzleBuilder;
+
+    /// 3x3 with an L-shaped `6+` over (0,0), (0,1), (1,0) and givens elsewhere.
+    fn editor() -> PuzzleEditor {
+        let rules = Ruleset::keen_baseline();
+        let puzzle = PuzzleBuilder::new(3)
+            .cage(Op::Add, 6, &[(0, 0), (0, 1), (1, 0)])
+            .unwrap()
+            .auto_singletons(&[1, 2, 3, 3, 1, 2, 2, 3, 1])
+            .unwrap()
+            .build(rules)
+            .unwrap();
+        PuzzleEditor::new(puzzle, rules).unwrap()
+    }
+
+    #[test]
+    fn split_rejects_foreign_and_repeated_cells() {
+        let mut ed = editor();
+        let before = ed.clone();
+
+        let err = ed.split_cage(0, &[CellId(4)], Op::Eq, 1).unwrap_err();
+        assert!(matches!(
+            err,
+            CoreError::CellNotInCage {
+                cell: CellId(4),
+                cage: 0
+            }
+        ));
+        let err = ed
+            .split_cage(0, &[CellId(1), CellId(1)], Op::Eq, 2)
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            CoreError::CellDuplicated {
+                cell: CellId(1),
+                ..
+            }
+        ));
+        assert_eq!(ed, before);
+    }
+
+    #[test]
+    fn split_rejects_empty_and_disconnected_results() {
+        let mut ed = editor();
+        let before = ed.clone();
+
+        let err = ed.split_cage(0, &[], Op::Eq, 1).unwrap_err();
+        assert!(matches!(err, CoreError::EmptyCage));
+        let err = ed
+            .split_cage(0, &[CellId(0), CellId(1), CellId(3)], Op::Add, 6)
+            .unwrap_err();
+        assert!(matches!(err, CoreError::EmptyCage));
+        // Taking the corner leaves (0,1) and (1,0) touching only diagonally.
+        let err = ed.split_cage(0, &[CellId(0)], Op::Eq, 1).unwrap_err();
+        assert!(matches!(
+            err,
+            CoreError::CageNotConnected { cage: Some(0), ref components, .. }
+                if components.len() == 2
+        ));
+        assert_eq!(ed, before);
+    }
+
+    #[test]
+    fn move_cell_rejects_emptying_and_disconnecting() {
+        let mut ed = editor();
+        let before = ed.clone();
+
+        // (2,2) is the only cell of its given.
+        let err = ed.move_cell(CellId(8), 0).unwrap_err();
+        assert!(matches!(err, CoreError::EmptyCage));
+        // Pulling the corner out of the L disconnects what remains.
+        let given = ed.cage_of(CellId(4)).unwrap();
+        let err = ed.move_cell(CellId(0), given).unwrap_err();
+        assert!(matches!(err, CoreError::CageNotConnected { .. }));
+        assert!(matches!(
+            ed.move_cell(CellId(9), 0).unwrap_err(),
+            CoreError::CellOutOfRange { n: 3, .. }
+        ));
+        assert!(matches!(
+            ed.move_cell(CellId(1), 99).unwrap_err(),
+            CoreError::CageIndexOutOfRange { index: 99, len: 7 }
+        ));
+        assert_eq!(ed, before);
+
+        ed.move_cell(CellId(1), 0).unwrap();
+        assert_eq!(ed, before);
+    }
+
+    #[test]
+    fn set_clue_rejects_invalid_clues() {
+        let mut ed = editor();
+        let before = ed.clone();
+
+        assert!(matches!(
+            ed.set_clue(0, Op::Sub, 1).unwrap_err(),
+            CoreError::SubDivMustBeTwoCell
+        ));
+        assert!(matches!(
+            ed.set_clue(0, Op::Add, 0).unwrap_err(),
+            CoreError::TargetMustBeNonZero
+        ));
+        assert!(matches!(
+            ed.set_clue(1, Op::Eq, 4).unwrap_err(),
+            CoreError::EqTargetOutOfRange
+        ));
+        assert_eq!(ed, before);
+
+        ed.set_clue(0, Op::Mul, 6).unwrap();
+        assert_eq!(ed.puzzle().cages[0].op, Op::Mul);
+    }
+
+    #[test]
+    fn merge_rejects_self_merge() {
+        let mut ed = editor();
+        assert!(matches!(
+            ed.merge_cages(0, 0, Op::Add, 6).unwrap_err(),
+            CoreError::CellDuplicated {
+                cell: CellId(0),
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn new_rejects_invalid_puzzle() {
+        let puzzle = Puzzle {
+            n: 2,
+            cages: Vec::new(),
+        };
+        assert!(matches!(
+            PuzzleEditor::new(puzzle, Ruleset::keen_baseline()),
+            Err(CoreError::CellUncovered {
+                cell: CellId(0),
+                ..
+            })
+        ));
+    }
+}
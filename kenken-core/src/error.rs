@@ -11,11 +11,11 @@ pub enum CoreError {
     #[error("cell id {cell} out of range for N={n}")]
     CellOutOfRange { n: u8, cell: CellId },
 
-    #[error("cell id {0} appears in more than one cage")]
-    CellDuplicated(CellId),
+    #[error("cell id {cell} ({coord}) appears in more than one cage")]
+    CellDuplicated { cell: CellId, coord: Coord },
 
-    #[error("grid cell {0} is not covered by any cage")]
-    CellUncovered(CellId),
+    #[error("grid cell {cell} ({coord}) is not covered by any cage")]
+    CellUncovered { cell: CellId, coord: Coord },
 
     #[error("cage operation {op:?} not valid for cage size {len}")]
     InvalidOpForCageSize { op: crate::rules::Op, len: usize },
@@ -32,8 +32,126 @@ pub enum CoreError {
     #[error("cage target must be non-zero")]
     TargetMustBeNonZero,
 
-    #[error("cage is not orthogonally connected")]
-    CageNotConnected,
+    #[error("{op:?} cage target {target} is negative")]
+    TargetNegative { op: crate::rules::Op, target: i64 },
+
+    #[error("cage {cage} cells are not sorted ascending by cell id")]
+    CageCellsUnsorted { cage: usize },
+
+    /// `components` are the cage's orthogonally connected pieces, ordered by their first cell
+    /// and each in row-major order; `anchor` is its top-left-most cell. `cage` is the cage's
+    /// index when it was checked as part of a puzzle, `None` for cells checked on their own.
+    #[error(
+        "{} at {anchor} is not orthogonally connected: {} pieces, {}",
+        cage_label(*cage),
+        components.len(),
+        pieces(components)
+    )]
+    CageNotConnected {
+        cage: Option<usize>,
+        anchor: Coord,
+        components: Vec<Vec<Coord>>,
+    },
+
+    #[error("solution has {got} cells, expected {expected}")]
+    SolutionLengthMismatch { expected: usize, got: usize },
+
+    #[error("digit {value} at row {row}, col {col} is outside 1..={n}")]
+    DigitOutOfRange { row: u8, col: u8, value: u8, n: u8 },
+
+    #[error("row {row} repeats {value} at cols {first_col} and {second_col}")]
+    RowRepeat {
+        row: u8,
+        value: u8,
+        first_col: u8,
+        second_col: u8,
+    },
+
+    #[error("col {col} repeats {value} at rows {first_row} and {second_row}")]
+    ColumnRepeat {
+        col: u8,
+        value: u8,
+        first_row: u8,
+        second_row: u8,
+    },
+
+    #[error("cage {cage} ({op:?} {target}, anchored at row {row}, col {col}) is not satisfied")]
+    CageUnsatisfied {
+        cage: usize,
+        op: crate::rules::Op,
+        target: i64,
+        row: u8,
+        col: u8,
+    },
+
+    #[error("cage index {index} out of range for {len} cages")]
+    CageIndexOutOfRange { index: usize, len: usize },
+
+    #[error("cell id {cell} is not in cage {cage}")]
+    CellNotInCage { cell: CellId, cage: usize },
+
+    #[error("cage {cage} has an Eq clue on {len} cells")]
+    EqCageNotSingleton { cage: usize, len: usize },
+
+    #[error("cage {cage}: {op:?} target {target} is outside {min}..={max}")]
+    ClueTargetOutOfRange {
+        cage: usize,
+        op: crate::rules::Op,
+        target: i64,
+        min: i64,
+        max: i64,
+    },
+
+    #[error("cage {cage}: no digits reach {op:?} target {target}")]
+    ClueUnsatisfiable {
+        cage: usize,
+        op: crate::rules::Op,
+        target: i64,
+    },
+
+    /// The sgt desc format has no way to write this cage faithfully; `reason` says why.
+    #[error("cage {cage_index} cannot be written as an sgt desc: {reason}")]
+    NotRepresentableInSgtDesc {
+        cage_index: usize,
+        reason: &'static str,
+    },
+}
+
+impl CoreError {
+    /// This error with `index` filled in as the cage a [`Cage::validate_shape`] error came
+    /// from.
+    ///
+    /// [`Cage::validate_shape`]: crate::Cage::validate_shape
+    pub(crate) fn in_cage(self, index: usize) -> Self {
+        match self {
+            CoreError::CageNotConnected {
+                cage: None,
+                anchor,
+                components,
+            } => CoreError::CageNotConnected {
+                cage: Some(index),
+                anchor,
+                components,
+            },
+            e => e,
+        }
+    }
+}
+
+fn cage_label(cage: Option<usize>) -> String {
+    cage.map_or_else(|| "cage".to_string(), |index| format!("cage {index}"))
+}
+
+/// `[r1c1 r1c2] [r3c3]`.
+fn pieces(components: &[Vec<Coord>]) -> String {
+    components
+        .iter()
+        .map(|piece| {
+            let cells: Vec<String> = piece.iter().map(Coord::to_string).collect();
+            format!("[{}]", cells.join(" "))
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
 }
 
-use crate::puzzle::CellId;
+use crate::puzzle::{CellId, Coord};
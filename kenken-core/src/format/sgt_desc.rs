@@ -1,58 +1,188 @@
+use core::fmt;
+
 use crate::error::CoreError;
-use crate::puzzle::{Cage, CellId, Puzzle};
-use crate::rules::{Op, Ruleset};
+use crate::puzzle::{Cage, CellId, Coord, Puzzle, is_orthogonally_connected};
+use crate::rules::{Op, Ruleset, RulesetDescriptorError};
 
+/// Why a desc failed to parse. Offsets count characters from the start of the desc.
 #[derive(Debug, thiserror::Error)]
 pub enum SgtDescError {
-    #[error("expected ',' after block structure")]
-    MissingComma,
+    #[error("expected ',' after block structure at offset {offset}")]
+    MissingComma { offset: usize },
+
+    #[error("invalid character {ch:?} in block structure at offset {offset}")]
+    InvalidBlockChar { offset: usize, ch: char },
+
+    #[error("block structure: run at offset {offset} goes past the last of {edges} edges")]
+    BlockTooMuchData { offset: usize, edges: usize },
+
+    /// `read` and `expected` count edge slots, including the closing slot after the last
+    /// internal edge that ends the final run.
+    #[error("block structure ends at offset {offset} after {read} of {expected} edge slots")]
+    BlockNotEnoughData {
+        offset: usize,
+        read: usize,
+        expected: usize,
+    },
+
+    /// `remaining` counts edge slots left before the run, as in `BlockNotEnoughData`.
+    #[error(
+        "block structure: repeat count {count} at offset {offset} needs more than the {remaining} edge slots left"
+    )]
+    RepeatCountTooLarge {
+        offset: usize,
+        count: u64,
+        remaining: usize,
+    },
+
+    #[error("number at offset {offset} is longer than {max_digits} digits")]
+    NumberTooLong { offset: usize, max_digits: usize },
+
+    #[error("{site}: clue stream ends at offset {offset}")]
+    CluesTooFew { offset: usize, site: ClueSite },
+
+    #[error("unexpected data at offset {offset} after the last of {clues} clues")]
+    CluesTooMany { offset: usize, clues: usize },
 
-    #[error("invalid character in block structure")]
-    InvalidBlockChar,
+    #[error("{site}: unrecognized clue type {ch:?} at offset {offset}")]
+    ClueTypeUnknown {
+        offset: usize,
+        site: ClueSite,
+        ch: char,
+    },
 
-    #[error("block structure: too much data")]
-    BlockTooMuchData,
+    /// A clue that is not an op letter followed by an integer target, as found by
+    /// [`validate_keen_desc_syntax`], which does not know the clue's cage.
+    #[error("clue {index}: expected an op letter and an integer target at offset {offset}")]
+    ClueSyntax { offset: usize, index: usize },
 
-    #[error("block structure: not enough data")]
-    BlockNotEnoughData,
+    #[error("{site}: {} cage has {} cells", op_noun(*.op), .site.cells)]
+    SubDivMustBeTwoCell {
+        offset: usize,
+        site: ClueSite,
+        op: Op,
+    },
 
-    #[error("unexpected end of clue stream")]
-    CluesTooFew,
+    #[error("{site}: missing or invalid target at offset {offset}")]
+    InvalidTarget { offset: usize, site: ClueSite },
 
-    #[error("too many clues for block structure")]
-    CluesTooMany,
+    /// Every clue's target is positive, whatever its op; see [`CoreError::TargetNegative`].
+    #[error("{site}: target {target} at offset {offset} is not positive")]
+    TargetNotPositive {
+        offset: usize,
+        site: ClueSite,
+        target: i64,
+    },
 
-    #[error("unrecognized clue type")]
-    ClueTypeUnknown,
+    #[error("{site}: given {target} is outside 1..={n}")]
+    GivenOutOfRange {
+        offset: usize,
+        site: ClueSite,
+        target: i64,
+        n: u8,
+    },
 
-    #[error("subtraction/division cages must have area 2")]
-    SubDivMustBeTwoCell,
+    /// The block structure's edge slots fit no grid size from 2 to 16, so
+    /// [`parse_keen_desc_auto`] has no size to parse it at.
+    #[error("block structure does not match any grid size from 2 to 16")]
+    UnknownGridSize,
 
-    #[error("invalid target number")]
-    InvalidTarget,
+    /// An extended desc opens a `[` ruleset header and never closes it.
+    #[error("ruleset header has no closing ']'")]
+    RulesetHeaderUnterminated,
+
+    #[error("invalid ruleset header: {0}")]
+    InvalidRulesetHeader(RulesetDescriptorError),
 
     #[error(transparent)]
     Core(#[from] CoreError),
 }
 
+/// The clue a [`SgtDescError`] is about: its index in the clue stream and its cage.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ClueSite {
+    /// Index in the clue stream; clues are ordered by their cage's smallest cell id.
+    pub index: usize,
+    /// The cage's smallest (row-major first) cell.
+    pub anchor: Coord,
+    /// Cells in the cage; 1 means a given (an `Eq` cage).
+    pub cells: usize,
+}
+
+impl fmt::Display for ClueSite {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let Coord { row, col } = self.anchor;
+        if self.cells == 1 {
+            write!(f, "clue {} (given at r{row}c{col})", self.index)
+        } else {
+            write!(f, "clue {} (cage anchored at r{row}c{col})", self.index)
+        }
+    }
+}
+
+fn op_noun(op: Op) -> &'static str {
+    match op {
+        Op::Add => "addition",
+        Op::Mul => "multiplication",
+        Op::Sub => "subtraction",
+        Op::Div => "division",
+        Op::Eq => "given",
+    }
+}
+
 /// Parse the upstream sgt-puzzles Keen "desc" format into a `Puzzle`.
 ///
 /// Notes:
 /// - The upstream format does not explicitly represent 1-cell cages with an `Eq` op.
-/// - This parser maps any 1-cell cage to `Op::Eq` regardless of clue type.
+/// - This parser maps any 1-cell cage to `Op::Eq` regardless of clue type, so the
+///   two-cell rule for Sub/Div clues does not apply to them; their target must be a digit.
 pub fn parse_keen_desc(n: u8, desc: &str) -> Result<Puzzle, SgtDescError> {
-    if !(1..=16).contains(&n) {
-        return Err(CoreError::InvalidGridSize(n).into());
+    parse_keen_desc_with_rules(n, desc, Ruleset::keen_baseline())
+}
+
+/// Like [`parse_keen_desc`], but validates against `rules` instead of the baseline.
+///
+/// With `sub_div_two_cell_only` off, Sub/Div clues on cages of any size are accepted and use
+/// the n-ary semantics of [`Op::apply`].
+pub fn parse_keen_desc_with_rules(
+    n: u8,
+    desc: &str,
+    rules: Ruleset,
+) -> Result<Puzzle, SgtDescError> {
+    parse_desc(n, Cursor::new(desc), rules, Dialect::Upstream)
+}
+
+/// Which clue letters a desc uses.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Dialect {
+    /// Upstream Keen: `a`/`m`/`s`/`d`, with 1-cell cages read as givens whatever their letter.
+    Upstream,
+    /// [`encode_keen_desc_ext`]: every cage's op spelled out, `e` for `Eq`.
+    Ext,
+}
+
+fn parse_desc(
+    n: u8,
+    mut it: Cursor<'_>,
+    rules: Ruleset,
+    dialect: Dialect,
+) -> Result<Puzzle, SgtDescError> {
+    // A malformed block structure fails here, before the union-find is allocated. A malformed
+    // clue is left to the parse below, which names its cage.
+    match scan_desc(n, it.clone(), dialect) {
+        Ok(_) | Err(SgtDescError::ClueSyntax { .. }) => {}
+        Err(err) => return Err(err),
     }
 
-    let a = (n as usize) * (n as usize);
-    let mut it = desc.chars().peekable();
+    let w = n as usize;
+    let a = w * w;
     let mut dsf = Dsu::new(a);
 
-    parse_block_structure(&mut it, n, &mut dsf)?;
+    parse_block_structure(&mut it, n, Some(&mut dsf))?;
 
+    let offset = it.offset;
     if it.next() != Some(',') {
-        return Err(SgtDescError::MissingComma);
+        return Err(SgtDescError::MissingComma { offset });
     }
 
     let (min_of, size_of) = dsf.component_mins_and_sizes();
@@ -66,9 +196,21 @@ pub fn parse_keen_desc(n: u8, desc: &str) -> Result<Puzzle, SgtDescError> {
         if cage_size == 0 {
             continue;
         }
-        let (op, target) = parse_clue(&mut it, cage_size)?;
+        let site = ClueSite {
+            index: cages_by_min.len(),
+            anchor: Coord {
+                row: (min / w) as u8,
+                col: (min % w) as u8,
+            },
+            cells: cage_size,
+        };
+        let (op, target) = parse_clue(&mut it, n, site, rules, dialect)?;
         let members = core::mem::take(&mut members_by_min[min]);
-        let cage_op = if members.len() == 1 { Op::Eq } else { op };
+        let cage_op = if dialect == Dialect::Upstream && members.len() == 1 {
+            Op::Eq
+        } else {
+            op
+        };
         cages_by_min.push((
             min,
             Cage {
@@ -80,7 +222,10 @@ pub fn parse_keen_desc(n: u8, desc: &str) -> Result<Puzzle, SgtDescError> {
     }
 
     if it.peek().is_some() {
-        return Err(SgtDescError::CluesTooMany);
+        return Err(SgtDescError::CluesTooMany {
+            offset: it.offset,
+            clues: cages_by_min.len(),
+        });
     }
 
     cages_by_min.sort_by_key(|(min, _)| *min);
@@ -89,15 +234,196 @@ pub fn parse_keen_desc(n: u8, desc: &str) -> Result<Puzzle, SgtDescError> {
         cages: cages_by_min.into_iter().map(|(_, cage)| cage).collect(),
     };
 
-    puzzle.validate(Ruleset::keen_baseline())?;
+    puzzle.validate(rules)?;
     Ok(puzzle)
 }
 
+/// What [`validate_keen_desc_syntax`] reads from a desc without building its cages.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DescSyntaxInfo {
+    /// Clues in the clue stream; a desc that parses has one per cage.
+    pub cages: usize,
+    /// Most internal edges one run of the block structure joins, `z` chains included.
+    pub largest_run: usize,
+}
+
+/// Check a desc's syntax for an `n` x `n` grid in one linear scan, without building a puzzle.
+///
+/// Checks the block structure (run characters, repeat counts, exactly the grid's edge slots),
+/// the `,` after it, and that every clue is an op letter (`a`, `m`, `s`, `d`) followed by an
+/// integer target, and allocates nothing. A malformed clue fails with
+/// [`SgtDescError::ClueSyntax`], as the scan does not know its cage.
+///
+/// Passing does not mean [`parse_keen_desc`] succeeds: what needs the cages is left to it,
+/// such as a clue count that does not match the cages, a Sub/Div clue on other than two cells,
+/// a target out of range, or [`Puzzle::validate`]. `parse_keen_desc` runs this scan first, so
+/// a malformed block structure fails before anything is allocated.
+pub fn validate_keen_desc_syntax(n: u8, desc: &str) -> Result<DescSyntaxInfo, SgtDescError> {
+    scan_desc(n, Cursor::new(desc), Dialect::Upstream)
+}
+
+fn scan_desc(n: u8, mut it: Cursor<'_>, dialect: Dialect) -> Result<DescSyntaxInfo, SgtDescError> {
+    // Same lower bound as `validate_grid_size`; upstream keen caps the format at 16.
+    if !(2..=16).contains(&n) {
+        return Err(CoreError::InvalidGridSize(n).into());
+    }
+
+    let largest_run = parse_block_structure(&mut it, n, None)?;
+
+    let offset = it.offset;
+    if it.next() != Some(',') {
+        return Err(SgtDescError::MissingComma { offset });
+    }
+
+    let mut cages = 0usize;
+    while let Some(ch) = it.peek() {
+        let malformed = |offset| SgtDescError::ClueSyntax {
+            offset,
+            index: cages,
+        };
+        let offset = it.offset;
+        it.next();
+        if clue_op(ch, dialect).is_none() {
+            return Err(malformed(offset));
+        }
+        let target_offset = it.offset;
+        let target = it.read_number(true).map_err(|_| malformed(target_offset))?;
+        if target.parse::<i64>().is_err() {
+            return Err(malformed(target_offset));
+        }
+        cages += 1;
+    }
+
+    Ok(DescSyntaxInfo { cages, largest_run })
+}
+
+/// Grid size a desc's block structure encodes, read from its length alone.
+///
+/// An `n` x `n` grid has `2 * n * (n - 1)` internal edges, and the block structure covers
+/// them plus one closing slot, so the slot count pins `n` down. Repeat counts and `z` runs are
+/// counted without expanding them or building cages, so this is cheap enough to call on every
+/// parse failure to tell a wrong size from a corrupt desc. `None` if the block structure is
+/// malformed, has no trailing `,`, or its length fits no size from 2 to 16.
+pub fn infer_keen_desc_size(desc: &str) -> Option<u8> {
+    const MAX_SLOTS: u64 = 2 * 16 * 15 + 1;
+    let mut chars = desc.chars().peekable();
+    let mut slots = 0u64;
+    loop {
+        let ch = chars.next()?;
+        if ch == ',' {
+            break;
+        }
+        let (c, adv) = block_run(ch)?;
+        let mut count = 0u64;
+        let mut digits = 0usize;
+        while let Some(d) = chars.peek().and_then(|d| d.to_digit(10)) {
+            digits += 1;
+            if digits > MAX_NUMBER_DIGITS {
+                return None;
+            }
+            count = count.saturating_mul(10).saturating_add(u64::from(d));
+            chars.next();
+        }
+        let repeats = if digits == 0 { 1 } else { count.max(1) };
+        slots = slots.saturating_add(repeats.saturating_mul((c + usize::from(adv)) as u64));
+        if slots > MAX_SLOTS {
+            return None;
+        }
+    }
+    (2..=16u8).find(|&n| {
+        let n = u64::from(n);
+        2 * n * (n - 1) + 1 == slots
+    })
+}
+
+/// [`parse_keen_desc`] at the size [`infer_keen_desc_size`] reads from the desc.
+pub fn parse_keen_desc_auto(desc: &str) -> Result<Puzzle, SgtDescError> {
+    let n = infer_keen_desc_size(desc).ok_or(SgtDescError::UnknownGridSize)?;
+    parse_keen_desc(n, desc)
+}
+
+/// Parse a desc written by [`encode_keen_desc_ext`], returning the puzzle and the ruleset from
+/// its header (the baseline when it has none).
+///
+/// Unlike [`parse_keen_desc`], every cage keeps the op its clue letter names, so a 1-cell `a3`
+/// is an addition cage and only `e` makes a given.
+pub fn parse_keen_desc_ext(n: u8, desc: &str) -> Result<(Puzzle, Ruleset), SgtDescError> {
+    let mut it = Cursor::new(desc);
+    let mut rules = Ruleset::keen_baseline();
+    if let Some(header) = desc.strip_prefix('[') {
+        let (descriptor, _) = header
+            .split_once(']')
+            .ok_or(SgtDescError::RulesetHeaderUnterminated)?;
+        rules = Ruleset::from_descriptor(descriptor).map_err(SgtDescError::InvalidRulesetHeader)?;
+        for _ in 0..descriptor.chars().count() + 2 {
+            it.next();
+        }
+    }
+    let puzzle = parse_desc(n, it, rules, Dialect::Ext)?;
+    Ok((puzzle, rules))
+}
+
 /// Encode a `Puzzle` into the upstream sgt-puzzles Keen "desc" format.
 ///
-/// This is intended for corpus tooling and compatibility tests.
+/// This is intended for corpus tooling and compatibility tests. The desc does not record
+/// `rules`; read it back with [`parse_keen_desc_with_rules`] and the same ruleset. Cages the
+/// format cannot write faithfully fail with [`CoreError::NotRepresentableInSgtDesc`] rather
+/// than coming back changed:
+/// - a 1-cell cage with an op other than `Eq` (upstream reads every 1-cell cage as a given);
+/// - a subtraction or division cage on other than two cells (upstream has no such clue);
+/// - a cage that is not orthogonally connected (the block structure cannot draw it).
+///
+/// [`encode_keen_desc_ext`] writes all but the last.
 pub fn encode_keen_desc(puzzle: &Puzzle, rules: Ruleset) -> Result<String, CoreError> {
+    encode_desc(puzzle, rules, Dialect::Upstream)
+}
+
+/// Encode a `Puzzle` losslessly in an extension of the desc format for engine-internal corpora:
+/// the same block structure, but every clue spells out its op (`a`, `m`, `s`, `d`, or `e` for
+/// `Eq`), and a ruleset other than the baseline leads as a `[`[`Ruleset::to_descriptor`]`]`
+/// header, e.g. `[connected,max6]a_3aba_,s1a5a3a5`. Upstream Keen cannot read it; use
+/// [`parse_keen_desc_ext`].
+///
+/// Cages that are not orthogonally connected still fail with
+/// [`CoreError::NotRepresentableInSgtDesc`].
+pub fn encode_keen_desc_ext(puzzle: &Puzzle, rules: Ruleset) -> Result<String, CoreError> {
+    encode_desc(puzzle, rules, Dialect::Ext)
+}
+
+/// Clue letter of `cage` in `dialect`, or why the dialect cannot write it.
+fn clue_letter(cage: &Cage, dialect: Dialect) -> Result<char, &'static str> {
+    // Exhaustive on purpose: a new op has to pick its letter, or be refused, in both dialects.
+    match (dialect, cage.op, cage.cells.len()) {
+        (Dialect::Ext, Op::Eq, _) => Ok('e'),
+        // Upstream leaves givens implicit; `a` is what its own encoder writes for them.
+        (Dialect::Upstream, Op::Eq, _) => Ok('a'),
+        (Dialect::Upstream, _, 1) => {
+            Err("a 1-cell cage reads back as a given, so its op must be Eq")
+        }
+        (Dialect::Upstream, Op::Sub | Op::Div, len) if len != 2 => {
+            Err("upstream Keen has subtraction and division clues on two cells only")
+        }
+        (_, Op::Add, _) => Ok('a'),
+        (_, Op::Mul, _) => Ok('m'),
+        (_, Op::Sub, _) => Ok('s'),
+        (_, Op::Div, _) => Ok('d'),
+    }
+}
+
+fn encode_desc(puzzle: &Puzzle, rules: Ruleset, dialect: Dialect) -> Result<String, CoreError> {
     puzzle.validate(rules)?;
+    let mut letters = Vec::with_capacity(puzzle.cages.len());
+    for (cage_index, cage) in puzzle.cages.iter().enumerate() {
+        let not_representable =
+            |reason| CoreError::NotRepresentableInSgtDesc { cage_index, reason };
+        if !is_orthogonally_connected(puzzle.n, &cage.cells) {
+            return Err(not_representable(
+                "the block structure only draws orthogonally connected cages",
+            ));
+        }
+        letters.push(clue_letter(cage, dialect).map_err(not_representable)?);
+    }
+
     let n = puzzle.n as usize;
     let a = n * n;
 
@@ -150,135 +476,250 @@ pub fn encode_keen_desc(puzzle: &Puzzle, rules: Ruleset) -> Result<String, CoreE
     let block = compress_runs(&raw);
 
     // Clues are ordered by minimal cell id per cage.
-    let mut cages = puzzle.cages.clone();
-    cages.sort_by_key(|c| c.cells.iter().map(|c| c.0).min().unwrap_or(u16::MAX));
+    let mut clues: Vec<(&Cage, char)> = puzzle.cages.iter().zip(letters).collect();
+    clues.sort_by_key(|(c, _)| c.cells.iter().map(|c| c.0).min().unwrap_or(u16::MAX));
 
     let mut out = String::new();
+    if dialect == Dialect::Ext && rules != Ruleset::keen_baseline() {
+        out.push('[');
+        out.push_str(&rules.to_descriptor());
+        out.push(']');
+    }
     out.push_str(&block);
     out.push(',');
-    for cage in cages {
-        let clue_op = match cage.op {
-            Op::Add => 'a',
-            Op::Mul => 'm',
-            Op::Sub => 's',
-            Op::Div => 'd',
-            Op::Eq => 'a', // singleton cages aren't explicit upstream; use addition as a degenerate case
-        };
-        out.push(clue_op);
+    for (cage, letter) in clues {
+        out.push(letter);
         out.push_str(&cage.target.to_string());
     }
 
     Ok(out)
 }
 
-fn parse_block_structure<I: Iterator<Item = char>>(
-    it: &mut core::iter::Peekable<I>,
+/// Character cursor over a desc that tracks the offset of the next character.
+#[derive(Clone)]
+struct Cursor<'a> {
+    desc: &'a str,
+    /// Byte index of the next character in `desc`.
+    byte: usize,
+    offset: usize,
+}
+
+impl<'a> Cursor<'a> {
+    fn new(desc: &'a str) -> Self {
+        Self {
+            desc,
+            byte: 0,
+            offset: 0,
+        }
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.desc[self.byte..].chars().next()
+    }
+
+    fn next(&mut self) -> Option<char> {
+        let ch = self.peek()?;
+        self.byte += ch.len_utf8();
+        self.offset += 1;
+        Some(ch)
+    }
+
+    /// Consume a run of ASCII digits (after an optional leading '-' when `signed`), failing
+    /// once it exceeds [`MAX_NUMBER_DIGITS`] so a long run is not scanned to its end.
+    fn read_number(&mut self, signed: bool) -> Result<&'a str, SgtDescError> {
+        let (offset, start) = (self.offset, self.byte);
+        let mut digits = 0usize;
+        while let Some(d) = self.peek() {
+            if d.is_ascii_digit() {
+                digits += 1;
+                if digits > MAX_NUMBER_DIGITS {
+                    return Err(SgtDescError::NumberTooLong {
+                        offset,
+                        max_digits: MAX_NUMBER_DIGITS,
+                    });
+                }
+            } else if !(signed && self.byte == start && d == '-') {
+                break;
+            }
+            self.next();
+        }
+        Ok(&self.desc[start..self.byte])
+    }
+}
+
+/// Longest digit run accepted in a repetition count or target; `i64::MAX` has 19 digits.
+pub const MAX_NUMBER_DIGITS: usize = 19;
+
+/// Edge slots a block-structure run character covers: the internal edges it joins, and whether
+/// a cage boundary follows them. `z` joins 25 edges with no boundary, so longer runs chain.
+fn block_run(ch: char) -> Option<(usize, bool)> {
+    match ch {
+        '_' => Some((0, true)),
+        'a'..='y' => Some(((ch as u8 - b'a' + 1) as usize, true)),
+        'z' => Some((25, false)),
+        _ => None,
+    }
+}
+
+/// Read the block structure, joining the cells of each internal edge it crosses in `dsf` when
+/// given. Returns the most internal edges one run joins.
+fn parse_block_structure(
+    it: &mut Cursor<'_>,
     n: u8,
-    dsf: &mut Dsu,
-) -> Result<(), SgtDescError> {
+    mut dsf: Option<&mut Dsu>,
+) -> Result<usize, SgtDescError> {
     let w = n as usize;
+    let edges = 2 * w * (w - 1);
     let mut pos = 0usize;
-    let mut repc = 0usize;
+    let mut repc = (0usize, true);
     let mut repn = 0usize;
+    // Offset of the run character being expanded, for errors raised by its repeats.
+    let mut run_offset = 0usize;
+    let (mut run, mut largest_run) = (0usize, 0usize);
 
-    while let Some(&ch) = it.peek() {
+    while let Some(ch) = it.peek() {
         if repn == 0 && ch == ',' {
             break;
         }
 
-        let c = if repn > 0 {
+        let (c, adv) = if repn > 0 {
             repn -= 1;
             repc
         } else {
-            let ch = it.next().ok_or(SgtDescError::InvalidBlockChar)?;
-            if ch == '_' {
-                0
-            } else if ch.is_ascii_lowercase() {
-                (ch as u8 - b'a' + 1) as usize
-            } else {
-                return Err(SgtDescError::InvalidBlockChar);
-            }
+            run_offset = it.offset;
+            it.next();
+            block_run(ch).ok_or(SgtDescError::InvalidBlockChar {
+                offset: run_offset,
+                ch,
+            })?
         };
 
         // Optional run repetition count (e.g., "_12").
         if repn == 0 {
-            let mut digits = String::new();
-            while let Some(&d) = it.peek() {
-                if d.is_ascii_digit() {
-                    digits.push(d);
-                    it.next();
-                } else {
-                    break;
-                }
-            }
+            let count_offset = it.offset;
+            let digits = it.read_number(false)?;
             if !digits.is_empty() {
-                repc = c;
-                repn = digits
-                    .parse::<usize>()
-                    .map_err(|_| SgtDescError::InvalidBlockChar)?;
-                repn = repn.saturating_sub(1);
+                // At most 19 digits, so this fits; every repeat fills at least one edge slot
+                // (25 for 'z'), so a count is checked against the slots left before any of it
+                // is expanded.
+                let count = digits.parse::<u64>().unwrap_or(u64::MAX).max(1);
+                let slots_each = (c + usize::from(adv)) as u64;
+                let remaining = edges + 1 - pos;
+                if count.saturating_mul(slots_each) > remaining as u64 {
+                    return Err(SgtDescError::RepeatCountTooLarge {
+                        offset: count_offset,
+                        count,
+                        remaining,
+                    });
+                }
+                repc = (c, adv);
+                repn = count as usize - 1;
             }
         }
 
-        let adv = c != 25;
+        let too_much = SgtDescError::BlockTooMuchData {
+            offset: run_offset,
+            edges,
+        };
         let mut remaining = c;
         while remaining > 0 {
-            if pos >= 2 * w * (w - 1) {
-                return Err(SgtDescError::BlockTooMuchData);
+            if pos >= edges {
+                return Err(too_much);
+            }
+            if let Some(dsf) = dsf.as_deref_mut() {
+                let (p0, p1) = edge_cells(w, pos);
+                dsf.union(p0, p1);
             }
-            let (p0, p1) = edge_cells(w, pos);
-            dsf.union(p0, p1);
             pos += 1;
             remaining -= 1;
         }
+        run += c;
+        largest_run = largest_run.max(run);
 
         if adv {
+            run = 0;
             pos += 1;
-            if pos > 2 * w * (w - 1) + 1 {
-                return Err(SgtDescError::BlockTooMuchData);
+            if pos > edges + 1 {
+                return Err(too_much);
             }
         }
     }
 
-    if pos != 2 * w * (w - 1) + 1 {
-        return Err(SgtDescError::BlockNotEnoughData);
+    if pos != edges + 1 {
+        return Err(SgtDescError::BlockNotEnoughData {
+            offset: it.offset,
+            read: pos,
+            expected: edges + 1,
+        });
     }
 
-    Ok(())
+    Ok(largest_run)
 }
 
-fn parse_clue<I: Iterator<Item = char>>(
-    it: &mut core::iter::Peekable<I>,
-    cage_size: usize,
-) -> Result<(Op, i32), SgtDescError> {
-    let opch = it.next().ok_or(SgtDescError::CluesTooFew)?;
-    let op = match opch {
-        'a' => Op::Add,
-        'm' => Op::Mul,
-        's' => Op::Sub,
-        'd' => Op::Div,
-        _ => return Err(SgtDescError::ClueTypeUnknown),
+/// Op a clue letter names in `dialect`.
+fn clue_op(ch: char, dialect: Dialect) -> Option<Op> {
+    match ch {
+        'a' => Some(Op::Add),
+        'm' => Some(Op::Mul),
+        's' => Some(Op::Sub),
+        'd' => Some(Op::Div),
+        'e' if dialect == Dialect::Ext => Some(Op::Eq),
+        _ => None,
+    }
+}
+
+fn parse_clue(
+    it: &mut Cursor<'_>,
+    n: u8,
+    site: ClueSite,
+    rules: Ruleset,
+    dialect: Dialect,
+) -> Result<(Op, i64), SgtDescError> {
+    let offset = it.offset;
+    let opch = it
+        .next()
+        .ok_or(SgtDescError::CluesTooFew { offset, site })?;
+    let op = clue_op(opch, dialect).ok_or(SgtDescError::ClueTypeUnknown {
+        offset,
+        site,
+        ch: opch,
+    })?;
+    // Upstream, a given becomes an `Eq` cage whatever its clue type, so only real cages are
+    // held to the two-cell rule; the extended dialect keeps every op as written.
+    let given = match dialect {
+        Dialect::Upstream => site.cells == 1,
+        Dialect::Ext => op == Op::Eq,
     };
 
-    if matches!(op, Op::Sub | Op::Div) && cage_size != 2 {
-        return Err(SgtDescError::SubDivMustBeTwoCell);
+    if rules.sub_div_two_cell_only && matches!(op, Op::Sub | Op::Div) && !given && site.cells != 2 {
+        return Err(SgtDescError::SubDivMustBeTwoCell { offset, site, op });
     }
 
-    let mut digits = String::new();
-    while let Some(&d) = it.peek() {
-        if d.is_ascii_digit() || (digits.is_empty() && d == '-') {
-            digits.push(d);
-            it.next();
-        } else {
-            break;
-        }
-    }
+    let target_offset = it.offset;
+    let invalid_target = SgtDescError::InvalidTarget {
+        offset: target_offset,
+        site,
+    };
+    let digits = it.read_number(true)?;
     if digits.is_empty() || digits == "-" {
-        return Err(SgtDescError::InvalidTarget);
+        return Err(invalid_target);
+    }
+    let target = digits.parse::<i64>().map_err(|_| invalid_target)?;
+    if given && !(1..=n as i64).contains(&target) {
+        return Err(SgtDescError::GivenOutOfRange {
+            offset: target_offset,
+            site,
+            target,
+            n,
+        });
+    }
+    if target <= 0 {
+        return Err(SgtDescError::TargetNotPositive {
+            offset: target_offset,
+            site,
+            target,
+        });
     }
-    let target = digits
-        .parse::<i32>()
-        .map_err(|_| SgtDescError::InvalidTarget)?;
     Ok((op, target))
 }
 
@@ -396,4 +837,564 @@ mod tests {
         let enc = encode_keen_desc(&p, Ruleset::keen_baseline()).unwrap();
         assert_eq!(enc, desc);
     }
+
+    #[test]
+    fn n_ary_sub_needs_permissive_rules() {
+        // 3x3 with an L-shaped 3-cell `1-` cage. Parsing only checks structure and rules, so
+        // the clues need not be satisfiable.
+        let permissive = Ruleset {
+            sub_div_two_cell_only: false,
+            ..Ruleset::keen_baseline()
+        };
+        let puzzle = Puzzle {
+            n: 3,
+            cages: vec![
+                Cage {
+                    cells: [CellId(0), CellId(1), CellId(4)].into_iter().collect(),
+                    op: Op::Sub,
+                    target: 1,
+                },
+                Cage {
+                    cells: [CellId(2), CellId(5)].into_iter().collect(),
+                    op: Op::Add,
+                    target: 5,
+                },
+                Cage {
+                    cells: [CellId(3), CellId(6)].into_iter().collect(),
+                    op: Op::Add,
+                    target: 3,
+                },
+                Cage {
+                    cells: [CellId(7), CellId(8)].into_iter().collect(),
+                    op: Op::Add,
+                    target: 5,
+                },
+            ],
+        };
+        // Upstream has no n-ary subtraction, so the strict encoder refuses the cage.
+        assert!(matches!(
+            encode_keen_desc(&puzzle, permissive),
+            Err(CoreError::NotRepresentableInSgtDesc { cage_index: 0, .. })
+        ));
+        let desc = "a_3aba_,s1a5a3a5";
+
+        assert!(matches!(
+            parse_keen_desc(3, desc),
+            Err(SgtDescError::SubDivMustBeTwoCell { .. })
+        ));
+        let parsed = parse_keen_desc_with_rules(3, desc, permissive).unwrap();
+        assert_eq!(parsed, puzzle);
+
+        let ext = encode_keen_desc_ext(&puzzle, permissive).unwrap();
+        assert_eq!(ext, format!("[connected,max6]{desc}"));
+        assert_eq!(parse_keen_desc_ext(3, &ext).unwrap(), (puzzle, permissive));
+    }
+
+    #[test]
+    fn strict_encoder_refuses_cages_the_format_would_change() {
+        let rules = Ruleset::keen_baseline();
+        let mut puzzle = parse_keen_desc(2, "_5,a1a2a2a1").unwrap();
+        let refused = |puzzle: &Puzzle, rules| match encode_keen_desc(puzzle, rules) {
+            Err(CoreError::NotRepresentableInSgtDesc { cage_index, reason }) => {
+                (cage_index, reason)
+            }
+            other => panic!("expected NotRepresentableInSgtDesc, got {other:?}"),
+        };
+
+        // A 1-cell addition cage is valid, but upstream would read it back as a given.
+        puzzle.cages[2].op = Op::Add;
+        puzzle.validate(rules).unwrap();
+        let (cage_index, reason) = refused(&puzzle, rules);
+        assert_eq!(cage_index, 2);
+        assert!(reason.contains("given"), "{reason}");
+        let ext = encode_keen_desc_ext(&puzzle, rules).unwrap();
+        assert_eq!(ext, "_5,e1e2a2e1");
+        assert_eq!(parse_keen_desc_ext(2, &ext).unwrap(), (puzzle, rules));
+
+        // The block structure merges touching cells, so a split cage cannot be drawn at all.
+        let loose = Ruleset {
+            require_orthogonal_cage_connectivity: false,
+            ..rules
+        };
+        let diagonals = Puzzle {
+            n: 2,
+            cages: vec![
+                Cage {
+                    cells: [CellId(0), CellId(3)].into_iter().collect(),
+                    op: Op::Add,
+                    target: 2,
+                },
+                Cage {
+                    cells: [CellId(1), CellId(2)].into_iter().collect(),
+                    op: Op::Add,
+                    target: 4,
+                },
+            ],
+        };
+        let (cage_index, reason) = refused(&diagonals, loose);
+        assert_eq!(cage_index, 0);
+        assert!(reason.contains("connected"), "{reason}");
+        assert!(matches!(
+            encode_keen_desc_ext(&diagonals, loose),
+            Err(CoreError::NotRepresentableInSgtDesc { cage_index: 0, .. })
+        ));
+
+        // Invalid puzzles still fail validation first.
+        let mut eq_pair = parse_keen_desc(2, "b__,a3a3").unwrap();
+        eq_pair.cages[0].op = Op::Eq;
+        assert!(matches!(
+            encode_keen_desc(&eq_pair, rules),
+            Err(CoreError::InvalidOpForCageSize { op: Op::Eq, len: 2 })
+        ));
+    }
+
+    #[test]
+    fn ext_descs_keep_ops_and_rulesets() {
+        let desc = "_5,a1a2a2a1";
+        let puzzle = parse_keen_desc(2, desc).unwrap();
+        let rules = Ruleset::keen_baseline();
+        assert_eq!(encode_keen_desc(&puzzle, rules).unwrap(), desc);
+        // The baseline needs no header.
+        let ext = encode_keen_desc_ext(&puzzle, rules).unwrap();
+        assert_eq!(ext, "_5,e1e2e2e1");
+        assert_eq!(
+            parse_keen_desc_ext(2, &ext).unwrap(),
+            (puzzle.clone(), rules)
+        );
+
+        let wide = Ruleset {
+            max_cage_size: 9,
+            ..rules
+        };
+        let rows = row_cages(9);
+        let ext = encode_keen_desc_ext(&rows, wide).unwrap();
+        assert!(ext.starts_with("[subdiv2,connected,max9]"), "{ext}");
+        assert_eq!(parse_keen_desc_ext(9, &ext).unwrap(), (rows, wide));
+        // Headerless descs read under the baseline, which caps cages at 6 cells.
+        let bare = ext.split_once(']').unwrap().1;
+        assert!(matches!(
+            parse_keen_desc_ext(9, bare),
+            Err(SgtDescError::Core(CoreError::CageTooLarge {
+                len: 9,
+                max: 6
+            }))
+        ));
+
+        // `e` is only a clue letter in the extended dialect, and only for givens.
+        assert!(matches!(
+            parse_keen_desc(2, "_5,e1e2e2e1"),
+            Err(SgtDescError::ClueTypeUnknown {
+                offset: 3,
+                ch: 'e',
+                ..
+            })
+        ));
+        assert!(matches!(
+            parse_keen_desc_ext(2, "b__,e2a3"),
+            Err(SgtDescError::Core(CoreError::InvalidOpForCageSize {
+                op: Op::Eq,
+                len: 2
+            }))
+        ));
+        assert!(matches!(
+            parse_keen_desc_ext(2, "_5,e1e3e2e1"),
+            Err(SgtDescError::GivenOutOfRange {
+                offset: 6,
+                target: 3,
+                ..
+            })
+        ));
+        // Offsets count from the start of the header.
+        assert!(matches!(
+            parse_keen_desc_ext(2, "[connected,max6]_5,e1e2e2x1"),
+            Err(SgtDescError::ClueTypeUnknown {
+                offset: 25,
+                ch: 'x',
+                ..
+            })
+        ));
+        assert!(matches!(
+            parse_keen_desc_ext(2, "[connected,max6_5,e1e2e2e1"),
+            Err(SgtDescError::RulesetHeaderUnterminated)
+        ));
+        assert!(matches!(
+            parse_keen_desc_ext(2, "[diagonal,max6]_5,e1e2e2e1"),
+            Err(SgtDescError::InvalidRulesetHeader(
+                RulesetDescriptorError::UnknownFlag(_)
+            ))
+        ));
+    }
+
+    #[test]
+    fn one_by_one_grids_are_rejected() {
+        // The 1x1 block structure has no internal edges, so "_1,a1" would otherwise parse.
+        assert!(matches!(
+            parse_keen_desc(1, "_1,a1"),
+            Err(SgtDescError::Core(CoreError::InvalidGridSize(1)))
+        ));
+        assert!(matches!(
+            parse_keen_desc(0, ",a1"),
+            Err(SgtDescError::Core(CoreError::InvalidGridSize(0)))
+        ));
+
+        let puzzle = Puzzle {
+            n: 1,
+            cages: vec![Cage {
+                cells: [CellId(0)].into_iter().collect(),
+                op: Op::Eq,
+                target: 1,
+            }],
+        };
+        assert!(matches!(
+            encode_keen_desc(&puzzle, Ruleset::keen_baseline()),
+            Err(CoreError::InvalidGridSize(1))
+        ));
+    }
+
+    #[test]
+    fn errors_report_positions() {
+        let err = |n, desc| parse_keen_desc(n, desc).unwrap_err();
+        let site = |index, row, col, cells| ClueSite {
+            index,
+            anchor: Coord { row, col },
+            cells,
+        };
+
+        // "b__" is the whole 2x2 block structure.
+        assert!(matches!(
+            err(2, "b_!_,a3a3"),
+            SgtDescError::InvalidBlockChar { offset: 2, ch: '!' }
+        ));
+        assert!(matches!(
+            err(2, "b___,a3a3"),
+            SgtDescError::BlockTooMuchData {
+                offset: 3,
+                edges: 4
+            }
+        ));
+        assert!(matches!(
+            err(2, "b_,a3a3"),
+            SgtDescError::BlockNotEnoughData {
+                offset: 2,
+                read: 4,
+                expected: 5
+            }
+        ));
+        assert!(matches!(
+            err(2, "b__"),
+            SgtDescError::MissingComma { offset: 3 }
+        ));
+
+        match err(2, "b__,a3") {
+            SgtDescError::CluesTooFew { offset: 6, site: s } => assert_eq!(s, site(1, 1, 0, 2)),
+            other => panic!("{other:?}"),
+        }
+        assert!(matches!(
+            err(2, "b__,a3a3a3"),
+            SgtDescError::CluesTooMany {
+                offset: 8,
+                clues: 2
+            }
+        ));
+        match err(2, "b__,a3x3") {
+            SgtDescError::ClueTypeUnknown {
+                offset: 6,
+                site: s,
+                ch: 'x',
+            } => assert_eq!(s, site(1, 1, 0, 2)),
+            other => panic!("{other:?}"),
+        }
+        match err(2, "b__,a3a") {
+            SgtDescError::InvalidTarget { offset: 7, site: s } => {
+                assert_eq!(s, site(1, 1, 0, 2))
+            }
+            other => panic!("{other:?}"),
+        }
+    }
+
+    #[test]
+    fn clue_errors_name_the_cage() {
+        // The L-shaped `1-` cage from `n_ary_sub_needs_permissive_rules`.
+        let l_shape = "a_3aba_,s1a5a3a5";
+        let err = parse_keen_desc(3, l_shape).unwrap_err();
+        assert!(matches!(
+            err,
+            SgtDescError::SubDivMustBeTwoCell {
+                offset: 8,
+                op: Op::Sub,
+                ..
+            }
+        ));
+        assert_eq!(
+            err.to_string(),
+            "clue 0 (cage anchored at r0c0): subtraction cage has 3 cells"
+        );
+
+        // Givens become `Eq` cages: any clue type is fine, but the target must be a digit.
+        let singletons = "_25,a1a2a3a4a2a1a4a3a3a4a1a2a4a3a2a1";
+        let sub_given = singletons.replacen("a4", "s4", 1);
+        assert!(parse_keen_desc(4, &sub_given).is_ok());
+        let big_given = singletons.replacen("a3a4", "a3a5", 1);
+        let err = parse_keen_desc(4, &big_given).unwrap_err();
+        assert!(matches!(
+            err,
+            SgtDescError::GivenOutOfRange {
+                offset: 11,
+                target: 5,
+                n: 4,
+                ..
+            }
+        ));
+        assert_eq!(
+            err.to_string(),
+            "clue 3 (given at r0c3): given 5 is outside 1..=4"
+        );
+    }
+
+    #[test]
+    fn non_positive_targets_fail_at_parse_time() {
+        for (desc, offset, target) in [
+            ("b__,a-3a3", 5, -3),
+            ("b__,s0a3", 5, 0),
+            ("b__,a3m-6", 7, -6),
+        ] {
+            let err = parse_keen_desc(2, desc).unwrap_err();
+            match err {
+                SgtDescError::TargetNotPositive {
+                    offset: o,
+                    target: t,
+                    ..
+                } => assert_eq!((o, t), (offset, target), "{desc}"),
+                ref other => panic!("{desc}: {other:?}"),
+            }
+        }
+        assert_eq!(
+            parse_keen_desc(2, "b__,a3m-6").unwrap_err().to_string(),
+            "clue 1 (cage anchored at r1c0): target -6 at offset 7 is not positive"
+        );
+        // Givens keep their own range error.
+        assert!(matches!(
+            parse_keen_desc(2, "_5,a-1a2a2a1").unwrap_err(),
+            SgtDescError::GivenOutOfRange { target: -1, .. }
+        ));
+    }
+
+    #[test]
+    fn adversarial_block_structures_fail_fast() {
+        // Each input is rejected after a bounded amount of work however long it is; the time
+        // limit is loose enough for unoptimized CI builds, far below what expanding the
+        // counts or scanning the digit runs would take.
+        let huge_digits = format!("_{},a1", "9".repeat(1 << 20));
+        let long_target = format!("b__,a{}", "1".repeat(1 << 20));
+        let cases: [(&str, u8, &str); 5] = [
+            ("huge repeat count", 16, "_999999999999,a1"),
+            ("'z' repeat", 16, "z999999999,a1"),
+            ("overflowing count", 16, "a99999999999999999999,a1"),
+            ("long digit run", 16, &huge_digits),
+            ("long target", 2, &long_target),
+        ];
+        for (label, n, desc) in cases {
+            let start = std::time::Instant::now();
+            let err = parse_keen_desc(n, desc).unwrap_err();
+            assert!(
+                start.elapsed() < std::time::Duration::from_millis(50),
+                "{label}: {:?}",
+                start.elapsed()
+            );
+            assert!(
+                matches!(
+                    err,
+                    SgtDescError::RepeatCountTooLarge { .. } | SgtDescError::NumberTooLong { .. }
+                ),
+                "{label}: {err:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn repeat_counts_are_checked_against_the_edge_budget() {
+        // 2x2: four internal edges plus the closing slot.
+        assert!(matches!(
+            parse_keen_desc(2, "_6,a1a2a2a1"),
+            Err(SgtDescError::RepeatCountTooLarge {
+                offset: 1,
+                count: 6,
+                remaining: 5
+            })
+        ));
+        assert!(matches!(
+            parse_keen_desc(2, "b_3,a3a3"),
+            Err(SgtDescError::RepeatCountTooLarge {
+                offset: 2,
+                count: 3,
+                remaining: 2
+            })
+        ));
+        // Exactly filling the budget still parses.
+        assert!(parse_keen_desc(2, "_5,a1a2a2a1").is_ok());
+        assert!(parse_keen_desc(2, "b_2,a3a3").is_ok());
+        assert!(matches!(
+            parse_keen_desc(2, "b__,a3a12345678901234567890"),
+            Err(SgtDescError::NumberTooLong {
+                offset: 7,
+                max_digits: MAX_NUMBER_DIGITS
+            })
+        ));
+    }
+
+    /// `n` x `n` grid cut into its rows, one addition cage each.
+    fn row_cages(n: u8) -> Puzzle {
+        let w = u16::from(n);
+        Puzzle {
+            n,
+            cages: (0..w)
+                .map(|r| Cage {
+                    cells: (0..w).map(|c| CellId(r * w + c)).collect(),
+                    op: Op::Add,
+                    target: i64::from(w * (w + 1) / 2),
+                })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn long_joined_runs_round_trip_through_z() {
+        // Row cages join every vertical edge: 30 in a row at 6x6, written "ze".
+        let puzzle = row_cages(6);
+        let desc = encode_keen_desc(&puzzle, Ruleset::keen_baseline()).unwrap();
+        assert!(desc.starts_with("ze_30,"), "{desc}");
+        assert_eq!(parse_keen_desc(6, &desc).unwrap(), puzzle);
+        // 'y' joins 25 edges and then closes the run, unlike 'z'.
+        assert!(matches!(
+            parse_keen_desc(6, "ye_30,a21a21a21a21a21a21"),
+            Err(SgtDescError::RepeatCountTooLarge { .. })
+        ));
+    }
+
+    #[test]
+    fn syntax_check_needs_no_cages() {
+        let info = |n, desc| validate_keen_desc_syntax(n, desc).unwrap();
+        assert_eq!(
+            info(2, "b__,a3a3"),
+            DescSyntaxInfo {
+                cages: 2,
+                largest_run: 2
+            }
+        );
+        let rows = encode_keen_desc(&row_cages(6), Ruleset::keen_baseline()).unwrap();
+        assert_eq!(
+            info(6, &rows),
+            DescSyntaxInfo {
+                cages: 6,
+                largest_run: 30
+            }
+        );
+
+        // What takes the cages to see passes: a missing clue, a three-cell `1-`, a big given.
+        assert_eq!(info(2, "b__,a3").cages, 1);
+        info(3, "a_3aba_,s1a5a3a5");
+        info(2, "_5,a1a2a2a9");
+
+        // The full parse reports these with the clue's cage; the scan only knows its index.
+        let err = |n, desc| validate_keen_desc_syntax(n, desc).unwrap_err();
+        assert!(matches!(
+            err(2, "b__,a3x3"),
+            SgtDescError::ClueSyntax {
+                offset: 6,
+                index: 1
+            }
+        ));
+        assert!(matches!(
+            err(2, "b__,a3a"),
+            SgtDescError::ClueSyntax {
+                offset: 7,
+                index: 1
+            }
+        ));
+        assert!(matches!(
+            err(2, "b__,a9223372036854775808"),
+            SgtDescError::ClueSyntax {
+                offset: 5,
+                index: 0
+            }
+        ));
+        assert!(matches!(
+            parse_keen_desc(2, "b__,a9223372036854775808"),
+            Err(SgtDescError::InvalidTarget { offset: 5, .. })
+        ));
+        assert!(matches!(
+            err(2, "b_!_,a3a3"),
+            SgtDescError::InvalidBlockChar { offset: 2, ch: '!' }
+        ));
+        assert!(matches!(
+            err(2, "b__"),
+            SgtDescError::MissingComma { offset: 3 }
+        ));
+        assert!(matches!(
+            err(1, "_1,a1"),
+            SgtDescError::Core(CoreError::InvalidGridSize(1))
+        ));
+    }
+
+    #[test]
+    fn grid_size_is_inferred_from_the_block_structure() {
+        let permissive = Ruleset {
+            max_cage_size: 9,
+            ..Ruleset::keen_baseline()
+        };
+        for n in 2..=9u8 {
+            let w = u16::from(n);
+            let singletons = Puzzle {
+                n,
+                cages: (0..w * w)
+                    .map(|c| Cage {
+                        cells: [CellId(c)].into_iter().collect(),
+                        op: Op::Eq,
+                        target: 1,
+                    })
+                    .collect(),
+            };
+            for puzzle in [singletons, row_cages(n)] {
+                let desc = encode_keen_desc(&puzzle, permissive).unwrap();
+                assert_eq!(infer_keen_desc_size(&desc), Some(n), "{desc}");
+                assert_eq!(
+                    parse_keen_desc_with_rules(n, &desc, permissive).unwrap(),
+                    puzzle
+                );
+            }
+        }
+        assert_eq!(
+            parse_keen_desc_auto("b__,a3a3").unwrap(),
+            parse_keen_desc(2, "b__,a3a3").unwrap()
+        );
+    }
+
+    #[test]
+    fn size_inference_rejects_garbage() {
+        let huge_count = format!("_{},a1", "9".repeat(1 << 20));
+        for desc in [
+            "",
+            ",",
+            ",a1",
+            "b__",
+            "_6,a1",
+            "5_,a1",
+            "_A,a1",
+            "?,a1",
+            "z99999999999999999999,a1",
+            &huge_count,
+            "é,a1",
+        ] {
+            assert_eq!(infer_keen_desc_size(desc), None, "{desc:?}");
+        }
+        assert!(matches!(
+            parse_keen_desc_auto("_6,a1"),
+            Err(SgtDescError::UnknownGridSize)
+        ));
+        // The size is right but the clues are not; the parse error comes through.
+        assert!(matches!(
+            parse_keen_desc_auto("_5,a1"),
+            Err(SgtDescError::CluesTooFew { .. })
+        ));
+    }
 }
@@ -1,15 +1,27 @@
 #![forbid(unsafe_code)]
 #![doc = include_str!("../README.md")]
 
+pub mod arith;
+pub mod builder;
+pub mod cage_graph;
+pub mod clue;
+pub mod content_hash;
 #[cfg(feature = "core-bitvec")]
 pub mod domain;
+pub mod editor;
 pub mod error;
 #[cfg(feature = "format-sgt-desc")]
 pub mod format;
+pub mod limits;
 pub mod puzzle;
+pub mod render;
 pub mod rules;
 
+pub use crate::builder::PuzzleBuilder;
+pub use crate::cage_graph::CageGraph;
+pub use crate::clue::{CluePlacement, ClueStyle, clue_options};
 #[cfg(feature = "core-bitvec")]
 pub use crate::domain::BitDomain;
+pub use crate::editor::PuzzleEditor;
 pub use crate::error::CoreError;
 pub use crate::puzzle::{Cage, CellId, Coord, Puzzle};
@@ -0,0 +1,39 @@
+//! Size limits shared by the engine's crates, so cage storage, clue checks and the solvers'
+//! tuple enumeration agree on what a "large" cage is.
+//!
+//! Use [`Cage::tuple_count_upper_bound`](crate::Cage::tuple_count_upper_bound) and
+//! [`Cage::is_tractable`](crate::Cage::is_tractable) to test a cage against these budgets
+//! before enumerating it.
+
+/// Cells a cage (and each of its digit tuples) holds inline before spilling to the heap.
+///
+/// [`Ruleset::keen_baseline`](crate::rules::Ruleset::keen_baseline) caps cages at 6 cells;
+/// 8 leaves room for the common permissive rulesets at no cost for cells
+/// ([`CellId`](crate::CellId) is two bytes, so 8 of them fit in the space the heap pointer and
+/// length take anyway). Larger cages still work, but each one allocates.
+pub const INLINE_CAGE_CELLS: usize = 8;
+
+/// Largest cage [`Puzzle::validate_clues`](crate::Puzzle::validate_clues) searches for a
+/// satisfying tuple; at most `n^4` tuples, and usually far fewer after pruning.
+pub const CLUE_TUPLE_CHECK_MAX_CELLS: usize = 4;
+
+/// Satisfying tuples per cage the SAT encoder lists as an allowlist before falling back to
+/// search.
+///
+/// For T tuples in a k-cell cage the allowlist costs T selector variables, `2 * k * T`
+/// implication clauses and `T * (T - 1) / 2` at-most-one clauses: about 135k clauses at
+/// T = 512 with k = 4. For n <= 9 and the baseline's 6-cell cages, 2- and 3-cell `Add` cages
+/// stay far below this (at most 8 and about 80 tuples), 4-cell cages approach it, and only
+/// 5- and 6-cell `Mul` cages exceed it. The fallback, a bounded solution count, is cheap on
+/// exactly the small grids where tuple explosion is rare. See `docs/sat_cage_encoding.md`
+/// section 3.4.
+pub const SAT_TUPLE_THRESHOLD: usize = 512;
+
+/// Enumeration nodes the deducing search spends on one cage deduction before degrading that
+/// cage to per-candidate min/max bounds.
+///
+/// Search re-enumerates cages at every propagation round, so its budget is per round rather
+/// than per puzzle; 128 SAT allowlists' worth keeps every cage of the golden corpus exact
+/// while capping a single round on a pathological cage (a 10-cell `Add` with six candidates
+/// per cell is `6^10`, about 60 million nodes) at a few milliseconds.
+pub const SEARCH_TUPLE_BUDGET: usize = SAT_TUPLE_THRESHOLD * 128;
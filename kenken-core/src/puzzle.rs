@@ -1,6 +1,9 @@
 use smallvec::SmallVec;
 
+use crate::arith;
 use crate::error::CoreError;
+pub use crate::limits::CLUE_TUPLE_CHECK_MAX_CELLS;
+use crate::limits::INLINE_CAGE_CELLS;
 use crate::rules::{Op, Ruleset};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
@@ -17,11 +20,26 @@ pub struct Coord {
     pub row: u8,
     pub col: u8,
 }
+
+/// `r<row>c<col>`, counting from 1 as players do: `r1c1` is the top-left cell.
+impl core::fmt::Display for Coord {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(
+            f,
+            "r{}c{}",
+            u16::from(self.row) + 1,
+            u16::from(self.col) + 1
+        )
+    }
+}
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Cage {
-    pub cells: SmallVec<[CellId; 6]>,
+    /// The cage's cells, sorted ascending by [`CellId`] (so row-major, and `cells[0]` is the
+    /// top-left-most); [`Puzzle::validate`] rejects any other order. Per-cell data such as
+    /// [`valid_permutations`](Self::valid_permutations) tuples is positional in this order.
+    pub cells: SmallVec<[CellId; INLINE_CAGE_CELLS]>,
     pub op: Op,
-    pub target: i32,
+    pub target: i64,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -44,31 +62,24 @@ mod _layout_assertions {
 impl Puzzle {
     pub fn validate(&self, rules: Ruleset) -> Result<(), CoreError> {
         let n = self.n;
-
-        // Feature-gated grid size validation
-        #[cfg(not(any(feature = "core-u64", feature = "core-bitvec")))]
-        if !(1..=31).contains(&n) {
-            return Err(CoreError::InvalidGridSize(n));
-        }
-
-        #[cfg(all(feature = "core-u64", not(feature = "core-bitvec")))]
-        if !(1..=63).contains(&n) {
-            return Err(CoreError::InvalidGridSize(n));
-        }
-
-        #[cfg(feature = "core-bitvec")]
-        if !(1..=255).contains(&n) {
-            return Err(CoreError::InvalidGridSize(n));
-        }
+        validate_grid_size(n)?;
         let a = (n as usize) * (n as usize);
 
         let mut seen = vec![false; a];
-        for cage in &self.cages {
-            cage.validate_shape(n, rules)?;
+        for (cage_index, cage) in self.cages.iter().enumerate() {
+            cage.validate_shape(n, rules)
+                .map_err(|e| e.in_cage(cage_index))?;
+            // Non-strict, so a cell repeated within a cage is reported as a duplicate below.
+            if !cage.cells.is_sorted() {
+                return Err(CoreError::CageCellsUnsorted { cage: cage_index });
+            }
             for &cell in &cage.cells {
                 let idx = cell_index(n, cell)?;
                 if seen[idx] {
-                    return Err(CoreError::CellDuplicated(cell));
+                    return Err(CoreError::CellDuplicated {
+                        cell,
+                        coord: coord_unchecked(n, cell),
+                    });
                 }
                 seen[idx] = true;
             }
@@ -76,15 +87,238 @@ impl Puzzle {
 
         for (idx, covered) in seen.into_iter().enumerate() {
             if !covered {
-                return Err(CoreError::CellUncovered(CellId(idx as u16)));
+                let cell = CellId(idx as u16);
+                return Err(CoreError::CellUncovered {
+                    cell,
+                    coord: coord_unchecked(n, cell),
+                });
             }
         }
 
         Ok(())
     }
+
+    /// Arithmetic plausibility of every clue, beyond the structure [`validate`](Self::validate)
+    /// checks: each target must lie within [`Cage::target_bounds_with_rules`], and cages of up to
+    /// [`CLUE_TUPLE_CHECK_MAX_CELLS`] cells must have at least one digit tuple meeting their
+    /// clue ([`Cage::valid_permutations`], Latin constraints aside). Errors name the first
+    /// offending cage by its index. Not run by the solver, which finds such puzzles
+    /// unsolvable anyway; use [`validate_full`](Self::validate_full) for both checks.
+    pub fn validate_clues(&self, rules: Ruleset) -> Result<(), CoreError> {
+        let n = self.n;
+        for (idx, cage) in self.cages.iter().enumerate() {
+            let len = cage.cells.len();
+            if cage.op == Op::Eq && len != 1 {
+                return Err(CoreError::EqCageNotSingleton { cage: idx, len });
+            }
+            let (min, max) = cage.target_bounds_with_rules(n, rules)?;
+            if !(min..=max).contains(&cage.target) {
+                return Err(CoreError::ClueTargetOutOfRange {
+                    cage: idx,
+                    op: cage.op,
+                    target: cage.target,
+                    min,
+                    max,
+                });
+            }
+            if len <= CLUE_TUPLE_CHECK_MAX_CELLS
+                && cage
+                    .valid_permutations(n, rules, 1)?
+                    .is_some_and(|tuples| tuples.is_empty())
+            {
+                return Err(CoreError::ClueUnsatisfiable {
+                    cage: idx,
+                    op: cage.op,
+                    target: cage.target,
+                });
+            }
+        }
+        Ok(())
+    }
+
+    /// [`validate`](Self::validate), then [`validate_clues`](Self::validate_clues).
+    pub fn validate_full(&self, rules: Ruleset) -> Result<(), CoreError> {
+        self.validate(rules)?;
+        self.validate_clues(rules)
+    }
+
+    /// Check a filled row-major `grid` against this puzzle.
+    ///
+    /// Reports the first violation found, checking in a fixed order: grid length, digit
+    /// range, rows, columns, then cages in list order. Coordinates in the errors are 0-based.
+    /// The puzzle itself is assumed to have passed [`validate`](Self::validate).
+    pub fn check_solution(&self, grid: &[u8]) -> Result<(), CoreError> {
+        self.check_solution_with_rules(grid, Ruleset::keen_baseline())
+    }
+
+    /// [`check_solution`](Self::check_solution) under `rules`: columns are only checked when
+    /// [`Ruleset::enforce_columns`] is set.
+    pub fn check_solution_with_rules(&self, grid: &[u8], rules: Ruleset) -> Result<(), CoreError> {
+        let n = self.n;
+        let n_usize = n as usize;
+        if grid.len() != n_usize * n_usize {
+            return Err(CoreError::SolutionLengthMismatch {
+                expected: n_usize * n_usize,
+                got: grid.len(),
+            });
+        }
+        if let Some(idx) = grid.iter().position(|&v| !(1..=n).contains(&v)) {
+            return Err(CoreError::DigitOutOfRange {
+                row: (idx / n_usize) as u8,
+                col: (idx % n_usize) as u8,
+                value: grid[idx],
+                n,
+            });
+        }
+
+        let mut seen = vec![None; n_usize + 1];
+        for row in 0..n {
+            seen.fill(None);
+            for col in 0..n {
+                let value = grid[row as usize * n_usize + col as usize];
+                if let Some(first_col) = seen[value as usize].replace(col) {
+                    return Err(CoreError::RowRepeat {
+                        row,
+                        value,
+                        first_col,
+                        second_col: col,
+                    });
+                }
+            }
+        }
+        for col in (0..n).filter(|_| rules.enforce_columns) {
+            seen.fill(None);
+            for row in 0..n {
+                let value = grid[row as usize * n_usize + col as usize];
+                if let Some(first_row) = seen[value as usize].replace(row) {
+                    return Err(CoreError::ColumnRepeat {
+                        col,
+                        value,
+                        first_row,
+                        second_row: row,
+                    });
+                }
+            }
+        }
+
+        for (idx, cage) in self.cages.iter().enumerate() {
+            let values: SmallVec<[u8; INLINE_CAGE_CELLS]> = cage
+                .cells
+                .iter()
+                .map(|&cell| cell_index(n, cell).map(|i| grid[i]))
+                .collect::<Result<_, _>>()?;
+            if !arith::op_satisfied(cage.op, cage.target, &values) {
+                let anchor = coord(n, cage.cells[0])?;
+                return Err(CoreError::CageUnsatisfied {
+                    cage: idx,
+                    op: cage.op,
+                    target: cage.target,
+                    row: anchor.row,
+                    col: anchor.col,
+                });
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Puzzle {
+    /// Row and column of `cell` in this grid.
+    ///
+    /// Unchecked: an id past the grid maps to a row past the last one. Use [`coord`] to
+    /// reject those instead.
+    pub fn coord_of(&self, cell: CellId) -> Coord {
+        coord_unchecked(self.n, cell)
+    }
+
+    /// The cell at `row`, `col`, or [`CoreError::CellOutOfRange`] if either is `>= n`.
+    pub fn cell_at(&self, row: u8, col: u8) -> Result<CellId, CoreError> {
+        cell_id(self.n, Coord { row, col })
+    }
 }
 
 impl Cage {
+    /// Row and column of each cell on an `n` x `n` grid, in `cells` order; unchecked like
+    /// [`Puzzle::coord_of`].
+    pub fn coords(&self, n: u8) -> impl Iterator<Item = Coord> + '_ {
+        self.cells.iter().map(move |&cell| coord_unchecked(n, cell))
+    }
+
+    /// The cage's smallest cell id: its top-left cell in reading order, where clues are drawn.
+    ///
+    /// # Panics
+    ///
+    /// If the cage is empty, which [`Puzzle::validate`] rejects.
+    pub fn anchor(&self) -> CellId {
+        *self
+            .cells
+            .iter()
+            .min()
+            .expect("an empty cage has no anchor")
+    }
+
+    /// Rows the cage has cells in on an `n` x `n` grid, ascending and without repeats.
+    pub fn rows_touched(&self, n: u8) -> SmallVec<[u8; INLINE_CAGE_CELLS]> {
+        sorted_unique(self.coords(n).map(|c| c.row))
+    }
+
+    /// Columns the cage has cells in on an `n` x `n` grid, ascending and without repeats.
+    pub fn cols_touched(&self, n: u8) -> SmallVec<[u8; INLINE_CAGE_CELLS]> {
+        sorted_unique(self.coords(n).map(|c| c.col))
+    }
+
+    /// Inclusive range a target can take under this cage's op on an `n` x `n` grid, from its
+    /// size and whether its cells share a row or column (forcing distinct digits):
+    ///
+    /// - `Add`: `len..=len * n`, or the sums of the `len` smallest and largest distinct
+    ///   digits when every cell is in one row or column.
+    /// - `Mul`: `1..=n^len` (saturating).
+    /// - `Sub`: `1..=n - 1` for two cells; with more (n-ary, see [`arith`]) the low end
+    ///   is `n * (2 - len)`.
+    /// - `Div`: `2..=n` for two cells in one row or column, else `1..=n`.
+    /// - `Eq`: `1..=n`.
+    pub fn target_bounds(&self, n: u8) -> Result<(i64, i64), CoreError> {
+        self.target_bounds_with_rules(n, Ruleset::keen_baseline())
+    }
+
+    /// [`target_bounds`](Self::target_bounds) under `rules`: without
+    /// [`Ruleset::enforce_columns`] a column is no unit, so a vertical `Div` pair may hold
+    /// equal digits and reach 1.
+    pub fn target_bounds_with_rules(&self, n: u8, rules: Ruleset) -> Result<(i64, i64), CoreError> {
+        let coords = self
+            .cells
+            .iter()
+            .map(|&cell| coord(n, cell))
+            .collect::<Result<SmallVec<[Coord; INLINE_CAGE_CELLS]>, _>>()?;
+        let one_unit = coords.iter().all(|c| c.row == coords[0].row)
+            || (rules.enforce_columns && coords.iter().all(|c| c.col == coords[0].col));
+        Ok(arith::target_bounds(self.op, self.cells.len(), n, one_unit))
+    }
+
+    /// Upper bound on this cage's tuple count on an `n` x `n` grid ([`cage_tuple_count`]),
+    /// from its shape alone: `n^len`, less at each cell the digits already taken by earlier
+    /// cells in its row or column. Ignores the op and target, costs no enumeration, and
+    /// saturates at `u64::MAX`. Unchecked like [`coords`](Self::coords). Assumes Latin
+    /// columns; without [`Ruleset::enforce_columns`] only rows discount.
+    pub fn tuple_count_upper_bound(&self, n: u8) -> u64 {
+        let coords: SmallVec<[Coord; INLINE_CAGE_CELLS]> = self.coords(n).collect();
+        coords.iter().enumerate().fold(1u64, |bound, (i, c)| {
+            let earlier = &coords[..i];
+            let same_row = earlier.iter().filter(|e| e.row == c.row).count();
+            let same_col = earlier.iter().filter(|e| e.col == c.col).count();
+            let free = (n as usize).saturating_sub(same_row.max(same_col));
+            bound.saturating_mul(free as u64)
+        })
+    }
+
+    /// True if [`tuple_count_upper_bound`](Self::tuple_count_upper_bound) is within `budget`
+    /// (e.g. [`SEARCH_TUPLE_BUDGET`](crate::limits::SEARCH_TUPLE_BUDGET)), so at most `budget`
+    /// of the cage's tuples respect its rows and columns. `false` only means the shape alone
+    /// cannot promise that; a tight clue may still leave few tuples.
+    pub fn is_tractable(&self, n: u8, budget: usize) -> bool {
+        self.tuple_count_upper_bound(n) <= budget as u64
+    }
+
     pub fn validate_shape(&self, n: u8, rules: Ruleset) -> Result<(), CoreError> {
         if self.cells.is_empty() {
             return Err(CoreError::EmptyCage);
@@ -108,10 +342,18 @@ impl Cage {
             (_, _) => {}
         }
 
+        // Every op's target is positive: sums, products, differences and quotients of digits
+        // in 1..=n, and Eq digits themselves.
         if self.target == 0 {
             return Err(CoreError::TargetMustBeNonZero);
         }
-        if self.op == Op::Eq && !(1..=(n as i32)).contains(&self.target) {
+        if self.target < 0 {
+            return Err(CoreError::TargetNegative {
+                op: self.op,
+                target: self.target,
+            });
+        }
+        if self.op == Op::Eq && !(1..=(n as i64)).contains(&self.target) {
             return Err(CoreError::EqTargetOutOfRange);
         }
 
@@ -121,7 +363,7 @@ impl Cage {
 
         if rules.require_orthogonal_cage_connectivity && !is_orthogonally_connected(n, &self.cells)
         {
-            return Err(CoreError::CageNotConnected);
+            return Err(cage_not_connected(n, &self.cells));
         }
 
         Ok(())
@@ -143,7 +385,7 @@ impl Cage {
         n: u8,
         rules: Ruleset,
         max_tuples: usize,
-    ) -> Result<Option<Vec<SmallVec<[u8; 6]>>>, CoreError> {
+    ) -> Result<Option<Vec<SmallVec<[u8; INLINE_CAGE_CELLS]>>>, CoreError> {
         let len = self.cells.len();
         if len == 0 {
             return Err(CoreError::EmptyCage);
@@ -157,59 +399,59 @@ impl Cage {
         }
 
         let target = self.target;
-        let n_i32 = n as i32;
+        let n_i64 = n as i64;
         let max_tuples = max_tuples.max(1);
 
-        let mut out: Vec<SmallVec<[u8; 6]>> = Vec::new();
+        let mut out: Vec<SmallVec<[u8; INLINE_CAGE_CELLS]>> = Vec::new();
 
         match self.op {
             Op::Eq => {
-                if !(1..=n_i32).contains(&target) {
+                if !(1..=n_i64).contains(&target) {
                     Ok(Some(out))
                 } else {
-                    let mut t = SmallVec::<[u8; 6]>::new();
+                    let mut t = SmallVec::<[u8; INLINE_CAGE_CELLS]>::new();
                     t.push(target as u8);
                     out.push(t);
                     Ok(Some(out))
                 }
             }
-            Op::Sub => {
+            Op::Sub | Op::Div => {
                 if target <= 0 {
                     Ok(Some(out))
                 } else {
-                    for a in 1..=n {
-                        for b in 1..=n {
-                            if (a as i32 - b as i32).abs() == target {
-                                let mut t = SmallVec::<[u8; 6]>::with_capacity(2);
-                                t.push(a);
-                                t.push(b);
-                                out.push(t);
+                    // No partial pruning: the largest value is only known once the tuple is
+                    // complete, so check each full tuple with the n-ary semantics.
+                    fn rec(
+                        n: u8,
+                        op: Op,
+                        target: i64,
+                        len: usize,
+                        cur: &mut SmallVec<[u8; INLINE_CAGE_CELLS]>,
+                        out: &mut Vec<SmallVec<[u8; INLINE_CAGE_CELLS]>>,
+                        max_tuples: usize,
+                    ) -> bool {
+                        if cur.len() == len {
+                            if arith::op_satisfied(op, target, cur) {
+                                out.push(cur.clone());
                                 if out.len() >= max_tuples {
-                                    return Ok(None);
+                                    return false;
                                 }
                             }
+                            return true;
                         }
-                    }
-                    Ok(Some(out))
-                }
-            }
-            Op::Div => {
-                if target <= 0 {
-                    Ok(Some(out))
-                } else {
-                    for a in 1..=n {
-                        for b in 1..=n {
-                            let (num, den) = if a >= b { (a, b) } else { (b, a) };
-                            if den != 0 && (num as i32) == (den as i32).saturating_mul(target) {
-                                let mut t = SmallVec::<[u8; 6]>::with_capacity(2);
-                                t.push(a);
-                                t.push(b);
-                                out.push(t);
-                                if out.len() >= max_tuples {
-                                    return Ok(None);
-                                }
+                        for v in 1..=n {
+                            cur.push(v);
+                            if !rec(n, op, target, len, cur, out, max_tuples) {
+                                return false;
                             }
+                            cur.pop();
                         }
+                        true
+                    }
+
+                    let mut cur = SmallVec::<[u8; INLINE_CAGE_CELLS]>::with_capacity(len);
+                    if !rec(n, self.op, target, len, &mut cur, &mut out, max_tuples) {
+                        return Ok(None);
                     }
                     Ok(Some(out))
                 }
@@ -221,12 +463,12 @@ impl Cage {
                     #[allow(clippy::too_many_arguments)]
                     fn rec(
                         n: u8,
-                        target: i32,
+                        target: i64,
                         pos: usize,
                         len: usize,
-                        sum: i32,
-                        cur: &mut SmallVec<[u8; 6]>,
-                        out: &mut Vec<SmallVec<[u8; 6]>>,
+                        sum: i64,
+                        cur: &mut SmallVec<[u8; INLINE_CAGE_CELLS]>,
+                        out: &mut Vec<SmallVec<[u8; INLINE_CAGE_CELLS]>>,
                         max_tuples: usize,
                     ) -> bool {
                         if pos == len {
@@ -239,7 +481,7 @@ impl Cage {
                             return true;
                         }
                         for v in 1..=n {
-                            let next_sum = sum + v as i32;
+                            let next_sum = sum + v as i64;
                             if next_sum > target {
                                 continue;
                             }
@@ -252,7 +494,7 @@ impl Cage {
                         true
                     }
 
-                    let mut cur = SmallVec::<[u8; 6]>::with_capacity(len);
+                    let mut cur = SmallVec::<[u8; INLINE_CAGE_CELLS]>::with_capacity(len);
                     if !rec(n, target, 0, len, 0, &mut cur, &mut out, max_tuples) {
                         return Ok(None);
                     }
@@ -266,12 +508,12 @@ impl Cage {
                     #[allow(clippy::too_many_arguments)]
                     fn rec(
                         n: u8,
-                        target: i32,
+                        target: i64,
                         pos: usize,
                         len: usize,
-                        prod: i32,
-                        cur: &mut SmallVec<[u8; 6]>,
-                        out: &mut Vec<SmallVec<[u8; 6]>>,
+                        prod: i64,
+                        cur: &mut SmallVec<[u8; INLINE_CAGE_CELLS]>,
+                        out: &mut Vec<SmallVec<[u8; INLINE_CAGE_CELLS]>>,
                         max_tuples: usize,
                     ) -> bool {
                         if pos == len {
@@ -284,7 +526,7 @@ impl Cage {
                             return true;
                         }
                         for v in 1..=n {
-                            let next = prod.saturating_mul(v as i32);
+                            let next = prod.saturating_mul(v as i64);
                             if next == 0 {
                                 continue;
                             }
@@ -300,7 +542,7 @@ impl Cage {
                         true
                     }
 
-                    let mut cur = SmallVec::<[u8; 6]>::with_capacity(len);
+                    let mut cur = SmallVec::<[u8; INLINE_CAGE_CELLS]>::with_capacity(len);
                     if !rec(n, target, 0, len, 1, &mut cur, &mut out, max_tuples) {
                         return Ok(None);
                     }
@@ -311,9 +553,101 @@ impl Cage {
     }
 }
 
+/// Count the value tuples that satisfy `cage`'s clue with distinct values in cells that share
+/// a row or column: the Latin constraints of the rows and columns the cage touches, and
+/// nothing from the rest of the grid. Columns only count when [`Ruleset::enforce_columns`]
+/// is set.
+///
+/// The counting sibling of [`Cage::valid_permutations`]: the same checks and depth-first
+/// search, but tuples are counted rather than collected, and there is no cap.
+pub fn cage_tuple_count(cage: &Cage, n: u8, rules: Ruleset) -> Result<u64, CoreError> {
+    let len = cage.cells.len();
+    if len == 0 {
+        return Err(CoreError::EmptyCage);
+    }
+    if rules.sub_div_two_cell_only && matches!(cage.op, Op::Sub | Op::Div) && len != 2 {
+        return Err(CoreError::SubDivMustBeTwoCell);
+    }
+    if cage.op == Op::Eq && len != 1 {
+        return Err(CoreError::InvalidOpForCageSize { op: cage.op, len });
+    }
+
+    let coords = cage
+        .cells
+        .iter()
+        .map(|&cell| coord(n, cell))
+        .collect::<Result<SmallVec<[Coord; INLINE_CAGE_CELLS]>, _>>()?;
+    if cage.target <= 0 {
+        return Ok(0);
+    }
+
+    struct Search {
+        n: u8,
+        op: Op,
+        target: i64,
+        /// Per position, the earlier positions in the same row or column.
+        clashes: Vec<SmallVec<[usize; INLINE_CAGE_CELLS]>>,
+        cur: SmallVec<[u8; INLINE_CAGE_CELLS]>,
+    }
+
+    impl Search {
+        /// `acc` is the running sum (Add) or product (Mul) of `cur`, for pruning.
+        fn rec(&mut self, acc: i64) -> u64 {
+            let pos = self.cur.len();
+            if pos == self.clashes.len() {
+                return u64::from(arith::op_satisfied(self.op, self.target, &self.cur));
+            }
+            let mut count = 0u64;
+            for v in 1..=self.n {
+                if self.clashes[pos].iter().any(|&p| self.cur[p] == v) {
+                    continue;
+                }
+                let next = match self.op {
+                    Op::Add => acc + v as i64,
+                    Op::Mul => acc.saturating_mul(v as i64),
+                    _ => acc,
+                };
+                match self.op {
+                    Op::Add if next > self.target => continue,
+                    Op::Mul if self.target % next != 0 => continue,
+                    _ => {}
+                }
+                self.cur.push(v);
+                count += self.rec(next);
+                self.cur.pop();
+            }
+            count
+        }
+    }
+
+    let clashes = (0..len)
+        .map(|i| {
+            (0..i)
+                .filter(|&j| {
+                    coords[j].row == coords[i].row
+                        || (rules.enforce_columns && coords[j].col == coords[i].col)
+                })
+                .collect()
+        })
+        .collect();
+    let mut search = Search {
+        n,
+        op: cage.op,
+        target: cage.target,
+        clashes,
+        cur: SmallVec::with_capacity(len),
+    };
+    let start = if cage.op == Op::Mul { 1 } else { 0 };
+    Ok(search.rec(start))
+}
+
 #[cfg(test)]
 mod tuple_enum_tests {
-    use super::{Cage, CellId};
+    use smallvec::SmallVec;
+
+    use super::{Cage, CellId, cage_tuple_count};
+    use crate::error::CoreError;
+    use crate::limits::INLINE_CAGE_CELLS;
     use crate::rules::{Op, Ruleset};
 
     #[test]
@@ -331,6 +665,34 @@ mod tuple_enum_tests {
         assert!(tuples.iter().any(|t| t.as_slice() == [2, 1]));
     }
 
+    #[test]
+    fn three_cell_sub_requires_permissive_rules() {
+        let cage = Cage {
+            cells: [CellId(0), CellId(1), CellId(2)].into_iter().collect(),
+            op: Op::Sub,
+            target: 1,
+        };
+        assert!(
+            cage.valid_permutations(4, Ruleset::keen_baseline(), 1024)
+                .is_err()
+        );
+
+        let rules = Ruleset {
+            sub_div_two_cell_only: false,
+            ..Ruleset::keen_baseline()
+        };
+        let tuples = cage.valid_permutations(4, rules, 1024).unwrap().unwrap();
+        // max - (sum of rest) == 1, e.g. 4 - (1 + 2).
+        assert!(tuples.iter().any(|t| t.as_slice() == [4, 1, 2]));
+        assert!(tuples.iter().any(|t| t.as_slice() == [1, 4, 2]));
+        assert!(!tuples.iter().any(|t| t.as_slice() == [3, 1, 2]));
+        assert!(tuples.iter().all(|t| {
+            let max = *t.iter().max().unwrap() as i64;
+            let sum: i64 = t.iter().map(|&v| v as i64).sum();
+            2 * max - sum == 1
+        }));
+    }
+
     #[test]
     fn threshold_returns_none() {
         let cage = Cage {
@@ -345,6 +707,151 @@ mod tuple_enum_tests {
                 .is_none()
         );
     }
+
+    #[test]
+    fn tuple_counts_match_latin_filtered_permutations() {
+        // (row, col) shapes on a 5x5: domino, straight and bent triominoes, a square, an S.
+        let shapes: [&[(u16, u16)]; 5] = [
+            &[(0, 0), (0, 1)],
+            &[(0, 0), (1, 0), (2, 0)],
+            &[(0, 0), (0, 1), (1, 1)],
+            &[(0, 0), (0, 1), (1, 0), (1, 1)],
+            &[(0, 1), (0, 2), (1, 0), (1, 1)],
+        ];
+        let rules = Ruleset {
+            sub_div_two_cell_only: false,
+            ..Ruleset::keen_baseline()
+        };
+        for n in [4u8, 5] {
+            for shape in shapes {
+                let cells: SmallVec<[CellId; INLINE_CAGE_CELLS]> = shape
+                    .iter()
+                    .map(|&(r, c)| CellId(r * n as u16 + c))
+                    .collect();
+                for op in [Op::Add, Op::Mul, Op::Sub, Op::Div] {
+                    for target in 1..=40 {
+                        let cage = Cage {
+                            cells: cells.clone(),
+                            op,
+                            target,
+                        };
+                        let latin = |t: &&SmallVec<[u8; INLINE_CAGE_CELLS]>| {
+                            (0..shape.len()).all(|i| {
+                                (0..i).all(|j| {
+                                    let (a, b) = (shape[i], shape[j]);
+                                    (a.0 != b.0 && a.1 != b.1) || t[i] != t[j]
+                                })
+                            })
+                        };
+                        let expected = cage
+                            .valid_permutations(n, rules, usize::MAX)
+                            .unwrap()
+                            .unwrap()
+                            .iter()
+                            .filter(latin)
+                            .count() as u64;
+                        assert_eq!(
+                            cage_tuple_count(&cage, n, rules).unwrap(),
+                            expected,
+                            "n={n} {shape:?} {op} {target}"
+                        );
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn tuple_count_rejects_what_valid_permutations_rejects() {
+        let three_cell_sub = Cage {
+            cells: [CellId(0), CellId(1), CellId(2)].into_iter().collect(),
+            op: Op::Sub,
+            target: 1,
+        };
+        assert!(matches!(
+            cage_tuple_count(&three_cell_sub, 4, Ruleset::keen_baseline()),
+            Err(CoreError::SubDivMustBeTwoCell)
+        ));
+        let off_grid = Cage {
+            cells: [CellId(15), CellId(16)].into_iter().collect(),
+            op: Op::Add,
+            target: 3,
+        };
+        assert!(matches!(
+            cage_tuple_count(&off_grid, 4, Ruleset::keen_baseline()),
+            Err(CoreError::CellOutOfRange { .. })
+        ));
+    }
+
+    #[test]
+    fn upper_bound_discounts_shared_lines_and_covers_the_count() {
+        let rules = Ruleset {
+            sub_div_two_cell_only: false,
+            max_cage_size: 8,
+            ..Ruleset::keen_baseline()
+        };
+        let on_5x5 = |cells: &[u16], op, target| Cage {
+            cells: cells.iter().copied().map(CellId).collect(),
+            op,
+            target,
+        };
+        // A row triple is 5 * 4 * 3; the square's last cell clashes once per line, so 5 * 4^3.
+        let row = on_5x5(&[0, 1, 2], Op::Add, 6);
+        assert_eq!(row.tuple_count_upper_bound(5), 60);
+        let square = on_5x5(&[0, 1, 5, 6], Op::Mul, 24);
+        assert_eq!(square.tuple_count_upper_bound(5), 320);
+        // Seven and eight cells over two rows, and a 2x4 block, whatever the clue.
+        let shapes: [&[u16]; 3] = [
+            &[0, 1, 2, 3, 4, 5, 6],
+            &[0, 1, 2, 3, 4, 5, 6, 7],
+            &[0, 1, 5, 6, 10, 11, 15, 16],
+        ];
+        for shape in shapes {
+            for op in [Op::Add, Op::Mul, Op::Sub, Op::Div] {
+                for target in [1, 2, 12, 20, 30, 120] {
+                    let cage = on_5x5(shape, op, target);
+                    let bound = cage.tuple_count_upper_bound(5);
+                    assert!(
+                        cage_tuple_count(&cage, 5, rules).unwrap() <= bound,
+                        "{shape:?} {op} {target}"
+                    );
+                }
+            }
+        }
+
+        let long = Cage {
+            cells: (0..12).map(CellId).collect(),
+            op: Op::Add,
+            target: 100,
+        };
+        assert_eq!(long.tuple_count_upper_bound(64), u64::MAX);
+        assert!(!long.is_tractable(64, crate::limits::SEARCH_TUPLE_BUDGET));
+        assert!(square.is_tractable(5, 320));
+        assert!(!square.is_tractable(5, 319));
+    }
+}
+
+/// Smallest grid size accepted by [`validate_grid_size`] in every configuration: a 1x1 grid
+/// has no Latin constraint to solve, and the solver, sgt-desc format and generator all assume
+/// at least two symbols.
+pub const MIN_GRID_SIZE: u8 = 2;
+
+/// Largest grid size accepted by [`validate_grid_size`] in this build. Follows the domain
+/// width (`core-u64`: 63, `core-bitvec`: 255, else 31).
+#[cfg(not(any(feature = "core-u64", feature = "core-bitvec")))]
+pub const MAX_GRID_SIZE: u8 = 31;
+#[cfg(all(feature = "core-u64", not(feature = "core-bitvec")))]
+pub const MAX_GRID_SIZE: u8 = 63;
+#[cfg(feature = "core-bitvec")]
+pub const MAX_GRID_SIZE: u8 = 255;
+
+/// Check `n` against the grid sizes this build supports
+/// (`MIN_GRID_SIZE..=MAX_GRID_SIZE`).
+pub fn validate_grid_size(n: u8) -> Result<(), CoreError> {
+    if !(MIN_GRID_SIZE..=MAX_GRID_SIZE).contains(&n) {
+        return Err(CoreError::InvalidGridSize(n));
+    }
+    Ok(())
 }
 
 pub fn cell_id(n: u8, coord: Coord) -> Result<CellId, CoreError> {
@@ -365,7 +872,23 @@ pub fn coord(n: u8, cell: CellId) -> Result<Coord, CoreError> {
     })
 }
 
-fn cell_index(n: u8, cell: CellId) -> Result<usize, CoreError> {
+/// [`coord`] without the range check; `n == 0` is treated as 1 rather than dividing by zero.
+pub(crate) fn coord_unchecked(n: u8, cell: CellId) -> Coord {
+    let n = u16::from(n.max(1));
+    Coord {
+        row: (cell.0 / n) as u8,
+        col: (cell.0 % n) as u8,
+    }
+}
+
+fn sorted_unique(lines: impl Iterator<Item = u8>) -> SmallVec<[u8; INLINE_CAGE_CELLS]> {
+    let mut lines: SmallVec<[u8; INLINE_CAGE_CELLS]> = lines.collect();
+    lines.sort_unstable();
+    lines.dedup();
+    lines
+}
+
+pub(crate) fn cell_index(n: u8, cell: CellId) -> Result<usize, CoreError> {
     let a = (n as usize) * (n as usize);
     let idx = cell.0 as usize;
     if idx >= a {
@@ -374,7 +897,7 @@ fn cell_index(n: u8, cell: CellId) -> Result<usize, CoreError> {
     Ok(idx)
 }
 
-fn is_orthogonally_connected(n: u8, cells: &[CellId]) -> bool {
+pub(crate) fn is_orthogonally_connected(n: u8, cells: &[CellId]) -> bool {
     if cells.len() <= 1 {
         return true;
     }
@@ -431,6 +954,74 @@ fn is_orthogonally_connected(n: u8, cells: &[CellId]) -> bool {
     count == cells.len()
 }
 
+/// The orthogonally connected pieces of `cells`, each in row-major order and ordered by its
+/// first cell. The flood fill behind [`CoreError::CageNotConnected`]; hot paths only need
+/// [`is_orthogonally_connected`]. Cells off the grid are left out.
+pub(crate) fn orthogonal_components(n: u8, cells: &[CellId]) -> Vec<Vec<CellId>> {
+    let n = n as usize;
+    let a = n * n;
+    let mut in_cage = vec![false; a];
+    for &cell in cells {
+        if let Some(slot) = in_cage.get_mut(cell.0 as usize) {
+            *slot = true;
+        }
+    }
+
+    let mut sorted: Vec<usize> = cells
+        .iter()
+        .map(|c| c.0 as usize)
+        .filter(|&i| i < a)
+        .collect();
+    sorted.sort_unstable();
+    let mut visited = vec![false; a];
+    let mut components = Vec::new();
+    for start in sorted {
+        if visited[start] {
+            continue;
+        }
+        visited[start] = true;
+        let mut piece = Vec::new();
+        let mut stack = vec![start];
+        while let Some(idx) = stack.pop() {
+            piece.push(CellId(idx as u16));
+            let (r, c) = (idx / n, idx % n);
+            let neighbours = [
+                (r > 0).then(|| idx - n),
+                (r + 1 < n).then(|| idx + n),
+                (c > 0).then(|| idx - 1),
+                (c + 1 < n).then(|| idx + 1),
+            ];
+            for next in neighbours.into_iter().flatten() {
+                if in_cage[next] && !visited[next] {
+                    visited[next] = true;
+                    stack.push(next);
+                }
+            }
+        }
+        piece.sort_unstable();
+        components.push(piece);
+    }
+    components
+}
+
+/// [`CoreError::CageNotConnected`] for `cells`, which [`is_orthogonally_connected`] rejected,
+/// without a cage index; see [`CoreError::in_cage`].
+pub(crate) fn cage_not_connected(n: u8, cells: &[CellId]) -> CoreError {
+    let components: Vec<Vec<Coord>> = orthogonal_components(n, cells)
+        .into_iter()
+        .map(|piece| piece.into_iter().map(|c| coord_unchecked(n, c)).collect())
+        .collect();
+    let anchor = cells
+        .iter()
+        .min()
+        .map_or(Coord { row: 0, col: 0 }, |&c| coord_unchecked(n, c));
+    CoreError::CageNotConnected {
+        cage: None,
+        anchor,
+        components,
+    }
+}
+
 // ============================================================
 // Kani Verification Harnesses
 // ============================================================
@@ -547,7 +1138,7 @@ mod kani_verification {
 mod tests {
     use super::*;
 
-    fn eq(n: u8, row: u8, col: u8, target: i32) -> Cage {
+    fn eq(n: u8, row: u8, col: u8, target: i64) -> Cage {
         Cage {
             cells: SmallVec::from_slice(&[CellId((row as u16) * (n as u16) + col as u16)]),
             op: Op::Eq,
@@ -562,10 +1153,66 @@ mod tests {
             n,
             cages: vec![eq(n, 0, 0, 1), eq(n, 0, 1, 2), eq(n, 1, 0, 2)],
         };
+        let err = p.validate(Ruleset::keen_baseline()).unwrap_err();
         assert!(matches!(
-            p.validate(Ruleset::keen_baseline()),
-            Err(CoreError::CellUncovered(_))
+            err,
+            CoreError::CellUncovered {
+                cell: CellId(3),
+                coord: Coord { row: 1, col: 1 }
+            }
         ));
+        assert_eq!(
+            err.to_string(),
+            "grid cell 3 (r2c2) is not covered by any cage"
+        );
+    }
+
+    #[test]
+    fn validate_reports_the_pieces_of_a_fragmented_cage() {
+        let n = 4;
+        let fragmented = Cage {
+            cells: [1, 2, 7, 12, 13].into_iter().map(CellId).collect(),
+            op: Op::Add,
+            target: 10,
+        };
+        let p = Puzzle {
+            n,
+            cages: vec![eq(n, 0, 0, 1), eq(n, 0, 3, 4), fragmented.clone()],
+        };
+        let at = |row, col| Coord { row, col };
+        let err = p.validate(Ruleset::keen_baseline()).unwrap_err();
+        let CoreError::CageNotConnected {
+            cage,
+            anchor,
+            ref components,
+        } = err
+        else {
+            panic!("expected CageNotConnected, got {err:?}");
+        };
+        assert_eq!(cage, Some(2));
+        assert_eq!(anchor, at(0, 1));
+        assert_eq!(
+            *components,
+            [
+                vec![at(0, 1), at(0, 2)],
+                vec![at(1, 3)],
+                vec![at(3, 0), at(3, 1)],
+            ]
+        );
+        assert_eq!(
+            err.to_string(),
+            "cage 2 at r1c2 is not orthogonally connected: 3 pieces, [r1c2 r1c3] [r2c4] [r4c1 r4c2]"
+        );
+
+        // On its own the cage has no index to report.
+        let err = fragmented
+            .validate_shape(n, Ruleset::keen_baseline())
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            CoreError::CageNotConnected { cage: None, .. }
+        ));
+        assert!(err.to_string().starts_with("cage at r1c2 "), "{err}");
     }
 
     #[test]
@@ -581,9 +1228,319 @@ mod tests {
                 eq(n, 1, 1, 1),
             ],
         };
+        let err = p.validate(Ruleset::keen_baseline()).unwrap_err();
+        assert!(matches!(
+            err,
+            CoreError::CellDuplicated {
+                cell: CellId(0),
+                coord: Coord { row: 0, col: 0 }
+            }
+        ));
+        assert_eq!(
+            err.to_string(),
+            "cell id 0 (r1c1) appears in more than one cage"
+        );
+    }
+
+    #[test]
+    fn validate_rejects_unsorted_cage_cells() {
+        let rules = Ruleset::keen_baseline();
+        let pair = |cells: [u16; 2]| Cage {
+            cells: cells.into_iter().map(CellId).collect(),
+            op: Op::Add,
+            target: 3,
+        };
+        let mut p = Puzzle {
+            n: 2,
+            cages: vec![pair([0, 1]), pair([3, 2])],
+        };
+        assert!(matches!(
+            p.validate(rules),
+            Err(CoreError::CageCellsUnsorted { cage: 1 })
+        ));
+        p.normalize();
+        p.validate(rules).unwrap();
+
+        // A cell repeated within a cage is still a duplicate, not an ordering error. (A
+        // repeated cell does not count as connected, so connectivity is off here.)
+        p.cages[1] = pair([2, 2]);
+        let loose = Ruleset {
+            require_orthogonal_cage_connectivity: false,
+            ..rules
+        };
+        assert!(matches!(
+            p.validate(loose),
+            Err(CoreError::CellDuplicated {
+                cell: CellId(2),
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn validate_rejects_non_positive_targets() {
+        let rules = Ruleset::keen_baseline();
+        let pair = |op, target| Cage {
+            cells: SmallVec::from_slice(&[CellId(0), CellId(1)]),
+            op,
+            target,
+        };
+        for op in [Op::Add, Op::Mul, Op::Sub, Op::Div] {
+            assert!(matches!(
+                pair(op, 0).validate_shape(2, rules),
+                Err(CoreError::TargetMustBeNonZero)
+            ));
+            match pair(op, -3).validate_shape(2, rules) {
+                Err(CoreError::TargetNegative {
+                    op: got,
+                    target: -3,
+                }) => assert_eq!(got, op),
+                other => panic!("{op:?}: {other:?}"),
+            }
+        }
+        assert!(matches!(
+            eq(2, 0, 0, -1).validate_shape(2, rules),
+            Err(CoreError::TargetNegative {
+                op: Op::Eq,
+                target: -1
+            })
+        ));
+    }
+
+    #[test]
+    fn validate_rejects_one_by_one_grid() {
+        let p = Puzzle {
+            n: 1,
+            cages: vec![eq(1, 0, 0, 1)],
+        };
         assert!(matches!(
             p.validate(Ruleset::keen_baseline()),
-            Err(CoreError::CellDuplicated(_))
+            Err(CoreError::InvalidGridSize(1))
+        ));
+        assert!(matches!(
+            validate_grid_size(0),
+            Err(CoreError::InvalidGridSize(0))
         ));
+        assert!(validate_grid_size(2).is_ok());
+    }
+
+    #[test]
+    fn check_solution_reports_first_violation() {
+        let n = 2;
+        let p = Puzzle {
+            n,
+            cages: vec![
+                eq(n, 0, 0, 1),
+                eq(n, 0, 1, 2),
+                eq(n, 1, 0, 2),
+                eq(n, 1, 1, 1),
+            ],
+        };
+        assert!(p.check_solution(&[1, 2, 2, 1]).is_ok());
+        assert!(matches!(
+            p.check_solution(&[1, 2, 2]),
+            Err(CoreError::SolutionLengthMismatch {
+                expected: 4,
+                got: 3
+            })
+        ));
+        assert!(matches!(
+            p.check_solution(&[1, 2, 3, 1]),
+            Err(CoreError::DigitOutOfRange {
+                row: 1,
+                col: 0,
+                value: 3,
+                n: 2
+            })
+        ));
+        assert!(matches!(
+            p.check_solution(&[1, 2, 1, 1]),
+            Err(CoreError::RowRepeat {
+                row: 1,
+                value: 1,
+                first_col: 0,
+                second_col: 1
+            })
+        ));
+        assert!(matches!(
+            p.check_solution(&[1, 2, 1, 2]),
+            Err(CoreError::ColumnRepeat {
+                col: 0,
+                value: 1,
+                first_row: 0,
+                second_row: 1
+            })
+        ));
+        assert!(matches!(
+            p.check_solution(&[2, 1, 1, 2]),
+            Err(CoreError::CageUnsatisfied {
+                cage: 0,
+                op: Op::Eq,
+                target: 1,
+                row: 0,
+                col: 0
+            })
+        ));
+
+        // Rows only: the column repeat is fine, and the cages are checked next.
+        let rows_only = Ruleset {
+            enforce_columns: false,
+            ..Ruleset::keen_baseline()
+        };
+        assert!(matches!(
+            p.check_solution_with_rules(&[1, 2, 1, 2], rows_only),
+            Err(CoreError::CageUnsatisfied { cage: 2, .. })
+        ));
+        assert!(matches!(
+            p.check_solution_with_rules(&[1, 2, 1, 1], rows_only),
+            Err(CoreError::RowRepeat { row: 1, .. })
+        ));
+        assert!(
+            p.check_solution_with_rules(&[1, 2, 2, 1], rows_only)
+                .is_ok()
+        );
+    }
+
+    fn cage(cells: &[u16], op: Op, target: i64) -> Cage {
+        Cage {
+            cells: cells.iter().copied().map(CellId).collect(),
+            op,
+            target,
+        }
+    }
+
+    /// `validate_clues` on a 4x4 puzzle whose third cage is `bad`, after two fine ones.
+    fn clue_error(bad: Cage) -> Option<CoreError> {
+        let p = Puzzle {
+            n: 4,
+            cages: vec![cage(&[0], Op::Eq, 4), cage(&[1, 2], Op::Add, 7), bad],
+        };
+        p.validate_clues(Ruleset::keen_baseline()).err()
+    }
+
+    fn out_of_range(bad: Cage) -> Option<(i64, i64)> {
+        match clue_error(bad)? {
+            CoreError::ClueTargetOutOfRange {
+                cage: 2, min, max, ..
+            } => Some((min, max)),
+            other => panic!("unexpected {other:?}"),
+        }
+    }
+
+    #[test]
+    fn add_bounds_tighten_inside_one_row_or_column() {
+        // A domino always shares a unit: 1 + 2 up to 4 + 3.
+        assert_eq!(out_of_range(cage(&[4, 5], Op::Add, 8)), Some((3, 7)));
+        assert_eq!(out_of_range(cage(&[4, 8], Op::Add, 2)), Some((3, 7)));
+        assert!(clue_error(cage(&[4, 5], Op::Add, 7)).is_none());
+        // Three in a row: 1 + 2 + 3 up to 4 + 3 + 2.
+        assert_eq!(out_of_range(cage(&[4, 5, 6], Op::Add, 10)), Some((6, 9)));
+        // An L can repeat a digit across its corner.
+        assert!(clue_error(cage(&[4, 5, 8], Op::Add, 12)).is_none());
+        assert_eq!(out_of_range(cage(&[4, 5, 8], Op::Add, 13)), Some((3, 12)));
+    }
+
+    #[test]
+    fn sub_div_and_eq_bounds() {
+        assert_eq!(out_of_range(cage(&[4, 5], Op::Sub, 4)), Some((1, 3)));
+        assert!(clue_error(cage(&[4, 5], Op::Sub, 3)).is_none());
+        assert_eq!(out_of_range(cage(&[4, 5], Op::Div, 5)), Some((2, 4)));
+        assert_eq!(out_of_range(cage(&[4, 5], Op::Div, 1)), Some((2, 4)));
+        assert!(clue_error(cage(&[4, 5], Op::Div, 3)).is_none());
+        assert_eq!(out_of_range(cage(&[4], Op::Eq, 5)), Some((1, 4)));
+        assert!(matches!(
+            clue_error(cage(&[4, 5], Op::Eq, 1)),
+            Some(CoreError::EqCageNotSingleton { cage: 2, len: 2 })
+        ));
+    }
+
+    #[test]
+    fn mul_targets_need_a_factorization_into_digits() {
+        assert_eq!(out_of_range(cage(&[4, 5], Op::Mul, 17)), Some((1, 16)));
+        assert!(matches!(
+            clue_error(cage(&[4, 5], Op::Mul, 7)),
+            Some(CoreError::ClueUnsatisfiable {
+                cage: 2,
+                op: Op::Mul,
+                target: 7
+            })
+        ));
+        assert!(clue_error(cage(&[4, 5], Op::Mul, 12)).is_none());
+        assert!(clue_error(cage(&[4, 5, 6], Op::Mul, 64)).is_none());
+    }
+
+    #[test]
+    fn validate_full_adds_the_clue_checks() {
+        let rules = Ruleset::keen_baseline();
+        let ok = Puzzle {
+            n: 2,
+            cages: vec![cage(&[0, 1], Op::Add, 3), cage(&[2, 3], Op::Add, 3)],
+        };
+        assert!(ok.validate_full(rules).is_ok());
+
+        // Structurally fine, but a domino on a 2x2 grid always sums to 3.
+        let bad = Puzzle {
+            n: 2,
+            cages: vec![cage(&[0, 1], Op::Add, 3), cage(&[2, 3], Op::Add, 4)],
+        };
+        assert!(bad.validate(rules).is_ok());
+        assert!(matches!(
+            bad.validate_full(rules),
+            Err(CoreError::ClueTargetOutOfRange {
+                cage: 1,
+                min: 3,
+                max: 3,
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn coordinates_round_trip_and_check_bounds() {
+        let puzzle = Puzzle {
+            n: 4,
+            cages: Vec::new(),
+        };
+        for row in 0..4 {
+            for col in 0..4 {
+                let cell = puzzle.cell_at(row, col).unwrap();
+                assert_eq!(puzzle.coord_of(cell), Coord { row, col });
+                assert_eq!(coord(4, cell).unwrap(), Coord { row, col });
+            }
+        }
+        for (row, col) in [(4, 0), (0, 4), (u8::MAX, u8::MAX)] {
+            assert!(matches!(
+                puzzle.cell_at(row, col),
+                Err(CoreError::CellOutOfRange { n: 4, .. })
+            ));
+        }
+        // Unchecked: one past the grid lands in the row after the last.
+        assert_eq!(puzzle.coord_of(CellId(16)), Coord { row: 4, col: 0 });
+    }
+
+    #[test]
+    fn cage_coordinates_anchor_and_lines() {
+        // An L on a 4x4 grid, listed out of order.
+        let l = cage(&[9, 4, 5, 1], Op::Add, 10);
+        let coords: Vec<(u8, u8)> = l.coords(4).map(|c| (c.row, c.col)).collect();
+        assert_eq!(coords, [(2, 1), (1, 0), (1, 1), (0, 1)]);
+        assert_eq!(l.anchor(), CellId(1));
+        assert_eq!(l.rows_touched(4).as_slice(), [0, 1, 2]);
+        assert_eq!(l.cols_touched(4).as_slice(), [0, 1]);
+
+        let single = eq(4, 3, 2, 1);
+        assert_eq!(single.anchor(), CellId(14));
+        assert_eq!(single.rows_touched(4).as_slice(), [3]);
+        assert_eq!(single.cols_touched(4).as_slice(), [2]);
+
+        let empty = cage(&[], Op::Add, 1);
+        assert_eq!(empty.coords(4).count(), 0);
+        assert!(empty.rows_touched(4).is_empty() && empty.cols_touched(4).is_empty());
+    }
+
+    #[test]
+    #[should_panic(expected = "empty cage has no anchor")]
+    fn empty_cage_has_no_anchor() {
+        cage(&[], Op::Add, 1).anchor();
     }
 }
@@ -0,0 +1,297 @@
+//! Human-readable grid rendering for debugging cage layouts.
+//!
+//! Cage borders are drawn with heavy box-drawing lines and borders between cells of the
+//! same cage with light lines. Each cage's clue (`target` followed by the op symbol) sits
+//! in its top-left cell; solution digits, when given, are centered on the line below.
+
+use crate::clue::ClueStyle;
+use crate::puzzle::Puzzle;
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Weight {
+    None,
+    Light,
+    Heavy,
+}
+
+/// Render `puzzle` as a box-drawn grid, optionally filling in `solution` digits.
+///
+/// `solution` is row-major with `n*n` entries (0 = blank). Cells not covered by any cage
+/// are treated as their own region so malformed puzzles still render.
+pub fn render_ascii(puzzle: &Puzzle, solution: Option<&[u8]>) -> String {
+    let n = puzzle.n as usize;
+    let a = n * n;
+
+    let mut cage_of_cell = vec![usize::MAX; a];
+    let mut clue_at: Vec<Option<String>> = vec![None; a];
+    for (cage_idx, cage) in puzzle.cages.iter().enumerate() {
+        let mut top_left: Option<usize> = None;
+        for cell in &cage.cells {
+            let idx = cell.0 as usize;
+            if idx < a {
+                cage_of_cell[idx] = cage_idx;
+                top_left = Some(top_left.map_or(idx, |t| t.min(idx)));
+            }
+        }
+        if let Some(idx) = top_left {
+            clue_at[idx] = Some(CLUE_STYLE.format(cage.op, cage.target));
+        }
+    }
+
+    let digit = |idx: usize| -> String {
+        match solution.and_then(|s| s.get(idx)) {
+            Some(&v) if v != 0 => v.to_string(),
+            _ => String::new(),
+        }
+    };
+
+    let width = clue_at
+        .iter()
+        .flatten()
+        .map(|c| c.chars().count() + 1)
+        .chain(std::iter::once(n.to_string().len() + 2))
+        .max()
+        .unwrap_or(3)
+        .max(3);
+
+    let same_cage = |x: usize, y: usize| {
+        let (cx, cy) = (cage_of_cell[x], cage_of_cell[y]);
+        cx != usize::MAX && cx == cy
+    };
+    // Edge above cell (r, c), for r in 0..=n.
+    let h_edge = |r: usize, c: usize| {
+        if r == 0 || r == n || !same_cage((r - 1) * n + c, r * n + c) {
+            Weight::Heavy
+        } else {
+            Weight::Light
+        }
+    };
+    // Edge left of cell (r, c), for c in 0..=n.
+    let v_edge = |r: usize, c: usize| {
+        if c == 0 || c == n || !same_cage(r * n + c - 1, r * n + c) {
+            Weight::Heavy
+        } else {
+            Weight::Light
+        }
+    };
+
+    let mut out = String::new();
+    for r in 0..=n {
+        // Border line above row r.
+        for c in 0..=n {
+            let up = if r > 0 {
+                v_edge(r - 1, c)
+            } else {
+                Weight::None
+            };
+            let down = if r < n { v_edge(r, c) } else { Weight::None };
+            let left = if c > 0 {
+                h_edge(r, c - 1)
+            } else {
+                Weight::None
+            };
+            let right = if c < n { h_edge(r, c) } else { Weight::None };
+            out.push(junction(up, right, down, left));
+            if c < n {
+                let fill = if right == Weight::Heavy { '━' } else { '─' };
+                out.extend(std::iter::repeat_n(fill, width));
+            }
+        }
+        out.push('\n');
+        if r == n {
+            break;
+        }
+
+        // Clue line, then digit line.
+        for line in 0..2 {
+            for c in 0..=n {
+                out.push(vertical(v_edge(r, c)));
+                if c == n {
+                    break;
+                }
+                let idx = r * n + c;
+                if line == 0 {
+                    let text = clue_at[idx].as_deref().unwrap_or("");
+                    out.push_str(text);
+                    let pad = width - text.chars().count();
+                    out.extend(std::iter::repeat_n(' ', pad));
+                } else {
+                    let text = digit(idx);
+                    let pad = width - text.len();
+                    out.extend(std::iter::repeat_n(' ', pad / 2));
+                    out.push_str(&text);
+                    out.extend(std::iter::repeat_n(' ', pad - pad / 2));
+                }
+            }
+            out.push('\n');
+        }
+    }
+    out
+}
+
+/// Unicode glyphs, but a plain hyphen for subtraction.
+const CLUE_STYLE: ClueStyle = ClueStyle {
+    sub: '-',
+    ..ClueStyle::UNICODE
+};
+
+fn vertical(w: Weight) -> char {
+    match w {
+        Weight::Heavy => '┃',
+        _ => '│',
+    }
+}
+
+/// Box-drawing junction for the given arm weights (up, right, down, left).
+///
+/// The outer frame is always heavy, so only the combinations produced by `render_ascii`
+/// are mapped: corners, frame T-junctions, and interior crossings.
+fn junction(up: Weight, right: Weight, down: Weight, left: Weight) -> char {
+    use Weight::{Heavy as H, Light as L, None as N};
+    match (up, right, down, left) {
+        // Corners.
+        (N, H, H, N) => '┏',
+        (N, N, H, H) => '┓',
+        (H, H, N, N) => '┗',
+        (H, N, N, H) => '┛',
+        // Frame T-junctions (inward arm may be light).
+        (N, H, L, H) => '┯',
+        (N, H, H, H) => '┳',
+        (L, H, N, H) => '┷',
+        (H, H, N, H) => '┻',
+        (H, L, H, N) => '┠',
+        (H, H, H, N) => '┣',
+        (H, N, H, L) => '┨',
+        (H, N, H, H) => '┫',
+        // Interior crossings.
+        (L, L, L, L) => '┼',
+        (L, L, L, H) => '┽',
+        (L, H, L, L) => '┾',
+        (L, H, L, H) => '┿',
+        (H, L, L, L) => '╀',
+        (L, L, H, L) => '╁',
+        (H, L, H, L) => '╂',
+        (H, L, L, H) => '╃',
+        (H, H, L, L) => '╄',
+        (L, L, H, H) => '╅',
+        (L, H, H, L) => '╆',
+        (H, H, L, H) => '╇',
+        (L, H, H, H) => '╈',
+        (H, L, H, H) => '╉',
+        (H, H, H, L) => '╊',
+        (H, H, H, H) => '╋',
+        // Only reachable for n == 0.
+        _ => ' ',
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::puzzle::{Cage, CellId};
+    use crate::rules::Op;
+
+    fn cage(cells: &[u16], op: Op, target: i64) -> Cage {
+        Cage {
+            cells: cells.iter().copied().map(CellId).collect(),
+            op,
+            target,
+        }
+    }
+
+    #[cfg(feature = "format-sgt-desc")]
+    #[test]
+    fn renders_2x2_with_solution() {
+        let puzzle = crate::format::sgt_desc::parse_keen_desc(2, "b__,a3a3").unwrap();
+        let expected = "\
+┏━━━┯━━━┓
+┃3+ │   ┃
+┃ 1 │ 2 ┃
+┣━━━┿━━━┫
+┃3+ │   ┃
+┃ 2 │ 1 ┃
+┗━━━┷━━━┛
+";
+        assert_eq!(render_ascii(&puzzle, Some(&[1, 2, 2, 1])), expected);
+    }
+
+    #[cfg(feature = "format-sgt-desc")]
+    #[test]
+    fn renders_4x4_golden_without_solution() {
+        // Golden corpus "4x4 singleton grid B (cyclic)".
+        let puzzle =
+            crate::format::sgt_desc::parse_keen_desc(4, "_25,a1a2a3a4a2a3a4a1a3a4a1a2a4a1a2a3")
+                .unwrap();
+        let expected = "\
+┏━━━┳━━━┳━━━┳━━━┓
+┃1  ┃2  ┃3  ┃4  ┃
+┃   ┃   ┃   ┃   ┃
+┣━━━╋━━━╋━━━╋━━━┫
+┃2  ┃3  ┃4  ┃1  ┃
+┃   ┃   ┃   ┃   ┃
+┣━━━╋━━━╋━━━╋━━━┫
+┃3  ┃4  ┃1  ┃2  ┃
+┃   ┃   ┃   ┃   ┃
+┣━━━╋━━━╋━━━╋━━━┫
+┃4  ┃1  ┃2  ┃3  ┃
+┃   ┃   ┃   ┃   ┃
+┗━━━┻━━━┻━━━┻━━━┛
+";
+        assert_eq!(render_ascii(&puzzle, None), expected);
+    }
+
+    #[test]
+    fn renders_4x4_mixed_cages_with_wide_clue() {
+        // Solution:
+        // 1 2 3 4
+        // 2 3 4 1
+        // 3 4 1 2
+        // 4 1 2 3
+        let puzzle = Puzzle {
+            n: 4,
+            cages: vec![
+                cage(&[0, 1, 4], Op::Add, 5),
+                cage(&[2, 3, 6, 7], Op::Mul, 48),
+                cage(&[5, 9], Op::Sub, 1),
+                cage(&[8, 12], Op::Sub, 1),
+                cage(&[10, 11, 14, 15], Op::Mul, 12),
+                cage(&[13], Op::Eq, 1),
+            ],
+        };
+        let solution = [1, 2, 3, 4, 2, 3, 4, 1, 3, 4, 1, 2, 4, 1, 2, 3];
+        let expected = "\
+┏━━━━┯━━━━┳━━━━┯━━━━┓
+┃5+  │    ┃48× │    ┃
+┃ 1  │ 2  ┃ 3  │ 4  ┃
+┠────╆━━━━╉────┼────┨
+┃    ┃1-  ┃    │    ┃
+┃ 2  ┃ 3  ┃ 4  │ 1  ┃
+┣━━━━╉────╊━━━━┿━━━━┫
+┃1-  ┃    ┃12× │    ┃
+┃ 3  ┃ 4  ┃ 1  │ 2  ┃
+┠────╊━━━━╉────┼────┨
+┃    ┃1   ┃    │    ┃
+┃ 4  ┃ 1  ┃ 2  │ 3  ┃
+┗━━━━┻━━━━┻━━━━┷━━━━┛
+";
+        assert_eq!(render_ascii(&puzzle, Some(&solution)), expected);
+    }
+
+    #[test]
+    fn wide_grids_keep_columns_aligned() {
+        let n = 16u8;
+        let a = (n as usize) * (n as usize);
+        let puzzle = Puzzle {
+            n,
+            cages: (0..a)
+                .map(|idx| cage(&[idx as u16], Op::Eq, ((idx % 16) + 1) as i64))
+                .collect(),
+        };
+        let solution: Vec<u8> = (0..a).map(|idx| ((idx % 16) + 1) as u8).collect();
+        let rendered = render_ascii(&puzzle, Some(&solution));
+        let widths: Vec<usize> = rendered.lines().map(|l| l.chars().count()).collect();
+        assert_eq!(widths.len(), 3 * 16 + 1);
+        assert!(widths.iter().all(|&w| w == widths[0]));
+        assert!(rendered.contains(" 16 "));
+    }
+}
@@ -1,8 +1,14 @@
+use core::fmt;
+use core::str::FromStr;
+
 #[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
 
+/// Cage operation. The canonical string forms (`Display`/`FromStr`, and serde with the
+/// `serde` feature) are `add`, `mul`, `sub`, `div` and `eq`.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "lowercase"))]
 pub enum Op {
     Add,
     Mul,
@@ -11,12 +17,124 @@ pub enum Op {
     Eq,
 }
 
+/// A string that names no variant of an engine enum (an [`Op`], or a solver tier).
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[error("unknown {kind} {value:?} (expected one of: {expected})")]
+pub struct ParseNameError {
+    /// What was being parsed, e.g. `"op"`.
+    pub kind: &'static str,
+    pub value: String,
+    /// The accepted names, comma-separated.
+    pub expected: &'static str,
+}
+
+impl Op {
+    pub const ALL: [Op; 5] = [Op::Add, Op::Mul, Op::Sub, Op::Div, Op::Eq];
+
+    /// Canonical lowercase name, as accepted by `FromStr`.
+    pub const fn name(self) -> &'static str {
+        match self {
+            Op::Add => "add",
+            Op::Mul => "mul",
+            Op::Sub => "sub",
+            Op::Div => "div",
+            Op::Eq => "eq",
+        }
+    }
+
+    /// Stable numeric code for binary formats (snapshots): `add` = 0 through `eq` = 4.
+    pub const fn code(self) -> u8 {
+        match self {
+            Op::Add => 0,
+            Op::Mul => 1,
+            Op::Sub => 2,
+            Op::Div => 3,
+            Op::Eq => 4,
+        }
+    }
+
+    /// Inverse of [`Op::code`].
+    pub const fn from_code(code: u8) -> Option<Op> {
+        match code {
+            0 => Some(Op::Add),
+            1 => Some(Op::Mul),
+            2 => Some(Op::Sub),
+            3 => Some(Op::Div),
+            4 => Some(Op::Eq),
+            _ => None,
+        }
+    }
+
+    /// Value of a full cage assignment under this op, or `None` if it has none; see
+    /// [`arith::op_value`](crate::arith::op_value).
+    ///
+    /// `Sub` and `Div` use the n-ary convention: the largest value minus the sum of the rest,
+    /// and the largest value divided by each of the rest in turn (`None` unless every division
+    /// is exact). For two cells these reduce to `|a - b|` and `max / min`. `Eq` is defined
+    /// only when every value is equal.
+    pub fn apply(self, values: &[i64]) -> Option<i64> {
+        crate::arith::op_value(self, values)
+    }
+
+    /// True if a full cage assignment `values` meets `target` under this op.
+    pub fn satisfied_by(self, values: &[i64], target: i64) -> bool {
+        crate::arith::op_satisfied(self, target, values)
+    }
+}
+
+impl fmt::Display for Op {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for Op {
+    type Err = ParseNameError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Op::ALL
+            .into_iter()
+            .find(|op| op.name() == s)
+            .ok_or_else(|| ParseNameError {
+                kind: "op",
+                value: s.to_string(),
+                expected: "add, mul, sub, div, eq",
+            })
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Ruleset {
     pub sub_div_two_cell_only: bool,
     pub require_orthogonal_cage_connectivity: bool,
     pub max_cage_size: u8,
+    /// Columns are Latin like rows. Off for the rows-only ("Mathdoku-lite") variant, where a
+    /// digit may repeat down a column; cages and rows are unchanged. Defaults to on, also
+    /// when absent from serialized rulesets written before the knob existed.
+    #[cfg_attr(feature = "serde", serde(default = "enforce_columns_default"))]
+    pub enforce_columns: bool,
+}
+
+#[cfg(feature = "serde")]
+fn enforce_columns_default() -> bool {
+    true
+}
+
+/// Why [`Ruleset::from_descriptor`] rejected a descriptor.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum RulesetDescriptorError {
+    #[error("unknown ruleset flag {0:?}")]
+    UnknownFlag(String),
+
+    #[error("ruleset flag {0:?} given more than once")]
+    DuplicateFlag(String),
+
+    #[error("invalid max cage size {0:?}")]
+    InvalidMaxCageSize(String),
+
+    #[error("ruleset descriptor has no max<N> flag")]
+    MissingMaxCageSize,
 }
 
 impl Ruleset {
@@ -25,6 +143,180 @@ impl Ruleset {
             sub_div_two_cell_only: true,
             require_orthogonal_cage_connectivity: true,
             max_cage_size: 6,
+            enforce_columns: true,
+        }
+    }
+
+    /// Compact textual form: comma-separated flags, in this order when set,
+    /// - `subdiv2`: `sub_div_two_cell_only`,
+    /// - `connected`: `require_orthogonal_cage_connectivity`,
+    /// - `max<N>`: `max_cage_size` (always present),
+    /// - `rowsonly`: `enforce_columns` *off*, so descriptors written before the knob existed
+    ///   keep meaning Latin columns.
+    ///
+    /// The baseline is `subdiv2,connected,max6`.
+    pub fn to_descriptor(&self) -> String {
+        let mut flags = Vec::with_capacity(4);
+        if self.sub_div_two_cell_only {
+            flags.push("subdiv2".to_string());
+        }
+        if self.require_orthogonal_cage_connectivity {
+            flags.push("connected".to_string());
         }
+        flags.push(format!("max{}", self.max_cage_size));
+        if !self.enforce_columns {
+            flags.push("rowsonly".to_string());
+        }
+        flags.join(",")
+    }
+
+    /// Parse a [`Ruleset::to_descriptor`] string. Flags may appear in any order; absent
+    /// boolean flags are off.
+    pub fn from_descriptor(descriptor: &str) -> Result<Self, RulesetDescriptorError> {
+        let mut sub_div = None;
+        let mut connected = None;
+        let mut rows_only = None;
+        let mut max = None;
+        for flag in descriptor.split(',') {
+            let slot = match flag {
+                "subdiv2" => &mut sub_div,
+                "connected" => &mut connected,
+                "rowsonly" => &mut rows_only,
+                _ => match flag.strip_prefix("max") {
+                    Some(digits) => {
+                        let size = digits.parse::<u8>().map_err(|_| {
+                            RulesetDescriptorError::InvalidMaxCageSize(digits.to_string())
+                        })?;
+                        if max.replace(size).is_some() {
+                            return Err(RulesetDescriptorError::DuplicateFlag("max".to_string()));
+                        }
+                        continue;
+                    }
+                    None => return Err(RulesetDescriptorError::UnknownFlag(flag.to_string())),
+                },
+            };
+            if slot.replace(true).is_some() {
+                return Err(RulesetDescriptorError::DuplicateFlag(flag.to_string()));
+            }
+        }
+        Ok(Self {
+            sub_div_two_cell_only: sub_div.unwrap_or(false),
+            require_orthogonal_cage_connectivity: connected.unwrap_or(false),
+            max_cage_size: max.ok_or(RulesetDescriptorError::MissingMaxCageSize)?,
+            enforce_columns: rows_only.is_none(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Op, Ruleset, RulesetDescriptorError};
+
+    #[test]
+    fn two_cell_sub_div_match_pairwise_semantics() {
+        for a in 1..=9i64 {
+            for b in 1..=9i64 {
+                assert_eq!(Op::Sub.apply(&[a, b]), Some((a - b).abs()));
+                let (hi, lo) = (a.max(b), a.min(b));
+                let div = (hi % lo == 0).then_some(hi / lo);
+                assert_eq!(Op::Div.apply(&[a, b]), div, "{a} / {b}");
+            }
+        }
+    }
+
+    #[test]
+    fn n_ary_sub_div_use_largest_value() {
+        assert_eq!(Op::Sub.apply(&[2, 9, 3]), Some(4));
+        assert_eq!(Op::Sub.apply(&[4, 3, 2]), Some(-1));
+        assert_eq!(Op::Div.apply(&[2, 8, 2]), Some(2));
+        assert_eq!(Op::Div.apply(&[3, 6, 2]), Some(1));
+        assert_eq!(Op::Div.apply(&[2, 6, 4]), None);
+        // Repeated maxima: only one copy is the dividend.
+        assert_eq!(Op::Div.apply(&[4, 4, 1]), Some(1));
+        assert!(Op::Sub.satisfied_by(&[6, 1, 2], 3));
+        assert!(!Op::Sub.satisfied_by(&[6, 1, 2], 2));
+    }
+
+    #[test]
+    fn empty_and_eq_assignments() {
+        assert_eq!(Op::Add.apply(&[]), None);
+        assert_eq!(Op::Eq.apply(&[3]), Some(3));
+        assert_eq!(Op::Eq.apply(&[3, 4]), None);
+    }
+
+    #[test]
+    fn op_names_and_codes_round_trip() {
+        for op in Op::ALL {
+            assert_eq!(op.to_string().parse::<Op>(), Ok(op));
+            assert_eq!(Op::from_code(op.code()), Some(op));
+        }
+        assert_eq!(Op::Sub.to_string(), "sub");
+        assert_eq!(Op::from_code(5), None);
+        let err = "Add".parse::<Op>().unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "unknown op \"Add\" (expected one of: add, mul, sub, div, eq)"
+        );
+    }
+
+    #[test]
+    fn ruleset_descriptors_round_trip() {
+        let baseline = Ruleset::keen_baseline();
+        assert_eq!(baseline.to_descriptor(), "subdiv2,connected,max6");
+        let mixed = Ruleset {
+            sub_div_two_cell_only: false,
+            require_orthogonal_cage_connectivity: true,
+            max_cage_size: 9,
+            enforce_columns: true,
+        };
+        assert_eq!(mixed.to_descriptor(), "connected,max9");
+        let rows_only = Ruleset {
+            enforce_columns: false,
+            ..baseline
+        };
+        assert_eq!(rows_only.to_descriptor(), "subdiv2,connected,max6,rowsonly");
+        for rules in [baseline, mixed, rows_only] {
+            assert_eq!(Ruleset::from_descriptor(&rules.to_descriptor()), Ok(rules));
+        }
+        assert_eq!(
+            Ruleset::from_descriptor("max4,subdiv2"),
+            Ok(Ruleset {
+                sub_div_two_cell_only: true,
+                require_orthogonal_cage_connectivity: false,
+                max_cage_size: 4,
+                enforce_columns: true,
+            })
+        );
+        assert_eq!(
+            Ruleset::from_descriptor("rowsonly,max6,subdiv2,connected"),
+            Ok(rows_only)
+        );
+
+        assert_eq!(
+            Ruleset::from_descriptor("connected"),
+            Err(RulesetDescriptorError::MissingMaxCageSize)
+        );
+        assert_eq!(
+            Ruleset::from_descriptor("connected,connected,max6"),
+            Err(RulesetDescriptorError::DuplicateFlag(
+                "connected".to_string()
+            ))
+        );
+        assert_eq!(
+            Ruleset::from_descriptor("max6,rowsonly,rowsonly"),
+            Err(RulesetDescriptorError::DuplicateFlag(
+                "rowsonly".to_string()
+            ))
+        );
+        assert_eq!(
+            Ruleset::from_descriptor("max6,diagonal"),
+            Err(RulesetDescriptorError::UnknownFlag("diagonal".to_string()))
+        );
+        assert_eq!(
+            Ruleset::from_descriptor("max300"),
+            Err(RulesetDescriptorError::InvalidMaxCageSize(
+                "300".to_string()
+            ))
+        );
     }
 }
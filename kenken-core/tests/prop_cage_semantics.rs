@@ -5,6 +5,7 @@
 //! - Cell coordinate roundtrip
 //! - Cage validation invariants
 
+use kenken_core::limits::INLINE_CAGE_CELLS;
 use kenken_core::puzzle::{Cage, CellId, Coord, cell_id, coord};
 use kenken_core::rules::{Op, Ruleset};
 use proptest::prelude::*;
@@ -16,9 +17,9 @@ proptest! {
     fn add_cage_tuples_sum_to_target(
         n in 2u8..=6,
         size in 2usize..=4,
-        target in 2i32..=24,
+        target in 2i64..=24,
     ) {
-        let cells: SmallVec<[CellId; 6]> = (0..size).map(|i| CellId(i as u16)).collect();
+        let cells: SmallVec<[CellId; INLINE_CAGE_CELLS]> = (0..size).map(|i| CellId(i as u16)).collect();
         let cage = Cage {
             cells,
             op: Op::Add,
@@ -28,7 +29,7 @@ proptest! {
         let rules = Ruleset::keen_baseline();
         if let Ok(Some(tuples)) = cage.valid_permutations(n, rules, 10000) {
             for tuple in &tuples {
-                let sum: i32 = tuple.iter().map(|&v| v as i32).sum();
+                let sum: i64 = tuple.iter().map(|&v| v as i64).sum();
                 prop_assert_eq!(sum, target, "Tuple {:?} sum {} != target {}", tuple, sum, target);
             }
         }
@@ -39,9 +40,9 @@ proptest! {
     fn mul_cage_tuples_product_equals_target(
         n in 2u8..=6,
         size in 2usize..=3,
-        target in 1i32..=100,
+        target in 1i64..=100,
     ) {
-        let cells: SmallVec<[CellId; 6]> = (0..size).map(|i| CellId(i as u16)).collect();
+        let cells: SmallVec<[CellId; INLINE_CAGE_CELLS]> = (0..size).map(|i| CellId(i as u16)).collect();
         let cage = Cage {
             cells,
             op: Op::Mul,
@@ -51,7 +52,7 @@ proptest! {
         let rules = Ruleset::keen_baseline();
         if let Ok(Some(tuples)) = cage.valid_permutations(n, rules, 10000) {
             for tuple in &tuples {
-                let prod: i32 = tuple.iter().fold(1, |acc, &v| acc * v as i32);
+                let prod: i64 = tuple.iter().fold(1, |acc, &v| acc * v as i64);
                 prop_assert_eq!(prod, target, "Tuple {:?} product {} != target {}", tuple, prod, target);
             }
         }
@@ -61,9 +62,9 @@ proptest! {
     #[test]
     fn sub_cage_tuples_diff_equals_target(
         n in 2u8..=9,
-        target in 1i32..=8,
+        target in 1i64..=8,
     ) {
-        let cells: SmallVec<[CellId; 6]> = [CellId(0), CellId(1)].into_iter().collect();
+        let cells: SmallVec<[CellId; INLINE_CAGE_CELLS]> = [CellId(0), CellId(1)].into_iter().collect();
         let cage = Cage {
             cells,
             op: Op::Sub,
@@ -74,7 +75,7 @@ proptest! {
         if let Ok(Some(tuples)) = cage.valid_permutations(n, rules, 1000) {
             for tuple in &tuples {
                 prop_assert_eq!(tuple.len(), 2);
-                let diff = (tuple[0] as i32 - tuple[1] as i32).abs();
+                let diff = (tuple[0] as i64 - tuple[1] as i64).abs();
                 prop_assert_eq!(diff, target, "Tuple {:?} diff {} != target {}", tuple, diff, target);
             }
         }
@@ -84,9 +85,9 @@ proptest! {
     #[test]
     fn div_cage_tuples_quotient_equals_target(
         n in 2u8..=9,
-        target in 1i32..=8,
+        target in 1i64..=8,
     ) {
-        let cells: SmallVec<[CellId; 6]> = [CellId(0), CellId(1)].into_iter().collect();
+        let cells: SmallVec<[CellId; INLINE_CAGE_CELLS]> = [CellId(0), CellId(1)].into_iter().collect();
         let cage = Cage {
             cells,
             op: Op::Div,
@@ -103,8 +104,8 @@ proptest! {
                     (tuple[1], tuple[0])
                 };
                 if den != 0 {
-                    let quot = num as i32 / den as i32;
-                    let rem = num as i32 % den as i32;
+                    let quot = num as i64 / den as i64;
+                    let rem = num as i64 % den as i64;
                     prop_assert_eq!(rem, 0, "Tuple {:?} not evenly divisible", tuple);
                     prop_assert_eq!(quot, target, "Tuple {:?} quotient {} != target {}", tuple, quot, target);
                 }
@@ -131,9 +132,9 @@ proptest! {
     fn tuple_values_in_range(
         n in 2u8..=6,
         size in 1usize..=3,
-        target in 1i32..=50,
+        target in 1i64..=50,
     ) {
-        let cells: SmallVec<[CellId; 6]> = (0..size).map(|i| CellId(i as u16)).collect();
+        let cells: SmallVec<[CellId; INLINE_CAGE_CELLS]> = (0..size).map(|i| CellId(i as u16)).collect();
         let op = if size == 1 { Op::Eq } else { Op::Add };
         let cage = Cage { cells, op, target };
 
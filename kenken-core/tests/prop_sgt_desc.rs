@@ -0,0 +1,204 @@
+//! Property-based tests for the sgt desc parser on arbitrary input.
+//!
+//! These tests verify:
+//! - Neither parser (upstream or extended) panics, whatever the characters or run lengths
+//! - A repeat count past the grid's edge budget is rejected before it is expanded
+//! - The syntax check accepts every desc that parses, and rejects every desc whose parse
+//!   fails on syntax
+
+use kenken_core::format::sgt_desc::{
+    SgtDescError, encode_keen_desc, parse_keen_desc, parse_keen_desc_ext, validate_keen_desc_syntax,
+};
+use kenken_core::rules::{Op, Ruleset};
+use kenken_core::{Cage, CellId, CoreError, Puzzle};
+use proptest::prelude::*;
+
+/// Strings over the desc alphabet, biased towards long digit runs and repeat counts.
+fn desc_like() -> impl Strategy<Value = String> {
+    let token = prop_oneof![
+        3 => "[_a-z]",
+        2 => "[0-9]{1,3}",
+        1 => "[0-9]{15,40}",
+        1 => "[,amsde\\[\\]-]",
+    ];
+    prop::collection::vec(token, 0..40).prop_map(|tokens| tokens.concat())
+}
+
+/// Valid descs: row-strip cages of up to six cells, each joined to its right neighbour per
+/// `joins`, with ops and targets picked from `seeds`.
+fn valid_desc() -> impl Strategy<Value = (u8, String)> {
+    (2u8..=9).prop_flat_map(|n| {
+        let cells = n as usize * n as usize;
+        (
+            Just(n),
+            prop::collection::vec(any::<bool>(), cells),
+            prop::collection::vec(any::<u16>(), cells),
+        )
+            .prop_map(|(n, joins, seeds)| {
+                let w = n as usize;
+                let mut strips: Vec<Vec<usize>> = Vec::new();
+                for (cell, &join) in joins.iter().enumerate() {
+                    match strips.last_mut() {
+                        Some(strip) if cell % w != 0 && join && strip.len() < 6 => strip.push(cell),
+                        _ => strips.push(vec![cell]),
+                    }
+                }
+                let cages = strips
+                    .into_iter()
+                    .map(|strip| {
+                        let seed = seeds[strip[0]];
+                        let (op, target) = match strip.len() {
+                            1 => (Op::Eq, i64::from(seed % u16::from(n)) + 1),
+                            2 => {
+                                let ops = [Op::Add, Op::Mul, Op::Sub, Op::Div];
+                                (ops[usize::from(seed % 4)], i64::from(seed % 30) + 1)
+                            }
+                            _ => (
+                                [Op::Add, Op::Mul][usize::from(seed % 2)],
+                                i64::from(seed) + 1,
+                            ),
+                        };
+                        Cage {
+                            cells: strip.into_iter().map(|c| CellId(c as u16)).collect(),
+                            op,
+                            target,
+                        }
+                    })
+                    .collect();
+                let puzzle = Puzzle { n, cages };
+                (
+                    n,
+                    encode_keen_desc(&puzzle, Ruleset::keen_baseline()).unwrap(),
+                )
+            })
+    })
+}
+
+/// How a desc is corrupted: truncated, or one character replaced, inserted or removed.
+#[derive(Debug, Clone)]
+enum Mutation {
+    Truncate(prop::sample::Index),
+    Replace(prop::sample::Index, char),
+    Insert(prop::sample::Index, char),
+    Remove(prop::sample::Index),
+}
+
+fn mutation() -> impl Strategy<Value = Mutation> {
+    let ch = prop::sample::select("_abmsdeyz0123456789,-!".chars().collect::<Vec<_>>());
+    prop_oneof![
+        any::<prop::sample::Index>().prop_map(Mutation::Truncate),
+        (any::<prop::sample::Index>(), ch.clone()).prop_map(|(at, ch)| Mutation::Replace(at, ch)),
+        (any::<prop::sample::Index>(), ch).prop_map(|(at, ch)| Mutation::Insert(at, ch)),
+        any::<prop::sample::Index>().prop_map(Mutation::Remove),
+    ]
+}
+
+fn mutate(desc: &str, mutations: &[Mutation]) -> String {
+    let mut chars: Vec<char> = desc.chars().collect();
+    for mutation in mutations {
+        let len = chars.len().max(1);
+        match *mutation {
+            Mutation::Truncate(at) => chars.truncate(at.index(len)),
+            Mutation::Replace(at, ch) if !chars.is_empty() => chars[at.index(len)] = ch,
+            Mutation::Insert(at, ch) => chars.insert(at.index(len + 1).min(chars.len()), ch),
+            Mutation::Remove(at) if !chars.is_empty() => {
+                chars.remove(at.index(len));
+            }
+            _ => {}
+        }
+    }
+    chars.into_iter().collect()
+}
+
+/// Whether a parse failure is one of syntax, which the syntax check must catch without the
+/// cages: the rest (clue counts, cage sizes, target ranges, puzzle validation) need them.
+fn is_syntax_failure(err: &SgtDescError) -> bool {
+    match err {
+        SgtDescError::MissingComma { .. }
+        | SgtDescError::InvalidBlockChar { .. }
+        | SgtDescError::BlockTooMuchData { .. }
+        | SgtDescError::BlockNotEnoughData { .. }
+        | SgtDescError::RepeatCountTooLarge { .. }
+        | SgtDescError::NumberTooLong { .. }
+        | SgtDescError::ClueTypeUnknown { .. }
+        | SgtDescError::InvalidTarget { .. }
+        | SgtDescError::ClueSyntax { .. }
+        | SgtDescError::Core(CoreError::InvalidGridSize(_)) => true,
+        SgtDescError::CluesTooFew { .. }
+        | SgtDescError::CluesTooMany { .. }
+        | SgtDescError::SubDivMustBeTwoCell { .. }
+        | SgtDescError::TargetNotPositive { .. }
+        | SgtDescError::GivenOutOfRange { .. }
+        | SgtDescError::Core(_) => false,
+        SgtDescError::UnknownGridSize
+        | SgtDescError::RulesetHeaderUnterminated
+        | SgtDescError::InvalidRulesetHeader(_) => {
+            unreachable!("not raised by parse_keen_desc: {err:?}")
+        }
+    }
+}
+
+/// The syntax check agrees with the full parse: it accepts what parses, with one cage per
+/// clue, and rejects what fails on syntax. Semantic failures may pass it.
+fn check_syntax_agrees(n: u8, desc: &str) -> Result<(), TestCaseError> {
+    let syntax = validate_keen_desc_syntax(n, desc);
+    match parse_keen_desc(n, desc) {
+        Ok(puzzle) => {
+            let info = syntax.map_err(|err| TestCaseError::fail(format!("{desc}: {err}")))?;
+            prop_assert_eq!(info.cages, puzzle.cages.len(), "{}", desc);
+        }
+        Err(err) if is_syntax_failure(&err) => {
+            prop_assert!(
+                syntax.is_err(),
+                "{} passed the syntax check: {:?}",
+                desc,
+                err
+            );
+        }
+        Err(_) => {}
+    }
+    Ok(())
+}
+
+proptest! {
+    #[test]
+    fn valid_descs_pass_the_syntax_check((n, desc) in valid_desc()) {
+        prop_assert!(parse_keen_desc(n, &desc).is_ok(), "{}", desc);
+        check_syntax_agrees(n, &desc)?;
+    }
+
+    #[test]
+    fn the_syntax_check_catches_every_syntax_failure(
+        (n, desc) in valid_desc(),
+        mutations in prop::collection::vec(mutation(), 1..4),
+    ) {
+        check_syntax_agrees(n, &mutate(&desc, &mutations))?;
+    }
+
+    #[test]
+    fn the_syntax_check_agrees_on_arbitrary_input(n in 2u8..=16, desc in desc_like()) {
+        check_syntax_agrees(n, &desc)?;
+    }
+
+    #[test]
+    fn parser_never_panics(n in 2u8..=16, desc in desc_like()) {
+        let _ = parse_keen_desc(n, &desc);
+        let _ = parse_keen_desc_ext(n, &desc);
+    }
+
+    #[test]
+    fn oversized_repeat_counts_are_rejected_up_front(
+        n in 2u8..=16,
+        run in "[_a-z]",
+        excess in 1u64..=u64::MAX / 2,
+    ) {
+        // Edge slots: internal edges plus the closing one.
+        let slots = 2 * n as u64 * (n as u64 - 1) + 1;
+        let desc = format!("{run}{},a1", slots.saturating_add(excess));
+        let is_too_large = matches!(
+            parse_keen_desc(n, &desc),
+            Err(SgtDescError::RepeatCountTooLarge { offset: 1, .. })
+        );
+        prop_assert!(is_too_large, "{}", desc);
+    }
+}
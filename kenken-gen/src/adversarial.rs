@@ -0,0 +1,367 @@
+//! Puzzles built to stress the solver's worst cases, for budget, cancellation and
+//! enumeration-cap tests and for benchmarks.
+//!
+//! Every constructor is deterministic, builds its cages around a known Latin square (so that
+//! square is always a solution) and validates the result against the ruleset it returns, which
+//! raises `max_cage_size` where the construction needs it.
+
+use kenken_core::arith::target_for;
+use kenken_core::limits::INLINE_CAGE_CELLS;
+use kenken_core::rules::{Op, Ruleset};
+use kenken_core::{Cage, CellId, Puzzle};
+use kenken_solver::{DeductionTier, ResumableOutcome, count_solutions_resumable};
+use rand::Rng;
+use rand::seq::SliceRandom;
+use smallvec::SmallVec;
+
+use crate::GenError;
+use crate::seed::rng_from_u64;
+use crate::versioned::v1::{assign_ops_and_targets, attempt_seed, random_cage_partition};
+
+/// An adversarial puzzle, the ruleset it validates under and one of its solutions.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Adversarial {
+    pub puzzle: Puzzle,
+    pub rules: Ruleset,
+    /// Row-major solution the cage targets were derived from.
+    pub solution: Vec<u8>,
+}
+
+/// Result of [`deep_backtrack_puzzle`]: the costliest candidate and what it cost.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DeepBacktrack {
+    pub adversarial: Adversarial,
+    /// Seed of the winning candidate, which [`deep_backtrack_candidate`] rebuilds.
+    pub candidate_seed: u64,
+    /// Search nodes a [`DEEP_BACKTRACK_TIER`] uniqueness count spent on it, capped at
+    /// [`DEEP_BACKTRACK_NODE_BUDGET`].
+    pub nodes_visited: u64,
+}
+
+/// Candidates [`deep_backtrack_puzzle`] tries per call.
+pub const DEEP_BACKTRACK_CANDIDATES: u32 = 16;
+
+/// Search nodes each candidate may spend before it is scored at the cap.
+pub const DEEP_BACKTRACK_NODE_BUDGET: u64 = 20_000;
+
+/// Tier the candidates are scored at: the cheapest propagation that still prunes, so node
+/// counts reflect branching rather than a missing deduction.
+pub const DEEP_BACKTRACK_TIER: DeductionTier = DeductionTier::Easy;
+
+/// The cyclic Latin square `((row + col) % n) + 1`, row-major.
+pub fn cyclic_latin(n: u8) -> Vec<u8> {
+    let n = n as usize;
+    (0..n * n)
+        .map(|idx| ((idx / n + idx % n) % n + 1) as u8)
+        .collect()
+}
+
+/// Every cell a singleton `Eq` cage: no search at all, so timings measure fixed overhead.
+pub fn singleton_grid(n: u8) -> Result<Adversarial, GenError> {
+    let solution = cyclic_latin(n);
+    let cages = (0..solution.len()).map(|idx| vec![idx]).collect::<Vec<_>>();
+    build(n, solution, &cages, |_| Op::Eq, Ruleset::keen_baseline())
+}
+
+/// The grid read as a boustrophedon (left to right, then right to left on the next row) and
+/// cut into `Add` cages of `cage_len` cells; a shorter last run keeps its length, and a
+/// single leftover cell is an `Eq` cage.
+///
+/// Each cage bends across row ends whenever `cage_len` does not divide `n`, so no cage lies
+/// in one unit and `Add` propagation only bounds digits by the cage total. Expect long tuple
+/// enumerations per cage and many solutions when `cage_len` is large.
+pub fn snake_cage_puzzle(n: u8, cage_len: usize) -> Result<Adversarial, GenError> {
+    let n_usize = n as usize;
+    let path: Vec<usize> = (0..n_usize)
+        .flat_map(|row| {
+            (0..n_usize).map(move |k| {
+                let col = if row % 2 == 0 { k } else { n_usize - 1 - k };
+                row * n_usize + col
+            })
+        })
+        .collect();
+    let cages: Vec<Vec<usize>> = if cage_len == 0 {
+        vec![Vec::new()]
+    } else {
+        path.chunks(cage_len).map(<[usize]>::to_vec).collect()
+    };
+    let rules = Ruleset {
+        max_cage_size: max_cage_size(cage_len.max(1)),
+        ..Ruleset::keen_baseline()
+    };
+    build(n, cyclic_latin(n), &cages, add_or_eq, rules)
+}
+
+/// The first `n * n / 2` cells in reading order as one `Add` cage and the rest as another.
+///
+/// The second cage's target is implied by the first (every grid sums to `n * n * (n + 1) / 2`),
+/// so the puzzle is a near-empty Latin square constrained by a single sum: tuple enumeration
+/// for either cage is out of reach past tiny `n`, and the solution count grows with the
+/// number of Latin squares. The 3x3 instance has 4 solutions.
+pub fn two_big_add_cages(n: u8) -> Result<Adversarial, GenError> {
+    let a = n as usize * n as usize;
+    let half = a / 2;
+    let cages = vec![(0..half).collect::<Vec<_>>(), (half..a).collect()];
+    let rules = Ruleset {
+        max_cage_size: max_cage_size(a - half),
+        ..Ruleset::keen_baseline()
+    };
+    build(n, cyclic_latin(n), &cages, add_or_eq, rules)
+}
+
+/// One `Add` cage per row, each with target `n * (n + 1) / 2`.
+///
+/// Every clue holds for every row, so the solutions are exactly the Latin squares of order
+/// `n` (2, 12, 576 and 161280 for `n` = 2..=5) and the puzzle is invariant under any row,
+/// column or symbol permutation: no deduction fires and a full count enumerates all of them.
+pub fn symmetric_multisolution(n: u8) -> Result<Adversarial, GenError> {
+    let n_usize = n as usize;
+    let cages: Vec<Vec<usize>> = (0..n_usize)
+        .map(|row| (row * n_usize..(row + 1) * n_usize).collect())
+        .collect();
+    let rules = Ruleset {
+        max_cage_size: max_cage_size(n_usize),
+        ..Ruleset::keen_baseline()
+    };
+    build(n, cyclic_latin(n), &cages, add_or_eq, rules)
+}
+
+/// The candidate among [`DEEP_BACKTRACK_CANDIDATES`] seeded from `seed` whose uniqueness
+/// count (limit 2, at [`DEEP_BACKTRACK_TIER`]) visits the most search nodes, each count
+/// stopped at [`DEEP_BACKTRACK_NODE_BUDGET`]. Ties go to the earliest candidate.
+///
+/// Candidates look like ordinary generated puzzles (see [`deep_backtrack_candidate`]), which
+/// is the point: they stress the search rather than the propagators. They need not be
+/// unique. Fails only if no candidate could be built.
+pub fn deep_backtrack_puzzle(n: u8, seed: u64) -> Result<DeepBacktrack, GenError> {
+    let mut best: Option<DeepBacktrack> = None;
+    let mut last_err = None;
+    for attempt in 0..DEEP_BACKTRACK_CANDIDATES {
+        let candidate_seed = attempt_seed(seed, attempt);
+        let adversarial = match deep_backtrack_candidate(n, candidate_seed) {
+            Ok(Some(adversarial)) => adversarial,
+            Ok(None) => continue,
+            Err(e) => {
+                last_err = Some(e);
+                continue;
+            }
+        };
+        let nodes_visited = budgeted_nodes(&adversarial)?;
+        if best
+            .as_ref()
+            .is_none_or(|b| nodes_visited > b.nodes_visited)
+        {
+            best = Some(DeepBacktrack {
+                adversarial,
+                candidate_seed,
+                nodes_visited,
+            });
+        }
+    }
+    best.ok_or_else(|| {
+        last_err.unwrap_or(GenError::AttemptsExhausted {
+            attempts: DEEP_BACKTRACK_CANDIDATES,
+        })
+    })
+}
+
+/// One [`deep_backtrack_puzzle`] candidate: a cyclic Latin square under seeded row, column
+/// and symbol permutations, partitioned and clued the way the V1 generator does. `Ok(None)`
+/// when the seeded partition strands a cell.
+pub fn deep_backtrack_candidate(n: u8, seed: u64) -> Result<Option<Adversarial>, GenError> {
+    kenken_core::puzzle::validate_grid_size(n)?;
+    let rules = Ruleset::keen_baseline();
+    let mut rng = rng_from_u64(seed);
+    let solution = permuted(n, &cyclic_latin(n), &mut rng);
+    let Some(cages) = random_cage_partition(n, rules, 0.5, &mut rng) else {
+        return Ok(None);
+    };
+    let puzzle = assign_ops_and_targets(n, &solution, &cages, rules, &mut rng)?;
+    puzzle.validate(rules)?;
+    Ok(Some(Adversarial {
+        puzzle,
+        rules,
+        solution,
+    }))
+}
+
+/// Nodes a limit-2 count spends on `adversarial`, stopping at the budget.
+fn budgeted_nodes(adversarial: &Adversarial) -> Result<u64, GenError> {
+    let outcome = count_solutions_resumable(
+        &adversarial.puzzle,
+        adversarial.rules,
+        DEEP_BACKTRACK_TIER,
+        2,
+        None,
+        DEEP_BACKTRACK_NODE_BUDGET,
+    )?;
+    Ok(match outcome {
+        ResumableOutcome::Complete { stats, .. } => stats.nodes_visited,
+        ResumableOutcome::Suspended(checkpoint) => checkpoint.stats.nodes_visited,
+    })
+}
+
+fn permuted<R: Rng + ?Sized>(n: u8, grid: &[u8], rng: &mut R) -> Vec<u8> {
+    let n = n as usize;
+    let mut rows: Vec<usize> = (0..n).collect();
+    let mut cols: Vec<usize> = (0..n).collect();
+    let mut symbols: Vec<u8> = (1..=n as u8).collect();
+    rows.shuffle(rng);
+    cols.shuffle(rng);
+    symbols.shuffle(rng);
+    (0..n * n)
+        .map(|idx| symbols[grid[rows[idx / n] * n + cols[idx % n]] as usize - 1])
+        .collect()
+}
+
+fn add_or_eq(len: usize) -> Op {
+    if len == 1 { Op::Eq } else { Op::Add }
+}
+
+/// `len` as a ruleset cage cap; oversized cages keep the largest cap and fail validation.
+fn max_cage_size(len: usize) -> u8 {
+    len.clamp(6, u8::MAX as usize) as u8
+}
+
+/// Cages over `cells` (stored sorted) with `op_for(len)` and targets taken from `solution`,
+/// validated.
+fn build(
+    n: u8,
+    solution: Vec<u8>,
+    cages: &[Vec<usize>],
+    op_for: impl Fn(usize) -> Op,
+    rules: Ruleset,
+) -> Result<Adversarial, GenError> {
+    let cages = cages
+        .iter()
+        .map(|cells| {
+            let op = op_for(cells.len());
+            let values: Vec<u8> = cells.iter().map(|&idx| solution[idx]).collect();
+            let mut cells: SmallVec<[CellId; INLINE_CAGE_CELLS]> =
+                cells.iter().map(|&idx| CellId(idx as u16)).collect();
+            cells.sort_unstable();
+            Cage {
+                cells,
+                op,
+                target: target_for(op, &values).unwrap_or(0),
+            }
+        })
+        .collect();
+    let puzzle = Puzzle { n, cages };
+    puzzle.validate(rules)?;
+    Ok(Adversarial {
+        puzzle,
+        rules,
+        solution,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use kenken_solver::count_solutions_up_to;
+
+    use super::*;
+
+    fn count(adversarial: &Adversarial, limit: u32) -> u32 {
+        count_solutions_up_to(&adversarial.puzzle, adversarial.rules, limit).unwrap()
+    }
+
+    fn cage_lens(adversarial: &Adversarial) -> Vec<usize> {
+        adversarial
+            .puzzle
+            .cages
+            .iter()
+            .map(|c| c.cells.len())
+            .collect()
+    }
+
+    #[test]
+    fn every_constructor_keeps_its_solution() {
+        for n in 2..=6 {
+            for adversarial in [
+                singleton_grid(n).unwrap(),
+                snake_cage_puzzle(n, 3).unwrap(),
+                two_big_add_cages(n).unwrap(),
+                symmetric_multisolution(n).unwrap(),
+            ] {
+                adversarial
+                    .puzzle
+                    .check_solution(&adversarial.solution)
+                    .unwrap();
+            }
+        }
+    }
+
+    #[test]
+    fn snake_cages_follow_the_boustrophedon() {
+        let snake = snake_cage_puzzle(4, 3).unwrap();
+        assert_eq!(cage_lens(&snake), [3, 3, 3, 3, 3, 1]);
+        // The second cage runs off row 0 and back along row 1.
+        let second: Vec<u16> = snake.puzzle.cages[1].cells.iter().map(|c| c.0).collect();
+        assert_eq!(second, [3, 6, 7]);
+        assert_eq!(snake.puzzle.cages[5].op, Op::Eq);
+        assert!(snake.puzzle.cages[..5].iter().all(|c| c.op == Op::Add));
+
+        let long = snake_cage_puzzle(6, 10).unwrap();
+        assert_eq!(cage_lens(&long), [10, 10, 10, 6]);
+        assert_eq!(long.rules.max_cage_size, 10);
+        assert_eq!(count(&long, 2), 2);
+
+        assert!(matches!(
+            snake_cage_puzzle(4, 0),
+            Err(GenError::Core(kenken_core::CoreError::EmptyCage))
+        ));
+    }
+
+    #[test]
+    fn two_big_add_cages_halve_the_grid() {
+        let big = two_big_add_cages(5).unwrap();
+        assert_eq!(cage_lens(&big), [12, 13]);
+        assert_eq!(big.rules.max_cage_size, 13);
+        let sums: Vec<i64> = big.puzzle.cages.iter().map(|c| c.target).collect();
+        assert_eq!(sums.iter().sum::<i64>(), 75);
+        assert_eq!(count(&two_big_add_cages(3).unwrap(), 100), 4);
+    }
+
+    #[test]
+    fn symmetric_multisolution_counts_every_latin_square() {
+        for (n, latin_squares) in [(2, 2), (3, 12), (4, 576)] {
+            let symmetric = symmetric_multisolution(n).unwrap();
+            assert_eq!(cage_lens(&symmetric), vec![n as usize; n as usize]);
+            assert_eq!(count(&symmetric, 1000), latin_squares, "n={n}");
+        }
+    }
+
+    #[test]
+    fn singleton_grid_is_fully_given() {
+        let given = singleton_grid(5).unwrap();
+        assert_eq!(cage_lens(&given), [1; 25]);
+        assert_eq!(count(&given, 2), 1);
+    }
+
+    #[test]
+    fn deep_backtrack_picks_the_costliest_candidate_within_budget() {
+        let deep = deep_backtrack_puzzle(6, 7).unwrap();
+        assert!(deep.nodes_visited <= DEEP_BACKTRACK_NODE_BUDGET);
+        assert_eq!(
+            deep_backtrack_candidate(6, deep.candidate_seed).unwrap(),
+            Some(deep.adversarial.clone())
+        );
+        deep.adversarial
+            .puzzle
+            .check_solution(&deep.adversarial.solution)
+            .unwrap();
+
+        for attempt in 0..DEEP_BACKTRACK_CANDIDATES {
+            let Some(candidate) = deep_backtrack_candidate(6, attempt_seed(7, attempt)).unwrap()
+            else {
+                continue;
+            };
+            let nodes = budgeted_nodes(&candidate).unwrap();
+            assert!(nodes <= DEEP_BACKTRACK_NODE_BUDGET);
+            assert!(nodes <= deep.nodes_visited, "candidate {attempt}: {nodes}");
+        }
+        // Same seed, same puzzle.
+        assert_eq!(deep_backtrack_puzzle(6, 7).unwrap(), deep);
+    }
+}
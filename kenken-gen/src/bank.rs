@@ -0,0 +1,359 @@
+//! In-memory puzzle bank with filtered, deterministic sampling.
+//!
+//! Entries are grouped into buckets by `(n, difficulty)`. Sampling first picks one of the
+//! buckets that still has an eligible entry, all equally likely, then an entry within it, so
+//! a difficulty with few puzzles is served as often as one with many until it runs out.
+
+use std::collections::HashSet;
+
+use kenken_core::Puzzle;
+use kenken_core::format::sgt_desc::encode_keen_desc;
+use kenken_core::rules::Ruleset;
+use kenken_solver::DifficultyTier;
+use rand::Rng;
+
+use crate::GenError;
+use crate::generator::GeneratedPuzzleWithStats;
+
+/// One puzzle in a [`PuzzleBank`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BankEntry {
+    pub n: u8,
+    pub difficulty: DifficultyTier,
+    /// The puzzle's sgt desc, which is the same for any cage order, so equal puzzles share a
+    /// key. Used for deduplication and for excluding already-served puzzles.
+    pub key: String,
+    pub puzzle: Puzzle,
+    pub solution: Vec<u8>,
+}
+
+impl BankEntry {
+    pub fn new(
+        puzzle: Puzzle,
+        solution: Vec<u8>,
+        difficulty: DifficultyTier,
+        rules: Ruleset,
+    ) -> Result<Self, GenError> {
+        Ok(Self {
+            n: puzzle.n,
+            difficulty,
+            key: encode_keen_desc(&puzzle, rules)?,
+            puzzle,
+            solution,
+        })
+    }
+
+    pub fn from_generated(
+        generated: &GeneratedPuzzleWithStats,
+        rules: Ruleset,
+    ) -> Result<Self, GenError> {
+        Self::new(
+            generated.puzzle.clone(),
+            generated.solution.clone(),
+            generated.difficulty,
+            rules,
+        )
+    }
+}
+
+/// Which entries [`PuzzleBank::sample`] may return. The default accepts every entry.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct BankFilter<'a> {
+    /// Only this grid size.
+    pub n: Option<u8>,
+    /// Only these difficulties; empty accepts any.
+    pub difficulties: &'a [DifficultyTier],
+    /// Skip entries whose key is in this set, e.g. puzzles already served this session.
+    pub exclude: Option<&'a HashSet<String>>,
+}
+
+impl BankFilter<'_> {
+    fn accepts_bucket(&self, n: u8, difficulty: DifficultyTier) -> bool {
+        self.n.is_none_or(|want| want == n)
+            && (self.difficulties.is_empty() || self.difficulties.contains(&difficulty))
+    }
+
+    fn accepts_key(&self, key: &str) -> bool {
+        self.exclude.is_none_or(|served| !served.contains(key))
+    }
+}
+
+/// Entry count of one `(n, difficulty)` bucket, as reported by [`PuzzleBank::stats`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BucketStats {
+    pub n: u8,
+    pub difficulty: DifficultyTier,
+    pub count: usize,
+}
+
+#[derive(Debug, Clone)]
+struct Bucket {
+    n: u8,
+    difficulty: DifficultyTier,
+    /// Indices into `PuzzleBank::entries`, in insertion order.
+    entries: Vec<usize>,
+}
+
+/// Puzzle bank indexed by grid size and difficulty.
+///
+/// Sampling is a pure function of the bank's insertion order, the filter and the RNG state,
+/// so a session seeded with [`crate::seed::rng_from_u64`] replays exactly.
+#[derive(Debug, Clone, Default)]
+pub struct PuzzleBank {
+    entries: Vec<BankEntry>,
+    /// Sorted by `(n, difficulty)`, so bucket choice does not depend on insertion order.
+    buckets: Vec<Bucket>,
+    keys: HashSet<String>,
+}
+
+impl PuzzleBank {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add `entry`; returns `false` (and drops it) if an entry with the same key exists.
+    pub fn insert(&mut self, entry: BankEntry) -> bool {
+        if !self.keys.insert(entry.key.clone()) {
+            return false;
+        }
+        let sort_key = (entry.n, tier_rank(entry.difficulty));
+        let pos = match self
+            .buckets
+            .binary_search_by_key(&sort_key, |b| (b.n, tier_rank(b.difficulty)))
+        {
+            Ok(pos) => pos,
+            Err(pos) => {
+                self.buckets.insert(
+                    pos,
+                    Bucket {
+                        n: entry.n,
+                        difficulty: entry.difficulty,
+                        entries: Vec::new(),
+                    },
+                );
+                pos
+            }
+        };
+        self.buckets[pos].entries.push(self.entries.len());
+        self.entries.push(entry);
+        true
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn entries(&self) -> &[BankEntry] {
+        &self.entries
+    }
+
+    pub fn get(&self, key: &str) -> Option<&BankEntry> {
+        self.entries.iter().find(|e| e.key == key)
+    }
+
+    /// A random entry passing `filter`, or `None` if there is none.
+    ///
+    /// Every bucket with an eligible entry is equally likely, then every eligible entry in the
+    /// chosen bucket. Scans the whole bank, which is linear in its size.
+    pub fn sample<R: Rng + ?Sized>(&self, rng: &mut R, filter: &BankFilter) -> Option<&BankEntry> {
+        let eligible: Vec<Vec<usize>> = self
+            .buckets
+            .iter()
+            .filter(|b| filter.accepts_bucket(b.n, b.difficulty))
+            .map(|b| {
+                b.entries
+                    .iter()
+                    .copied()
+                    .filter(|&idx| filter.accepts_key(&self.entries[idx].key))
+                    .collect::<Vec<_>>()
+            })
+            .filter(|entries| !entries.is_empty())
+            .collect();
+        if eligible.is_empty() {
+            return None;
+        }
+        let bucket = &eligible[rng.random_range(0..eligible.len())];
+        Some(&self.entries[bucket[rng.random_range(0..bucket.len())]])
+    }
+
+    /// Entry counts per non-empty `(n, difficulty)` bucket, ordered by `n` then difficulty.
+    pub fn stats(&self) -> Vec<BucketStats> {
+        self.stats_excluding(&HashSet::new())
+    }
+
+    /// Like [`Self::stats`], counting only entries whose key is not in `served`, i.e. what
+    /// is left to serve. Buckets that are fully served are reported with count 0.
+    pub fn stats_excluding(&self, served: &HashSet<String>) -> Vec<BucketStats> {
+        self.buckets
+            .iter()
+            .map(|b| BucketStats {
+                n: b.n,
+                difficulty: b.difficulty,
+                count: b
+                    .entries
+                    .iter()
+                    .filter(|&&idx| !served.contains(&self.entries[idx].key))
+                    .count(),
+            })
+            .collect()
+    }
+}
+
+impl Extend<BankEntry> for PuzzleBank {
+    fn extend<I: IntoIterator<Item = BankEntry>>(&mut self, iter: I) {
+        for entry in iter {
+            self.insert(entry);
+        }
+    }
+}
+
+impl FromIterator<BankEntry> for PuzzleBank {
+    fn from_iter<I: IntoIterator<Item = BankEntry>>(iter: I) -> Self {
+        let mut bank = Self::new();
+        bank.extend(iter);
+        bank
+    }
+}
+
+fn tier_rank(tier: DifficultyTier) -> usize {
+    DifficultyTier::ALL
+        .iter()
+        .position(|&t| t == tier)
+        .expect("ALL lists every tier")
+}
+
+#[cfg(all(test, feature = "gen-dlx"))]
+mod tests {
+    use super::*;
+    use crate::generator::{GenerateConfig, generate_with_stats};
+    use crate::seed::rng_from_u64;
+
+    fn generated_bank() -> PuzzleBank {
+        let rules = Ruleset::keen_baseline();
+        let mut bank = PuzzleBank::new();
+        for (n, seeds) in [(3u8, 0..6u64), (4, 0..10)] {
+            for seed in seeds {
+                let cfg = GenerateConfig {
+                    max_attempts: 1_000,
+                    ..GenerateConfig::keen_baseline(n, seed)
+                };
+                let generated = generate_with_stats(cfg).unwrap();
+                bank.insert(BankEntry::from_generated(&generated, rules).unwrap());
+            }
+        }
+        bank
+    }
+
+    #[test]
+    fn stats_cover_every_entry_once() {
+        let bank = generated_bank();
+        let stats = bank.stats();
+        assert_eq!(stats.iter().map(|s| s.count).sum::<usize>(), bank.len());
+        for s in &stats {
+            let in_bucket = bank
+                .entries()
+                .iter()
+                .filter(|e| e.n == s.n && e.difficulty == s.difficulty)
+                .count();
+            assert_eq!(s.count, in_bucket, "{s:?}");
+        }
+        assert!(
+            stats.windows(2).all(
+                |w| (w[0].n, tier_rank(w[0].difficulty)) < (w[1].n, tier_rank(w[1].difficulty))
+            )
+        );
+    }
+
+    #[test]
+    fn duplicate_keys_are_dropped() {
+        let mut bank = generated_bank();
+        let before = bank.len();
+        let again = bank.entries()[0].clone();
+        assert!(!bank.insert(again));
+        assert_eq!(bank.len(), before);
+    }
+
+    #[test]
+    fn samples_respect_size_and_difficulty_filters() {
+        let bank = generated_bank();
+        let mut rng = rng_from_u64(7);
+        let wanted = [bank.entries().last().unwrap().difficulty];
+        let filter = BankFilter {
+            n: Some(4),
+            difficulties: &wanted,
+            exclude: None,
+        };
+        for _ in 0..50 {
+            let e = bank.sample(&mut rng, &filter).unwrap();
+            assert_eq!((e.n, e.difficulty), (4, wanted[0]));
+        }
+
+        let missing = BankFilter {
+            n: Some(5),
+            ..BankFilter::default()
+        };
+        assert!(bank.sample(&mut rng, &missing).is_none());
+    }
+
+    #[test]
+    fn excluding_served_keys_drains_the_bank_without_repeats() {
+        let bank = generated_bank();
+        let mut rng = rng_from_u64(11);
+        let mut served = HashSet::new();
+        while let Some(e) = bank.sample(
+            &mut rng,
+            &BankFilter {
+                exclude: Some(&served),
+                ..BankFilter::default()
+            },
+        ) {
+            assert!(served.insert(e.key.clone()), "served {} twice", e.key);
+        }
+        assert_eq!(served.len(), bank.len());
+        assert!(bank.stats_excluding(&served).iter().all(|s| s.count == 0));
+    }
+
+    #[test]
+    fn small_buckets_are_not_starved() {
+        let bank = generated_bank();
+        let buckets = bank.stats();
+        let smallest = buckets.iter().min_by_key(|s| s.count).unwrap();
+        let mut rng = rng_from_u64(3);
+        let draws = 2_000;
+        let hits = (0..draws)
+            .filter(|_| {
+                let e = bank.sample(&mut rng, &BankFilter::default()).unwrap();
+                (e.n, e.difficulty) == (smallest.n, smallest.difficulty)
+            })
+            .count();
+        // Each bucket gets about draws / buckets, whatever its size.
+        let expected = draws / buckets.len();
+        assert!(hits * 2 > expected, "{hits} of {draws} for {smallest:?}");
+    }
+
+    #[test]
+    fn same_seed_replays_the_same_sequence() {
+        let bank = generated_bank();
+        let session = |seed: u64| {
+            let mut rng = rng_from_u64(seed);
+            let mut served = HashSet::new();
+            let mut keys = Vec::new();
+            for _ in 0..8 {
+                let filter = BankFilter {
+                    exclude: Some(&served),
+                    ..BankFilter::default()
+                };
+                let key = bank.sample(&mut rng, &filter).unwrap().key.clone();
+                served.insert(key.clone());
+                keys.push(key);
+            }
+            keys
+        };
+        assert_eq!(session(5), session(5));
+        assert_ne!(session(5), session(6));
+    }
+}
@@ -0,0 +1,204 @@
+//! Daily sets: three puzzles of rising difficulty derived from a date seed alone, so every
+//! device produces the same set for the same day without a server.
+//!
+//! # Stability
+//!
+//! A set is a function of `(date_seed, n)` and this crate's release: the slot seeds, budgets,
+//! op retries and acceptance rules below, and the [`crate::generate_with_stats`] pipeline they feed, which
+//! here draws partitions and first op assignments from one sequential stream per slot, as it
+//! did when the sets were pinned. Golden tests pin the descs of a few sets, and like the
+//! [`crate::versioned`] goldens they must not be updated to match new output; a change that
+//! moves them needs a new entry point.
+
+use kenken_core::format::sgt_desc::encode_keen_desc;
+use kenken_core::rules::Ruleset;
+use kenken_solver::DifficultyTier;
+
+use crate::GenError;
+use crate::generator::{
+    GenerateConfig, GeneratedPuzzleWithStats, difficulty_ordinal, generate_with_sequential_streams,
+};
+
+/// Target tier of each slot of a daily set, in order.
+pub const DAILY_TARGETS: [DifficultyTier; 3] = [
+    DifficultyTier::Easy,
+    DifficultyTier::Normal,
+    DifficultyTier::Hard,
+];
+
+/// Attempts a slot gets at its exact target tier before falling back to tolerance 1.
+pub const DAILY_EXACT_ATTEMPTS: u32 = 2_000;
+
+/// Attempts a slot gets at tolerance 1, once its exact budget is spent.
+pub const DAILY_FALLBACK_ATTEMPTS: u32 = 20_000;
+
+/// Op assignments each slot tries per partition: the generator default when the sets were
+/// pinned, kept here so a change to that default cannot move them.
+const DAILY_OPS_RETRIES: u32 = 4;
+
+/// Times a slot is regenerated under a bumped seed when its puzzle repeats an earlier slot's
+/// or falls below its difficulty, before the set gives up.
+pub const DAILY_MAX_BUMPS: u32 = 8;
+
+/// Generate the daily set for `date_seed`: an Easy, a Normal and a Hard `n`x`n` puzzle.
+///
+/// Each slot has its own seed derived from `date_seed`, so slots are independent draws. A slot
+/// first looks for its exact tier within [`DAILY_EXACT_ATTEMPTS`], then accepts a neighbouring
+/// tier within [`DAILY_FALLBACK_ATTEMPTS`]; the tolerance it ended up with is its
+/// [`GeneratedPuzzleWithStats::difficulty_tolerance`]. A fallback puzzle that repeats an
+/// earlier slot (same sgt desc) or is easier than it is regenerated under a bumped seed, so the
+/// three puzzles are distinct and their difficulties never decrease.
+///
+/// # Errors
+/// [`GenError::AttemptsExhaustedWithStats`] when a slot finds nothing within either budget, and
+/// [`GenError::AttemptsExhausted`] (counting the slot's draws) when its bumps run out.
+pub fn generate_daily_set(
+    date_seed: u64,
+    n: u8,
+) -> Result<[GeneratedPuzzleWithStats; 3], GenError> {
+    daily_set_with_budget(date_seed, n, DAILY_EXACT_ATTEMPTS)
+}
+
+/// [`generate_daily_set`] with `exact_attempts` in place of [`DAILY_EXACT_ATTEMPTS`].
+fn daily_set_with_budget(
+    date_seed: u64,
+    n: u8,
+    exact_attempts: u32,
+) -> Result<[GeneratedPuzzleWithStats; 3], GenError> {
+    let rules = Ruleset::keen_baseline();
+    let mut set: Vec<GeneratedPuzzleWithStats> = Vec::with_capacity(DAILY_TARGETS.len());
+    let mut keys: Vec<String> = Vec::with_capacity(DAILY_TARGETS.len());
+    for (slot, target) in DAILY_TARGETS.into_iter().enumerate() {
+        let mut accepted = None;
+        for bump in 0..=DAILY_MAX_BUMPS {
+            let seed = slot_seed(date_seed, slot, bump);
+            let generated = generate_slot(n, seed, target, exact_attempts)?;
+            let key = encode_keen_desc(&generated.puzzle, rules)?;
+            let easier = set.last().is_some_and(|earlier| {
+                difficulty_ordinal(generated.difficulty) < difficulty_ordinal(earlier.difficulty)
+            });
+            if !easier && !keys.contains(&key) {
+                accepted = Some((key, generated));
+                break;
+            }
+        }
+        let (key, generated) = accepted.ok_or(GenError::AttemptsExhausted {
+            attempts: DAILY_MAX_BUMPS + 1,
+        })?;
+        keys.push(key);
+        set.push(generated);
+    }
+    Ok(set.try_into().expect("one puzzle per slot"))
+}
+
+/// One slot at `target`: exact tier for `exact_attempts`, then tolerance 1.
+fn generate_slot(
+    n: u8,
+    seed: u64,
+    target: DifficultyTier,
+    exact_attempts: u32,
+) -> Result<GeneratedPuzzleWithStats, GenError> {
+    let exact = GenerateConfig {
+        max_attempts: exact_attempts,
+        ops_retries_per_partition: DAILY_OPS_RETRIES,
+        ..GenerateConfig::with_difficulty(n, seed, target)
+    };
+    match generate_with_sequential_streams(exact) {
+        Err(GenError::AttemptsExhaustedWithStats { .. }) => {
+            generate_with_sequential_streams(GenerateConfig {
+                max_attempts: DAILY_FALLBACK_ATTEMPTS,
+                difficulty_tolerance: 1,
+                ..exact
+            })
+        }
+        result => result,
+    }
+}
+
+/// Seed of `slot`'s `bump`th draw: SplitMix64's finalizer over the date seed, offset per slot
+/// and bump so no two draws of any day share a seed stream in practice.
+fn slot_seed(date_seed: u64, slot: usize, bump: u32) -> u64 {
+    let mut z = date_seed
+        .wrapping_add((slot as u64 + 1).wrapping_mul(0x9E37_79B9_7F4A_7C15))
+        .wrapping_add(u64::from(bump).wrapping_mul(0xD1B5_4A32_D194_9B2F));
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^ (z >> 31)
+}
+
+#[cfg(all(test, feature = "gen-dlx"))]
+mod tests {
+    use super::*;
+    use crate::seed::rng_from_u64;
+    use rand::Rng;
+
+    /// Distinct descs, difficulties that never decrease, and each slot within the tolerance it
+    /// records of its target.
+    fn assert_well_formed(set: &[GeneratedPuzzleWithStats; 3], label: &str) {
+        let rules = Ruleset::keen_baseline();
+        let descs: Vec<String> = set
+            .iter()
+            .map(|g| encode_keen_desc(&g.puzzle, rules).unwrap())
+            .collect();
+        for (slot, (generated, target)) in set.iter().zip(DAILY_TARGETS).enumerate() {
+            assert!(generated.difficulty_tolerance <= 1, "{label} slot {slot}");
+            let distance =
+                difficulty_ordinal(generated.difficulty).abs_diff(difficulty_ordinal(target));
+            assert!(
+                distance <= generated.difficulty_tolerance,
+                "{label} slot {slot}: {:?} for {target:?} at tolerance {}",
+                generated.difficulty,
+                generated.difficulty_tolerance
+            );
+            for earlier in 0..slot {
+                assert_ne!(
+                    descs[earlier], descs[slot],
+                    "{label}: slots {earlier} and {slot}"
+                );
+                assert!(
+                    difficulty_ordinal(set[earlier].difficulty)
+                        <= difficulty_ordinal(generated.difficulty),
+                    "{label}: slot {slot} is easier than slot {earlier}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn random_date_seeds_give_ordered_distinct_sets() {
+        let mut rng = rng_from_u64(0xDA11);
+        for _ in 0..20 {
+            let date_seed: u64 = rng.random();
+            let set = generate_daily_set(date_seed, 5).unwrap();
+            assert_well_formed(&set, &format!("date seed {date_seed:#x}"));
+        }
+    }
+
+    #[test]
+    fn sets_replay_from_the_date_seed() {
+        let puzzles = |set: [GeneratedPuzzleWithStats; 3]| set.map(|g| g.puzzle);
+        assert_eq!(
+            puzzles(generate_daily_set(7, 4).unwrap()),
+            puzzles(generate_daily_set(7, 4).unwrap())
+        );
+    }
+
+    #[test]
+    fn spent_exact_budgets_fall_back_to_tolerance_one() {
+        for date_seed in 0..5 {
+            let set = daily_set_with_budget(date_seed, 4, 0).unwrap();
+            assert!(set.iter().all(|g| g.difficulty_tolerance == 1));
+            assert_well_formed(&set, &format!("date seed {date_seed}"));
+        }
+    }
+
+    #[test]
+    fn slot_seeds_differ_per_slot_and_bump() {
+        let mut seeds: Vec<u64> = (0..3)
+            .flat_map(|slot| (0..=DAILY_MAX_BUMPS).map(move |bump| slot_seed(1, slot, bump)))
+            .collect();
+        seeds.sort_unstable();
+        seeds.dedup();
+        assert_eq!(seeds.len(), 3 * (DAILY_MAX_BUMPS as usize + 1));
+    }
+}
@@ -1,17 +1,34 @@
 #![allow(clippy::needless_range_loop)]
 
+use std::time::{Duration, Instant};
+
+use kenken_core::arith::{applicable_ops, target_for};
+use kenken_core::limits::INLINE_CAGE_CELLS;
+use kenken_core::puzzle::validate_grid_size;
 use kenken_core::rules::{Op, Ruleset};
 use kenken_core::{Cage, CellId, Puzzle};
 use kenken_solver::{
-    DeductionTier, DifficultyTier, TierRequiredResult, classify_difficulty_from_tier,
-    classify_tier_required, count_solutions_up_to_with_deductions,
+    CountStrategy, DeductionTier, DifficultyTier, SearchOptions, TierRequiredResult,
+    TupleCacheHandle, classify_difficulty_from_tier, classify_tier_required,
+    count_solutions_up_to_with_strategy, estimate_difficulty, search_footprint, solve_other_than,
 };
-use rand::Rng;
-use rand::seq::SliceRandom;
-use smallvec::SmallVec;
 
 use crate::GenError;
+use crate::partition::{PartitionFilter, PartitionMetrics};
 use crate::seed::rng_from_u64;
+// The default pipeline shares V1's steps until an improvement replaces one.
+pub use crate::versioned::v1::partition_constraint_score;
+use crate::versioned::v1::{
+    assign_ops_and_targets, attempt_seed, ops_retry_rng, random_cage_partition, too_loose,
+    two_cell_ops,
+};
+#[cfg(feature = "gen-dlx")]
+use crate::versioned::v1::{least_latin_square, permute_latin};
+use crate::versioned::{GenAlgoVersion, generate_versioned};
+use rand::Rng;
+use rand::seq::SliceRandom;
+use rand_chacha::ChaCha20Rng;
+use smallvec::SmallVec;
 
 #[cfg(feature = "telemetry-tracing")]
 use tracing::trace;
@@ -21,6 +38,22 @@ macro_rules! trace {
     ($($tt:tt)*) => {};
 }
 
+/// `rng` (a `&mut` RNG) as the given pipeline stage draws from it: under `rng-audit`, an
+/// [`AuditedRng`](crate::rng_audit::AuditedRng) counting its draws, otherwise `rng` itself.
+#[cfg(feature = "rng-audit")]
+macro_rules! stage_rng {
+    ($rng:expr, $stage:ident) => {
+        &mut crate::rng_audit::AuditedRng::new($rng, crate::rng_audit::RngStage::$stage)
+    };
+}
+
+#[cfg(not(feature = "rng-audit"))]
+macro_rules! stage_rng {
+    ($rng:expr, $stage:ident) => {
+        $rng
+    };
+}
+
 /// Configuration for puzzle generation.
 #[derive(Debug, Clone, Copy)]
 pub struct GenerateConfig {
@@ -32,8 +65,17 @@ pub struct GenerateConfig {
     pub rules: Ruleset,
     /// Deduction tier for uniqueness verification.
     pub tier: DeductionTier,
-    /// Maximum generation attempts before giving up.
+    /// Maximum generation attempts before giving up. Each attempt draws one Latin square and
+    /// cage partition.
     pub max_attempts: u32,
+    /// Op/target assignments tried on each attempt's partition before drawing a new one.
+    /// `1` gives every partition a single assignment; `0` is treated as `1`. Default `4`.
+    /// Retries stop early once a second solution holds the same digits in every cage (no
+    /// assignment could rule it out; see [`GenAttemptStats::inseparable_partitions`]), and an
+    /// assignment that still admits a second solution found earlier is rejected without a
+    /// uniqueness check. Each retry draws from its own RNG sub-stream, so neither shortcut
+    /// changes the puzzle generated. [`generate`] ignores it and always tries four.
+    pub ops_retries_per_partition: u32,
     /// Probability of creating 2-cell cages (dominoes) during partitioning.
     pub domino_probability: f64,
     /// Target difficulty tier (None = accept any unique puzzle).
@@ -41,8 +83,148 @@ pub struct GenerateConfig {
     /// Difficulty tolerance: allow tiers within +/- this range.
     /// E.g., tolerance=1 with target=Normal accepts Easy/Normal/Hard.
     pub difficulty_tolerance: u8,
+    /// With `target_difficulty` set, reject unique candidates whose
+    /// [`estimate_difficulty`] is confidently (at least [`PREFILTER_MIN_CONFIDENCE`]) outside
+    /// the target tolerance, skipping their full classification. Trades a few puzzles the
+    /// estimate gets wrong for far fewer `classify_tier_required` calls.
+    pub prefilter: bool,
+    /// Reject op assignments whose [`partition_constraint_score`] exceeds this before their
+    /// uniqueness count. Loose cages make multiple solutions likely, so this skips the counts
+    /// that would most likely reject anyway. `None` disables the check.
+    pub max_constraint_score: Option<f64>,
+    /// Reject random partitions whose [`PartitionMetrics`] break this filter before any ops
+    /// are assigned to them. Read by [`generate_with_stats`] only: [`generate`] runs the frozen
+    /// V1 pipeline, and layouts passed to [`generate_for_layout`] are used as given.
+    pub partition_filter: Option<PartitionFilter>,
+    /// Share one [`TupleCacheHandle`] across every uniqueness count of a
+    /// [`generate_with_stats`] run, so cages repeated between candidates (same shape and clue)
+    /// are enumerated once. Counts and puzzles are unchanged; hits and misses are reported in
+    /// [`GenAttemptStats`]. [`generate`] ignores it.
+    pub shared_tuple_cache: bool,
+    /// Order each uniqueness count of a [`generate_with_stats`] run to diverge from the
+    /// attempt's Latin square ([`kenken_solver::solve_other_than`], the search of
+    /// [`CountStrategy::DivergeFrom`]), so a near-unique candidate's second solution turns up
+    /// early. Counts and puzzles are unchanged; [`GenAttemptStats::uniqueness_nodes`] drops,
+    /// and the second solutions found let op retries skip futile checks (see
+    /// `ops_retries_per_partition`). [`generate`] always diverges.
+    pub diverge_from_solution: bool,
+    /// Cap clue targets per op. A cage whose drawn op would exceed its cap takes another op
+    /// that fits; when none does, the partition is rejected
+    /// ([`GenAttemptStats::clue_bound_rejections`]). Read by [`generate_with_stats`] and
+    /// [`generate_for_layout_with_stats`] only; `None` leaves targets unbounded.
+    pub max_target: Option<ClueBounds>,
+    /// Decide uniqueness with [`kenken_solver::uniqueness_portfolio`], racing the native
+    /// count against the SAT encoding, instead of the native count alone. Puzzles are
+    /// unchanged, but [`GenAttemptStats::uniqueness_nodes`] no longer counts those checks.
+    /// Needs the `uniqueness-portfolio` feature ([`GenError::PortfolioRequired`] otherwise);
+    /// [`generate`] ignores it.
+    pub uniqueness_portfolio: bool,
+    /// Fewest cages of each op a puzzle must have. An op assignment that falls short re-clues
+    /// cages whose digits allow a missing op (within `max_target`), preferring two-cell cages
+    /// and never dropping another op below its own minimum; a partition that still falls short
+    /// is rejected before its uniqueness count ([`GenAttemptStats::op_count_rejections`]).
+    /// For 5x5 with one `Div` and one `Sub`, this costs fewer than [`OP_COUNT_ATTEMPT_FACTOR`]
+    /// times the attempts of unconstrained generation. Read by [`generate_with_stats`] and
+    /// [`generate_for_layout_with_stats`] only; `None` puts no minimum on any op.
+    pub min_op_counts: Option<OpCounts>,
+    /// Bytes generation may hold at once, for memory-constrained targets. Below
+    /// [`min_memory_budget`] every entry point fails with [`GenError::MemoryBudgetExceeded`]
+    /// before its first attempt; above it, the rest bounds the shared tuple cache
+    /// ([`TupleCacheHandle::with_byte_limit`]). Puzzles are unchanged. The Latin square draw
+    /// permutes the least Latin square in place and never builds a DLX matrix
+    /// ([`kenken_solver::dlx_latin::matrix_footprint`]), so no draw needs a fallback. `None`
+    /// sets no budget.
+    pub memory_budget: Option<usize>,
+}
+
+/// Largest target a clue may show, per op, to keep generated puzzles human-friendly (a
+/// five-cell `2160x` is valid but unpleasant). Sub, Div and Eq targets never exceed `n`, so
+/// they are not capped.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ClueBounds {
+    pub add_max: i64,
+    pub mul_max: i64,
+}
+
+impl ClueBounds {
+    /// No cap on any op.
+    pub const UNBOUNDED: Self = Self {
+        add_max: i64::MAX,
+        mul_max: i64::MAX,
+    };
+
+    /// Whether a clue `op` with `target` is within the caps.
+    pub fn allows(&self, op: Op, target: i64) -> bool {
+        match op {
+            Op::Add => target <= self.add_max,
+            Op::Mul => target <= self.mul_max,
+            Op::Sub | Op::Div | Op::Eq => true,
+        }
+    }
+}
+
+impl Default for ClueBounds {
+    fn default() -> Self {
+        Self::UNBOUNDED
+    }
+}
+
+/// Cage count per op, as a puzzle's op histogram or as the minimums of
+/// [`GenerateConfig::min_op_counts`].
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct OpCounts {
+    pub add: u32,
+    pub mul: u32,
+    pub sub: u32,
+    pub div: u32,
+    pub eq: u32,
+}
+
+impl OpCounts {
+    /// The op histogram of `puzzle`'s cages.
+    pub fn of(puzzle: &Puzzle) -> Self {
+        let mut counts = Self::default();
+        for cage in &puzzle.cages {
+            *counts.get_mut(cage.op) += 1;
+        }
+        counts
+    }
+
+    /// Count for `op`.
+    pub fn get(&self, op: Op) -> u32 {
+        match op {
+            Op::Add => self.add,
+            Op::Mul => self.mul,
+            Op::Sub => self.sub,
+            Op::Div => self.div,
+            Op::Eq => self.eq,
+        }
+    }
+
+    fn get_mut(&mut self, op: Op) -> &mut u32 {
+        match op {
+            Op::Add => &mut self.add,
+            Op::Mul => &mut self.mul,
+            Op::Sub => &mut self.sub,
+            Op::Div => &mut self.div,
+            Op::Eq => &mut self.eq,
+        }
+    }
+
+    /// Whether every op's count is at least its count in `min`.
+    pub fn meets(&self, min: &OpCounts) -> bool {
+        Op::ALL.into_iter().all(|op| self.get(op) >= min.get(op))
+    }
 }
 
+/// Upper bound on the attempt inflation [`GenerateConfig::min_op_counts`] costs for 5x5 with
+/// one `Div` and one `Sub`: total attempts over a batch of seeds, against the same seeds
+/// unconstrained.
+pub const OP_COUNT_ATTEMPT_FACTOR: f64 = 1.5;
+
+/// Estimate confidence at which [`GenerateConfig::prefilter`] trusts an off-target estimate.
+pub const PREFILTER_MIN_CONFIDENCE: f32 = 0.8;
+
 impl GenerateConfig {
     pub fn keen_baseline(n: u8, seed: u64) -> Self {
         Self {
@@ -51,9 +233,19 @@ impl GenerateConfig {
             rules: Ruleset::keen_baseline(),
             tier: DeductionTier::Hard,
             max_attempts: 10_000,
+            ops_retries_per_partition: 4,
             domino_probability: 0.55,
             target_difficulty: None,
             difficulty_tolerance: 0,
+            prefilter: false,
+            max_constraint_score: None,
+            partition_filter: None,
+            shared_tuple_cache: true,
+            diverge_from_solution: true,
+            max_target: None,
+            uniqueness_portfolio: false,
+            min_op_counts: None,
+            memory_budget: None,
         }
     }
 
@@ -65,9 +257,19 @@ impl GenerateConfig {
             rules: Ruleset::keen_baseline(),
             tier: DeductionTier::Hard,
             max_attempts: 50_000, // More attempts needed for targeting
+            ops_retries_per_partition: 4,
             domino_probability: 0.55,
             target_difficulty: Some(target),
             difficulty_tolerance: 0,
+            prefilter: false,
+            max_constraint_score: None,
+            partition_filter: None,
+            shared_tuple_cache: true,
+            diverge_from_solution: true,
+            max_target: None,
+            uniqueness_portfolio: false,
+            min_op_counts: None,
+            memory_budget: None,
         }
     }
 }
@@ -90,316 +292,703 @@ pub struct GeneratedPuzzleWithStats {
     pub difficulty: DifficultyTier,
     /// Tier classification result with solve statistics.
     pub tier_result: TierRequiredResult,
-    /// Number of generation attempts before accepting this puzzle.
+    /// Number of generation attempts (partitions drawn) up to and including the accepted one.
     pub attempts: u32,
+    /// Work done and rejections seen before this puzzle was accepted.
+    pub attempt_stats: GenAttemptStats,
+    /// The `difficulty_tolerance` the puzzle was accepted under. [`crate::generate_daily_set`]
+    /// raises it past the config's for a slot whose exact-tier budget ran out.
+    pub difficulty_tolerance: u8,
 }
 
-#[cfg(feature = "gen-dlx")]
-fn latin_solution_seeded(n: u8, seed: u64) -> Result<Vec<u8>, GenError> {
-    use kenken_solver::dlx_latin::solve_latin_one;
+/// Per-reason rejection counters accumulated across generation attempts.
+///
+/// Useful for tuning `GenerateConfig` (e.g. `domino_probability`) when generation
+/// is slow or exhausts its attempt budget. A partition can be retried with several op
+/// assignments, so every counter after `op_count_rejections` counts op assignments,
+/// not attempts.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct GenAttemptStats {
+    /// Attempts (Latin square plus cage partition) drawn, including the accepted one.
+    pub partitions_tried: u32,
+    /// Op/target assignments tried across all partitions, including the accepted one.
+    pub op_assignments_tried: u32,
+    /// Attempts where the random cage partition could not cover the grid.
+    pub partition_failures: u32,
+    /// Attempts whose partition failed `GenerateConfig::partition_filter`; no ops were
+    /// assigned to these.
+    pub partition_filter_rejections: u32,
+    /// Attempts with a cage no op could clue within `GenerateConfig::max_target`.
+    pub clue_bound_rejections: u32,
+    /// Attempts whose ops could not be steered to `GenerateConfig::min_op_counts`.
+    pub op_count_rejections: u32,
+    /// Op assignments whose cages failed `Puzzle::validate`.
+    pub validation_failures: u32,
+    /// Op assignments rejected because the puzzle had more than one solution.
+    pub multi_solution_rejections: u32,
+    /// Partitions abandoned with op retries left because a second solution held the same
+    /// digits in every cage: each target depends only on its cage's digits, so no op
+    /// assignment could rule that solution out. Its multi-solution rejection is counted above.
+    pub inseparable_partitions: u32,
+    /// Op assignments rejected because the puzzle had no solution (should not happen,
+    /// since clues are derived from a Latin square).
+    pub zero_solution_rejections: u32,
+    /// Op assignments rejected because the unique puzzle's difficulty estimate was
+    /// confidently off target (`GenerateConfig::prefilter`); these are never classified.
+    pub prefilter_rejections: u32,
+    /// Op assignments rejected for a constraint score above
+    /// `GenerateConfig::max_constraint_score`; these are never counted.
+    pub constraint_score_rejections: u32,
+    /// Op assignments rejected because the unique puzzle missed the target difficulty.
+    pub difficulty_mismatches: u32,
+    /// Realized difficulty of each mismatched attempt, indexed by ordinal
+    /// (Easy=0, Normal=1, Hard=2, Extreme=3, Unreasonable=4).
+    pub difficulty_histogram: [u32; 5],
+    /// Uniqueness counts run: one per op assignment that got past every cheaper rejection.
+    pub uniqueness_checks: u32,
+    /// Total search nodes spent in uniqueness checks. Grids up to 4x4 are checked against the
+    /// [`kenken_solver::small_n`] tables instead of searched, and add none.
+    pub uniqueness_nodes: u64,
+    /// Cage deductions in uniqueness checks answered by the run's shared tuple cache
+    /// (`GenerateConfig::shared_tuple_cache`).
+    pub tuple_cache_hits: u64,
+    /// Cage deductions in uniqueness checks that had to fill the shared tuple cache.
+    pub tuple_cache_misses: u64,
+    /// Generation used a caller-supplied cage layout ([`generate_for_layout`]):
+    /// `partitions_tried` then counts Latin squares drawn for that layout, and
+    /// `partition_failures` stays 0.
+    pub fixed_layout: bool,
+}
+
+impl GenAttemptStats {
+    /// Total number of rejections across all reasons: failed partitions plus rejected op
+    /// assignments.
+    pub fn total_rejections(&self) -> u32 {
+        self.partition_failures
+            + self.partition_filter_rejections
+            + self.clue_bound_rejections
+            + self.op_count_rejections
+            + self.validation_failures
+            + self.multi_solution_rejections
+            + self.zero_solution_rejections
+            + self.prefilter_rejections
+            + self.constraint_score_rejections
+            + self.difficulty_mismatches
+    }
 
-    let a = (n as usize) * (n as usize);
-    let base =
-        solve_latin_one(n, &vec![0u8; a]).ok_or(GenError::AttemptsExhausted { attempts: 1 })?;
+    /// Add `other`'s counters to these, as for several generations reported together;
+    /// `fixed_layout` is set if either side used a fixed layout.
+    pub fn merge(&mut self, other: &GenAttemptStats) {
+        self.partitions_tried += other.partitions_tried;
+        self.op_assignments_tried += other.op_assignments_tried;
+        self.partition_failures += other.partition_failures;
+        self.partition_filter_rejections += other.partition_filter_rejections;
+        self.clue_bound_rejections += other.clue_bound_rejections;
+        self.op_count_rejections += other.op_count_rejections;
+        self.validation_failures += other.validation_failures;
+        self.multi_solution_rejections += other.multi_solution_rejections;
+        self.inseparable_partitions += other.inseparable_partitions;
+        self.zero_solution_rejections += other.zero_solution_rejections;
+        self.prefilter_rejections += other.prefilter_rejections;
+        self.constraint_score_rejections += other.constraint_score_rejections;
+        self.difficulty_mismatches += other.difficulty_mismatches;
+        for (total, &count) in self
+            .difficulty_histogram
+            .iter_mut()
+            .zip(&other.difficulty_histogram)
+        {
+            *total += count;
+        }
+        self.uniqueness_checks += other.uniqueness_checks;
+        self.uniqueness_nodes += other.uniqueness_nodes;
+        self.tuple_cache_hits += other.tuple_cache_hits;
+        self.tuple_cache_misses += other.tuple_cache_misses;
+        self.fixed_layout |= other.fixed_layout;
+    }
+
+    /// Number of difficulty mismatches whose realized difficulty was `tier`.
+    pub fn mismatches_for(&self, tier: DifficultyTier) -> u32 {
+        self.difficulty_histogram[difficulty_ordinal(tier) as usize]
+    }
 
-    // DLX returns a deterministic “first” solution; add variety via group actions
-    // (row/col/symbol permutations) under a deterministic RNG stream.
-    let mut rng = rng_from_u64(seed);
-    Ok(permute_latin(n, &base, &mut rng))
+    fn record_mismatch(&mut self, tier: DifficultyTier) {
+        self.difficulty_mismatches += 1;
+        self.difficulty_histogram[difficulty_ordinal(tier) as usize] += 1;
+    }
 }
 
-#[cfg(not(feature = "gen-dlx"))]
-fn latin_solution_seeded(_n: u8, _seed: u64) -> Result<Vec<u8>, GenError> {
-    Err(GenError::DlxRequired)
+/// Smallest [`GenerateConfig::memory_budget`] generation accepts for `n`: by estimate, what an
+/// attempt holds besides tuple caches. That is its Latin square, a partition and puzzle of at
+/// most one cage per cell, and a uniqueness count's search ([`search_footprint`]).
+pub fn min_memory_budget(n: u8) -> usize {
+    use std::mem::size_of;
+    let cells = n as usize * n as usize;
+    let cages = cells * (size_of::<Vec<CellId>>() + size_of::<CellId>() + size_of::<Cage>());
+    cells + cages + search_footprint(n)
 }
 
-#[cfg(feature = "gen-dlx")]
-fn permute_latin<R: Rng + ?Sized>(n: u8, grid: &[u8], rng: &mut R) -> Vec<u8> {
-    let n_usize = n as usize;
-    let a = n_usize * n_usize;
-    assert_eq!(grid.len(), a);
+/// Bytes `config.memory_budget` leaves for tuple caches, `None` without a budget.
+pub(crate) fn cache_budget(config: &GenerateConfig) -> Result<Option<usize>, GenError> {
+    let Some(budget) = config.memory_budget else {
+        return Ok(None);
+    };
+    let needed = min_memory_budget(config.n);
+    if budget < needed {
+        return Err(GenError::MemoryBudgetExceeded {
+            n: config.n,
+            needed,
+            budget,
+        });
+    }
+    Ok(Some(budget - needed))
+}
 
-    let mut rows: Vec<usize> = (0..n_usize).collect();
-    let mut cols: Vec<usize> = (0..n_usize).collect();
-    rows.shuffle(rng);
-    cols.shuffle(rng);
+/// Options for an attempt's uniqueness counts: the shared tuple cache when enabled, bounded by
+/// [`cache_budget`].
+fn count_options(config: &GenerateConfig) -> Result<SearchOptions, GenError> {
+    let cache_budget = cache_budget(config)?;
+    let tuple_cache = config.shared_tuple_cache.then(|| {
+        cache_budget.map_or_else(TupleCacheHandle::new, TupleCacheHandle::with_byte_limit)
+    });
+    Ok(SearchOptions {
+        tuple_cache,
+        ..SearchOptions::default()
+    })
+}
 
-    let mut syms: Vec<u8> = (1..=n).collect();
-    syms.shuffle(rng);
-    let mut map = vec![0u8; n_usize + 1];
-    for (from0, &to) in syms.iter().enumerate() {
-        map[from0 + 1] = to;
-    }
+/// Generate a unique puzzle with the latest algorithm, [`GenAlgoVersion::LATEST`].
+///
+/// The puzzle for a given config may change when the algorithm improves; use
+/// [`generate_versioned`] to pin a version.
+pub fn generate(config: GenerateConfig) -> Result<GeneratedPuzzle, GenError> {
+    generate_versioned(config, GenAlgoVersion::LATEST)
+}
 
-    let mut out = vec![0u8; a];
-    for r in 0..n_usize {
-        for c in 0..n_usize {
-            let v = grid[rows[r] * n_usize + cols[c]];
-            out[r * n_usize + c] = map[v as usize];
-        }
-    }
-    out
+/// Generate a puzzle with full difficulty classification.
+///
+/// This is the preferred API when you need to know or control the puzzle difficulty.
+/// Unlike `generate()`, this always classifies the puzzle and can optionally
+/// filter by target difficulty.
+///
+/// # Arguments
+/// * `config` - Generation configuration, optionally with `target_difficulty` set
+///
+/// # Returns
+/// * `Ok(GeneratedPuzzleWithStats)` - A unique puzzle with difficulty classification
+/// * `Err(GenError)` - If no suitable puzzle found within max_attempts; exhaustion is
+///   reported as `GenError::AttemptsExhaustedWithStats` with per-reason rejection counts
+pub fn generate_with_stats(config: GenerateConfig) -> Result<GeneratedPuzzleWithStats, GenError> {
+    generate_classified(config, None, None)
 }
 
-fn neighbors(n: usize, idx: usize) -> [Option<usize>; 4] {
-    let row = idx / n;
-    let col = idx % n;
-    [
-        (row > 0).then(|| (row - 1) * n + col),
-        (row + 1 < n).then(|| (row + 1) * n + col),
-        (col > 0).then(|| row * n + (col - 1)),
-        (col + 1 < n).then(|| row * n + (col + 1)),
-    ]
+/// [`generate_with_stats`] under a wall-clock budget, returning the best unique puzzle found
+/// when `deadline` runs out instead of holding out for an exact difficulty match.
+///
+/// Unique candidates that miss the target are kept, ranked by difficulty distance to
+/// `target_difficulty` and then by fewer cages. A candidate within `difficulty_tolerance` (or
+/// the first unique one, without a target) is returned at once, like [`generate_with_stats`].
+/// The deadline is checked between attempts, so a slow uniqueness check can overrun it.
+///
+/// The returned puzzle is always unique. `attempts` is the attempt that found it, while
+/// `attempt_stats` covers all work up to the return, including the returned candidate as a
+/// difficulty mismatch when it misses the target. Which candidate comes back depends on timing,
+/// so unlike the other entry points the result is not a function of the config alone.
+///
+/// # Errors
+/// [`GenError::DeadlineExceeded`] when the deadline passes before any unique puzzle is found,
+/// or [`GenError::AttemptsExhaustedWithStats`] when `max_attempts` runs out first.
+pub fn generate_anytime(
+    config: GenerateConfig,
+    deadline: Duration,
+) -> Result<GeneratedPuzzleWithStats, GenError> {
+    generate_classified(config, None, Some(Instant::now() + deadline))
 }
 
-fn random_cage_partition<R: Rng + ?Sized>(
+/// Generate a unique puzzle on a fixed cage layout, choosing only the Latin square, ops and
+/// targets. See [`generate_for_layout_with_stats`].
+pub fn generate_for_layout(
+    layout: &[SmallVec<[CellId; INLINE_CAGE_CELLS]>],
     n: u8,
-    rules: Ruleset,
-    domino_probability: f64,
-    rng: &mut R,
-) -> Option<Vec<SmallVec<[CellId; 6]>>> {
-    let n_usize = n as usize;
-    let a = n_usize * n_usize;
-    let max_size = rules.max_cage_size as usize;
-
-    let mut cages: Vec<SmallVec<[CellId; 6]>> = (0..a)
-        .map(|i| {
-            let mut v = SmallVec::new();
-            v.push(CellId(i as u16));
-            v
+    config: &GenerateConfig,
+) -> Result<GeneratedPuzzle, GenError> {
+    let generated = generate_for_layout_with_stats(layout, n, config)?;
+    Ok(GeneratedPuzzle {
+        puzzle: generated.puzzle,
+        solution: generated.solution,
+    })
+}
+
+/// [`generate_with_stats`] on a hand-drawn cage layout: each attempt draws a Latin square and
+/// assigns ops and targets to `layout`'s cages, with the same uniqueness check and difficulty
+/// targeting. `n` overrides `config.n`, and `domino_probability` is unused.
+///
+/// The layout is checked up front against `config.rules` (every cell covered once, cages
+/// connected and at most `max_cage_size` cells); a bad layout is reported as
+/// [`GenError::Core`] without any attempts. Exhaustion reports
+/// [`GenAttemptStats::fixed_layout`] set. A cage's cells may be listed in any order; the
+/// puzzle stores them sorted.
+pub fn generate_for_layout_with_stats(
+    layout: &[SmallVec<[CellId; INLINE_CAGE_CELLS]>],
+    n: u8,
+    config: &GenerateConfig,
+) -> Result<GeneratedPuzzleWithStats, GenError> {
+    let layout: Vec<SmallVec<[CellId; INLINE_CAGE_CELLS]>> = layout
+        .iter()
+        .map(|cells| {
+            let mut cells = cells.clone();
+            cells.sort_unstable();
+            cells
         })
         .collect();
-    let mut cage_of: Vec<usize> = (0..a).collect();
-
-    fn merge_cages(
-        cages: &mut [SmallVec<[CellId; 6]>],
-        cage_of: &mut [usize],
-        dst: usize,
-        src: usize,
-        max_size: usize,
-    ) -> bool {
-        if dst == src || cages[src].is_empty() {
-            return false;
-        }
-        if cages[dst].len() + cages[src].len() > max_size {
-            return false;
-        }
+    validate_layout(&layout, n, config.rules)?;
+    generate_classified(GenerateConfig { n, ..*config }, Some(&layout), None)
+}
 
-        let moved: SmallVec<[CellId; 6]> = cages[src].drain(..).collect();
-        for cell in moved {
-            let idx = cell.0 as usize;
-            cage_of[idx] = dst;
-            cages[dst].push(cell);
-        }
-        true
-    }
+/// Validate `layout` as a puzzle whose cages all carry placeholder clues that pass
+/// [`Cage::validate_shape`], so only the cell structure can fail.
+fn validate_layout(
+    layout: &[SmallVec<[CellId; INLINE_CAGE_CELLS]>],
+    n: u8,
+    rules: Ruleset,
+) -> Result<(), GenError> {
+    let cages = layout
+        .iter()
+        .map(|cells| Cage {
+            cells: cells.clone(),
+            op: if cells.len() == 1 { Op::Eq } else { Op::Add },
+            target: 1,
+        })
+        .collect();
+    Ok(Puzzle { n, cages }.validate(rules)?)
+}
 
-    // Phase 1: try to create a reasonable number of dominoes (2-cages) early.
-    let mut order: Vec<usize> = (0..a).collect();
-    order.shuffle(rng);
-    for &cell in &order {
-        let cid = cage_of[cell];
-        if cages[cid].len() != 1 {
-            continue;
-        }
-        if !rng.random_bool(domino_probability) {
-            continue;
-        }
+/// Shared loop of [`generate_with_stats`], [`generate_for_layout_with_stats`] and
+/// [`generate_anytime`]: with a `layout`, every attempt reuses it instead of drawing a random
+/// partition; with a `deadline`, off-target unique puzzles are kept as fallbacks and the loop
+/// stops once it passes.
+fn generate_classified(
+    config: GenerateConfig,
+    layout: Option<&[SmallVec<[CellId; INLINE_CAGE_CELLS]>]>,
+    deadline: Option<Instant>,
+) -> Result<GeneratedPuzzleWithStats, GenError> {
+    generate_classified_with(config, layout, deadline, Streams::PerAttempt)
+}
 
-        let mut neighs: Vec<usize> = neighbors(n_usize, cell).into_iter().flatten().collect();
-        neighs.shuffle(rng);
-        let Some(&ncell) = neighs.iter().find(|&&j| cages[cage_of[j]].len() == 1) else {
-            continue;
-        };
-        let nid = cage_of[ncell];
-        merge_cages(&mut cages, &mut cage_of, cid, nid, max_size);
-    }
+/// [`generate_with_stats`] drawing every attempt's partition and first op assignment from one
+/// sequential stream, as it did when the daily goldens were pinned. Only
+/// [`crate::generate_daily_set`] uses it, to keep its sets; its attempts cannot be replayed.
+pub(crate) fn generate_with_sequential_streams(
+    config: GenerateConfig,
+) -> Result<GeneratedPuzzleWithStats, GenError> {
+    let streams = Streams::Sequential(Box::new(rng_from_u64(config.seed)));
+    generate_classified_with(config, None, None, streams)
+}
 
-    // Phase 2: merge remaining singletons into neighbors, respecting size cap.
-    let mut singletons: Vec<usize> = (0..a).filter(|&i| cages[cage_of[i]].len() == 1).collect();
-    singletons.shuffle(rng);
+/// Where an attempt's partition and first op assignment draw from.
+enum Streams {
+    /// The attempt's own sub-streams; see [`replay_attempt`].
+    PerAttempt,
+    /// One stream seeded from `config.seed`, consumed by every attempt in turn.
+    Sequential(Box<ChaCha20Rng>),
+}
 
-    for cell in singletons {
-        let cid = cage_of[cell];
-        if cages[cid].len() != 1 {
-            continue;
-        }
-        let mut options: Vec<usize> = neighbors(n_usize, cell)
-            .into_iter()
-            .flatten()
-            .map(|j| cage_of[j])
-            .filter(|&other| {
-                other != cid && !cages[other].is_empty() && cages[other].len() < max_size
-            })
-            .collect();
-        options.sort_unstable();
-        options.dedup();
-        options.shuffle(rng);
+/// ChaCha stream of an attempt's seed that its random partition draws from. The Latin square
+/// draws from stream 0.
+const PARTITION_STREAM: u64 = 1;
 
-        let dst = options.into_iter().next()?;
-        merge_cages(&mut cages, &mut cage_of, dst, cid, max_size);
-    }
+/// ChaCha stream of an attempt's seed that its first op assignment draws from. Later retries
+/// draw from V1's per-retry seeds.
+const OPS_STREAM: u64 = 2;
 
-    let out: Vec<SmallVec<[CellId; 6]>> = cages.into_iter().filter(|c| !c.is_empty()).collect();
-    Some(out)
+/// Stream `stream` of the ChaCha generator keyed by `attempt_seed`.
+fn attempt_stream(attempt_seed: u64, stream: u64) -> ChaCha20Rng {
+    let mut rng = rng_from_u64(attempt_seed);
+    rng.set_stream(stream);
+    rng
 }
 
-fn assign_ops_and_targets<R: Rng + ?Sized>(
-    n: u8,
-    solution: &[u8],
-    cages: Vec<SmallVec<[CellId; 6]>>,
-    rules: Ruleset,
-    rng: &mut R,
-) -> Result<Puzzle, GenError> {
-    let n_usize = n as usize;
-    let a = n_usize * n_usize;
-    if solution.len() != a {
-        return Err(GenError::AttemptsExhausted { attempts: 1 });
+/// The attempt's solution grid, drawn from stream 0 of `attempt_seed`: V1's permuted Latin
+/// square, or for a rows-only ruleset an independent shuffle of each row.
+fn seed_solution(config: &GenerateConfig, attempt_seed: u64) -> Result<Vec<u8>, GenError> {
+    let mut rng = rng_from_u64(attempt_seed);
+    let rng = stage_rng!(&mut rng, Solution);
+    if config.rules.enforce_columns {
+        return latin_solution(config.n, rng);
+    }
+    let mut row: Vec<u8> = (1..=config.n).collect();
+    let mut solution = Vec::with_capacity(usize::from(config.n) * usize::from(config.n));
+    for _ in 0..config.n {
+        row.shuffle(rng);
+        solution.extend_from_slice(&row);
     }
+    Ok(solution)
+}
 
-    let mut out_cages: Vec<Cage> = Vec::with_capacity(cages.len());
-    for cells in cages {
-        let values: SmallVec<[u8; 6]> = cells.iter().map(|c| solution[c.0 as usize]).collect();
+/// V1's `latin_solution_seeded`, drawing from `rng` instead of seeding its own.
+#[cfg(feature = "gen-dlx")]
+fn latin_solution<R: Rng + ?Sized>(n: u8, rng: &mut R) -> Result<Vec<u8>, GenError> {
+    Ok(permute_latin(n, &least_latin_square(n), rng))
+}
 
-        let (op, target) = match cells.len() {
-            1 => (Op::Eq, values[0] as i32),
-            2 => {
-                let a = values[0];
-                let b = values[1];
-                let mut ops: SmallVec<[Op; 4]> = SmallVec::new();
-                ops.push(Op::Add);
-                ops.push(Op::Mul);
-                if rules.sub_div_two_cell_only {
-                    ops.push(Op::Sub);
-                    if a.is_multiple_of(b) || b.is_multiple_of(a) {
-                        ops.push(Op::Div);
-                    }
-                }
-                ops.shuffle(rng);
-                let chosen = ops[0];
-                let target = match chosen {
-                    Op::Add => (a as i32) + (b as i32),
-                    Op::Mul => (a as i32) * (b as i32),
-                    Op::Sub => (a as i32 - b as i32).abs(),
-                    Op::Div => {
-                        let (num, den) = if a >= b { (a, b) } else { (b, a) };
-                        (num / den) as i32
-                    }
-                    Op::Eq => unreachable!(),
-                };
-                (chosen, target)
-            }
-            _ => {
-                let op = if rng.random_bool(0.55) {
-                    Op::Add
-                } else {
-                    Op::Mul
-                };
-                let target = match op {
-                    Op::Add => values.iter().map(|&v| v as i32).sum(),
-                    Op::Mul => values.iter().fold(1i32, |acc, &v| acc * (v as i32)),
-                    _ => unreachable!(),
-                };
-                (op, target)
-            }
-        };
+#[cfg(not(feature = "gen-dlx"))]
+fn latin_solution<R: Rng + ?Sized>(_n: u8, _rng: &mut R) -> Result<Vec<u8>, GenError> {
+    Err(GenError::DlxRequired)
+}
 
-        out_cages.push(Cage { cells, op, target });
+fn generate_classified_with(
+    config: GenerateConfig,
+    layout: Option<&[SmallVec<[CellId; INLINE_CAGE_CELLS]>]>,
+    deadline: Option<Instant>,
+    mut streams: Streams,
+) -> Result<GeneratedPuzzleWithStats, GenError> {
+    validate_grid_size(config.n)?;
+    if config.uniqueness_portfolio && !cfg!(feature = "uniqueness-portfolio") {
+        return Err(GenError::PortfolioRequired);
     }
-
-    let puzzle = Puzzle {
-        n,
-        cages: out_cages,
+    let mut attempt_stats = GenAttemptStats {
+        fixed_layout: layout.is_some(),
+        ..GenAttemptStats::default()
     };
-    puzzle.validate(rules)?;
-    Ok(puzzle)
-}
-
-pub fn generate(config: GenerateConfig) -> Result<GeneratedPuzzle, GenError> {
-    let mut rng = rng_from_u64(config.seed);
 
     trace!(
         n = config.n,
         seed = config.seed,
         max_attempts = config.max_attempts,
-        "gen.start"
+        target_difficulty = ?config.target_difficulty,
+        tolerance = config.difficulty_tolerance,
+        fixed_layout = layout.is_some(),
+        anytime = deadline.is_some(),
+        "gen.start_with_stats"
     );
 
-    for attempt in 0..config.max_attempts {
-        // Derive attempt-local streams deterministically.
-        let attempt_seed = config.seed ^ ((attempt as u64).wrapping_mul(0x9E37_79B9_7F4A_7C15));
-        trace!(attempt, attempt_seed, "gen.attempt");
-        let solution = latin_solution_seeded(config.n, attempt_seed)?;
-
-        let Some(partition) =
-            random_cage_partition(config.n, config.rules, config.domino_probability, &mut rng)
-        else {
-            continue;
-        };
+    let count_options = count_options(&config)?;
 
-        let puzzle =
-            assign_ops_and_targets(config.n, &solution, partition, config.rules, &mut rng)?;
+    // Best off-target candidate so far, with its rank (lower is better).
+    let mut best: Option<((u8, usize), GeneratedPuzzleWithStats)> = None;
+    let mut timed_out = false;
 
-        let count = count_solutions_up_to_with_deductions(&puzzle, config.rules, config.tier, 2)?;
-        if count == 1 {
-            trace!(attempt, "gen.accept");
-            return Ok(GeneratedPuzzle { puzzle, solution });
+    for attempt in 0..config.max_attempts {
+        if deadline.is_some_and(|deadline| Instant::now() >= deadline) {
+            trace!(attempt, "gen.deadline");
+            timed_out = true;
+            break;
+        }
+        let run = run_attempt(
+            &config,
+            layout,
+            attempt,
+            &mut streams,
+            &count_options,
+            &mut attempt_stats,
+            deadline.is_some().then_some(&mut best),
+        )?;
+        if let Some(tier_result) = run.tier_result {
+            let artifacts = run.artifacts;
+            return Ok(GeneratedPuzzleWithStats {
+                puzzle: artifacts.puzzle.expect("accepted attempts assign ops"),
+                solution: artifacts.solution,
+                difficulty: classify_difficulty_from_tier(tier_result),
+                tier_result,
+                attempts: attempt + 1,
+                attempt_stats,
+                difficulty_tolerance: config.difficulty_tolerance,
+            });
         }
     }
 
-    Err(GenError::AttemptsExhausted {
+    if let Some((_, best)) = best {
+        trace!(attempts = best.attempts, difficulty = ?best.difficulty, "gen.accept_best");
+        return Ok(GeneratedPuzzleWithStats {
+            attempt_stats,
+            ..best
+        });
+    }
+    if timed_out {
+        return Err(GenError::DeadlineExceeded {
+            attempts: attempt_stats.partitions_tried,
+            stats: attempt_stats,
+        });
+    }
+    Err(GenError::AttemptsExhaustedWithStats {
         attempts: config.max_attempts,
+        stats: attempt_stats,
     })
 }
 
-/// Generate a puzzle with full difficulty classification.
-///
-/// This is the preferred API when you need to know or control the puzzle difficulty.
-/// Unlike `generate()`, this always classifies the puzzle and can optionally
-/// filter by target difficulty.
+/// What one generation attempt drew and how far it got; see [`replay_attempt`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AttemptArtifacts {
+    /// The attempt's Latin square, row-major.
+    pub solution: Vec<u8>,
+    /// The attempt's cages as sorted cell-id lists, or `None` when the random partition could
+    /// not cover the grid.
+    pub partition: Option<Vec<Vec<CellId>>>,
+    /// The last op assignment the attempt built: the accepted puzzle, or the one whose
+    /// rejection ended the attempt. `None` when the attempt stopped before any.
+    pub puzzle: Option<Puzzle>,
+    /// Op assignments the attempt tried, including any that failed validation.
+    pub op_assignments_tried: u32,
+    /// Solutions of `puzzle` up to 2, when its uniqueness was checked.
+    pub uniqueness_count: Option<u32>,
+    /// Classified difficulty of `puzzle`, when it was unique and got that far.
+    pub difficulty: Option<DifficultyTier>,
+    /// Whether the attempt produced the puzzle generation returns.
+    pub accepted: bool,
+}
+
+/// Rebuild attempt `attempt` (0-based) of [`generate_with_stats`] under `config`: its Latin
+/// square, partition, op assignment and uniqueness count, without running the attempts before
+/// it. The accepted attempt of a run is its `attempts - 1`; `attempt` need not be below
+/// `max_attempts`, and an attempt `generate_with_stats` never reached is rebuilt all the same.
 ///
-/// # Arguments
-/// * `config` - Generation configuration, optionally with `target_difficulty` set
+/// Every attempt is a function of `(config, attempt)` alone. Its seed is V1's attempt seed of
+/// `config.seed`, and each draw has its own ChaCha stream under that seed: the Latin square
+/// stream 0, the partition stream 1, the first op assignment stream 2, and retry `r > 0` V1's
+/// per-retry seed. So a failing attempt reported by telemetry replays here exactly.
 ///
-/// # Returns
-/// * `Ok(GeneratedPuzzleWithStats)` - A unique puzzle with difficulty classification
-/// * `Err(GenError)` - If no suitable puzzle found within max_attempts
-pub fn generate_with_stats(config: GenerateConfig) -> Result<GeneratedPuzzleWithStats, GenError> {
-    let mut rng = rng_from_u64(config.seed);
+/// Random partitions only: [`generate_for_layout_with_stats`] attempts replay from the same
+/// streams but need their layout, and [`crate::generate_daily_set`] keeps the sequential stream
+/// its goldens were pinned under.
+pub fn replay_attempt(config: &GenerateConfig, attempt: u32) -> Result<AttemptArtifacts, GenError> {
+    validate_grid_size(config.n)?;
+    if config.uniqueness_portfolio && !cfg!(feature = "uniqueness-portfolio") {
+        return Err(GenError::PortfolioRequired);
+    }
+    // A shared tuple cache never changes counts, so a fresh one replays the same attempt.
+    let count_options = count_options(config)?;
+    let run = run_attempt(
+        config,
+        None,
+        attempt,
+        &mut Streams::PerAttempt,
+        &count_options,
+        &mut GenAttemptStats::default(),
+        None,
+    )?;
+    Ok(run.artifacts)
+}
 
-    trace!(
-        n = config.n,
-        seed = config.seed,
-        max_attempts = config.max_attempts,
-        target_difficulty = ?config.target_difficulty,
-        tolerance = config.difficulty_tolerance,
-        "gen.start_with_stats"
-    );
+/// An attempt's artifacts, plus the tier result of its puzzle when it was accepted.
+struct AttemptRun {
+    artifacts: AttemptArtifacts,
+    tier_result: Option<TierRequiredResult>,
+}
 
-    for attempt in 0..config.max_attempts {
-        // Derive attempt-local streams deterministically.
-        let attempt_seed = config.seed ^ ((attempt as u64).wrapping_mul(0x9E37_79B9_7F4A_7C15));
-        trace!(attempt, attempt_seed, "gen.attempt");
-        let solution = latin_solution_seeded(config.n, attempt_seed)?;
+/// One attempt of [`generate_classified_with`]: draw the Latin square and partition (or reuse
+/// `layout`), then try op assignments until one is accepted or the retries run out, counting
+/// rejections into `stats`. With `best`, off-target unique puzzles compete for it.
+fn run_attempt(
+    config: &GenerateConfig,
+    layout: Option<&[SmallVec<[CellId; INLINE_CAGE_CELLS]>]>,
+    attempt: u32,
+    streams: &mut Streams,
+    count_options: &SearchOptions,
+    stats: &mut GenAttemptStats,
+    mut best: Option<&mut Option<((u8, usize), GeneratedPuzzleWithStats)>>,
+) -> Result<AttemptRun, GenError> {
+    let attempt_seed = attempt_seed(config.seed, attempt);
+    trace!(attempt, attempt_seed, "gen.attempt");
+    let solution = seed_solution(config, attempt_seed)?;
+    let mut artifacts = AttemptArtifacts {
+        solution,
+        partition: None,
+        puzzle: None,
+        op_assignments_tried: 0,
+        uniqueness_count: None,
+        difficulty: None,
+        accepted: false,
+    };
+    let rejected = |artifacts| {
+        Ok(AttemptRun {
+            artifacts,
+            tier_result: None,
+        })
+    };
 
-        let Some(partition) =
-            random_cage_partition(config.n, config.rules, config.domino_probability, &mut rng)
-        else {
-            continue;
+    stats.partitions_tried += 1;
+    let random_partition;
+    let partition = match layout {
+        Some(layout) => layout,
+        None => {
+            let mut own_rng;
+            let rng = match streams {
+                Streams::Sequential(rng) => &mut **rng,
+                Streams::PerAttempt => {
+                    own_rng = attempt_stream(attempt_seed, PARTITION_STREAM);
+                    &mut own_rng
+                }
+            };
+            let Some(partition) = random_cage_partition(
+                config.n,
+                config.rules,
+                config.domino_probability,
+                stage_rng!(rng, Partition),
+            ) else {
+                trace!(attempt, "gen.partition_failed");
+                stats.partition_failures += 1;
+                return rejected(artifacts);
+            };
+            random_partition = partition;
+            &random_partition
+        }
+    };
+    artifacts.partition = Some(partition.iter().map(|cells| cells.to_vec()).collect());
+    if layout.is_none()
+        && let Some(filter) = config.partition_filter
+    {
+        let metrics = PartitionMetrics::of(config.n, partition);
+        if !filter.accepts(&metrics) {
+            trace!(attempt, metrics = ?metrics, "gen.partition_filtered");
+            stats.partition_filter_rejections += 1;
+            return rejected(artifacts);
+        }
+    }
+
+    // Second solutions found on this partition that another op assignment could still rule out.
+    let mut others: Vec<Vec<u8>> = Vec::new();
+    for retry in 0..config.ops_retries_per_partition.max(1) {
+        stats.op_assignments_tried += 1;
+        artifacts.op_assignments_tried += 1;
+        artifacts.uniqueness_count = None;
+        artifacts.difficulty = None;
+        let mut own_rng;
+        let ops_rng = match (retry, &mut *streams) {
+            (0, Streams::Sequential(rng)) => &mut **rng,
+            (0, Streams::PerAttempt) => {
+                own_rng = attempt_stream(attempt_seed, OPS_STREAM);
+                &mut own_rng
+            }
+            _ => {
+                own_rng = ops_retry_rng(attempt_seed, retry);
+                &mut own_rng
+            }
+        };
+        let solution = &artifacts.solution;
+        let assigned = match config.max_target {
+            None => assign_ops_and_targets(
+                config.n,
+                solution,
+                partition,
+                config.rules,
+                stage_rng!(ops_rng, Ops),
+            )
+            .map(Some),
+            Some(bounds) => assign_bounded_ops_and_targets(
+                config.n,
+                solution,
+                partition,
+                config.rules,
+                bounds,
+                stage_rng!(ops_rng, Ops),
+            ),
+        };
+        let mut puzzle = match assigned {
+            Ok(Some(puzzle)) => puzzle,
+            // The cage's digits rule out every op, whatever the retry draws.
+            Ok(None) => {
+                trace!(attempt, retry, "gen.clue_bound_rejected");
+                stats.clue_bound_rejections += 1;
+                break;
+            }
+            Err(GenError::Core(_err)) => {
+                trace!(attempt, retry, error = %_err, "gen.validation_failed");
+                stats.validation_failures += 1;
+                continue;
+            }
+            Err(e) => return Err(e),
         };
+        let steered = config
+            .min_op_counts
+            .is_none_or(|min| steer_to_op_counts(&mut puzzle, solution, min, config));
+        let puzzle = &*artifacts.puzzle.insert(puzzle);
+        if !steered {
+            // Steering already tried every cage whose digits allow a missing op.
+            trace!(attempt, retry, "gen.op_count_rejected");
+            stats.op_count_rejections += 1;
+            break;
+        }
+        // Targets come from a Latin square, so every clue is reachable.
+        debug_assert!(
+            puzzle.validate_clues(config.rules).is_ok(),
+            "{:?}",
+            puzzle.validate_clues(config.rules)
+        );
+        if too_loose(puzzle, config)? {
+            trace!(attempt, retry, "gen.constraint_score_rejected");
+            stats.constraint_score_rejections += 1;
+            continue;
+        }
 
-        let puzzle =
-            assign_ops_and_targets(config.n, &solution, partition, config.rules, &mut rng)?;
+        // A second solution an earlier assignment admitted rules this one out without a search.
+        if others.iter().any(|other| {
+            puzzle
+                .check_solution_with_rules(other, config.rules)
+                .is_ok()
+        }) {
+            trace!(attempt, retry, "gen.known_second_solution");
+            artifacts.uniqueness_count = Some(2);
+            stats.multi_solution_rejections += 1;
+            continue;
+        }
 
         // First check uniqueness with fast count
-        let count = count_solutions_up_to_with_deductions(&puzzle, config.rules, config.tier, 2)?;
-        if count != 1 {
-            continue;
+        let check = uniqueness_count(puzzle, &artifacts.solution, config, count_options)?;
+        let count = check.count;
+        artifacts.uniqueness_count = Some(count);
+        stats.uniqueness_checks += 1;
+        stats.uniqueness_nodes += check.nodes;
+        if let Some(cache) = &count_options.tuple_cache {
+            let cache_stats = cache.stats();
+            stats.tuple_cache_hits = cache_stats.hits;
+            stats.tuple_cache_misses = cache_stats.misses;
+        }
+        match count {
+            1 => {}
+            0 => {
+                trace!(attempt, retry, "gen.zero_solutions");
+                stats.zero_solution_rejections += 1;
+                continue;
+            }
+            _ => {
+                trace!(attempt, retry, "gen.multiple_solutions");
+                stats.multi_solution_rejections += 1;
+                if let Some(other) = check.other
+                    && retry + 1 < config.ops_retries_per_partition
+                {
+                    if !ops_can_separate(partition, &artifacts.solution, &other) {
+                        trace!(attempt, retry, "gen.inseparable_partition");
+                        stats.inseparable_partitions += 1;
+                        break;
+                    }
+                    others.push(other);
+                }
+                continue;
+            }
+        }
+
+        if let Some(target) = config.target_difficulty
+            && config.prefilter
+        {
+            let estimate = estimate_difficulty(puzzle, config.rules)?;
+            if estimate.confidence >= PREFILTER_MIN_CONFIDENCE
+                && !within_difficulty_tolerance(estimate.tier, target, config.difficulty_tolerance)
+            {
+                trace!(
+                    attempt,
+                    retry,
+                    estimate = ?estimate.tier,
+                    target = ?target,
+                    "gen.prefilter_rejected"
+                );
+                stats.prefilter_rejections += 1;
+                continue;
+            }
         }
 
         // Classify difficulty
-        let tier_result = classify_tier_required(&puzzle, config.rules)?;
+        let tier_result = classify_tier_required(puzzle, config.rules)?;
         let difficulty = classify_difficulty_from_tier(tier_result);
+        artifacts.difficulty = Some(difficulty);
 
         // Check if difficulty matches target (if specified)
         if let Some(target) = config.target_difficulty
@@ -407,49 +996,247 @@ pub fn generate_with_stats(config: GenerateConfig) -> Result<GeneratedPuzzleWith
         {
             trace!(
                 attempt,
+                retry,
                 actual = ?difficulty,
                 target = ?target,
                 "gen.difficulty_mismatch"
             );
+            stats.record_mismatch(difficulty);
+            if let Some(best) = best.as_deref_mut() {
+                let distance = difficulty_ordinal(difficulty).abs_diff(difficulty_ordinal(target));
+                let rank = (distance, puzzle.cages.len());
+                if best.as_ref().is_none_or(|(best_rank, _)| rank < *best_rank) {
+                    *best = Some((
+                        rank,
+                        GeneratedPuzzleWithStats {
+                            puzzle: puzzle.clone(),
+                            solution: artifacts.solution.clone(),
+                            difficulty,
+                            tier_result,
+                            attempts: attempt + 1,
+                            attempt_stats: *stats,
+                            difficulty_tolerance: config.difficulty_tolerance,
+                        },
+                    ));
+                }
+            }
             continue;
         }
 
         trace!(
             attempt,
+            retry,
             difficulty = ?difficulty,
             "gen.accept_with_stats"
         );
+        artifacts.accepted = true;
+        return Ok(AttemptRun {
+            artifacts,
+            tier_result: Some(tier_result),
+        });
+    }
+    rejected(artifacts)
+}
 
-        return Ok(GeneratedPuzzleWithStats {
+/// One uniqueness check of a generated puzzle.
+struct UniquenessCheck {
+    /// Solutions, up to 2.
+    count: u32,
+    /// Search nodes spent.
+    nodes: u64,
+    /// A second solution, when a count diverging from the generating solution found one.
+    other: Option<Vec<u8>>,
+}
+
+/// Uniqueness of `puzzle`, generated from `solution`, from the native search (diverging from
+/// `solution` under [`GenerateConfig::diverge_from_solution`]) or, with
+/// [`GenerateConfig::uniqueness_portfolio`], the portfolio (which reports no nodes).
+fn uniqueness_count(
+    puzzle: &Puzzle,
+    solution: &[u8],
+    config: &GenerateConfig,
+    count_options: &SearchOptions,
+) -> Result<UniquenessCheck, GenError> {
+    #[cfg(feature = "uniqueness-portfolio")]
+    if config.uniqueness_portfolio {
+        use kenken_solver::sat_latin::SatUniqueness;
+
+        let count = match kenken_solver::uniqueness_portfolio(puzzle, config.rules, config.tier)? {
+            SatUniqueness::Unsat => 0,
+            SatUniqueness::Unique => 1,
+            SatUniqueness::Multiple => 2,
+        };
+        return Ok(UniquenessCheck {
+            count,
+            nodes: 0,
+            other: None,
+        });
+    }
+    if config.diverge_from_solution {
+        // The clues were read off `solution`, so it solves the puzzle and counts as the first.
+        let (other, stats) = solve_other_than(
             puzzle,
+            config.rules,
+            config.tier,
             solution,
-            difficulty,
-            tier_result,
-            attempts: attempt + 1,
+            count_options.clone(),
+        )?;
+        return Ok(UniquenessCheck {
+            count: 1 + u32::from(other.is_some()),
+            nodes: stats.nodes_visited,
+            other: other.map(|other| other.grid),
         });
     }
-
-    Err(GenError::AttemptsExhausted {
-        attempts: config.max_attempts,
+    let (count, stats) = count_solutions_up_to_with_strategy(
+        puzzle,
+        config.rules,
+        config.tier,
+        2,
+        CountStrategy::Default,
+        count_options.clone(),
+    )?;
+    Ok(UniquenessCheck {
+        count,
+        nodes: stats.nodes_visited,
+        other: None,
     })
 }
 
-/// Check if actual difficulty is within tolerance of target.
-///
-/// Uses ordinal distance: Easy=0, Normal=1, Hard=2, Extreme=3, Unreasonable=4.
-fn within_difficulty_tolerance(
-    actual: DifficultyTier,
-    target: DifficultyTier,
-    tolerance: u8,
+/// Whether another op assignment on `partition` could rule out `other`, a second solution
+/// besides `solution`: not when it holds the same digits in every cage, since each clue's
+/// target is a function of its cage's digits alone (see [`kenken_core::arith`]).
+fn ops_can_separate(
+    partition: &[SmallVec<[CellId; INLINE_CAGE_CELLS]>],
+    solution: &[u8],
+    other: &[u8],
 ) -> bool {
-    let actual_ord = difficulty_ordinal(actual);
-    let target_ord = difficulty_ordinal(target);
-    let distance = actual_ord.abs_diff(target_ord);
+    let digits = |grid: &[u8], cells: &[CellId]| {
+        let mut digits: SmallVec<[u8; INLINE_CAGE_CELLS]> =
+            cells.iter().map(|c| grid[c.0 as usize]).collect();
+        digits.sort_unstable();
+        digits
+    };
+    partition
+        .iter()
+        .any(|cells| digits(solution, cells) != digits(other, cells))
+}
+
+/// V1's op and target assignment under `bounds`: each cage draws its op the same way, and an
+/// op whose target exceeds its cap is replaced by the next fitting one (for 3+ cells, the
+/// other of Add and Mul). `Ok(None)` when some cage has no op within the caps.
+fn assign_bounded_ops_and_targets<R: Rng + ?Sized>(
+    n: u8,
+    solution: &[u8],
+    cages: &[SmallVec<[CellId; INLINE_CAGE_CELLS]>],
+    rules: Ruleset,
+    bounds: ClueBounds,
+    rng: &mut R,
+) -> Result<Option<Puzzle>, GenError> {
+    if solution.len() != n as usize * n as usize {
+        return Err(GenError::AttemptsExhausted { attempts: 1 });
+    }
+
+    let mut out_cages: Vec<Cage> = Vec::with_capacity(cages.len());
+    for cells in cages {
+        let values: SmallVec<[u8; INLINE_CAGE_CELLS]> =
+            cells.iter().map(|c| solution[c.0 as usize]).collect();
+
+        let ops: SmallVec<[Op; 5]> = match cells.len() {
+            1 => SmallVec::from_slice(&[Op::Eq]),
+            2 => {
+                let mut ops = two_cell_ops(&values, rules);
+                ops.shuffle(rng);
+                ops
+            }
+            _ => {
+                if rng.random_bool(0.55) {
+                    SmallVec::from_slice(&[Op::Add, Op::Mul])
+                } else {
+                    SmallVec::from_slice(&[Op::Mul, Op::Add])
+                }
+            }
+        };
+        let Some((op, target)) = ops
+            .into_iter()
+            .filter_map(|op| Some((op, target_for(op, &values)?)))
+            .find(|&(op, target)| bounds.allows(op, target))
+        else {
+            return Ok(None);
+        };
+
+        out_cages.push(Cage {
+            cells: cells.clone(),
+            op,
+            target,
+        });
+    }
+
+    let puzzle = Puzzle {
+        n,
+        cages: out_cages,
+    };
+    puzzle.validate(rules)?;
+    Ok(Some(puzzle))
+}
+
+/// Re-clue cages of `puzzle` until its op histogram meets `min`, or report that it cannot.
+///
+/// For each op short of its minimum, the first cage that can take it is switched: two-cell
+/// cages before larger ones, then index order. A cage can take `op` when [`applicable_ops`]
+/// offers it for the cage's digits in `solution`, its target fits `config.max_target`, and the
+/// cage's current op stays at or above its own minimum without it.
+fn steer_to_op_counts(
+    puzzle: &mut Puzzle,
+    solution: &[u8],
+    min: OpCounts,
+    config: &GenerateConfig,
+) -> bool {
+    let bounds = config.max_target.unwrap_or_default();
+    let mut counts = OpCounts::of(puzzle);
+    for op in Op::ALL {
+        while counts.get(op) < min.get(op) {
+            let switch = puzzle
+                .cages
+                .iter_mut()
+                .filter(|cage| cage.op != op && counts.get(cage.op) > min.get(cage.op))
+                .filter_map(|cage| {
+                    let values: SmallVec<[u8; INLINE_CAGE_CELLS]> =
+                        cage.cells.iter().map(|c| solution[c.0 as usize]).collect();
+                    if !applicable_ops(&values, config.rules).contains(&op) {
+                        return None;
+                    }
+                    let target = target_for(op, &values).filter(|&t| bounds.allows(op, t))?;
+                    Some((cage, target))
+                })
+                .min_by_key(|(cage, _)| cage.cells.len() != 2);
+            let Some((cage, target)) = switch else {
+                return false;
+            };
+            *counts.get_mut(cage.op) -= 1;
+            *counts.get_mut(op) += 1;
+            cage.op = op;
+            cage.target = target;
+        }
+    }
+    true
+}
+
+/// Check if actual difficulty is within tolerance of target.
+///
+/// Uses ordinal distance: Easy=0, Normal=1, Hard=2, Extreme=3, Unreasonable=4.
+fn within_difficulty_tolerance(
+    actual: DifficultyTier,
+    target: DifficultyTier,
+    tolerance: u8,
+) -> bool {
+    let actual_ord = difficulty_ordinal(actual);
+    let target_ord = difficulty_ordinal(target);
+    let distance = actual_ord.abs_diff(target_ord);
     distance <= tolerance
 }
 
 /// Convert difficulty tier to ordinal for distance calculation.
-fn difficulty_ordinal(tier: DifficultyTier) -> u8 {
+pub(crate) fn difficulty_ordinal(tier: DifficultyTier) -> u8 {
     match tier {
         DifficultyTier::Easy => 0,
         DifficultyTier::Normal => 1,
@@ -461,7 +1248,23 @@ fn difficulty_ordinal(tier: DifficultyTier) -> u8 {
 
 #[cfg(all(test, feature = "gen-dlx"))]
 mod tests {
+    use kenken_solver::count_solutions_up_to_with_deductions;
+
     use super::*;
+    use crate::versioned::v1::latin_solution_seeded;
+
+    #[test]
+    fn one_by_one_grid_is_rejected_before_generation() {
+        // Rejected before any Latin square is built or partition attempted.
+        assert!(matches!(
+            generate(GenerateConfig::keen_baseline(1, 0)),
+            Err(GenError::Core(kenken_core::CoreError::InvalidGridSize(1)))
+        ));
+        assert!(matches!(
+            generate_with_stats(GenerateConfig::keen_baseline(1, 0)),
+            Err(GenError::Core(kenken_core::CoreError::InvalidGridSize(1)))
+        ));
+    }
 
     #[test]
     fn cage_partition_covers_grid_and_is_connected() {
@@ -496,6 +1299,43 @@ mod tests {
         );
     }
 
+    #[test]
+    fn rows_only_generation_seeds_per_row_shuffles() {
+        let config = GenerateConfig {
+            rules: Ruleset {
+                enforce_columns: false,
+                ..Ruleset::keen_baseline()
+            },
+            ..GenerateConfig::keen_baseline(4, 42)
+        };
+        let grids: Vec<Vec<u8>> = (0..32)
+            .map(|seed| seed_solution(&config, seed).unwrap())
+            .collect();
+        for grid in &grids {
+            for row in grid.chunks(4) {
+                let mut row = row.to_vec();
+                row.sort_unstable();
+                assert_eq!(row, [1, 2, 3, 4]);
+            }
+        }
+        let latin = Puzzle {
+            n: 4,
+            cages: vec![],
+        };
+        assert!(grids.iter().any(|grid| matches!(
+            latin.check_solution(grid),
+            Err(kenken_core::CoreError::ColumnRepeat { .. })
+        )));
+
+        // Rows-only puzzles are rarely unique on random partitions; singletons pin every cell.
+        let layout = layout_from_labels(3, b"ABCDEFGHI");
+        let generated = generate_for_layout(&layout, 3, &config).unwrap();
+        generated
+            .puzzle
+            .check_solution_with_rules(&generated.solution, config.rules)
+            .unwrap();
+    }
+
     #[test]
     fn generate_with_stats_classifies_difficulty() {
         let cfg = GenerateConfig {
@@ -592,4 +1432,720 @@ mod tests {
         // It's OK if this fails due to attempts exhausted - Easy puzzles
         // can be rare depending on the seed and grid size
     }
+
+    #[test]
+    fn exhausted_generation_reports_rejection_histogram() {
+        // 3x3 puzzles never reach Extreme, so every unique attempt is a difficulty mismatch.
+        let cfg = GenerateConfig {
+            max_attempts: 50,
+            target_difficulty: Some(DifficultyTier::Extreme),
+            difficulty_tolerance: 0,
+            ..GenerateConfig::keen_baseline(3, 5)
+        };
+
+        match generate_with_stats(cfg) {
+            Err(GenError::AttemptsExhaustedWithStats { attempts, stats }) => {
+                assert_eq!(attempts, cfg.max_attempts);
+                assert_eq!(stats.partitions_tried, cfg.max_attempts);
+                assert_eq!(
+                    stats.total_rejections(),
+                    stats.partition_failures + stats.op_assignments_tried
+                );
+                assert_eq!(
+                    stats.difficulty_histogram.iter().sum::<u32>(),
+                    stats.difficulty_mismatches
+                );
+                assert_eq!(stats.mismatches_for(DifficultyTier::Extreme), 0);
+                assert!(stats.difficulty_mismatches > 0);
+                // 3x3 uniqueness is looked up in the small-n tables, not searched.
+                assert_eq!(stats.uniqueness_nodes, 0);
+            }
+            other => panic!("expected exhaustion with stats, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn ops_retries_are_deterministic_and_accounted() {
+        let cfg = GenerateConfig {
+            max_attempts: 1_000,
+            ops_retries_per_partition: 4,
+            ..GenerateConfig::keen_baseline(4, 31)
+        };
+        let a = generate_with_stats(cfg).unwrap();
+        let b = generate_with_stats(cfg).unwrap();
+        assert_eq!(a.puzzle, b.puzzle);
+        assert_eq!(a.attempt_stats, b.attempt_stats);
+
+        let stats = a.attempt_stats;
+        assert_eq!(stats.partitions_tried, a.attempts);
+        let assigned = stats.partitions_tried - stats.partition_failures;
+        assert!(stats.op_assignments_tried >= assigned);
+        assert!(stats.op_assignments_tried <= assigned * cfg.ops_retries_per_partition);
+        // Everything but the accepted assignment was rejected.
+        assert_eq!(
+            stats.total_rejections() + 1,
+            stats.partition_failures + stats.op_assignments_tried
+        );
+    }
+
+    #[test]
+    fn replayed_accepted_attempts_rebuild_the_generated_puzzle() {
+        for (n, seed, target) in [
+            (4, 3, None),
+            (4, 31, Some(DifficultyTier::Hard)),
+            (5, 7, None),
+            (5, 11, Some(DifficultyTier::Normal)),
+            (6, 2, None),
+        ] {
+            let cfg = GenerateConfig {
+                target_difficulty: target,
+                ..GenerateConfig::keen_baseline(n, seed)
+            };
+            let generated = generate_with_stats(cfg).unwrap();
+            let replayed = replay_attempt(&cfg, generated.attempts - 1).unwrap();
+            assert!(replayed.accepted, "n={n} seed={seed}");
+            assert_eq!(
+                replayed.puzzle.as_ref(),
+                Some(&generated.puzzle),
+                "n={n} seed={seed}"
+            );
+            assert_eq!(replayed.solution, generated.solution);
+            assert_eq!(replayed.uniqueness_count, Some(1));
+            assert_eq!(replayed.difficulty, Some(generated.difficulty));
+            let cages: Vec<Vec<CellId>> = generated
+                .puzzle
+                .cages
+                .iter()
+                .map(|cage| cage.cells.to_vec())
+                .collect();
+            assert_eq!(replayed.partition, Some(cages));
+        }
+    }
+
+    #[test]
+    fn replayed_rejected_attempts_account_for_the_run() {
+        // One assignment per attempt, so each replay shows every classification it made.
+        let cfg = GenerateConfig {
+            max_attempts: 100,
+            ops_retries_per_partition: 1,
+            ..GenerateConfig::with_difficulty(3, 5, DifficultyTier::Extreme)
+        };
+        let Err(GenError::AttemptsExhaustedWithStats { stats, .. }) = generate_with_stats(cfg)
+        else {
+            panic!("3x3 puzzles never reach Extreme");
+        };
+        let mut replayed = GenAttemptStats::default();
+        for attempt in 0..cfg.max_attempts {
+            let artifacts = replay_attempt(&cfg, attempt).unwrap();
+            assert!(!artifacts.accepted);
+            replayed.op_assignments_tried += artifacts.op_assignments_tried;
+            replayed.partition_failures += u32::from(artifacts.partition.is_none());
+            if let Some(difficulty) = artifacts.difficulty {
+                replayed.record_mismatch(difficulty);
+            }
+        }
+        assert_eq!(replayed.op_assignments_tried, stats.op_assignments_tried);
+        assert_eq!(replayed.partition_failures, stats.partition_failures);
+        assert_eq!(replayed.difficulty_histogram, stats.difficulty_histogram);
+    }
+
+    #[test]
+    fn attempts_replay_out_of_order() {
+        let cfg = GenerateConfig::keen_baseline(5, 99);
+        let forward: Vec<AttemptArtifacts> = (0..6)
+            .map(|attempt| replay_attempt(&cfg, attempt).unwrap())
+            .collect();
+        for attempt in (0..6).rev() {
+            assert_eq!(
+                replay_attempt(&cfg, attempt).unwrap(),
+                forward[attempt as usize]
+            );
+        }
+        assert_ne!(forward[0].partition, forward[1].partition);
+    }
+
+    /// The default four op assignments per partition versus a single one, over 50 seeds at n=5.
+    /// Measured in release: retries draw 144 partitions instead of 155 and run 154 uniqueness
+    /// checks instead of 155, in about 20 ms instead of 23. Each tier's share of the 50 puzzles
+    /// may move by two standard deviations of the difference of two such samples.
+    #[test]
+    fn ops_retries_save_uniqueness_checks() {
+        let run = |retries: u32| {
+            let mut totals = GenAttemptStats::default();
+            let mut histogram = [0u32; 5];
+            for seed in 0..50 {
+                let cfg = GenerateConfig {
+                    ops_retries_per_partition: retries,
+                    ..GenerateConfig::keen_baseline(5, seed)
+                };
+                let g = generate_with_stats(cfg).unwrap();
+                totals.merge(&g.attempt_stats);
+                histogram[difficulty_ordinal(g.difficulty) as usize] += 1;
+            }
+            (totals, histogram)
+        };
+
+        let (single, single_histogram) = run(1);
+        let default = GenerateConfig::keen_baseline(5, 0).ops_retries_per_partition;
+        assert_eq!(default, 4);
+        let (retried, retried_histogram) = run(default);
+        assert!(
+            retried.uniqueness_checks < single.uniqueness_checks,
+            "{} checks with retries, {} without",
+            retried.uniqueness_checks,
+            single.uniqueness_checks
+        );
+        assert!(retried.partitions_tried < single.partitions_tried);
+        assert!(retried.inseparable_partitions > 0);
+        for (tier, (&a, &b)) in single_histogram.iter().zip(&retried_histogram).enumerate() {
+            let pooled = f64::from(a + b) / 100.0;
+            let sd = (2.0 * 50.0 * pooled * (1.0 - pooled)).sqrt();
+            assert!(
+                f64::from(a.abs_diff(b)) <= 2.0 * sd,
+                "tier {tier}: {a} vs {b} of 50"
+            );
+        }
+    }
+
+    #[test]
+    fn prefilter_rejects_confident_mismatches_before_classifying() {
+        let cfg = GenerateConfig {
+            max_attempts: 1_000,
+            prefilter: true,
+            ..GenerateConfig::with_difficulty(4, 3, DifficultyTier::Normal)
+        };
+        let g = generate_with_stats(cfg).unwrap();
+        assert_eq!(g.difficulty, DifficultyTier::Normal);
+
+        let stats = g.attempt_stats;
+        assert!(stats.prefilter_rejections > 0, "{stats:?}");
+        assert_eq!(
+            stats.total_rejections() + 1,
+            stats.partition_failures + stats.op_assignments_tried
+        );
+    }
+
+    /// Estimated versus fully classified difficulty over 100 generated puzzles each at n=4
+    /// and n=5 (the calibration set for `estimate_difficulty`'s scoring).
+    #[test]
+    fn estimate_agrees_with_full_classification() {
+        let mut agreed = 0;
+        let mut total = 0;
+        for n in [4, 5] {
+            for seed in 0..100 {
+                let cfg = GenerateConfig::keen_baseline(n, seed);
+                let g = generate_with_stats(cfg).unwrap();
+                let estimate = estimate_difficulty(&g.puzzle, cfg.rules).unwrap();
+                let distance =
+                    difficulty_ordinal(estimate.tier).abs_diff(difficulty_ordinal(g.difficulty));
+                assert!(
+                    distance <= 2,
+                    "n={n} seed={seed}: estimated {:?}, classified {:?}",
+                    estimate.tier,
+                    g.difficulty
+                );
+                agreed += u32::from(distance == 0);
+                total += 1;
+            }
+        }
+        eprintln!("estimate agreed on {agreed} of {total}");
+        assert!(agreed * 10 >= total * 7, "{agreed} of {total}");
+    }
+
+    /// Share of uniqueness counts that find a unique puzzle, with and without a constraint
+    /// score cap, over 300 partitions of 6x6 squares with 8 op assignments each. The score is
+    /// a weak signal here: the cap skips about a fifth of the counts and lifts acceptance by
+    /// about a point.
+    #[test]
+    fn constraint_score_cap_raises_acceptance_at_n6() {
+        let rules = Ruleset::keen_baseline();
+        let capped = GenerateConfig {
+            max_constraint_score: Some(41.0),
+            ..GenerateConfig::keen_baseline(6, 0)
+        };
+        let (mut counted, mut unique) = ([0u32; 2], [0u32; 2]);
+        for seed in 0..300 {
+            let mut rng = rng_from_u64(seed);
+            let solution = latin_solution_seeded(6, seed).unwrap();
+            let partition = random_cage_partition(6, rules, 0.55, &mut rng).unwrap();
+            for _ in 0..8 {
+                let puzzle =
+                    assign_ops_and_targets(6, &solution, &partition, rules, &mut rng).unwrap();
+                let is_unique =
+                    count_solutions_up_to_with_deductions(&puzzle, rules, DeductionTier::Hard, 2)
+                        .unwrap()
+                        == 1;
+                for (slot, rejected) in [false, too_loose(&puzzle, &capped).unwrap()]
+                    .into_iter()
+                    .enumerate()
+                {
+                    if !rejected {
+                        counted[slot] += 1;
+                        unique[slot] += u32::from(is_unique);
+                    }
+                }
+            }
+        }
+        eprintln!(
+            "uncapped {}/{}, capped {}/{}",
+            unique[0], counted[0], unique[1], counted[1]
+        );
+        assert!(counted[1] < counted[0]);
+        assert!(unique[1] as f64 / counted[1] as f64 > unique[0] as f64 / counted[0] as f64);
+    }
+
+    /// Unique puzzles per uniqueness count, with and without the default partition filter,
+    /// over 200 seeds at n=6. Filtered partitions cost a redraw but no count.
+    #[test]
+    fn default_partition_filter_raises_acceptance_at_n6() {
+        let run = |partition_filter: Option<PartitionFilter>| {
+            let (mut counts, mut filtered) = (0, 0);
+            for seed in 0..200 {
+                let cfg = GenerateConfig {
+                    partition_filter,
+                    ..GenerateConfig::keen_baseline(6, seed)
+                };
+                let stats = generate_with_stats(cfg).unwrap().attempt_stats;
+                counts += stats.multi_solution_rejections + stats.zero_solution_rejections + 1;
+                filtered += stats.partition_filter_rejections;
+                assert_eq!(
+                    stats.total_rejections() + 1,
+                    stats.partition_failures
+                        + stats.partition_filter_rejections
+                        + stats.op_assignments_tried
+                );
+            }
+            eprintln!("filter {partition_filter:?}: 200/{counts} unique, {filtered} filtered");
+            (200.0 / counts as f64, filtered)
+        };
+
+        let (unfiltered_rate, none_filtered) = run(None);
+        let (filtered_rate, filtered) = run(Some(PartitionFilter::default()));
+        assert_eq!(none_filtered, 0);
+        assert!(filtered > 0);
+        assert!(filtered_rate > unfiltered_rate);
+    }
+
+    #[cfg(feature = "uniqueness-portfolio")]
+    #[test]
+    fn uniqueness_portfolio_generates_the_same_puzzles() {
+        for seed in 0..20 {
+            let native = generate_with_stats(GenerateConfig::keen_baseline(5, seed)).unwrap();
+            let raced = generate_with_stats(GenerateConfig {
+                uniqueness_portfolio: true,
+                ..GenerateConfig::keen_baseline(5, seed)
+            })
+            .unwrap();
+            assert_eq!(raced.puzzle, native.puzzle, "seed {seed}");
+            assert_eq!(raced.solution, native.solution, "seed {seed}");
+            assert_eq!(raced.attempt_stats.uniqueness_nodes, 0);
+        }
+    }
+
+    #[cfg(not(feature = "uniqueness-portfolio"))]
+    #[test]
+    fn uniqueness_portfolio_needs_its_feature() {
+        let config = GenerateConfig {
+            uniqueness_portfolio: true,
+            ..GenerateConfig::keen_baseline(4, 0)
+        };
+        assert!(matches!(
+            generate_with_stats(config),
+            Err(GenError::PortfolioRequired)
+        ));
+    }
+
+    #[test]
+    fn shared_tuple_cache_hits_without_changing_puzzles() {
+        let (mut hits, mut misses) = (0, 0);
+        for seed in 0..100 {
+            let cached = generate_with_stats(GenerateConfig::keen_baseline(5, seed)).unwrap();
+            let plain = generate_with_stats(GenerateConfig {
+                shared_tuple_cache: false,
+                ..GenerateConfig::keen_baseline(5, seed)
+            })
+            .unwrap();
+            assert_eq!(cached.puzzle, plain.puzzle, "seed {seed}");
+            assert_eq!(cached.difficulty, plain.difficulty, "seed {seed}");
+            let (with, without) = (cached.attempt_stats, plain.attempt_stats);
+            assert_eq!(
+                with.uniqueness_nodes, without.uniqueness_nodes,
+                "seed {seed}"
+            );
+            assert_eq!(with.op_assignments_tried, without.op_assignments_tried);
+            assert_eq!(
+                (without.tuple_cache_hits, without.tuple_cache_misses),
+                (0, 0)
+            );
+            hits += with.tuple_cache_hits;
+            misses += with.tuple_cache_misses;
+        }
+        eprintln!("shared tuple cache: {hits} hits, {misses} misses");
+        assert!(hits > 0);
+    }
+
+    #[test]
+    fn diverging_counts_decide_the_same_with_fewer_nodes() {
+        // Search order moves tuple cache traffic and node counts, nothing else. One op
+        // assignment per partition, since retries also use the second solutions only a
+        // diverging count reports.
+        let config = |seed, diverge_from_solution| GenerateConfig {
+            ops_retries_per_partition: 1,
+            diverge_from_solution,
+            ..GenerateConfig::keen_baseline(6, seed)
+        };
+        let decisions = |stats: GenAttemptStats| GenAttemptStats {
+            uniqueness_nodes: 0,
+            tuple_cache_hits: 0,
+            tuple_cache_misses: 0,
+            ..stats
+        };
+        let (mut diverging_nodes, mut default_nodes, mut attempts) = (0, 0, 0);
+        for seed in 0..100 {
+            let diverging = generate_with_stats(config(seed, true)).unwrap();
+            let default = generate_with_stats(config(seed, false)).unwrap();
+            assert_eq!(diverging.puzzle, default.puzzle, "seed {seed}");
+            assert_eq!(diverging.attempts, default.attempts, "seed {seed}");
+            assert_eq!(
+                decisions(diverging.attempt_stats),
+                decisions(default.attempt_stats),
+                "seed {seed}"
+            );
+            diverging_nodes += diverging.attempt_stats.uniqueness_nodes;
+            default_nodes += default.attempt_stats.uniqueness_nodes;
+            attempts += diverging.attempts;
+        }
+        eprintln!(
+            "uniqueness nodes over {attempts} attempts: {diverging_nodes} diverging, {default_nodes} default"
+        );
+        assert!(attempts >= 100);
+        assert!(diverging_nodes < default_nodes);
+    }
+
+    #[test]
+    fn a_memory_budget_leaves_puzzles_unchanged() {
+        let min = min_memory_budget(6);
+        assert!(min < kenken_solver::dlx_latin::matrix_footprint(6));
+        for budget in [min, usize::MAX] {
+            for seed in 0..10 {
+                let config = GenerateConfig {
+                    memory_budget: Some(budget),
+                    ..GenerateConfig::keen_baseline(6, seed)
+                };
+                let plain = GenerateConfig::keen_baseline(6, seed);
+                let budgeted = generate_with_stats(config).unwrap();
+                let unbudgeted = generate_with_stats(plain).unwrap();
+                assert_eq!(budgeted.puzzle, unbudgeted.puzzle, "seed {seed}");
+                assert_eq!(
+                    budgeted.attempt_stats.uniqueness_nodes,
+                    unbudgeted.attempt_stats.uniqueness_nodes
+                );
+                assert_eq!(
+                    generate(config).unwrap().puzzle,
+                    generate(plain).unwrap().puzzle
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn a_budget_below_the_minimum_fails_up_front() {
+        let needed = min_memory_budget(6);
+        let config = GenerateConfig {
+            memory_budget: Some(needed - 1),
+            ..GenerateConfig::keen_baseline(6, 0)
+        };
+        for result in [
+            generate_with_stats(config).map(|g| g.puzzle),
+            generate(config).map(|g| g.puzzle),
+        ] {
+            assert!(matches!(
+                result,
+                Err(GenError::MemoryBudgetExceeded { n: 6, needed: got, budget })
+                    if got == needed && budget == needed - 1
+            ));
+        }
+    }
+
+    #[test]
+    fn clue_bounds_cap_mul_targets_without_collapsing_acceptance() {
+        let bounds = ClueBounds {
+            mul_max: 120,
+            ..ClueBounds::UNBOUNDED
+        };
+        let (mut bounded_partitions, mut free_partitions, mut rejections) = (0, 0, 0);
+        let mut free_over_cap = 0;
+        for seed in 0..100 {
+            let bounded = generate_with_stats(GenerateConfig {
+                max_target: Some(bounds),
+                ..GenerateConfig::keen_baseline(6, seed)
+            })
+            .unwrap();
+            for cage in &bounded.puzzle.cages {
+                assert!(bounds.allows(cage.op, cage.target), "seed {seed}: {cage:?}");
+            }
+            bounded_partitions += bounded.attempt_stats.partitions_tried;
+            rejections += bounded.attempt_stats.clue_bound_rejections;
+
+            let free = generate_with_stats(GenerateConfig::keen_baseline(6, seed)).unwrap();
+            assert_eq!(free.attempt_stats.clue_bound_rejections, 0);
+            free_partitions += free.attempt_stats.partitions_tried;
+            free_over_cap += free
+                .puzzle
+                .cages
+                .iter()
+                .filter(|cage| !bounds.allows(cage.op, cage.target))
+                .count();
+        }
+        eprintln!(
+            "mul_max 120: {bounded_partitions} partitions ({rejections} rejected by bounds) vs \
+             {free_partitions} unbounded, {free_over_cap} unbounded clues over the cap"
+        );
+        assert!(free_over_cap > 0);
+        assert!(bounded_partitions <= 3 * free_partitions);
+    }
+
+    #[test]
+    fn clue_bounds_reject_partitions_no_op_fits() {
+        // No Add or Mul clue over three or more cells stays within these caps.
+        let config = GenerateConfig {
+            max_attempts: 50,
+            max_target: Some(ClueBounds {
+                add_max: 5,
+                mul_max: 5,
+            }),
+            ..GenerateConfig::keen_baseline(5, 1)
+        };
+        let Err(GenError::AttemptsExhaustedWithStats { stats, .. }) = generate_with_stats(config)
+        else {
+            panic!("no partition should fit");
+        };
+        assert!(stats.clue_bound_rejections > 0);
+        assert_eq!(
+            stats.op_assignments_tried,
+            stats.partitions_tried - stats.partition_failures
+        );
+    }
+
+    #[test]
+    fn min_op_counts_hold_within_the_documented_attempt_factor() {
+        let min = OpCounts {
+            div: 1,
+            sub: 1,
+            ..OpCounts::default()
+        };
+        let (mut constrained, mut free, mut rejections) = (0, 0, 0);
+        for seed in 0..50 {
+            let generated = generate_with_stats(GenerateConfig {
+                min_op_counts: Some(min),
+                ..GenerateConfig::keen_baseline(5, seed)
+            })
+            .unwrap();
+            let counts = OpCounts::of(&generated.puzzle);
+            assert!(counts.meets(&min), "seed {seed}: {counts:?}");
+            let count = count_solutions_up_to_with_deductions(
+                &generated.puzzle,
+                Ruleset::keen_baseline(),
+                DeductionTier::Hard,
+                2,
+            )
+            .unwrap();
+            assert_eq!(count, 1, "seed {seed}");
+            constrained += generated.attempt_stats.partitions_tried;
+            rejections += generated.attempt_stats.op_count_rejections;
+            free += generate_with_stats(GenerateConfig::keen_baseline(5, seed))
+                .unwrap()
+                .attempt_stats
+                .partitions_tried;
+        }
+        let factor = f64::from(constrained) / f64::from(free);
+        eprintln!(
+            "min one div, one sub: {constrained} partitions ({rejections} short of the \
+             minimums) vs {free} unconstrained, {factor:.2}x"
+        );
+        assert!(factor < OP_COUNT_ATTEMPT_FACTOR, "{factor:.2}x");
+    }
+
+    #[test]
+    fn unreachable_op_minimums_reject_every_partition() {
+        // Sub and Div only clue two-cell cages under the baseline rules, and a 4x4 grid has
+        // at most eight of them.
+        let config = GenerateConfig {
+            max_attempts: 20,
+            min_op_counts: Some(OpCounts {
+                sub: 5,
+                div: 4,
+                ..OpCounts::default()
+            }),
+            ..GenerateConfig::keen_baseline(4, 3)
+        };
+        let Err(GenError::AttemptsExhaustedWithStats { stats, .. }) = generate_with_stats(config)
+        else {
+            panic!("no partition should meet the minimums");
+        };
+        assert_eq!(
+            stats.op_count_rejections,
+            stats.partitions_tried - stats.partition_failures
+        );
+        assert_eq!(stats.uniqueness_nodes, 0);
+    }
+
+    #[test]
+    fn anytime_with_a_generous_deadline_matches_the_target() {
+        let cfg = GenerateConfig {
+            max_attempts: 1_000,
+            ..GenerateConfig::with_difficulty(4, 3, DifficultyTier::Normal)
+        };
+        let g = generate_anytime(cfg, Duration::from_secs(60)).unwrap();
+        assert_eq!(g.difficulty, DifficultyTier::Normal);
+        assert_eq!(
+            count_solutions_up_to_with_deductions(&g.puzzle, cfg.rules, cfg.tier, 2).unwrap(),
+            1
+        );
+        assert!(g.puzzle.check_solution(&g.solution).is_ok());
+    }
+
+    #[test]
+    fn anytime_with_no_time_reports_the_deadline() {
+        let cfg = GenerateConfig::keen_baseline(4, 0);
+        match generate_anytime(cfg, Duration::ZERO) {
+            Err(GenError::DeadlineExceeded { attempts, stats }) => {
+                assert_eq!(attempts, 0);
+                assert_eq!(stats, GenAttemptStats::default());
+            }
+            other => panic!("expected the deadline error, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn anytime_falls_back_to_the_closest_unique_candidate() {
+        // 3x3 puzzles never reach Extreme, so the attempt budget runs out first and the
+        // hardest candidate seen comes back instead of an error.
+        let cfg = GenerateConfig {
+            max_attempts: 50,
+            target_difficulty: Some(DifficultyTier::Extreme),
+            ..GenerateConfig::keen_baseline(3, 5)
+        };
+        assert!(generate_with_stats(cfg).is_err());
+        let g = generate_anytime(cfg, Duration::from_secs(3600)).unwrap();
+        assert_eq!(
+            count_solutions_up_to_with_deductions(&g.puzzle, cfg.rules, cfg.tier, 2).unwrap(),
+            1
+        );
+        let stats = g.attempt_stats;
+        assert_eq!(stats.partitions_tried, cfg.max_attempts);
+        assert!(g.attempts <= cfg.max_attempts);
+        let hardest = (0..5).rev().find(|&t| stats.difficulty_histogram[t] > 0);
+        assert_eq!(hardest, Some(difficulty_ordinal(g.difficulty) as usize));
+    }
+
+    /// Row-major cage cells from a grid of cage labels.
+    fn layout_from_labels(n: u8, labels: &[u8]) -> Vec<SmallVec<[CellId; INLINE_CAGE_CELLS]>> {
+        let mut layout: Vec<SmallVec<[CellId; INLINE_CAGE_CELLS]>> = Vec::new();
+        let mut seen = Vec::new();
+        for (idx, &label) in labels.iter().enumerate() {
+            let cage = match seen.iter().position(|&l| l == label) {
+                Some(cage) => cage,
+                None => {
+                    seen.push(label);
+                    layout.push(SmallVec::new());
+                    layout.len() - 1
+                }
+            };
+            layout[cage].push(CellId(idx as u16));
+        }
+        assert_eq!(labels.len(), n as usize * n as usize);
+        layout
+    }
+
+    #[test]
+    fn pinwheel_layout_gets_a_unique_puzzle() {
+        #[rustfmt::skip]
+        let layout = layout_from_labels(4, b"\
+            AAAB\
+            DABB\
+            DDCB\
+            DCCC");
+        let config = GenerateConfig {
+            max_attempts: 500,
+            ..GenerateConfig::keen_baseline(4, 0)
+        };
+        let generated = generate_for_layout_with_stats(&layout, 4, &config).unwrap();
+
+        let cells: Vec<_> = generated.puzzle.cages.iter().map(|c| &c.cells).collect();
+        assert_eq!(cells, layout.iter().collect::<Vec<_>>());
+        assert!(generated.attempt_stats.fixed_layout);
+        assert_eq!(generated.attempt_stats.partition_failures, 0);
+        assert_eq!(
+            count_solutions_up_to_with_deductions(
+                &generated.puzzle,
+                config.rules,
+                DeductionTier::Hard,
+                2
+            )
+            .unwrap(),
+            1
+        );
+        assert!(generated.puzzle.check_solution(&generated.solution).is_ok());
+
+        let plain = generate_for_layout(&layout, 4, &config).unwrap();
+        assert_eq!(plain.puzzle, generated.puzzle);
+    }
+
+    #[test]
+    fn layout_with_a_seven_cell_cage_is_rejected_up_front() {
+        #[rustfmt::skip]
+        let layout = layout_from_labels(4, b"\
+            AAAA\
+            ABBA\
+            ABCC\
+            DDDC");
+        let config = GenerateConfig::keen_baseline(4, 0);
+        assert_eq!(config.rules.max_cage_size, 6);
+        assert!(matches!(
+            generate_for_layout(&layout, 4, &config),
+            Err(GenError::Core(kenken_core::CoreError::CageTooLarge {
+                len: 7,
+                max: 6
+            }))
+        ));
+    }
+
+    #[test]
+    fn small_n_tables_agree_with_the_search_on_generated_4x4() {
+        use kenken_solver::{
+            DeductionTier, SearchOptions, SmallNTables, count_solutions_up_to_with_options,
+            solve_one_with_options,
+        };
+        let rules = Ruleset::keen_baseline();
+        let tables = |small_n_tables| SearchOptions {
+            small_n_tables,
+            ..SearchOptions::default()
+        };
+        for seed in 0..500 {
+            let generated = generate(GenerateConfig::keen_baseline(4, seed)).unwrap();
+            let puzzle = &generated.puzzle;
+            for tier in [DeductionTier::None, DeductionTier::Hard] {
+                let count = |mode| {
+                    count_solutions_up_to_with_options(puzzle, rules, tier, 2, tables(mode))
+                        .unwrap()
+                        .0
+                };
+                assert_eq!(
+                    count(SmallNTables::Counting),
+                    count(SmallNTables::Never),
+                    "seed {seed} {tier:?} {}",
+                    kenken_core::format::sgt_desc::encode_keen_desc(puzzle, rules).unwrap()
+                );
+                let solve = |mode| {
+                    solve_one_with_options(puzzle, rules, tier, tables(mode))
+                        .unwrap()
+                        .0
+                };
+                let searched = solve(SmallNTables::Never).unwrap();
+                assert_eq!(solve(SmallNTables::Always), Some(searched.clone()));
+                assert_eq!(searched.grid, generated.solution, "seed {seed}");
+            }
+        }
+    }
 }
@@ -6,14 +6,33 @@ use kenken_core::rules::Ruleset;
 use kenken_solver::error::SolveError;
 use kenken_solver::{DeductionTier, count_solutions_up_to_with_deductions};
 
+#[cfg(feature = "test-adversarial")]
+pub mod adversarial;
+pub mod bank;
+pub mod daily;
 pub mod generator;
 pub mod minimizer;
+pub mod partition;
+pub mod repair;
+#[cfg(feature = "rng-audit")]
+pub mod rng_audit;
 pub mod seed;
+pub mod stream;
+pub mod versioned;
 
+pub use bank::{BankEntry, BankFilter, BucketStats, PuzzleBank};
+pub use daily::generate_daily_set;
 pub use generator::{
-    GenerateConfig, GeneratedPuzzle, GeneratedPuzzleWithStats, generate, generate_with_stats,
+    AttemptArtifacts, ClueBounds, GenAttemptStats, GenerateConfig, GeneratedPuzzle,
+    GeneratedPuzzleWithStats, OP_COUNT_ATTEMPT_FACTOR, OpCounts, generate, generate_anytime,
+    generate_for_layout, generate_for_layout_with_stats, generate_with_stats, min_memory_budget,
+    partition_constraint_score, replay_attempt,
 };
-pub use minimizer::{MinimizeConfig, MinimizeResult, minimize_puzzle};
+pub use minimizer::{DifficultyGoal, MinimizeConfig, MinimizeResult, minimize_puzzle};
+pub use partition::{PartitionFilter, PartitionMetrics};
+pub use repair::{ClueRepair, suggest_clue_repairs};
+pub use stream::{StreamEnd, StreamSummary, generate_stream, stream_seed};
+pub use versioned::{GenAlgoVersion, generate_versioned};
 
 #[derive(thiserror::Error, Debug)]
 pub enum GenError {
@@ -23,8 +42,51 @@ pub enum GenError {
     Solve(#[from] SolveError),
     #[error("generation requires `kenken-gen/gen-dlx` (and `kenken-solver/solver-dlx`)")]
     DlxRequired,
+    #[error("`uniqueness_portfolio` requires `kenken-gen/uniqueness-portfolio`")]
+    PortfolioRequired,
     #[error("generation exhausted attempts ({attempts})")]
     AttemptsExhausted { attempts: u32 },
+    #[error(
+        "generation exhausted attempts ({attempts}): {} rejected ({} partition, {} validation, {} multi-solution, {} zero-solution, {} difficulty)",
+        stats.total_rejections(),
+        stats.partition_failures,
+        stats.validation_failures,
+        stats.multi_solution_rejections,
+        stats.zero_solution_rejections,
+        stats.difficulty_mismatches
+    )]
+    AttemptsExhaustedWithStats {
+        attempts: u32,
+        stats: GenAttemptStats,
+    },
+    #[error(
+        "generation at n={n} needs an estimated {needed} bytes, over its memory budget of {budget}"
+    )]
+    MemoryBudgetExceeded { n: u8, needed: usize, budget: usize },
+    #[error(
+        "generation found no unique puzzle before its deadline ({attempts} attempts, {} rejected)",
+        stats.total_rejections()
+    )]
+    DeadlineExceeded {
+        attempts: u32,
+        stats: GenAttemptStats,
+    },
+    #[error(
+        "generate_stream's sink panicked after {} delivered puzzles: {message}",
+        summary.delivered()
+    )]
+    SinkPanicked {
+        summary: Box<StreamSummary>,
+        message: String,
+    },
+}
+
+/// [`kenken_solver::capabilities`] with `generate` filled in: whether this build can generate
+/// (`gen-dlx`) or fails every generation call with [`GenError::DlxRequired`].
+pub fn capabilities() -> kenken_solver::Capabilities {
+    let mut caps = kenken_solver::capabilities();
+    caps.generate = cfg!(feature = "gen-dlx");
+    caps
 }
 
 pub fn count_solutions_batch(
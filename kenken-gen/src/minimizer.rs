@@ -12,6 +12,13 @@
 //! 3. If the merged puzzle is still unique, accept the merge
 //! 4. Repeat until no more merges preserve uniqueness
 //!
+//! A rejected pair is skipped and the search moves on to the next one, so every adjacent pair
+//! of the result has been tried: no single merge of it stays unique (unless
+//! `MinimizeConfig::max_iterations` ran out first).
+//!
+//! With `MinimizeConfig::target_difficulty` set, each unique merge is also classified
+//! and rolled back if it moves the puzzle's difficulty further from the goal.
+//!
 //! # Constraints
 //!
 //! Merges respect ruleset constraints:
@@ -19,13 +26,19 @@
 //! - Sub/Div restricted to 2-cell cages
 //! - Orthogonal connectivity requirement
 
+use kenken_core::arith::target_for;
+use kenken_core::limits::INLINE_CAGE_CELLS;
 use kenken_core::rules::{Op, Ruleset};
-use kenken_core::{Cage, CellId, Puzzle};
-use kenken_solver::{DeductionTier, count_solutions_up_to_with_deductions};
+use kenken_core::{Cage, CageGraph, CellId, Puzzle};
+use kenken_solver::{
+    CountStrategy, DeductionTier, DifficultyTier, SearchOptions, classify_difficulty_from_tier,
+    classify_tier_required, count_solutions_up_to_with_strategy,
+};
 use smallvec::SmallVec;
 use std::collections::HashSet;
 
 use crate::GenError;
+use crate::generator::{ClueBounds, difficulty_ordinal};
 
 #[cfg(feature = "telemetry-tracing")]
 use tracing::trace;
@@ -46,6 +59,19 @@ pub struct MinimizeConfig {
     pub max_iterations: u32,
     /// Prefer Add operations when merging (vs Mul).
     pub prefer_add: bool,
+    /// Optional difficulty steering. When set, every uniqueness-preserving merge is
+    /// classified and rolled back if it moves the difficulty further from the goal.
+    /// Classification is expensive, so leave this `None` when only uniqueness matters.
+    pub target_difficulty: Option<(DifficultyTier, DifficultyGoal)>,
+    /// Start each uniqueness check from the known solution, which a merge keeps valid, so the
+    /// search only has to look for a second one, diverging from the known one as early as it
+    /// can ([`CountStrategy::DivergeFrom`]). Same results, fewer nodes.
+    pub warm_start: bool,
+    /// Cap merged clues' targets like [`GenerateConfig::max_target`]: a merge whose preferred
+    /// op exceeds its cap takes the other of Add and Mul, and is skipped when neither fits.
+    ///
+    /// [`GenerateConfig::max_target`]: crate::GenerateConfig::max_target
+    pub max_target: Option<ClueBounds>,
 }
 
 impl MinimizeConfig {
@@ -55,6 +81,33 @@ impl MinimizeConfig {
             tier: DeductionTier::Hard,
             max_iterations: 1000,
             prefer_add: true,
+            target_difficulty: None,
+            warm_start: true,
+            max_target: None,
+        }
+    }
+}
+
+/// How the classified difficulty should relate to the target tier.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DifficultyGoal {
+    /// Keep difficulty at or below the target.
+    AtMost,
+    /// Push difficulty up to (or beyond) the target.
+    AtLeast,
+    /// Steer difficulty towards exactly the target.
+    Exactly,
+}
+
+impl DifficultyGoal {
+    /// Number of tiers `actual` lies outside the goal (0 = satisfied).
+    fn distance(self, actual: DifficultyTier, target: DifficultyTier) -> u8 {
+        let actual = difficulty_ordinal(actual);
+        let target = difficulty_ordinal(target);
+        match self {
+            Self::AtMost => actual.saturating_sub(target),
+            Self::AtLeast => target.saturating_sub(actual),
+            Self::Exactly => actual.abs_diff(target),
         }
     }
 }
@@ -72,6 +125,16 @@ pub struct MinimizeResult {
     pub merges_performed: u32,
     /// Number of merge attempts that failed uniqueness check.
     pub merges_rejected: u32,
+    /// Number of unique merges rolled back because they moved away from the difficulty goal.
+    pub merges_rejected_for_difficulty: u32,
+    /// Classified difficulty of the result (only computed when a goal is set).
+    pub final_difficulty: Option<DifficultyTier>,
+    /// Whether the result satisfies the difficulty goal (`None` when no goal is set).
+    ///
+    /// `Some(false)` means no merge sequence reaching the goal was found.
+    pub difficulty_goal_met: Option<bool>,
+    /// Search nodes spent on uniqueness checks.
+    pub uniqueness_nodes: u64,
 }
 
 /// Minimize a puzzle by merging adjacent cages while preserving uniqueness.
@@ -100,7 +163,17 @@ pub fn minimize_puzzle(
     let mut current = puzzle;
     let mut merges_performed = 0u32;
     let mut merges_rejected = 0u32;
+    let mut merges_rejected_for_difficulty = 0u32;
+    let mut uniqueness_nodes = 0u64;
     let mut iteration = 0u32;
+    // Pairs rejected against the current puzzle; indices shift after a merge, so this
+    // is cleared whenever a merge is accepted.
+    let mut rejected_pairs: HashSet<(usize, usize)> = HashSet::new();
+
+    let mut current_difficulty = match config.target_difficulty {
+        Some(_) => Some(classify(&current, config.rules)?),
+        None => None,
+    };
 
     trace!(
         n = current.n,
@@ -116,59 +189,86 @@ pub fn minimize_puzzle(
         iteration += 1;
 
         // Find a valid merge candidate
-        let merge_candidate = find_merge_candidate(&current, solution, config);
-
-        match merge_candidate {
-            Some((cage_a, cage_b, merged_cage)) => {
-                // Build candidate puzzle with merged cage
-                let candidate = apply_merge(&current, cage_a, cage_b, merged_cage);
-
-                // Verify uniqueness
-                let count = count_solutions_up_to_with_deductions(
-                    &candidate,
-                    config.rules,
-                    config.tier,
-                    2,
-                )?;
-
-                if count == 1 {
-                    trace!(
-                        iteration,
-                        cage_a,
-                        cage_b,
-                        new_cage_count = candidate.cages.len(),
-                        "minimizer.merge_accepted"
-                    );
-                    current = candidate;
-                    merges_performed += 1;
-                } else {
-                    trace!(
-                        iteration,
-                        cage_a,
-                        cage_b,
-                        solutions = count,
-                        "minimizer.merge_rejected"
-                    );
-                    merges_rejected += 1;
-                    // Mark this pair as tried and continue searching
-                    // For simplicity, we'll break and return current best
-                    // A more sophisticated version could track tried pairs
-                    break;
-                }
-            }
-            None => {
-                trace!(iteration, "minimizer.no_candidates");
-                break;
+        let merge_candidate = find_merge_candidate(&current, solution, config, &rejected_pairs);
+
+        let Some((cage_a, cage_b, merged_cage)) = merge_candidate else {
+            trace!(iteration, "minimizer.no_candidates");
+            break;
+        };
+
+        // Build candidate puzzle with merged cage
+        let candidate = apply_merge(&current, cage_a, cage_b, merged_cage);
+
+        // Verify uniqueness
+        let strategy = if config.warm_start {
+            CountStrategy::DivergeFrom(solution)
+        } else {
+            CountStrategy::Default
+        };
+        let (count, stats) = count_solutions_up_to_with_strategy(
+            &candidate,
+            config.rules,
+            config.tier,
+            2,
+            strategy,
+            SearchOptions::default(),
+        )?;
+        uniqueness_nodes += stats.nodes_visited;
+        if count != 1 {
+            trace!(
+                iteration,
+                cage_a,
+                cage_b,
+                solutions = count,
+                "minimizer.merge_rejected"
+            );
+            merges_rejected += 1;
+            rejected_pairs.insert((cage_a, cage_b));
+            continue;
+        }
+
+        if let (Some((target, goal)), Some(before)) = (config.target_difficulty, current_difficulty)
+        {
+            let after = classify(&candidate, config.rules)?;
+            if goal.distance(after, target) > goal.distance(before, target) {
+                trace!(
+                    iteration,
+                    cage_a,
+                    cage_b,
+                    before = ?before,
+                    after = ?after,
+                    "minimizer.merge_rejected_for_difficulty"
+                );
+                merges_rejected_for_difficulty += 1;
+                rejected_pairs.insert((cage_a, cage_b));
+                continue;
             }
+            current_difficulty = Some(after);
         }
+
+        trace!(
+            iteration,
+            cage_a,
+            cage_b,
+            new_cage_count = candidate.cages.len(),
+            "minimizer.merge_accepted"
+        );
+        current = candidate;
+        merges_performed += 1;
+        rejected_pairs.clear();
     }
 
     let final_cage_count = current.cages.len();
+    let difficulty_goal_met = config
+        .target_difficulty
+        .zip(current_difficulty)
+        .map(|((target, goal), actual)| goal.distance(actual, target) == 0);
     trace!(
         original_cages = original_cage_count,
         final_cages = final_cage_count,
         merges_performed,
         merges_rejected,
+        merges_rejected_for_difficulty,
         "minimizer.done"
     );
 
@@ -178,73 +278,40 @@ pub fn minimize_puzzle(
         final_cage_count,
         merges_performed,
         merges_rejected,
+        merges_rejected_for_difficulty,
+        final_difficulty: current_difficulty,
+        difficulty_goal_met,
+        uniqueness_nodes,
     })
 }
 
+fn classify(puzzle: &Puzzle, rules: Ruleset) -> Result<DifficultyTier, GenError> {
+    Ok(classify_difficulty_from_tier(classify_tier_required(
+        puzzle, rules,
+    )?))
+}
+
 /// Find a pair of adjacent cages that can be merged.
 ///
-/// Returns `Some((cage_a_idx, cage_b_idx, merged_cage))` if a valid candidate is found.
+/// Returns `Some((cage_a_idx, cage_b_idx, merged_cage))` if a valid candidate is found,
+/// skipping pairs already rejected against this puzzle.
 fn find_merge_candidate(
     puzzle: &Puzzle,
     solution: &[u8],
     config: MinimizeConfig,
+    rejected_pairs: &HashSet<(usize, usize)>,
 ) -> Option<(usize, usize, Cage)> {
-    let n = puzzle.n;
-    let n_usize = n as usize;
-
-    // Build cell-to-cage mapping
-    let a = n_usize * n_usize;
-    let mut cell_to_cage = vec![usize::MAX; a];
-    for (cage_idx, cage) in puzzle.cages.iter().enumerate() {
-        for &cell in &cage.cells {
-            let idx = cell.0 as usize;
-            if idx < a {
-                cell_to_cage[idx] = cage_idx;
-            }
-        }
-    }
-
-    // Find adjacent cage pairs
-    let mut tried_pairs: HashSet<(usize, usize)> = HashSet::new();
+    let graph = CageGraph::build(puzzle);
 
+    // Each adjacent pair is visited once, as (lower index, higher index).
     for (cage_a_idx, cage_a) in puzzle.cages.iter().enumerate() {
-        for &cell in &cage_a.cells {
-            let idx = cell.0 as usize;
-            let row = idx / n_usize;
-            let col = idx % n_usize;
-
-            // Check orthogonal neighbors
-            let neighbors = [
-                (row > 0).then(|| (row - 1) * n_usize + col),
-                (row + 1 < n_usize).then(|| (row + 1) * n_usize + col),
-                (col > 0).then(|| row * n_usize + (col - 1)),
-                (col + 1 < n_usize).then(|| row * n_usize + (col + 1)),
-            ];
-
-            for neighbor_idx in neighbors.into_iter().flatten() {
-                let cage_b_idx = cell_to_cage[neighbor_idx];
-                if cage_b_idx == usize::MAX || cage_b_idx == cage_a_idx {
-                    continue;
-                }
-
-                // Normalize pair order for deduplication
-                let pair = if cage_a_idx < cage_b_idx {
-                    (cage_a_idx, cage_b_idx)
-                } else {
-                    (cage_b_idx, cage_a_idx)
-                };
-
-                if tried_pairs.contains(&pair) {
-                    continue;
-                }
-                tried_pairs.insert(pair);
-
-                let cage_b = &puzzle.cages[cage_b_idx];
-
-                // Try to merge these cages
-                if let Some(merged) = try_merge_cages(n, cage_a, cage_b, solution, config) {
-                    return Some((pair.0, pair.1, merged));
-                }
+        for &cage_b_idx in graph.neighbors(cage_a_idx) {
+            if cage_b_idx < cage_a_idx || rejected_pairs.contains(&(cage_a_idx, cage_b_idx)) {
+                continue;
+            }
+            let cage_b = &puzzle.cages[cage_b_idx];
+            if let Some(merged) = try_merge_cages(puzzle.n, cage_a, cage_b, solution, config) {
+                return Some((cage_a_idx, cage_b_idx, merged));
             }
         }
     }
@@ -262,10 +329,11 @@ fn try_merge_cages(
     solution: &[u8],
     config: MinimizeConfig,
 ) -> Option<Cage> {
-    let mut cells: SmallVec<[CellId; 6]> =
+    let mut cells: SmallVec<[CellId; INLINE_CAGE_CELLS]> =
         SmallVec::with_capacity(cage_a.cells.len() + cage_b.cells.len());
     cells.extend(cage_a.cells.iter().copied());
     cells.extend(cage_b.cells.iter().copied());
+    cells.sort_unstable();
 
     // Check max cage size
     if cells.len() > config.rules.max_cage_size as usize {
@@ -273,10 +341,11 @@ fn try_merge_cages(
     }
 
     // Collect cell values from solution
-    let values: SmallVec<[u8; 6]> = cells.iter().map(|c| solution[c.0 as usize]).collect();
+    let values: SmallVec<[u8; INLINE_CAGE_CELLS]> =
+        cells.iter().map(|c| solution[c.0 as usize]).collect();
 
     // Determine operation and target
-    let (op, target) = choose_op_and_target(&values, config);
+    let (op, target) = choose_op_and_target(&values, config)?;
 
     // Build candidate cage
     let merged = Cage { cells, op, target };
@@ -289,36 +358,19 @@ fn try_merge_cages(
     Some(merged)
 }
 
-/// Choose operation and target for merged cage based on cell values.
-fn choose_op_and_target(values: &[u8], config: MinimizeConfig) -> (Op, i32) {
-    let len = values.len();
-
-    match len {
-        1 => (Op::Eq, values[0] as i32),
-        2 => {
-            let a = values[0];
-            let b = values[1];
-
-            // For 2-cell cages, we have more options
-            if config.prefer_add {
-                // Try Add first
-                (Op::Add, (a as i32) + (b as i32))
-            } else {
-                // Try Mul first
-                (Op::Mul, (a as i32) * (b as i32))
-            }
-        }
-        _ => {
-            // For 3+ cells, can only use Add or Mul (Sub/Div are 2-cell only)
-            if config.prefer_add {
-                let sum: i32 = values.iter().map(|&v| v as i32).sum();
-                (Op::Add, sum)
-            } else {
-                let prod: i32 = values.iter().fold(1, |acc, &v| acc * (v as i32));
-                (Op::Mul, prod)
-            }
-        }
-    }
+/// Choose operation and target for merged cage based on cell values: the preferred of Add and
+/// Mul, or the other when the preferred one's target exceeds `config.max_target`.
+fn choose_op_and_target(values: &[u8], config: MinimizeConfig) -> Option<(Op, i64)> {
+    // Merges always have 2+ cells; Sub/Div are never chosen for them.
+    let ops = match values.len() {
+        1 => [Op::Eq, Op::Eq],
+        _ if config.prefer_add => [Op::Add, Op::Mul],
+        _ => [Op::Mul, Op::Add],
+    };
+    let bounds = config.max_target.unwrap_or_default();
+    ops.into_iter()
+        .filter_map(|op| Some((op, target_for(op, values)?)))
+        .find(|&(op, target)| bounds.allows(op, target))
 }
 
 /// Apply a merge to produce a new puzzle.
@@ -346,7 +398,8 @@ fn apply_merge(puzzle: &Puzzle, cage_a_idx: usize, cage_b_idx: usize, merged: Ca
 #[cfg(all(test, feature = "gen-dlx"))]
 mod tests {
     use super::*;
-    use crate::generator::{GenerateConfig, generate};
+    use crate::generator::{GenerateConfig, generate, generate_with_stats};
+    use kenken_solver::count_solutions_up_to_with_deductions;
 
     #[test]
     fn minimizer_preserves_uniqueness() {
@@ -385,6 +438,37 @@ mod tests {
         );
     }
 
+    #[test]
+    fn a_rejected_pair_does_not_end_minimization() {
+        let min_cfg = MinimizeConfig::keen_baseline();
+        for seed in [12345, 54321, 7, 2026] {
+            let generated = generate(GenerateConfig::keen_baseline(5, seed)).unwrap();
+            let result =
+                minimize_puzzle(generated.puzzle.clone(), &generated.solution, min_cfg).unwrap();
+            let puzzle = &result.puzzle;
+            let graph = CageGraph::build(puzzle);
+            for (a, cage_a) in puzzle.cages.iter().enumerate() {
+                for &b in graph.neighbors(a).iter().filter(|&&b| b > a) {
+                    let cage_b = &puzzle.cages[b];
+                    let Some(merged) =
+                        try_merge_cages(puzzle.n, cage_a, cage_b, &generated.solution, min_cfg)
+                    else {
+                        continue;
+                    };
+                    let candidate = apply_merge(puzzle, a, b, merged);
+                    let count = count_solutions_up_to_with_deductions(
+                        &candidate,
+                        min_cfg.rules,
+                        min_cfg.tier,
+                        2,
+                    )
+                    .unwrap();
+                    assert_ne!(count, 1, "seed {seed}: cages {a} and {b} still merge");
+                }
+            }
+        }
+    }
+
     #[test]
     fn minimizer_handles_already_minimal_puzzle() {
         // Create a puzzle that's already "minimal" (all singletons)
@@ -423,4 +507,133 @@ mod tests {
         // Just verify the result is valid
         result.puzzle.validate(min_cfg.rules).unwrap();
     }
+
+    #[test]
+    fn at_most_easy_keeps_easy_puzzle_easy() {
+        let gen_cfg = GenerateConfig {
+            max_attempts: 10_000,
+            target_difficulty: Some(DifficultyTier::Easy),
+            difficulty_tolerance: 0,
+            ..GenerateConfig::keen_baseline(4, 2024)
+        };
+        let generated = generate_with_stats(gen_cfg).unwrap();
+        assert_eq!(
+            classify(&generated.puzzle, gen_cfg.rules).unwrap(),
+            DifficultyTier::Easy
+        );
+
+        let min_cfg = MinimizeConfig {
+            target_difficulty: Some((DifficultyTier::Easy, DifficultyGoal::AtMost)),
+            ..MinimizeConfig::keen_baseline()
+        };
+        let result = minimize_puzzle(generated.puzzle, &generated.solution, min_cfg).unwrap();
+
+        assert_eq!(result.final_difficulty, Some(DifficultyTier::Easy));
+        assert_eq!(result.difficulty_goal_met, Some(true));
+        assert_eq!(
+            classify(&result.puzzle, min_cfg.rules).unwrap(),
+            DifficultyTier::Easy
+        );
+    }
+
+    #[test]
+    fn at_least_normal_reaches_goal_or_reports_failure() {
+        let gen_cfg = GenerateConfig {
+            max_attempts: 10_000,
+            target_difficulty: Some(DifficultyTier::Easy),
+            difficulty_tolerance: 0,
+            ..GenerateConfig::keen_baseline(4, 2024)
+        };
+        let generated = generate_with_stats(gen_cfg).unwrap();
+
+        let min_cfg = MinimizeConfig {
+            target_difficulty: Some((DifficultyTier::Normal, DifficultyGoal::AtLeast)),
+            ..MinimizeConfig::keen_baseline()
+        };
+        let result = minimize_puzzle(generated.puzzle, &generated.solution, min_cfg).unwrap();
+        let actual = classify(&result.puzzle, min_cfg.rules).unwrap();
+
+        assert_eq!(result.final_difficulty, Some(actual));
+        match result.difficulty_goal_met {
+            Some(true) => assert!(difficulty_ordinal(actual) >= 1, "got {actual:?}"),
+            Some(false) => assert_eq!(actual, DifficultyTier::Easy),
+            None => panic!("goal was set, so goal status must be reported"),
+        }
+        assert_eq!(
+            count_solutions_up_to_with_deductions(&result.puzzle, min_cfg.rules, min_cfg.tier, 2)
+                .unwrap(),
+            1
+        );
+    }
+
+    #[test]
+    fn no_goal_skips_classification() {
+        let generated = generate(GenerateConfig::keen_baseline(4, 7)).unwrap();
+        let result = minimize_puzzle(
+            generated.puzzle,
+            &generated.solution,
+            MinimizeConfig::keen_baseline(),
+        )
+        .unwrap();
+        assert_eq!(result.final_difficulty, None);
+        assert_eq!(result.difficulty_goal_met, None);
+        assert_eq!(result.merges_rejected_for_difficulty, 0);
+    }
+
+    #[test]
+    fn warm_start_minimizes_identically_with_fewer_nodes() {
+        let (mut warm_nodes, mut cold_nodes) = (0u64, 0u64);
+        for seed in 0..12 {
+            let generated = generate(GenerateConfig::keen_baseline(5, seed)).unwrap();
+            let minimize = |warm_start| {
+                let config = MinimizeConfig {
+                    warm_start,
+                    ..MinimizeConfig::keen_baseline()
+                };
+                minimize_puzzle(generated.puzzle.clone(), &generated.solution, config).unwrap()
+            };
+            let (warm, cold) = (minimize(true), minimize(false));
+            assert_eq!(warm.puzzle, cold.puzzle, "seed {seed}");
+            assert_eq!(warm.merges_performed, cold.merges_performed, "seed {seed}");
+            assert_eq!(warm.merges_rejected, cold.merges_rejected, "seed {seed}");
+            warm_nodes += warm.uniqueness_nodes;
+            cold_nodes += cold.uniqueness_nodes;
+        }
+        assert!(
+            warm_nodes < cold_nodes,
+            "warm {warm_nodes} vs cold {cold_nodes} nodes"
+        );
+    }
+
+    #[test]
+    fn bounded_merges_fall_back_to_mul_or_are_skipped() {
+        let bounds = ClueBounds {
+            add_max: 12,
+            mul_max: 60,
+        };
+        let config = MinimizeConfig {
+            max_target: Some(bounds),
+            ..MinimizeConfig::keen_baseline()
+        };
+        assert_eq!(
+            choose_op_and_target(&[1, 5, 6], config),
+            Some((Op::Add, 12))
+        );
+        assert_eq!(
+            choose_op_and_target(&[2, 6, 5], config),
+            Some((Op::Mul, 60))
+        );
+        assert_eq!(choose_op_and_target(&[5, 4, 6], config), None);
+
+        let generated = generate_with_stats(GenerateConfig {
+            max_target: Some(bounds),
+            ..GenerateConfig::keen_baseline(5, 3)
+        })
+        .unwrap();
+        let result = minimize_puzzle(generated.puzzle, &generated.solution, config).unwrap();
+        assert!(result.merges_performed > 0);
+        for cage in &result.puzzle.cages {
+            assert!(bounds.allows(cage.op, cage.target), "{cage:?}");
+        }
+    }
 }
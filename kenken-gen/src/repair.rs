@@ -0,0 +1,253 @@
+//! Clue repair suggestions for puzzles that do not solve, such as imports with a mistyped target.
+//!
+//! Every cage is tried with nearby clues: its target moved by up to
+//! [`MAX_REPAIR_DISTANCE`], a `Mul` or `Div` target multiplied or divided by a small factor
+//! (a product mistyped as twice its value lands far from the original), and its op swapped for
+//! its partner (`Add` with `Mul`, `Sub` with `Div`) at the same or a nearby target.
+//! Candidates run cheapest first, one cage edited at a time, each checked by a solution count
+//! capped at 2 under a node budget.
+
+use std::collections::HashSet;
+
+use kenken_core::Puzzle;
+use kenken_core::rules::{Op, Ruleset};
+use kenken_solver::{DeductionTier, ResumableOutcome, count_solutions_resumable};
+
+use crate::GenError;
+
+/// Furthest a suggested clue may be from the original: the target's absolute change, plus one
+/// for an op swap. A `Mul` or `Div` target multiplied or divided by `k` is at distance `k`,
+/// whichever is nearer when both edits reach it.
+pub const MAX_REPAIR_DISTANCE: i64 = 10;
+
+/// One suggested clue edit and what it does to the puzzle.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ClueRepair {
+    /// Index of the edited cage in [`Puzzle::cages`].
+    pub cage: usize,
+    pub op: Op,
+    pub target: i64,
+    /// Solutions of the edited puzzle, capped at 2 (2 means two or more).
+    pub solutions: u32,
+    /// Edit distance from the original clue; see [`MAX_REPAIR_DISTANCE`].
+    pub distance: i64,
+}
+
+/// The op a cage's clue may be swapped to: `Add` and `Mul` trade places, as do `Sub` and `Div`.
+fn swapped_op(op: Op) -> Option<Op> {
+    match op {
+        Op::Add => Some(Op::Mul),
+        Op::Mul => Some(Op::Add),
+        Op::Sub => Some(Op::Div),
+        Op::Div => Some(Op::Sub),
+        Op::Eq => None,
+    }
+}
+
+/// Single-clue edits of `puzzle` that make it solvable, nearest first.
+///
+/// Candidates are ordered by distance, then cage index, then op (the cage's own before its
+/// swap), then target, and only clues inside the cage's
+/// [`target_bounds_with_rules`](kenken_core::Cage::target_bounds_with_rules) are tried. Each is
+/// counted up to 2 solutions at `tier`, giving up after `budget` search nodes (0 for no limit);
+/// a candidate whose count runs out of budget is left out, as is one with no solution. The
+/// search stops once `max_suggestions` of the returned repairs make the puzzle unique; repairs
+/// leaving several solutions are returned too, in candidate order, but do not count toward the
+/// limit.
+///
+/// Nothing requires `puzzle` to be unsolvable: on a solvable one the suggestions are its
+/// nearby alternatives.
+pub fn suggest_clue_repairs(
+    puzzle: &Puzzle,
+    rules: Ruleset,
+    tier: DeductionTier,
+    max_suggestions: usize,
+    budget: u64,
+) -> Result<Vec<ClueRepair>, GenError> {
+    puzzle.validate(rules)?;
+    let mut candidates = Vec::new();
+    for (cage_idx, cage) in puzzle.cages.iter().enumerate() {
+        // A swap keeps the cage's size, which its partner op accepts whenever its own op does.
+        for (rank, op) in [Some(cage.op), swapped_op(cage.op)]
+            .into_iter()
+            .flatten()
+            .enumerate()
+        {
+            let penalty = rank as i64;
+            let mut edited = cage.clone();
+            edited.op = op;
+            let (min, max) = edited.target_bounds_with_rules(puzzle.n, rules)?;
+            // N-ary `Sub` bounds reach below 1, a target no puzzle may show.
+            let min = min.max(1);
+            let reach = MAX_REPAIR_DISTANCE - penalty;
+            for target in (cage.target - reach).max(min)..=(cage.target + reach).min(max) {
+                if op == cage.op && target == cage.target {
+                    continue;
+                }
+                let distance = (target - cage.target).abs() + penalty;
+                candidates.push((distance, cage_idx, rank, op, target));
+            }
+            if rank == 0 && matches!(op, Op::Mul | Op::Div) {
+                for k in 2..=MAX_REPAIR_DISTANCE {
+                    let scaled = [
+                        Some(cage.target.saturating_mul(k)),
+                        (cage.target % k == 0).then(|| cage.target / k),
+                    ];
+                    for target in scaled.into_iter().flatten() {
+                        if (min..=max).contains(&target) {
+                            candidates.push((k, cage_idx, rank, op, target));
+                        }
+                    }
+                }
+            }
+        }
+    }
+    candidates
+        .sort_unstable_by_key(|&(distance, cage, rank, _, target)| (distance, cage, rank, target));
+    // A target reached both by a shift and by a factor keeps its nearer distance.
+    let mut seen = HashSet::new();
+    candidates.retain(|&(_, cage, rank, _, target)| seen.insert((cage, rank, target)));
+
+    let mut repairs = Vec::new();
+    let mut unique = 0;
+    let mut edited = puzzle.clone();
+    for (distance, cage, _, op, target) in candidates {
+        if unique >= max_suggestions {
+            break;
+        }
+        let original = &puzzle.cages[cage];
+        edited.cages[cage].op = op;
+        edited.cages[cage].target = target;
+        let outcome = count_solutions_resumable(&edited, rules, tier, 2, None, budget)?;
+        edited.cages[cage].op = original.op;
+        edited.cages[cage].target = original.target;
+        let ResumableOutcome::Complete { count, .. } = outcome else {
+            continue;
+        };
+        if count == 0 {
+            continue;
+        }
+        unique += usize::from(count == 1);
+        repairs.push(ClueRepair {
+            cage,
+            op,
+            target,
+            solutions: count,
+            distance,
+        });
+    }
+    Ok(repairs)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use kenken_core::{Cage, CellId};
+    use kenken_solver::count_solutions_up_to_with_deductions;
+
+    fn cage(cells: &[u16], op: Op, target: i64) -> Cage {
+        Cage {
+            cells: cells.iter().copied().map(CellId).collect(),
+            op,
+            target,
+        }
+    }
+
+    /// Unique, over 2 1 3 4 / 3 4 2 1 / 4 3 1 2 / 1 2 4 3.
+    fn golden() -> Puzzle {
+        Puzzle {
+            n: 4,
+            cages: vec![
+                cage(&[0, 1, 2], Op::Add, 6),
+                cage(&[3, 7], Op::Add, 5),
+                cage(&[4, 5, 9], Op::Mul, 36),
+                cage(&[6, 10], Op::Sub, 1),
+                cage(&[8, 12], Op::Sub, 3),
+                cage(&[13, 14], Op::Mul, 8),
+                cage(&[11, 15], Op::Add, 5),
+            ],
+        }
+    }
+
+    fn unsolvable(puzzle: &Puzzle) -> bool {
+        count_solutions_up_to_with_deductions(
+            puzzle,
+            Ruleset::keen_baseline(),
+            DeductionTier::Hard,
+            2,
+        )
+        .unwrap()
+            == 0
+    }
+
+    #[test]
+    fn a_mistyped_add_target_is_repaired_back() {
+        let mut puzzle = golden();
+        puzzle.cages[1].target += 1;
+        assert!(unsolvable(&puzzle));
+
+        let repairs = suggest_clue_repairs(
+            &puzzle,
+            Ruleset::keen_baseline(),
+            DeductionTier::Hard,
+            3,
+            10_000,
+        )
+        .unwrap();
+        assert_eq!(
+            repairs[0],
+            ClueRepair {
+                cage: 1,
+                op: Op::Add,
+                target: 5,
+                solutions: 1,
+                distance: 1,
+            }
+        );
+        assert!(repairs.is_sorted_by_key(|r| r.distance));
+        assert_eq!(repairs.iter().filter(|r| r.solutions == 1).count(), 3);
+        assert!(repairs.iter().all(|r| r.solutions > 0));
+    }
+
+    #[test]
+    fn a_doubled_product_is_repaired_by_its_factor() {
+        let mut puzzle = golden();
+        puzzle.cages[5].target = 16;
+        assert!(unsolvable(&puzzle));
+
+        let repairs =
+            suggest_clue_repairs(&puzzle, Ruleset::keen_baseline(), DeductionTier::Hard, 1, 0)
+                .unwrap();
+        assert_eq!(
+            repairs,
+            [ClueRepair {
+                cage: 5,
+                op: Op::Mul,
+                target: 8,
+                solutions: 1,
+                distance: 2,
+            }]
+        );
+    }
+
+    #[test]
+    fn a_swapped_op_is_repaired_back() {
+        let mut puzzle = golden();
+        puzzle.cages[5].op = Op::Add;
+        assert!(unsolvable(&puzzle));
+
+        let repairs =
+            suggest_clue_repairs(&puzzle, Ruleset::keen_baseline(), DeductionTier::Hard, 1, 0)
+                .unwrap();
+        assert_eq!(
+            repairs,
+            [ClueRepair {
+                cage: 5,
+                op: Op::Mul,
+                target: 8,
+                solutions: 1,
+                distance: 1,
+            }]
+        );
+    }
+}
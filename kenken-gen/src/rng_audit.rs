@@ -0,0 +1,237 @@
+//! RNG draw auditing (`rng-audit`): how much randomness each generation stage consumed.
+//!
+//! The output-pinning goldens say *that* a seed now yields a different puzzle; the draw counts
+//! here say *where*. With the feature on, every stage of the default pipeline draws through an
+//! [`AuditedRng`] tagged with its [`RngStage`], and [`record`] collects the draws made on the
+//! calling thread while a closure runs, typically [`crate::replay_attempt`] for one
+//! `(n, seed, attempt)`. With the feature off the stages draw from ChaCha directly.
+//!
+//! Counts are of core draws (`next_u32`, `next_u64`, `fill_bytes`), the calls every `Rng`
+//! method bottoms out in, so a new `random_bool` or a wider `random_range` shows up as a
+//! changed count for its stage.
+
+use std::cell::RefCell;
+
+use rand::RngCore;
+
+/// A stage of the generation pipeline that draws randomness.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum RngStage {
+    /// The solution grid: the Latin square's permutations, or a rows-only grid's row shuffles.
+    Solution,
+    /// The random cage partition.
+    Partition,
+    /// Op and target assignment, over every retry.
+    Ops,
+}
+
+impl RngStage {
+    /// Every stage, in pipeline order.
+    pub const ALL: [RngStage; 3] = [RngStage::Solution, RngStage::Partition, RngStage::Ops];
+}
+
+/// Core draws made through [`AuditedRng`]s of one stage.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct DrawCounts {
+    /// `next_u32` calls.
+    pub u32_draws: u64,
+    /// `next_u64` calls.
+    pub u64_draws: u64,
+    /// Bytes requested through `fill_bytes`.
+    pub fill_bytes: u64,
+}
+
+/// Draw counts per stage, as [`record`] collects them.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct DrawLog {
+    /// [`RngStage::Solution`] draws.
+    pub solution: DrawCounts,
+    /// [`RngStage::Partition`] draws.
+    pub partition: DrawCounts,
+    /// [`RngStage::Ops`] draws.
+    pub ops: DrawCounts,
+}
+
+impl DrawLog {
+    /// The counts of `stage`.
+    pub fn stage(&self, stage: RngStage) -> DrawCounts {
+        match stage {
+            RngStage::Solution => self.solution,
+            RngStage::Partition => self.partition,
+            RngStage::Ops => self.ops,
+        }
+    }
+
+    fn stage_mut(&mut self, stage: RngStage) -> &mut DrawCounts {
+        match stage {
+            RngStage::Solution => &mut self.solution,
+            RngStage::Partition => &mut self.partition,
+            RngStage::Ops => &mut self.ops,
+        }
+    }
+
+    /// One line per stage whose counts differ from `expected`, empty when none do.
+    pub fn diff(&self, expected: &DrawLog) -> Vec<String> {
+        RngStage::ALL
+            .into_iter()
+            .filter(|&stage| self.stage(stage) != expected.stage(stage))
+            .map(|stage| {
+                format!(
+                    "{stage:?}: got {:?}, expected {:?}",
+                    self.stage(stage),
+                    expected.stage(stage)
+                )
+            })
+            .collect()
+    }
+}
+
+thread_local! {
+    static LOG: RefCell<DrawLog> = RefCell::new(DrawLog::default());
+}
+
+/// Run `f` and return the draws its [`AuditedRng`]s made on this thread, starting from zero.
+/// Draws on other threads (a parallel count, say) are not seen. The log in place before the
+/// call is restored after it, so nested calls do not add to the outer one.
+pub fn record<T>(f: impl FnOnce() -> T) -> (T, DrawLog) {
+    let outer = LOG.take();
+    let out = f();
+    (out, LOG.replace(outer))
+}
+
+/// An RNG that counts the draws made through it against `stage`, then delegates to `inner`.
+/// It returns exactly what `inner` would, so auditing never changes what is generated.
+#[derive(Debug, Clone)]
+pub struct AuditedRng<R> {
+    inner: R,
+    stage: RngStage,
+}
+
+impl<R: RngCore> AuditedRng<R> {
+    pub fn new(inner: R, stage: RngStage) -> Self {
+        Self { inner, stage }
+    }
+
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+
+    fn count(&self, draw: impl FnOnce(&mut DrawCounts)) {
+        LOG.with_borrow_mut(|log| draw(log.stage_mut(self.stage)));
+    }
+}
+
+impl<R: RngCore> RngCore for AuditedRng<R> {
+    fn next_u32(&mut self) -> u32 {
+        self.count(|counts| counts.u32_draws += 1);
+        self.inner.next_u32()
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.count(|counts| counts.u64_draws += 1);
+        self.inner.next_u64()
+    }
+
+    fn fill_bytes(&mut self, dst: &mut [u8]) {
+        self.count(|counts| counts.fill_bytes += dst.len() as u64);
+        self.inner.fill_bytes(dst);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::seed::rng_from_u64;
+    use rand::Rng;
+    use rand::seq::SliceRandom;
+    use rand_chacha::ChaCha20Rng;
+
+    /// Runs `draw` on a plain and an audited ChaCha stream, checks they return the same
+    /// values, and checks the audited counts account for every 32-bit word consumed.
+    fn audit<T: PartialEq + std::fmt::Debug>(draw: impl Fn(&mut dyn RngCore) -> T) -> DrawCounts {
+        let mut plain = rng_from_u64(7);
+        let expected = draw(&mut plain);
+        let (got, log) = record(|| {
+            let mut audited = AuditedRng::new(rng_from_u64(7), RngStage::Ops);
+            let got = draw(&mut audited);
+            (got, audited.into_inner())
+        });
+        let (got, inner): (T, ChaCha20Rng) = got;
+        assert_eq!(got, expected);
+        assert_eq!(inner.get_word_pos(), plain.get_word_pos());
+        let counts = log.ops;
+        assert_eq!(
+            u128::from(counts.u32_draws + 2 * counts.u64_draws + counts.fill_bytes.div_ceil(4)),
+            plain.get_word_pos()
+        );
+        assert_eq!(log.solution, DrawCounts::default());
+        assert_eq!(log.partition, DrawCounts::default());
+        counts
+    }
+
+    #[test]
+    fn random_bool_is_counted() {
+        let counts = audit(|rng| (0..10).map(|_| rng.random_bool(0.55)).collect::<Vec<_>>());
+        assert_eq!(counts.u32_draws + counts.u64_draws, 10);
+    }
+
+    #[test]
+    fn shuffle_is_counted() {
+        let counts = audit(|rng| {
+            let mut values: Vec<u8> = (1..=9).collect();
+            values.shuffle(rng);
+            values
+        });
+        assert!(counts.u32_draws + counts.u64_draws > 0);
+    }
+
+    #[test]
+    fn random_ranges_are_counted() {
+        audit(|rng| {
+            (0..10)
+                .map(|len| rng.random_range(0..=len))
+                .collect::<Vec<usize>>()
+        });
+        audit(|rng| {
+            (1..10u8)
+                .map(|n| rng.random_range(1..=n))
+                .collect::<Vec<_>>()
+        });
+        audit(|rng| rng.random_range(0..u64::MAX));
+    }
+
+    #[test]
+    fn fill_bytes_is_counted() {
+        let counts = audit(|rng| {
+            let mut buf = [0u8; 12];
+            rng.fill_bytes(&mut buf);
+            buf
+        });
+        assert_eq!(counts.fill_bytes, 12);
+    }
+
+    #[test]
+    fn record_separates_stages_and_restores_the_outer_log() {
+        let ((), outer) = record(|| {
+            AuditedRng::new(rng_from_u64(1), RngStage::Partition).next_u32();
+            let ((), inner) = record(|| {
+                AuditedRng::new(rng_from_u64(1), RngStage::Solution).next_u64();
+            });
+            assert_eq!(inner.solution.u64_draws, 1);
+            assert_eq!(inner.partition, DrawCounts::default());
+        });
+        assert_eq!(outer.partition.u32_draws, 1);
+        assert_eq!(outer.solution, DrawCounts::default());
+    }
+
+    #[test]
+    fn diff_names_the_changed_stage() {
+        let expected = DrawLog::default();
+        let mut got = expected;
+        got.partition.u32_draws = 3;
+        let diff = got.diff(&expected);
+        assert_eq!(diff.len(), 1);
+        assert!(diff[0].starts_with("Partition:"), "{diff:?}");
+        assert!(expected.diff(&expected).is_empty());
+    }
+}
@@ -0,0 +1,352 @@
+//! Streaming generation: puzzles handed to a sink one by one as they are accepted, so a
+//! long-running caller can persist each before the next is done.
+//!
+//! Puzzle `i` of a stream is [`generate_with_stats`] under the stream's config with its seed
+//! replaced by [`stream_seed`]`(config.seed, i)`, so every index replays on its own. With
+//! `parallel-rayon`, one puzzle per idle pool thread is generated at once; a puzzle finished
+//! ahead of an earlier index waits in a reorder buffer while its thread moves on to the next
+//! undispatched index. The sink runs on the caller's thread and sees the same puzzles in the
+//! same order either way.
+
+use std::ops::ControlFlow;
+use std::panic::{AssertUnwindSafe, catch_unwind};
+
+use crate::GenError;
+use crate::generator::{
+    GenAttemptStats, GenerateConfig, GeneratedPuzzleWithStats, generate_with_stats,
+};
+
+/// How a [`generate_stream`] run ended.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StreamEnd {
+    /// Every requested puzzle was delivered.
+    Complete,
+    /// The sink returned [`ControlFlow::Break`] for the last delivered puzzle.
+    Stopped,
+    /// Puzzle `index` exhausted its attempts after `attempts` of them; nothing from that index
+    /// on was delivered.
+    Exhausted { index: u32, attempts: u32 },
+    /// The sink panicked on puzzle `index`, which is not counted as delivered. Only seen in
+    /// the summary carried by [`GenError::SinkPanicked`].
+    SinkPanicked { index: u32 },
+}
+
+/// What a [`generate_stream`] run delivered.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StreamSummary {
+    /// Generation attempts of each delivered puzzle, by index.
+    pub attempts: Vec<u32>,
+    /// Rejection counters and search work merged over every delivered puzzle, plus the
+    /// exhausted one when the stream ran out of attempts.
+    pub attempt_stats: GenAttemptStats,
+    pub end: StreamEnd,
+}
+
+impl StreamSummary {
+    /// Puzzles the sink accepted, indices `0..delivered()`.
+    pub fn delivered(&self) -> u32 {
+        self.attempts.len() as u32
+    }
+}
+
+/// Generator seed of puzzle `index` in a stream seeded with `seed` (SplitMix64's finalizer
+/// over both).
+pub fn stream_seed(seed: u64, index: u32) -> u64 {
+    let mut z = seed.wrapping_add((u64::from(index) + 1).wrapping_mul(0x9E37_79B9_7F4A_7C15));
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^ (z >> 31)
+}
+
+/// Generate up to `count` puzzles under `config`, handing each to `sink` in index order as
+/// soon as it and every earlier one are accepted. The stream stops early when `sink` returns
+/// [`ControlFlow::Break`] or a puzzle exhausts `config.max_attempts`; [`StreamSummary::end`]
+/// says which.
+///
+/// # Errors
+/// Any generation error but exhaustion, such as [`GenError::DlxRequired`]; and
+/// [`GenError::SinkPanicked`] when `sink` panics, carrying the summary of the puzzles it had
+/// already accepted.
+pub fn generate_stream(
+    config: GenerateConfig,
+    count: u32,
+    mut sink: impl FnMut(GeneratedPuzzleWithStats) -> ControlFlow<()>,
+) -> Result<StreamSummary, GenError> {
+    let mut summary = StreamSummary {
+        attempts: Vec::new(),
+        attempt_stats: GenAttemptStats::default(),
+        end: StreamEnd::Complete,
+    };
+    let flow = in_index_order(
+        count,
+        |index| generate_with_stats(stream_config(config, index)),
+        |index, generated| {
+            let generated = match generated {
+                Ok(generated) => generated,
+                Err(GenError::AttemptsExhaustedWithStats { attempts, stats }) => {
+                    summary.attempt_stats.merge(&stats);
+                    summary.end = StreamEnd::Exhausted { index, attempts };
+                    return ControlFlow::Break(Halt::Ended);
+                }
+                Err(e) => return ControlFlow::Break(Halt::Failed(e)),
+            };
+            let (attempts, stats) = (generated.attempts, generated.attempt_stats);
+            match catch_unwind(AssertUnwindSafe(|| sink(generated))) {
+                Ok(flow) => {
+                    summary.attempts.push(attempts);
+                    summary.attempt_stats.merge(&stats);
+                    if flow.is_break() {
+                        summary.end = StreamEnd::Stopped;
+                        return ControlFlow::Break(Halt::Ended);
+                    }
+                    ControlFlow::Continue(())
+                }
+                Err(payload) => {
+                    summary.end = StreamEnd::SinkPanicked { index };
+                    let message = payload
+                        .downcast_ref::<&str>()
+                        .map(|s| s.to_string())
+                        .or_else(|| payload.downcast_ref::<String>().cloned())
+                        .unwrap_or_default();
+                    ControlFlow::Break(Halt::SinkPanicked(message))
+                }
+            }
+        },
+    );
+    match flow {
+        ControlFlow::Break(Halt::Failed(e)) => Err(e),
+        ControlFlow::Break(Halt::SinkPanicked(message)) => Err(GenError::SinkPanicked {
+            summary: Box::new(summary),
+            message,
+        }),
+        ControlFlow::Continue(()) | ControlFlow::Break(Halt::Ended) => Ok(summary),
+    }
+}
+
+/// Why delivery stopped before the last index.
+enum Halt {
+    /// [`StreamSummary::end`] says why.
+    Ended,
+    Failed(GenError),
+    SinkPanicked(String),
+}
+
+fn stream_config(config: GenerateConfig, index: u32) -> GenerateConfig {
+    GenerateConfig {
+        seed: stream_seed(config.seed, index),
+        ..config
+    }
+}
+
+/// Puzzles generated ahead of delivery at once. A caller on a pool thread blocks it while it
+/// waits, so that thread is not counted.
+#[cfg(feature = "parallel-rayon")]
+fn max_in_flight() -> u32 {
+    let threads = rayon::current_num_threads();
+    let idle = threads.saturating_sub(usize::from(rayon::current_thread_index().is_some()));
+    idle.min(u32::MAX as usize) as u32
+}
+
+/// Hand `job(index)` for each index in `0..count` to `deliver` in index order, until `deliver`
+/// breaks.
+///
+/// Jobs run on the rayon pool, [`max_in_flight`] at a time. Results that finish ahead of an
+/// earlier index wait in a reorder buffer; each delivery frees a slot, which goes to the next
+/// undispatched index, so one slow job holds back delivery but not the other slots. A
+/// panicking job is resumed on the caller once its index is due.
+#[cfg(feature = "parallel-rayon")]
+fn in_index_order<T: Send, B>(
+    count: u32,
+    job: impl Fn(u32) -> T + Sync,
+    mut deliver: impl FnMut(u32, T) -> ControlFlow<B>,
+) -> ControlFlow<B> {
+    use std::collections::BTreeMap;
+    use std::panic::resume_unwind;
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::sync::mpsc;
+
+    let window = max_in_flight();
+    if window <= 1 {
+        return (0..count).try_for_each(|index| deliver(index, job(index)));
+    }
+    // Set once delivery ends, so jobs still queued return without running.
+    let stopped = AtomicBool::new(false);
+    let (done, finished) = mpsc::channel();
+    rayon::in_place_scope(|scope| {
+        let dispatch = |index: u32| {
+            let (done, job, stopped) = (done.clone(), &job, &stopped);
+            scope.spawn(move |_| {
+                if !stopped.load(Ordering::Relaxed) {
+                    let _ = done.send((index, catch_unwind(AssertUnwindSafe(|| job(index)))));
+                }
+            });
+        };
+        let mut dispatched = count.min(window);
+        (0..dispatched).for_each(dispatch);
+        let mut ready = BTreeMap::new();
+        let mut next = 0;
+        let flow = 'deliver: loop {
+            if next == count {
+                break ControlFlow::Continue(());
+            }
+            let (index, result) = finished.recv().expect("the scope holds a sender");
+            ready.insert(index, result);
+            while let Some(result) = ready.remove(&next) {
+                let value = result.unwrap_or_else(|payload| {
+                    stopped.store(true, Ordering::Relaxed);
+                    resume_unwind(payload)
+                });
+                if let ControlFlow::Break(b) = deliver(next, value) {
+                    break 'deliver ControlFlow::Break(b);
+                }
+                next += 1;
+                if dispatched < count {
+                    dispatch(dispatched);
+                    dispatched += 1;
+                }
+            }
+        };
+        stopped.store(true, Ordering::Relaxed);
+        flow
+    })
+}
+
+#[cfg(not(feature = "parallel-rayon"))]
+fn in_index_order<T, B>(
+    count: u32,
+    job: impl Fn(u32) -> T,
+    mut deliver: impl FnMut(u32, T) -> ControlFlow<B>,
+) -> ControlFlow<B> {
+    (0..count).try_for_each(|index| deliver(index, job(index)))
+}
+
+#[cfg(all(test, feature = "gen-dlx"))]
+mod tests {
+    use super::*;
+    use kenken_solver::DifficultyTier;
+
+    fn config() -> GenerateConfig {
+        GenerateConfig::keen_baseline(4, 2026)
+    }
+
+    #[test]
+    fn each_index_replays_on_its_own() {
+        let mut streamed = Vec::new();
+        let summary = generate_stream(config(), 6, |generated| {
+            streamed.push(generated);
+            ControlFlow::Continue(())
+        })
+        .unwrap();
+        assert_eq!(summary.end, StreamEnd::Complete);
+        assert_eq!(summary.delivered(), 6);
+
+        let mut partitions = 0;
+        for (index, generated) in (0..).zip(&streamed) {
+            let alone = generate_with_stats(stream_config(config(), index)).unwrap();
+            assert_eq!(generated.puzzle, alone.puzzle, "index {index}");
+            assert_eq!(generated.solution, alone.solution, "index {index}");
+            assert_eq!(summary.attempts[index as usize], alone.attempts);
+            partitions += alone.attempt_stats.partitions_tried;
+        }
+        assert_eq!(summary.attempt_stats.partitions_tried, partitions);
+        let seeds: std::collections::HashSet<_> = (0..6).map(|i| stream_seed(2026, i)).collect();
+        assert_eq!(seeds.len(), 6);
+    }
+
+    #[test]
+    fn a_break_stops_the_stream_after_that_puzzle() {
+        let mut seen = 0;
+        let summary = generate_stream(config(), 10, |_| {
+            seen += 1;
+            if seen == 3 {
+                ControlFlow::Break(())
+            } else {
+                ControlFlow::Continue(())
+            }
+        })
+        .unwrap();
+        assert_eq!(seen, 3);
+        assert_eq!(summary.end, StreamEnd::Stopped);
+        assert_eq!(summary.delivered(), 3);
+    }
+
+    #[test]
+    fn a_sink_panic_returns_the_delivered_summary() {
+        let mut seen = 0;
+        let result = generate_stream(config(), 5, |_| {
+            seen += 1;
+            assert!(seen < 3, "disk full");
+            ControlFlow::Continue(())
+        });
+        let Err(GenError::SinkPanicked { summary, message }) = result else {
+            panic!("expected SinkPanicked, got {result:?}");
+        };
+        assert_eq!(summary.end, StreamEnd::SinkPanicked { index: 2 });
+        assert_eq!(summary.delivered(), 2);
+        assert!(message.contains("disk full"), "{message}");
+    }
+
+    #[test]
+    fn exhaustion_ends_the_stream_with_its_attempts() {
+        // 4x4 puzzles are never Unreasonable.
+        let config = GenerateConfig {
+            max_attempts: 2,
+            ..GenerateConfig::with_difficulty(4, 7, DifficultyTier::Unreasonable)
+        };
+        let mut seen = 0;
+        let summary = generate_stream(config, 3, |_| {
+            seen += 1;
+            ControlFlow::Continue(())
+        })
+        .unwrap();
+        assert_eq!(seen, 0);
+        assert_eq!(
+            summary.end,
+            StreamEnd::Exhausted {
+                index: 0,
+                attempts: 2
+            }
+        );
+        assert_eq!(summary.attempt_stats.partitions_tried, 2);
+    }
+
+    #[cfg(feature = "parallel-rayon")]
+    #[test]
+    fn a_later_index_finishing_first_waits_for_the_earlier_one() {
+        use std::sync::{Mutex, mpsc};
+
+        let pool = rayon::ThreadPoolBuilder::new()
+            .num_threads(4)
+            .build()
+            .unwrap();
+        let (one_done, wait_for_one) = mpsc::channel();
+        let wait_for_one = Mutex::new(wait_for_one);
+        let finished = Mutex::new(Vec::new());
+        let mut delivered = Vec::new();
+        let flow = pool.install(|| {
+            in_index_order(
+                8,
+                |index| {
+                    if index == 0 {
+                        wait_for_one.lock().unwrap().recv().unwrap();
+                    }
+                    finished.lock().unwrap().push(index);
+                    if index == 1 {
+                        one_done.send(()).unwrap();
+                    }
+                    index * 10
+                },
+                |index, value| {
+                    assert_eq!(value, index * 10);
+                    delivered.push(index);
+                    ControlFlow::<()>::Continue(())
+                },
+            )
+        });
+        assert!(flow.is_continue());
+        assert_eq!(delivered, (0..8).collect::<Vec<_>>());
+        let finished = finished.into_inner().unwrap();
+        let at = |index| finished.iter().position(|&i| i == index).unwrap();
+        assert!(at(1) < at(0), "finish order {finished:?}");
+    }
+}
@@ -0,0 +1,419 @@
+//! Generation algorithm V1, frozen (see [`GenAlgoVersion::V1`](super::GenAlgoVersion::V1)).
+//!
+//! Nothing in this file may change what it returns for any input. The default pipeline in
+//! [`crate::generator`] shares these steps while it still produces the same output; an
+//! improvement to a step is written as a new function there, under a new algorithm version,
+//! and this copy stays as it is.
+
+#![allow(clippy::needless_range_loop)]
+
+use kenken_core::arith::{applicable_ops, target_for};
+use kenken_core::limits::INLINE_CAGE_CELLS;
+use kenken_core::puzzle::{cage_tuple_count, validate_grid_size};
+use kenken_core::rules::{Op, Ruleset};
+use kenken_core::{Cage, CellId, Puzzle};
+use kenken_solver::{CountStrategy, SearchOptions, count_solutions_up_to_with_strategy};
+use rand::Rng;
+use rand::seq::SliceRandom;
+use rand_chacha::ChaCha20Rng;
+use smallvec::SmallVec;
+
+use crate::GenError;
+use crate::generator::{GenerateConfig, GeneratedPuzzle};
+use crate::seed::rng_from_u64;
+
+#[cfg(feature = "telemetry-tracing")]
+use tracing::trace;
+
+#[cfg(not(feature = "telemetry-tracing"))]
+macro_rules! trace {
+    ($($tt:tt)*) => {};
+}
+
+/// Op assignments V1 tries per partition, whatever `ops_retries_per_partition` says:
+/// `keen_baseline`'s default when V1 was frozen.
+const OPS_RETRIES: u32 = 4;
+
+pub(crate) fn generate(config: GenerateConfig) -> Result<GeneratedPuzzle, GenError> {
+    validate_grid_size(config.n)?;
+    let mut rng = rng_from_u64(config.seed);
+
+    trace!(
+        n = config.n,
+        seed = config.seed,
+        max_attempts = config.max_attempts,
+        "gen.start"
+    );
+
+    for attempt in 0..config.max_attempts {
+        let attempt_seed = attempt_seed(config.seed, attempt);
+        trace!(attempt, attempt_seed, "gen.attempt");
+        let solution = latin_solution_seeded(config.n, attempt_seed)?;
+
+        let Some(partition) =
+            random_cage_partition(config.n, config.rules, config.domino_probability, &mut rng)
+        else {
+            continue;
+        };
+
+        for retry in 0..OPS_RETRIES {
+            let mut retry_rng;
+            let ops_rng = if retry == 0 {
+                &mut rng
+            } else {
+                retry_rng = ops_retry_rng(attempt_seed, retry);
+                &mut retry_rng
+            };
+            let puzzle =
+                assign_ops_and_targets(config.n, &solution, &partition, config.rules, ops_rng)?;
+            if too_loose(&puzzle, &config)? {
+                trace!(attempt, retry, "gen.constraint_score_rejected");
+                continue;
+            }
+
+            // Diverging from `solution` changes only the nodes spent, never the count.
+            let (count, _) = count_solutions_up_to_with_strategy(
+                &puzzle,
+                config.rules,
+                config.tier,
+                2,
+                CountStrategy::DivergeFrom(&solution),
+                SearchOptions::default(),
+            )?;
+            if count == 1 {
+                trace!(attempt, retry, "gen.accept");
+                return Ok(GeneratedPuzzle { puzzle, solution });
+            }
+        }
+    }
+
+    Err(GenError::AttemptsExhausted {
+        attempts: config.max_attempts,
+    })
+}
+
+/// Seed of the Latin square (and of later op retries) for `attempt`.
+pub(crate) fn attempt_seed(seed: u64, attempt: u32) -> u64 {
+    seed ^ ((attempt as u64).wrapping_mul(0x9E37_79B9_7F4A_7C15))
+}
+
+#[cfg(feature = "gen-dlx")]
+pub(crate) fn latin_solution_seeded(n: u8, seed: u64) -> Result<Vec<u8>, GenError> {
+    let base = least_latin_square(n);
+
+    // The base square is always the same; add variety via group actions
+    // (row/col/symbol permutations) under a deterministic RNG stream.
+    let mut rng = rng_from_u64(seed);
+    Ok(permute_latin(n, &base, &mut rng))
+}
+
+#[cfg(not(feature = "gen-dlx"))]
+pub(crate) fn latin_solution_seeded(_n: u8, _seed: u64) -> Result<Vec<u8>, GenError> {
+    Err(GenError::DlxRequired)
+}
+
+/// The lexicographically least Latin square of order `n`, read row-major. This is the first
+/// cover `kenken_solver::dlx_latin::solve_latin_one` finds for an empty grid, which V1
+/// originally permuted; building it directly keeps V1 independent of the DLX search order.
+#[cfg(feature = "gen-dlx")]
+pub(crate) fn least_latin_square(n: u8) -> Vec<u8> {
+    let n = n as usize;
+    let mut grid = vec![0u8; n * n];
+    // Bit `v` is set when digit `v` is used in that row / column.
+    let mut row_used = vec![0u32; n];
+    let mut col_used = vec![0u32; n];
+    let mut idx = 0;
+    while idx < n * n {
+        let (r, c) = (idx / n, idx % n);
+        let prev = grid[idx];
+        if prev != 0 {
+            row_used[r] &= !(1 << prev);
+            col_used[c] &= !(1 << prev);
+        }
+        let used = row_used[r] | col_used[c];
+        match (prev + 1..=n as u8).find(|&v| used & (1 << v) == 0) {
+            Some(v) => {
+                grid[idx] = v;
+                row_used[r] |= 1 << v;
+                col_used[c] |= 1 << v;
+                idx += 1;
+            }
+            None => {
+                // Cell 0 always takes 1, so this never backtracks past the start.
+                grid[idx] = 0;
+                idx -= 1;
+            }
+        }
+    }
+    grid
+}
+
+#[cfg(feature = "gen-dlx")]
+pub(crate) fn permute_latin<R: Rng + ?Sized>(n: u8, grid: &[u8], rng: &mut R) -> Vec<u8> {
+    let n_usize = n as usize;
+    let a = n_usize * n_usize;
+    assert_eq!(grid.len(), a);
+
+    let mut rows: Vec<usize> = (0..n_usize).collect();
+    let mut cols: Vec<usize> = (0..n_usize).collect();
+    rows.shuffle(rng);
+    cols.shuffle(rng);
+
+    let mut syms: Vec<u8> = (1..=n).collect();
+    syms.shuffle(rng);
+    let mut map = vec![0u8; n_usize + 1];
+    for (from0, &to) in syms.iter().enumerate() {
+        map[from0 + 1] = to;
+    }
+
+    let mut out = vec![0u8; a];
+    for r in 0..n_usize {
+        for c in 0..n_usize {
+            let v = grid[rows[r] * n_usize + cols[c]];
+            out[r * n_usize + c] = map[v as usize];
+        }
+    }
+    out
+}
+
+fn neighbors(n: usize, idx: usize) -> [Option<usize>; 4] {
+    let row = idx / n;
+    let col = idx % n;
+    [
+        (row > 0).then(|| (row - 1) * n + col),
+        (row + 1 < n).then(|| (row + 1) * n + col),
+        (col > 0).then(|| row * n + (col - 1)),
+        (col + 1 < n).then(|| row * n + (col + 1)),
+    ]
+}
+
+pub(crate) fn random_cage_partition<R: Rng + ?Sized>(
+    n: u8,
+    rules: Ruleset,
+    domino_probability: f64,
+    rng: &mut R,
+) -> Option<Vec<SmallVec<[CellId; INLINE_CAGE_CELLS]>>> {
+    let n_usize = n as usize;
+    let a = n_usize * n_usize;
+    let max_size = rules.max_cage_size as usize;
+
+    let mut cages: Vec<SmallVec<[CellId; INLINE_CAGE_CELLS]>> = (0..a)
+        .map(|i| {
+            let mut v = SmallVec::new();
+            v.push(CellId(i as u16));
+            v
+        })
+        .collect();
+    let mut cage_of: Vec<usize> = (0..a).collect();
+
+    fn merge_cages(
+        cages: &mut [SmallVec<[CellId; INLINE_CAGE_CELLS]>],
+        cage_of: &mut [usize],
+        dst: usize,
+        src: usize,
+        max_size: usize,
+    ) -> bool {
+        if dst == src || cages[src].is_empty() {
+            return false;
+        }
+        if cages[dst].len() + cages[src].len() > max_size {
+            return false;
+        }
+
+        let moved: SmallVec<[CellId; INLINE_CAGE_CELLS]> = cages[src].drain(..).collect();
+        for cell in moved {
+            let idx = cell.0 as usize;
+            cage_of[idx] = dst;
+            cages[dst].push(cell);
+        }
+        true
+    }
+
+    // Phase 1: try to create a reasonable number of dominoes (2-cages) early.
+    let mut order: Vec<usize> = (0..a).collect();
+    order.shuffle(rng);
+    for &cell in &order {
+        let cid = cage_of[cell];
+        if cages[cid].len() != 1 {
+            continue;
+        }
+        if !rng.random_bool(domino_probability) {
+            continue;
+        }
+
+        let mut neighs: Vec<usize> = neighbors(n_usize, cell).into_iter().flatten().collect();
+        neighs.shuffle(rng);
+        let Some(&ncell) = neighs.iter().find(|&&j| cages[cage_of[j]].len() == 1) else {
+            continue;
+        };
+        let nid = cage_of[ncell];
+        merge_cages(&mut cages, &mut cage_of, cid, nid, max_size);
+    }
+
+    // Phase 2: merge remaining singletons into neighbors, respecting size cap.
+    let mut singletons: Vec<usize> = (0..a).filter(|&i| cages[cage_of[i]].len() == 1).collect();
+    singletons.shuffle(rng);
+
+    for cell in singletons {
+        let cid = cage_of[cell];
+        if cages[cid].len() != 1 {
+            continue;
+        }
+        let mut options: Vec<usize> = neighbors(n_usize, cell)
+            .into_iter()
+            .flatten()
+            .map(|j| cage_of[j])
+            .filter(|&other| {
+                other != cid && !cages[other].is_empty() && cages[other].len() < max_size
+            })
+            .collect();
+        options.sort_unstable();
+        options.dedup();
+        options.shuffle(rng);
+
+        let dst = options.into_iter().next()?;
+        merge_cages(&mut cages, &mut cage_of, dst, cid, max_size);
+    }
+
+    // Cells come out in merge order; puzzles store them sorted. Ops and targets do not depend
+    // on cell order, so sorting leaves V1's output unchanged.
+    let out: Vec<SmallVec<[CellId; INLINE_CAGE_CELLS]>> = cages
+        .into_iter()
+        .filter(|c| !c.is_empty())
+        .map(|mut c| {
+            c.sort_unstable();
+            c
+        })
+        .collect();
+    Some(out)
+}
+
+/// The ops V1 draws from for a two-cell cage: `Add` and `Mul`, and `Sub` and `Div` (where
+/// [`applicable_ops`] allows them) only when `rules` confines those to two cells.
+pub(crate) fn two_cell_ops(values: &[u8], rules: Ruleset) -> SmallVec<[Op; 5]> {
+    if rules.sub_div_two_cell_only {
+        applicable_ops(values, rules)
+    } else {
+        SmallVec::from_slice(&[Op::Add, Op::Mul])
+    }
+}
+
+pub(crate) fn assign_ops_and_targets<R: Rng + ?Sized>(
+    n: u8,
+    solution: &[u8],
+    cages: &[SmallVec<[CellId; INLINE_CAGE_CELLS]>],
+    rules: Ruleset,
+    rng: &mut R,
+) -> Result<Puzzle, GenError> {
+    let n_usize = n as usize;
+    let a = n_usize * n_usize;
+    if solution.len() != a {
+        return Err(GenError::AttemptsExhausted { attempts: 1 });
+    }
+
+    let mut out_cages: Vec<Cage> = Vec::with_capacity(cages.len());
+    for cells in cages {
+        let values: SmallVec<[u8; INLINE_CAGE_CELLS]> =
+            cells.iter().map(|c| solution[c.0 as usize]).collect();
+
+        let op = match cells.len() {
+            1 => Op::Eq,
+            2 => {
+                let mut ops = two_cell_ops(&values, rules);
+                ops.shuffle(rng);
+                ops[0]
+            }
+            _ => {
+                if rng.random_bool(0.55) {
+                    Op::Add
+                } else {
+                    Op::Mul
+                }
+            }
+        };
+        let target = target_for(op, &values).expect("every offered op clues its values");
+
+        out_cages.push(Cage {
+            cells: cells.clone(),
+            op,
+            target,
+        });
+    }
+
+    let puzzle = Puzzle {
+        n,
+        cages: out_cages,
+    };
+    puzzle.validate(rules)?;
+    Ok(puzzle)
+}
+
+/// How loosely `puzzle`'s clues pin down their cages: the sum over cages of log2 of
+/// [`cage_tuple_count`], the tuples each clue allows under its own rows' and columns' Latin
+/// constraints. Cheap next to a uniqueness count, and higher scores make multiple solutions
+/// more likely.
+pub fn partition_constraint_score(puzzle: &Puzzle, rules: Ruleset) -> Result<f64, GenError> {
+    let mut score = 0.0;
+    for cage in &puzzle.cages {
+        score += (cage_tuple_count(cage, puzzle.n, rules)?.max(1) as f64).log2();
+    }
+    Ok(score)
+}
+
+/// Whether `config.max_constraint_score` rejects `puzzle`.
+pub(crate) fn too_loose(puzzle: &Puzzle, config: &GenerateConfig) -> Result<bool, GenError> {
+    Ok(match config.max_constraint_score {
+        Some(max) => partition_constraint_score(puzzle, config.rules)? > max,
+        None => false,
+    })
+}
+
+/// RNG for op assignment `retry` on the attempt seeded with `attempt_seed`.
+///
+/// Retry 0 draws from the generator's main stream, exactly as a single assignment per
+/// partition would; later retries get their own streams so the main stream, and with it every
+/// later attempt, is the same however many retries a partition used.
+pub(crate) fn ops_retry_rng(attempt_seed: u64, retry: u32) -> ChaCha20Rng {
+    rng_from_u64(
+        attempt_seed
+            ^ (retry as u64)
+                .wrapping_mul(0xD1B5_4A32_D194_9B2F)
+                .rotate_left(17),
+    )
+}
+
+#[cfg(all(test, feature = "gen-dlx"))]
+mod tests {
+    use kenken_solver::dlx_latin::solve_latin_one;
+
+    use super::*;
+
+    #[test]
+    fn least_latin_square_is_the_first_dlx_cover() {
+        for n in 1..=5u8 {
+            let empty = vec![0u8; n as usize * n as usize];
+            assert_eq!(
+                Some(least_latin_square(n)),
+                solve_latin_one(n, &empty),
+                "n={n}"
+            );
+        }
+    }
+
+    #[test]
+    fn least_latin_square_is_latin_up_to_16() {
+        for n in 1..=16u8 {
+            let n_usize = n as usize;
+            let grid = least_latin_square(n);
+            assert_eq!(&grid[..n_usize], (1..=n).collect::<Vec<_>>(), "n={n}");
+            for i in 0..n_usize {
+                let mut row: Vec<u8> = grid[i * n_usize..(i + 1) * n_usize].to_vec();
+                let mut col: Vec<u8> = (0..n_usize).map(|r| grid[r * n_usize + i]).collect();
+                row.sort_unstable();
+                col.sort_unstable();
+                assert_eq!(row, (1..=n).collect::<Vec<_>>(), "n={n} row {i}");
+                assert_eq!(col, (1..=n).collect::<Vec<_>>(), "n={n} col {i}");
+            }
+        }
+    }
+}
@@ -0,0 +1,42 @@
+//! Golden daily sets from `generate_daily_set`.
+//!
+//! Every device derives the day's puzzles from the date seed, so these descs must not move: a
+//! failure here means the daily set changed, and the fix is to restore the old behaviour,
+//! never to update the expected descs.
+#![cfg(feature = "gen-dlx")]
+
+use kenken_core::format::sgt_desc::encode_keen_desc;
+use kenken_core::rules::Ruleset;
+use kenken_gen::generate_daily_set;
+
+fn check(date_seed: u64, n: u8, expected: [&str; 3]) {
+    let set = generate_daily_set(date_seed, n).unwrap();
+    let got = set.map(|g| encode_keen_desc(&g.puzzle, Ruleset::keen_baseline()).unwrap());
+    assert_eq!(got, expected, "date_seed={date_seed} n={n}");
+}
+
+#[test]
+fn daily_4x4_sets_are_pinned() {
+    check(
+        20261015,
+        4,
+        [
+            "a_a_3a_a_a_b_a__,s2a3m12m8s1a3d3m8",
+            "aa_6a4_a3,a4a10m6m4m12s1s2",
+            "_a_7a4_a3,m6s1a5s2a6d4m6m2",
+        ],
+    );
+}
+
+#[test]
+fn daily_6x6_sets_are_pinned() {
+    check(
+        20260101,
+        6,
+        [
+            "_aa_a_10aa_3a__ba__aa__a3_aa_ca_,a5s3m18m120m6m15m2d3s1a13s1s1s1a3m72d5",
+            "a3ba_aab__a_3a_abaa_11a__a_3a_,a9a7m6a6m300a8m18m4m48a4m20s2d5d2s1m30",
+            "b_a__a_3a3_aa__aa_a_aa_3a__a_6a_ab,m72m4s3m12a7a8m15m10a6a6s1m24m30m30a3s3",
+        ],
+    );
+}
@@ -0,0 +1,92 @@
+//! Golden outputs of `GenAlgoVersion::V1`.
+//!
+//! These descs are part of V1's compatibility contract: a failure here means V1's output
+//! changed, and the fix is to restore the old behaviour, never to update the expected desc.
+#![cfg(feature = "gen-dlx")]
+
+use kenken_core::format::sgt_desc::encode_keen_desc;
+use kenken_gen::{GenAlgoVersion, GenerateConfig, generate_versioned};
+use kenken_solver::DifficultyTier;
+
+/// Each seed's desc from the public constructors' configs as they are, so a change to their
+/// defaults that reached V1 would fail here, and from configs overriding the fields V1 ignores.
+fn check(n: u8, expected: [&str; 10]) {
+    for (seed, want) in expected.into_iter().enumerate() {
+        let seed = seed as u64;
+        let baseline = GenerateConfig::keen_baseline(n, seed);
+        let configs = [
+            baseline,
+            GenerateConfig::with_difficulty(n, seed, DifficultyTier::Extreme),
+            GenerateConfig {
+                ops_retries_per_partition: 1,
+                ..baseline
+            },
+            GenerateConfig {
+                ops_retries_per_partition: 16,
+                ..baseline
+            },
+        ];
+        for config in configs {
+            let g = generate_versioned(config, GenAlgoVersion::V1).unwrap();
+            let got = encode_keen_desc(&g.puzzle, config.rules).unwrap();
+            assert_eq!(got, want, "n={n} seed={seed}: {config:?}");
+        }
+    }
+}
+
+#[test]
+fn v1_4x4_descs_are_pinned() {
+    check(
+        4,
+        [
+            "__a_b_a_a_a_3a_a,s1a3d2d3s2a7a3a4",
+            "ba_5a__aa_a3,a6a5m36s1s3a5m8",
+            "aa_a__a__a_a__a_a,d2a4s1m2s2m6a5s2",
+            "ab__a_a__a__a__b,a7a6s1m12s2s1a5",
+            "a3_a4_7a_,m8s2a5a7d4a3d3s2",
+            "_a__b_ba__a__ba,a5m2a9m9a11a5",
+            "_b_3aabb__a_a_,s1m8a7m8a7m6",
+            "_a_aabb_7a__,s1d2d4a7s2m8m8m3",
+            "a3_a4__a_3a_,m8d3s1a5m2m12a6",
+            "a_a_3a_a_a_b_a__,m12d2d3a3a6m3s1a3",
+        ],
+    );
+}
+
+#[test]
+fn v1_5x5_descs_are_pinned() {
+    check(
+        5,
+        [
+            "b_a__a_aa_b_3a_5a_a_b_a,a8a8d2a7m5m48a3m6d4a8a8",
+            "_a__a_3a__a__cbaa_3a__aa_,s3m6s1a5m6m12d4s3s1d3a10",
+            "a__a_7a4_ba_a4b__,s3a8m4m15a7m12a11a9s2m10",
+            "a_b_10a_4a__aabaca,d5s1m120s3s3s1a8a8a5d3d2",
+            "aa_b_7a_a_a4_a__aba,s1a8a9d3m200d4a12a9m6s2",
+            "_3a_6a__abaca4_3aa,s1a8s3m12m12a6m8s2m20a8",
+            "_3a_3a_4a_a_a_b_c__a4,a3s2m24d5s2a4a9m12a4m75a6",
+            "_3a_4a_aabbaa__a_3a__b_,d4d2m15a9a8m8a10a4a6a11s2",
+            "a_8b__a5ba_b__a_3,s1s1m12m5d3m24m40s3a9m3m8",
+            "_3a_4a__a__acb__a__aa_aa,a6d5m12a8s4m20a8s1a3a8s1",
+        ],
+    );
+}
+
+#[test]
+fn v1_6x6_descs_are_pinned() {
+    check(
+        6,
+        [
+            "baa_14a_aba_aa_a__b__a4baa,m8a9m24s2a13s2m6m12m12d3a6s1m18a14m12",
+            "_a_aa__aa__a3_aabaa_9aa_4a_3ab,s3a7m3s4s5d2a8a10a10a7s2m24a5m30s3a8",
+            "aa__a_3aa_3a_4a_a3b_aa__a_a3_3aa__,a6s3s3s2a10s1m6a13d6m6a6a10a10a7s1m30",
+            "ab_aa__b__a_3a5c_aa_12a4,m30a12d2m12m6m72s3a8a11s1a13a12s4a6",
+            "ac_a_6a_ca3ba_ba_3a__a_3a_3a_3,m36a14s3m60s3d2m15d2a12m48a7a7m10a10",
+            "a_aa_a_4a_6a__aa_ab_aba_3a_4aa_b,s3s2a7s3m60m2m10s4a7s5m36a8a11a9d3m24",
+            "a_3a_5a_4aa_a4_a_3a_aab_3b__ab_,m20d4a5m40m6d2d2s4m18a12m15a6m60s2s1m2",
+            "aa_a_9a__aa__aa__a3_3a__aa_cbaa,a11m36m72m10m60a5m6d2m24a6m12m12a12s2a10",
+            "aab_a_3a3__abaa_a_a_a_b_10a_ba,s1m30a7a16d4a13a10a7d4m432d3m20d6s3",
+            "aab_9a__aa_ab__a_3a__a__aa__aa_ba,s1m8a7s5s1m6a12a3m60d3a11s1s3m5m6a11",
+        ],
+    );
+}
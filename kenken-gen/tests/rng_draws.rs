@@ -0,0 +1,86 @@
+//! Golden RNG draw counts of the default pipeline, per stage (`rng-audit`).
+//!
+//! The output goldens fail when a seed yields a different puzzle; these fail with the stage
+//! whose randomness consumption changed. An extra `random_bool` in the partition loop, say,
+//! shows up as a `Partition` diff even when the puzzles happen to agree. Update a count only
+//! alongside a deliberate change to that stage.
+#![cfg(feature = "rng-audit")]
+
+use kenken_core::rules::Ruleset;
+use kenken_gen::rng_audit::{DrawCounts, DrawLog, record};
+use kenken_gen::{GenerateConfig, generate_with_stats, replay_attempt};
+
+/// `keen_baseline` with the four op assignments per partition these counts were pinned with,
+/// spelled out so a change to its default cannot move them. Counts do not diverge from the
+/// solution, so no second solution cuts a partition's retries short and the ops stage's retry
+/// draws stay covered.
+fn baseline(n: u8, seed: u64) -> GenerateConfig {
+    GenerateConfig {
+        ops_retries_per_partition: 4,
+        diverge_from_solution: false,
+        ..GenerateConfig::keen_baseline(n, seed)
+    }
+}
+
+/// `(u32 draws, u64 draws)` of the solution, partition and ops stages.
+type Pinned = [(u64, u64); 3];
+
+fn check(label: &str, got: DrawLog, [solution, partition, ops]: Pinned) {
+    let counts = |(u32_draws, u64_draws)| DrawCounts {
+        u32_draws,
+        u64_draws,
+        fill_bytes: 0,
+    };
+    let expected = DrawLog {
+        solution: counts(solution),
+        partition: counts(partition),
+        ops: counts(ops),
+    };
+    let diff = got.diff(&expected);
+    assert!(diff.is_empty(), "{label}: {}", diff.join("; "));
+}
+
+#[test]
+fn attempt_draws_are_pinned() {
+    let pinned: [(u8, u64, u32, Pinned); 5] = [
+        (4, 42, 0, [(3, 0), (14, 14), (19, 8)]),
+        (4, 42, 5, [(4, 0), (13, 12), (28, 4)]),
+        (6, 7, 0, [(3, 0), (28, 27), (58, 12)]),
+        (6, 7, 3, [(3, 0), (34, 26), (38, 20)]),
+        (9, 2024, 1, [(4, 0), (75, 61), (94, 44)]),
+    ];
+    for (n, seed, attempt, expected) in pinned {
+        let config = baseline(n, seed);
+        let (_, got) = record(|| replay_attempt(&config, attempt).unwrap());
+        check(
+            &format!("n={n} seed={seed} attempt={attempt}"),
+            got,
+            expected,
+        );
+    }
+}
+
+#[test]
+fn rows_only_attempt_draws_are_pinned() {
+    let config = GenerateConfig {
+        rules: Ruleset {
+            enforce_columns: false,
+            ..Ruleset::keen_baseline()
+        },
+        ..baseline(5, 11)
+    };
+    let (_, got) = record(|| replay_attempt(&config, 2).unwrap());
+    check(
+        "rows-only n=5 seed=11 attempt=2",
+        got,
+        [(5, 0), (22, 17), (41, 8)],
+    );
+}
+
+#[test]
+fn whole_run_draws_are_pinned() {
+    let config = baseline(4, 42);
+    let (generated, got) = record(|| generate_with_stats(config).unwrap());
+    assert_eq!(generated.attempts, 48);
+    check("n=4 seed=42", got, [(157, 0), (661, 557), (1047, 352)]);
+}
@@ -9,9 +9,32 @@ pub enum IoError {
     #[error(transparent)]
     Rkyv(#[from] rkyv::rancor::Error),
 
+    #[cfg(feature = "io-bank")]
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+
+    #[cfg(feature = "io-bank")]
+    #[error(transparent)]
+    Solve(#[from] kenken_solver::SolveError),
+
+    #[cfg(feature = "io-sgt-save")]
+    #[error(transparent)]
+    SgtSave(#[from] crate::sgt_save::SgtSaveError),
+
     #[error("invalid snapshot magic")]
     InvalidSnapshotMagic,
 
     #[error("invalid snapshot data")]
     InvalidSnapshotData,
+
+    #[error(
+        "snapshot ruleset encoding v{version} is newer than this build supports (v{supported})"
+    )]
+    UnsupportedRuleset { version: u16, supported: u16 },
+
+    #[error("cage target {target} does not fit in a v1/v2 snapshot (use v3)")]
+    TargetOutOfRange { target: i64 },
+
+    #[error("a rows-only ruleset does not fit in a v2/v3 snapshot (use v4)")]
+    RowsOnlyRuleset,
 }
@@ -3,5 +3,13 @@
 
 pub mod error;
 
+#[cfg(feature = "io-bank")]
+pub mod revalidate;
+#[cfg(feature = "io-bank")]
+pub mod rkyv_bank;
+#[cfg(feature = "io-checkpoint")]
+pub mod rkyv_checkpoint;
 #[cfg(feature = "io-rkyv")]
 pub mod rkyv_snapshot;
+#[cfg(feature = "io-sgt-save")]
+pub mod sgt_save;
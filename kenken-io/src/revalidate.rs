@@ -0,0 +1,312 @@
+//! Bank revalidation: regrade stored puzzles with the current solver.
+//!
+//! A solver upgrade that adds or changes deductions moves puzzles between tiers, so labels
+//! written by an older build drift from what [`kenken_solver::classify_tier_required`] now
+//! says, and difficulty filters over the bank quietly stop meaning what they say.
+//! [`revalidate_bank`] streams a bank into a new one, reclassifying every record under its own
+//! ruleset and stamping it with this solver's version, and reports how the labels moved.
+//!
+//! Classification assumes uniqueness was established when the bank was built, so it is not
+//! rechecked unless [`RevalidateOptions::deep`] is set.
+//!
+//! # Resuming
+//!
+//! A run can be split into chunks with [`RevalidateOptions::limit`]. Each call flushes the
+//! output before returning its [`RevalidationReport::checkpoint`], so a caller that saves the
+//! checkpoint together with the output's length can restart after a crash: truncate the output
+//! to that length, continue it with [`BankWriter::append`], and call again on a fresh reader
+//! with [`RevalidateOptions::start`] set to the checkpoint.
+
+use std::io::{Read, Write};
+
+use kenken_solver::{
+    DifficultyTier, capabilities, classify_difficulty_from_tier, classify_tier_required_dispatched,
+    count_solutions_up_to_dispatched,
+};
+
+use crate::error::IoError;
+use crate::rkyv_bank::{BankReader, BankRecord, BankWriter};
+
+/// What [`revalidate_bank`] does.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RevalidateOptions {
+    /// Also count each puzzle's solutions, dropping those without exactly one.
+    pub deep: bool,
+    /// Input records to skip first, unread: the checkpoint of an earlier run.
+    pub start: u64,
+    /// Most input records to revalidate in this call; `None` runs to the end of the bank.
+    pub limit: Option<u64>,
+}
+
+/// Outcome of one [`revalidate_bank`] call.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RevalidationReport {
+    /// Input records revalidated by this call.
+    pub revalidated: u64,
+    /// Index of the first input record this call did not revalidate; pass it as
+    /// [`RevalidateOptions::start`] to continue.
+    pub checkpoint: u64,
+    /// Whether the input bank ran out, so there is nothing left to continue.
+    pub finished: bool,
+    /// `transitions[from][to]`: records stored as `from` and now classified `to`, both indexes
+    /// into [`DifficultyTier::ALL`]. Dropped records are not counted.
+    pub transitions: [[u64; DifficultyTier::ALL.len()]; DifficultyTier::ALL.len()],
+    /// Records a deep run dropped for not having exactly one solution.
+    pub dropped_not_unique: u64,
+}
+
+impl RevalidationReport {
+    /// Records stored as `from` and now classified `to`.
+    pub fn transition(&self, from: DifficultyTier, to: DifficultyTier) -> u64 {
+        self.transitions[tier_index(from)][tier_index(to)]
+    }
+
+    /// Records whose label changed.
+    pub fn changed(&self) -> u64 {
+        let mut changed = 0;
+        for (from, row) in self.transitions.iter().enumerate() {
+            for (to, &count) in row.iter().enumerate() {
+                if from != to {
+                    changed += count;
+                }
+            }
+        }
+        changed
+    }
+}
+
+/// Copy `reader`'s records to `writer` with their difficulty reclassified by the current
+/// solver, and `classified_with_version` set to this `kenken-solver` version. Each record is
+/// classified under the ruleset stored with it.
+///
+/// `reader` must be freshly opened: [`RevalidateOptions::start`] counts from the first record.
+/// The output is flushed before returning. See the [module docs](self) for resuming.
+///
+/// # Errors
+/// Anything [`BankReader`] or [`BankWriter`] reports, and [`IoError::Solve`] for a record the
+/// solver rejects. Records before the failing one have been written, but not flushed.
+pub fn revalidate_bank<R: Read, W: Write>(
+    reader: &mut BankReader<R>,
+    writer: &mut BankWriter<W>,
+    options: RevalidateOptions,
+) -> Result<RevalidationReport, IoError> {
+    let version = capabilities().version;
+    let mut report = RevalidationReport {
+        checkpoint: reader.skip_records(options.start)?,
+        ..RevalidationReport::default()
+    };
+    report.finished = report.checkpoint < options.start;
+    while !report.finished && options.limit.is_none_or(|limit| report.revalidated < limit) {
+        let Some(record) = reader.next().transpose()? else {
+            report.finished = true;
+            break;
+        };
+        report.revalidated += 1;
+        report.checkpoint += 1;
+        if options.deep && count_solutions_up_to_dispatched(&record.puzzle, record.rules, 2)? != 1 {
+            report.dropped_not_unique += 1;
+            continue;
+        }
+        let tier = classify_tier_required_dispatched(&record.puzzle, record.rules)?;
+        let difficulty = classify_difficulty_from_tier(tier);
+        report.transitions[tier_index(record.difficulty)][tier_index(difficulty)] += 1;
+        writer.push(&BankRecord {
+            difficulty,
+            classified_with_version: Some(version.to_string()),
+            ..record
+        })?;
+    }
+    writer.flush()?;
+    Ok(report)
+}
+
+fn tier_index(tier: DifficultyTier) -> usize {
+    DifficultyTier::ALL
+        .iter()
+        .position(|&t| t == tier)
+        .expect("ALL lists every tier")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use kenken_core::Puzzle;
+    use kenken_core::format::sgt_desc::parse_keen_desc;
+    use kenken_core::rules::Ruleset;
+
+    /// `(n, desc, solution)` of unique puzzles.
+    const UNIQUE: [(u8, &str, &[u8]); 2] = [
+        (2, "_5,a1a2a2a1", &[1, 2, 2, 1]),
+        (
+            4,
+            "a3_aaba__a__a_,a7a12m3m24a5d2",
+            &[1, 2, 3, 4, 4, 3, 2, 1, 2, 1, 4, 3, 3, 4, 1, 2],
+        ),
+    ];
+
+    fn current_difficulty(puzzle: &Puzzle, rules: Ruleset) -> DifficultyTier {
+        classify_difficulty_from_tier(classify_tier_required_dispatched(puzzle, rules).unwrap())
+    }
+
+    /// Each unique puzzle three times, stored as its current label, one tier off, and
+    /// `Unreasonable`.
+    fn perturbed_records() -> Vec<BankRecord> {
+        let rules = Ruleset::keen_baseline();
+        let mut records = Vec::new();
+        for (n, desc, solution) in UNIQUE {
+            let puzzle = parse_keen_desc(n, desc).unwrap();
+            let current = tier_index(current_difficulty(&puzzle, rules));
+            for stored in [current, (current + 1) % 5, 4] {
+                records.push(BankRecord {
+                    puzzle: puzzle.clone(),
+                    rules,
+                    solution: solution.to_vec(),
+                    difficulty: DifficultyTier::ALL[stored],
+                    classified_with_version: Some("0.0.0-old".to_string()),
+                });
+            }
+        }
+        records
+    }
+
+    fn write(records: &[BankRecord]) -> Vec<u8> {
+        let mut writer = BankWriter::new(Vec::new()).unwrap();
+        for record in records {
+            writer.push(record).unwrap();
+        }
+        writer.finish().unwrap()
+    }
+
+    fn read(bytes: &[u8]) -> Vec<BankRecord> {
+        BankReader::new(bytes)
+            .unwrap()
+            .collect::<Result<_, _>>()
+            .unwrap()
+    }
+
+    fn revalidate(input: &[u8], options: RevalidateOptions) -> (RevalidationReport, Vec<u8>) {
+        let mut reader = BankReader::new(input).unwrap();
+        let mut writer = BankWriter::new(Vec::new()).unwrap();
+        let report = revalidate_bank(&mut reader, &mut writer, options).unwrap();
+        (report, writer.finish().unwrap())
+    }
+
+    #[test]
+    fn perturbed_labels_are_corrected_and_counted() {
+        let records = perturbed_records();
+        let (report, output) = revalidate(&write(&records), RevalidateOptions::default());
+        assert_eq!(report.revalidated, 6);
+        assert_eq!(report.checkpoint, 6);
+        assert!(report.finished);
+        assert_eq!(report.dropped_not_unique, 0);
+
+        // The 2x2 grades Easy and the 4x4 Normal.
+        use DifficultyTier::{Easy, Hard, Normal, Unreasonable};
+        for (from, to) in [
+            (Easy, Easy),
+            (Normal, Easy),
+            (Unreasonable, Easy),
+            (Normal, Normal),
+            (Hard, Normal),
+            (Unreasonable, Normal),
+        ] {
+            assert_eq!(report.transition(from, to), 1, "{from:?} -> {to:?}");
+        }
+        assert_eq!(report.changed(), 4);
+
+        let output = read(&output);
+        assert_eq!(output.len(), records.len());
+        for (before, after) in records.iter().zip(&output) {
+            assert_eq!(
+                after.difficulty,
+                current_difficulty(&before.puzzle, before.rules)
+            );
+            assert_eq!(
+                after.classified_with_version.as_deref(),
+                Some(capabilities().version)
+            );
+            assert_eq!(after.puzzle, before.puzzle);
+            assert_eq!(after.solution, before.solution);
+        }
+        let labels: Vec<DifficultyTier> = output.iter().map(|r| r.difficulty).collect();
+        assert_eq!(labels, [Easy, Easy, Easy, Normal, Normal, Normal]);
+    }
+
+    #[test]
+    fn chunked_runs_resume_from_their_checkpoint() {
+        let input = write(&perturbed_records());
+        let (whole, expected) = revalidate(&input, RevalidateOptions::default());
+
+        let mut output = BankWriter::new(Vec::new()).unwrap().finish().unwrap();
+        let mut transitions = [[0; 5]; 5];
+        let mut checkpoint = 0;
+        loop {
+            // A fresh reader and an appending writer per chunk, as after a restart.
+            let mut reader = BankReader::new(input.as_slice()).unwrap();
+            let mut writer = BankWriter::append(&mut output);
+            let options = RevalidateOptions {
+                start: checkpoint,
+                limit: Some(4),
+                ..RevalidateOptions::default()
+            };
+            let report = revalidate_bank(&mut reader, &mut writer, options).unwrap();
+            assert!(report.revalidated <= 4);
+            assert_eq!(report.checkpoint, checkpoint + report.revalidated);
+            for (total, row) in transitions.iter_mut().zip(report.transitions) {
+                for (total, count) in total.iter_mut().zip(row) {
+                    *total += count;
+                }
+            }
+            checkpoint = report.checkpoint;
+            if report.finished {
+                break;
+            }
+        }
+        assert_eq!(checkpoint, 6);
+        assert_eq!(transitions, whole.transitions);
+        assert_eq!(output, expected);
+
+        // Starting past the end is a finished no-op.
+        let options = RevalidateOptions {
+            start: 10,
+            ..RevalidateOptions::default()
+        };
+        let (report, output) = revalidate(&input, options);
+        assert_eq!((report.checkpoint, report.finished), (6, true));
+        assert!(read(&output).is_empty());
+    }
+
+    #[test]
+    fn only_deep_runs_check_uniqueness() {
+        let rules = Ruleset::keen_baseline();
+        let mut records = perturbed_records();
+        let ambiguous = parse_keen_desc(2, "b__,a3a3").unwrap();
+        records.insert(
+            1,
+            BankRecord {
+                difficulty: current_difficulty(&ambiguous, rules),
+                puzzle: ambiguous,
+                rules,
+                solution: vec![1, 2, 2, 1],
+                classified_with_version: None,
+            },
+        );
+        let input = write(&records);
+
+        let (shallow, output) = revalidate(&input, RevalidateOptions::default());
+        assert_eq!(shallow.dropped_not_unique, 0);
+        assert_eq!(read(&output).len(), 7);
+
+        let deep = RevalidateOptions {
+            deep: true,
+            ..RevalidateOptions::default()
+        };
+        let (report, output) = revalidate(&input, deep);
+        assert_eq!(report.revalidated, 7);
+        assert_eq!(report.dropped_not_unique, 1);
+        let output = read(&output);
+        assert_eq!(output.len(), 6);
+        assert!(output.iter().all(|r| r.puzzle != records[1].puzzle));
+        assert_eq!(report.changed(), shallow.changed());
+    }
+}
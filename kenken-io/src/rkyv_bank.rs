@@ -0,0 +1,438 @@
+//! Streamed puzzle banks: many solved, graded puzzles in one file.
+//!
+//! A bank is written one record at a time by [`BankWriter`] and read back the same way by
+//! [`BankReader`], so neither side holds the whole bank in memory. Framing mirrors snapshot
+//! v2+ (`KEENBANK` magic, u16 version, u16 header length, 4 reserved bytes); each record
+//! follows as a little-endian `u32` byte length and an `rkyv` record. Version 3 banks, the ones
+//! [`BankWriter`] writes, hold [`BankRecordV3`]s: a snapshot v4 payload, the solution, and the
+//! difficulty along with the solver version that assigned it. [`BankReader`] also reads version
+//! 2 banks of [`BankRecordV2`]s and version 1 banks of [`BankRecordV1`]s (snapshot v3), whose
+//! records carry no solver version.
+use std::io::{Read, Write};
+
+use kenken_core::Puzzle;
+use kenken_core::rules::Ruleset;
+use kenken_solver::DifficultyTier;
+
+use rkyv::util::AlignedVec;
+use rkyv::{Archive, Deserialize, Serialize};
+
+use crate::error::IoError;
+use crate::rkyv_snapshot::{
+    SnapshotPayloadV3, SnapshotPayloadV4, SnapshotPuzzleV3, SnapshotRulesetV2,
+};
+
+const BANK_MAGIC: [u8; 8] = *b"KEENBANK";
+const BANK_VERSION_V1: u16 = 1;
+const BANK_VERSION_V2: u16 = 2;
+const BANK_VERSION_V3: u16 = 3;
+const BANK_HEADER_LEN_V1: u16 = 16;
+
+/// Largest record [`BankReader`] accepts, so a corrupt length cannot trigger a huge allocation.
+pub const MAX_BANK_RECORD_LEN: u32 = 1 << 24;
+
+#[derive(Archive, Serialize, Deserialize, Debug, PartialEq, Eq)]
+#[rkyv(derive(Debug))]
+pub struct BankRecordV1 {
+    pub payload: SnapshotPayloadV3,
+    /// Row-major solution grid.
+    pub solution: Vec<u8>,
+    /// Index into `DifficultyTier::ALL`.
+    pub difficulty: u8,
+}
+
+/// Version 2 record: [`BankRecordV1`] with its rules in the versioned
+/// [`SnapshotRulesetV2`] encoding.
+#[derive(Archive, Serialize, Deserialize, Debug, PartialEq, Eq)]
+#[rkyv(derive(Debug))]
+pub struct BankRecordV2 {
+    pub payload: SnapshotPayloadV4,
+    /// Row-major solution grid.
+    pub solution: Vec<u8>,
+    /// Index into `DifficultyTier::ALL`.
+    pub difficulty: u8,
+}
+
+/// Version 3 record: [`BankRecordV2`] plus the solver version that classified it.
+#[derive(Archive, Serialize, Deserialize, Debug, PartialEq, Eq)]
+#[rkyv(derive(Debug))]
+pub struct BankRecordV3 {
+    pub payload: SnapshotPayloadV4,
+    /// Row-major solution grid.
+    pub solution: Vec<u8>,
+    /// Index into `DifficultyTier::ALL`.
+    pub difficulty: u8,
+    pub classified_with_version: Option<String>,
+}
+
+/// One bank entry.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BankRecord {
+    pub puzzle: Puzzle,
+    pub rules: Ruleset,
+    pub solution: Vec<u8>,
+    pub difficulty: DifficultyTier,
+    /// `kenken-solver` version whose classification `difficulty` is; `None` when unknown, as
+    /// for every record of a version 1 or 2 bank.
+    pub classified_with_version: Option<String>,
+}
+
+/// Appends records to a bank; see the [module docs](self) for the layout.
+///
+/// Records go straight to the underlying writer, so wrap files in a `BufWriter`.
+pub struct BankWriter<W: Write> {
+    inner: W,
+    records: u64,
+}
+
+impl<W: Write> BankWriter<W> {
+    /// Write the bank header to `inner`.
+    pub fn new(mut inner: W) -> Result<Self, IoError> {
+        inner.write_all(&BANK_MAGIC)?;
+        inner.write_all(&BANK_VERSION_V3.to_le_bytes())?;
+        inner.write_all(&BANK_HEADER_LEN_V1.to_le_bytes())?;
+        inner.write_all(&0u32.to_le_bytes())?;
+        Ok(Self { inner, records: 0 })
+    }
+
+    /// Append to a bank this writer's version started, with `inner` positioned just after its
+    /// last complete record. Nothing is written until the first [`BankWriter::push`], and
+    /// [`BankWriter::records`] counts only the records appended.
+    pub fn append(inner: W) -> Self {
+        Self { inner, records: 0 }
+    }
+
+    pub fn push(&mut self, record: &BankRecord) -> Result<(), IoError> {
+        let difficulty = DifficultyTier::ALL
+            .iter()
+            .position(|&tier| tier == record.difficulty)
+            .expect("ALL lists every tier") as u8;
+        let encoded = BankRecordV3 {
+            payload: SnapshotPayloadV4 {
+                rules: SnapshotRulesetV2::from(record.rules),
+                puzzle: SnapshotPuzzleV3::from(&record.puzzle),
+            },
+            solution: record.solution.clone(),
+            difficulty,
+            classified_with_version: record.classified_with_version.clone(),
+        };
+        let bytes = rkyv::to_bytes::<rkyv::rancor::Error>(&encoded)?;
+        let len = u32::try_from(bytes.len())
+            .ok()
+            .filter(|&len| len <= MAX_BANK_RECORD_LEN)
+            .ok_or(IoError::InvalidSnapshotData)?;
+        self.inner.write_all(&len.to_le_bytes())?;
+        self.inner.write_all(&bytes)?;
+        self.records += 1;
+        Ok(())
+    }
+
+    /// Records written so far.
+    pub fn records(&self) -> u64 {
+        self.records
+    }
+
+    /// Flush the underlying writer, so every record pushed so far has reached it.
+    pub fn flush(&mut self) -> Result<(), IoError> {
+        self.inner.flush()?;
+        Ok(())
+    }
+
+    /// Flush and return the underlying writer.
+    pub fn finish(mut self) -> Result<W, IoError> {
+        self.inner.flush()?;
+        Ok(self.inner)
+    }
+}
+
+/// Iterates over a bank's records, reading one at a time.
+///
+/// A bank that ends inside a record (e.g. a writer killed mid-record) yields
+/// [`IoError::InvalidSnapshotData`] for that record, after every complete one.
+pub struct BankReader<R: Read> {
+    inner: R,
+    /// Bank version from the header, which fixes the record layout.
+    version: u16,
+    done: bool,
+}
+
+impl<R: Read> BankReader<R> {
+    /// Read and check the bank header.
+    pub fn new(mut inner: R) -> Result<Self, IoError> {
+        let mut header = [0u8; BANK_HEADER_LEN_V1 as usize];
+        inner.read_exact(&mut header).map_err(eof_as_invalid)?;
+        if header[..8] != BANK_MAGIC {
+            return Err(IoError::InvalidSnapshotMagic);
+        }
+        let version = u16::from_le_bytes(header[8..10].try_into().unwrap());
+        let header_len = u16::from_le_bytes(header[10..12].try_into().unwrap());
+        if !matches!(version, BANK_VERSION_V1..=BANK_VERSION_V3) || header_len != BANK_HEADER_LEN_V1
+        {
+            return Err(IoError::InvalidSnapshotData);
+        }
+        Ok(Self {
+            inner,
+            version,
+            done: false,
+        })
+    }
+
+    /// Discard up to `count` records without decoding them; returns how many there were.
+    pub fn skip_records(&mut self, count: u64) -> Result<u64, IoError> {
+        let mut skipped = 0;
+        while skipped < count && !self.done {
+            let step = self.skip_record();
+            self.done = !matches!(step, Ok(true));
+            if step? {
+                skipped += 1;
+            }
+        }
+        Ok(skipped)
+    }
+
+    fn skip_record(&mut self) -> Result<bool, IoError> {
+        let Some(len) = self.next_len()? else {
+            return Ok(false);
+        };
+        let skipped = std::io::copy(
+            &mut (&mut self.inner).take(u64::from(len)),
+            &mut std::io::sink(),
+        )?;
+        if skipped < u64::from(len) {
+            return Err(IoError::InvalidSnapshotData);
+        }
+        Ok(true)
+    }
+
+    /// Length prefix of the next record, or `None` at a clean end of the bank.
+    fn next_len(&mut self) -> Result<Option<u32>, IoError> {
+        let mut len = [0u8; 4];
+        let mut filled = 0;
+        while filled < len.len() {
+            match self.inner.read(&mut len[filled..]) {
+                Ok(0) if filled == 0 => return Ok(None),
+                Ok(0) => return Err(IoError::InvalidSnapshotData),
+                Ok(read) => filled += read,
+                Err(e) if e.kind() == std::io::ErrorKind::Interrupted => {}
+                Err(e) => return Err(e.into()),
+            }
+        }
+        let len = u32::from_le_bytes(len);
+        if len > MAX_BANK_RECORD_LEN {
+            return Err(IoError::InvalidSnapshotData);
+        }
+        Ok(Some(len))
+    }
+
+    fn next_record(&mut self) -> Result<Option<BankRecord>, IoError> {
+        let Some(len) = self.next_len()? else {
+            return Ok(None);
+        };
+        let mut bytes = vec![0u8; len as usize];
+        self.inner.read_exact(&mut bytes).map_err(eof_as_invalid)?;
+        // `rkyv::access` needs the record at its archive alignment.
+        let mut aligned = AlignedVec::<16>::with_capacity(bytes.len());
+        aligned.extend_from_slice(&bytes);
+
+        let (puzzle, rules, solution, difficulty, classified_with_version) = match self.version {
+            BANK_VERSION_V1 => {
+                let archived = rkyv::access::<ArchivedBankRecordV1, rkyv::rancor::Error>(&aligned)?;
+                let record: BankRecordV1 =
+                    rkyv::deserialize::<BankRecordV1, rkyv::rancor::Error>(archived)?;
+                let SnapshotPayloadV3 { rules, puzzle } = record.payload;
+                let rules = Ruleset::from(rules);
+                (puzzle, rules, record.solution, record.difficulty, None)
+            }
+            BANK_VERSION_V2 => {
+                let archived = rkyv::access::<ArchivedBankRecordV2, rkyv::rancor::Error>(&aligned)?;
+                let record: BankRecordV2 =
+                    rkyv::deserialize::<BankRecordV2, rkyv::rancor::Error>(archived)?;
+                let SnapshotPayloadV4 { rules, puzzle } = record.payload;
+                let rules = Ruleset::try_from(rules)?;
+                (puzzle, rules, record.solution, record.difficulty, None)
+            }
+            _ => {
+                let archived = rkyv::access::<ArchivedBankRecordV3, rkyv::rancor::Error>(&aligned)?;
+                let record: BankRecordV3 =
+                    rkyv::deserialize::<BankRecordV3, rkyv::rancor::Error>(archived)?;
+                let SnapshotPayloadV4 { rules, puzzle } = record.payload;
+                let rules = Ruleset::try_from(rules)?;
+                let version = record.classified_with_version;
+                (puzzle, rules, record.solution, record.difficulty, version)
+            }
+        };
+        let difficulty = *DifficultyTier::ALL
+            .get(difficulty as usize)
+            .ok_or(IoError::InvalidSnapshotData)?;
+        Ok(Some(BankRecord {
+            puzzle: Puzzle::try_from(puzzle)?,
+            rules,
+            solution,
+            difficulty,
+            classified_with_version,
+        }))
+    }
+}
+
+impl<R: Read> Iterator for BankReader<R> {
+    type Item = Result<BankRecord, IoError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+        let next = self.next_record().transpose();
+        self.done = !matches!(next, Some(Ok(_)));
+        next
+    }
+}
+
+fn eof_as_invalid(e: std::io::Error) -> IoError {
+    if e.kind() == std::io::ErrorKind::UnexpectedEof {
+        IoError::InvalidSnapshotData
+    } else {
+        e.into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use kenken_core::format::sgt_desc::parse_keen_desc;
+
+    fn records() -> Vec<BankRecord> {
+        let rules = Ruleset::keen_baseline();
+        vec![
+            BankRecord {
+                puzzle: parse_keen_desc(2, "_5,a1a2a2a1").unwrap(),
+                rules,
+                solution: vec![1, 2, 2, 1],
+                difficulty: DifficultyTier::Easy,
+                classified_with_version: None,
+            },
+            BankRecord {
+                puzzle: parse_keen_desc(4, "a3_aaba__a__a_,a7a12m3m24a5d2").unwrap(),
+                rules,
+                solution: vec![1, 2, 3, 4, 4, 3, 2, 1, 2, 1, 4, 3, 3, 4, 1, 2],
+                difficulty: DifficultyTier::Normal,
+                classified_with_version: Some("0.1.0".to_string()),
+            },
+        ]
+    }
+
+    fn write(records: &[BankRecord]) -> Vec<u8> {
+        let mut writer = BankWriter::new(Vec::new()).unwrap();
+        for record in records {
+            writer.push(record).unwrap();
+        }
+        assert_eq!(writer.records(), records.len() as u64);
+        writer.finish().unwrap()
+    }
+
+    #[test]
+    fn bank_roundtrips_records_in_order() {
+        let records = records();
+        let bytes = write(&records);
+        let read: Vec<BankRecord> = BankReader::new(bytes.as_slice())
+            .unwrap()
+            .collect::<Result<_, _>>()
+            .unwrap();
+        assert_eq!(read, records);
+
+        let empty = write(&[]);
+        assert_eq!(BankReader::new(empty.as_slice()).unwrap().count(), 0);
+    }
+
+    #[test]
+    fn truncated_bank_yields_complete_records_then_an_error() {
+        let bytes = write(&records());
+        let cut = &bytes[..bytes.len() - 3];
+        let mut reader = BankReader::new(cut).unwrap();
+        assert!(matches!(reader.next(), Some(Ok(_))));
+        assert!(matches!(
+            reader.next(),
+            Some(Err(IoError::InvalidSnapshotData))
+        ));
+        assert!(reader.next().is_none());
+    }
+
+    /// `records` as a version 1 bank, the layout written before rulesets were versioned.
+    fn write_v1(records: &[BankRecord]) -> Vec<u8> {
+        let mut bytes = b"KEENBANK\x01\x00\x10\x00\x00\x00\x00\x00".to_vec();
+        for record in records {
+            let encoded = BankRecordV1 {
+                payload: SnapshotPayloadV3 {
+                    rules: record.rules.try_into().unwrap(),
+                    puzzle: SnapshotPuzzleV3::from(&record.puzzle),
+                },
+                solution: record.solution.clone(),
+                difficulty: DifficultyTier::ALL
+                    .iter()
+                    .position(|&tier| tier == record.difficulty)
+                    .unwrap() as u8,
+            };
+            let record = rkyv::to_bytes::<rkyv::rancor::Error>(&encoded).unwrap();
+            bytes.extend_from_slice(&(record.len() as u32).to_le_bytes());
+            bytes.extend_from_slice(&record);
+        }
+        bytes
+    }
+
+    #[test]
+    fn version_1_banks_still_read() {
+        let mut records = records();
+        let read: Vec<BankRecord> = BankReader::new(write_v1(&records).as_slice())
+            .unwrap()
+            .collect::<Result<_, _>>()
+            .unwrap();
+        // Version 1 records predate the classifying version.
+        records[1].classified_with_version = None;
+        assert_eq!(read, records);
+    }
+
+    #[test]
+    fn skip_discards_records_and_appends_continue_the_bank() {
+        let records = records();
+        let mut bytes = write(&records[..1]);
+        let mut appender = BankWriter::append(&mut bytes);
+        appender.push(&records[1]).unwrap();
+        appender.finish().unwrap();
+        assert_eq!(bytes, write(&records));
+
+        let mut reader = BankReader::new(bytes.as_slice()).unwrap();
+        assert_eq!(reader.skip_records(1).unwrap(), 1);
+        assert_eq!(reader.next().unwrap().unwrap(), records[1]);
+        let mut reader = BankReader::new(bytes.as_slice()).unwrap();
+        assert_eq!(reader.skip_records(5).unwrap(), 2);
+        assert!(reader.next().is_none());
+    }
+
+    #[test]
+    fn non_baseline_rules_roundtrip() {
+        let mut records = records();
+        records[1].rules = Ruleset {
+            sub_div_two_cell_only: false,
+            require_orthogonal_cage_connectivity: false,
+            max_cage_size: 9,
+            enforce_columns: false,
+        };
+        let read: Vec<BankRecord> = BankReader::new(write(&records).as_slice())
+            .unwrap()
+            .collect::<Result<_, _>>()
+            .unwrap();
+        assert_eq!(read, records);
+    }
+
+    #[test]
+    fn reader_rejects_other_headers() {
+        let snapshot = b"KEENSNAP\x03\x00\x10\x00\x00\x00\x00\x00";
+        assert!(matches!(
+            BankReader::new(&snapshot[..]),
+            Err(IoError::InvalidSnapshotMagic)
+        ));
+        assert!(matches!(
+            BankReader::new(&b"KEENBANK"[..]),
+            Err(IoError::InvalidSnapshotData)
+        ));
+    }
+}
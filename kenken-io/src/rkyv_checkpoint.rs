@@ -0,0 +1,166 @@
+//! Versioned solver search checkpoints using `rkyv`.
+//!
+//! Pairs with `kenken_solver::count_solutions_resumable`: persist the returned
+//! `SearchCheckpoint` between process runs and feed it back in to continue a long count.
+//!
+//! Framing mirrors snapshot v2+ (`KEENCKPT` magic, u16 version, u16 header length,
+//! 4 reserved bytes) followed by the `rkyv` payload.
+use kenken_solver::{CheckpointFrame, SearchCheckpoint, SolveStats};
+
+use rkyv::{Archive, Deserialize, Serialize};
+
+use crate::error::IoError;
+
+const CHECKPOINT_MAGIC: [u8; 8] = *b"KEENCKPT";
+const CHECKPOINT_VERSION_V1: u16 = 1;
+const CHECKPOINT_HEADER_LEN_V1: u16 = 16;
+
+#[derive(Archive, Serialize, Deserialize, Debug, PartialEq, Eq)]
+#[rkyv(derive(Debug))]
+pub struct CheckpointPayloadV1 {
+    pub frames: Vec<CheckpointFrameV1>,
+    pub count: u32,
+    pub stats: CheckpointStatsV1,
+}
+
+#[derive(Archive, Serialize, Deserialize, Debug, PartialEq, Eq)]
+#[rkyv(derive(Debug))]
+pub struct CheckpointFrameV1 {
+    pub cell: u16,
+    pub value: u8,
+    pub remaining: u64,
+}
+
+/// Feature-independent subset of `SolveStats` (feature-gated counters are not persisted).
+#[derive(Archive, Serialize, Deserialize, Debug, PartialEq, Eq)]
+#[rkyv(derive(Debug))]
+pub struct CheckpointStatsV1 {
+    pub nodes_visited: u64,
+    pub assignments: u64,
+    pub max_depth: u32,
+    pub backtracked: bool,
+}
+
+impl From<&SearchCheckpoint> for CheckpointPayloadV1 {
+    fn from(cp: &SearchCheckpoint) -> Self {
+        Self {
+            frames: cp
+                .frames
+                .iter()
+                .map(|f| CheckpointFrameV1 {
+                    cell: f.cell,
+                    value: f.value,
+                    remaining: f.remaining,
+                })
+                .collect(),
+            count: cp.count,
+            stats: CheckpointStatsV1 {
+                nodes_visited: cp.stats.nodes_visited,
+                assignments: cp.stats.assignments,
+                max_depth: cp.stats.max_depth,
+                backtracked: cp.stats.backtracked,
+            },
+        }
+    }
+}
+
+impl From<CheckpointPayloadV1> for SearchCheckpoint {
+    // `SolveStats` gains counters under solver features (e.g. `nogood-learning`).
+    #[allow(clippy::needless_update)]
+    fn from(p: CheckpointPayloadV1) -> Self {
+        Self {
+            frames: p
+                .frames
+                .into_iter()
+                .map(|f| CheckpointFrame {
+                    cell: f.cell,
+                    value: f.value,
+                    remaining: f.remaining,
+                })
+                .collect(),
+            count: p.count,
+            stats: SolveStats {
+                nodes_visited: p.stats.nodes_visited,
+                assignments: p.stats.assignments,
+                max_depth: p.stats.max_depth,
+                backtracked: p.stats.backtracked,
+                ..SolveStats::default()
+            },
+        }
+    }
+}
+
+pub fn encode_checkpoint_v1(checkpoint: &SearchCheckpoint) -> Result<Vec<u8>, IoError> {
+    let payload = CheckpointPayloadV1::from(checkpoint);
+    let bytes = rkyv::to_bytes::<rkyv::rancor::Error>(&payload)?;
+    let mut out = Vec::with_capacity(CHECKPOINT_HEADER_LEN_V1 as usize + bytes.len());
+    out.extend_from_slice(&CHECKPOINT_MAGIC);
+    out.extend_from_slice(&CHECKPOINT_VERSION_V1.to_le_bytes());
+    out.extend_from_slice(&CHECKPOINT_HEADER_LEN_V1.to_le_bytes());
+    out.extend_from_slice(&0u32.to_le_bytes());
+    out.extend_from_slice(&bytes);
+    Ok(out)
+}
+
+pub fn decode_checkpoint_v1(bytes: &[u8]) -> Result<SearchCheckpoint, IoError> {
+    if bytes.len() < CHECKPOINT_HEADER_LEN_V1 as usize {
+        return Err(IoError::InvalidSnapshotData);
+    }
+    if bytes[..8] != CHECKPOINT_MAGIC {
+        return Err(IoError::InvalidSnapshotMagic);
+    }
+    let version = u16::from_le_bytes(bytes[8..10].try_into().unwrap());
+    let header_len = u16::from_le_bytes(bytes[10..12].try_into().unwrap());
+    if version != CHECKPOINT_VERSION_V1 || header_len != CHECKPOINT_HEADER_LEN_V1 {
+        return Err(IoError::InvalidSnapshotData);
+    }
+
+    let archived = rkyv::access::<ArchivedCheckpointPayloadV1, rkyv::rancor::Error>(
+        &bytes[header_len as usize..],
+    )?;
+    let payload: CheckpointPayloadV1 =
+        rkyv::deserialize::<CheckpointPayloadV1, rkyv::rancor::Error>(archived)?;
+    Ok(payload.into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use kenken_core::rules::Ruleset;
+    use kenken_solver::{DeductionTier, ResumableOutcome, count_solutions_resumable};
+
+    #[test]
+    fn checkpoint_roundtrips_and_resumes() {
+        let puzzle = kenken_core::format::sgt_desc::parse_keen_desc(2, "b__,a3a3").unwrap();
+        let rules = Ruleset::keen_baseline();
+
+        let ResumableOutcome::Suspended(cp) =
+            count_solutions_resumable(&puzzle, rules, DeductionTier::None, 10, None, 2).unwrap()
+        else {
+            panic!("expected the count to suspend");
+        };
+
+        let bytes = encode_checkpoint_v1(&cp).unwrap();
+        let decoded = decode_checkpoint_v1(&bytes).unwrap();
+        assert_eq!(decoded.frames, cp.frames);
+        assert_eq!(decoded.count, cp.count);
+        assert_eq!(decoded.stats.nodes_visited, cp.stats.nodes_visited);
+
+        let resumed =
+            count_solutions_resumable(&puzzle, rules, DeductionTier::None, 10, Some(decoded), 0)
+                .unwrap();
+        assert!(matches!(
+            resumed,
+            ResumableOutcome::Complete { count: 2, .. }
+        ));
+    }
+
+    #[test]
+    fn decode_rejects_snapshot_bytes() {
+        let bytes = b"KEENSNAP\x02\x00\x10\x00\x00\x00\x00\x00";
+        assert!(matches!(
+            decode_checkpoint_v1(bytes),
+            Err(IoError::InvalidSnapshotMagic)
+        ));
+    }
+}
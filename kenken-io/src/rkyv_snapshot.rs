@@ -5,7 +5,7 @@
 //! - caches (e.g., generated puzzle banks)
 //! - reproducible corpora without re-parsing text formats
 //!
-use kenken_core::rules::Op;
+use kenken_core::rules::{Op, Ruleset};
 use kenken_core::{Cage, CellId, Puzzle};
 
 use rkyv::{Archive, Deserialize, Serialize};
@@ -16,6 +16,30 @@ const SNAPSHOT_MAGIC_V1: [u8; 8] = *b"KEENRKYV";
 const SNAPSHOT_ENVELOPE_MAGIC: [u8; 8] = *b"KEENSNAP";
 const SNAPSHOT_ENVELOPE_VERSION_V2: u16 = 2;
 const SNAPSHOT_ENVELOPE_HEADER_LEN_V2: u16 = 16;
+const SNAPSHOT_ENVELOPE_VERSION_V3: u16 = 3;
+const SNAPSHOT_ENVELOPE_VERSION_V4: u16 = 4;
+
+/// Newest [`SnapshotRulesetV2::version`] this build decodes: the set of [`Ruleset`] knobs it
+/// knows. Bumped whenever `Ruleset` gains a field, alongside a new flag bit or field here.
+pub const SNAPSHOT_RULESET_VERSION: u16 = 2;
+
+/// [`SnapshotRulesetV2::flags`] bit for [`Ruleset::sub_div_two_cell_only`].
+pub const RULESET_FLAG_SUB_DIV_TWO_CELL_ONLY: u32 = 1 << 0;
+/// [`SnapshotRulesetV2::flags`] bit for [`Ruleset::require_orthogonal_cage_connectivity`].
+pub const RULESET_FLAG_ORTHOGONAL_CONNECTIVITY: u32 = 1 << 1;
+/// [`SnapshotRulesetV2::flags`] bit for [`Ruleset::enforce_columns`] being *off*, so rulesets
+/// from before version 2 decode with Latin columns. Since version 2.
+pub const RULESET_FLAG_ROWS_ONLY: u32 = 1 << 2;
+
+/// Every flag bit defined at ruleset encoding `version`.
+const fn ruleset_known_flags(version: u16) -> u32 {
+    let v1 = RULESET_FLAG_SUB_DIV_TWO_CELL_ONLY | RULESET_FLAG_ORTHOGONAL_CONNECTIVITY;
+    if version >= 2 {
+        v1 | RULESET_FLAG_ROWS_ONLY
+    } else {
+        v1
+    }
+}
 
 #[derive(Archive, Serialize, Deserialize, Debug, PartialEq, Eq)]
 #[rkyv(derive(Debug))]
@@ -54,6 +78,19 @@ pub struct SnapshotRulesetV1 {
     pub max_cage_size: u8,
 }
 
+/// Versioned ruleset encoding: boolean knobs as bits of `flags`, the rest as fields.
+///
+/// A build decodes `version` up to [`SNAPSHOT_RULESET_VERSION`] and fails with
+/// [`IoError::UnsupportedRuleset`] past it, instead of dropping knobs it does not know.
+#[derive(Archive, Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[rkyv(derive(Debug))]
+pub struct SnapshotRulesetV2 {
+    pub version: u16,
+    /// `RULESET_FLAG_*` bits.
+    pub flags: u32,
+    pub max_cage_size: u8,
+}
+
 #[derive(Archive, Serialize, Deserialize, Debug, PartialEq, Eq)]
 #[rkyv(derive(Debug))]
 pub struct SnapshotPuzzleV2 {
@@ -61,35 +98,76 @@ pub struct SnapshotPuzzleV2 {
     pub cages: Vec<SnapshotCageV1>,
 }
 
-fn encode_op(op: Op) -> u8 {
-    match op {
-        Op::Add => 0,
-        Op::Mul => 1,
-        Op::Sub => 2,
-        Op::Div => 3,
-        Op::Eq => 4,
+/// Snapshot v3 widens cage targets to `i64` (large-n `Mul` cages overflow `i32`).
+#[derive(Archive, Serialize, Deserialize, Debug, PartialEq, Eq)]
+#[rkyv(derive(Debug))]
+pub struct SnapshotPayloadV3 {
+    pub rules: SnapshotRulesetV1,
+    pub puzzle: SnapshotPuzzleV3,
+}
+
+/// Snapshot v4 carries a [`SnapshotRulesetV2`], so every ruleset knob round-trips.
+#[derive(Archive, Serialize, Deserialize, Debug, PartialEq, Eq)]
+#[rkyv(derive(Debug))]
+pub struct SnapshotPayloadV4 {
+    pub rules: SnapshotRulesetV2,
+    pub puzzle: SnapshotPuzzleV3,
+}
+
+#[derive(Archive, Serialize, Deserialize, Debug, PartialEq, Eq)]
+#[rkyv(derive(Debug))]
+pub struct SnapshotPuzzleV3 {
+    pub n: u8,
+    pub cages: Vec<SnapshotCageV3>,
+}
+
+#[derive(Archive, Serialize, Deserialize, Debug, PartialEq, Eq)]
+#[rkyv(derive(Debug))]
+pub struct SnapshotCageV3 {
+    pub cells: Vec<u16>,
+    pub op: u8,
+    pub target: i64,
+}
+
+impl TryFrom<&Puzzle> for SnapshotPuzzleV1 {
+    type Error = IoError;
+
+    fn try_from(p: &Puzzle) -> Result<Self, Self::Error> {
+        let cages = p
+            .cages
+            .iter()
+            .map(|c| {
+                Ok(SnapshotCageV1 {
+                    cells: c.cells.iter().map(|id| id.0).collect(),
+                    op: c.op.code(),
+                    target: i32::try_from(c.target)
+                        .map_err(|_| IoError::TargetOutOfRange { target: c.target })?,
+                })
+            })
+            .collect::<Result<Vec<_>, IoError>>()?;
+        Ok(Self { n: p.n, cages })
     }
 }
 
-fn decode_op(op: u8) -> Option<Op> {
-    match op {
-        0 => Some(Op::Add),
-        1 => Some(Op::Mul),
-        2 => Some(Op::Sub),
-        3 => Some(Op::Div),
-        4 => Some(Op::Eq),
-        _ => None,
+impl TryFrom<&Puzzle> for SnapshotPuzzleV2 {
+    type Error = IoError;
+
+    fn try_from(p: &Puzzle) -> Result<Self, Self::Error> {
+        Ok(Self {
+            n: p.n,
+            cages: SnapshotPuzzleV1::try_from(p)?.cages,
+        })
     }
 }
 
-impl From<&Puzzle> for SnapshotPuzzleV1 {
+impl From<&Puzzle> for SnapshotPuzzleV3 {
     fn from(p: &Puzzle) -> Self {
         let cages = p
             .cages
             .iter()
-            .map(|c| SnapshotCageV1 {
+            .map(|c| SnapshotCageV3 {
                 cells: c.cells.iter().map(|id| id.0).collect(),
-                op: encode_op(c.op),
+                op: c.op.code(),
                 target: c.target,
             })
             .collect();
@@ -97,15 +175,6 @@ impl From<&Puzzle> for SnapshotPuzzleV1 {
     }
 }
 
-impl From<&Puzzle> for SnapshotPuzzleV2 {
-    fn from(p: &Puzzle) -> Self {
-        Self {
-            n: p.n,
-            cages: SnapshotPuzzleV1::from(p).cages,
-        }
-    }
-}
-
 impl TryFrom<SnapshotPuzzleV1> for Puzzle {
     type Error = IoError;
 
@@ -114,11 +183,14 @@ impl TryFrom<SnapshotPuzzleV1> for Puzzle {
             .cages
             .into_iter()
             .map(|c| {
-                let op = decode_op(c.op).ok_or(IoError::InvalidSnapshotData)?;
+                let op = Op::from_code(c.op).ok_or(IoError::InvalidSnapshotData)?;
+                // Snapshots may predate sorted cage cells.
+                let mut cells = c.cells;
+                cells.sort_unstable();
                 Ok(Cage {
-                    cells: c.cells.into_iter().map(CellId).collect(),
+                    cells: cells.into_iter().map(CellId).collect(),
                     op,
-                    target: c.target,
+                    target: i64::from(c.target),
                 })
             })
             .collect::<Result<Vec<_>, IoError>>()?;
@@ -137,45 +209,140 @@ impl TryFrom<SnapshotPuzzleV2> for Puzzle {
     }
 }
 
-pub fn encode_puzzle_v1(puzzle: &Puzzle) -> Result<Vec<u8>, IoError> {
-    let file = SnapshotFileV1 {
-        magic: SNAPSHOT_MAGIC_V1,
-        puzzle: SnapshotPuzzleV1::from(puzzle),
-    };
-    Ok(rkyv::to_bytes::<rkyv::rancor::Error>(&file)?.to_vec())
+impl TryFrom<SnapshotPuzzleV3> for Puzzle {
+    type Error = IoError;
+
+    fn try_from(p: SnapshotPuzzleV3) -> Result<Self, Self::Error> {
+        let cages = p
+            .cages
+            .into_iter()
+            .map(|c| {
+                let op = Op::from_code(c.op).ok_or(IoError::InvalidSnapshotData)?;
+                // Snapshots may predate sorted cage cells.
+                let mut cells = c.cells;
+                cells.sort_unstable();
+                Ok(Cage {
+                    cells: cells.into_iter().map(CellId).collect(),
+                    op,
+                    target: c.target,
+                })
+            })
+            .collect::<Result<Vec<_>, IoError>>()?;
+        Ok(Puzzle { n: p.n, cages })
+    }
 }
 
-pub fn decode_puzzle_v1(bytes: &[u8]) -> Result<Puzzle, IoError> {
-    let archived = rkyv::access::<ArchivedSnapshotFileV1, rkyv::rancor::Error>(bytes)?;
-    if archived.magic != SNAPSHOT_MAGIC_V1 {
-        return Err(IoError::InvalidSnapshotMagic);
+/// V1 has a field per knob of the rulesets it predates. Destructured so a new `Ruleset` field
+/// breaks the build here until the V1 encoding decides what to do with it.
+fn encode_rules(rules: Ruleset) -> Result<SnapshotRulesetV1, IoError> {
+    let Ruleset {
+        sub_div_two_cell_only,
+        require_orthogonal_cage_connectivity,
+        max_cage_size,
+        enforce_columns,
+    } = rules;
+    // Every V1 ruleset has Latin columns; refuse rather than drop the knob.
+    if !enforce_columns {
+        return Err(IoError::RowsOnlyRuleset);
     }
-    let file: SnapshotFileV1 = rkyv::deserialize::<SnapshotFileV1, rkyv::rancor::Error>(archived)?;
-    Puzzle::try_from(file.puzzle)
+    Ok(SnapshotRulesetV1 {
+        sub_div_two_cell_only,
+        require_orthogonal_cage_connectivity,
+        max_cage_size,
+    })
 }
 
-pub fn encode_puzzle_v2(
-    puzzle: &Puzzle,
-    rules: kenken_core::rules::Ruleset,
-) -> Result<Vec<u8>, IoError> {
-    let payload = SnapshotPayloadV2 {
-        rules: SnapshotRulesetV1 {
-            sub_div_two_cell_only: rules.sub_div_two_cell_only,
-            require_orthogonal_cage_connectivity: rules.require_orthogonal_cage_connectivity,
+fn decode_rules(rules: SnapshotRulesetV1) -> Ruleset {
+    Ruleset {
+        sub_div_two_cell_only: rules.sub_div_two_cell_only,
+        require_orthogonal_cage_connectivity: rules.require_orthogonal_cage_connectivity,
+        max_cage_size: rules.max_cage_size,
+        enforce_columns: true,
+    }
+}
+
+impl TryFrom<Ruleset> for SnapshotRulesetV1 {
+    type Error = IoError;
+
+    /// Fails with [`IoError::RowsOnlyRuleset`] for a ruleset without Latin columns.
+    fn try_from(rules: Ruleset) -> Result<Self, Self::Error> {
+        encode_rules(rules)
+    }
+}
+
+impl From<SnapshotRulesetV1> for Ruleset {
+    fn from(rules: SnapshotRulesetV1) -> Self {
+        decode_rules(rules)
+    }
+}
+
+impl From<Ruleset> for SnapshotRulesetV2 {
+    fn from(rules: Ruleset) -> Self {
+        // Exhaustive on purpose: a new `Ruleset` field must get a flag bit or field here (and
+        // a `SNAPSHOT_RULESET_VERSION` bump) before this compiles.
+        let Ruleset {
+            sub_div_two_cell_only,
+            require_orthogonal_cage_connectivity,
+            max_cage_size,
+            enforce_columns,
+        } = rules;
+        let mut flags = 0;
+        if sub_div_two_cell_only {
+            flags |= RULESET_FLAG_SUB_DIV_TWO_CELL_ONLY;
+        }
+        if require_orthogonal_cage_connectivity {
+            flags |= RULESET_FLAG_ORTHOGONAL_CONNECTIVITY;
+        }
+        if !enforce_columns {
+            flags |= RULESET_FLAG_ROWS_ONLY;
+        }
+        Self {
+            version: SNAPSHOT_RULESET_VERSION,
+            flags,
+            max_cage_size,
+        }
+    }
+}
+
+impl TryFrom<SnapshotRulesetV2> for Ruleset {
+    type Error = IoError;
+
+    /// Fails with [`IoError::UnsupportedRuleset`] for a version past
+    /// [`SNAPSHOT_RULESET_VERSION`], and with [`IoError::InvalidSnapshotData`] for a flag bit
+    /// its version does not define.
+    fn try_from(rules: SnapshotRulesetV2) -> Result<Self, Self::Error> {
+        if rules.version > SNAPSHOT_RULESET_VERSION {
+            return Err(IoError::UnsupportedRuleset {
+                version: rules.version,
+                supported: SNAPSHOT_RULESET_VERSION,
+            });
+        }
+        if rules.version == 0 || rules.flags & !ruleset_known_flags(rules.version) != 0 {
+            return Err(IoError::InvalidSnapshotData);
+        }
+        Ok(Ruleset {
+            sub_div_two_cell_only: rules.flags & RULESET_FLAG_SUB_DIV_TWO_CELL_ONLY != 0,
+            require_orthogonal_cage_connectivity: rules.flags
+                & RULESET_FLAG_ORTHOGONAL_CONNECTIVITY
+                != 0,
             max_cage_size: rules.max_cage_size,
-        },
-        puzzle: SnapshotPuzzleV2::from(puzzle),
-    };
-    let mut out = Vec::new();
+            enforce_columns: rules.flags & RULESET_FLAG_ROWS_ONLY == 0,
+        })
+    }
+}
+
+fn write_envelope(version: u16, payload: &[u8]) -> Vec<u8> {
+    let mut out = Vec::with_capacity(SNAPSHOT_ENVELOPE_HEADER_LEN_V2 as usize + payload.len());
     out.extend_from_slice(&SNAPSHOT_ENVELOPE_MAGIC);
-    out.extend_from_slice(&SNAPSHOT_ENVELOPE_VERSION_V2.to_le_bytes());
+    out.extend_from_slice(&version.to_le_bytes());
     out.extend_from_slice(&SNAPSHOT_ENVELOPE_HEADER_LEN_V2.to_le_bytes());
     out.extend_from_slice(&0u32.to_le_bytes());
-    out.extend_from_slice(&rkyv::to_bytes::<rkyv::rancor::Error>(&payload)?);
-    Ok(out)
+    out.extend_from_slice(payload);
+    out
 }
 
-pub fn decode_puzzle_v2(bytes: &[u8]) -> Result<(Puzzle, kenken_core::rules::Ruleset), IoError> {
+/// Validate the v2+ envelope and return `(version, payload_bytes)`.
+fn read_envelope(bytes: &[u8]) -> Result<(u16, &[u8]), IoError> {
     if bytes.len() < SNAPSHOT_ENVELOPE_HEADER_LEN_V2 as usize {
         return Err(IoError::InvalidSnapshotData);
     }
@@ -186,25 +353,95 @@ pub fn decode_puzzle_v2(bytes: &[u8]) -> Result<(Puzzle, kenken_core::rules::Rul
         return Err(IoError::InvalidSnapshotMagic);
     }
     let version = u16::from_le_bytes(bytes[8..10].try_into().unwrap());
-    if version != SNAPSHOT_ENVELOPE_VERSION_V2 {
+    let header_len = u16::from_le_bytes(bytes[10..12].try_into().unwrap());
+    if header_len != SNAPSHOT_ENVELOPE_HEADER_LEN_V2 {
         return Err(IoError::InvalidSnapshotData);
     }
+    Ok((version, &bytes[header_len as usize..]))
+}
 
-    let header_len = u16::from_le_bytes(bytes[10..12].try_into().unwrap());
-    if header_len != SNAPSHOT_ENVELOPE_HEADER_LEN_V2 {
+pub fn encode_puzzle_v1(puzzle: &Puzzle) -> Result<Vec<u8>, IoError> {
+    let file = SnapshotFileV1 {
+        magic: SNAPSHOT_MAGIC_V1,
+        puzzle: SnapshotPuzzleV1::try_from(puzzle)?,
+    };
+    Ok(rkyv::to_bytes::<rkyv::rancor::Error>(&file)?.to_vec())
+}
+
+pub fn decode_puzzle_v1(bytes: &[u8]) -> Result<Puzzle, IoError> {
+    let archived = rkyv::access::<ArchivedSnapshotFileV1, rkyv::rancor::Error>(bytes)?;
+    if archived.magic != SNAPSHOT_MAGIC_V1 {
+        return Err(IoError::InvalidSnapshotMagic);
+    }
+    let file: SnapshotFileV1 = rkyv::deserialize::<SnapshotFileV1, rkyv::rancor::Error>(archived)?;
+    Puzzle::try_from(file.puzzle)
+}
+
+pub fn encode_puzzle_v2(puzzle: &Puzzle, rules: Ruleset) -> Result<Vec<u8>, IoError> {
+    let payload = SnapshotPayloadV2 {
+        rules: encode_rules(rules)?,
+        puzzle: SnapshotPuzzleV2::try_from(puzzle)?,
+    };
+    let bytes = rkyv::to_bytes::<rkyv::rancor::Error>(&payload)?;
+    Ok(write_envelope(SNAPSHOT_ENVELOPE_VERSION_V2, &bytes))
+}
+
+pub fn decode_puzzle_v2(bytes: &[u8]) -> Result<(Puzzle, Ruleset), IoError> {
+    let (version, payload_bytes) = read_envelope(bytes)?;
+    if version != SNAPSHOT_ENVELOPE_VERSION_V2 {
         return Err(IoError::InvalidSnapshotData);
     }
-    let payload_bytes = &bytes[header_len as usize..];
     let archived = rkyv::access::<ArchivedSnapshotPayloadV2, rkyv::rancor::Error>(payload_bytes)?;
     let payload: SnapshotPayloadV2 =
         rkyv::deserialize::<SnapshotPayloadV2, rkyv::rancor::Error>(archived)?;
 
     let puzzle = Puzzle::try_from(payload.puzzle)?;
-    let rules = kenken_core::rules::Ruleset {
-        sub_div_two_cell_only: payload.rules.sub_div_two_cell_only,
-        require_orthogonal_cage_connectivity: payload.rules.require_orthogonal_cage_connectivity,
-        max_cage_size: payload.rules.max_cage_size,
+    Ok((puzzle, decode_rules(payload.rules)))
+}
+
+pub fn encode_puzzle_v3(puzzle: &Puzzle, rules: Ruleset) -> Result<Vec<u8>, IoError> {
+    let payload = SnapshotPayloadV3 {
+        rules: encode_rules(rules)?,
+        puzzle: SnapshotPuzzleV3::from(puzzle),
+    };
+    let bytes = rkyv::to_bytes::<rkyv::rancor::Error>(&payload)?;
+    Ok(write_envelope(SNAPSHOT_ENVELOPE_VERSION_V3, &bytes))
+}
+
+pub fn decode_puzzle_v3(bytes: &[u8]) -> Result<(Puzzle, Ruleset), IoError> {
+    let (version, payload_bytes) = read_envelope(bytes)?;
+    if version != SNAPSHOT_ENVELOPE_VERSION_V3 {
+        return Err(IoError::InvalidSnapshotData);
+    }
+    let archived = rkyv::access::<ArchivedSnapshotPayloadV3, rkyv::rancor::Error>(payload_bytes)?;
+    let payload: SnapshotPayloadV3 =
+        rkyv::deserialize::<SnapshotPayloadV3, rkyv::rancor::Error>(archived)?;
+
+    let puzzle = Puzzle::try_from(payload.puzzle)?;
+    Ok((puzzle, decode_rules(payload.rules)))
+}
+
+pub fn encode_puzzle_v4(puzzle: &Puzzle, rules: Ruleset) -> Result<Vec<u8>, IoError> {
+    let payload = SnapshotPayloadV4 {
+        rules: SnapshotRulesetV2::from(rules),
+        puzzle: SnapshotPuzzleV3::from(puzzle),
     };
+    let bytes = rkyv::to_bytes::<rkyv::rancor::Error>(&payload)?;
+    Ok(write_envelope(SNAPSHOT_ENVELOPE_VERSION_V4, &bytes))
+}
+
+/// Decode a v4 snapshot. A ruleset newer than this build is [`IoError::UnsupportedRuleset`].
+pub fn decode_puzzle_v4(bytes: &[u8]) -> Result<(Puzzle, Ruleset), IoError> {
+    let (version, payload_bytes) = read_envelope(bytes)?;
+    if version != SNAPSHOT_ENVELOPE_VERSION_V4 {
+        return Err(IoError::InvalidSnapshotData);
+    }
+    let archived = rkyv::access::<ArchivedSnapshotPayloadV4, rkyv::rancor::Error>(payload_bytes)?;
+    let payload: SnapshotPayloadV4 =
+        rkyv::deserialize::<SnapshotPayloadV4, rkyv::rancor::Error>(archived)?;
+
+    let rules = Ruleset::try_from(payload.rules)?;
+    let puzzle = Puzzle::try_from(payload.puzzle)?;
     Ok((puzzle, rules))
 }
 
@@ -212,13 +449,15 @@ pub fn decode_puzzle_v2(bytes: &[u8]) -> Result<(Puzzle, kenken_core::rules::Rul
 pub enum SnapshotVersion {
     V1,
     V2,
+    V3,
+    V4,
 }
 
 #[derive(Debug, PartialEq, Eq)]
 pub struct DecodedSnapshot {
     pub version: SnapshotVersion,
     pub puzzle: Puzzle,
-    pub rules: Option<kenken_core::rules::Ruleset>,
+    pub rules: Option<Ruleset>,
 }
 
 pub fn decode_snapshot(bytes: &[u8]) -> Result<DecodedSnapshot, IoError> {
@@ -226,9 +465,15 @@ pub fn decode_snapshot(bytes: &[u8]) -> Result<DecodedSnapshot, IoError> {
     if bytes.len() >= SNAPSHOT_ENVELOPE_HEADER_LEN_V2 as usize
         && bytes[..8] == SNAPSHOT_ENVELOPE_MAGIC
     {
-        let (puzzle, rules) = decode_puzzle_v2(bytes)?;
+        let (version, _) = read_envelope(bytes)?;
+        let (version, (puzzle, rules)) = match version {
+            SNAPSHOT_ENVELOPE_VERSION_V2 => (SnapshotVersion::V2, decode_puzzle_v2(bytes)?),
+            SNAPSHOT_ENVELOPE_VERSION_V3 => (SnapshotVersion::V3, decode_puzzle_v3(bytes)?),
+            SNAPSHOT_ENVELOPE_VERSION_V4 => (SnapshotVersion::V4, decode_puzzle_v4(bytes)?),
+            _ => return Err(IoError::InvalidSnapshotData),
+        };
         return Ok(DecodedSnapshot {
-            version: SnapshotVersion::V2,
+            version,
             puzzle,
             rules: Some(rules),
         });
@@ -246,7 +491,6 @@ pub fn decode_snapshot(bytes: &[u8]) -> Result<DecodedSnapshot, IoError> {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use kenken_core::rules::Ruleset;
 
     #[test]
     fn rkyv_roundtrips_puzzle() {
@@ -278,4 +522,131 @@ mod tests {
         assert_eq!(decoded.rules, Some(rules));
         assert_eq!(decoded.puzzle, puzzle);
     }
+
+    #[test]
+    fn rows_only_rulesets_need_v4() {
+        let puzzle = kenken_core::format::sgt_desc::parse_keen_desc(2, "b__,a3a3").unwrap();
+        let rules = Ruleset {
+            enforce_columns: false,
+            ..Ruleset::keen_baseline()
+        };
+        for encoded in [
+            encode_puzzle_v2(&puzzle, rules),
+            encode_puzzle_v3(&puzzle, rules),
+        ] {
+            assert!(matches!(encoded, Err(IoError::RowsOnlyRuleset)));
+        }
+        let decoded = decode_snapshot(&encode_puzzle_v4(&puzzle, rules).unwrap()).unwrap();
+        assert_eq!(decoded.rules, Some(rules));
+    }
+
+    #[test]
+    fn v3_roundtrips_targets_beyond_i32() {
+        let puzzle = Puzzle {
+            n: 9,
+            cages: vec![Cage {
+                cells: (0..9).map(CellId).collect(),
+                op: Op::Mul,
+                target: 362_880 * 9_000,
+            }],
+        };
+        let rules = Ruleset::keen_baseline();
+
+        assert!(matches!(
+            encode_puzzle_v2(&puzzle, rules),
+            Err(IoError::TargetOutOfRange { .. })
+        ));
+
+        let bytes = encode_puzzle_v3(&puzzle, rules).unwrap();
+        let decoded = decode_snapshot(&bytes).unwrap();
+        assert_eq!(decoded.version, SnapshotVersion::V3);
+        assert_eq!(decoded.rules, Some(rules));
+        assert_eq!(decoded.puzzle, puzzle);
+    }
+
+    /// Every `Ruleset` the current fields can express.
+    fn all_rulesets() -> impl Iterator<Item = Ruleset> {
+        [false, true].into_iter().flat_map(|sub_div_two_cell_only| {
+            [false, true].into_iter().flat_map(move |connectivity| {
+                [false, true].into_iter().flat_map(move |enforce_columns| {
+                    (0..=u8::MAX).map(move |max_cage_size| Ruleset {
+                        sub_div_two_cell_only,
+                        require_orthogonal_cage_connectivity: connectivity,
+                        max_cage_size,
+                        enforce_columns,
+                    })
+                })
+            })
+        })
+    }
+
+    #[test]
+    fn v4_roundtrips_every_ruleset() {
+        let puzzle = kenken_core::format::sgt_desc::parse_keen_desc(2, "b__,a3a3").unwrap();
+        for rules in all_rulesets() {
+            let encoded = SnapshotRulesetV2::from(rules);
+            assert_eq!(encoded.version, SNAPSHOT_RULESET_VERSION);
+            assert_eq!(Ruleset::try_from(encoded).unwrap(), rules);
+
+            let decoded = decode_snapshot(&encode_puzzle_v4(&puzzle, rules).unwrap()).unwrap();
+            assert_eq!(decoded.version, SnapshotVersion::V4);
+            assert_eq!(decoded.rules, Some(rules));
+            assert_eq!(decoded.puzzle, puzzle);
+        }
+    }
+
+    /// A v4 snapshot carrying `rules` as some other build might have written them.
+    fn v4_with_rules(rules: SnapshotRulesetV2) -> Vec<u8> {
+        let puzzle = kenken_core::format::sgt_desc::parse_keen_desc(2, "b__,a3a3").unwrap();
+        let payload = SnapshotPayloadV4 {
+            rules,
+            puzzle: SnapshotPuzzleV3::from(&puzzle),
+        };
+        let bytes = rkyv::to_bytes::<rkyv::rancor::Error>(&payload).unwrap();
+        write_envelope(SNAPSHOT_ENVELOPE_VERSION_V4, &bytes)
+    }
+
+    #[test]
+    fn newer_ruleset_versions_are_a_typed_error() {
+        let newer = SnapshotRulesetV2 {
+            version: SNAPSHOT_RULESET_VERSION + 1,
+            flags: RULESET_FLAG_SUB_DIV_TWO_CELL_ONLY | 1 << 3,
+            max_cage_size: 6,
+        };
+        let err = decode_snapshot(&v4_with_rules(newer)).unwrap_err();
+        assert!(
+            matches!(
+                err,
+                IoError::UnsupportedRuleset { version, supported }
+                    if version == SNAPSHOT_RULESET_VERSION + 1
+                        && supported == SNAPSHOT_RULESET_VERSION
+            ),
+            "{err:?}"
+        );
+    }
+
+    #[test]
+    fn undefined_ruleset_flags_are_rejected() {
+        for rules in [
+            SnapshotRulesetV2 {
+                flags: 1 << 3,
+                ..SnapshotRulesetV2::from(Ruleset::keen_baseline())
+            },
+            // Rows-only postdates version 1.
+            SnapshotRulesetV2 {
+                version: 1,
+                flags: RULESET_FLAG_ROWS_ONLY,
+                max_cage_size: 6,
+            },
+            SnapshotRulesetV2 {
+                version: 0,
+                ..SnapshotRulesetV2::from(Ruleset::keen_baseline())
+            },
+        ] {
+            assert!(matches!(
+                decode_snapshot(&v4_with_rules(rules)),
+                Err(IoError::InvalidSnapshotData)
+            ));
+        }
+    }
 }
@@ -0,0 +1,376 @@
+//! Upstream sgt-puzzles save files for Keen.
+//!
+//! A save file is a sequence of `KEY:LEN:VALUE` records: the key is padded to eight
+//! characters, `LEN` is the byte length of `VALUE`, and each record ends with a newline. The
+//! desktop game writes the puzzle as a `DESC` record and every move since the start as a
+//! `MOVE` record; `STATEPOS` says how many of those moves are applied (the rest are redo
+//! history). Keen moves set a digit with `R<col>,<row>,<digit>` (digit 0 clears), toggle a
+//! pencil mark with `P...`, fill all pencil marks with `M`, and fill the solution with
+//! `S<digits>`. Pencil marks are not represented here and are skipped.
+
+use std::io::Read;
+
+use kenken_core::format::sgt_desc::{SgtDescError, encode_keen_desc, parse_keen_desc};
+use kenken_core::rules::Ruleset;
+use kenken_core::{CoreError, Puzzle};
+
+use crate::error::IoError;
+
+const SAVEFILE_MAGIC: &str = "Simon Tatham's Portable Puzzle Collection";
+const SAVEFILE_VERSION: &str = "1";
+const GAME_NAME: &str = "Keen";
+
+/// Why a save file could not be read. Wrapped in [`IoError::SgtSave`].
+#[derive(thiserror::Error, Debug)]
+pub enum SgtSaveError {
+    #[error(transparent)]
+    Read(#[from] std::io::Error),
+
+    #[error("not an sgt-puzzles save file")]
+    NotASaveFile,
+
+    #[error("malformed record at byte {offset}")]
+    MalformedRecord { offset: usize },
+
+    #[error("record {key} declares {declared} bytes but only {available} remain")]
+    TruncatedRecord {
+        key: String,
+        declared: usize,
+        available: usize,
+    },
+
+    #[error("save file is for {0}, not Keen")]
+    WrongGame(String),
+
+    #[error("unsupported save file version {0}")]
+    UnsupportedVersion(String),
+
+    #[error("save file has no {0} record")]
+    MissingRecord(&'static str),
+
+    #[error("invalid PARAMS {0:?}")]
+    InvalidParams(String),
+
+    #[error("invalid DESC: {0}")]
+    InvalidDesc(#[source] SgtDescError),
+
+    #[error("invalid {key} value {value:?}")]
+    InvalidNumber { key: &'static str, value: String },
+
+    #[error("STATEPOS {pos} is outside 1..={states} for {states} states")]
+    StatePosOutOfRange { pos: usize, states: usize },
+
+    #[error("NSTATES is {declared} but the file records {recorded} states")]
+    StateCountMismatch { declared: usize, recorded: usize },
+
+    #[error("move {index} ({text:?}) is not a valid Keen move")]
+    InvalidMove { index: usize, text: String },
+}
+
+/// A Keen save file reduced to the puzzle and the digits entered so far.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SgtSave {
+    /// `PARAMS` as written by the game (e.g. `5dh`); the grid size is `puzzle.n`.
+    pub params: String,
+    /// `DESC`, the upstream desc string.
+    pub desc: String,
+    /// `DESC` parsed with [`parse_keen_desc`].
+    pub puzzle: Puzzle,
+    /// Row-major digits after replaying the first `STATEPOS - 1` moves; 0 = empty.
+    pub grid: Vec<u8>,
+}
+
+/// One `KEY:LEN:VALUE` record, key with its padding removed.
+struct Record<'a> {
+    key: &'a str,
+    value: &'a str,
+}
+
+fn records(text: &str) -> Result<Vec<Record<'_>>, SgtSaveError> {
+    let bytes = text.as_bytes();
+    let mut out = Vec::new();
+    let mut pos = 0;
+    loop {
+        while pos < bytes.len() && bytes[pos].is_ascii_whitespace() {
+            pos += 1;
+        }
+        if pos == bytes.len() {
+            return Ok(out);
+        }
+
+        let start = pos;
+        let malformed = SgtSaveError::MalformedRecord { offset: start };
+        let key_end = text[pos..].find(':').ok_or(malformed)? + pos;
+        let key = text[pos..key_end].trim_end();
+        if key.is_empty() || key.len() > 8 {
+            return Err(SgtSaveError::MalformedRecord { offset: start });
+        }
+        pos = key_end + 1;
+        let len_end = text[pos..]
+            .find(':')
+            .ok_or(SgtSaveError::MalformedRecord { offset: start })?
+            + pos;
+        let declared: usize = text[pos..len_end]
+            .parse()
+            .map_err(|_| SgtSaveError::MalformedRecord { offset: start })?;
+        pos = len_end + 1;
+        let available = bytes.len() - pos;
+        if declared > available {
+            return Err(SgtSaveError::TruncatedRecord {
+                key: key.to_string(),
+                declared,
+                available,
+            });
+        }
+        let value = text
+            .get(pos..pos + declared)
+            .ok_or(SgtSaveError::MalformedRecord { offset: start })?;
+        pos += declared;
+        out.push(Record { key, value });
+    }
+}
+
+/// Read a Keen save file written by the sgt-puzzles desktop game.
+///
+/// Pencil-mark moves are skipped; `R` moves set or clear a digit (a later move on the same
+/// cell overwrites an earlier one), `S` moves and `SOLVE` records fill the whole grid, and
+/// `RESTART` records clear it.
+pub fn parse_sgt_save<R: Read>(mut reader: R) -> Result<SgtSave, IoError> {
+    let mut text = String::new();
+    reader
+        .read_to_string(&mut text)
+        .map_err(SgtSaveError::Read)?;
+    Ok(parse_records(&records(&text)?)?)
+}
+
+fn parse_records(records: &[Record<'_>]) -> Result<SgtSave, SgtSaveError> {
+    match records.first() {
+        Some(r) if r.key == "SAVEFILE" && r.value == SAVEFILE_MAGIC => {}
+        _ => return Err(SgtSaveError::NotASaveFile),
+    }
+    let field = |key: &'static str| {
+        records
+            .iter()
+            .find(|r| r.key == key)
+            .map(|r| r.value)
+            .ok_or(SgtSaveError::MissingRecord(key))
+    };
+    let number = |key: &'static str| -> Result<usize, SgtSaveError> {
+        let value = field(key)?;
+        value.parse().map_err(|_| SgtSaveError::InvalidNumber {
+            key,
+            value: value.to_string(),
+        })
+    };
+
+    let version = field("VERSION")?;
+    if version != SAVEFILE_VERSION {
+        return Err(SgtSaveError::UnsupportedVersion(version.to_string()));
+    }
+    let game = field("GAME")?;
+    if game != GAME_NAME {
+        return Err(SgtSaveError::WrongGame(game.to_string()));
+    }
+
+    // Keen params are the width followed by optional flags (`d<difficulty>`, `m`).
+    let params = field("PARAMS")?;
+    let digits = params.bytes().take_while(u8::is_ascii_digit).count();
+    let n: u8 = params[..digits]
+        .parse()
+        .map_err(|_| SgtSaveError::InvalidParams(params.to_string()))?;
+
+    let desc = field("DESC")?;
+    let puzzle = parse_keen_desc(n, desc).map_err(SgtSaveError::InvalidDesc)?;
+
+    let moves: Vec<&Record<'_>> = records
+        .iter()
+        .filter(|r| matches!(r.key, "MOVE" | "SOLVE" | "RESTART"))
+        .collect();
+    let states = number("NSTATES")?;
+    if states != moves.len() + 1 {
+        return Err(SgtSaveError::StateCountMismatch {
+            declared: states,
+            recorded: moves.len() + 1,
+        });
+    }
+    let pos = number("STATEPOS")?;
+    if !(1..=states).contains(&pos) {
+        return Err(SgtSaveError::StatePosOutOfRange { pos, states });
+    }
+
+    let mut grid = vec![0u8; n as usize * n as usize];
+    for (index, record) in moves.iter().take(pos - 1).enumerate() {
+        if record.key == "RESTART" {
+            grid.fill(0);
+            continue;
+        }
+        apply_move(n, &mut grid, record.value).ok_or_else(|| SgtSaveError::InvalidMove {
+            index,
+            text: record.value.to_string(),
+        })?;
+    }
+
+    Ok(SgtSave {
+        params: params.to_string(),
+        desc: desc.to_string(),
+        puzzle,
+        grid,
+    })
+}
+
+/// Apply one Keen move string to `grid`; `None` if it is not a move Keen would accept.
+fn apply_move(n: u8, grid: &mut [u8], text: &str) -> Option<()> {
+    let (kind, rest) = text.split_at_checked(1)?;
+    match kind {
+        "R" | "P" => {
+            let mut fields = rest.split(',').map(|f| f.parse::<u8>().ok());
+            let (col, row, digit) = (fields.next()??, fields.next()??, fields.next()??);
+            if fields.next().is_some() || col >= n || row >= n || digit > n {
+                return None;
+            }
+            if kind == "R" {
+                grid[row as usize * n as usize + col as usize] = digit;
+            }
+        }
+        "M" if rest.is_empty() => {}
+        "S" if rest.len() == grid.len() => {
+            for (cell, b) in grid.iter_mut().zip(rest.bytes()) {
+                let digit = b.checked_sub(b'0').filter(|&d| (1..=n).contains(&d))?;
+                *cell = digit;
+            }
+        }
+        _ => return None,
+    }
+    Some(())
+}
+
+/// Write `puzzle` with the digits in `partial` (row-major, 0 = empty) as a Keen save file.
+///
+/// Each filled cell becomes one `R` move, all of them applied. The game re-derives
+/// everything else (pencil marks, timer, undo history) from these records.
+pub fn encode_sgt_save(puzzle: &Puzzle, partial: &[u8]) -> Result<String, IoError> {
+    let n = puzzle.n as usize;
+    if partial.len() != n * n {
+        return Err(CoreError::SolutionLengthMismatch {
+            expected: n * n,
+            got: partial.len(),
+        }
+        .into());
+    }
+    if let Some(idx) = partial.iter().position(|&v| v > puzzle.n) {
+        return Err(CoreError::DigitOutOfRange {
+            row: (idx / n) as u8,
+            col: (idx % n) as u8,
+            value: partial[idx],
+            n: puzzle.n,
+        }
+        .into());
+    }
+
+    let desc = encode_keen_desc(puzzle, Ruleset::keen_baseline())?;
+    let params = puzzle.n.to_string();
+    let moves: Vec<String> = partial
+        .iter()
+        .enumerate()
+        .filter(|&(_, &v)| v != 0)
+        .map(|(idx, &v)| format!("R{},{},{v}", idx % n, idx / n))
+        .collect();
+    let states = (moves.len() + 1).to_string();
+
+    let mut out = String::new();
+    let mut record = |key: &str, value: &str| {
+        out.push_str(&format!("{key:<8}:{}:{value}\n", value.len()));
+    };
+    record("SAVEFILE", SAVEFILE_MAGIC);
+    record("VERSION", SAVEFILE_VERSION);
+    record("GAME", GAME_NAME);
+    record("PARAMS", &params);
+    record("CPARAMS", &params);
+    record("DESC", &desc);
+    record("NSTATES", &states);
+    record("STATEPOS", &states);
+    for mv in &moves {
+        record("MOVE", mv);
+    }
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DESC_2X2: &str = "b__,a3a3";
+
+    fn parse(text: &str) -> Result<SgtSave, IoError> {
+        parse_sgt_save(text.as_bytes())
+    }
+
+    #[test]
+    fn encoded_save_round_trips() {
+        let puzzle = parse_keen_desc(2, DESC_2X2).unwrap();
+        let text = encode_sgt_save(&puzzle, &[1, 0, 0, 1]).unwrap();
+        assert!(text.starts_with("SAVEFILE:41:Simon Tatham's Portable Puzzle Collection\n"));
+        assert!(text.contains("DESC    :8:b__,a3a3\nNSTATES :1:3\nSTATEPOS:1:3\n"));
+        assert!(text.ends_with("MOVE    :6:R0,0,1\nMOVE    :6:R1,1,1\n"));
+
+        let save = parse(&text).unwrap();
+        assert_eq!(save.puzzle, puzzle);
+        assert_eq!(save.grid, vec![1, 0, 0, 1]);
+    }
+
+    #[test]
+    fn encode_rejects_bad_partial_grids() {
+        let puzzle = parse_keen_desc(2, DESC_2X2).unwrap();
+        assert!(matches!(
+            encode_sgt_save(&puzzle, &[0; 3]),
+            Err(IoError::Core(CoreError::SolutionLengthMismatch { .. }))
+        ));
+        assert!(matches!(
+            encode_sgt_save(&puzzle, &[0, 3, 0, 0]),
+            Err(IoError::Core(CoreError::DigitOutOfRange { col: 1, .. }))
+        ));
+    }
+
+    #[test]
+    fn statepos_excludes_redo_history_and_restart_clears() {
+        let text = "SAVEFILE:41:Simon Tatham's Portable Puzzle Collection\n\
+                    VERSION :1:1\nGAME    :4:Keen\nPARAMS  :1:2\nDESC    :8:b__,a3a3\n\
+                    NSTATES :1:5\nSTATEPOS:1:4\n\
+                    MOVE    :6:R0,0,2\nRESTART :8:b__,a3a3\nMOVE    :5:S1221\n\
+                    MOVE    :6:R1,0,1\n";
+        assert_eq!(parse(text).unwrap().grid, vec![1, 2, 2, 1]);
+    }
+
+    #[test]
+    fn malformed_records_are_reported() {
+        let err = |text: &str| match parse(text) {
+            Err(IoError::SgtSave(e)) => e,
+            other => panic!("expected a save error, got {other:?}"),
+        };
+        assert!(matches!(err("VERSION :1:1\n"), SgtSaveError::NotASaveFile));
+        assert!(matches!(
+            err("SAVEFILE:x:y\n"),
+            SgtSaveError::MalformedRecord { offset: 0 }
+        ));
+        assert!(matches!(
+            err("SAVEFILE:99:Simon"),
+            SgtSaveError::TruncatedRecord { declared: 99, .. }
+        ));
+        let header = "SAVEFILE:41:Simon Tatham's Portable Puzzle Collection\nVERSION :1:1\n";
+        assert!(matches!(
+            err(&format!("{header}GAME    :4:Solo\n")),
+            SgtSaveError::WrongGame(g) if g == "Solo"
+        ));
+        assert!(matches!(
+            err(&format!("{header}GAME    :4:Keen\nPARAMS  :1:x\n")),
+            SgtSaveError::InvalidParams(_)
+        ));
+        assert!(matches!(
+            err(&format!(
+                "{header}GAME    :4:Keen\nPARAMS  :1:2\nDESC    :8:b__,a3a3\n\
+                 NSTATES :1:2\nSTATEPOS:1:2\nMOVE    :6:R2,0,1\n"
+            )),
+            SgtSaveError::InvalidMove { index: 0, .. }
+        ));
+    }
+}
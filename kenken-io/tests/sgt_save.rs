@@ -0,0 +1,59 @@
+//! Hand-written sgt-puzzles save files in `tests/fixtures`.
+#![cfg(feature = "io-sgt-save")]
+
+use kenken_core::format::sgt_desc::SgtDescError;
+use kenken_io::error::IoError;
+use kenken_io::sgt_save::{SgtSaveError, encode_sgt_save, parse_sgt_save};
+
+fn fixture(name: &str) -> Result<kenken_io::sgt_save::SgtSave, IoError> {
+    let path = format!("{}/tests/fixtures/{name}", env!("CARGO_MANIFEST_DIR"));
+    parse_sgt_save(std::fs::File::open(path).unwrap())
+}
+
+#[test]
+fn save_without_moves_has_an_empty_grid() {
+    let save = fixture("no_moves.sav").unwrap();
+    assert_eq!(save.params, "4de");
+    assert_eq!(save.puzzle.n, 4);
+    assert_eq!(save.puzzle.cages.len(), 16);
+    assert_eq!(save.grid, vec![0; 16]);
+}
+
+#[test]
+fn later_moves_overwrite_earlier_ones_and_redo_history_is_ignored() {
+    let save = fixture("overwrites.sav").unwrap();
+    // (0,0) is set to 3 then 1, (3,1) is set then cleared, the pencil mark is skipped and
+    // the last move is past STATEPOS.
+    #[rustfmt::skip]
+    let expected = vec![
+        1, 0, 0, 0,
+        0, 0, 0, 0,
+        0, 0, 0, 0,
+        0, 0, 2, 0,
+    ];
+    assert_eq!(save.grid, expected);
+}
+
+#[test]
+fn invalid_desc_in_a_well_formed_save_is_reported() {
+    let err = fixture("invalid_desc.sav").unwrap_err();
+    assert!(
+        matches!(
+            err,
+            IoError::SgtSave(SgtSaveError::InvalidDesc(
+                SgtDescError::ClueTypeUnknown { .. }
+            ))
+        ),
+        "{err:?}"
+    );
+}
+
+#[test]
+fn fixtures_survive_a_round_trip() {
+    let save = fixture("overwrites.sav").unwrap();
+    let text = encode_sgt_save(&save.puzzle, &save.grid).unwrap();
+    let again = parse_sgt_save(text.as_bytes()).unwrap();
+    assert_eq!(again.puzzle, save.puzzle);
+    assert_eq!(again.grid, save.grid);
+    assert_eq!(again.desc, save.desc);
+}
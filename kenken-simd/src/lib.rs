@@ -4,29 +4,110 @@
 //! to call `#[target_feature]`-compiled functions.
 //!
 //! Everything exported from this crate should be safe to call.
+//!
+//! Each dispatcher picks its implementation on first call and caches the function pointer in
+//! a `OnceLock`, so every later call is an atomic load plus an indirect call. [`init`]
+//! resolves them all up front. With the `compile-time-dispatch` feature, the scalar popcounts
+//! skip the `OnceLock` whenever the build already targets a popcount instruction (see
+//! [`COMPILE_TIME_POPCOUNT`]) and inline to it. Targets without runtime feature detection,
+//! such as `wasm32`, always take the portable paths.
 
 #![deny(warnings)]
+// 32-bit ARM NEON intrinsics, their runtime detection and `target_feature(enable = "neon")`
+// are still unstable there (aarch64 has them on stable).
+#![cfg_attr(
+    target_arch = "arm",
+    feature(
+        stdarch_arm_neon_intrinsics,
+        stdarch_arm_feature_detection,
+        arm_target_feature
+    )
+)]
 
 use std::sync::OnceLock;
 
+/// Whether [`popcount_u32`], [`popcount_u64`], [`popcount_u128`] and [`popcount_u256`] are
+/// resolved at build time: `compile-time-dispatch` is on and the target has a popcount
+/// instruction that `count_ones` compiles to (x86_64 built with `popcnt`, aarch64, wasm32).
+/// Otherwise they go through the runtime dispatcher.
+pub const COMPILE_TIME_POPCOUNT: bool = cfg!(all(
+    feature = "compile-time-dispatch",
+    any(
+        all(target_arch = "x86_64", target_feature = "popcnt"),
+        target_arch = "aarch64",
+        target_arch = "wasm32"
+    )
+));
+
+static POPCOUNT_U32: OnceLock<fn(u32) -> u32> = OnceLock::new();
+static POPCOUNT_U64: OnceLock<fn(u64) -> u32> = OnceLock::new();
+static POPCOUNT_U128: OnceLock<fn([u64; 2]) -> u32> = OnceLock::new();
+static POPCOUNT_U256: OnceLock<fn([u64; 4]) -> u32> = OnceLock::new();
+static POPCOUNT_U32_SLICE_SUM: OnceLock<fn(&[u32]) -> u32> = OnceLock::new();
+static POPCOUNT_U64_SLICE_SUM: OnceLock<fn(&[u64]) -> u32> = OnceLock::new();
+static ANDNOT_POPCOUNT_BATCH_U32: OnceLock<BatchU32> = OnceLock::new();
+static ANDNOT_POPCOUNT_BATCH_U64: OnceLock<BatchU64> = OnceLock::new();
+
+/// Resolve every dispatcher now rather than on its first call, so CPU feature detection does
+/// not land inside a timed region (e.g. the first solve of a benchmark). Optional: calling it
+/// never changes a result, and calling it again does nothing.
+pub fn init() {
+    POPCOUNT_U32.get_or_init(select_popcount_u32);
+    POPCOUNT_U64.get_or_init(select_popcount_u64);
+    POPCOUNT_U128.get_or_init(select_popcount_u128);
+    POPCOUNT_U256.get_or_init(select_popcount_u256);
+    POPCOUNT_U32_SLICE_SUM.get_or_init(select_popcount_u32_slice_sum);
+    POPCOUNT_U64_SLICE_SUM.get_or_init(select_popcount_u64_slice_sum);
+    ANDNOT_POPCOUNT_BATCH_U32.get_or_init(select_andnot_popcount_batch_u32);
+    ANDNOT_POPCOUNT_BATCH_U64.get_or_init(select_andnot_popcount_batch_u64);
+}
+
+#[inline]
 pub fn popcount_u32(x: u32) -> u32 {
-    static IMPL: OnceLock<fn(u32) -> u32> = OnceLock::new();
-    (IMPL.get_or_init(select_popcount_u32))(x)
+    if COMPILE_TIME_POPCOUNT {
+        return popcount_u32_scalar(x);
+    }
+    popcount_u32_runtime(x)
 }
 
+#[inline]
 pub fn popcount_u64(x: u64) -> u32 {
-    static IMPL: OnceLock<fn(u64) -> u32> = OnceLock::new();
-    (IMPL.get_or_init(select_popcount_u64))(x)
+    if COMPILE_TIME_POPCOUNT {
+        return popcount_u64_scalar(x);
+    }
+    popcount_u64_runtime(x)
 }
 
+#[inline]
 pub fn popcount_u128(x: [u64; 2]) -> u32 {
-    static IMPL: OnceLock<fn([u64; 2]) -> u32> = OnceLock::new();
-    (IMPL.get_or_init(select_popcount_u128))(x)
+    if COMPILE_TIME_POPCOUNT {
+        return popcount_u128_scalar(x);
+    }
+    popcount_u128_runtime(x)
 }
 
+#[inline]
 pub fn popcount_u256(x: [u64; 4]) -> u32 {
-    static IMPL: OnceLock<fn([u64; 4]) -> u32> = OnceLock::new();
-    (IMPL.get_or_init(select_popcount_u256))(x)
+    if COMPILE_TIME_POPCOUNT {
+        return popcount_u256_scalar(x);
+    }
+    popcount_u256_runtime(x)
+}
+
+fn popcount_u32_runtime(x: u32) -> u32 {
+    (POPCOUNT_U32.get_or_init(select_popcount_u32))(x)
+}
+
+fn popcount_u64_runtime(x: u64) -> u32 {
+    (POPCOUNT_U64.get_or_init(select_popcount_u64))(x)
+}
+
+fn popcount_u128_runtime(x: [u64; 2]) -> u32 {
+    (POPCOUNT_U128.get_or_init(select_popcount_u128))(x)
+}
+
+fn popcount_u256_runtime(x: [u64; 4]) -> u32 {
+    (POPCOUNT_U256.get_or_init(select_popcount_u256))(x)
 }
 
 fn select_popcount_u32() -> fn(u32) -> u32 {
@@ -37,6 +118,15 @@ fn select_popcount_u32() -> fn(u32) -> u32 {
         }
     }
 
+    // aarch64 lowers `count_ones` to CNT already; ARMv7 without NEON in its baseline
+    // features falls back to a bit-twiddling sequence.
+    #[cfg(target_arch = "arm")]
+    {
+        if std::arch::is_arm_feature_detected!("neon") {
+            return popcount_u32_arm_neon;
+        }
+    }
+
     popcount_u32_scalar
 }
 
@@ -48,6 +138,14 @@ fn select_popcount_u64() -> fn(u64) -> u32 {
         }
     }
 
+    // On ARMv7, `u64::count_ones` is a library call over both halves.
+    #[cfg(target_arch = "arm")]
+    {
+        if std::arch::is_arm_feature_detected!("neon") {
+            return popcount_u64_arm_neon;
+        }
+    }
+
     popcount_u64_scalar
 }
 
@@ -121,18 +219,23 @@ fn select_popcount_u256() -> fn([u64; 4]) -> u32 {
     popcount_u256_scalar
 }
 
+// On the `COMPILE_TIME_POPCOUNT` targets, `count_ones` is the popcount instruction itself.
+#[inline]
 fn popcount_u32_scalar(x: u32) -> u32 {
     x.count_ones()
 }
 
+#[inline]
 fn popcount_u64_scalar(x: u64) -> u32 {
     x.count_ones()
 }
 
+#[inline]
 fn popcount_u128_scalar(x: [u64; 2]) -> u32 {
     x[0].count_ones() + x[1].count_ones()
 }
 
+#[inline]
 fn popcount_u256_scalar(x: [u64; 4]) -> u32 {
     x[0].count_ones() + x[1].count_ones() + x[2].count_ones() + x[3].count_ones()
 }
@@ -163,6 +266,34 @@ unsafe fn popcount_u64_x86_popcnt_inner(x: u64) -> u32 {
     core::arch::x86_64::_popcnt64(x as i64) as u32
 }
 
+// ARM (armv7l) NEON popcount_u32 / popcount_u64
+#[cfg(target_arch = "arm")]
+fn popcount_u32_arm_neon(x: u32) -> u32 {
+    // Safety: selected only when the host CPU reports NEON.
+    unsafe { popcount_u64_arm_neon_inner(x as u64) }
+}
+
+#[cfg(target_arch = "arm")]
+fn popcount_u64_arm_neon(x: u64) -> u32 {
+    // Safety: selected only when the host CPU reports NEON.
+    unsafe { popcount_u64_arm_neon_inner(x) }
+}
+
+#[cfg(target_arch = "arm")]
+#[target_feature(enable = "neon")]
+unsafe fn popcount_u64_arm_neon_inner(x: u64) -> u32 {
+    use core::arch::arm::*;
+
+    // Move 64 bits into a D register and count bits per byte
+    let counts = vcnt_u8(vcreate_u8(x));
+
+    // Horizontal sum: 8 u8 -> 1 u64 using pairwise addition
+    let sum_u16 = vpaddl_u8(counts);
+    let sum_u32 = vpaddl_u16(sum_u16);
+    let sum_u64 = vpaddl_u32(sum_u32);
+    vget_lane_u64(sum_u64, 0) as u32
+}
+
 // ============================================================================
 // popcount_u128 implementations
 // ============================================================================
@@ -407,8 +538,7 @@ unsafe fn popcount_u256_arm_neon_inner(x: [u64; 4]) -> u32 {
 
 /// Sum popcounts over a slice. This is useful for "count bits in many masks".
 pub fn popcount_u32_slice_sum(xs: &[u32]) -> u32 {
-    static IMPL: OnceLock<fn(&[u32]) -> u32> = OnceLock::new();
-    (IMPL.get_or_init(select_popcount_u32_slice_sum))(xs)
+    (POPCOUNT_U32_SLICE_SUM.get_or_init(select_popcount_u32_slice_sum))(xs)
 }
 
 fn select_popcount_u32_slice_sum() -> fn(&[u32]) -> u32 {
@@ -419,6 +549,13 @@ fn select_popcount_u32_slice_sum() -> fn(&[u32]) -> u32 {
         }
     }
 
+    #[cfg(target_arch = "arm")]
+    {
+        if std::arch::is_arm_feature_detected!("neon") {
+            return popcount_u32_slice_sum_arm_neon;
+        }
+    }
+
     popcount_u32_slice_sum_scalar
 }
 
@@ -460,6 +597,665 @@ unsafe fn popcount_u32_slice_sum_aarch64_neon_inner(xs: &[u32]) -> u32 {
     sum + rem.iter().map(|&x| x.count_ones()).sum::<u32>()
 }
 
+// ARM (armv7l) NEON popcount_u32_slice_sum
+#[cfg(target_arch = "arm")]
+fn popcount_u32_slice_sum_arm_neon(xs: &[u32]) -> u32 {
+    // Safety: selected only when the host CPU reports NEON.
+    unsafe { popcount_u32_slice_sum_arm_neon_inner(xs) }
+}
+
+#[cfg(target_arch = "arm")]
+#[target_feature(enable = "neon")]
+unsafe fn popcount_u32_slice_sum_arm_neon_inner(xs: &[u32]) -> u32 {
+    use core::arch::arm::*;
+
+    // Process 16 bytes at a time (4 u32s). `vcntq_u8` counts bits per byte.
+    let mut sum: u32 = 0;
+    let mut i = 0usize;
+    let chunks = xs.len() / 4;
+    while i < chunks {
+        let p = xs.as_ptr().add(i * 4) as *const u8;
+        let bytes: uint8x16_t = vld1q_u8(p);
+        let counts: uint8x16_t = vcntq_u8(bytes);
+        // Horizontal sum of 16 u8 lanes into u32.
+        let sum_u16: uint16x8_t = vpaddlq_u8(counts);
+        let sum_u32: uint32x4_t = vpaddlq_u16(sum_u16);
+        let sum_u64: uint64x2_t = vpaddlq_u32(sum_u32);
+        let lane0 = vgetq_lane_u64(sum_u64, 0);
+        let lane1 = vgetq_lane_u64(sum_u64, 1);
+        sum = sum.wrapping_add((lane0 + lane1) as u32);
+        i += 1;
+    }
+
+    // Tail.
+    let rem = &xs[(i * 4)..];
+    sum + rem.iter().map(|&x| x.count_ones()).sum::<u32>()
+}
+
+/// Sum popcounts over a slice of 64-bit masks.
+pub fn popcount_u64_slice_sum(xs: &[u64]) -> u32 {
+    (POPCOUNT_U64_SLICE_SUM.get_or_init(select_popcount_u64_slice_sum))(xs)
+}
+
+fn select_popcount_u64_slice_sum() -> fn(&[u64]) -> u32 {
+    #[cfg(target_arch = "aarch64")]
+    {
+        if std::arch::is_aarch64_feature_detected!("neon") {
+            return popcount_u64_slice_sum_aarch64_neon;
+        }
+    }
+
+    #[cfg(target_arch = "arm")]
+    {
+        if std::arch::is_arm_feature_detected!("neon") {
+            return popcount_u64_slice_sum_arm_neon;
+        }
+    }
+
+    popcount_u64_slice_sum_scalar
+}
+
+fn popcount_u64_slice_sum_scalar(xs: &[u64]) -> u32 {
+    xs.iter().map(|&x| x.count_ones()).sum()
+}
+
+#[cfg(target_arch = "aarch64")]
+fn popcount_u64_slice_sum_aarch64_neon(xs: &[u64]) -> u32 {
+    // Safety: selected only when the host CPU reports NEON.
+    unsafe { popcount_u64_slice_sum_aarch64_neon_inner(xs) }
+}
+
+#[cfg(target_arch = "aarch64")]
+#[target_feature(enable = "neon")]
+unsafe fn popcount_u64_slice_sum_aarch64_neon_inner(xs: &[u64]) -> u32 {
+    use core::arch::aarch64::*;
+
+    // Process 16 bytes at a time (2 u64s). `vcntq_u8` counts bits per byte.
+    let mut sum: u32 = 0;
+    let mut i = 0usize;
+    let chunks = xs.len() / 2;
+    while i < chunks {
+        let p = xs.as_ptr().add(i * 2) as *const u8;
+        let counts: uint8x16_t = vcntq_u8(vld1q_u8(p));
+        // Horizontal sum of 16 u8 lanes (at most 128) in one across-vector add.
+        sum = sum.wrapping_add(vaddlvq_u8(counts) as u32);
+        i += 1;
+    }
+
+    // Tail.
+    let rem = &xs[(i * 2)..];
+    sum + rem.iter().map(|&x| x.count_ones()).sum::<u32>()
+}
+
+#[cfg(target_arch = "arm")]
+fn popcount_u64_slice_sum_arm_neon(xs: &[u64]) -> u32 {
+    // Safety: selected only when the host CPU reports NEON.
+    unsafe { popcount_u64_slice_sum_arm_neon_inner(xs) }
+}
+
+#[cfg(target_arch = "arm")]
+#[target_feature(enable = "neon")]
+unsafe fn popcount_u64_slice_sum_arm_neon_inner(xs: &[u64]) -> u32 {
+    use core::arch::arm::*;
+
+    // Process 16 bytes at a time (2 u64s). `vcntq_u8` counts bits per byte.
+    let mut sum: u32 = 0;
+    let mut i = 0usize;
+    let chunks = xs.len() / 2;
+    while i < chunks {
+        let p = xs.as_ptr().add(i * 2) as *const u8;
+        let counts: uint8x16_t = vcntq_u8(vld1q_u8(p));
+        // Horizontal sum of 16 u8 lanes into u32 (ARMv7 has no across-vector add).
+        let sum_u16: uint16x8_t = vpaddlq_u8(counts);
+        let sum_u32: uint32x4_t = vpaddlq_u16(sum_u16);
+        let sum_u64: uint64x2_t = vpaddlq_u32(sum_u32);
+        let lane0 = vgetq_lane_u64(sum_u64, 0);
+        let lane1 = vgetq_lane_u64(sum_u64, 1);
+        sum = sum.wrapping_add((lane0 + lane1) as u32);
+        i += 1;
+    }
+
+    // Tail.
+    let rem = &xs[(i * 2)..];
+    sum + rem.iter().map(|&x| x.count_ones()).sum::<u32>()
+}
+
+/// Candidate masks and their popcounts for every cell of an `n` x `n` grid, `n = rows.len()`:
+/// `out_masks[r * n + c] = statics[r * n + c] & !(rows[r] | cols[c])`.
+///
+/// The layout is the solver's: `statics` and both outputs are row-major over the grid, `rows`
+/// and `cols` hold the digits already placed in each row and column. A row's mask is shared by
+/// the whole row, so each row is one broadcast plus an AND-NOT and popcount over `n` words.
+///
+/// # Panics
+/// If `cols` is not `n` long or `statics`, `out_masks` or `out_popcounts` is not `n * n` long.
+pub fn andnot_popcount_batch_u32(
+    statics: &[u32],
+    rows: &[u32],
+    cols: &[u32],
+    out_masks: &mut [u32],
+    out_popcounts: &mut [u32],
+) {
+    check_batch_lengths(
+        statics.len(),
+        rows.len(),
+        cols.len(),
+        out_masks.len(),
+        out_popcounts.len(),
+    );
+    (ANDNOT_POPCOUNT_BATCH_U32.get_or_init(select_andnot_popcount_batch_u32))(
+        statics,
+        rows,
+        cols,
+        out_masks,
+        out_popcounts,
+    )
+}
+
+/// [`andnot_popcount_batch_u32`] over 64-bit masks, for grids past 31x31.
+///
+/// # Panics
+/// As [`andnot_popcount_batch_u32`].
+pub fn andnot_popcount_batch_u64(
+    statics: &[u64],
+    rows: &[u64],
+    cols: &[u64],
+    out_masks: &mut [u64],
+    out_popcounts: &mut [u32],
+) {
+    check_batch_lengths(
+        statics.len(),
+        rows.len(),
+        cols.len(),
+        out_masks.len(),
+        out_popcounts.len(),
+    );
+    (ANDNOT_POPCOUNT_BATCH_U64.get_or_init(select_andnot_popcount_batch_u64))(
+        statics,
+        rows,
+        cols,
+        out_masks,
+        out_popcounts,
+    )
+}
+
+/// `(statics, rows, cols, out_masks, out_popcounts)`, as [`andnot_popcount_batch_u32`] takes.
+type BatchU32 = fn(&[u32], &[u32], &[u32], &mut [u32], &mut [u32]);
+/// `(statics, rows, cols, out_masks, out_popcounts)`, as [`andnot_popcount_batch_u64`] takes.
+type BatchU64 = fn(&[u64], &[u64], &[u64], &mut [u64], &mut [u32]);
+
+/// The SIMD paths index by these lengths without bounds checks.
+fn check_batch_lengths(statics: usize, rows: usize, cols: usize, masks: usize, pops: usize) {
+    let cells = rows * rows;
+    assert!(
+        cols == rows && statics == cells && masks == cells && pops == cells,
+        "batch of {rows} rows needs {rows} cols and {cells} statics/outputs, \
+         got {cols}, {statics}, {masks}, {pops}"
+    );
+}
+
+fn select_andnot_popcount_batch_u32() -> BatchU32 {
+    #[cfg(target_arch = "x86_64")]
+    {
+        if std::arch::is_x86_feature_detected!("avx2") {
+            return andnot_popcount_batch_u32_x86_avx2;
+        }
+    }
+
+    #[cfg(target_arch = "aarch64")]
+    {
+        if std::arch::is_aarch64_feature_detected!("neon") {
+            return andnot_popcount_batch_u32_aarch64_neon;
+        }
+    }
+
+    #[cfg(target_arch = "arm")]
+    {
+        if std::arch::is_arm_feature_detected!("neon") {
+            return andnot_popcount_batch_u32_arm_neon;
+        }
+    }
+
+    andnot_popcount_batch_u32_scalar
+}
+
+fn select_andnot_popcount_batch_u64() -> BatchU64 {
+    #[cfg(target_arch = "x86_64")]
+    {
+        if std::arch::is_x86_feature_detected!("avx2") {
+            return andnot_popcount_batch_u64_x86_avx2;
+        }
+    }
+
+    #[cfg(target_arch = "aarch64")]
+    {
+        if std::arch::is_aarch64_feature_detected!("neon") {
+            return andnot_popcount_batch_u64_aarch64_neon;
+        }
+    }
+
+    #[cfg(target_arch = "arm")]
+    {
+        if std::arch::is_arm_feature_detected!("neon") {
+            return andnot_popcount_batch_u64_arm_neon;
+        }
+    }
+
+    andnot_popcount_batch_u64_scalar
+}
+
+fn andnot_popcount_batch_u32_scalar(
+    statics: &[u32],
+    rows: &[u32],
+    cols: &[u32],
+    out_masks: &mut [u32],
+    out_popcounts: &mut [u32],
+) {
+    let n = rows.len();
+    for (r, &row) in rows.iter().enumerate() {
+        for (c, &col) in cols.iter().enumerate() {
+            let mask = statics[r * n + c] & !(row | col);
+            out_masks[r * n + c] = mask;
+            out_popcounts[r * n + c] = mask.count_ones();
+        }
+    }
+}
+
+fn andnot_popcount_batch_u64_scalar(
+    statics: &[u64],
+    rows: &[u64],
+    cols: &[u64],
+    out_masks: &mut [u64],
+    out_popcounts: &mut [u32],
+) {
+    let n = rows.len();
+    for (r, &row) in rows.iter().enumerate() {
+        for (c, &col) in cols.iter().enumerate() {
+            let mask = statics[r * n + c] & !(row | col);
+            out_masks[r * n + c] = mask;
+            out_popcounts[r * n + c] = mask.count_ones();
+        }
+    }
+}
+
+#[cfg(target_arch = "x86_64")]
+fn andnot_popcount_batch_u32_x86_avx2(
+    statics: &[u32],
+    rows: &[u32],
+    cols: &[u32],
+    out_masks: &mut [u32],
+    out_popcounts: &mut [u32],
+) {
+    // Safety: selected only when the host CPU reports AVX2; lengths checked by the caller.
+    unsafe {
+        andnot_popcount_batch_u32_x86_avx2_inner(statics, rows, cols, out_masks, out_popcounts)
+    }
+}
+
+#[cfg(target_arch = "x86_64")]
+fn andnot_popcount_batch_u64_x86_avx2(
+    statics: &[u64],
+    rows: &[u64],
+    cols: &[u64],
+    out_masks: &mut [u64],
+    out_popcounts: &mut [u32],
+) {
+    // Safety: selected only when the host CPU reports AVX2; lengths checked by the caller.
+    unsafe {
+        andnot_popcount_batch_u64_x86_avx2_inner(statics, rows, cols, out_masks, out_popcounts)
+    }
+}
+
+/// Per-byte popcounts of `v` via the PSHUFB nibble table.
+#[cfg(target_arch = "x86_64")]
+#[target_feature(enable = "avx2")]
+fn byte_popcounts_x86_avx2(v: core::arch::x86_64::__m256i) -> core::arch::x86_64::__m256i {
+    use core::arch::x86_64::*;
+
+    let lookup = _mm256_setr_epi8(
+        0, 1, 1, 2, 1, 2, 2, 3, 1, 2, 2, 3, 2, 3, 3, 4, // low 128-bit lane
+        0, 1, 1, 2, 1, 2, 2, 3, 1, 2, 2, 3, 2, 3, 3, 4, // high 128-bit lane
+    );
+    let nibble = _mm256_set1_epi8(0x0F);
+    let lo = _mm256_shuffle_epi8(lookup, _mm256_and_si256(v, nibble));
+    let hi = _mm256_shuffle_epi8(lookup, _mm256_and_si256(_mm256_srli_epi16(v, 4), nibble));
+    _mm256_add_epi8(lo, hi)
+}
+
+#[cfg(target_arch = "x86_64")]
+#[target_feature(enable = "avx2")]
+unsafe fn andnot_popcount_batch_u32_x86_avx2_inner(
+    statics: &[u32],
+    rows: &[u32],
+    cols: &[u32],
+    out_masks: &mut [u32],
+    out_popcounts: &mut [u32],
+) {
+    use core::arch::x86_64::*;
+
+    let n = rows.len();
+    let ones_u8 = _mm256_set1_epi8(1);
+    let ones_u16 = _mm256_set1_epi16(1);
+    for (r, &row) in rows.iter().enumerate() {
+        let base = r * n;
+        let row_v = _mm256_set1_epi32(row as i32);
+        let mut c = 0usize;
+        // Eight cells per step.
+        while c + 8 <= n {
+            // Safety: the step stays below `n`, inside the lengths the caller checked.
+            let (s, col) = unsafe {
+                (
+                    _mm256_loadu_si256(statics.as_ptr().add(base + c) as *const __m256i),
+                    _mm256_loadu_si256(cols.as_ptr().add(c) as *const __m256i),
+                )
+            };
+            let mask = _mm256_andnot_si256(_mm256_or_si256(row_v, col), s);
+            // Byte counts -> 16-bit pair sums -> 32-bit lane sums.
+            let pairs = _mm256_maddubs_epi16(byte_popcounts_x86_avx2(mask), ones_u8);
+            let counts = _mm256_madd_epi16(pairs, ones_u16);
+            // Safety: as for the loads.
+            unsafe {
+                _mm256_storeu_si256(out_masks.as_mut_ptr().add(base + c) as *mut __m256i, mask);
+                _mm256_storeu_si256(
+                    out_popcounts.as_mut_ptr().add(base + c) as *mut __m256i,
+                    counts,
+                );
+            }
+            c += 8;
+        }
+        // Tail.
+        for c in c..n {
+            let mask = statics[base + c] & !(row | cols[c]);
+            out_masks[base + c] = mask;
+            out_popcounts[base + c] = mask.count_ones();
+        }
+    }
+}
+
+#[cfg(target_arch = "x86_64")]
+#[target_feature(enable = "avx2")]
+unsafe fn andnot_popcount_batch_u64_x86_avx2_inner(
+    statics: &[u64],
+    rows: &[u64],
+    cols: &[u64],
+    out_masks: &mut [u64],
+    out_popcounts: &mut [u32],
+) {
+    use core::arch::x86_64::*;
+
+    let n = rows.len();
+    for (r, &row) in rows.iter().enumerate() {
+        let base = r * n;
+        let row_v = _mm256_set1_epi64x(row as i64);
+        let mut c = 0usize;
+        // Four cells per step.
+        while c + 4 <= n {
+            // Safety: the step stays below `n`, inside the lengths the caller checked.
+            let (s, col) = unsafe {
+                (
+                    _mm256_loadu_si256(statics.as_ptr().add(base + c) as *const __m256i),
+                    _mm256_loadu_si256(cols.as_ptr().add(c) as *const __m256i),
+                )
+            };
+            let mask = _mm256_andnot_si256(_mm256_or_si256(row_v, col), s);
+            // SAD against zero sums the byte counts of each 64-bit lane.
+            let counts = _mm256_sad_epu8(byte_popcounts_x86_avx2(mask), _mm256_setzero_si256());
+            // Each 64-bit count fits its low 32 bits: gather those into the low 128 bits.
+            let packed =
+                _mm256_permutevar8x32_epi32(counts, _mm256_setr_epi32(0, 2, 4, 6, 0, 0, 0, 0));
+            // Safety: as for the loads.
+            unsafe {
+                _mm256_storeu_si256(out_masks.as_mut_ptr().add(base + c) as *mut __m256i, mask);
+                _mm_storeu_si128(
+                    out_popcounts.as_mut_ptr().add(base + c) as *mut __m128i,
+                    _mm256_castsi256_si128(packed),
+                );
+            }
+            c += 4;
+        }
+        // Tail.
+        for c in c..n {
+            let mask = statics[base + c] & !(row | cols[c]);
+            out_masks[base + c] = mask;
+            out_popcounts[base + c] = mask.count_ones();
+        }
+    }
+}
+
+#[cfg(target_arch = "aarch64")]
+fn andnot_popcount_batch_u32_aarch64_neon(
+    statics: &[u32],
+    rows: &[u32],
+    cols: &[u32],
+    out_masks: &mut [u32],
+    out_popcounts: &mut [u32],
+) {
+    // Safety: selected only when the host CPU reports NEON; lengths checked by the caller.
+    unsafe {
+        andnot_popcount_batch_u32_aarch64_neon_inner(statics, rows, cols, out_masks, out_popcounts)
+    }
+}
+
+#[cfg(target_arch = "aarch64")]
+#[target_feature(enable = "neon")]
+unsafe fn andnot_popcount_batch_u32_aarch64_neon_inner(
+    statics: &[u32],
+    rows: &[u32],
+    cols: &[u32],
+    out_masks: &mut [u32],
+    out_popcounts: &mut [u32],
+) {
+    use core::arch::aarch64::*;
+
+    let n = rows.len();
+    for (r, &row) in rows.iter().enumerate() {
+        let base = r * n;
+        let row_v = vdupq_n_u32(row);
+        let mut c = 0usize;
+        // Four cells per step.
+        while c + 4 <= n {
+            // Safety: the step stays below `n`, inside the lengths the caller checked.
+            let (s, col) = unsafe {
+                (
+                    vld1q_u32(statics.as_ptr().add(base + c)),
+                    vld1q_u32(cols.as_ptr().add(c)),
+                )
+            };
+            // BIC: s & !(row | col).
+            let mask = vbicq_u32(s, vorrq_u32(row_v, col));
+            let bytes = vcntq_u8(vreinterpretq_u8_u32(mask));
+            let counts = vpaddlq_u16(vpaddlq_u8(bytes));
+            // Safety: as for the loads.
+            unsafe {
+                vst1q_u32(out_masks.as_mut_ptr().add(base + c), mask);
+                vst1q_u32(out_popcounts.as_mut_ptr().add(base + c), counts);
+            }
+            c += 4;
+        }
+        // Tail.
+        for c in c..n {
+            let mask = statics[base + c] & !(row | cols[c]);
+            out_masks[base + c] = mask;
+            out_popcounts[base + c] = mask.count_ones();
+        }
+    }
+}
+
+#[cfg(target_arch = "aarch64")]
+fn andnot_popcount_batch_u64_aarch64_neon(
+    statics: &[u64],
+    rows: &[u64],
+    cols: &[u64],
+    out_masks: &mut [u64],
+    out_popcounts: &mut [u32],
+) {
+    // Safety: selected only when the host CPU reports NEON; lengths checked by the caller.
+    unsafe {
+        andnot_popcount_batch_u64_aarch64_neon_inner(statics, rows, cols, out_masks, out_popcounts)
+    }
+}
+
+#[cfg(target_arch = "aarch64")]
+#[target_feature(enable = "neon")]
+unsafe fn andnot_popcount_batch_u64_aarch64_neon_inner(
+    statics: &[u64],
+    rows: &[u64],
+    cols: &[u64],
+    out_masks: &mut [u64],
+    out_popcounts: &mut [u32],
+) {
+    use core::arch::aarch64::*;
+
+    let n = rows.len();
+    for (r, &row) in rows.iter().enumerate() {
+        let base = r * n;
+        let row_v = vdupq_n_u64(row);
+        let mut c = 0usize;
+        // Two cells per step.
+        while c + 2 <= n {
+            // Safety: the step stays below `n`, inside the lengths the caller checked.
+            let (s, col) = unsafe {
+                (
+                    vld1q_u64(statics.as_ptr().add(base + c)),
+                    vld1q_u64(cols.as_ptr().add(c)),
+                )
+            };
+            let mask = vbicq_u64(s, vorrq_u64(row_v, col));
+            let bytes = vcntq_u8(vreinterpretq_u8_u64(mask));
+            let counts = vpaddlq_u32(vpaddlq_u16(vpaddlq_u8(bytes)));
+            // Safety: as for the loads.
+            unsafe {
+                vst1q_u64(out_masks.as_mut_ptr().add(base + c), mask);
+                vst1_u32(out_popcounts.as_mut_ptr().add(base + c), vmovn_u64(counts));
+            }
+            c += 2;
+        }
+        // Tail.
+        for c in c..n {
+            let mask = statics[base + c] & !(row | cols[c]);
+            out_masks[base + c] = mask;
+            out_popcounts[base + c] = mask.count_ones();
+        }
+    }
+}
+
+// ARM (armv7l) NEON andnot_popcount_batch
+#[cfg(target_arch = "arm")]
+fn andnot_popcount_batch_u32_arm_neon(
+    statics: &[u32],
+    rows: &[u32],
+    cols: &[u32],
+    out_masks: &mut [u32],
+    out_popcounts: &mut [u32],
+) {
+    // Safety: selected only when the host CPU reports NEON; lengths checked by the caller.
+    unsafe {
+        andnot_popcount_batch_u32_arm_neon_inner(statics, rows, cols, out_masks, out_popcounts)
+    }
+}
+
+#[cfg(target_arch = "arm")]
+#[target_feature(enable = "neon", enable = "v7")]
+unsafe fn andnot_popcount_batch_u32_arm_neon_inner(
+    statics: &[u32],
+    rows: &[u32],
+    cols: &[u32],
+    out_masks: &mut [u32],
+    out_popcounts: &mut [u32],
+) {
+    use core::arch::arm::*;
+
+    let n = rows.len();
+    for (r, &row) in rows.iter().enumerate() {
+        let base = r * n;
+        let row_v = vdupq_n_u32(row);
+        let mut c = 0usize;
+        // Four cells per step.
+        while c + 4 <= n {
+            // Safety: the step stays below `n`, inside the lengths the caller checked.
+            let (s, col) = unsafe {
+                (
+                    vld1q_u32(statics.as_ptr().add(base + c)),
+                    vld1q_u32(cols.as_ptr().add(c)),
+                )
+            };
+            let mask = vbicq_u32(s, vorrq_u32(row_v, col));
+            let bytes = vcntq_u8(vreinterpretq_u8_u32(mask));
+            let counts = vpaddlq_u16(vpaddlq_u8(bytes));
+            // Safety: as for the loads.
+            unsafe {
+                vst1q_u32(out_masks.as_mut_ptr().add(base + c), mask);
+                vst1q_u32(out_popcounts.as_mut_ptr().add(base + c), counts);
+            }
+            c += 4;
+        }
+        // Tail.
+        for c in c..n {
+            let mask = statics[base + c] & !(row | cols[c]);
+            out_masks[base + c] = mask;
+            out_popcounts[base + c] = mask.count_ones();
+        }
+    }
+}
+
+#[cfg(target_arch = "arm")]
+fn andnot_popcount_batch_u64_arm_neon(
+    statics: &[u64],
+    rows: &[u64],
+    cols: &[u64],
+    out_masks: &mut [u64],
+    out_popcounts: &mut [u32],
+) {
+    // Safety: selected only when the host CPU reports NEON; lengths checked by the caller.
+    unsafe {
+        andnot_popcount_batch_u64_arm_neon_inner(statics, rows, cols, out_masks, out_popcounts)
+    }
+}
+
+#[cfg(target_arch = "arm")]
+#[target_feature(enable = "neon", enable = "v7")]
+unsafe fn andnot_popcount_batch_u64_arm_neon_inner(
+    statics: &[u64],
+    rows: &[u64],
+    cols: &[u64],
+    out_masks: &mut [u64],
+    out_popcounts: &mut [u32],
+) {
+    use core::arch::arm::*;
+
+    let n = rows.len();
+    for (r, &row) in rows.iter().enumerate() {
+        let base = r * n;
+        let row_v = vdupq_n_u64(row);
+        let mut c = 0usize;
+        // Two cells per step.
+        while c + 2 <= n {
+            // Safety: the step stays below `n`, inside the lengths the caller checked.
+            let (s, col) = unsafe {
+                (
+                    vld1q_u64(statics.as_ptr().add(base + c)),
+                    vld1q_u64(cols.as_ptr().add(c)),
+                )
+            };
+            let mask = vbicq_u64(s, vorrq_u64(row_v, col));
+            let bytes = vcntq_u8(vreinterpretq_u8_u64(mask));
+            let counts = vpaddlq_u32(vpaddlq_u16(vpaddlq_u8(bytes)));
+            // Safety: as for the loads.
+            unsafe {
+                vst1q_u64(out_masks.as_mut_ptr().add(base + c), mask);
+                vst1_u32(out_popcounts.as_mut_ptr().add(base + c), vmovn_u64(counts));
+            }
+            c += 2;
+        }
+        // Tail.
+        for c in c..n {
+            let mask = statics[base + c] & !(row | cols[c]);
+            out_masks[base + c] = mask;
+            out_popcounts[base + c] = mask.count_ones();
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -497,6 +1293,22 @@ mod tests {
         );
     }
 
+    #[test]
+    fn popcount_u64_slice_sum_matches_scalar() {
+        // Lengths 0..=5 cover an empty slice, a lone tail and full chunks with and without one.
+        let xs = (0..257u64)
+            .map(|i| i.wrapping_mul(0x9E37_79B9_7F4A_7C15))
+            .collect::<Vec<_>>();
+        for len in (0..=5).chain([256, 257]) {
+            assert_eq!(
+                popcount_u64_slice_sum(&xs[..len]),
+                xs[..len].iter().map(|&x| x.count_ones()).sum::<u32>(),
+                "len {len}"
+            );
+        }
+        assert_eq!(popcount_u64_slice_sum(&[u64::MAX; 9]), 9 * 64);
+    }
+
     #[test]
     fn popcount_u128_matches_scalar() {
         let test_cases = vec![
@@ -542,4 +1354,201 @@ mod tests {
             assert_eq!(popcount_u128(x), 1);
         }
     }
+
+    /// Deterministic pseudo-random words for the batch tests (SplitMix64).
+    #[test]
+    fn dispatch_modes_agree() {
+        let words = words(0x5EED, 512);
+        for (i, &w) in words.iter().enumerate() {
+            let x = w as u32;
+            assert_eq!(popcount_u32(x), popcount_u32_runtime(x), "u32 {x:#x}");
+            assert_eq!(
+                popcount_u32_runtime(x),
+                popcount_u32_scalar(x),
+                "u32 {x:#x}"
+            );
+            assert_eq!(popcount_u64(w), popcount_u64_runtime(w), "u64 {w:#x}");
+            assert_eq!(
+                popcount_u64_runtime(w),
+                popcount_u64_scalar(w),
+                "u64 {w:#x}"
+            );
+            let pair = [w, words[(i + 1) % words.len()]];
+            assert_eq!(
+                popcount_u128(pair),
+                popcount_u128_runtime(pair),
+                "u128 {pair:x?}"
+            );
+            assert_eq!(popcount_u128_runtime(pair), popcount_u128_scalar(pair));
+            let quad = [pair[0], pair[1], words[(i + 2) % words.len()], !w];
+            assert_eq!(
+                popcount_u256(quad),
+                popcount_u256_runtime(quad),
+                "u256 {quad:x?}"
+            );
+            assert_eq!(popcount_u256_runtime(quad), popcount_u256_scalar(quad));
+        }
+    }
+
+    #[test]
+    fn init_resolves_every_dispatcher() {
+        init();
+        assert!(POPCOUNT_U32.get().is_some());
+        assert!(POPCOUNT_U64.get().is_some());
+        assert!(POPCOUNT_U128.get().is_some());
+        assert!(POPCOUNT_U256.get().is_some());
+        assert!(POPCOUNT_U32_SLICE_SUM.get().is_some());
+        assert!(POPCOUNT_U64_SLICE_SUM.get().is_some());
+        assert!(ANDNOT_POPCOUNT_BATCH_U32.get().is_some());
+        assert!(ANDNOT_POPCOUNT_BATCH_U64.get().is_some());
+        init();
+        assert_eq!(popcount_u64(u64::MAX), 64);
+    }
+
+    #[test]
+    #[ignore] // timing only; run with: cargo test -p kenken-simd --release --features compile-time-dispatch popcount_u32_dispatch_overhead -- --ignored --nocapture
+    fn popcount_u32_dispatch_overhead() {
+        use std::hint::black_box;
+        use std::time::Instant;
+
+        const ROUNDS: usize = 20_000;
+        let xs: Vec<u32> = words(0x0DD, 1 << 10).iter().map(|&w| w as u32).collect();
+        fn time(label: &str, xs: &[u32], f: impl Fn(u32) -> u32) -> u32 {
+            let start = Instant::now();
+            let mut acc = 0u32;
+            for _ in 0..ROUNDS {
+                for &x in xs {
+                    acc = acc.wrapping_add(f(black_box(x)));
+                }
+            }
+            let ns = start.elapsed().as_nanos() as f64 / (ROUNDS * xs.len()) as f64;
+            println!("{label:>14}: {ns:.3} ns/call");
+            acc
+        }
+        init();
+        println!("COMPILE_TIME_POPCOUNT = {COMPILE_TIME_POPCOUNT}");
+        let runtime = time("runtime", &xs, popcount_u32_runtime);
+        let inline = time("count_ones", &xs, popcount_u32_scalar);
+        let public = time("popcount_u32", &xs, popcount_u32);
+        assert_eq!(runtime, inline);
+        assert_eq!(public, inline);
+    }
+
+    fn words(seed: u64, len: usize) -> Vec<u64> {
+        let mut state = seed;
+        (0..len)
+            .map(|_| {
+                state = state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+                let mut z = state;
+                z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+                z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+                z ^ (z >> 31)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn andnot_popcount_batch_matches_scalar() {
+        // Sizes cover empty, all-tail, exact vector multiples and multiples plus a tail, for
+        // both lane widths.
+        for (seed, n) in (0..).zip([0usize, 1, 2, 3, 4, 5, 7, 8, 9, 12, 16, 17, 31, 63]) {
+            let statics = words(seed, n * n);
+            let lines = words(seed + 100, 2 * n);
+            // Sparse placed-digit masks, as in a real grid, besides the dense ones.
+            let sparse: Vec<u64> = lines.iter().map(|&w| w & (w >> 7) & (w >> 13)).collect();
+            for lines in [&lines, &sparse] {
+                let (rows, cols) = lines.split_at(n);
+
+                let mut masks = vec![0u64; n * n];
+                let mut pops = vec![0u32; n * n];
+                let mut want_masks = vec![0u64; n * n];
+                let mut want_pops = vec![0u32; n * n];
+                andnot_popcount_batch_u64(&statics, rows, cols, &mut masks, &mut pops);
+                andnot_popcount_batch_u64_scalar(
+                    &statics,
+                    rows,
+                    cols,
+                    &mut want_masks,
+                    &mut want_pops,
+                );
+                assert_eq!(masks, want_masks, "u64 n={n}");
+                assert_eq!(pops, want_pops, "u64 n={n}");
+
+                let narrow = |xs: &[u64]| xs.iter().map(|&x| x as u32).collect::<Vec<_>>();
+                let (statics, rows, cols) = (narrow(&statics), narrow(rows), narrow(cols));
+                let mut masks = vec![0u32; n * n];
+                let mut want_masks = vec![0u32; n * n];
+                andnot_popcount_batch_u32(&statics, &rows, &cols, &mut masks, &mut pops);
+                andnot_popcount_batch_u32_scalar(
+                    &statics,
+                    &rows,
+                    &cols,
+                    &mut want_masks,
+                    &mut want_pops,
+                );
+                assert_eq!(masks, want_masks, "u32 n={n}");
+                assert_eq!(pops, want_pops, "u32 n={n}");
+            }
+        }
+        let mut pops = [0u32; 4];
+        let mut masks = [0u64; 4];
+        andnot_popcount_batch_u64(&[u64::MAX; 4], &[0, 0], &[0, 0], &mut masks, &mut pops);
+        assert_eq!(pops, [64; 4]);
+    }
+
+    #[test]
+    #[should_panic(expected = "batch of 2 rows")]
+    fn andnot_popcount_batch_rejects_mismatched_lengths() {
+        let mut masks = [0u32; 4];
+        let mut pops = [0u32; 3];
+        andnot_popcount_batch_u32(&[0; 4], &[0; 2], &[0; 2], &mut masks, &mut pops);
+    }
+
+    /// Calls the NEON paths directly, whatever the dispatchers picked, so a cross-compiled
+    /// run on ARMv7 (`cross test -p kenken-simd --target armv7-unknown-linux-gnueabihf`)
+    /// checks every one of them against the scalar results.
+    #[cfg(target_arch = "arm")]
+    #[test]
+    fn arm_neon_paths_match_scalar() {
+        if !std::arch::is_arm_feature_detected!("neon") {
+            return;
+        }
+        let words = (0..67u64)
+            .map(|i| i.wrapping_mul(0x9E37_79B9_7F4A_7C15) ^ (i << 59))
+            .chain([0, u64::MAX, 1 << 63, 0x5555_5555_5555_5555])
+            .collect::<Vec<_>>();
+        for &x in &words {
+            assert_eq!(popcount_u64_arm_neon(x), x.count_ones(), "{x:#x}");
+            assert_eq!(popcount_u32_arm_neon(x as u32), (x as u32).count_ones());
+        }
+        let halves = words.iter().map(|&x| x as u32).collect::<Vec<_>>();
+        for n in 0..=8 {
+            let (statics, lines) = (&words[..n * n], &words[n * n..n * n + 2 * n]);
+            let (rows, cols) = lines.split_at(n);
+            let mut got = (vec![0u64; n * n], vec![0u32; n * n]);
+            let mut want = (vec![0u64; n * n], vec![0u32; n * n]);
+            andnot_popcount_batch_u64_arm_neon(statics, rows, cols, &mut got.0, &mut got.1);
+            andnot_popcount_batch_u64_scalar(statics, rows, cols, &mut want.0, &mut want.1);
+            assert_eq!(got, want, "u64 n={n}");
+            let (statics, lines) = (&halves[..n * n], &halves[n * n..n * n + 2 * n]);
+            let (rows, cols) = lines.split_at(n);
+            let mut got = (vec![0u32; n * n], vec![0u32; n * n]);
+            let mut want = (vec![0u32; n * n], vec![0u32; n * n]);
+            andnot_popcount_batch_u32_arm_neon(statics, rows, cols, &mut got.0, &mut got.1);
+            andnot_popcount_batch_u32_scalar(statics, rows, cols, &mut want.0, &mut want.1);
+            assert_eq!(got, want, "u32 n={n}");
+        }
+        for len in 0..words.len() {
+            assert_eq!(
+                popcount_u64_slice_sum_arm_neon(&words[..len]),
+                popcount_u64_slice_sum_scalar(&words[..len]),
+                "len {len}"
+            );
+            assert_eq!(
+                popcount_u32_slice_sum_arm_neon(&halves[..len]),
+                popcount_u32_slice_sum_scalar(&halves[..len]),
+                "len {len}"
+            );
+        }
+    }
 }
@@ -26,7 +26,7 @@ fn create_trivial_puzzle(n: u8) -> Puzzle {
         cages.push(Cage {
             cells: smallvec![CellId(cell_id)],
             op: Op::Eq,
-            target: val as i32,
+            target: val as i64,
         });
     }
     Puzzle { n, cages }
@@ -56,10 +56,10 @@ fn create_mixed_puzzle(n: u8) -> Puzzle {
             cages.push(Cage {
                 cells: cage_cells,
                 op: Op::Add,
-                target: sum as i32,
+                target: sum as i64,
             });
         } else {
-            let val = sum as i32;
+            let val = sum as i64;
             cages.push(Cage {
                 cells: cage_cells,
                 op: Op::Eq,
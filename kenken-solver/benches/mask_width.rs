@@ -0,0 +1,78 @@
+//! The `u32` mask path against `u64` on the grids it covers.
+//!
+//! Every n <= 31 search runs on `u32` row, column and static masks; the `mask-u64` feature
+//! forces `u64` everywhere, as before the narrow path. Only the masks change width: the grid
+//! stays one `u8` per cell and the cage index one `u16` per cell, each in its own buffer.
+//!
+//! Groups, each at Hard:
+//! - `solve`: first solution
+//! - `unique`: a solution count capped at 2, the generator's uniqueness check
+//!
+//! on `6x6_mixed` from `regression_fixtures` and a generated 9x9 (`keen_baseline(9, 862)`,
+//! Hard to classify).
+//!
+//! Compare the widths with a saved baseline:
+//!
+//! ```text
+//! cargo bench -p kenken-solver --bench mask_width --features mask-u64 -- --save-baseline u64
+//! cargo bench -p kenken-solver --bench mask_width -- --baseline u64
+//! ```
+//!
+//! Results are recorded in `docs/benchmark_baselines.md`.
+
+mod regression_fixtures;
+
+use std::hint::black_box;
+
+use criterion::{Criterion, criterion_group, criterion_main};
+use kenken_core::Puzzle;
+use kenken_core::format::sgt_desc::parse_keen_desc;
+use kenken_core::rules::Ruleset;
+use kenken_solver::{
+    DeductionTier, count_solutions_up_to_with_deductions, solve_one_with_deductions,
+};
+use regression_fixtures::solve_fixtures;
+
+/// `GenerateConfig::keen_baseline(9, 862)` from kenken-gen, encoded.
+const GENERATED_9X9: &str = "_4ab_4aa_a3__a3_a_aa__c_a_ab__aa_3ba_3a6_a_a_6a_a_4a_12a_3ab_5b_aba,\
+    a3s1m72s1m12a18m27s4a13m48m14a8d5m20m56m63s1d3s5m40m24m64a9m210a9s4a30a18d3a10s2m54m112a12";
+
+fn puzzles() -> Vec<(&'static str, Puzzle)> {
+    let mixed = solve_fixtures()
+        .into_iter()
+        .find(|f| f.label == "6x6_mixed")
+        .expect("regression fixture")
+        .puzzle;
+    let generated = parse_keen_desc(9, GENERATED_9X9).expect("valid desc");
+    vec![("6x6_mixed", mixed), ("9x9_generated", generated)]
+}
+
+fn bench_mask_width(c: &mut Criterion) {
+    let rules = Ruleset::keen_baseline();
+    let tier = DeductionTier::Hard;
+    let width = if cfg!(feature = "mask-u64") {
+        "u64"
+    } else {
+        "u32"
+    };
+    println!("mask_width: {width} masks");
+
+    let mut group = c.benchmark_group("solve");
+    for (label, puzzle) in &puzzles() {
+        group.bench_function(*label, |b| {
+            b.iter(|| solve_one_with_deductions(black_box(puzzle), rules, tier))
+        });
+    }
+    group.finish();
+
+    let mut group = c.benchmark_group("unique");
+    for (label, puzzle) in &puzzles() {
+        group.bench_function(*label, |b| {
+            b.iter(|| count_solutions_up_to_with_deductions(black_box(puzzle), rules, tier, 2))
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_mask_width);
+criterion_main!(benches);
@@ -0,0 +1,252 @@
+//! Deterministic inputs shared by the `solver_regression` benchmark and the
+//! `node_ceilings` integration test.
+//!
+//! Every puzzle is built from a fixed Latin square and a hand-drawn cage layout; cage targets
+//! are derived from the square, so the square is always a solution. Nothing here uses an RNG.
+
+// Each consumer uses a different subset of the fixtures.
+#![allow(dead_code)]
+
+use kenken_core::rules::Op;
+use kenken_core::{Cage, CellId, Puzzle};
+
+/// A named fixture puzzle.
+pub struct Fixture {
+    pub label: &'static str,
+    pub puzzle: Puzzle,
+}
+
+/// Build a puzzle from a row-major `solution` and cage `labels` (`labels[idx]` = cage index).
+///
+/// Ops are assigned by cage size and index: singletons are `Eq`, pairs alternate `Div`
+/// (when divisible) and `Sub`, larger cages alternate `Add` and `Mul`.
+pub fn from_layout(n: u8, solution: &[u8], labels: &[usize]) -> Puzzle {
+    let cage_count = labels.iter().copied().max().map_or(0, |m| m + 1);
+    let mut cells: Vec<Vec<usize>> = vec![Vec::new(); cage_count];
+    for (idx, &label) in labels.iter().enumerate() {
+        cells[label].push(idx);
+    }
+
+    let cages = cells
+        .into_iter()
+        .enumerate()
+        .map(|(cage_idx, cells)| {
+            let values: Vec<i64> = cells.iter().map(|&idx| solution[idx] as i64).collect();
+            let (op, target) = match values.len() {
+                1 => (Op::Eq, values[0]),
+                2 => {
+                    let (hi, lo) = (values[0].max(values[1]), values[0].min(values[1]));
+                    if cage_idx % 2 == 0 && hi % lo == 0 && hi != lo {
+                        (Op::Div, hi / lo)
+                    } else {
+                        (Op::Sub, hi - lo)
+                    }
+                }
+                _ if cage_idx % 2 == 0 => (Op::Add, values.iter().sum()),
+                _ => (Op::Mul, values.iter().product()),
+            };
+            Cage {
+                cells: cells.iter().map(|&idx| CellId(idx as u16)).collect(),
+                op,
+                target,
+            }
+        })
+        .collect();
+
+    Puzzle { n, cages }
+}
+
+#[rustfmt::skip]
+const SOLUTION_4X4: [u8; 16] = [
+    1, 2, 3, 4,
+    3, 4, 1, 2,
+    4, 3, 2, 1,
+    2, 1, 4, 3,
+];
+
+#[rustfmt::skip]
+const LAYOUT_4X4: [usize; 16] = [
+    0, 0, 1, 2,
+    3, 4, 1, 2,
+    3, 4, 5, 5,
+    6, 6, 7, 5,
+];
+
+#[rustfmt::skip]
+const LAYOUT_5X5: [usize; 25] = [
+    0,  0,  1,  1,  2,
+    3,  4,  4,  1,  2,
+    3,  5,  6,  6,  2,
+    7,  5,  8,  9,  9,
+    7, 10,  8,  8, 11,
+];
+
+#[rustfmt::skip]
+const LAYOUT_6X6: [usize; 36] = [
+     0,  0,  1,  2,  2,  3,
+     4,  1,  1,  5,  3,  3,
+     4,  6,  7,  5,  5,  8,
+     9,  6,  7, 10,  8,  8,
+     9, 11, 11, 10, 12, 13,
+    14, 14, 11, 12, 12, 13,
+];
+
+/// Six 2x3 blocks, one cage each.
+#[rustfmt::skip]
+const LAYOUT_6X6_BLOCKS: [usize; 36] = [
+    0, 0, 0, 1, 1, 1,
+    0, 0, 0, 1, 1, 1,
+    2, 2, 2, 3, 3, 3,
+    2, 2, 2, 3, 3, 3,
+    4, 4, 4, 5, 5, 5,
+    4, 4, 4, 5, 5, 5,
+];
+
+/// Eighteen dominoes: horizontal in rows 0-1 and 4-5, vertical across rows 2-3.
+#[rustfmt::skip]
+const LAYOUT_6X6_DOMINOES: [usize; 36] = [
+     0,  0,  1,  1,  2,  2,
+     3,  3,  4,  4,  5,  5,
+     6,  7,  8,  9, 10, 11,
+     6,  7,  8,  9, 10, 11,
+    12, 12, 13, 13, 14, 14,
+    15, 15, 16, 16, 17, 17,
+];
+
+/// Mostly dominoes and L-triples around one singleton; every multi-cell cage becomes `Mul`.
+#[rustfmt::skip]
+const LAYOUT_7X7_MUL: [usize; 49] = [
+     0,  0,  1,  1,  2,  3,  3,
+     4,  5,  5,  1,  2,  6,  3,
+     4,  7,  8,  8,  9,  6, 10,
+    11,  7,  7, 12,  9,  9, 10,
+    11, 13, 14, 12, 15, 16, 16,
+    17, 13, 14, 14, 15, 18, 19,
+    17, 17, 20, 20, 21, 18, 19,
+];
+
+/// `(2r + c) mod 5`, a Latin square because 2 is coprime to 5.
+fn solution_5x5() -> Vec<u8> {
+    (0..25)
+        .map(|idx| ((2 * (idx / 5) + idx % 5) % 5 + 1) as u8)
+        .collect()
+}
+
+/// Cyclic 6x6 square with rows and columns permuted so no row is a plain shift.
+fn solution_6x6() -> Vec<u8> {
+    const ROWS: [usize; 6] = [0, 3, 1, 5, 2, 4];
+    const COLS: [usize; 6] = [2, 0, 5, 1, 4, 3];
+    (0..36)
+        .map(|idx| ((ROWS[idx / 6] + COLS[idx % 6]) % 6 + 1) as u8)
+        .collect()
+}
+
+/// `(3r + c) mod 7` with rows and columns permuted, a Latin square because 3 is coprime to 7.
+fn solution_7x7() -> Vec<u8> {
+    const ROWS: [usize; 7] = [4, 0, 6, 2, 5, 1, 3];
+    const COLS: [usize; 7] = [1, 5, 0, 3, 6, 2, 4];
+    (0..49)
+        .map(|idx| ((3 * ROWS[idx / 7] + COLS[idx % 7]) % 7 + 1) as u8)
+        .collect()
+}
+
+/// Mixed-op puzzles used for the per-tier solve benchmarks.
+pub fn solve_fixtures() -> Vec<Fixture> {
+    vec![
+        Fixture {
+            label: "4x4_mixed",
+            puzzle: from_layout(4, &SOLUTION_4X4, &LAYOUT_4X4),
+        },
+        Fixture {
+            label: "5x5_mixed",
+            puzzle: from_layout(5, &solution_5x5(), &LAYOUT_5X5),
+        },
+        Fixture {
+            label: "6x6_mixed",
+            puzzle: from_layout(6, &solution_6x6(), &LAYOUT_6X6),
+        },
+        Fixture {
+            label: "7x7_mul",
+            puzzle: mul_heavy_7x7(),
+        },
+    ]
+}
+
+/// 7x7 where every multi-cell cage is `Mul`, for the divisor pre-filter in propagation.
+fn mul_heavy_7x7() -> Puzzle {
+    let solution = solution_7x7();
+    let mut puzzle = from_layout(7, &solution, &LAYOUT_7X7_MUL);
+    for cage in &mut puzzle.cages {
+        if cage.cells.len() > 1 {
+            cage.op = Op::Mul;
+            cage.target = cage
+                .cells
+                .iter()
+                .map(|c| solution[c.0 as usize] as i64)
+                .product();
+        }
+    }
+    puzzle
+}
+
+/// 3x3 with one `6+` cage per row: every 3x3 Latin square fits, so it has 12 solutions.
+pub fn twelve_solution_3x3() -> Puzzle {
+    #[rustfmt::skip]
+    let solution = [
+        1, 2, 3,
+        2, 3, 1,
+        3, 1, 2,
+    ];
+    let labels = [0, 0, 0, 2, 2, 2, 4, 4, 4];
+    let mut puzzle = from_layout(3, &solution, &labels);
+    // Labels 1 and 3 are unused; drop the empty cages they produce.
+    puzzle.cages.retain(|c| !c.cells.is_empty());
+    puzzle
+}
+
+/// 6x6 of eighteen dominoes alternating `Add` and `Mul`, for two-cell cage propagation.
+pub fn domino_6x6() -> Puzzle {
+    let solution = solution_6x6();
+    let mut puzzle = from_layout(6, &solution, &LAYOUT_6X6_DOMINOES);
+    for (cage_idx, cage) in puzzle.cages.iter_mut().enumerate() {
+        let (a, b) = (
+            solution[cage.cells[0].0 as usize] as i64,
+            solution[cage.cells[1].0 as usize] as i64,
+        );
+        (cage.op, cage.target) = if cage_idx % 2 == 0 {
+            (Op::Add, a + b)
+        } else {
+            (Op::Mul, a * b)
+        };
+    }
+    puzzle
+}
+
+/// 6x6 made of six 6-cell blocks for the propagation-only benchmark.
+pub fn large_cage_6x6() -> Puzzle {
+    from_layout(6, &solution_6x6(), &LAYOUT_6X6_BLOCKS)
+}
+
+/// Grid size for the synthetic cage used by the tuple-enumeration benchmark.
+pub const TUPLE_GRID_N: u8 = 9;
+
+/// A 5-cell L-shaped `25+` cage on a 9x9 grid (cells 0, 1, 2, 11, 20).
+pub fn five_cell_add_cage() -> Cage {
+    Cage {
+        cells: [0u16, 1, 2, 11, 20].into_iter().map(CellId).collect(),
+        op: Op::Add,
+        target: 25,
+    }
+}
+
+/// Candidate domains for the 9x9 grid at three densities: `full` (1-9), `odd` (1, 3, 5, 7,
+/// 9) and `sparse` (2, 5, 8).
+pub fn tuple_domain_sets() -> Vec<(&'static str, Vec<u64>)> {
+    let a = (TUPLE_GRID_N as usize) * (TUPLE_GRID_N as usize);
+    let mask = |digits: &[u32]| digits.iter().fold(0u64, |m, &d| m | (1u64 << d));
+    vec![
+        ("full", vec![mask(&[1, 2, 3, 4, 5, 6, 7, 8, 9]); a]),
+        ("odd", vec![mask(&[1, 3, 5, 7, 9]); a]),
+        ("sparse", vec![mask(&[2, 5, 8]); a]),
+    ]
+}
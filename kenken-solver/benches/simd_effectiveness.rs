@@ -7,12 +7,18 @@
 /// - Single value popcount (u32 and u64)
 /// - Slice popcount with various patterns
 /// - Performance impact of SIMD dispatch vs direct scalar
+/// - Batched domain AND-NOT + popcount vs the scalar per-cell loop
+/// - Solving a 9x9 puzzle; compare runs with and without `--features simd-dispatch` to see
+///   what the batched propagation seeding buys
 ///
 /// Flamegraph Output:
 /// - CPU flamegraphs generated to target/criterion/*/profile/flamegraph.svg
 /// - Run with `cargo bench --bench simd_effectiveness` to generate profiling data
 use criterion::{Criterion, criterion_group, criterion_main};
-use kenken_simd::{popcount_u32, popcount_u32_slice_sum, popcount_u64};
+use kenken_core::format::sgt_desc::parse_keen_desc;
+use kenken_core::rules::Ruleset;
+use kenken_simd::{andnot_popcount_batch_u32, popcount_u32, popcount_u32_slice_sum, popcount_u64};
+use kenken_solver::{DeductionTier, solve_one_with_deductions};
 use pprof::criterion::{Output, PProfProfiler};
 
 fn benchmark_popcount_u32_single(c: &mut Criterion) {
@@ -51,6 +57,55 @@ fn benchmark_popcount_u32_slice_large(c: &mut Criterion) {
     });
 }
 
+/// Generated 9x9 (seed 0, keen baseline) with a unique solution.
+const DESC_9X9: &str = "_aa__c_7a_a_4aa__a_a_b__a__a__ba_5ba_4bb__a_a__a_3ab_a_aba_4b_5b__a_ba_a__ab_3,\
+     a21s2a9d3s2a15m50s1a3m144a4d2m45m54m18m14m480s2a10m28m10a13s8m112m252a12m30d4a6s3a27a11m21";
+
+fn benchmark_andnot_popcount_batch_9x9(c: &mut Criterion) {
+    let n = 9;
+    let word = |i: u32| i.wrapping_mul(0x9E3779B9) & 0x3FE;
+    let statics: Vec<u32> = (0..n * n).map(word).collect();
+    let rows: Vec<u32> = (0..n).map(|i| word(i + 100) & 0x0F0).collect();
+    let cols: Vec<u32> = (0..n).map(|i| word(i + 200) & 0x30C).collect();
+    let mut out = vec![0u32; (n * n) as usize];
+    let mut counts = vec![0u32; (n * n) as usize];
+
+    let mut group = c.benchmark_group("andnot_popcount_9x9");
+    group.bench_function("batch", |b| {
+        b.iter(|| {
+            andnot_popcount_batch_u32(
+                std::hint::black_box(&statics),
+                &rows,
+                &cols,
+                &mut out,
+                &mut counts,
+            )
+        })
+    });
+    group.bench_function("scalar", |b| {
+        b.iter(|| {
+            let statics = std::hint::black_box(&statics);
+            for (idx, (slot, count)) in out.iter_mut().zip(counts.iter_mut()).enumerate() {
+                let dom = statics[idx] & !(rows[idx / 9] | cols[idx % 9]);
+                *slot = dom;
+                *count = dom.count_ones();
+            }
+        })
+    });
+    group.finish();
+}
+
+fn benchmark_solve_9x9(c: &mut Criterion) {
+    let puzzle = parse_keen_desc(9, DESC_9X9).unwrap();
+    let rules = Ruleset::keen_baseline();
+
+    c.bench_function("solve_9x9_normal", |b| {
+        b.iter(|| {
+            solve_one_with_deductions(std::hint::black_box(&puzzle), rules, DeductionTier::Normal)
+        })
+    });
+}
+
 criterion_group! {
     name = benches;
     config = Criterion::default().with_profiler(PProfProfiler::new(100, Output::Flamegraph(None)));
@@ -60,6 +115,8 @@ criterion_group! {
         benchmark_popcount_u32_slice_small,
         benchmark_popcount_u32_slice_medium,
         benchmark_popcount_u32_slice_large,
+        benchmark_andnot_popcount_batch_9x9,
+        benchmark_solve_9x9,
 }
 
 criterion_main!(benches);
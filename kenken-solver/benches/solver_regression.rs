@@ -0,0 +1,163 @@
+//! Regression benchmark suite for solver hot paths.
+//!
+//! Groups:
+//! - `solve_tiers`: `solve_one_with_deductions` at every tier on fixed 4x4/5x5/6x6 puzzles
+//! - `count_solutions`: full count on the 12-solution 3x3
+//! - `propagation`: root propagation fixpoint on a 6x6 of six 6-cell cages and on a 6x6 of
+//!   Add/Mul dominoes
+//! - `cage_tuples`: tuple enumeration for a 5-cell Add cage at three domain densities
+//! - `observer`: the 6x6 solve with and without a no-op `SearchObserver` (budget: <2% apart)
+//!
+//! All inputs come from `regression_fixtures` and are built without an RNG. The
+//! `node_ceilings` integration test runs the same inputs under `cargo test` and asserts
+//! search-node budgets, so algorithmic regressions fail CI without relying on timings.
+//!
+//! Run with `cargo bench -p kenken-solver --bench solver_regression`.
+
+mod regression_fixtures;
+
+use std::hint::black_box;
+
+use criterion::{BenchmarkId, Criterion, criterion_group, criterion_main};
+use kenken_core::rules::Ruleset;
+use kenken_solver::{
+    DeductionTier, SearchObserver, cage_tuple_supports, count_solutions_up_to_with_deductions,
+    propagate_to_fixpoint, solve_one_observed, solve_one_with_deductions,
+};
+use regression_fixtures::{
+    TUPLE_GRID_N, domino_6x6, five_cell_add_cage, large_cage_6x6, solve_fixtures,
+    tuple_domain_sets, twelve_solution_3x3,
+};
+
+const TIERS: [DeductionTier; 4] = [
+    DeductionTier::None,
+    DeductionTier::Easy,
+    DeductionTier::Normal,
+    DeductionTier::Hard,
+];
+
+fn bench_solve_tiers(c: &mut Criterion) {
+    let rules = Ruleset::keen_baseline();
+    let mut group = c.benchmark_group("solve_tiers");
+
+    for fixture in solve_fixtures() {
+        for tier in TIERS {
+            group.bench_with_input(
+                BenchmarkId::new(fixture.label, format!("{tier:?}")),
+                &tier,
+                |b, &tier| {
+                    b.iter(|| solve_one_with_deductions(black_box(&fixture.puzzle), rules, tier));
+                },
+            );
+        }
+    }
+
+    group.finish();
+}
+
+fn bench_count_solutions(c: &mut Criterion) {
+    let rules = Ruleset::keen_baseline();
+    let puzzle = twelve_solution_3x3();
+    let mut group = c.benchmark_group("count_solutions");
+
+    for tier in [DeductionTier::None, DeductionTier::Normal] {
+        group.bench_with_input(
+            BenchmarkId::new("3x3_twelve", format!("{tier:?}")),
+            &tier,
+            |b, &tier| {
+                b.iter(|| {
+                    count_solutions_up_to_with_deductions(black_box(&puzzle), rules, tier, 100)
+                });
+            },
+        );
+    }
+
+    group.finish();
+}
+
+fn bench_propagation(c: &mut Criterion) {
+    let rules = Ruleset::keen_baseline();
+    let puzzle = large_cage_6x6();
+    let dominoes = domino_6x6();
+    let mut group = c.benchmark_group("propagation");
+
+    for tier in [
+        DeductionTier::Easy,
+        DeductionTier::Normal,
+        DeductionTier::Hard,
+    ] {
+        group.bench_with_input(
+            BenchmarkId::new("6x6_blocks", format!("{tier:?}")),
+            &tier,
+            |b, &tier| {
+                b.iter(|| propagate_to_fixpoint(black_box(&puzzle), rules, tier));
+            },
+        );
+        group.bench_with_input(
+            BenchmarkId::new("6x6_dominoes", format!("{tier:?}")),
+            &tier,
+            |b, &tier| {
+                b.iter(|| propagate_to_fixpoint(black_box(&dominoes), rules, tier));
+            },
+        );
+    }
+
+    group.finish();
+}
+
+fn bench_cage_tuples(c: &mut Criterion) {
+    let cage = five_cell_add_cage();
+    let mut group = c.benchmark_group("cage_tuples");
+
+    for (density, domains) in tuple_domain_sets() {
+        group.bench_with_input(BenchmarkId::new("add5", density), &domains, |b, domains| {
+            b.iter(|| cage_tuple_supports(TUPLE_GRID_N, black_box(&cage), domains));
+        });
+    }
+
+    group.finish();
+}
+
+struct NoopObserver;
+
+impl SearchObserver for NoopObserver {}
+
+fn bench_observer(c: &mut Criterion) {
+    let rules = Ruleset::keen_baseline();
+    let fixture = solve_fixtures()
+        .into_iter()
+        .find(|f| f.label == "6x6_mixed")
+        .unwrap();
+    let mut group = c.benchmark_group("observer");
+
+    for tier in [DeductionTier::None, DeductionTier::Normal] {
+        group.bench_with_input(
+            BenchmarkId::new("6x6_plain", format!("{tier:?}")),
+            &tier,
+            |b, &tier| {
+                b.iter(|| solve_one_with_deductions(black_box(&fixture.puzzle), rules, tier));
+            },
+        );
+        group.bench_with_input(
+            BenchmarkId::new("6x6_noop_observer", format!("{tier:?}")),
+            &tier,
+            |b, &tier| {
+                b.iter(|| {
+                    solve_one_observed(black_box(&fixture.puzzle), rules, tier, &mut NoopObserver)
+                });
+            },
+        );
+    }
+
+    group.finish();
+}
+
+criterion_group!(
+    benches,
+    bench_solve_tiers,
+    bench_count_solutions,
+    bench_propagation,
+    bench_cage_tuples,
+    bench_observer
+);
+criterion_main!(benches);
@@ -32,7 +32,7 @@ fn create_trivial_puzzle(n: u8) -> Puzzle {
             cages.push(Cage {
                 cells: smallvec![cell_id],
                 op: Op::Eq,
-                target: ((row * n + col) % n + 1) as i32,
+                target: ((row * n + col) % n + 1) as i64,
             });
         }
     }
@@ -0,0 +1,98 @@
+//! Batch solving over many puzzles.
+//!
+//! With the `parallel-rayon` feature the batch is spread across rayon's global
+//! thread pool; without it the same functions run sequentially. Either way the
+//! output vector is index-aligned with the input slice, and a failure on one
+//! puzzle is reported in its slot without affecting the others.
+//!
+//! Each puzzle is solved independently, so every worker owns its search state;
+//! nothing is shared between puzzles.
+
+use crate::{
+    DeductionTier, Puzzle, Ruleset, Solution, SolveError, TierRequiredResult,
+    classify_tier_required_dispatched, solve_one_with_deductions_dispatched,
+};
+
+/// Solves every puzzle in `puzzles`, returning results in input order.
+///
+/// Each slot holds what [`solve_one_with_deductions_dispatched`] would return for
+/// the corresponding puzzle.
+///
+/// # Example
+///
+/// A line-oriented batch front end (one `N DESC` pair per line, as the CLI batch
+/// mode reads them) parses its input up front and hands the whole set over:
+///
+/// ```
+/// use kenken_core::format::sgt_desc::parse_keen_desc;
+/// use kenken_solver::batch::solve_batch;
+/// use kenken_solver::{DeductionTier, Ruleset};
+///
+/// let input = "2 b__,a3a3\n3 _13,a1a2a3a2a3a1a3a1a2\n";
+/// let puzzles: Vec<_> = input
+///     .lines()
+///     .map(|line| {
+///         let (n, desc) = line.split_once(' ').unwrap();
+///         parse_keen_desc(n.parse().unwrap(), desc).unwrap()
+///     })
+///     .collect();
+///
+/// let results = solve_batch(&puzzles, Ruleset::keen_baseline(), DeductionTier::Normal);
+/// assert_eq!(results.len(), puzzles.len());
+/// for (puzzle, result) in puzzles.iter().zip(&results) {
+///     match result {
+///         Ok(Some(sol)) => assert_eq!(sol.n, puzzle.n),
+///         Ok(None) => println!("n={}: no solution", puzzle.n),
+///         Err(e) => println!("n={}: {e}", puzzle.n),
+///     }
+/// }
+/// ```
+pub fn solve_batch(
+    puzzles: &[Puzzle],
+    rules: Ruleset,
+    tier: DeductionTier,
+) -> Vec<Result<Option<Solution>, SolveError>> {
+    #[cfg(feature = "parallel-rayon")]
+    {
+        use rayon::prelude::*;
+        puzzles
+            .par_iter()
+            .map(|p| solve_one_with_deductions_dispatched(p, rules, tier))
+            .collect()
+    }
+
+    #[cfg(not(feature = "parallel-rayon"))]
+    {
+        puzzles
+            .iter()
+            .map(|p| solve_one_with_deductions_dispatched(p, rules, tier))
+            .collect()
+    }
+}
+
+/// Classifies the minimum deduction tier of every puzzle in `puzzles`, returning
+/// results in input order.
+///
+/// Each slot holds what [`classify_tier_required_dispatched`] would return for
+/// the corresponding puzzle.
+pub fn classify_batch(
+    puzzles: &[Puzzle],
+    rules: Ruleset,
+) -> Vec<Result<TierRequiredResult, SolveError>> {
+    #[cfg(feature = "parallel-rayon")]
+    {
+        use rayon::prelude::*;
+        puzzles
+            .par_iter()
+            .map(|p| classify_tier_required_dispatched(p, rules))
+            .collect()
+    }
+
+    #[cfg(not(feature = "parallel-rayon"))]
+    {
+        puzzles
+            .iter()
+            .map(|p| classify_tier_required_dispatched(p, rules))
+            .collect()
+    }
+}
@@ -0,0 +1,269 @@
+//! Pencil-mark assistance: which digit pairs a two-cell cage can still take.
+//!
+//! Candidates come from the board as a player sees it, not from search: each cell's digits are
+//! those its row and column leave open in `partial`, with `Eq` cages pinning their cell as if
+//! filled. A pair is kept when it meets the cage's clue ([`kenken_core::arith`]) and, for cells
+//! sharing a row or column, its digits differ. Columns drop out of both checks when
+//! [`Ruleset::enforce_columns`] is off.
+
+use kenken_core::arith::op_pair_satisfied;
+use kenken_core::puzzle::validate_grid_size;
+use kenken_core::rules::Op;
+use kenken_core::{CellId, CoreError};
+
+use crate::{Puzzle, Ruleset, SolveError};
+
+/// Ordered `(first, second)` digit pairs that cage `cage_idx` can still take given `partial`,
+/// a row-major `n`×`n` grid with 0 for blank cells. `first` goes in the cage's first cell
+/// (`cage.cells[0]`, the lower cell id), `second` in the other; pairs are sorted
+/// lexicographically.
+///
+/// A filled cell's only candidate is its digit, unless that digit repeats in its row or column,
+/// in which case it has none. A board that already contradicts itself around the cage
+/// yields an empty list, not an error.
+///
+/// Errors: the puzzle fails [`Puzzle::validate`], `partial` has the wrong length or a digit
+/// above `n` ([`SolveError::GivenOutOfRange`]), `cage_idx` is out of range
+/// ([`CoreError::CageIndexOutOfRange`]), or the cage does not have exactly two cells
+/// ([`SolveError::NotTwoCellCage`]).
+pub fn cage_candidate_pairs(
+    puzzle: &Puzzle,
+    rules: Ruleset,
+    partial: &[u8],
+    cage_idx: usize,
+) -> Result<Vec<(u8, u8)>, SolveError> {
+    puzzle.validate(rules)?;
+    check_partial(puzzle.n, partial)?;
+    let cage = puzzle
+        .cages
+        .get(cage_idx)
+        .ok_or(CoreError::CageIndexOutOfRange {
+            index: cage_idx,
+            len: puzzle.cages.len(),
+        })?;
+    let &[a, b] = cage.cells.as_slice() else {
+        return Err(SolveError::NotTwoCellCage {
+            cage: cage_idx,
+            len: cage.cells.len(),
+        });
+    };
+
+    let n = puzzle.n as usize;
+    let mut board = partial.to_vec();
+    for pin in puzzle.cages.iter().filter(|c| c.op == Op::Eq) {
+        let idx = pin.cells[0].0 as usize;
+        if board[idx] == 0 && (1..=i64::from(puzzle.n)).contains(&pin.target) {
+            board[idx] = pin.target as u8;
+        }
+    }
+    // Bit `d` set: digit `d` is still open for the cell.
+    let candidates = |cell: CellId| -> u64 {
+        let idx = cell.0 as usize;
+        let (row, col) = (idx / n, idx % n);
+        let mut seen = 0u64;
+        for k in 0..n {
+            let col_peer = rules.enforce_columns.then_some(k * n + col);
+            for other in [Some(row * n + k), col_peer].into_iter().flatten() {
+                if other != idx {
+                    seen |= 1 << board[other];
+                }
+            }
+        }
+        let open = match board[idx] {
+            0 => ((1u64 << (n + 1)) - 1) & !1,
+            digit => 1 << digit,
+        };
+        open & !seen
+    };
+    let (mask_a, mask_b) = (candidates(a), candidates(b));
+    let coord_a = puzzle.coord_of(a);
+    let coord_b = puzzle.coord_of(b);
+    let same_unit =
+        coord_a.row == coord_b.row || (rules.enforce_columns && coord_a.col == coord_b.col);
+
+    let mut pairs = Vec::new();
+    for x in (1..=puzzle.n).filter(|&d| mask_a & (1 << d) != 0) {
+        for y in (1..=puzzle.n).filter(|&d| mask_b & (1 << d) != 0) {
+            if (!same_unit || x != y) && op_pair_satisfied(cage.op, cage.target, x, y) {
+                pairs.push((x, y));
+            }
+        }
+    }
+    Ok(pairs)
+}
+
+/// Check that `partial` is a row-major `n`×`n` grid of digits in `0..=n` (0 = blank).
+pub(crate) fn check_partial(n: u8, partial: &[u8]) -> Result<(), SolveError> {
+    validate_grid_size(n)?;
+    let a = n as usize * n as usize;
+    if partial.len() != a {
+        return Err(CoreError::SolutionLengthMismatch {
+            expected: a,
+            got: partial.len(),
+        }
+        .into());
+    }
+    if let Some((idx, &value)) = partial.iter().enumerate().find(|&(_, &v)| v > n) {
+        return Err(SolveError::GivenOutOfRange {
+            cell: CellId(idx as u16),
+            value,
+            n,
+        });
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use kenken_core::Cage;
+    use kenken_core::format::sgt_desc::parse_keen_desc;
+    use smallvec::SmallVec;
+
+    use super::*;
+
+    /// 4x4, solution `1234 / 4321 / 2143 / 3412`. Two-cell cages: `3×` on cells 7 and 11
+    /// (cage 2), `5+` on 9 and 10 (cage 4), `2÷` on 14 and 15 (cage 5).
+    const NORMAL_4X4: &str = "a3_aaba__a__a_,a7a12m3m24a5d2";
+
+    fn normal_4x4() -> Puzzle {
+        parse_keen_desc(4, NORMAL_4X4).unwrap()
+    }
+
+    #[test]
+    fn half_filled_board_narrows_each_pair_cage() {
+        let puzzle = normal_4x4();
+        let rules = Ruleset::keen_baseline();
+        // The top two rows, less cell 7.
+        let partial = [1, 2, 3, 4, 4, 3, 2, 0, 0, 0, 0, 0, 0, 0, 0, 0];
+        let pairs = |cage| cage_candidate_pairs(&puzzle, rules, &partial, cage).unwrap();
+
+        // Cell 7 can only be 1, so cell 11 is 3.
+        assert_eq!(puzzle.cages[2].cells.as_slice(), [CellId(7), CellId(11)]);
+        assert_eq!(pairs(2), [(1, 3)]);
+        // Columns 1 and 2 hold 2 and 3 already, leaving {1, 4} for both cells.
+        assert_eq!(pairs(4), [(1, 4), (4, 1)]);
+        // Cell 14 has {1, 4}; cell 15 has {1, 2, 3}, and 1÷1 is not 2.
+        assert_eq!(pairs(5), [(1, 2), (4, 2)]);
+
+        // On an empty board only the clue and distinctness apply.
+        let empty = [0; 16];
+        assert_eq!(
+            cage_candidate_pairs(&puzzle, rules, &empty, 4).unwrap(),
+            [(1, 4), (2, 3), (3, 2), (4, 1)]
+        );
+    }
+
+    #[test]
+    fn pairs_follow_sorted_cell_order() {
+        let rules = Ruleset::keen_baseline();
+        let partial = [1, 2, 3, 4, 4, 3, 2, 0, 0, 0, 0, 0, 0, 0, 0, 0];
+        // Listed as cells 11 then 7, the `3×` cage would put 7's digit second: (3, 1).
+        let mut reversed = normal_4x4();
+        reversed.cages[2].cells.reverse();
+        assert!(matches!(
+            cage_candidate_pairs(&reversed, rules, &partial, 2),
+            Err(SolveError::Core(CoreError::CageCellsUnsorted { cage: 2 }))
+        ));
+        reversed.normalize();
+        let cage = reversed
+            .cages
+            .iter()
+            .position(|c| c.cells.as_slice() == [CellId(7), CellId(11)])
+            .unwrap();
+        assert_eq!(
+            cage_candidate_pairs(&reversed, rules, &partial, cage).unwrap(),
+            [(1, 3)]
+        );
+    }
+
+    #[test]
+    fn contradictions_leave_no_pairs() {
+        let puzzle = normal_4x4();
+        let rules = Ruleset::keen_baseline();
+        // Cell 9 repeats the 2 above it in column 1.
+        let mut partial = [0; 16];
+        partial[1] = 2;
+        partial[9] = 2;
+        assert_eq!(
+            cage_candidate_pairs(&puzzle, rules, &partial, 4).unwrap(),
+            []
+        );
+        // Without Latin columns the repeat is legal.
+        let rows_only = Ruleset {
+            enforce_columns: false,
+            ..rules
+        };
+        assert!(
+            !cage_candidate_pairs(&puzzle, rows_only, &partial, 4)
+                .unwrap()
+                .is_empty()
+        );
+        // Row 2 holds 1 and 2 outside the 5+ cage, leaving {3, 4}, which sum to 7.
+        let mut partial = [0; 16];
+        partial[8] = 1;
+        partial[11] = 2;
+        assert_eq!(
+            cage_candidate_pairs(&puzzle, rules, &partial, 4).unwrap(),
+            []
+        );
+    }
+
+    #[test]
+    fn eq_cages_pin_their_cell() {
+        let cage = |cells: &[u16], op, target| Cage {
+            cells: cells.iter().map(|&c| CellId(c)).collect::<SmallVec<_>>(),
+            op,
+            target,
+        };
+        let puzzle = Puzzle {
+            n: 3,
+            cages: vec![
+                cage(&[0], Op::Eq, 1),
+                cage(&[1, 2], Op::Sub, 1),
+                cage(&[3, 4, 5], Op::Add, 6),
+                cage(&[6, 7, 8], Op::Add, 6),
+            ],
+        };
+        let rules = Ruleset::keen_baseline();
+        // The Eq 1 in cell 0 rules out 1 for the rest of row 0.
+        assert_eq!(
+            cage_candidate_pairs(&puzzle, rules, &[0; 9], 1).unwrap(),
+            [(2, 3), (3, 2)]
+        );
+    }
+
+    #[test]
+    fn bad_inputs_are_errors() {
+        let puzzle = normal_4x4();
+        let rules = Ruleset::keen_baseline();
+        let empty = [0; 16];
+        assert!(matches!(
+            cage_candidate_pairs(&puzzle, rules, &empty, 0),
+            Err(SolveError::NotTwoCellCage { cage: 0, len: 3 })
+        ));
+        assert!(matches!(
+            cage_candidate_pairs(&puzzle, rules, &empty, 6),
+            Err(SolveError::Core(CoreError::CageIndexOutOfRange {
+                index: 6,
+                len: 6
+            }))
+        ));
+        assert!(matches!(
+            cage_candidate_pairs(&puzzle, rules, &empty[..15], 4),
+            Err(SolveError::Core(CoreError::SolutionLengthMismatch {
+                expected: 16,
+                got: 15
+            }))
+        ));
+        let mut partial = empty;
+        partial[3] = 5;
+        assert!(matches!(
+            cage_candidate_pairs(&puzzle, rules, &partial, 4),
+            Err(SolveError::GivenOutOfRange {
+                cell: CellId(3),
+                value: 5,
+                n: 4
+            })
+        ));
+    }
+}
@@ -0,0 +1,116 @@
+//! Runtime view of the optional features this library was built with.
+//!
+//! Bindings cannot see Cargo features, so [`capabilities`] reports them as plain data: an app
+//! can disable a "Generate" button or refuse a 40x40 request up front instead of getting `None`
+//! or an error back from a call that was never going to work.
+
+use kenken_core::puzzle::MIN_GRID_SIZE;
+
+use crate::SOLVER_MAX_N;
+
+/// Optional capabilities compiled into this build. Returned by [`capabilities`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct Capabilities {
+    /// Puzzle generation. Always `false` here: generation lives in `kenken-gen`, and adapters
+    /// that link it report their own value.
+    pub generate: bool,
+    /// `solver-dlx`: exact-cover Latin square enumeration.
+    pub solver_dlx: bool,
+    /// `sat-varisat`: SAT-backed uniqueness checks.
+    pub sat_varisat: bool,
+    /// `verify`: Z3 cross-checking.
+    pub verify: bool,
+    /// `simd-dispatch`: runtime-selected SIMD domain operations.
+    pub simd_dispatch: bool,
+    /// `parallel-rayon`: batch entry points run on rayon's thread pool and
+    /// `count_solutions_parallel` is available.
+    pub parallel_rayon: bool,
+    /// Smallest `n` any entry point accepts.
+    pub min_grid_size: u8,
+    /// Largest `n` the dispatched entry points accept: the solver's domain width. Each
+    /// `solver-*` width feature enables the matching kenken-core feature, so
+    /// [`kenken_core::puzzle::validate_grid_size`] never accepts less.
+    pub max_grid_size: u8,
+    /// `kenken-solver` crate version.
+    pub version: &'static str,
+}
+
+/// Report what this build of `kenken-solver` can do.
+pub fn capabilities() -> Capabilities {
+    Capabilities {
+        generate: false,
+        solver_dlx: cfg!(feature = "solver-dlx"),
+        sat_varisat: cfg!(feature = "sat-varisat"),
+        verify: cfg!(feature = "verify"),
+        simd_dispatch: cfg!(feature = "simd-dispatch"),
+        parallel_rayon: cfg!(feature = "parallel-rayon"),
+        min_grid_size: MIN_GRID_SIZE,
+        max_grid_size: SOLVER_MAX_N,
+        version: env!("CARGO_PKG_VERSION"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Puzzle, Ruleset, SolveError, solve_one_dispatched};
+    use kenken_core::CoreError;
+
+    fn empty(n: u8) -> Puzzle {
+        Puzzle {
+            n,
+            cages: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn reported_grid_range_matches_validation() {
+        let caps = capabilities();
+        for n in caps.min_grid_size..=caps.max_grid_size {
+            kenken_core::puzzle::validate_grid_size(n).unwrap();
+            crate::validate_grid_size(n).unwrap();
+        }
+        assert!(kenken_core::puzzle::validate_grid_size(caps.min_grid_size - 1).is_err());
+        if let Some(over) = caps.max_grid_size.checked_add(1) {
+            let rejected = kenken_core::puzzle::validate_grid_size(over).is_err()
+                || crate::validate_grid_size(over).is_err();
+            assert!(rejected, "n={over} accepted beyond reported max");
+        }
+    }
+
+    #[test]
+    fn dispatched_solve_agrees_with_reported_range() {
+        let caps = capabilities();
+        let rules = Ruleset::keen_baseline();
+
+        // In range, the only complaint is about the (deliberately empty) cage list.
+        let err = solve_one_dispatched(&empty(caps.max_grid_size), rules).unwrap_err();
+        assert!(matches!(
+            err,
+            SolveError::Core(CoreError::CellUncovered { .. })
+        ));
+        let err = solve_one_dispatched(&empty(caps.min_grid_size - 1), rules).unwrap_err();
+        assert!(matches!(
+            err,
+            SolveError::Core(CoreError::InvalidGridSize(1))
+        ));
+        if let Some(over) = caps.max_grid_size.checked_add(1) {
+            let err = solve_one_dispatched(&empty(over), rules).unwrap_err();
+            assert!(matches!(
+                err,
+                SolveError::GridSizeTooLarge { .. }
+                    | SolveError::Core(CoreError::InvalidGridSize(_))
+            ));
+        }
+    }
+
+    #[test]
+    fn feature_flags_and_version_are_reported() {
+        let caps = capabilities();
+        assert!(!caps.generate);
+        assert_eq!(caps.solver_dlx, cfg!(feature = "solver-dlx"));
+        assert_eq!(caps.parallel_rayon, cfg!(feature = "parallel-rayon"));
+        assert_eq!(caps.version, env!("CARGO_PKG_VERSION"));
+    }
+}
@@ -0,0 +1,500 @@
+//! A sanctioned window into the solver's search state, for consistency tooling (feature
+//! `debug-api`).
+//!
+//! [`DebugSolver`] holds the same state the search runs on and changes it only through the
+//! search's own helpers: placements pass the check checkpoint replay uses and go through
+//! `place`/`unplace`, and [`DebugSolver::propagate_once`] is one round of the propagation the
+//! search runs. So the masks it reports are the ones the engine would see after the same moves,
+//! which is what a tool replaying a recorded game needs to find where a UI and the engine part.
+//!
+//! [`SearchInspector`] goes the other way: set in [`SearchOptions::inspector`], it pauses a real
+//! search at chosen node counts and shows a [`SearchView`] of its state, borrowed in place, to
+//! find out what a slow solve is doing at node 1,000,000.
+//!
+//! Masks are `u64`s with bit `d` set for digit `d` (bit 0 unused), whatever width the search
+//! itself would pick for the grid.
+//!
+//! [`SearchOptions::inspector`]: crate::SearchOptions::inspector
+
+use std::fmt;
+use std::sync::{Arc, Mutex, PoisonError};
+
+use kenken_core::CellId;
+use kenken_core::render::render_ascii;
+
+use crate::candidates::check_partial;
+use crate::solver::{State, place, propagate_rounds, unplace, validate_for_search};
+use crate::{CheckpointFrame, DeductionTier, Puzzle, Ruleset, SolveError};
+
+/// What one [`DebugSolver::propagate_once`] round did.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PropagateOutcome {
+    /// The round forced nothing: the state is at its propagation fixpoint.
+    Stable,
+    /// The round placed these `(cell, digit)` singles, in placement order.
+    Forced(Vec<(usize, u8)>),
+    /// The round proved the position unsolvable; the state is as it was before the call.
+    Contradiction,
+}
+
+/// Search state for `puzzle` built from a partial grid, stepped one move or propagation round
+/// at a time. See the [module docs](self).
+pub struct DebugSolver<'a> {
+    puzzle: &'a Puzzle,
+    rules: Ruleset,
+    state: State<u64>,
+    /// Placements of each step still applied, oldest first; [`DebugSolver::undo`] reverts the
+    /// last one.
+    steps: Vec<Vec<(usize, u8)>>,
+}
+
+impl<'a> DebugSolver<'a> {
+    /// State for `puzzle` with the digits of `partial` (row-major, 0 = blank) placed as givens,
+    /// which [`DebugSolver::undo`] does not revert.
+    ///
+    /// Errors: `puzzle` fails validation, `partial` is not an `n`×`n` grid of digits in
+    /// `0..=n`, or a given clashes with an earlier one ([`SolveError::IllegalPlacement`]).
+    pub fn new(puzzle: &'a Puzzle, rules: Ruleset, partial: &[u8]) -> Result<Self, SolveError> {
+        validate_for_search(puzzle, rules)?;
+        check_partial(puzzle.n, partial)?;
+        let mut solver = Self {
+            puzzle,
+            rules,
+            state: State::for_puzzle(puzzle, rules),
+            steps: Vec::new(),
+        };
+        for (cell, &value) in partial.iter().enumerate() {
+            if value != 0 {
+                solver.apply(cell, value)?;
+            }
+        }
+        solver.steps.clear();
+        Ok(solver)
+    }
+
+    pub fn n(&self) -> u8 {
+        self.puzzle.n
+    }
+
+    /// Row-major grid, 0 for an empty cell.
+    pub fn grid(&self) -> &[u8] {
+        self.state.grid()
+    }
+
+    /// Digits placed in row `r`.
+    pub fn row_mask(&self, r: usize) -> u64 {
+        self.state.row_mask(r)
+    }
+
+    /// Digits placed in column `c`.
+    pub fn col_mask(&self, c: usize) -> u64 {
+        self.state.col_mask(c)
+    }
+
+    /// Candidates of `cell` as the search sees them: its digit if placed, otherwise `1..=n`
+    /// minus its row's and column's digits and anything an `Eq` pin rules out. Cage arithmetic
+    /// narrows only through [`DebugSolver::propagate_once`] placements.
+    pub fn domain(&self, cell: usize) -> u64 {
+        self.state.cell_domain(cell)
+    }
+
+    /// Place `value` in `cell` as one undoable step.
+    ///
+    /// Errors, leaving the state unchanged: [`SolveError::IllegalPlacement`] when the cell does
+    /// not exist or is filled, or `value` is outside `1..=n`, already in the row or column, or
+    /// ruled out by an `Eq` pin. Cage arithmetic is not checked, so a wrong move can be
+    /// replayed and inspected.
+    pub fn apply(&mut self, cell: usize, value: u8) -> Result<(), SolveError> {
+        if let Some(reason) = self.state.placement_conflict(cell, value) {
+            return Err(SolveError::IllegalPlacement {
+                cell: CellId(cell as u16),
+                value,
+                reason,
+            });
+        }
+        let n = self.puzzle.n as usize;
+        place(&mut self.state, cell / n, cell % n, value);
+        self.steps.push(vec![(cell, value)]);
+        Ok(())
+    }
+
+    /// Revert the last [`DebugSolver::apply`] or forcing [`DebugSolver::propagate_once`];
+    /// `false` when no step is left.
+    pub fn undo(&mut self) -> bool {
+        let Some(step) = self.steps.pop() else {
+            return false;
+        };
+        self.unwind(&step);
+        true
+    }
+
+    /// Run one round of the search's propagation at `tier`. Forced singles become one
+    /// undoable step.
+    pub fn propagate_once(&mut self, tier: DeductionTier) -> Result<PropagateOutcome, SolveError> {
+        let mut forced = Vec::new();
+        let feasible = propagate_rounds(
+            self.puzzle,
+            self.rules,
+            tier,
+            &mut self.state,
+            1,
+            &mut forced,
+        );
+        if !matches!(feasible, Ok(true)) {
+            self.unwind(&forced);
+            return feasible.map(|_| PropagateOutcome::Contradiction);
+        }
+        if forced.is_empty() {
+            return Ok(PropagateOutcome::Stable);
+        }
+        self.steps.push(forced.clone());
+        Ok(PropagateOutcome::Forced(forced))
+    }
+
+    fn unwind(&mut self, placements: &[(usize, u8)]) {
+        let n = self.puzzle.n as usize;
+        for &(cell, value) in placements.iter().rev() {
+            unplace(&mut self.state, cell / n, cell % n, value);
+        }
+    }
+}
+
+/// What the search does after a [`SearchInspector`] callback returns.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InspectAction {
+    /// Carry on to the next breakpoint.
+    Continue,
+    /// Stop the search with [`SolveError::InspectorAbort`].
+    Abort,
+}
+
+type InspectCallback = dyn FnMut(&SearchView<'_>) -> InspectAction + Send;
+
+/// Breakpoints for [`SearchOptions::inspector`](crate::SearchOptions::inspector): when the
+/// deducing search enters a node whose count ([`SolveStats::nodes_visited`] so far, this one
+/// included) is a breakpoint, it calls the callback with a [`SearchView`] before doing
+/// anything else there.
+///
+/// Counts are per search, so an entry point that searches several times (tier classification,
+/// say) stops at each breakpoint once per search. Clones share the callback; they compare
+/// equal when they do.
+///
+/// [`SolveStats::nodes_visited`]: crate::SolveStats::nodes_visited
+#[derive(Clone)]
+pub struct SearchInspector {
+    breakpoints: Arc<[u64]>,
+    callback: Arc<Mutex<Box<InspectCallback>>>,
+}
+
+impl SearchInspector {
+    /// Call `callback` at each of `breakpoints`, in any order; repeats are dropped.
+    pub fn new(
+        breakpoints: impl IntoIterator<Item = u64>,
+        callback: impl FnMut(&SearchView<'_>) -> InspectAction + Send + 'static,
+    ) -> Self {
+        let mut breakpoints: Vec<u64> = breakpoints.into_iter().collect();
+        breakpoints.sort_unstable();
+        breakpoints.dedup();
+        Self {
+            breakpoints: breakpoints.into(),
+            callback: Arc::new(Mutex::new(Box::new(callback))),
+        }
+    }
+
+    /// The breakpoints, ascending.
+    pub fn breakpoints(&self) -> &[u64] {
+        &self.breakpoints
+    }
+}
+
+impl fmt::Debug for SearchInspector {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("SearchInspector")
+            .field(&self.breakpoints)
+            .finish()
+    }
+}
+
+impl PartialEq for SearchInspector {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.callback, &other.callback)
+    }
+}
+
+impl Eq for SearchInspector {}
+
+/// The parts of a search's state a [`SearchView`] reads, whatever its mask width.
+pub(crate) trait InspectSource {
+    fn grid(&self) -> &[u8];
+    fn row_mask(&self, r: usize) -> u64;
+    fn col_mask(&self, c: usize) -> u64;
+    fn decisions(&self) -> &[CheckpointFrame];
+    fn tuple_cache_len(&self) -> usize;
+}
+
+/// Read-only view of a paused search, borrowed from its state for the length of one
+/// [`SearchInspector`] callback.
+///
+/// `Display` renders a dump: a header, the partial grid in [`render_ascii`]'s box drawing, the
+/// decision stack and the row and column masks.
+pub struct SearchView<'a> {
+    puzzle: &'a Puzzle,
+    source: &'a dyn InspectSource,
+    depth: u32,
+    nodes: u64,
+}
+
+impl SearchView<'_> {
+    pub fn puzzle(&self) -> &Puzzle {
+        self.puzzle
+    }
+
+    /// Nodes visited so far, this one included: the breakpoint that fired.
+    pub fn nodes(&self) -> u64 {
+        self.nodes
+    }
+
+    /// Recursion depth of this node; one decision per level.
+    pub fn depth(&self) -> u32 {
+        self.depth
+    }
+
+    /// The branching decisions leading here, outermost first, as [`SearchCheckpoint`] frames
+    /// record them: each frame's `remaining` holds the digits still to try at its level.
+    ///
+    /// [`SearchCheckpoint`]: crate::SearchCheckpoint
+    pub fn decisions(&self) -> &[CheckpointFrame] {
+        self.source.decisions()
+    }
+
+    /// Row-major partial grid, 0 for an empty cell: decisions and everything propagation
+    /// forced from them.
+    pub fn grid(&self) -> &[u8] {
+        self.source.grid()
+    }
+
+    /// Digits placed in row `r`.
+    pub fn row_mask(&self, r: usize) -> u64 {
+        self.source.row_mask(r)
+    }
+
+    /// Digits placed in column `c`.
+    pub fn col_mask(&self, c: usize) -> u64 {
+        self.source.col_mask(c)
+    }
+
+    /// Entries in the search's own cage enumeration memo.
+    pub fn tuple_cache_len(&self) -> usize {
+        self.source.tuple_cache_len()
+    }
+}
+
+impl fmt::Display for SearchView<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let n = self.puzzle.n as usize;
+        let filled = self.grid().iter().filter(|&&d| d != 0).count();
+        writeln!(
+            f,
+            "node {} at depth {}: {filled}/{} cells filled, {} tuple cache entries",
+            self.nodes,
+            self.depth,
+            n * n,
+            self.tuple_cache_len()
+        )?;
+        f.write_str(&render_ascii(self.puzzle, Some(self.grid())))?;
+        writeln!(f, "decisions:")?;
+        for (level, decision) in self.decisions().iter().enumerate() {
+            writeln!(
+                f,
+                "  {level}: r{}c{} = {}, left {}",
+                decision.cell as usize / n + 1,
+                decision.cell as usize % n + 1,
+                decision.value,
+                digits(decision.remaining)
+            )?;
+        }
+        let rows: Vec<String> = (0..n).map(|r| digits(self.row_mask(r))).collect();
+        writeln!(f, "row masks: {}", rows.join(" "))?;
+        let cols: Vec<String> = (0..n).map(|c| digits(self.col_mask(c))).collect();
+        writeln!(f, "col masks: {}", cols.join(" "))
+    }
+}
+
+/// `mask`'s digits as `{1,3,4}`.
+fn digits(mask: u64) -> String {
+    let digits: Vec<String> = (1..64)
+        .filter(|d| mask >> d & 1 == 1)
+        .map(|d| d.to_string())
+        .collect();
+    format!("{{{}}}", digits.join(","))
+}
+
+/// Call `inspector` if `nodes` is one of its breakpoints.
+pub(crate) fn inspect(
+    inspector: &SearchInspector,
+    puzzle: &Puzzle,
+    source: &dyn InspectSource,
+    depth: u32,
+    nodes: u64,
+) -> Result<(), SolveError> {
+    if inspector.breakpoints.binary_search(&nodes).is_err() {
+        return Ok(());
+    }
+    let view = SearchView {
+        puzzle,
+        source,
+        depth,
+        nodes,
+    };
+    let mut callback = inspector
+        .callback
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner);
+    match callback(&view) {
+        InspectAction::Continue => Ok(()),
+        InspectAction::Abort => Err(SolveError::InspectorAbort { nodes }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use kenken_core::format::sgt_desc::parse_keen_desc;
+
+    use super::*;
+
+    /// 4x4, solution `1234 / 4321 / 2143 / 3412`.
+    fn normal_4x4() -> Puzzle {
+        parse_keen_desc(4, "a3_aaba__a__a_,a7a12m3m24a5d2").unwrap()
+    }
+
+    /// Mask with bits `digits` set.
+    fn bits(digits: &[u8]) -> u64 {
+        digits.iter().fold(0, |mask, &d| mask | 1 << d)
+    }
+
+    #[test]
+    fn scripted_moves_track_masks_and_domains() {
+        let puzzle = normal_4x4();
+        let rules = Ruleset::keen_baseline();
+        let mut partial = [0; 16];
+        partial[0] = 1;
+        let mut solver = DebugSolver::new(&puzzle, rules, &partial).unwrap();
+        assert_eq!(solver.row_mask(0), bits(&[1]));
+        assert_eq!(solver.col_mask(0), bits(&[1]));
+        assert_eq!(solver.domain(0), bits(&[1]));
+        assert_eq!(solver.domain(5), bits(&[1, 2, 3, 4]));
+
+        solver.apply(1, 2).unwrap();
+        solver.apply(5, 3).unwrap();
+        assert_eq!(solver.row_mask(0), bits(&[1, 2]));
+        assert_eq!(solver.row_mask(1), bits(&[3]));
+        assert_eq!(solver.col_mask(1), bits(&[2, 3]));
+        // Cell 4 (r1c0): row 1 holds 3, column 0 holds 1.
+        assert_eq!(solver.domain(4), bits(&[2, 4]));
+        // Cell 13 (r3c1): column 1 holds 2 and 3.
+        assert_eq!(solver.domain(13), bits(&[1, 4]));
+
+        assert!(solver.undo());
+        assert_eq!(solver.row_mask(1), 0);
+        assert_eq!(solver.col_mask(1), bits(&[2]));
+        assert_eq!(solver.grid()[5], 0);
+        assert!(solver.undo());
+        assert_eq!(solver.row_mask(0), bits(&[1]));
+        // The given stays.
+        assert!(!solver.undo());
+        assert_eq!(solver.grid()[0], 1);
+    }
+
+    #[test]
+    fn latin_violations_are_rejected_without_touching_state() {
+        let puzzle = normal_4x4();
+        let rules = Ruleset::keen_baseline();
+        let mut solver = DebugSolver::new(&puzzle, rules, &[0; 16]).unwrap();
+        solver.apply(0, 1).unwrap();
+        let snapshot = |s: &DebugSolver| (s.grid().to_vec(), s.row_mask(0), s.col_mask(2));
+        let before = snapshot(&solver);
+
+        for (cell, value, reason) in [
+            (2, 1, "digit is already in the row"),
+            (8, 1, "digit is already in the column"),
+            (0, 2, "cell is already filled"),
+            (3, 5, "digit is outside 1..=n"),
+            (16, 1, "cell is out of range"),
+        ] {
+            let err = solver.apply(cell, value).unwrap_err();
+            assert!(
+                matches!(err, SolveError::IllegalPlacement { reason: r, .. } if r == reason),
+                "{cell}={value}: {err:?}"
+            );
+        }
+        assert_eq!(snapshot(&solver), before);
+        solver.apply(2, 3).unwrap();
+        assert_eq!(solver.row_mask(0), bits(&[1, 3]));
+    }
+
+    #[test]
+    fn clashing_givens_are_rejected() {
+        let puzzle = normal_4x4();
+        let mut partial = [0; 16];
+        partial[0] = 2;
+        partial[3] = 2;
+        assert!(matches!(
+            DebugSolver::new(&puzzle, Ruleset::keen_baseline(), &partial),
+            Err(SolveError::IllegalPlacement { .. })
+        ));
+    }
+
+    #[test]
+    fn propagation_rounds_reach_the_solution_and_undo_as_steps() {
+        let puzzle = normal_4x4();
+        let rules = Ruleset::keen_baseline();
+        let mut partial = [0; 16];
+        partial[..4].copy_from_slice(&[1, 2, 3, 4]);
+        let mut solver = DebugSolver::new(&puzzle, rules, &partial).unwrap();
+        let mut rounds = 0;
+        loop {
+            match solver.propagate_once(DeductionTier::Hard).unwrap() {
+                PropagateOutcome::Forced(forced) => {
+                    for (cell, value) in forced {
+                        assert_eq!(solver.grid()[cell], value);
+                    }
+                    rounds += 1;
+                }
+                PropagateOutcome::Stable => break,
+                PropagateOutcome::Contradiction => panic!("solvable puzzle"),
+            }
+        }
+        assert_eq!(
+            solver.grid(),
+            &[1, 2, 3, 4, 4, 3, 2, 1, 2, 1, 4, 3, 3, 4, 1, 2]
+        );
+        for r in 0..4 {
+            assert_eq!(solver.row_mask(r), bits(&[1, 2, 3, 4]));
+        }
+        assert!(rounds > 0);
+        for _ in 0..rounds {
+            assert!(solver.undo());
+        }
+        assert_eq!(solver.grid(), &partial);
+        assert_eq!(solver.row_mask(2), 0);
+        assert_eq!(solver.col_mask(3), bits(&[4]));
+    }
+
+    #[test]
+    fn contradictions_leave_the_state_as_it_was() {
+        let puzzle = normal_4x4();
+        let rules = Ruleset::keen_baseline();
+        let mut solver = DebugSolver::new(&puzzle, rules, &[0; 16]).unwrap();
+        // Cell 14 is in the 2÷ cage with cell 15, and 3 has no partner there.
+        solver.apply(14, 3).unwrap();
+        let before = solver.grid().to_vec();
+        assert_eq!(
+            solver.propagate_once(DeductionTier::Hard).unwrap(),
+            PropagateOutcome::Contradiction
+        );
+        assert_eq!(solver.grid(), before);
+        assert_eq!(solver.row_mask(3), bits(&[3]));
+        assert!(solver.undo());
+        assert_eq!(solver.row_mask(3), 0);
+    }
+}
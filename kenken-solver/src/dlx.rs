@@ -29,6 +29,30 @@ struct SearchState {
     done: bool,
 }
 
+/// Working state of the MRV search behind [`Solver::count_solutions`].
+struct Cover {
+    /// Options covering each constraint, in insertion order (index 0 unused).
+    by_constraint: Vec<Vec<usize>>,
+    covered: Vec<bool>,
+    stack: Vec<usize>,
+}
+
+impl Cover {
+    fn new<T>(solver: &Solver<T>) -> Self {
+        let mut by_constraint = vec![Vec::new(); solver.n_constraints + 1];
+        for (opt, (_, constraints)) in solver.options.iter().enumerate() {
+            for &c in constraints {
+                by_constraint[c].push(opt);
+            }
+        }
+        Self {
+            by_constraint,
+            covered: vec![false; solver.n_constraints + 1],
+            stack: Vec::new(),
+        }
+    }
+}
+
 impl<T: Clone> Solver<T> {
     /// Create a new solver with the given number of constraints
     pub fn new(n_constraints: usize) -> Self {
@@ -54,6 +78,27 @@ impl<T: Clone> Solver<T> {
         self.options.push((data.clone(), constraints.to_vec()));
     }
 
+    /// Bytes the options and a fresh [`Cover`] over them hold, counted from lengths, so
+    /// growth slack is left out. [`crate::dlx_latin::matrix_footprint`] is checked against it.
+    #[cfg(test)]
+    pub(crate) fn footprint(&self) -> usize {
+        use std::mem::size_of;
+        let cover = Cover::new(self);
+        let options: usize = self
+            .options
+            .iter()
+            .map(|(_, constraints)| {
+                size_of::<(T, Vec<usize>)>() + constraints.len() * size_of::<usize>()
+            })
+            .sum();
+        let index: usize = cover
+            .by_constraint
+            .iter()
+            .map(|opts| size_of::<Vec<usize>>() + opts.len() * size_of::<usize>())
+            .sum();
+        options + index + cover.covered.len() * size_of::<bool>()
+    }
+
     /// Find the next solution
     ///
     /// Returns Some(Vec<T>) with the selected options, or None if no more solutions exist.
@@ -69,7 +114,7 @@ impl<T: Clone> Solver<T> {
 
         // Take state out to avoid borrow issues
         let mut state = self.state.take().unwrap();
-        
+
         if state.done {
             self.state = Some(state);
             return None;
@@ -97,7 +142,7 @@ impl<T: Clone> Solver<T> {
 
             // Try to extend current solution
             let start_idx = state.stack.last().map(|(_, next)| *next).unwrap_or(0);
-            
+
             if !self.try_extend(&mut state, start_idx) {
                 // No more options at this level - backtrack
                 if !self.backtrack_one(&mut state) {
@@ -109,10 +154,93 @@ impl<T: Clone> Solver<T> {
         }
     }
 
+    /// Count exact covers, stopping once `limit` have been found.
+    ///
+    /// Unlike [`next`](Self::next), this branches on the uncovered constraint with the fewest
+    /// compatible options (Knuth's MRV heuristic), so it stays fast on large matrices with
+    /// many forced choices. It does not touch the iteration state used by `next`.
+    pub fn count_solutions(&self, limit: u64) -> u64 {
+        let mut count = 0;
+        if limit > 0 {
+            self.search(&mut Cover::new(self), &mut |_| {
+                count += 1;
+                count >= limit
+            });
+        }
+        count
+    }
+
+    /// The first exact cover found by the MRV search of
+    /// [`count_solutions`](Self::count_solutions), or `None` if there is none.
+    ///
+    /// Ties between constraints go to the lowest index and options are tried in the order
+    /// they were added, so the result depends only on the matrix and its insertion order.
+    pub fn first_solution(&self) -> Option<Vec<T>> {
+        let mut found = None;
+        self.search(&mut Cover::new(self), &mut |stack| {
+            found = Some(
+                stack
+                    .iter()
+                    .map(|&opt| self.options[opt].0.clone())
+                    .collect(),
+            );
+            true
+        });
+        found
+    }
+
+    /// Depth-first Algorithm X. `on_cover` receives each complete cover and returns `true`
+    /// to stop; the return value reports whether the search was stopped.
+    fn search(&self, cover: &mut Cover, on_cover: &mut dyn FnMut(&[usize]) -> bool) -> bool {
+        let mut best: Option<(usize, usize)> = None;
+        for c in 1..=self.n_constraints {
+            if cover.covered[c] {
+                continue;
+            }
+            let live = cover.by_constraint[c]
+                .iter()
+                .filter(|&&opt| self.fits(cover, opt))
+                .count();
+            if best.is_none_or(|(_, fewest)| live < fewest) {
+                best = Some((c, live));
+                if live == 0 {
+                    return false;
+                }
+            }
+        }
+        let Some((c, _)) = best else {
+            return on_cover(&cover.stack);
+        };
+
+        for i in 0..cover.by_constraint[c].len() {
+            let opt = cover.by_constraint[c][i];
+            if !self.fits(cover, opt) {
+                continue;
+            }
+            for &k in &self.options[opt].1 {
+                cover.covered[k] = true;
+            }
+            cover.stack.push(opt);
+            let stop = self.search(cover, on_cover);
+            cover.stack.pop();
+            for &k in &self.options[opt].1 {
+                cover.covered[k] = false;
+            }
+            if stop {
+                return true;
+            }
+        }
+        false
+    }
+
+    fn fits(&self, cover: &Cover, opt: usize) -> bool {
+        self.options[opt].1.iter().all(|&k| !cover.covered[k])
+    }
+
     fn try_extend(&self, state: &mut SearchState, start_idx: usize) -> bool {
         for i in start_idx..self.options.len() {
             let (_, ref constraints) = self.options[i];
-            
+
             // Check if this option conflicts with already covered constraints
             if constraints.iter().any(|&c| state.covered[c]) {
                 continue;
@@ -149,7 +277,7 @@ impl<T: Clone> Solver<T> {
             // Try extending from next option
             for i in next_start..self.options.len() {
                 let (_, ref constraints) = self.options[i];
-                
+
                 // Check if this option conflicts with already covered constraints
                 if constraints.iter().any(|&c| state.covered[c]) {
                     continue;
@@ -179,13 +307,13 @@ mod tests {
     #[test]
     fn test_simple_exact_cover() {
         let mut solver = Solver::new(3);
-        
+
         // Option 1 covers constraints {1, 2}
         solver.add_option(Choice { id: 1 }, &[1, 2]);
-        
+
         // Option 2 covers constraint {3}
         solver.add_option(Choice { id: 2 }, &[3]);
-        
+
         let solution = solver.next().unwrap();
         assert_eq!(solution.len(), 2);
         assert!(solution.contains(&Choice { id: 1 }));
@@ -195,13 +323,13 @@ mod tests {
     #[test]
     fn test_no_solution() {
         let mut solver = Solver::new(3);
-        
+
         // Option 1 covers {1, 2}
         solver.add_option(Choice { id: 1 }, &[1, 2]);
-        
+
         // Option 2 also covers {1, 2} - conflicts with option 1
         solver.add_option(Choice { id: 2 }, &[1, 2]);
-        
+
         // Constraint 3 is never covered
         let solution = solver.next();
         assert!(solution.is_none());
@@ -210,19 +338,42 @@ mod tests {
     #[test]
     fn test_multiple_solutions() {
         let mut solver = Solver::new(2);
-        
+
         // Two ways to cover both constraints
         solver.add_option(Choice { id: 1 }, &[1]);
         solver.add_option(Choice { id: 2 }, &[2]);
         solver.add_option(Choice { id: 3 }, &[1, 2]);
-        
+
         let sol1 = solver.next().unwrap();
         assert_eq!(sol1.len(), 2);
-        
+
         let sol2 = solver.next().unwrap();
         assert_eq!(sol2.len(), 1);
         assert_eq!(sol2[0].id, 3);
-        
+
         assert!(solver.next().is_none());
     }
+
+    #[test]
+    fn mrv_search_counts_and_finds_covers() {
+        let mut solver = Solver::new(2);
+        solver.add_option(Choice { id: 1 }, &[1]);
+        solver.add_option(Choice { id: 2 }, &[2]);
+        solver.add_option(Choice { id: 3 }, &[1, 2]);
+
+        assert_eq!(solver.count_solutions(10), 2);
+        assert_eq!(solver.count_solutions(1), 1);
+        assert_eq!(solver.count_solutions(0), 0);
+        assert_eq!(
+            solver.first_solution(),
+            Some(vec![Choice { id: 1 }, Choice { id: 2 }])
+        );
+        // The MRV search leaves `next` where it was.
+        assert_eq!(solver.next().map(|s| s.len()), Some(2));
+
+        let mut stuck = Solver::new(3);
+        stuck.add_option(Choice { id: 1 }, &[1, 2]);
+        assert_eq!(stuck.count_solutions(10), 0);
+        assert_eq!(stuck.first_solution(), None);
+    }
 }
@@ -1,8 +1,14 @@
 //! Latin-square exact-cover utilities using internal DLX implementation.
 //!
 //! This module intentionally encodes only the Latin constraints (cell, row-digit, col-digit).
-//! Cage constraints remain in the main solver (and future SAT encodings).
+//! Cage constraints remain in the main solver (and future SAT encodings). Under a ruleset
+//! without [`Ruleset::enforce_columns`] the col-digit constraints are left out of the matrix
+//! (see [`count_latin_completions_with_rules`]).
 //!
+use kenken_core::rules::Ruleset;
+
+use crate::SolveError;
+use crate::candidates::check_partial;
 use crate::dlx::Solver;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -141,10 +147,169 @@ pub fn count_latin_solutions_up_to(n: u8, givens: &[u8], limit: u32) -> u32 {
     count
 }
 
+/// Exact-cover matrix completing a checked `partial`: one option per given cell and one per
+/// digit of each blank cell, added a row of cells at a time. Without `enforce_columns` the
+/// matrix stops after the cell and row-digit constraints: a col-digit one left in would have
+/// no option covering it, and every column must be covered.
+///
+/// With `rng`, the rows and the cells within each row are visited in a shuffled order and
+/// each blank cell's digits are shuffled. The search tries options in insertion order, so
+/// the first cover found depends on the seed.
+fn latin_matrix(
+    n: u8,
+    partial: &[u8],
+    enforce_columns: bool,
+    mut rng: Option<&mut SplitMix64>,
+) -> Solver<LatinChoice> {
+    let n_usize = n as usize;
+    let mut rows: Vec<usize> = (0..n_usize).collect();
+    let mut cols: Vec<usize> = (0..n_usize).collect();
+    if let Some(rng) = rng.as_deref_mut() {
+        rng.shuffle(&mut rows);
+        rng.shuffle(&mut cols);
+    }
+
+    let covered = if enforce_columns { 3 } else { 2 };
+    let mut s = Solver::new(covered * n_usize * n_usize);
+    let mut digits: Vec<u8> = Vec::with_capacity(n_usize);
+    for &row in &rows {
+        for &col in &cols {
+            digits.clear();
+            match partial[row * n_usize + col] {
+                0 => {
+                    digits.extend(1..=n);
+                    if let Some(rng) = rng.as_deref_mut() {
+                        rng.shuffle(&mut digits);
+                    }
+                }
+                given => digits.push(given),
+            }
+            for &val in &digits {
+                let val0 = val as usize - 1;
+                let constraints = [
+                    constraint_cell(n_usize, row, col),
+                    constraint_row_val(n_usize, row, val0),
+                    constraint_col_val(n_usize, col, val0),
+                ];
+                s.add_option(
+                    LatinChoice {
+                        row: row as u8,
+                        col: col as u8,
+                        val,
+                    },
+                    &constraints[..covered],
+                );
+            }
+        }
+    }
+    s
+}
+
+fn cover_to_grid(n: u8, choices: Vec<LatinChoice>) -> Vec<u8> {
+    let n_usize = n as usize;
+    let mut grid = vec![0u8; n_usize * n_usize];
+    for ch in choices {
+        grid[ch.row as usize * n_usize + ch.col as usize] = ch.val;
+    }
+    grid
+}
+
+/// Count the Latin squares that agree with every non-zero cell of `partial`, stopping at
+/// `limit`.
+///
+/// Cages are ignored. Givens that clash with each other simply leave no completion (`Ok(0)`);
+/// a wrong-length grid or a digit above `n` is an error.
+pub fn count_latin_completions(n: u8, partial: &[u8], limit: u64) -> Result<u64, SolveError> {
+    count_latin_completions_with_rules(n, partial, Ruleset::keen_baseline(), limit)
+}
+
+/// [`count_latin_completions`] under `rules`: without [`Ruleset::enforce_columns`] it counts
+/// grids whose rows alone are permutations of `1..=n`.
+pub fn count_latin_completions_with_rules(
+    n: u8,
+    partial: &[u8],
+    rules: Ruleset,
+    limit: u64,
+) -> Result<u64, SolveError> {
+    check_partial(n, partial)?;
+    Ok(latin_matrix(n, partial, rules.enforce_columns, None).count_solutions(limit))
+}
+
+/// Find one Latin square completing `partial`, with the search order shuffled by `seed`.
+///
+/// The same `seed` always yields the same square; different seeds spread over the
+/// completions. Returns `Ok(None)` when the givens admit no completion.
+pub fn solve_latin_random(n: u8, partial: &[u8], seed: u64) -> Result<Option<Vec<u8>>, SolveError> {
+    solve_latin_random_with_rules(n, partial, Ruleset::keen_baseline(), seed)
+}
+
+/// [`solve_latin_random`] under `rules`, like [`count_latin_completions_with_rules`].
+pub fn solve_latin_random_with_rules(
+    n: u8,
+    partial: &[u8],
+    rules: Ruleset,
+    seed: u64,
+) -> Result<Option<Vec<u8>>, SolveError> {
+    check_partial(n, partial)?;
+    let mut rng = SplitMix64(seed);
+    let s = latin_matrix(n, partial, rules.enforce_columns, Some(&mut rng));
+    Ok(s.first_solution().map(|choices| cover_to_grid(n, choices)))
+}
+
+/// Estimated peak bytes of the exact-cover matrix behind [`count_latin_completions`] and
+/// [`solve_latin_random`] on an empty `n` x `n` grid: `n`³ options of three constraints each,
+/// the per-constraint index over them, and the cover stack, one option per cell. Givens only
+/// shrink it. Grows as `n`³: about 350 KB at `n = 16`, 2.7 MB at `n = 32`.
+pub fn matrix_footprint(n: u8) -> usize {
+    use std::mem::size_of;
+    let n = n as usize;
+    let options = n * n * n;
+    let constraints = 3 * n * n + 1;
+    options * (size_of::<(LatinChoice, Vec<usize>)>() + 3 * size_of::<usize>())
+        + constraints * (size_of::<Vec<usize>>() + size_of::<bool>())
+        + options * 3 * size_of::<usize>()
+        + n * n * size_of::<usize>()
+}
+
+/// SplitMix64 (Steele, Lea and Flood): a fixed, dependency-free generator, so a seed picks
+/// the same completion on every platform and release.
+struct SplitMix64(u64);
+
+impl SplitMix64 {
+    fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    /// Fisher-Yates shuffle.
+    fn shuffle<T>(&mut self, items: &mut [T]) {
+        for i in (1..items.len()).rev() {
+            let j = (self.next_u64() % (i as u64 + 1)) as usize;
+            items.swap(i, j);
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
+    use kenken_core::CellId;
+
     use super::*;
 
+    #[test]
+    fn matrix_footprint_matches_the_built_matrix() {
+        for n in 1..=9u8 {
+            let empty = vec![0; n as usize * n as usize];
+            let stack = n as usize * n as usize * std::mem::size_of::<usize>();
+            let built = latin_matrix(n, &empty, true, None).footprint() + stack;
+            assert_eq!(matrix_footprint(n), built, "n={n}");
+        }
+        assert!(matrix_footprint(9) > 8 * matrix_footprint(4));
+    }
+
     #[test]
     fn latin_2x2_counts_two() {
         let givens = [0u8; 4];
@@ -160,4 +325,113 @@ mod tests {
         let sol = solve_latin_one(2, &givens).unwrap();
         assert_eq!(sol[0], 1);
     }
+
+    #[test]
+    fn empty_4x4_has_576_completions() {
+        assert_eq!(count_latin_completions(4, &[0; 16], 1000).unwrap(), 576);
+        assert_eq!(count_latin_completions(4, &[0; 16], 10).unwrap(), 10);
+        assert_eq!(count_latin_completions(4, &[0; 16], 0).unwrap(), 0);
+    }
+
+    #[test]
+    fn rows_only_completions_drop_the_column_constraints() {
+        let rows_only = Ruleset {
+            enforce_columns: false,
+            ..Ruleset::keen_baseline()
+        };
+        // Each row is any of the 3! permutations.
+        assert_eq!(
+            count_latin_completions_with_rules(3, &[0; 9], rows_only, 1000).unwrap(),
+            216
+        );
+        // A column of 1s is fine, a repeat within a row is not.
+        let mut partial = [0u8; 9];
+        partial[0] = 1;
+        partial[3] = 1;
+        partial[6] = 1;
+        assert_eq!(
+            count_latin_completions_with_rules(3, &partial, rows_only, 1000).unwrap(),
+            8
+        );
+        assert_eq!(count_latin_completions(3, &partial, 1000).unwrap(), 0);
+        for seed in 0..8 {
+            let grid = solve_latin_random_with_rules(3, &partial, rows_only, seed)
+                .unwrap()
+                .unwrap();
+            assert_eq!((grid[0], grid[3], grid[6]), (1, 1, 1));
+            for row in grid.chunks(3) {
+                let mut sorted = row.to_vec();
+                sorted.sort_unstable();
+                assert_eq!(sorted, [1, 2, 3], "seed {seed}");
+            }
+        }
+    }
+
+    #[test]
+    fn one_blank_cell_has_exactly_one_completion() {
+        let mut partial = vec![1, 2, 3, 4, 2, 1, 4, 3, 3, 4, 1, 2, 4, 3, 2, 1];
+        partial[6] = 0;
+        assert_eq!(count_latin_completions(4, &partial, 10).unwrap(), 1);
+    }
+
+    #[test]
+    fn clashing_givens_have_no_completion() {
+        // Two 1s in row 0.
+        let mut partial = [0u8; 16];
+        partial[0] = 1;
+        partial[3] = 1;
+        assert_eq!(count_latin_completions(4, &partial, 10).unwrap(), 0);
+        assert_eq!(solve_latin_random(4, &partial, 7).unwrap(), None);
+    }
+
+    #[test]
+    fn malformed_partials_are_errors() {
+        assert!(matches!(
+            count_latin_completions(4, &[0; 15], 1),
+            Err(SolveError::Core(
+                kenken_core::CoreError::SolutionLengthMismatch {
+                    expected: 16,
+                    got: 15
+                }
+            ))
+        ));
+        let mut partial = [0u8; 9];
+        partial[4] = 4;
+        assert!(matches!(
+            solve_latin_random(3, &partial, 0),
+            Err(SolveError::GivenOutOfRange {
+                cell: CellId(4),
+                value: 4,
+                n: 3
+            })
+        ));
+    }
+
+    #[test]
+    fn seeded_completions_are_valid_deterministic_and_varied() {
+        let n = 6u8;
+        let mut partial = vec![0u8; 36];
+        partial[0] = 3;
+        partial[14] = 5;
+        let mut distinct = std::collections::HashSet::new();
+        for seed in 0..16u64 {
+            let grid = solve_latin_random(n, &partial, seed).unwrap().unwrap();
+            assert_eq!(
+                solve_latin_random(n, &partial, seed).unwrap().unwrap(),
+                grid
+            );
+            assert_eq!((grid[0], grid[14]), (3, 5));
+            let mut givens = grid.clone();
+            givens[0] = 0;
+            assert_eq!(count_latin_completions(n, &givens, 2).unwrap(), 1);
+            for i in 0..6 {
+                let row: std::collections::HashSet<u8> =
+                    grid[i * 6..i * 6 + 6].iter().copied().collect();
+                let col: std::collections::HashSet<u8> = (0..6).map(|r| grid[r * 6 + i]).collect();
+                assert_eq!((row.len(), col.len()), (6, 6), "seed {seed}");
+            }
+            distinct.insert(grid);
+        }
+        assert!(distinct.len() > 8);
+    }
 }
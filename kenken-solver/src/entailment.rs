@@ -0,0 +1,128 @@
+//! Entailed cells: the values a puzzle forces, whether or not propagation can derive them.
+//!
+//! A cell is entailed when it holds the same value in every solution. Propagation finds only
+//! the entailments its deductions reach; this finds all of them by search, which makes it the
+//! ground truth for hints and for counting the cells a player must guess. Starting from one
+//! solution, each cell still in doubt is checked by striking its value there and asking for
+//! another solution. None means the value is forced; any solution found clears every cell where
+//! it differs from the first, so those are never searched.
+
+use crate::solver::{solve_excluding_within, validate_for_search};
+use crate::{DeductionTier, Puzzle, Ruleset, SolveError, SolveStats};
+
+/// Per cell, row-major: the value it holds in every solution of `puzzle`, or `None` if two
+/// solutions disagree there. `Ok(None)` if the searches took more than `budget` nodes in
+/// total (`None` for no limit).
+///
+/// A unique puzzle entails every cell. An unsolvable one is reported as entailing none.
+pub fn entailed_cells(
+    puzzle: &Puzzle,
+    rules: Ruleset,
+    tier: DeductionTier,
+    budget: Option<u64>,
+) -> Result<Option<Vec<Option<u8>>>, SolveError> {
+    validate_for_search(puzzle, rules)?;
+    let mut stats = SolveStats::default();
+    let Some(first) = solve_excluding_within(puzzle, rules, tier, None, &mut stats, budget)? else {
+        return Ok(None);
+    };
+    let Some(first) = first else {
+        return Ok(Some(vec![None; puzzle.n as usize * puzzle.n as usize]));
+    };
+
+    let mut entailed: Vec<Option<u8>> = first.iter().copied().map(Some).collect();
+    for cell in 0..entailed.len() {
+        let Some(value) = entailed[cell] else {
+            continue;
+        };
+        let excluded = Some((cell, value));
+        match solve_excluding_within(puzzle, rules, tier, excluded, &mut stats, budget)? {
+            None => return Ok(None),
+            Some(None) => {}
+            Some(Some(other)) => {
+                for (slot, (&a, &b)) in entailed.iter_mut().zip(first.iter().zip(&other)) {
+                    if a != b {
+                        *slot = None;
+                    }
+                }
+            }
+        }
+    }
+    Ok(Some(entailed))
+}
+
+#[cfg(test)]
+mod tests {
+    use kenken_core::format::sgt_desc::parse_keen_desc;
+
+    use super::*;
+    use crate::reference::reference_solve_all;
+    use crate::solve_one;
+
+    const TIERS: [DeductionTier; 4] = [
+        DeductionTier::None,
+        DeductionTier::Easy,
+        DeductionTier::Normal,
+        DeductionTier::Hard,
+    ];
+
+    /// Per cell: the value every one of `solutions` agrees on, if any.
+    fn agreement(solutions: &[Vec<u8>]) -> Vec<Option<u8>> {
+        (0..solutions[0].len())
+            .map(|cell| {
+                let value = solutions[0][cell];
+                solutions.iter().all(|s| s[cell] == value).then_some(value)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn unique_puzzle_entails_its_solution() {
+        let rules = Ruleset::keen_baseline();
+        let puzzle = parse_keen_desc(4, "a3_aaba__a__a_,a7a12m3m24a5d2").unwrap();
+        let solution = solve_one(&puzzle, rules).unwrap().unwrap();
+        for tier in TIERS {
+            let entailed = entailed_cells(&puzzle, rules, tier, None).unwrap().unwrap();
+            let expected: Vec<Option<u8>> = solution.grid.iter().copied().map(Some).collect();
+            assert_eq!(entailed, expected, "{tier:?}");
+        }
+    }
+
+    #[test]
+    fn two_solution_puzzle_entails_only_agreeing_cells() {
+        let rules = Ruleset::keen_baseline();
+        let puzzle = parse_keen_desc(2, "b__,a3a3").unwrap();
+        let solutions = reference_solve_all(&puzzle, rules).unwrap();
+        assert_eq!(solutions.len(), 2);
+        for tier in TIERS {
+            let entailed = entailed_cells(&puzzle, rules, tier, None).unwrap().unwrap();
+            assert_eq!(entailed, agreement(&solutions), "{tier:?}");
+        }
+    }
+
+    #[test]
+    fn ambiguous_4x4_entails_exactly_the_agreeing_cells() {
+        let rules = Ruleset::keen_baseline();
+        for desc in ["a3_aaba__a3_,a7a12m3m24a8", "a3_ada__a__a_,a7a12m3a12a5"] {
+            let puzzle = parse_keen_desc(4, desc).unwrap();
+            let solutions = reference_solve_all(&puzzle, rules).unwrap();
+            let expected = agreement(&solutions);
+            assert!(solutions.len() > 1, "{desc}");
+            assert!(expected.iter().any(Option::is_some), "{desc}");
+            for tier in TIERS {
+                let entailed = entailed_cells(&puzzle, rules, tier, None).unwrap().unwrap();
+                assert_eq!(entailed, expected, "{desc} {tier:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn exhausted_budget_returns_none() {
+        let rules = Ruleset::keen_baseline();
+        let puzzle = parse_keen_desc(2, "b__,a3a3").unwrap();
+        assert_eq!(
+            entailed_cells(&puzzle, rules, DeductionTier::None, Some(0)).unwrap(),
+            None
+        );
+    }
+}
@@ -8,6 +8,50 @@ pub enum SolveError {
     #[error("grid size N={n} not supported by this configuration. {hint}")]
     GridSizeTooLarge { n: u8, hint: String },
 
+    #[error("invalid search checkpoint: {reason}")]
+    InvalidCheckpoint { reason: &'static str },
+
+    #[error("invalid symmetry: {reason}")]
+    InvalidSymmetry { reason: &'static str },
+
+    #[error("given {value} at cell {cell} is outside 1..={n}")]
+    GivenOutOfRange {
+        cell: kenken_core::CellId,
+        value: u8,
+        n: u8,
+    },
+
+    #[error("cannot place {value} at cell {cell}: {reason}")]
+    IllegalPlacement {
+        cell: kenken_core::CellId,
+        value: u8,
+        reason: &'static str,
+    },
+
+    #[error("cage {cage} has {len} cells; candidate pairs need a two-cell cage")]
+    NotTwoCellCage { cage: usize, len: usize },
+
+    /// A [`SearchInspector`](crate::debug::SearchInspector) callback stopped the search
+    /// (feature `debug-api`).
+    #[error("search aborted by its inspector at node {nodes}")]
+    InspectorAbort { nodes: u64 },
+
     #[error(transparent)]
     Core(#[from] kenken_core::CoreError),
 }
+
+/// Why [`crate::Solution::validate_against`] rejected a solution.
+#[derive(Debug, Error)]
+pub enum SolutionError {
+    #[error("solution is for n={solution} but the puzzle has n={puzzle}")]
+    SizeMismatch { solution: u8, puzzle: u8 },
+
+    #[error("invalid puzzle: {0}")]
+    InvalidPuzzle(kenken_core::CoreError),
+
+    /// The grid breaks a Latin or cage constraint; see [`Puzzle::check_solution`].
+    ///
+    /// [`Puzzle::check_solution`]: kenken_core::Puzzle::check_solution
+    #[error(transparent)]
+    Violation(kenken_core::CoreError),
+}
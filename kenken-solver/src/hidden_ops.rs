@@ -0,0 +1,56 @@
+//! Hidden cage ops: which clues can be shown as a bare target, op withheld, while the puzzle
+//! stays uniquely solvable.
+//!
+//! A cage whose op is hidden keeps its cells and target, and its digits meet it if any op the
+//! cage could carry does: `Add` or `Mul`, and `Sub` or `Div` where the ruleset allows them at
+//! the cage's size. A one-cell cage's clue is its digit whatever the op, so hiding its op
+//! changes nothing.
+
+use crate::solver::count_solutions_up_to_hiding_ops;
+use crate::{DeductionTier, Puzzle, Ruleset, SolveError};
+
+/// Solution-count cap used for every uniqueness check with hidden ops.
+pub const HIDDEN_OPS_SOLUTION_CAP: u32 = 2;
+
+/// Solutions of `puzzle` with the ops of the cages at the indices in `hidden` hidden, counted
+/// up to [`HIDDEN_OPS_SOLUTION_CAP`]: 1 means the puzzle is still uniquely solvable.
+///
+/// `tier` only selects the propagation strength of the count. Indices past the end of
+/// `puzzle.cages` are rejected with `CageIndexOutOfRange`.
+pub fn uniqueness_with_hidden_ops(
+    puzzle: &Puzzle,
+    rules: Ruleset,
+    hidden: &[usize],
+    tier: DeductionTier,
+) -> Result<u32, SolveError> {
+    count_solutions_up_to_hiding_ops(puzzle, rules, tier, HIDDEN_OPS_SOLUTION_CAP, hidden)
+}
+
+/// A large set of cages whose ops can be hidden together with `puzzle` still uniquely
+/// solvable, as ascending cage indices.
+///
+/// Greedy: cages are tried in order of their anchor (top-left-most cell, row-major), and each
+/// joins the set if the puzzle stays unique with it hidden alongside those already chosen. The
+/// set is maximal, not necessarily the largest. A puzzle without a unique solution gets an
+/// empty set.
+pub fn max_hideable_ops(
+    puzzle: &Puzzle,
+    rules: Ruleset,
+    tier: DeductionTier,
+) -> Result<Vec<usize>, SolveError> {
+    if uniqueness_with_hidden_ops(puzzle, rules, &[], tier)? != 1 {
+        return Ok(Vec::new());
+    }
+    let mut order: Vec<usize> = (0..puzzle.cages.len()).collect();
+    order.sort_by_key(|&idx| puzzle.cages[idx].cells.iter().map(|c| c.0).min());
+
+    let mut hidden = Vec::new();
+    for idx in order {
+        hidden.push(idx);
+        if uniqueness_with_hidden_ops(puzzle, rules, &hidden, tier)? != 1 {
+            hidden.pop();
+        }
+    }
+    hidden.sort_unstable();
+    Ok(hidden)
+}
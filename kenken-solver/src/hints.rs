@@ -7,6 +7,7 @@
 ///
 /// Uses cold path marking to hint the optimizer about branch probability.
 /// This is an internalized version of the `likely_stable` crate.
+#[allow(dead_code)]
 #[inline(always)]
 pub fn likely(b: bool) -> bool {
     if !b {
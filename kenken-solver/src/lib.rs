@@ -1,6 +1,26 @@
 #![forbid(unsafe_code)]
 #![doc = include_str!("../README.md")]
+//!
+//! # Thread safety
+//!
+//! Every solver entry point is a pure function of its arguments: the crate keeps no global,
+//! thread-local or cached state between calls, so any of them may run concurrently from any
+//! number of threads on a shared `&Puzzle`. Only the caller's own arguments are mutated
+//! (the `&mut` observer of [`solve_one_observed`], or an [`IncrementalChecker`]). The one
+//! store that outlives a call is a [`TupleCacheHandle`] the caller passes in
+//! [`SearchOptions`]; it is `Send + Sync`, locks internally, and never changes a result.
+//!
+//! The value types a solve takes or returns ([`Puzzle`], [`kenken_core::Cage`], [`Ruleset`],
+//! [`Solution`], [`SolveStats`] and the like) are `Send + Sync`. The stateful types
+//! ([`IncrementalChecker`], [`SearchCheckpoint`], [`SearchTree`]) are `Send`, so they can be
+//! handed to another thread, and hold no interior mutability, so `&` access is also safe to
+//! share. Both are asserted at compile time; a change that breaks either is a breaking change.
 
+pub mod batch;
+pub mod candidates;
+pub mod capabilities;
+#[cfg(feature = "debug-api")]
+pub mod debug;
 #[cfg(feature = "solver-dlx")]
 mod dlx;
 #[cfg(feature = "solver-dlx")]
@@ -14,24 +34,45 @@ pub mod domain_simd128;
 pub mod domain_simd256;
 #[cfg(feature = "solver-smallbitvec")]
 pub mod domain_smallbitvec;
+pub mod entailment;
 pub mod error;
+pub mod estimate;
+pub mod hidden_ops;
 mod hints;
+mod mask;
 #[cfg(feature = "nogood-learning")]
 pub mod nogood;
+pub mod observer;
 #[cfg(feature = "parallel-search")]
 pub mod parallel;
+#[cfg(all(feature = "sat-varisat", feature = "parallel-rayon"))]
+pub mod portfolio;
+#[cfg(feature = "debug-snapshots")]
+pub mod propagation_log;
+pub mod redundancy;
+#[cfg(any(test, feature = "reference-solver"))]
+pub mod reference;
 #[cfg(feature = "sat-varisat")]
 pub mod sat_cages;
 #[cfg(feature = "sat-varisat")]
 pub mod sat_common;
 #[cfg(feature = "sat-varisat")]
 pub mod sat_latin;
+pub mod search_tree;
+pub mod small_n;
+pub mod solution_classes;
 pub mod solver;
+pub mod stats;
 #[cfg(feature = "symmetry-breaking")]
 pub mod symmetry;
+pub mod tuple_cache;
+pub mod unsat_core;
 #[cfg(feature = "verify")]
 pub mod z3_verify;
 
+pub use crate::batch::{classify_batch, solve_batch};
+pub use crate::candidates::cage_candidate_pairs;
+pub use crate::capabilities::{Capabilities, capabilities};
 #[cfg(feature = "solver-fixedbitset")]
 pub use crate::domain_fixedbitset::FixedBitDomain;
 pub use crate::domain_ops::{Domain32, Domain64, DomainOps};
@@ -41,53 +82,97 @@ pub use crate::domain_simd128::Domain128;
 pub use crate::domain_simd256::Domain256;
 #[cfg(feature = "solver-smallbitvec")]
 pub use crate::domain_smallbitvec::SmallBitDomain;
-pub use crate::error::SolveError;
+pub use crate::entailment::entailed_cells;
+pub use crate::error::{SolutionError, SolveError};
+pub use crate::estimate::{DifficultyEstimate, DifficultyFeatures, estimate_difficulty};
+pub use crate::hidden_ops::{max_hideable_ops, uniqueness_with_hidden_ops};
+pub use crate::observer::{OBSERVER_NODE_INTERVAL, SearchObserver};
+#[cfg(all(feature = "sat-varisat", feature = "parallel-rayon"))]
+pub use crate::portfolio::uniqueness_portfolio;
+#[cfg(feature = "debug-snapshots")]
+pub use crate::propagation_log::{
+    ForcedPlacement, PropagationLog, PropagationSnapshot, solve_one_with_propagation_log,
+};
+pub use crate::redundancy::{ClueRedundancy, analyze_clue_redundancy};
+pub use crate::search_tree::{NodeOutcome, SearchNode, SearchTree, solve_one_with_trace_tree};
+pub use crate::solution_classes::{SymmetrySet, count_solution_classes};
+#[cfg(feature = "parallel-rayon")]
+pub use crate::solver::count_solutions_parallel;
 pub use crate::solver::{
-    DeductionTier, DifficultyTier, Solution, SolveStats, TierRequiredResult, classify_difficulty,
-    classify_difficulty_from_tier, classify_tier_required, count_solutions_up_to,
-    count_solutions_up_to_with_deductions, solve_one, solve_one_with_deductions,
-    solve_one_with_stats,
+    CheckpointFrame, ComponentSplit, CountStrategy, DEFAULT_MAX_TUPLES_PER_CAGE, DeductionTier,
+    DifficultyTier, IncrementalChecker, ResumableOutcome, SearchCheckpoint, SearchOptions,
+    SmallNTables, Solution, SolveMode, SolveStats, SolverBackend, TierRequiredResult,
+    cage_tuple_supports, classify_difficulty, classify_difficulty_from_tier,
+    classify_tier_required, count_solutions_resumable, count_solutions_up_to,
+    count_solutions_up_to_ignoring_cages, count_solutions_up_to_warm_start,
+    count_solutions_up_to_with_deductions, count_solutions_up_to_with_deductions_and_stats,
+    count_solutions_up_to_with_options, count_solutions_up_to_with_strategy, propagate_to_fixpoint,
+    search_footprint, solve_one, solve_one_ignoring_cages, solve_one_observed,
+    solve_one_with_deductions, solve_one_with_options, solve_one_with_stats, solve_other_than,
 };
+pub use crate::stats::{Distribution, StatsSummary};
+pub use crate::tuple_cache::{TupleCacheHandle, TupleCacheStats};
+pub use crate::unsat_core::find_unsat_core;
 pub use kenken_core::Puzzle;
 pub use kenken_core::rules::Ruleset;
 
+#[cfg(any(test, feature = "perf-assertions"))]
+mod _thread_safety_assertions {
+    use static_assertions::assert_impl_all;
+
+    use crate::*;
+
+    assert_impl_all!(Puzzle: Send, Sync);
+    assert_impl_all!(kenken_core::Cage: Send, Sync);
+    assert_impl_all!(Ruleset: Send, Sync);
+    assert_impl_all!(Solution: Send, Sync);
+    assert_impl_all!(SolveStats: Send, Sync);
+    assert_impl_all!(SolveError: Send, Sync);
+    assert_impl_all!(SearchOptions: Send, Sync);
+    assert_impl_all!(DeductionTier: Send, Sync);
+    assert_impl_all!(DifficultyTier: Send, Sync);
+    assert_impl_all!(IncrementalChecker: Send, Sync);
+    assert_impl_all!(SearchCheckpoint: Send, Sync);
+    assert_impl_all!(SearchTree: Send, Sync);
+    assert_impl_all!(TupleCacheHandle: Send, Sync);
+}
+
+/// Largest `n` the solver's domain representation handles in this build
+/// (`solver-u64` or `solver-bitdomain`: 63, else 31).
+///
+/// The search keeps every candidate set in a `u32` for n <= 31 and a `u64` above (bit `v` =
+/// digit `v`, bit 0 unused), so 63 is a hard ceiling until the search itself runs on wider
+/// domains; `solver-bitdomain` does not raise it.
+#[cfg(not(any(feature = "solver-u64", feature = "solver-bitdomain")))]
+pub(crate) const SOLVER_MAX_N: u8 = 31;
+#[cfg(any(feature = "solver-u64", feature = "solver-bitdomain"))]
+pub(crate) const SOLVER_MAX_N: u8 = 63;
+
 /// Validates that the puzzle grid size is supported by the current feature configuration.
 ///
 /// Returns `Ok(())` if the grid size is valid for the current features.
 /// Returns `Err(SolveError::GridSizeTooLarge)` if the grid size exceeds supported limits.
-fn validate_grid_size(n: u8) -> Result<(), SolveError> {
-    // Feature-gated grid size validation matching kenken-core
-    #[cfg(not(any(feature = "solver-u64", feature = "solver-bitdomain")))]
-    if n > 31 {
-        return Err(SolveError::GridSizeTooLarge {
-            n,
-            hint: "Grid size exceeds 31. Enable 'solver-u64' feature for 32-63 support".to_string(),
-        });
-    }
-
-    #[cfg(all(feature = "solver-u64", not(feature = "solver-bitdomain")))]
-    if n > 63 {
-        return Err(SolveError::GridSizeTooLarge {
-            n,
-            hint: "Grid size exceeds 63. Enable 'solver-bitdomain' feature for >63 support"
-                .to_string(),
-        });
+/// Every search entry point runs this before `Puzzle::validate`.
+pub(crate) fn validate_grid_size(n: u8) -> Result<(), SolveError> {
+    if (..=SOLVER_MAX_N).contains(&n) {
+        return Ok(());
     }
-
-    #[cfg(feature = "solver-bitdomain")]
-    {
-        // BitDomain supports up to u8::MAX (255), which is the natural limit for n
-        // so we don't need an explicit check here
-        let _ = n; // Use n to avoid unused variable warning
-    }
-
-    Ok(())
+    #[cfg(not(any(feature = "solver-u64", feature = "solver-bitdomain")))]
+    let hint = "Grid size exceeds 31. Enable 'solver-u64' feature for 32-63 support";
+    #[cfg(any(feature = "solver-u64", feature = "solver-bitdomain"))]
+    let hint = "Grid size exceeds 63, the widest grid the 64-bit search domains can hold";
+    Err(SolveError::GridSizeTooLarge {
+        n,
+        hint: hint.to_string(),
+    })
 }
 
 /// Solves a puzzle with grid size validation.
 ///
 /// This is a dispatch wrapper that validates the puzzle can be solved
 /// with the current feature configuration before attempting to solve it.
+/// The plain entry points such as [`solve_one`] run the same check, so the
+/// `*_dispatched` functions now differ from them only in name.
 pub fn solve_one_dispatched(
     puzzle: &Puzzle,
     rules: Ruleset,
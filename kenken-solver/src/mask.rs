@@ -0,0 +1,405 @@
+//! Fixed-width candidate masks used by the search.
+//!
+//! Bit `v` set means digit `v` is a candidate; bit 0 is never a digit. The search is generic
+//! over [`Mask`] and picks the narrowest word that holds `n` digits: grids up to 31x31 run on
+//! `u32`, which halves the mask traffic of every domain computation, and 32..=63 fall back to
+//! `u64`. [`WideMask`] is a 256-bit word the search only runs on when
+//! [`SolverBackend::Wide`](crate::SolverBackend::Wide) asks for it. Unlike
+//! [`DomainOps`](crate::DomainOps), which numbers digits from bit 0 and is meant for callers,
+//! this trait is internal and mirrors the integer operators so the hot paths read like plain
+//! bit twiddling.
+
+use core::fmt::Debug;
+use core::ops::{BitAnd, BitAndAssign, BitOr, BitOrAssign, Not};
+
+pub(crate) trait Mask:
+    Copy
+    + Eq
+    + Debug
+    + BitAnd<Output = Self>
+    + BitAndAssign
+    + BitOr<Output = Self>
+    + BitOrAssign
+    + Not<Output = Self>
+    + Send
+    + Sync
+    + 'static
+{
+    const ZERO: Self;
+    /// Largest `n` whose digits fit in this width.
+    const MAX_N: u8;
+
+    /// The single digit `v`.
+    fn bit(v: u8) -> Self;
+    /// Number of candidate digits.
+    fn count(self) -> u32;
+    /// Smallest candidate; only meaningful for a non-empty mask.
+    fn min_digit(self) -> u8;
+    /// Largest candidate; only meaningful for a non-empty mask.
+    fn max_digit(self) -> u8;
+    /// The mask without its smallest candidate.
+    fn clear_lowest(self) -> Self;
+    /// Widen to the public `u64` representation.
+    fn to_u64(self) -> u64;
+    /// Narrow from the public `u64` representation; digits above [`Mask::MAX_N`] are dropped.
+    fn from_u64(bits: u64) -> Self;
+
+    /// Digits `1..=n`.
+    #[inline]
+    fn full(n: u8) -> Self {
+        debug_assert!(n <= Self::MAX_N, "mask cannot hold digit {n}");
+        if n >= Self::MAX_N {
+            !Self::bit(0)
+        } else {
+            // `bit(n + 1)` is in range because n < MAX_N.
+            Self::from_u64((1u64 << (n as u32 + 1)) - 2)
+        }
+    }
+
+    /// Every cell's domain on an `n` x `n` grid, `n = rows.len()`:
+    /// `out[r * n + c] = statics[r * n + c] & !(rows[r] | cols[c])`, with each one's
+    /// [`Mask::count`] in `counts`. `u32` and `u64` run it through `kenken_simd`'s batched
+    /// AND-NOT and popcount.
+    #[cfg(feature = "simd-dispatch")]
+    #[inline]
+    fn domains_batch(
+        statics: &[Self],
+        rows: &[Self],
+        cols: &[Self],
+        out: &mut [Self],
+        counts: &mut [u32],
+    ) {
+        let n = rows.len();
+        for (r, &row) in rows.iter().enumerate() {
+            for (c, &col) in cols.iter().enumerate() {
+                let dom = statics[r * n + c] & !(row | col);
+                out[r * n + c] = dom;
+                counts[r * n + c] = dom.count();
+            }
+        }
+    }
+
+    #[inline]
+    fn is_empty(self) -> bool {
+        self == Self::ZERO
+    }
+
+    #[inline]
+    fn has(self, v: u8) -> bool {
+        !(self & Self::bit(v)).is_empty()
+    }
+}
+
+#[cfg(feature = "simd-dispatch")]
+#[inline]
+fn popcount_u32(x: u32) -> u32 {
+    kenken_simd::popcount_u32(x)
+}
+
+#[cfg(not(feature = "simd-dispatch"))]
+#[inline]
+fn popcount_u32(x: u32) -> u32 {
+    x.count_ones()
+}
+
+impl Mask for u32 {
+    const ZERO: Self = 0;
+    const MAX_N: u8 = 31;
+
+    #[inline]
+    fn bit(v: u8) -> Self {
+        1 << v
+    }
+
+    #[inline]
+    fn count(self) -> u32 {
+        popcount_u32(self)
+    }
+
+    #[inline]
+    fn min_digit(self) -> u8 {
+        self.trailing_zeros() as u8
+    }
+
+    #[inline]
+    fn max_digit(self) -> u8 {
+        (31 - self.leading_zeros()) as u8
+    }
+
+    #[inline]
+    fn clear_lowest(self) -> Self {
+        self & self.wrapping_sub(1)
+    }
+
+    #[inline]
+    fn to_u64(self) -> u64 {
+        self as u64
+    }
+
+    #[inline]
+    fn from_u64(bits: u64) -> Self {
+        bits as u32
+    }
+
+    #[cfg(feature = "simd-dispatch")]
+    #[inline]
+    fn domains_batch(
+        statics: &[Self],
+        rows: &[Self],
+        cols: &[Self],
+        out: &mut [Self],
+        counts: &mut [u32],
+    ) {
+        kenken_simd::andnot_popcount_batch_u32(statics, rows, cols, out, counts);
+    }
+}
+
+impl Mask for u64 {
+    const ZERO: Self = 0;
+    const MAX_N: u8 = 63;
+
+    #[inline]
+    fn bit(v: u8) -> Self {
+        1 << v
+    }
+
+    #[inline]
+    fn count(self) -> u32 {
+        self.count_ones()
+    }
+
+    #[inline]
+    fn min_digit(self) -> u8 {
+        self.trailing_zeros() as u8
+    }
+
+    #[inline]
+    fn max_digit(self) -> u8 {
+        (63 - self.leading_zeros()) as u8
+    }
+
+    #[inline]
+    fn clear_lowest(self) -> Self {
+        self & self.wrapping_sub(1)
+    }
+
+    #[inline]
+    fn to_u64(self) -> u64 {
+        self
+    }
+
+    #[inline]
+    fn from_u64(bits: u64) -> Self {
+        bits
+    }
+
+    #[cfg(feature = "simd-dispatch")]
+    #[inline]
+    fn domains_batch(
+        statics: &[Self],
+        rows: &[Self],
+        cols: &[Self],
+        out: &mut [Self],
+        counts: &mut [u32],
+    ) {
+        kenken_simd::andnot_popcount_batch_u64(statics, rows, cols, out, counts);
+    }
+}
+
+/// Four `u64` words, digit `v` in bit `v % 64` of word `v / 64`: room for digits up to 255.
+///
+/// The search still crosses into the public `u64` representation ([`Mask::to_u64`]) for tuple
+/// enumeration and checkpoints, so results only match the native widths while every digit
+/// fits in the low word (`n <= 63`, the solver's grid limit today).
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub(crate) struct WideMask([u64; 4]);
+
+impl WideMask {
+    #[inline]
+    fn zip(self, other: Self, f: impl Fn(u64, u64) -> u64) -> Self {
+        let [a0, a1, a2, a3] = self.0;
+        let [b0, b1, b2, b3] = other.0;
+        Self([f(a0, b0), f(a1, b1), f(a2, b2), f(a3, b3)])
+    }
+}
+
+impl BitAnd for WideMask {
+    type Output = Self;
+
+    #[inline]
+    fn bitand(self, rhs: Self) -> Self {
+        self.zip(rhs, |a, b| a & b)
+    }
+}
+
+impl BitAndAssign for WideMask {
+    #[inline]
+    fn bitand_assign(&mut self, rhs: Self) {
+        *self = *self & rhs;
+    }
+}
+
+impl BitOr for WideMask {
+    type Output = Self;
+
+    #[inline]
+    fn bitor(self, rhs: Self) -> Self {
+        self.zip(rhs, |a, b| a | b)
+    }
+}
+
+impl BitOrAssign for WideMask {
+    #[inline]
+    fn bitor_assign(&mut self, rhs: Self) {
+        *self = *self | rhs;
+    }
+}
+
+impl Not for WideMask {
+    type Output = Self;
+
+    #[inline]
+    fn not(self) -> Self {
+        Self(self.0.map(|w| !w))
+    }
+}
+
+impl Mask for WideMask {
+    const ZERO: Self = Self([0; 4]);
+    const MAX_N: u8 = 255;
+
+    #[inline]
+    fn bit(v: u8) -> Self {
+        let mut words = [0; 4];
+        words[v as usize / 64] = 1 << (v % 64);
+        Self(words)
+    }
+
+    #[inline]
+    fn count(self) -> u32 {
+        self.0.iter().map(|w| w.count_ones()).sum()
+    }
+
+    #[inline]
+    fn min_digit(self) -> u8 {
+        let word = self.0.iter().position(|&w| w != 0).unwrap_or(0);
+        (word * 64) as u8 + self.0[word].trailing_zeros() as u8
+    }
+
+    #[inline]
+    fn max_digit(self) -> u8 {
+        let word = self.0.iter().rposition(|&w| w != 0).unwrap_or(0);
+        (word * 64) as u8 + (63 - self.0[word].leading_zeros()) as u8
+    }
+
+    #[inline]
+    fn clear_lowest(self) -> Self {
+        let mut words = self.0;
+        if let Some(w) = words.iter_mut().find(|w| **w != 0) {
+            *w &= w.wrapping_sub(1);
+        }
+        Self(words)
+    }
+
+    #[inline]
+    fn to_u64(self) -> u64 {
+        self.0[0]
+    }
+
+    #[inline]
+    fn from_u64(bits: u64) -> Self {
+        Self([bits, 0, 0, 0])
+    }
+
+    #[inline]
+    fn full(n: u8) -> Self {
+        let mut words = [0u64; 4];
+        for (i, w) in words.iter_mut().enumerate() {
+            // Digits `64 * i ..= 64 * i + 63` that lie in `1..=n`.
+            let lo = 64 * i;
+            let hi = (n as usize + 1).min(lo + 64);
+            if hi > lo {
+                let len = hi - lo;
+                *w = if len == 64 { !0 } else { (1u64 << len) - 1 };
+            }
+        }
+        words[0] &= !1;
+        Self(words)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn agree(bits: u64) {
+        let narrow = u32::from_u64(bits);
+        assert_eq!(narrow.to_u64(), bits);
+        assert_eq!(narrow.count(), bits.count());
+        if bits != 0 {
+            assert_eq!(narrow.min_digit(), bits.min_digit());
+            assert_eq!(narrow.max_digit(), bits.max_digit());
+        }
+        assert_eq!(narrow.clear_lowest().to_u64(), bits.clear_lowest());
+    }
+
+    #[cfg(feature = "simd-dispatch")]
+    #[test]
+    fn domains_batch_matches_per_cell_domains() {
+        fn check<M: Mask>(n: usize, word: impl Fn(u64) -> M) {
+            let w = |i: usize| word((i as u64).wrapping_mul(0x9E37_79B9_7F4A_7C15) >> 7);
+            let statics: Vec<M> = (0..n * n).map(w).collect();
+            let rows: Vec<M> = (0..n).map(|i| w(i + 1000)).collect();
+            let cols: Vec<M> = (0..n).map(|i| w(i + 2000)).collect();
+            let mut out = vec![M::ZERO; n * n];
+            let mut counts = vec![0; n * n];
+            M::domains_batch(&statics, &rows, &cols, &mut out, &mut counts);
+            for idx in 0..n * n {
+                let dom = statics[idx] & !rows[idx / n] & !cols[idx % n];
+                assert_eq!(out[idx], dom, "n={n} cell {idx}");
+                assert_eq!(counts[idx], dom.count(), "n={n} cell {idx}");
+            }
+        }
+        for n in [1, 2, 4, 5, 8, 9, 13, 31] {
+            check(n, u32::from_u64);
+            check(n, u64::from_u64);
+        }
+    }
+
+    #[test]
+    fn widths_agree_on_shared_digits() {
+        for n in 1..=31 {
+            assert_eq!(u32::full(n).to_u64(), u64::full(n), "n={n}");
+            agree(u64::full(n));
+        }
+        assert_eq!(u64::full(63), !1);
+        for bits in [0, 0b10, 0b1010_0110, 1 << 31, 0xdead_beee] {
+            agree(bits);
+        }
+        assert!(u32::full(5).has(5) && !u32::full(5).has(6));
+    }
+
+    #[test]
+    fn wide_mask_agrees_with_u64_and_spans_its_words() {
+        for n in 1..=63 {
+            assert_eq!(WideMask::full(n).to_u64(), u64::full(n), "n={n}");
+        }
+        for bits in [0b10, 0b1010_0110, 1 << 31, 0xdead_beee, 1 << 63, !1] {
+            let wide = WideMask::from_u64(bits);
+            assert_eq!(wide.count(), bits.count());
+            assert_eq!(wide.min_digit(), bits.min_digit());
+            assert_eq!(wide.max_digit(), bits.max_digit());
+            assert_eq!(wide.clear_lowest().to_u64(), bits.clear_lowest());
+            assert_eq!((!wide & WideMask::full(63)).to_u64(), !bits & u64::full(63));
+        }
+
+        let full = WideMask::full(200);
+        assert_eq!(full.count(), 200);
+        assert_eq!((full.min_digit(), full.max_digit()), (1, 200));
+        assert!(full.has(64) && full.has(128) && !full.has(201) && !full.has(0));
+        let high = WideMask::bit(64) | WideMask::bit(130);
+        assert_eq!((high.min_digit(), high.max_digit()), (64, 130));
+        assert_eq!(high.clear_lowest(), WideMask::bit(130));
+        assert_eq!(WideMask::full(255).count(), 255);
+    }
+}
@@ -61,6 +61,7 @@ impl Nogood {
 ///
 /// Uses a VecDeque (not HashMap) for deterministic iteration order.
 /// When capacity is exceeded, oldest (least recently used) nogoods are evicted.
+#[derive(Clone)]
 pub struct NogoodCache {
     /// FIFO queue of nogoods (oldest at front for LRU eviction)
     cache: VecDeque<Nogood>,
@@ -0,0 +1,46 @@
+//! Progress hooks for the deducing search.
+//!
+//! [`SearchObserver`] lets an embedder (a progress bar, a UniFFI callback) watch a long solve
+//! without touching the solver. Hooks run synchronously on the search thread, so they should
+//! return quickly; every method defaults to doing nothing.
+
+/// `on_node` fires on the first node and then once every this many nodes.
+pub const OBSERVER_NODE_INTERVAL: u64 = 256;
+
+/// Callbacks invoked by [`solve_one_observed`](crate::solve_one_observed).
+pub trait SearchObserver {
+    /// A search node was entered at `depth`; `nodes` is the exact number of nodes visited so
+    /// far, including this one. Throttled to every [`OBSERVER_NODE_INTERVAL`] nodes.
+    fn on_node(&mut self, depth: u32, nodes: u64) {
+        let _ = (depth, nodes);
+    }
+
+    /// The branching cell at `depth` was set to `value` (row-major `cell` index), before any
+    /// check of the placement; `nodes` is the number of nodes visited so far. Not throttled:
+    /// this fires for every candidate tried.
+    fn on_branch(&mut self, depth: u32, cell: usize, value: u8, nodes: u64) {
+        let _ = (depth, cell, value, nodes);
+    }
+
+    /// The placement last reported by [`on_branch`](Self::on_branch) at `depth` broke a cage
+    /// or led propagation to a contradiction, so nothing below it is searched.
+    fn on_pruned(&mut self, depth: u32) {
+        let _ = depth;
+    }
+
+    /// A solution was found; `count` is the number found so far.
+    fn on_solution(&mut self, count: u32) {
+        let _ = count;
+    }
+
+    /// Every candidate of the branching cell at `depth` was tried without reaching the
+    /// solution limit, and the search is returning to `depth - 1`.
+    fn on_backtrack(&mut self, depth: u32) {
+        let _ = depth;
+    }
+}
+
+/// Observer used by the unobserved entry points.
+pub(crate) struct NoopObserver;
+
+impl SearchObserver for NoopObserver {}
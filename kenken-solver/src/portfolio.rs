@@ -0,0 +1,193 @@
+//! Uniqueness by portfolio: the native search and the SAT encoding race on two threads, and
+//! the first verdict wins.
+//!
+//! Neither backend dominates: propagation settles most puzzles in a few nodes, while SAT is
+//! steadier on instances that make the search backtrack. The native side runs as a
+//! [`count_solutions_resumable`] count, checking a shared cancellation flag every
+//! [`PORTFOLIO_POLL_NODES`] nodes; the SAT side cannot be interrupted, so when the native
+//! side wins it is left to finish on its detached thread and its verdict is dropped.
+
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc;
+use std::thread;
+
+use crate::sat_cages::puzzle_uniqueness_via_sat;
+use crate::sat_latin::SatUniqueness;
+use crate::{
+    DeductionTier, Puzzle, ResumableOutcome, Ruleset, SolveError, count_solutions_resumable,
+};
+
+/// Search nodes the native side runs between checks of the cancellation flag.
+pub const PORTFOLIO_POLL_NODES: u64 = 1024;
+
+/// Which side of [`uniqueness_portfolio`] produced a verdict.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Backend {
+    Native,
+    Sat,
+}
+
+/// Whether `puzzle` has no, one or several solutions, from whichever of a native count (up to
+/// 2, at `tier`) and [`puzzle_uniqueness_via_sat`] answers first.
+///
+/// The puzzle is validated up front, so both backends see a well-formed instance. In debug
+/// builds, if the losing backend has also answered by the time the winner is read, the two
+/// verdicts must agree.
+pub fn uniqueness_portfolio(
+    puzzle: &Puzzle,
+    rules: Ruleset,
+    tier: DeductionTier,
+) -> Result<SatUniqueness, SolveError> {
+    crate::validate_grid_size(puzzle.n)?;
+    puzzle.validate(rules)?;
+
+    let puzzle = Arc::new(puzzle.clone());
+    let cancel = Arc::new(AtomicBool::new(false));
+    let (tx, rx) = mpsc::channel();
+    {
+        let (puzzle, cancel, tx) = (Arc::clone(&puzzle), Arc::clone(&cancel), tx.clone());
+        thread::spawn(move || {
+            if let Some(verdict) = native_uniqueness(&puzzle, rules, tier, &cancel).transpose() {
+                // The receiver is gone once the SAT side has won.
+                let _ = tx.send((Backend::Native, verdict));
+            }
+        });
+    }
+    thread::spawn(move || {
+        let _ = tx.send((Backend::Sat, Ok(puzzle_uniqueness_via_sat(&puzzle, rules))));
+    });
+
+    let (winner, verdict) = rx
+        .recv()
+        .expect("a portfolio backend panicked before answering");
+    cancel.store(true, Ordering::Relaxed);
+    if cfg!(debug_assertions)
+        && let (Ok(first), Ok((loser, Ok(second)))) = (&verdict, rx.try_recv())
+    {
+        debug_assert_eq!(
+            *first, second,
+            "{winner:?} and {loser:?} disagree on uniqueness"
+        );
+    }
+    verdict
+}
+
+/// Native count up to 2 in slices of [`PORTFOLIO_POLL_NODES`] nodes; `Ok(None)` once `cancel`
+/// is set.
+fn native_uniqueness(
+    puzzle: &Puzzle,
+    rules: Ruleset,
+    tier: DeductionTier,
+    cancel: &AtomicBool,
+) -> Result<Option<SatUniqueness>, SolveError> {
+    let mut checkpoint = None;
+    loop {
+        if cancel.load(Ordering::Relaxed) {
+            return Ok(None);
+        }
+        match count_solutions_resumable(puzzle, rules, tier, 2, checkpoint, PORTFOLIO_POLL_NODES)? {
+            ResumableOutcome::Complete { count, .. } => {
+                return Ok(Some(match count {
+                    0 => SatUniqueness::Unsat,
+                    1 => SatUniqueness::Unique,
+                    _ => SatUniqueness::Multiple,
+                }));
+            }
+            ResumableOutcome::Suspended(next) => checkpoint = Some(next),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use kenken_core::format::sgt_desc::parse_keen_desc;
+    use kenken_core::rules::Op;
+    use kenken_core::{Cage, CellId};
+
+    use super::*;
+    use crate::count_solutions_up_to_with_deductions;
+
+    const TIERS: [DeductionTier; 4] = [
+        DeductionTier::None,
+        DeductionTier::Easy,
+        DeductionTier::Normal,
+        DeductionTier::Hard,
+    ];
+
+    /// Every verdict the portfolio can give at every tier must match both backends run alone.
+    fn assert_matches_both(puzzle: &Puzzle, rules: Ruleset, expected: SatUniqueness) {
+        assert_eq!(puzzle_uniqueness_via_sat(puzzle, rules), expected);
+        let count =
+            count_solutions_up_to_with_deductions(puzzle, rules, DeductionTier::Hard, 2).unwrap();
+        assert_eq!(count, expected as u32);
+        for tier in TIERS {
+            assert_eq!(
+                uniqueness_portfolio(puzzle, rules, tier).unwrap(),
+                expected,
+                "{tier:?}"
+            );
+            let cancel = AtomicBool::new(false);
+            assert_eq!(
+                native_uniqueness(puzzle, rules, tier, &cancel).unwrap(),
+                Some(expected),
+                "{tier:?}"
+            );
+        }
+    }
+
+    /// One `Add` cage per row of a 5x5: every Latin square fits. The native search finds two
+    /// solutions almost at once; SAT pays for a large tuple allowlist per row.
+    fn row_sums_5x5() -> Puzzle {
+        let cages = (0..5u16)
+            .map(|row| Cage {
+                cells: (row * 5..row * 5 + 5).map(CellId).collect(),
+                op: Op::Add,
+                target: 15,
+            })
+            .collect();
+        Puzzle { n: 5, cages }
+    }
+
+    #[test]
+    fn unique_puzzle_matches_both_backends() {
+        // Small and propagation-friendly: the native side usually answers first.
+        let puzzle = parse_keen_desc(4, "a3_aaba__a__a_,a7a12m3m24a5d2").unwrap();
+        assert_matches_both(&puzzle, Ruleset::keen_baseline(), SatUniqueness::Unique);
+    }
+
+    #[test]
+    fn multiple_and_unsat_puzzles_match_both_backends() {
+        let rules = Ruleset::keen_baseline();
+        let puzzle = row_sums_5x5();
+        assert_matches_both(&puzzle, rules, SatUniqueness::Multiple);
+
+        // Row sums of 14 and 16 in the first two rows: no Latin square fits.
+        let mut unsat = puzzle;
+        unsat.cages[0].target = 14;
+        unsat.cages[1].target = 16;
+        assert_matches_both(&unsat, rules, SatUniqueness::Unsat);
+    }
+
+    #[test]
+    fn cancelled_native_search_gives_no_verdict() {
+        let cancel = AtomicBool::new(true);
+        let verdict = native_uniqueness(
+            &row_sums_5x5(),
+            Ruleset::keen_baseline(),
+            DeductionTier::None,
+            &cancel,
+        );
+        assert_eq!(verdict.unwrap(), None);
+    }
+
+    #[test]
+    fn invalid_puzzles_are_errors() {
+        let mut puzzle = row_sums_5x5();
+        puzzle.cages.pop();
+        assert!(matches!(
+            uniqueness_portfolio(&puzzle, Ruleset::keen_baseline(), DeductionTier::Easy),
+            Err(SolveError::Core(_))
+        ));
+    }
+}
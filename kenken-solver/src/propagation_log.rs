@@ -0,0 +1,224 @@
+//! Propagation round snapshots, for stepping through deductions in a viewer.
+//!
+//! [`solve_one_with_propagation_log`] records, after every propagation round of a solve, each
+//! cell's candidate digits bit-packed into `(n + 1).div_ceil(8)` bytes and the placements the
+//! round forced. [`PropagationLog::to_json`] hands the log to a viewer.
+
+use serde::{Deserialize, Serialize};
+
+use crate::{DeductionTier, Puzzle, Ruleset, SearchOptions, Solution, SolveError};
+
+/// Default for [`SearchOptions::max_logged_rounds`].
+pub const DEFAULT_MAX_LOGGED_ROUNDS: usize = 1024;
+
+/// A cell a propagation round placed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ForcedPlacement {
+    /// Row-major cell index.
+    pub cell: u16,
+    pub value: u8,
+}
+
+/// State at the end of one propagation round.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PropagationSnapshot {
+    /// Index of the `propagate` call the round belongs to: 0 is the root, then one per search
+    /// placement.
+    pub call: u32,
+    /// Round within the call, from 1.
+    pub round: u32,
+    /// Candidate digits of every cell, row-major, [`PropagationLog::bytes_per_cell`] bytes each,
+    /// little-endian, bit `v` set = digit `v` possible. Placed cells hold their digit only.
+    pub domains: Vec<u8>,
+    /// Placements the round forced, in cell order.
+    pub forced: Vec<ForcedPlacement>,
+    /// The round ended the call with a contradiction (an empty domain, or two cells of one
+    /// row or column forced to the same digit).
+    pub contradiction: bool,
+}
+
+/// Propagation rounds of one solve, in the order they ran.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PropagationLog {
+    pub n: u8,
+    pub snapshots: Vec<PropagationSnapshot>,
+    /// `propagate` calls seen.
+    pub calls: u32,
+    /// Rounds not kept because the log was full. Past the cap the newest round replaces the
+    /// last snapshot, so the log always ends on the final round.
+    pub dropped_rounds: u64,
+    #[serde(skip)]
+    max_rounds: usize,
+}
+
+impl PropagationLog {
+    pub(crate) fn new(n: u8, max_rounds: usize) -> Self {
+        Self {
+            n,
+            snapshots: Vec::new(),
+            calls: 0,
+            dropped_rounds: 0,
+            max_rounds,
+        }
+    }
+
+    /// Bytes per cell in [`PropagationSnapshot::domains`].
+    pub fn bytes_per_cell(&self) -> usize {
+        (self.n as usize + 1).div_ceil(8)
+    }
+
+    /// Candidate mask of `cell` in `snapshot` (bit `v` = digit `v`).
+    pub fn domain(&self, snapshot: &PropagationSnapshot, cell: usize) -> u64 {
+        let width = self.bytes_per_cell();
+        snapshot.domains[cell * width..(cell + 1) * width]
+            .iter()
+            .rev()
+            .fold(0, |mask, &byte| (mask << 8) | u64::from(byte))
+    }
+
+    /// The log as a JSON object.
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).expect("a propagation log always serializes")
+    }
+
+    pub(crate) fn begin_call(&mut self) {
+        self.calls += 1;
+    }
+
+    pub(crate) fn record(
+        &mut self,
+        round: u32,
+        domains: impl Iterator<Item = u64>,
+        forced: &[(usize, u8)],
+        contradiction: bool,
+    ) {
+        if self.max_rounds == 0 {
+            self.dropped_rounds += 1;
+            return;
+        }
+        let width = self.bytes_per_cell();
+        let mut packed = Vec::with_capacity(self.n as usize * self.n as usize * width);
+        for mask in domains {
+            packed.extend_from_slice(&mask.to_le_bytes()[..width]);
+        }
+        let mut forced: Vec<ForcedPlacement> = forced
+            .iter()
+            .map(|&(cell, value)| ForcedPlacement {
+                cell: cell as u16,
+                value,
+            })
+            .collect();
+        forced.sort_by_key(|p| p.cell);
+        let snapshot = PropagationSnapshot {
+            call: self.calls.saturating_sub(1),
+            round,
+            domains: packed,
+            forced,
+            contradiction,
+        };
+        if self.snapshots.len() == self.max_rounds {
+            self.dropped_rounds += 1;
+            self.snapshots.pop();
+        }
+        self.snapshots.push(snapshot);
+    }
+}
+
+/// Solve like [`solve_one_with_options`](crate::solve_one_with_options), also returning every
+/// propagation round it ran.
+///
+/// At most [`SearchOptions::max_logged_rounds`] snapshots are kept, to bound memory; the
+/// search itself is unaffected, and [`PropagationLog::dropped_rounds`] reports what was cut.
+pub fn solve_one_with_propagation_log(
+    puzzle: &Puzzle,
+    rules: Ruleset,
+    tier: DeductionTier,
+    options: SearchOptions,
+) -> Result<(Option<Solution>, PropagationLog), SolveError> {
+    crate::solver::solve_one_logging_propagation(puzzle, rules, tier, options)
+}
+
+#[cfg(test)]
+mod tests {
+    use kenken_core::format::sgt_desc::parse_keen_desc;
+
+    use super::*;
+    use crate::{DifficultyTier, classify_difficulty_from_tier, classify_tier_required};
+
+    fn normal_4x4() -> Puzzle {
+        parse_keen_desc(4, "a3_aaba__a__a_,a7a12m3m24a5d2").unwrap()
+    }
+
+    fn popcount(log: &PropagationLog, snapshot: &PropagationSnapshot) -> u32 {
+        (0..16)
+            .map(|cell| log.domain(snapshot, cell).count_ones())
+            .sum()
+    }
+
+    #[test]
+    fn normal_puzzle_log_narrows_to_singletons() {
+        let rules = Ruleset::keen_baseline();
+        let puzzle = normal_4x4();
+        assert_eq!(
+            classify_difficulty_from_tier(classify_tier_required(&puzzle, rules).unwrap()),
+            DifficultyTier::Normal
+        );
+
+        let (solution, log) = solve_one_with_propagation_log(
+            &puzzle,
+            rules,
+            DeductionTier::Normal,
+            SearchOptions::default(),
+        )
+        .unwrap();
+        let solution = solution.unwrap();
+        // Normal deductions solve it without backtracking, so no placement is ever undone.
+        assert!(log.calls >= 1);
+        assert_eq!(log.dropped_rounds, 0);
+        assert_eq!(log.bytes_per_cell(), 1);
+        assert!(log.snapshots.len() >= 2);
+        assert!(log.snapshots.iter().all(|s| s.domains.len() == 16));
+
+        let last = log.snapshots.last().unwrap();
+        for placement in log.snapshots.iter().flat_map(|s| &s.forced) {
+            let cell = placement.cell as usize;
+            assert_eq!(solution.grid[cell], placement.value);
+            assert_eq!(log.domain(last, cell), 1 << placement.value, "cell {cell}");
+        }
+        for pair in log.snapshots.windows(2) {
+            assert!(popcount(&log, &pair[1]) <= popcount(&log, &pair[0]));
+        }
+        assert_eq!(popcount(&log, last), 16);
+
+        let json = log.to_json();
+        let back: PropagationLog = serde_json::from_str(&json).unwrap();
+        assert_eq!(back.snapshots, log.snapshots);
+    }
+
+    #[test]
+    fn round_cap_keeps_the_final_round() {
+        let rules = Ruleset::keen_baseline();
+        let puzzle = normal_4x4();
+        let tier = DeductionTier::Normal;
+        let (_, full) =
+            solve_one_with_propagation_log(&puzzle, rules, tier, SearchOptions::default()).unwrap();
+        let capped_options = SearchOptions {
+            max_logged_rounds: 1,
+            ..SearchOptions::default()
+        };
+        let (solution, capped) =
+            solve_one_with_propagation_log(&puzzle, rules, tier, capped_options).unwrap();
+        assert!(solution.is_some());
+        assert_eq!(capped.snapshots.len(), 1);
+        assert_eq!(capped.dropped_rounds, full.snapshots.len() as u64 - 1);
+        assert_eq!(capped.snapshots.last(), full.snapshots.last());
+
+        let off = SearchOptions {
+            max_logged_rounds: 0,
+            ..SearchOptions::default()
+        };
+        let (_, empty) = solve_one_with_propagation_log(&puzzle, rules, tier, off).unwrap();
+        assert!(empty.snapshots.is_empty());
+        assert_eq!(empty.dropped_rounds, full.snapshots.len() as u64);
+    }
+}
@@ -0,0 +1,156 @@
+//! Clue redundancy: which cages a unique puzzle actually needs.
+//!
+//! A cage's clue is *redundant* when the puzzle stays uniquely solvable with that cage's
+//! arithmetic ignored (its cells then obey only the Latin rules), and *essential* otherwise.
+//! Each cage is tested on its own; two redundant clues are not necessarily redundant together.
+
+use crate::{DeductionTier, Puzzle, Ruleset, SolveError, count_solutions_up_to_ignoring_cages};
+
+/// Solution-count cap used for every uniqueness check in the analysis.
+pub const REDUNDANCY_SOLUTION_CAP: u32 = 2;
+
+/// Verdict for one cage of [`analyze_clue_redundancy`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ClueRedundancy {
+    /// With the clue ignored, counting up to `solution_cap` still finds exactly one solution.
+    Redundant { solution_cap: u32 },
+    /// With the clue ignored the puzzle has `solutions` solutions (capped at
+    /// [`REDUNDANCY_SOLUTION_CAP`]; 0 only if the puzzle was already unsolvable).
+    Essential { solutions: u32 },
+}
+
+impl ClueRedundancy {
+    pub fn is_redundant(self) -> bool {
+        matches!(self, Self::Redundant { .. })
+    }
+}
+
+/// Classifies every cage of `puzzle`, index-aligned with `puzzle.cages`.
+///
+/// `tier` only selects the propagation strength used by the underlying counts; verdicts are
+/// the same at every tier. Ignoring a clue can only add solutions, so on a puzzle with several
+/// solutions every cage is `Essential`; the analysis is meant for uniquely solvable puzzles.
+pub fn analyze_clue_redundancy(
+    puzzle: &Puzzle,
+    rules: Ruleset,
+    tier: DeductionTier,
+) -> Result<Vec<ClueRedundancy>, SolveError> {
+    (0..puzzle.cages.len())
+        .map(|idx| {
+            let solutions = count_solutions_up_to_ignoring_cages(
+                puzzle,
+                rules,
+                tier,
+                REDUNDANCY_SOLUTION_CAP,
+                &[idx],
+            )?;
+            Ok(if solutions == 1 {
+                ClueRedundancy::Redundant {
+                    solution_cap: REDUNDANCY_SOLUTION_CAP,
+                }
+            } else {
+                ClueRedundancy::Essential { solutions }
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use kenken_core::PuzzleBuilder;
+    use kenken_core::rules::Op;
+
+    use super::*;
+    use crate::reference::reference_count_ignoring;
+
+    const TIERS: [DeductionTier; 4] = [
+        DeductionTier::None,
+        DeductionTier::Easy,
+        DeductionTier::Normal,
+        DeductionTier::Hard,
+    ];
+
+    /// `(r + c) mod 4 + 1`, row-major.
+    const CYCLIC_4X4: [u8; 16] = [1, 2, 3, 4, 2, 3, 4, 1, 3, 4, 1, 2, 4, 1, 2, 3];
+
+    /// Unique 4x4 over `CYCLIC_4X4` whose only essential clue is the `4×` domino at (1,2)-(2,2).
+    fn one_essential_clue() -> Puzzle {
+        PuzzleBuilder::new(4)
+            .cage(Op::Mul, 6, &[(0, 0), (1, 0), (2, 0)])
+            .unwrap()
+            .cage(Op::Add, 8, &[(0, 1), (0, 2), (1, 1)])
+            .unwrap()
+            .cage(Op::Mul, 4, &[(0, 3), (1, 3)])
+            .unwrap()
+            .cage(Op::Mul, 4, &[(1, 2), (2, 2)])
+            .unwrap()
+            .cage(Op::Eq, 4, &[(2, 1)])
+            .unwrap()
+            .cage(Op::Add, 5, &[(2, 3), (3, 3)])
+            .unwrap()
+            .cage(Op::Eq, 4, &[(3, 0)])
+            .unwrap()
+            .cage(Op::Mul, 2, &[(3, 1), (3, 2)])
+            .unwrap()
+            .build(Ruleset::keen_baseline())
+            .unwrap()
+    }
+
+    #[test]
+    fn singleton_grid_clues_are_all_redundant() {
+        // Every given is forced by the other three in its row, so none carries the puzzle.
+        let rules = Ruleset::keen_baseline();
+        let puzzle = PuzzleBuilder::new(4)
+            .auto_singletons(&CYCLIC_4X4)
+            .unwrap()
+            .build(rules)
+            .unwrap();
+
+        for tier in TIERS {
+            let verdicts = analyze_clue_redundancy(&puzzle, rules, tier).unwrap();
+            assert_eq!(verdicts.len(), 16);
+            assert!(verdicts.iter().all(|v| *v
+                == ClueRedundancy::Redundant {
+                    solution_cap: REDUNDANCY_SOLUTION_CAP
+                }));
+        }
+    }
+
+    #[test]
+    fn finds_the_single_essential_clue() {
+        let rules = Ruleset::keen_baseline();
+        let puzzle = one_essential_clue();
+
+        for tier in TIERS {
+            let verdicts = analyze_clue_redundancy(&puzzle, rules, tier).unwrap();
+            let essential: Vec<usize> = (0..verdicts.len())
+                .filter(|&i| !verdicts[i].is_redundant())
+                .collect();
+            assert_eq!(essential, vec![3], "{tier:?}");
+            assert_eq!(verdicts[3], ClueRedundancy::Essential { solutions: 2 });
+        }
+    }
+
+    #[test]
+    fn verdicts_match_reference_counts() {
+        let rules = Ruleset::keen_baseline();
+        let puzzle = one_essential_clue();
+        let verdicts = analyze_clue_redundancy(&puzzle, rules, DeductionTier::Hard).unwrap();
+
+        for (idx, verdict) in verdicts.into_iter().enumerate() {
+            let count = reference_count_ignoring(&puzzle, rules, &[idx]).unwrap();
+            assert_eq!(verdict.is_redundant(), count == 1, "cage {idx}");
+        }
+    }
+
+    #[test]
+    fn multi_solution_puzzle_has_no_redundant_clues() {
+        let rules = Ruleset::keen_baseline();
+        let puzzle = kenken_core::format::sgt_desc::parse_keen_desc(2, "b__,a3a3").unwrap();
+        let verdicts = analyze_clue_redundancy(&puzzle, rules, DeductionTier::Normal).unwrap();
+        assert_eq!(
+            verdicts,
+            vec![ClueRedundancy::Essential { solutions: 2 }; 2]
+        );
+    }
+}
@@ -0,0 +1,460 @@
+//! Brute-force reference solver for small grids.
+//!
+//! Enumerates every Latin square of order `n` (row/column uniqueness only, no cage
+//! propagation; rows only when [`Ruleset::enforce_columns`] is off) and keeps the squares
+//! whose cages all hold under [`Op::satisfied_by`], the same op semantics the production
+//! solver checks at its leaves. It is deliberately naive so
+//! it can serve as an oracle for the search and deduction tiers.
+//!
+//! Compiled for unit tests and behind the `reference-solver` feature; it is not part of the
+//! default build.
+//!
+//! [`Op::satisfied_by`]: kenken_core::rules::Op::satisfied_by
+
+use kenken_core::Puzzle;
+use kenken_core::rules::Ruleset;
+
+use crate::error::SolveError;
+
+/// Largest grid the reference solver accepts (5x5 has 161,280 Latin squares).
+pub const REFERENCE_MAX_N: u8 = 5;
+
+/// Largest grid the reference solver accepts without Latin columns (4x4 has 331,776 grids
+/// whose rows are permutations).
+pub const REFERENCE_MAX_N_ROWS_ONLY: u8 = 4;
+
+/// All solutions of `puzzle`, each row-major, in lexicographic order.
+pub fn reference_solve_all(puzzle: &Puzzle, rules: Ruleset) -> Result<Vec<Vec<u8>>, SolveError> {
+    let mut out = Vec::new();
+    enumerate(puzzle, rules, &[], &mut |grid| out.push(grid.to_vec()))?;
+    Ok(out)
+}
+
+/// Number of solutions of `puzzle`.
+pub fn reference_count(puzzle: &Puzzle, rules: Ruleset) -> Result<u32, SolveError> {
+    reference_count_ignoring(puzzle, rules, &[])
+}
+
+/// Number of Latin squares satisfying every cage of `puzzle` except those at `ignored_cages`.
+pub fn reference_count_ignoring(
+    puzzle: &Puzzle,
+    rules: Ruleset,
+    ignored_cages: &[usize],
+) -> Result<u32, SolveError> {
+    let mut count = 0u32;
+    enumerate(puzzle, rules, ignored_cages, &mut |_| count += 1)?;
+    Ok(count)
+}
+
+fn enumerate(
+    puzzle: &Puzzle,
+    rules: Ruleset,
+    ignored_cages: &[usize],
+    on_solution: &mut dyn FnMut(&[u8]),
+) -> Result<(), SolveError> {
+    puzzle.validate(rules)?;
+    let max_n = if rules.enforce_columns {
+        REFERENCE_MAX_N
+    } else {
+        REFERENCE_MAX_N_ROWS_ONLY
+    };
+    if puzzle.n > max_n {
+        return Err(SolveError::GridSizeTooLarge {
+            n: puzzle.n,
+            hint: format!(
+                "The reference solver enumerates every Latin square and is limited to N<={max_n}."
+            ),
+        });
+    }
+
+    let n = puzzle.n as usize;
+    let mut grid = vec![0u8; n * n];
+    fill(puzzle, rules, ignored_cages, &mut grid, 0, on_solution);
+    Ok(())
+}
+
+fn fill(
+    puzzle: &Puzzle,
+    rules: Ruleset,
+    ignored_cages: &[usize],
+    grid: &mut [u8],
+    idx: usize,
+    on_solution: &mut dyn FnMut(&[u8]),
+) {
+    let n = puzzle.n as usize;
+    if idx == grid.len() {
+        if cages_hold(puzzle, ignored_cages, grid) {
+            on_solution(grid);
+        }
+        return;
+    }
+
+    let (r, c) = (idx / n, idx % n);
+    for v in 1..=puzzle.n {
+        let clash = (0..c).any(|cc| grid[r * n + cc] == v)
+            || (rules.enforce_columns && (0..r).any(|rr| grid[rr * n + c] == v));
+        if !clash {
+            grid[idx] = v;
+            fill(puzzle, rules, ignored_cages, grid, idx + 1, on_solution);
+        }
+    }
+    grid[idx] = 0;
+}
+
+fn cages_hold(puzzle: &Puzzle, ignored_cages: &[usize], grid: &[u8]) -> bool {
+    puzzle.cages.iter().enumerate().all(|(idx, cage)| {
+        if ignored_cages.contains(&idx) {
+            return true;
+        }
+        let values: Vec<i64> = cage
+            .cells
+            .iter()
+            .map(|cell| grid[cell.0 as usize] as i64)
+            .collect();
+        cage.op.satisfied_by(&values, cage.target)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::small_n::table_solutions;
+    use crate::solver::{
+        DeductionTier, SearchOptions, SmallNTables, SolveMode,
+        count_solutions_up_to_ignoring_cages, count_solutions_up_to_with_options, solve_one,
+        solve_one_with_deductions, solve_one_with_options,
+    };
+    use kenken_core::rules::Op;
+    use kenken_core::{Cage, CellId};
+    use proptest::prelude::*;
+    use proptest::test_runner::{Config, RngSeed};
+
+    /// Above the number of 4x4 Latin squares (576), so counts are exact.
+    const COUNT_LIMIT: u32 = 1000;
+
+    const TIERS: [DeductionTier; 4] = [
+        DeductionTier::None,
+        DeductionTier::Easy,
+        DeductionTier::Normal,
+        DeductionTier::Hard,
+    ];
+
+    fn permissive() -> Ruleset {
+        Ruleset {
+            sub_div_two_cell_only: false,
+            ..Ruleset::keen_baseline()
+        }
+    }
+
+    fn seeded(cases: u32, seed: u64) -> Config {
+        Config {
+            cases,
+            rng_seed: RngSeed::Fixed(seed),
+            failure_persistence: None,
+            ..Config::default()
+        }
+    }
+
+    /// Raw choices for one random puzzle; `build` turns them into a valid `Puzzle`.
+    #[derive(Debug, Clone)]
+    struct PuzzleSpec {
+        n: u8,
+        rows: Vec<u8>,
+        cols: Vec<u8>,
+        symbols: Vec<u8>,
+        /// Use the Klein four-group square (`r ^ c`) instead of the cyclic one (n = 4 only).
+        klein: bool,
+        /// Per cell: 0 starts a new cage, 1 joins the left neighbour, 2 joins the one above.
+        joins: Vec<u8>,
+        /// Per cage: op choice.
+        ops: Vec<u8>,
+        /// Per cage: bump the target by one so some puzzles have no (or other) solutions.
+        bumps: Vec<bool>,
+    }
+
+    fn perm(n: u8) -> impl Strategy<Value = Vec<u8>> {
+        Just((0..n).collect::<Vec<u8>>()).prop_shuffle()
+    }
+
+    fn puzzle_spec(n: u8) -> impl Strategy<Value = PuzzleSpec> {
+        let a = (n as usize) * (n as usize);
+        (
+            perm(n),
+            perm(n),
+            perm(n),
+            any::<bool>(),
+            prop::collection::vec(0u8..3, a),
+            prop::collection::vec(0u8..4, a),
+            prop::collection::vec(prop::bool::weighted(0.1), a),
+        )
+            .prop_map(
+                move |(rows, cols, symbols, klein, joins, ops, bumps)| PuzzleSpec {
+                    n,
+                    rows,
+                    cols,
+                    symbols,
+                    klein: klein && n == 4,
+                    joins,
+                    ops,
+                    bumps,
+                },
+            )
+    }
+
+    /// Like `puzzle_spec`, but every multi-cell cage asks for `Mul`.
+    fn mul_heavy_spec(n: u8) -> impl Strategy<Value = PuzzleSpec> {
+        puzzle_spec(n).prop_map(|mut spec| {
+            spec.ops.fill(1);
+            spec
+        })
+    }
+
+    /// Build the puzzle and the Latin square its unbumped targets were derived from.
+    fn build(spec: &PuzzleSpec, rules: Ruleset) -> (Puzzle, Vec<u8>) {
+        let n = spec.n as usize;
+        let a = n * n;
+        let solution: Vec<u8> = (0..a)
+            .map(|idx| {
+                let (r, c) = (spec.rows[idx / n], spec.cols[idx % n]);
+                let base = if spec.klein { r ^ c } else { (r + c) % spec.n };
+                spec.symbols[base as usize] + 1
+            })
+            .collect();
+
+        // Grow cages left-to-right, top-to-bottom so every cage stays connected.
+        let max_size = (rules.max_cage_size as usize).min(4);
+        let mut label = vec![usize::MAX; a];
+        let mut cells: Vec<Vec<usize>> = Vec::new();
+        for idx in 0..a {
+            let (r, c) = (idx / n, idx % n);
+            let neighbour = match spec.joins[idx] {
+                1 if c > 0 => Some(label[idx - 1]),
+                2 if r > 0 => Some(label[idx - n]),
+                _ => None,
+            };
+            match neighbour {
+                Some(cage) if cells[cage].len() < max_size => {
+                    label[idx] = cage;
+                    cells[cage].push(idx);
+                }
+                _ => {
+                    label[idx] = cells.len();
+                    cells.push(vec![idx]);
+                }
+            }
+        }
+
+        let cages = cells
+            .into_iter()
+            .enumerate()
+            .map(|(cage_idx, cells)| {
+                let values: Vec<i64> = cells.iter().map(|&i| solution[i] as i64).collect();
+                let wanted = match (values.len(), spec.ops[cage_idx]) {
+                    (1, _) => Op::Eq,
+                    (_, 0) => Op::Add,
+                    (_, 1) => Op::Mul,
+                    (2, 2) => Op::Sub,
+                    (2, _) => Op::Div,
+                    (_, 2) if !rules.sub_div_two_cell_only => Op::Sub,
+                    (_, _) if !rules.sub_div_two_cell_only => Op::Div,
+                    (_, _) => Op::Add,
+                };
+                let (op, mut target) = match wanted.apply(&values) {
+                    Some(t) if t > 0 => (wanted, t),
+                    _ => (Op::Add, Op::Add.apply(&values).unwrap()),
+                };
+                if spec.bumps[cage_idx] && !(op == Op::Eq && target == spec.n as i64) {
+                    target += 1;
+                }
+                Cage {
+                    cells: cells.iter().map(|&i| CellId(i as u16)).collect(),
+                    op,
+                    target,
+                }
+            })
+            .collect();
+
+        (Puzzle { n: spec.n, cages }, solution)
+    }
+
+    fn check_against_reference(spec: &PuzzleSpec, rules: Ruleset) -> Result<(), TestCaseError> {
+        let (puzzle, _) = build(spec, rules);
+        let solutions = reference_solve_all(&puzzle, rules).unwrap();
+        let expected = solutions.len() as u32;
+        if let Some(table) = table_solutions(&puzzle, rules).unwrap() {
+            prop_assert_eq!(
+                table,
+                solutions.iter().map(Vec::as_slice).collect::<Vec<_>>()
+            );
+        }
+        // Searched, not answered from the small-n tables.
+        let options = SearchOptions {
+            small_n_tables: SmallNTables::Never,
+            ..SearchOptions::default()
+        };
+        for tier in TIERS {
+            let (count, _) = count_solutions_up_to_with_options(
+                &puzzle,
+                rules,
+                tier,
+                COUNT_LIMIT,
+                options.clone(),
+            )
+            .unwrap();
+            prop_assert_eq!(count, expected, "tier {:?}", tier);
+
+            // LexMin returns the smallest solution; MRV any one of them.
+            let first = solve_one_with_deductions(&puzzle, rules, tier).unwrap();
+            prop_assert_eq!(
+                first.map(|sol| sol.grid),
+                solutions.first().cloned(),
+                "tier {:?}",
+                tier
+            );
+            let mrv = SearchOptions {
+                solve_mode: SolveMode::Mrv,
+                ..options.clone()
+            };
+            match solve_one_with_options(&puzzle, rules, tier, mrv).unwrap().0 {
+                Some(sol) => prop_assert!(
+                    solutions.contains(&sol.grid),
+                    "tier {:?} returned non-solution {:?}",
+                    tier,
+                    sol.grid
+                ),
+                None => prop_assert_eq!(expected, 0, "tier {:?} found no solution", tier),
+            }
+        }
+        let first = solve_one(&puzzle, rules).unwrap();
+        prop_assert_eq!(first.map(|sol| sol.grid), solutions.first().cloned());
+        Ok(())
+    }
+
+    proptest! {
+        #![proptest_config(seeded(300, 0x6b65_656e_0303))]
+
+        #[test]
+        fn solver_matches_reference_3x3(spec in puzzle_spec(3)) {
+            check_against_reference(&spec, Ruleset::keen_baseline())?;
+        }
+    }
+
+    proptest! {
+        #![proptest_config(seeded(300, 0x6b65_656e_0404))]
+
+        #[test]
+        fn solver_matches_reference_4x4(spec in puzzle_spec(4)) {
+            check_against_reference(&spec, Ruleset::keen_baseline())?;
+        }
+    }
+
+    proptest! {
+        #![proptest_config(seeded(200, 0x6b65_656e_5d04))]
+
+        #[test]
+        fn solver_matches_reference_4x4_n_ary_sub_div(spec in puzzle_spec(4)) {
+            check_against_reference(&spec, permissive())?;
+        }
+    }
+
+    proptest! {
+        #![proptest_config(seeded(200, 0x6b65_656e_4904))]
+
+        #[test]
+        fn ignoring_cages_matches_reference_4x4(spec in puzzle_spec(4), pick in any::<usize>()) {
+            let rules = Ruleset::keen_baseline();
+            let (puzzle, _) = build(&spec, rules);
+            let ignored = [pick % puzzle.cages.len()];
+            let expected = reference_count_ignoring(&puzzle, rules, &ignored).unwrap();
+            for tier in TIERS {
+                let count = count_solutions_up_to_ignoring_cages(
+                    &puzzle, rules, tier, COUNT_LIMIT, &ignored,
+                )
+                .unwrap();
+                prop_assert_eq!(count, expected, "tier {:?}", tier);
+            }
+        }
+    }
+
+    proptest! {
+        #![proptest_config(seeded(200, 0x6b65_656e_4d04))]
+
+        #[test]
+        fn solver_matches_reference_4x4_mul_heavy(spec in mul_heavy_spec(4)) {
+            check_against_reference(&spec, Ruleset::keen_baseline())?;
+        }
+    }
+
+    #[test]
+    fn unbumped_puzzles_contain_their_square() {
+        let spec = PuzzleSpec {
+            n: 4,
+            rows: vec![2, 0, 3, 1],
+            cols: vec![1, 3, 0, 2],
+            symbols: vec![3, 1, 0, 2],
+            klein: true,
+            joins: vec![0, 1, 1, 2, 2, 0, 1, 2, 0, 2, 1, 2, 1, 2, 0, 1],
+            ops: vec![0, 1, 2, 3, 0, 1, 2, 3, 0, 1, 2, 3, 0, 1, 2, 3],
+            bumps: vec![false; 16],
+        };
+        let (puzzle, solution) = build(&spec, Ruleset::keen_baseline());
+        let solutions = reference_solve_all(&puzzle, Ruleset::keen_baseline()).unwrap();
+        assert!(solutions.contains(&solution));
+    }
+
+    #[test]
+    fn all_singleton_cages_pin_one_square() {
+        let grid = [2u8, 3, 1, 1, 2, 3, 3, 1, 2];
+        let puzzle = Puzzle {
+            n: 3,
+            cages: grid
+                .iter()
+                .enumerate()
+                .map(|(idx, &v)| Cage {
+                    cells: [CellId(idx as u16)].into_iter().collect(),
+                    op: Op::Eq,
+                    target: v as i64,
+                })
+                .collect(),
+        };
+        let rules = Ruleset::keen_baseline();
+        assert_eq!(reference_solve_all(&puzzle, rules).unwrap(), vec![grid]);
+    }
+
+    #[test]
+    fn whole_row_sum_cages_admit_every_latin_square() {
+        let rules = Ruleset::keen_baseline();
+        for (n, squares) in [(3u8, 12u32), (4, 576)] {
+            let puzzle = Puzzle {
+                n,
+                cages: (0..n)
+                    .map(|r| Cage {
+                        cells: (0..n).map(|c| CellId((r * n + c) as u16)).collect(),
+                        op: Op::Add,
+                        target: (1..=n as i64).sum(),
+                    })
+                    .collect(),
+            };
+            assert_eq!(reference_count(&puzzle, rules).unwrap(), squares);
+        }
+    }
+
+    #[test]
+    fn rejects_grids_above_the_limit() {
+        let n = REFERENCE_MAX_N + 1;
+        let puzzle = Puzzle {
+            n,
+            cages: (0..(n as u16) * (n as u16))
+                .map(|idx| Cage {
+                    cells: [CellId(idx)].into_iter().collect(),
+                    op: Op::Eq,
+                    target: 1,
+                })
+                .collect(),
+        };
+        assert!(matches!(
+            reference_count(&puzzle, Ruleset::keen_baseline()),
+            Err(SolveError::GridSizeTooLarge { .. })
+        ));
+    }
+}
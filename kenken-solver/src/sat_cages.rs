@@ -1,11 +1,16 @@
 //! Cage constraint SAT encoding utilities (Varisat).
 //!
-//! This module is a staging area for extending SAT support from Latin-only
-//! (`sat_latin`) to full KenKen cage arithmetic. See `docs/sat_cage_encoding.md`.
+//! [`SatPuzzleEncoder`] encodes a full puzzle (Latin constraints plus every cage) onto a
+//! caller-owned solver, so custom side constraints can be added before solving;
+//! [`puzzle_uniqueness_via_sat`] is built on it. See `docs/sat_cage_encoding.md`.
 
+use kenken_core::CoreError;
+use kenken_core::arith::op_pair_satisfied;
+use kenken_core::limits::INLINE_CAGE_CELLS;
 use kenken_core::rules::{Op, Ruleset};
 use kenken_core::{Cage, Puzzle};
 use smallvec::SmallVec;
+use thiserror::Error;
 use varisat::{ExtendFormula, Lit, Solver, Var};
 
 use crate::sat_common::LatinVarMap;
@@ -20,25 +25,9 @@ macro_rules! trace {
     ($($tt:tt)*) => {};
 }
 
-/// Upper bound on enumerated satisfying tuples per cage for SAT allowlist encoding.
-///
-/// Chosen as 512 based on the following considerations:
-///
-/// - **Clause count scaling**: For T tuples in a k-cell cage, we need T selector vars,
-///   2*k*T implication clauses, and T*(T-1)/2 at-most-one clauses. At T=512 with k=4,
-///   this is ~135k clauses per cage.
-///
-/// - **Practical bounds**: For N<=9 and cages<=6 cells, typical tuple counts are:
-///   - 2-cell Add: max 8 tuples
-///   - 3-cell Add: max ~80 tuples
-///   - 4-cell Add: hundreds (near threshold)
-///   - 5+ cell Mul: may exceed threshold
-///
-/// - **Fallback cost**: When exceeded, `count_solutions_up_to(..., limit=2)` is used,
-///   which is fast for small puzzles where tuple explosion is unlikely anyway.
-///
-/// See `docs/sat_cage_encoding.md` section 3.4 for detailed justification.
-pub const SAT_TUPLE_THRESHOLD: usize = 512;
+/// Upper bound on enumerated satisfying tuples per cage for SAT allowlist encoding; see
+/// [`kenken_core::limits::SAT_TUPLE_THRESHOLD`] for how it was chosen.
+pub const SAT_TUPLE_THRESHOLD: usize = kenken_core::limits::SAT_TUPLE_THRESHOLD;
 
 fn add_eq_cage_clauses(solver: &mut Solver, map: &LatinVarMap, cage: &Cage) -> bool {
     if cage.cells.len() != 1 {
@@ -48,22 +37,13 @@ fn add_eq_cage_clauses(solver: &mut Solver, map: &LatinVarMap, cage: &Cage) -> b
     let idx = cage.cells[0].0 as usize;
     let row = idx / n;
     let col = idx % n;
-    if cage.target <= 0 || cage.target > n as i32 {
+    if cage.target <= 0 || cage.target > n as i64 {
         return false;
     }
     solver.add_clause(&[map.lit(row, col, cage.target as usize - 1)]);
     true
 }
 
-fn allowed_sub_pair(a: u8, b: u8, target: i32) -> bool {
-    (a as i32 - b as i32).abs() == target
-}
-
-fn allowed_div_pair(a: u8, b: u8, target: i32) -> bool {
-    let (num, den) = if a >= b { (a, b) } else { (b, a) };
-    den != 0 && (num as i32) == (den as i32).saturating_mul(target)
-}
-
 fn add_two_cell_sub_div_cage_clauses(solver: &mut Solver, map: &LatinVarMap, cage: &Cage) -> bool {
     if cage.cells.len() != 2 {
         return false;
@@ -77,12 +57,7 @@ fn add_two_cell_sub_div_cage_clauses(solver: &mut Solver, map: &LatinVarMap, cag
     let mut selectors: Vec<(Var, u8, u8)> = Vec::new();
     for av in 1..=n as u8 {
         for bv in 1..=n as u8 {
-            let ok = match cage.op {
-                Op::Sub => allowed_sub_pair(av, bv, cage.target),
-                Op::Div => allowed_div_pair(av, bv, cage.target),
-                _ => false,
-            };
-            if !ok {
+            if !op_pair_satisfied(cage.op, cage.target, av, bv) {
                 continue;
             }
             let s = solver.new_var();
@@ -129,7 +104,7 @@ fn add_tuple_allowlist(
     solver: &mut Solver,
     map: &LatinVarMap,
     cage: &Cage,
-    tuples: &[SmallVec<[u8; 6]>],
+    tuples: &[SmallVec<[u8; INLINE_CAGE_CELLS]>],
 ) -> bool {
     if tuples.is_empty() {
         return false;
@@ -180,19 +155,142 @@ fn add_tuple_allowlist(
     true
 }
 
+/// Why [`SatPuzzleEncoder::encode`] could not encode a puzzle.
+#[derive(Debug, Error)]
+pub enum SatEncodeError {
+    /// The puzzle failed validation under the encoder's ruleset.
+    #[error(transparent)]
+    Core(#[from] CoreError),
+
+    /// Cage `cage` (index into `Puzzle::cages`) has more satisfying tuples than the
+    /// allowlist threshold.
+    #[error("cage {cage} has more than {threshold} satisfying tuples")]
+    TupleOverflow { cage: usize, threshold: usize },
+}
+
+/// Builder that encodes a puzzle onto an existing Varisat solver.
+///
+/// `encode` allocates a [`LatinVarMap`], adds the Latin constraints (rows only when the
+/// ruleset turns [`Ruleset::enforce_columns`] off) and one encoding per cage, and hands the
+/// map back so callers can add their own clauses before solving. A cage with no satisfying
+/// assignment adds the empty clause, making the formula unsatisfiable.
+///
+/// ```
+/// use kenken_core::format::sgt_desc::parse_keen_desc;
+/// use kenken_solver::sat_cages::SatPuzzleEncoder;
+/// use varisat::{ExtendFormula, Solver};
+///
+/// // 2x2 with two `3+` rows: two solutions until the top-left cell is pinned to 2.
+/// let puzzle = parse_keen_desc(2, "b__,a3a3").unwrap();
+/// let mut solver = Solver::new();
+/// let map = SatPuzzleEncoder::new(&puzzle).encode(&mut solver).unwrap();
+///
+/// solver.add_clause(&[map.lit(0, 0, 1)]);
+/// assert!(solver.solve().unwrap());
+/// let grid = map.model_to_grid(&solver.model().unwrap()).unwrap();
+/// assert_eq!(grid, vec![2, 1, 1, 2]);
+/// ```
+#[derive(Debug, Clone)]
+pub struct SatPuzzleEncoder<'a> {
+    puzzle: &'a Puzzle,
+    rules: Ruleset,
+    tuple_threshold: usize,
+}
+
+impl<'a> SatPuzzleEncoder<'a> {
+    /// Encoder for `puzzle` under `Ruleset::keen_baseline()` and [`SAT_TUPLE_THRESHOLD`].
+    pub fn new(puzzle: &'a Puzzle) -> Self {
+        Self {
+            puzzle,
+            rules: Ruleset::keen_baseline(),
+            tuple_threshold: SAT_TUPLE_THRESHOLD,
+        }
+    }
+
+    /// Ruleset used for validation and cage semantics.
+    pub fn rules(mut self, rules: Ruleset) -> Self {
+        self.rules = rules;
+        self
+    }
+
+    /// Maximum satisfying tuples per allowlist-encoded cage before `encode` reports
+    /// [`SatEncodeError::TupleOverflow`].
+    pub fn tuple_threshold(mut self, max_tuples: usize) -> Self {
+        self.tuple_threshold = max_tuples;
+        self
+    }
+
+    /// Encode the puzzle onto `solver`, which may already hold other variables and clauses.
+    ///
+    /// All cages are checked before anything is added, so on error `solver` is untouched.
+    pub fn encode(&self, solver: &mut Solver) -> Result<LatinVarMap, SatEncodeError> {
+        let puzzle = self.puzzle;
+        puzzle.validate(self.rules)?;
+
+        // Tuple allowlists for Add/Mul and n-ary Sub/Div; `None` for the direct encodings.
+        let mut allowlists: Vec<Option<Vec<SmallVec<[u8; INLINE_CAGE_CELLS]>>>> =
+            Vec::with_capacity(puzzle.cages.len());
+        for (cage_idx, cage) in puzzle.cages.iter().enumerate() {
+            let direct = cage.op == Op::Eq
+                || (matches!(cage.op, Op::Sub | Op::Div) && cage.cells.len() == 2);
+            if direct {
+                allowlists.push(None);
+                continue;
+            }
+            let Some(tuples) =
+                cage.valid_permutations(puzzle.n, self.rules, self.tuple_threshold)?
+            else {
+                trace!(
+                    op = ?cage.op,
+                    cells = cage.cells.len(),
+                    threshold = self.tuple_threshold,
+                    "sat.encode.tuple_overflow"
+                );
+                return Err(SatEncodeError::TupleOverflow {
+                    cage: cage_idx,
+                    threshold: self.tuple_threshold,
+                });
+            };
+            trace!(
+                op = ?cage.op,
+                cells = cage.cells.len(),
+                tuples = tuples.len(),
+                "sat.encode.tuples"
+            );
+            allowlists.push(Some(tuples));
+        }
+
+        let map = LatinVarMap::new(solver, puzzle.n as usize);
+        if self.rules.enforce_columns {
+            map.add_latin_constraints(solver);
+        } else {
+            map.add_row_latin_constraints(solver);
+        }
+        for (cage, tuples) in puzzle.cages.iter().zip(&allowlists) {
+            let encoded = match (cage.op, tuples) {
+                (Op::Eq, _) => add_eq_cage_clauses(solver, &map, cage),
+                (_, Some(tuples)) => add_tuple_allowlist(solver, &map, cage, tuples),
+                (_, None) => add_two_cell_sub_div_cage_clauses(solver, &map, cage),
+            };
+            if !encoded {
+                // Validated puzzles only fail here when the cage has no satisfying tuple.
+                solver.add_clause(&[]);
+            }
+        }
+        Ok(map)
+    }
+}
+
 /// SAT-based uniqueness check for a full puzzle, currently supporting:
 /// - Latin constraints
 /// - Eq cages
 /// - 2-cell Sub/Div cages (ruleset baseline)
+/// - Add/Mul cages, and n-ary Sub/Div cages when `sub_div_two_cell_only` is off, via tuple
+///   allowlists
 ///
 /// Add/Mul cage encoding is intentionally staged; see `docs/sat_cage_encoding.md`.
 pub fn puzzle_uniqueness_via_sat(puzzle: &Puzzle, rules: Ruleset) -> SatUniqueness {
-    if !rules.sub_div_two_cell_only {
-        return SatUniqueness::Multiple;
-    }
-
-    let n = puzzle.n as usize;
-    trace!(n, cages = puzzle.cages.len(), "sat.encode.start");
+    trace!(n = puzzle.n, cages = puzzle.cages.len(), "sat.encode.start");
 
     // If SAT encoding would be too large (tuple explosion), fall back to the native solver
     // which can still count solutions up to 2 with early exit.
@@ -204,54 +302,15 @@ pub fn puzzle_uniqueness_via_sat(puzzle: &Puzzle, rules: Ruleset) -> SatUniquene
             Err(_) => SatUniqueness::Multiple,
         };
 
-    // Start from a fresh solver and build the full encoding in one place.
     let mut solver = Solver::new();
-
-    let map = LatinVarMap::new(&mut solver, n);
-    map.add_latin_constraints(&mut solver);
-
-    // Cage constraints (partial).
-    for cage in &puzzle.cages {
-        match cage.op {
-            Op::Eq => {
-                if !add_eq_cage_clauses(&mut solver, &map, cage) {
-                    return SatUniqueness::Unsat;
-                }
-            }
-            Op::Sub | Op::Div => {
-                if rules.sub_div_two_cell_only && cage.cells.len() != 2 {
-                    return SatUniqueness::Unsat;
-                }
-                if !add_two_cell_sub_div_cage_clauses(&mut solver, &map, cage) {
-                    return SatUniqueness::Unsat;
-                }
-            }
-            Op::Add | Op::Mul => {
-                let Ok(maybe) = cage.valid_permutations(puzzle.n, rules, SAT_TUPLE_THRESHOLD)
-                else {
-                    return SatUniqueness::Unsat;
-                };
-                let Some(tuples) = maybe else {
-                    trace!(
-                        op = ?cage.op,
-                        cells = cage.cells.len(),
-                        threshold = SAT_TUPLE_THRESHOLD,
-                        "sat.encode.tuple_overflow"
-                    );
-                    return native_fallback();
-                };
-                trace!(
-                    op = ?cage.op,
-                    cells = cage.cells.len(),
-                    tuples = tuples.len(),
-                    "sat.encode.tuples"
-                );
-                if !add_tuple_allowlist(&mut solver, &map, cage, &tuples) {
-                    return SatUniqueness::Unsat;
-                }
-            }
-        }
-    }
+    let map = match SatPuzzleEncoder::new(puzzle)
+        .rules(rules)
+        .encode(&mut solver)
+    {
+        Ok(map) => map,
+        Err(SatEncodeError::TupleOverflow { .. }) => return native_fallback(),
+        Err(SatEncodeError::Core(_)) => return SatUniqueness::Unsat,
+    };
 
     match solver.solve() {
         Ok(true) => {}
@@ -280,9 +339,9 @@ mod tests {
     use super::*;
     use crate::DeductionTier;
     use crate::count_solutions_up_to_with_deductions;
+    use kenken_core::PuzzleBuilder;
     use kenken_core::format::sgt_desc::parse_keen_desc;
     use kenken_core::rules::Op;
-    use kenken_core::{Cage, CellId, Puzzle};
 
     #[test]
     fn sat_cages_matches_solver_for_small_example() {
@@ -299,31 +358,11 @@ mod tests {
         // 2x2 Latin square:
         // 1 2
         // 2 1
-        let puzzle = Puzzle {
-            n: 2,
-            cages: vec![
-                Cage {
-                    cells: [CellId(0)].into_iter().collect(),
-                    op: Op::Eq,
-                    target: 1,
-                },
-                Cage {
-                    cells: [CellId(1)].into_iter().collect(),
-                    op: Op::Eq,
-                    target: 2,
-                },
-                Cage {
-                    cells: [CellId(2)].into_iter().collect(),
-                    op: Op::Eq,
-                    target: 2,
-                },
-                Cage {
-                    cells: [CellId(3)].into_iter().collect(),
-                    op: Op::Eq,
-                    target: 1,
-                },
-            ],
-        };
+        let puzzle = PuzzleBuilder::new(2)
+            .auto_singletons(&[1, 2, 2, 1])
+            .unwrap()
+            .build(Ruleset::keen_baseline())
+            .unwrap();
         let rules = Ruleset::keen_baseline();
         assert_eq!(
             puzzle_uniqueness_via_sat(&puzzle, rules),
@@ -334,31 +373,11 @@ mod tests {
     #[test]
     fn sat_cages_reports_unsat_for_contradictory_eqs() {
         // Contradiction: row 0 has two 1s.
-        let puzzle = Puzzle {
-            n: 2,
-            cages: vec![
-                Cage {
-                    cells: [CellId(0)].into_iter().collect(),
-                    op: Op::Eq,
-                    target: 1,
-                },
-                Cage {
-                    cells: [CellId(1)].into_iter().collect(),
-                    op: Op::Eq,
-                    target: 1,
-                },
-                Cage {
-                    cells: [CellId(2)].into_iter().collect(),
-                    op: Op::Eq,
-                    target: 2,
-                },
-                Cage {
-                    cells: [CellId(3)].into_iter().collect(),
-                    op: Op::Eq,
-                    target: 2,
-                },
-            ],
-        };
+        let puzzle = PuzzleBuilder::new(2)
+            .auto_singletons(&[1, 1, 2, 2])
+            .unwrap()
+            .build(Ruleset::keen_baseline())
+            .unwrap();
         let rules = Ruleset::keen_baseline();
         assert_eq!(
             puzzle_uniqueness_via_sat(&puzzle, rules),
@@ -370,78 +389,25 @@ mod tests {
     fn sat_cages_matches_solver_for_mixed_ops_unique_puzzle() {
         // A mostly pinned 4x4 puzzle with a few 2-cell cages (Add/Sub/Div).
         // The heavy pinning keeps the test fast and makes uniqueness unambiguous.
-        let puzzle = Puzzle {
-            n: 4,
-            cages: vec![
-                Cage {
-                    cells: [CellId(0)].into_iter().collect(),
-                    op: Op::Eq,
-                    target: 1,
-                },
-                Cage {
-                    cells: [CellId(1)].into_iter().collect(),
-                    op: Op::Eq,
-                    target: 2,
-                },
-                Cage {
-                    cells: [CellId(2), CellId(3)].into_iter().collect(),
-                    op: Op::Add,
-                    target: 7,
-                },
-                Cage {
-                    cells: [CellId(4), CellId(8)].into_iter().collect(),
-                    op: Op::Sub,
-                    target: 1,
-                },
-                Cage {
-                    cells: [CellId(5)].into_iter().collect(),
-                    op: Op::Eq,
-                    target: 3,
-                },
-                Cage {
-                    cells: [CellId(6)].into_iter().collect(),
-                    op: Op::Eq,
-                    target: 4,
-                },
-                Cage {
-                    cells: [CellId(7), CellId(11)].into_iter().collect(),
-                    op: Op::Div,
-                    target: 2,
-                },
-                Cage {
-                    cells: [CellId(9)].into_iter().collect(),
-                    op: Op::Eq,
-                    target: 4,
-                },
-                Cage {
-                    cells: [CellId(10)].into_iter().collect(),
-                    op: Op::Eq,
-                    target: 1,
-                },
-                Cage {
-                    cells: [CellId(12)].into_iter().collect(),
-                    op: Op::Eq,
-                    target: 4,
-                },
-                Cage {
-                    cells: [CellId(13)].into_iter().collect(),
-                    op: Op::Eq,
-                    target: 1,
-                },
-                Cage {
-                    cells: [CellId(14)].into_iter().collect(),
-                    op: Op::Eq,
-                    target: 2,
-                },
-                Cage {
-                    cells: [CellId(15)].into_iter().collect(),
-                    op: Op::Eq,
-                    target: 3,
-                },
-            ],
-        };
+        #[rustfmt::skip]
+        let solution = [
+            1, 2, 3, 4,
+            2, 3, 4, 1,
+            3, 4, 1, 2,
+            4, 1, 2, 3,
+        ];
         let rules = Ruleset::keen_baseline();
-        puzzle.validate(rules).unwrap();
+        let puzzle = PuzzleBuilder::new(4)
+            .cage(Op::Add, 7, &[(0, 2), (0, 3)])
+            .unwrap()
+            .cage(Op::Sub, 1, &[(1, 0), (2, 0)])
+            .unwrap()
+            .cage(Op::Div, 2, &[(1, 3), (2, 3)])
+            .unwrap()
+            .auto_singletons(&solution)
+            .unwrap()
+            .build(rules)
+            .unwrap();
 
         let native =
             count_solutions_up_to_with_deductions(&puzzle, rules, DeductionTier::Hard, 2).unwrap();
@@ -451,4 +417,127 @@ mod tests {
             SatUniqueness::Unique
         );
     }
+
+    #[test]
+    fn sat_cages_matches_solver_for_n_ary_sub_cage() {
+        // Top row: a 3-cell `1-` cage (4 - 1 - 2) and a given 3.
+        #[rustfmt::skip]
+        let solution = [
+            4, 1, 2, 3,
+            2, 3, 4, 1,
+            1, 4, 3, 2,
+            3, 2, 1, 4,
+        ];
+        let rules = Ruleset {
+            sub_div_two_cell_only: false,
+            ..Ruleset::keen_baseline()
+        };
+        let top = || {
+            PuzzleBuilder::new(4)
+                .cage(Op::Sub, 1, &[(0, 0), (0, 1), (0, 2)])
+                .unwrap()
+        };
+
+        // Everything below the top row given.
+        let pinned = top();
+        // Second row left to two `5+` pairs.
+        let loose = top()
+            .cage(Op::Add, 5, &[(1, 0), (1, 1)])
+            .unwrap()
+            .cage(Op::Add, 5, &[(1, 2), (1, 3)])
+            .unwrap();
+
+        for builder in [pinned, loose] {
+            let puzzle = builder
+                .auto_singletons(&solution)
+                .unwrap()
+                .build(rules)
+                .unwrap();
+            assert_eq!(
+                puzzle_uniqueness_via_sat(&puzzle, Ruleset::keen_baseline()),
+                SatUniqueness::Unsat
+            );
+
+            let expected =
+                match count_solutions_up_to_with_deductions(&puzzle, rules, DeductionTier::Hard, 2)
+                    .unwrap()
+                {
+                    0 => SatUniqueness::Unsat,
+                    1 => SatUniqueness::Unique,
+                    _ => SatUniqueness::Multiple,
+                };
+            assert_ne!(expected, SatUniqueness::Unsat);
+            assert_eq!(puzzle_uniqueness_via_sat(&puzzle, rules), expected);
+        }
+    }
+
+    #[test]
+    fn encoder_reports_overflowing_cage_and_leaves_solver_untouched() {
+        // 3x3 rows: `6+` has 6 ordered tuples, `6×` too; a threshold of 5 rejects cage 0.
+        let row = |r: u8| [(r, 0), (r, 1), (r, 2)];
+        let puzzle = PuzzleBuilder::new(3)
+            .cage(Op::Add, 6, &row(0))
+            .unwrap()
+            .cage(Op::Mul, 6, &row(1))
+            .unwrap()
+            .cage(Op::Add, 6, &row(2))
+            .unwrap()
+            .build(Ruleset::keen_baseline())
+            .unwrap();
+        let mut solver = Solver::new();
+        let err = SatPuzzleEncoder::new(&puzzle)
+            .tuple_threshold(5)
+            .encode(&mut solver)
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            SatEncodeError::TupleOverflow {
+                cage: 0,
+                threshold: 5
+            }
+        ));
+        assert_eq!(solver.new_var().index(), 0);
+
+        let map = SatPuzzleEncoder::new(&puzzle).encode(&mut solver).unwrap();
+        assert_eq!(map.vars()[0].index(), 1);
+        // Forbid 1 in the top-left cell; 12 Latin squares minus the 4 starting with 1.
+        solver.add_clause(&[map.nlit(0, 0, 0)]);
+        let mut solutions = 0;
+        while solver.solve().unwrap() {
+            let model = solver.model().unwrap();
+            assert_ne!(map.model_to_grid(&model).unwrap()[0], 1);
+            solver.add_clause(&map.model_to_blocking_clause(&model).unwrap());
+            solutions += 1;
+        }
+        assert_eq!(solutions, 8);
+    }
+
+    #[test]
+    fn encoder_makes_unsatisfiable_cage_unsat_and_rejects_invalid_puzzles() {
+        // A `7+` pair on a 2x2 has no satisfying tuple.
+        let mut puzzle = parse_keen_desc(2, "b__,a3a3").unwrap();
+        puzzle.cages[0].target = 7;
+        let mut solver = Solver::new();
+        SatPuzzleEncoder::new(&puzzle).encode(&mut solver).unwrap();
+        assert!(!solver.solve().unwrap());
+
+        let n_ary_sub = PuzzleBuilder::new(3)
+            .cage(Op::Sub, 1, &[(0, 0), (0, 1), (0, 2)])
+            .unwrap()
+            .cage(
+                Op::Add,
+                12,
+                &[(1, 0), (1, 1), (1, 2), (2, 0), (2, 1), (2, 2)],
+            )
+            .unwrap()
+            .build(Ruleset {
+                sub_div_two_cell_only: false,
+                ..Ruleset::keen_baseline()
+            })
+            .unwrap();
+        assert!(matches!(
+            SatPuzzleEncoder::new(&n_ary_sub).encode(&mut Solver::new()),
+            Err(SatEncodeError::Core(CoreError::SubDivMustBeTwoCell))
+        ));
+    }
 }
@@ -6,9 +6,17 @@
 //! - model-to-blocking-clause extraction (ignoring auxiliary vars)
 //!
 //! It is `sat-varisat`-only by construction (module is only compiled when enabled).
+//!
+//! The map is public so callers can build their own encodings on the Latin skeleton, e.g. by
+//! adding side constraints next to [`crate::sat_cages::SatPuzzleEncoder`]. Values are passed
+//! 0-based (`val0 = digit - 1`); decoded grids use digits `1..=n`.
 
 use varisat::{ExtendFormula, Lit, Solver, Var};
 
+/// Boolean variables `X(r, c, v)` ("cell `(r, c)` holds digit `v + 1`") for an `n x n` grid.
+///
+/// The variables are allocated from the caller's solver, so they need not start at index 0
+/// and may sit alongside variables the caller created before or after.
 #[derive(Debug, Clone)]
 pub struct LatinVarMap {
     n: usize,
@@ -16,6 +24,7 @@ pub struct LatinVarMap {
 }
 
 impl LatinVarMap {
+    /// Allocate `n³` fresh variables in `solver`. No clauses are added.
     pub fn new(solver: &mut Solver, n: usize) -> Self {
         let a = n * n;
         let mut vars = Vec::with_capacity(a * n);
@@ -25,10 +34,12 @@ impl LatinVarMap {
         Self { n, vars }
     }
 
+    /// Grid size.
     pub fn n(&self) -> usize {
         self.n
     }
 
+    /// All mapped variables, ordered by `(row, col, val0)`.
     pub fn vars(&self) -> &[Var] {
         &self.vars
     }
@@ -37,10 +48,14 @@ impl LatinVarMap {
         (row * self.n + col) * self.n + val0
     }
 
+    /// Positive literal for "cell `(row, col)` holds digit `val0 + 1`".
+    ///
+    /// Panics if any coordinate is `>= n`.
     pub fn lit(&self, row: usize, col: usize, val0: usize) -> Lit {
         Lit::from_var(self.vars[self.var_idx(row, col, val0)], true)
     }
 
+    /// Negation of [`LatinVarMap::lit`].
     pub fn nlit(&self, row: usize, col: usize, val0: usize) -> Lit {
         Lit::from_var(self.vars[self.var_idx(row, col, val0)], false)
     }
@@ -50,6 +65,27 @@ impl LatinVarMap {
     /// - row uniqueness
     /// - column uniqueness
     pub fn add_latin_constraints(&self, solver: &mut Solver) {
+        self.add_row_latin_constraints(solver);
+
+        // Col uniqueness: no digit repeats in a column.
+        let n = self.n;
+        for col in 0..n {
+            for val0 in 0..n {
+                for r1 in 0..n {
+                    for r2 in (r1 + 1)..n {
+                        solver.add_clause(&[self.nlit(r1, col, val0), self.nlit(r2, col, val0)]);
+                    }
+                }
+            }
+        }
+    }
+
+    /// [`add_latin_constraints`](Self::add_latin_constraints) without column uniqueness, the
+    /// skeleton of a ruleset without
+    /// [`enforce_columns`](kenken_core::rules::Ruleset::enforce_columns):
+    /// - exactly one value per cell
+    /// - row uniqueness
+    pub fn add_row_latin_constraints(&self, solver: &mut Solver) {
         let n = self.n;
 
         // Exactly one value per cell (pairwise at-most-one).
@@ -78,19 +114,12 @@ impl LatinVarMap {
                 }
             }
         }
-
-        // Col uniqueness: no digit repeats in a column.
-        for col in 0..n {
-            for val0 in 0..n {
-                for r1 in 0..n {
-                    for r2 in (r1 + 1)..n {
-                        solver.add_clause(&[self.nlit(r1, col, val0), self.nlit(r2, col, val0)]);
-                    }
-                }
-            }
-        }
     }
 
+    /// Add one unit clause per non-zero entry of the row-major `givens` grid.
+    ///
+    /// Returns `false` (adding nothing further) if `givens` has the wrong length or a digit
+    /// above `n`.
     pub fn add_givens_or_unsat(&self, solver: &mut Solver, givens: &[u8]) -> bool {
         let n = self.n;
         let a = n * n;
@@ -112,12 +141,16 @@ impl LatinVarMap {
         true
     }
 
-    /// Build a clause that blocks the current Latin assignment, ignoring auxiliary vars.
-    pub fn model_to_blocking_clause(&self, model: &[Lit]) -> Option<Vec<Lit>> {
+    /// Decode a model into a row-major grid of digits `1..=n`, ignoring auxiliary vars.
+    ///
+    /// Returns `None` if some cell has no true value variable in `model` (e.g. the model does
+    /// not come from a solver that included the Latin constraints).
+    pub fn model_to_grid(&self, model: &[Lit]) -> Option<Vec<u8>> {
         let n = self.n;
         let a = n * n;
 
-        let mut assignment = vec![false; self.vars.len()];
+        let max_index = self.vars.iter().map(|v| v.index()).max()?;
+        let mut assignment = vec![false; max_index + 1];
         for lit in model {
             let idx = lit.var().index();
             if idx < assignment.len() {
@@ -125,21 +158,53 @@ impl LatinVarMap {
             }
         }
 
-        let mut blocking = Vec::with_capacity(a);
+        let mut grid = Vec::with_capacity(a);
         for row in 0..n {
             for col in 0..n {
-                let mut chosen = None;
-                for val0 in 0..n {
-                    let v = self.vars[self.var_idx(row, col, val0)];
-                    if assignment[v.index()] {
-                        chosen = Some(val0);
-                        break;
-                    }
-                }
-                let val0 = chosen?;
-                blocking.push(self.nlit(row, col, val0));
+                let val0 = (0..n)
+                    .find(|&val0| assignment[self.vars[self.var_idx(row, col, val0)].index()])?;
+                grid.push(val0 as u8 + 1);
             }
         }
-        Some(blocking)
+        Some(grid)
+    }
+
+    /// Build a clause that blocks the current Latin assignment, ignoring auxiliary vars.
+    pub fn model_to_blocking_clause(&self, model: &[Lit]) -> Option<Vec<Lit>> {
+        let n = self.n;
+        let grid = self.model_to_grid(model)?;
+        Some(
+            grid.iter()
+                .enumerate()
+                .map(|(idx, &v)| self.nlit(idx / n, idx % n, v as usize - 1))
+                .collect(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decodes_model_when_vars_do_not_start_at_zero() {
+        let mut solver = Solver::new();
+        // Caller-owned variables allocated before the map.
+        let extra: Vec<Var> = (0..5).map(|_| solver.new_var()).collect();
+        let map = LatinVarMap::new(&mut solver, 3);
+        assert!(map.vars()[0].index() >= extra.len());
+        map.add_latin_constraints(&mut solver);
+        assert!(map.add_givens_or_unsat(&mut solver, &[2, 3, 1, 0, 0, 0, 0, 0, 0]));
+        solver.add_clause(&[map.lit(1, 0, 0)]);
+
+        assert_eq!(solver.solve().ok(), Some(true));
+        let model = solver.model().unwrap();
+        let grid = map.model_to_grid(&model).unwrap();
+        assert_eq!(grid, vec![2, 3, 1, 1, 2, 3, 3, 1, 2]);
+
+        let blocking = map.model_to_blocking_clause(&model).unwrap();
+        assert_eq!(blocking.len(), 9);
+        solver.add_clause(&blocking);
+        assert_eq!(solver.solve().ok(), Some(false));
     }
 }
@@ -0,0 +1,261 @@
+//! Search trees of a single solve, for teaching and debugging.
+//!
+//! [`solve_one_with_trace_tree`] records every candidate placement the deducing search tries,
+//! through the branch hooks of [`SearchObserver`], into a [`SearchTree`] that
+//! [`SearchTree::to_dot`] renders for Graphviz.
+
+use std::fmt::Write as _;
+
+use crate::{
+    DeductionTier, Puzzle, Ruleset, SearchObserver, Solution, SolveError, solve_one_observed,
+};
+
+/// What came of a [`SearchNode`]'s placement.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NodeOutcome {
+    /// Searched below, and the solution lies below this node.
+    Descended,
+    /// Broke a cage or led propagation to a contradiction; nothing below was searched.
+    Pruned,
+    /// Completed the grid, directly or through propagation.
+    Solution,
+    /// Searched below without finding a solution.
+    Exhausted,
+}
+
+/// One candidate placement: the branching cell set to `value`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SearchNode {
+    /// Index of the placement this one was tried under; `None` for the first branching cell.
+    pub parent: Option<usize>,
+    /// Row-major cell index.
+    pub cell: usize,
+    pub value: u8,
+    pub outcome: NodeOutcome,
+    /// Search nodes visited when the placement was made.
+    pub nodes_at_entry: u64,
+}
+
+/// Arena of the placements one solve tried, in the order it tried them, so a parent always
+/// precedes its children. Cells the root propagation filled have no nodes.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SearchTree {
+    pub n: u8,
+    pub nodes: Vec<SearchNode>,
+    /// The node cap was reached; later placements were tried but not recorded.
+    pub truncated: bool,
+}
+
+impl SearchTree {
+    /// Graphviz `digraph` of the tree: one vertex `n<index>` per node labelled with its cell
+    /// (`r<row>c<col>`, 0-based), value and `nodes_at_entry`, under a `root` vertex. Pruned
+    /// nodes are red, exhausted ones grey and the solution filled green.
+    pub fn to_dot(&self) -> String {
+        let n = self.n.max(1) as usize;
+        let mut out = String::from("digraph search {\n  node [shape=box];\n");
+        if self.truncated {
+            let _ = writeln!(
+                out,
+                "  label=\"truncated after {} nodes\";",
+                self.nodes.len()
+            );
+        }
+        out.push_str("  root [shape=point];\n");
+        for (idx, node) in self.nodes.iter().enumerate() {
+            let style = match node.outcome {
+                NodeOutcome::Descended => "",
+                NodeOutcome::Pruned => " color=red",
+                NodeOutcome::Solution => " style=filled fillcolor=palegreen",
+                NodeOutcome::Exhausted => " color=grey",
+            };
+            let _ = writeln!(
+                out,
+                "  n{idx} [label=\"r{}c{}={}\\n#{}\"{style}];",
+                node.cell / n,
+                node.cell % n,
+                node.value,
+                node.nodes_at_entry
+            );
+            match node.parent {
+                Some(parent) => {
+                    let _ = writeln!(out, "  n{parent} -> n{idx};");
+                }
+                None => {
+                    let _ = writeln!(out, "  root -> n{idx};");
+                }
+            }
+        }
+        out.push_str("}\n");
+        out
+    }
+}
+
+struct TreeRecorder {
+    tree: SearchTree,
+    max_nodes: usize,
+    /// Recorded nodes on the current search path, indexed by depth.
+    path: Vec<usize>,
+    /// Depth of the latest placement, recorded or not.
+    depth: usize,
+}
+
+impl SearchObserver for TreeRecorder {
+    fn on_branch(&mut self, depth: u32, cell: usize, value: u8, nodes: u64) {
+        let depth = depth as usize;
+        self.depth = depth;
+        self.path.truncate(depth);
+        // Below an unrecorded placement, parents would be missing.
+        if self.path.len() < depth {
+            return;
+        }
+        if self.tree.nodes.len() >= self.max_nodes {
+            self.tree.truncated = true;
+            return;
+        }
+        self.path.push(self.tree.nodes.len());
+        self.tree.nodes.push(SearchNode {
+            parent: depth.checked_sub(1).map(|d| self.path[d]),
+            cell,
+            value,
+            outcome: NodeOutcome::Exhausted,
+            nodes_at_entry: nodes,
+        });
+    }
+
+    fn on_pruned(&mut self, depth: u32) {
+        if self.path.len() == depth as usize + 1 {
+            self.tree.nodes[self.path[depth as usize]].outcome = NodeOutcome::Pruned;
+        }
+    }
+
+    fn on_solution(&mut self, _count: u32) {
+        // A solution is found right below the latest placement.
+        let leaf = (self.path.len() == self.depth + 1).then(|| self.path[self.depth]);
+        for &idx in &self.path {
+            self.tree.nodes[idx].outcome = NodeOutcome::Descended;
+        }
+        if let Some(leaf) = leaf {
+            self.tree.nodes[leaf].outcome = NodeOutcome::Solution;
+        }
+    }
+}
+
+/// Solve like [`solve_one_observed`], also returning the tree of placements tried.
+///
+/// At most `max_nodes` nodes are recorded, to bound memory; the search itself runs to
+/// completion either way, and [`SearchTree::truncated`] reports whether any were dropped.
+pub fn solve_one_with_trace_tree(
+    puzzle: &Puzzle,
+    rules: Ruleset,
+    tier: DeductionTier,
+    max_nodes: usize,
+) -> Result<(Option<Solution>, SearchTree), SolveError> {
+    let mut recorder = TreeRecorder {
+        tree: SearchTree {
+            n: puzzle.n,
+            nodes: Vec::new(),
+            truncated: false,
+        },
+        max_nodes,
+        path: Vec::new(),
+        depth: 0,
+    };
+    let solution = solve_one_observed(puzzle, rules, tier, &mut recorder)?;
+    Ok((solution, recorder.tree))
+}
+
+#[cfg(test)]
+mod tests {
+    use kenken_core::format::sgt_desc::parse_keen_desc;
+
+    use super::*;
+    use crate::{DifficultyTier, classify_difficulty_from_tier, classify_tier_required};
+
+    const TIERS: [DeductionTier; 4] = [
+        DeductionTier::None,
+        DeductionTier::Easy,
+        DeductionTier::Normal,
+        DeductionTier::Hard,
+    ];
+
+    fn extreme_4x4() -> Puzzle {
+        parse_keen_desc(4, "__a__a_ab_a__a_a_,s1a5s2m12s1s2d2m3").unwrap()
+    }
+
+    /// `(from, to)` for every `nA -> nB` edge in `dot`.
+    fn dot_edges(dot: &str) -> Vec<(usize, usize)> {
+        dot.lines()
+            .filter_map(|line| {
+                let (from, to) = line.trim().strip_suffix(';')?.split_once(" -> ")?;
+                Some((
+                    from.strip_prefix('n')?.parse().ok()?,
+                    to.strip_prefix('n')?.parse().ok()?,
+                ))
+            })
+            .collect()
+    }
+
+    #[test]
+    fn extreme_puzzle_tree_has_prunes_and_one_solution() {
+        let rules = Ruleset::keen_baseline();
+        let puzzle = extreme_4x4();
+        let required = classify_tier_required(&puzzle, rules).unwrap();
+        assert_eq!(
+            classify_difficulty_from_tier(required),
+            DifficultyTier::Extreme
+        );
+
+        for tier in TIERS {
+            let (solution, tree) = solve_one_with_trace_tree(&puzzle, rules, tier, 10_000).unwrap();
+            let solution = solution.unwrap();
+            assert!(!tree.truncated);
+            let count = |outcome| tree.nodes.iter().filter(|n| n.outcome == outcome).count();
+            assert!(count(NodeOutcome::Pruned) >= 1, "{tier:?}");
+            assert_eq!(count(NodeOutcome::Solution), 1, "{tier:?}");
+
+            // The path to the solution node places solution values and is all Descended.
+            let leaf = tree
+                .nodes
+                .iter()
+                .position(|n| n.outcome == NodeOutcome::Solution)
+                .unwrap();
+            let mut at = Some(leaf);
+            while let Some(idx) = at {
+                let node = tree.nodes[idx];
+                assert_eq!(solution.grid[node.cell], node.value, "{tier:?} node {idx}");
+                if idx != leaf {
+                    assert_eq!(node.outcome, NodeOutcome::Descended, "{tier:?} node {idx}");
+                }
+                at = node.parent;
+            }
+
+            assert!(
+                tree.nodes
+                    .iter()
+                    .enumerate()
+                    .all(|(idx, n)| n.parent.is_none_or(|p| p < idx))
+            );
+            let dot = tree.to_dot();
+            let edges = dot_edges(&dot);
+            let roots = dot.matches("root -> ").count();
+            assert_eq!(edges.len() + roots, tree.nodes.len(), "{dot}");
+            assert!(edges.iter().all(|&(from, to)| from < to), "{dot}");
+        }
+    }
+
+    #[test]
+    fn node_cap_truncates_the_tree_but_not_the_search() {
+        let rules = Ruleset::keen_baseline();
+        let puzzle = extreme_4x4();
+        let (full_solution, full) =
+            solve_one_with_trace_tree(&puzzle, rules, DeductionTier::None, usize::MAX).unwrap();
+        assert!(full.nodes.len() > 3);
+
+        let (solution, tree) =
+            solve_one_with_trace_tree(&puzzle, rules, DeductionTier::None, 3).unwrap();
+        assert_eq!(solution, full_solution);
+        assert!(tree.truncated);
+        assert_eq!(tree.nodes, full.nodes[..3]);
+        assert!(tree.to_dot().contains("truncated after 3 nodes"));
+    }
+}
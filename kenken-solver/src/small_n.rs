@@ -0,0 +1,190 @@
+//! Every Latin square of order 2, 3 and 4, and the table lookup that answers small-grid
+//! searches from them.
+//!
+//! There are only 2, 12 and 576 such squares, so a 4x4 or smaller puzzle's solutions are just
+//! the squares whose cages all hold, checked with [`kenken_core::arith`] like everywhere else.
+//! Filtering the table is cheaper than the search's setup, which makes uniqueness checks on
+//! small teaching puzzles essentially free, and it shares none of the search's propagation, so
+//! it doubles as an independent oracle. The tables are built at compile time by a const
+//! backtracking enumeration, never written out by hand.
+//!
+//! # Order
+//!
+//! Each table lists its squares row-major and in strictly increasing lexicographic order, the
+//! order a search that fills cells row-major, trying digits upwards, finds them in. The solver
+//! does not search that way: it picks the most constrained cell next, so when a puzzle has
+//! several solutions its first can be any of them. A table lookup therefore only stands in for
+//! a first-solution search when the puzzle has at most one solution; see
+//! [`SmallNTables`](crate::SmallNTables).
+
+use kenken_core::Puzzle;
+use kenken_core::arith::op_satisfied;
+use kenken_core::rules::Ruleset;
+
+use crate::SolveError;
+use crate::solver::validate_for_search;
+
+/// Largest grid with a table.
+pub const SMALL_N_MAX: u8 = 4;
+
+/// The 2 Latin squares of order 2, row-major, in lexicographic order.
+pub const LATIN_SQUARES_2: [[u8; 4]; 2] = latin_squares::<2, 4, 2>();
+
+/// The 12 Latin squares of order 3, row-major, in lexicographic order.
+pub const LATIN_SQUARES_3: [[u8; 9]; 12] = latin_squares::<3, 9, 12>();
+
+/// The 576 Latin squares of order 4, row-major, in lexicographic order.
+pub const LATIN_SQUARES_4: [[u8; 16]; 576] = latin_squares::<4, 16, 576>();
+
+/// Every `N`x`N` Latin square, by backtracking over the cells row-major with digits tried
+/// upwards. Fails to compile unless there are exactly `COUNT`.
+const fn latin_squares<const N: usize, const CELLS: usize, const COUNT: usize>()
+-> [[u8; CELLS]; COUNT] {
+    let mut squares = [[0u8; CELLS]; COUNT];
+    let mut found = 0;
+    let mut grid = [0u8; CELLS];
+    let mut idx = 0;
+    loop {
+        let mut digit = grid[idx] + 1;
+        while digit as usize <= N && clashes(&grid, N, idx, digit) {
+            digit += 1;
+        }
+        if digit as usize > N {
+            grid[idx] = 0;
+            if idx == 0 {
+                break;
+            }
+            idx -= 1;
+        } else {
+            grid[idx] = digit;
+            if idx + 1 < CELLS {
+                idx += 1;
+            } else {
+                squares[found] = grid;
+                found += 1;
+            }
+        }
+    }
+    assert!(found == COUNT, "wrong Latin square count");
+    squares
+}
+
+/// Whether `digit` already appears left of or above `idx`.
+const fn clashes(grid: &[u8], n: usize, idx: usize, digit: u8) -> bool {
+    let (r, c) = (idx / n, idx % n);
+    let mut k = 0;
+    while k < c {
+        if grid[r * n + k] == digit {
+            return true;
+        }
+        k += 1;
+    }
+    k = 0;
+    while k < r {
+        if grid[k * n + c] == digit {
+            return true;
+        }
+        k += 1;
+    }
+    false
+}
+
+/// Every Latin square of order `n`, in table order; `None` above [`SMALL_N_MAX`].
+pub fn latin_squares_of(n: u8) -> Option<std::slice::ChunksExact<'static, u8>> {
+    let (table, cells) = match n {
+        2 => (LATIN_SQUARES_2.as_flattened(), 4),
+        3 => (LATIN_SQUARES_3.as_flattened(), 9),
+        4 => (LATIN_SQUARES_4.as_flattened(), 16),
+        _ => return None,
+    };
+    Some(table.chunks_exact(cells))
+}
+
+/// Solutions of `puzzle` in table order, found by filtering the table; `Ok(None)` when the grid
+/// is larger than [`SMALL_N_MAX`] or `rules` drops Latin columns (the tables hold Latin
+/// squares only).
+///
+/// Errors: `puzzle` fails the search's validation.
+pub fn table_solutions(
+    puzzle: &Puzzle,
+    rules: Ruleset,
+) -> Result<Option<Vec<&'static [u8]>>, SolveError> {
+    validate_for_search(puzzle, rules)?;
+    if !rules.enforce_columns {
+        return Ok(None);
+    }
+    Ok(latin_squares_of(puzzle.n).map(|squares| {
+        squares
+            .filter(|square| cages_hold(puzzle, square))
+            .collect()
+    }))
+}
+
+/// Up to `limit` matches of an already validated `puzzle`, and the first in table order; `None`
+/// when the grid has no table.
+pub(crate) fn table_search(puzzle: &Puzzle, limit: u32) -> Option<(u32, Option<&'static [u8]>)> {
+    let mut matches = latin_squares_of(puzzle.n)?.filter(|square| cages_hold(puzzle, square));
+    let first = matches.next();
+    let count = first.map_or(0, |_| 1 + matches.take(limit as usize - 1).count() as u32);
+    Some((count.min(limit), first))
+}
+
+fn cages_hold(puzzle: &Puzzle, square: &[u8]) -> bool {
+    let mut values = [0u8; 16];
+    puzzle.cages.iter().all(|cage| {
+        let values = &mut values[..cage.cells.len()];
+        for (value, cell) in values.iter_mut().zip(&cage.cells) {
+            *value = square[cell.0 as usize];
+        }
+        op_satisfied(cage.op, cage.target, values)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use kenken_core::format::sgt_desc::parse_keen_desc;
+
+    use super::*;
+    use crate::reference::reference_solve_all;
+
+    #[test]
+    fn tables_hold_every_latin_square_in_lexicographic_order() {
+        for (n, count) in [(2, 2), (3, 12), (4, 576)] {
+            let squares: Vec<&[u8]> = latin_squares_of(n).unwrap().collect();
+            assert_eq!(squares.len(), count, "n={n}");
+            assert!(squares.windows(2).all(|w| w[0] < w[1]), "n={n}");
+            let n = n as usize;
+            for square in &squares {
+                for line in 0..n {
+                    let mut row: Vec<u8> = square[line * n..][..n].to_vec();
+                    let mut col: Vec<u8> = (0..n).map(|r| square[r * n + line]).collect();
+                    row.sort_unstable();
+                    col.sort_unstable();
+                    let digits: Vec<u8> = (1..=n as u8).collect();
+                    assert_eq!((row, col), (digits.clone(), digits));
+                }
+            }
+        }
+        assert!(latin_squares_of(5).is_none());
+    }
+
+    #[test]
+    fn filtering_matches_the_reference_solver() {
+        let rules = Ruleset::keen_baseline();
+        for (n, desc) in [
+            (2, "b__,a3a3"),
+            (2, "_5,a1a2a2a1"),
+            (4, "a3_aaba__a__a_,a7a12m3m24a5d2"),
+            (4, "a3_aaba__a3_,a7a12m3m24a8"),
+            (4, "a3_ada__a__a_,a7a12m3a12a5"),
+        ] {
+            let puzzle = parse_keen_desc(n, desc).unwrap();
+            let found = table_solutions(&puzzle, rules).unwrap().unwrap();
+            let expected = reference_solve_all(&puzzle, rules).unwrap();
+            assert_eq!(found, expected, "{desc}");
+            let (count, first) = table_search(&puzzle, 2).unwrap();
+            assert_eq!(count as usize, expected.len().min(2), "{desc}");
+            assert_eq!(first, expected.first().map(Vec::as_slice), "{desc}");
+        }
+    }
+}
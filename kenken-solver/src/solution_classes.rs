@@ -0,0 +1,366 @@
+//! Solution counts up to symmetry: is a puzzle's non-uniqueness essential, or just a
+//! symmetric pair?
+//!
+//! [`count_solution_classes`] counts orbits of solutions under the group a [`SymmetrySet`]
+//! generates. Each allowed symmetry must map every solution of the puzzle to another one, which
+//! is checked against the cages before any search.
+
+use std::collections::HashSet;
+
+use kenken_core::Coord;
+
+use crate::solver::{DEFAULT_MAX_TUPLES_PER_CAGE, solutions_up_to};
+use crate::{DeductionTier, Puzzle, Ruleset, SolveError};
+
+/// Generators of a symmetry group on solution grids. The default is the trivial group, under
+/// which every solution is its own class.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SymmetrySet {
+    /// Reflect grids in the main diagonal. Requires every cage's transpose to be a cage with
+    /// the same op and target, and Latin columns (a transposed rows-only grid is columns-only).
+    pub transpose: bool,
+    /// Digit relabelings, each listing the images of `1..=n`: `perm[d - 1]` replaces `d`.
+    /// Each must map every Latin-compatible tuple satisfying a cage's clue to another
+    /// satisfying tuple, e.g. `[2, 1]` for a 2x2 made of `3+` dominoes.
+    pub symbol_permutations: Vec<Vec<u8>>,
+}
+
+/// One group element: an optional transpose, then a digit relabeling (`relabel[0]` unused).
+#[derive(Clone)]
+struct Element {
+    transpose: bool,
+    relabel: Vec<u8>,
+}
+
+impl Element {
+    fn apply(&self, n: usize, grid: &[u8]) -> Vec<u8> {
+        (0..n * n)
+            .map(|idx| {
+                let src = if self.transpose {
+                    (idx % n) * n + idx / n
+                } else {
+                    idx
+                };
+                self.relabel[grid[src] as usize]
+            })
+            .collect()
+    }
+}
+
+/// Count solution classes of `puzzle` under the group `symmetries` generates, up to `limit`.
+///
+/// Two solutions are in one class when a group element maps one to the other. Solutions are
+/// enumerated and reduced to the lexicographically least grid of their class; at most
+/// `limit` times the group order are materialized, which is enough since a class has at most
+/// that many members. With the default `SymmetrySet` this equals
+/// [`crate::count_solutions_up_to_with_deductions`].
+///
+/// Returns [`SolveError::InvalidSymmetry`] if `transpose` is set on a puzzle whose cages are
+/// not transpose-invariant, or a symbol permutation is not a permutation of `1..=n` or breaks
+/// a cage.
+pub fn count_solution_classes(
+    puzzle: &Puzzle,
+    rules: Ruleset,
+    tier: DeductionTier,
+    limit: u32,
+    symmetries: SymmetrySet,
+) -> Result<u32, SolveError> {
+    puzzle.validate(rules)?;
+    let group = symmetry_group(puzzle, rules, &symmetries)?;
+    if limit == 0 {
+        return Ok(0);
+    }
+
+    let n = puzzle.n as usize;
+    let raw_limit = limit.saturating_mul(u32::try_from(group.len()).unwrap_or(u32::MAX));
+    let mut classes = HashSet::new();
+    for grid in solutions_up_to(puzzle, rules, tier, raw_limit)? {
+        let canonical = group
+            .iter()
+            .map(|g| g.apply(n, &grid))
+            .min()
+            .expect("the group contains the identity");
+        classes.insert(canonical);
+        if classes.len() as u32 >= limit {
+            break;
+        }
+    }
+    Ok(classes.len() as u32)
+}
+
+/// Every element of the group `symmetries` generates, after checking each generator.
+fn symmetry_group(
+    puzzle: &Puzzle,
+    rules: Ruleset,
+    symmetries: &SymmetrySet,
+) -> Result<Vec<Element>, SolveError> {
+    let n = puzzle.n as usize;
+    if symmetries.transpose && !rules.enforce_columns {
+        return Err(SolveError::InvalidSymmetry {
+            reason: "transposing a rows-only grid does not preserve its rows",
+        });
+    }
+    if symmetries.transpose && !transpose_invariant(puzzle) {
+        return Err(SolveError::InvalidSymmetry {
+            reason: "cage layout and clues are not transpose-invariant",
+        });
+    }
+    let mut generators = Vec::new();
+    for perm in &symmetries.symbol_permutations {
+        let mut relabel = vec![0u8];
+        relabel.extend_from_slice(perm);
+        let mut sorted = perm.clone();
+        sorted.sort_unstable();
+        if !sorted.iter().copied().eq(1..=puzzle.n) {
+            return Err(SolveError::InvalidSymmetry {
+                reason: "symbol permutation is not a permutation of 1..=n",
+            });
+        }
+        if !preserves_cages(puzzle, rules, &relabel)? {
+            return Err(SolveError::InvalidSymmetry {
+                reason: "symbol permutation does not preserve every cage clue",
+            });
+        }
+        generators.push(relabel);
+    }
+
+    // Close the relabelings under composition; transposes commute with them.
+    let identity: Vec<u8> = (0..=puzzle.n).collect();
+    let mut relabelings = vec![identity.clone()];
+    let mut seen = HashSet::from([identity]);
+    let mut next = 0;
+    while next < relabelings.len() {
+        for generator in &generators {
+            let composed: Vec<u8> = (0..=n)
+                .map(|d| generator[relabelings[next][d] as usize])
+                .collect();
+            if seen.insert(composed.clone()) {
+                relabelings.push(composed);
+            }
+        }
+        next += 1;
+    }
+
+    let transposes: &[bool] = if symmetries.transpose {
+        &[false, true]
+    } else {
+        &[false]
+    };
+    Ok(transposes
+        .iter()
+        .flat_map(|&transpose| {
+            relabelings.iter().map(move |relabel| Element {
+                transpose,
+                relabel: relabel.clone(),
+            })
+        })
+        .collect())
+}
+
+/// Whether reflecting every cage in the main diagonal gives a cage of the puzzle with the same
+/// op and target.
+fn transpose_invariant(puzzle: &Puzzle) -> bool {
+    let n = puzzle.n as u16;
+    let sorted = |cells: &mut dyn Iterator<Item = u16>| {
+        let mut cells: Vec<u16> = cells.collect();
+        cells.sort_unstable();
+        cells
+    };
+    let cages: Vec<_> = puzzle
+        .cages
+        .iter()
+        .map(|cage| {
+            (
+                sorted(&mut cage.cells.iter().map(|c| c.0)),
+                cage.op,
+                cage.target,
+            )
+        })
+        .collect();
+    cages.iter().all(|(cells, op, target)| {
+        let transposed = sorted(&mut cells.iter().map(|&c| (c % n) * n + c / n));
+        cages.contains(&(transposed, *op, *target))
+    })
+}
+
+/// Whether relabeling maps each tuple satisfying a cage's clue, with distinct digits in cells
+/// that share a row or column, to one that also satisfies the clue.
+fn preserves_cages(puzzle: &Puzzle, rules: Ruleset, relabel: &[u8]) -> Result<bool, SolveError> {
+    for cage in &puzzle.cages {
+        let lines: Vec<Coord> = cage.coords(puzzle.n).collect();
+        let Some(tuples) = cage.valid_permutations(puzzle.n, rules, DEFAULT_MAX_TUPLES_PER_CAGE)?
+        else {
+            return Err(SolveError::InvalidSymmetry {
+                reason: "cage has too many tuples to check a symbol permutation against",
+            });
+        };
+        for tuple in tuples {
+            let latin = (0..tuple.len()).all(|i| {
+                (i + 1..tuple.len()).all(|j| {
+                    tuple[i] != tuple[j]
+                        || (lines[i].row != lines[j].row && lines[i].col != lines[j].col)
+                })
+            });
+            if !latin {
+                continue;
+            }
+            let mapped: Vec<i64> = tuple.iter().map(|&d| relabel[d as usize] as i64).collect();
+            if !cage.op.satisfied_by(&mapped, cage.target) {
+                return Ok(false);
+            }
+        }
+    }
+    Ok(true)
+}
+
+#[cfg(test)]
+mod tests {
+    use kenken_core::PuzzleBuilder;
+    use kenken_core::format::sgt_desc::parse_keen_desc;
+    use kenken_core::rules::Op;
+
+    use super::*;
+    use crate::count_solutions_up_to_with_deductions;
+
+    const TIERS: [DeductionTier; 4] = [
+        DeductionTier::None,
+        DeductionTier::Easy,
+        DeductionTier::Normal,
+        DeductionTier::Hard,
+    ];
+
+    fn swap_1_2() -> SymmetrySet {
+        SymmetrySet {
+            symbol_permutations: vec![vec![2, 1]],
+            ..SymmetrySet::default()
+        }
+    }
+
+    /// `6+` on each row of a 3x3, which every Latin square satisfies.
+    fn row_sums_3x3() -> Puzzle {
+        PuzzleBuilder::new(3)
+            .cage(Op::Add, 6, &[(0, 0), (0, 1), (0, 2)])
+            .unwrap()
+            .cage(Op::Add, 6, &[(1, 0), (1, 1), (1, 2)])
+            .unwrap()
+            .cage(Op::Add, 6, &[(2, 0), (2, 1), (2, 2)])
+            .unwrap()
+            .build(Ruleset::keen_baseline())
+            .unwrap()
+    }
+
+    #[test]
+    fn symbol_swap_merges_the_two_2x2_solutions() {
+        let rules = Ruleset::keen_baseline();
+        let puzzle = parse_keen_desc(2, "b__,a3a3").unwrap();
+        for tier in TIERS {
+            let none = SymmetrySet::default();
+            assert_eq!(
+                count_solution_classes(&puzzle, rules, tier, 10, none).unwrap(),
+                2
+            );
+            assert_eq!(
+                count_solution_classes(&puzzle, rules, tier, 10, swap_1_2()).unwrap(),
+                1
+            );
+        }
+        let none = SymmetrySet::default();
+        assert_eq!(
+            count_solution_classes(&puzzle, rules, DeductionTier::Hard, 1, none).unwrap(),
+            1
+        );
+    }
+
+    #[test]
+    fn relabelings_split_order_3_latin_squares_into_two_classes() {
+        let rules = Ruleset::keen_baseline();
+        let puzzle = row_sums_3x3();
+        assert_eq!(
+            count_solutions_up_to_with_deductions(&puzzle, rules, DeductionTier::Hard, 100)
+                .unwrap(),
+            12
+        );
+        // The transposition and 3-cycle generate all six relabelings; each class holds one
+        // square per first row, and the two classes differ in the order of the other rows.
+        let all_relabelings = SymmetrySet {
+            transpose: false,
+            symbol_permutations: vec![vec![2, 1, 3], vec![2, 3, 1]],
+        };
+        assert_eq!(
+            count_solution_classes(&puzzle, rules, DeductionTier::Normal, 100, all_relabelings)
+                .unwrap(),
+            2
+        );
+    }
+
+    #[test]
+    fn transpose_is_allowed_on_invariant_cages() {
+        // One `6+` cage over the whole 2x2: both solutions are symmetric, so transposing
+        // merges nothing, while the symbol swap still does.
+        let rules = Ruleset::keen_baseline();
+        let puzzle = PuzzleBuilder::new(2)
+            .cage(Op::Add, 6, &[(0, 0), (0, 1), (1, 0), (1, 1)])
+            .unwrap()
+            .build(rules)
+            .unwrap();
+        let transpose = SymmetrySet {
+            transpose: true,
+            ..SymmetrySet::default()
+        };
+        assert_eq!(
+            count_solution_classes(&puzzle, rules, DeductionTier::Hard, 10, transpose).unwrap(),
+            2
+        );
+        let both = SymmetrySet {
+            transpose: true,
+            ..swap_1_2()
+        };
+        assert_eq!(
+            count_solution_classes(&puzzle, rules, DeductionTier::Hard, 10, both).unwrap(),
+            1
+        );
+    }
+
+    #[test]
+    fn invalid_symmetries_are_rejected() {
+        let rules = Ruleset::keen_baseline();
+        let transpose = SymmetrySet {
+            transpose: true,
+            ..SymmetrySet::default()
+        };
+        assert!(matches!(
+            count_solution_classes(&row_sums_3x3(), rules, DeductionTier::Hard, 2, transpose),
+            Err(SolveError::InvalidSymmetry { .. })
+        ));
+
+        let puzzle = parse_keen_desc(2, "b__,a3a3").unwrap();
+        for perm in [vec![1, 1], vec![2], vec![2, 3]] {
+            let bad = SymmetrySet {
+                symbol_permutations: vec![perm.clone()],
+                ..SymmetrySet::default()
+            };
+            assert!(
+                matches!(
+                    count_solution_classes(&puzzle, rules, DeductionTier::Hard, 2, bad),
+                    Err(SolveError::InvalidSymmetry { .. })
+                ),
+                "{perm:?}"
+            );
+        }
+
+        // Swapping 1 and 2 turns the given 1 into a 2.
+        let givens = PuzzleBuilder::new(2)
+            .cage(Op::Eq, 1, &[(0, 0)])
+            .unwrap()
+            .cage(Op::Eq, 2, &[(0, 1)])
+            .unwrap()
+            .cage(Op::Add, 3, &[(1, 0), (1, 1)])
+            .unwrap()
+            .build(rules)
+            .unwrap();
+        assert!(matches!(
+            count_solution_classes(&givens, rules, DeductionTier::Hard, 2, swap_1_2()),
+            Err(SolveError::InvalidSymmetry { .. })
+        ));
+    }
+}
@@ -9,9 +9,17 @@
 //! - `tracing`: enables `tracing::trace!` in hot paths (no subscriber required by the library).
 //! - `perf-likely`: enables branch prediction hints for hot paths.
 //! - `alloc-bumpalo`: uses `bumpalo` scratch arenas for propagation temporaries.
+//! - `serde`: serde derives for the tiers, `SolveStats`, `TierRequiredResult` and
+//!   `StatsSummary` (and `kenken-core/serde`).
 //!
-use kenken_core::rules::{Op, Ruleset};
+use kenken_core::arith::{op_pair_satisfied, op_satisfied, target_bounds};
+use kenken_core::limits::INLINE_CAGE_CELLS;
+use kenken_core::rules::{Op, ParseNameError, Ruleset};
 use kenken_core::{Cage, CoreError, Puzzle};
+use smallvec::SmallVec;
+
+use crate::observer::{NoopObserver, OBSERVER_NODE_INTERVAL, SearchObserver};
+use crate::small_n::{SMALL_N_MAX, table_search};
 
 #[cfg(feature = "tracing")]
 use tracing::{instrument, trace};
@@ -38,18 +46,37 @@ fn likely(v: bool) -> bool {
 #[cfg(feature = "alloc-bumpalo")]
 use bumpalo::Bump;
 
-use crate::error::SolveError;
+use crate::error::{SolutionError, SolveError};
 
-#[cfg(feature = "simd-dispatch")]
-#[allow(dead_code)]
-fn popcount_u32(x: u32) -> u32 {
-    kenken_simd::popcount_u32(x)
+use crate::mask::{Mask, WideMask};
+#[cfg(feature = "debug-snapshots")]
+use crate::propagation_log::PropagationLog;
+use crate::tuple_cache::TupleCacheHandle;
+
+/// Evaluate `$body` with `$M` naming the narrowest [`Mask`] that holds `$n` digits (always
+/// `u64` under the `mask-u64` benchmarking feature).
+macro_rules! with_mask {
+    ($n:expr, $M:ident => $body:expr) => {
+        if $n <= <u32 as Mask>::MAX_N && !cfg!(feature = "mask-u64") {
+            type $M = u32;
+            $body
+        } else {
+            type $M = u64;
+            $body
+        }
+    };
 }
 
-#[cfg(not(feature = "simd-dispatch"))]
-#[allow(dead_code)]
-fn popcount_u32(x: u32) -> u32 {
-    x.count_ones()
+/// [`with_mask!`] unless `backend` forces [`WideMask`].
+macro_rules! with_backend_mask {
+    ($n:expr, $backend:expr, $M:ident => $body:expr) => {
+        if $backend == SolverBackend::Wide {
+            type $M = WideMask;
+            $body
+        } else {
+            with_mask!($n, $M => $body)
+        }
+    };
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -58,7 +85,70 @@ pub struct Solution {
     pub grid: Vec<u8>,
 }
 
+impl Solution {
+    /// Row `r` of the grid.
+    pub fn row(&self, r: usize) -> &[u8] {
+        let n = self.n as usize;
+        debug_assert!(r < n, "row {r} out of range for n={n}");
+        &self.grid[r * n..(r + 1) * n]
+    }
+
+    /// The digit at `(row, col)`, 0-based.
+    pub fn get(&self, row: usize, col: usize) -> u8 {
+        let n = self.n as usize;
+        debug_assert!(row < n && col < n, "({row}, {col}) out of range for n={n}");
+        self.grid[row * n + col]
+    }
+
+    /// Whether the grid is an `n`x`n` Latin square over `1..=n`, ignoring cages.
+    ///
+    /// One pass with a digit bitmask per row and column; no allocation. Always `false` for
+    /// `n > 64`, wider than any grid the solver produces.
+    pub fn is_latin(&self) -> bool {
+        let n = self.n as usize;
+        if n > 64 || self.grid.len() != n * n {
+            return false;
+        }
+        let mut cols = [0u64; 64];
+        for r in 0..n {
+            let mut row = 0u64;
+            for (c, col) in cols.iter_mut().enumerate().take(n) {
+                let v = self.grid[r * n + c];
+                if v == 0 || v as usize > n {
+                    return false;
+                }
+                let bit = 1u64 << (v - 1);
+                if (row | *col) & bit != 0 {
+                    return false;
+                }
+                row |= bit;
+                *col |= bit;
+            }
+        }
+        true
+    }
+
+    /// Check this solution against `puzzle`: sizes must agree, `puzzle` must pass
+    /// [`Puzzle::validate`] under `rules`, and the grid must pass
+    /// [`Puzzle::check_solution_with_rules`].
+    pub fn validate_against(&self, puzzle: &Puzzle, rules: Ruleset) -> Result<(), SolutionError> {
+        if self.n != puzzle.n {
+            return Err(SolutionError::SizeMismatch {
+                solution: self.n,
+                puzzle: puzzle.n,
+            });
+        }
+        puzzle
+            .validate(rules)
+            .map_err(SolutionError::InvalidPuzzle)?;
+        puzzle
+            .check_solution_with_rules(&self.grid, rules)
+            .map_err(SolutionError::Violation)
+    }
+}
+
 #[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SolveStats {
     pub nodes_visited: u64,
     pub assignments: u64,
@@ -66,6 +156,12 @@ pub struct SolveStats {
     /// True if the solver tried multiple values at any cell (branched/guessed).
     /// When false, deductions alone determined all cell values.
     pub backtracked: bool,
+    /// Cage deductions that hit [`SearchOptions::max_tuples_per_cage`] and fell back to
+    /// bound-based filtering.
+    pub degraded_cages: u64,
+    /// Rounds of deduction propagation run: each round seeds domains, applies every cage
+    /// deduction once and places the cells left with a single candidate.
+    pub propagation_rounds: u64,
     /// Phase 6.3: Count of nogood cache hits (pruned branches due to CDL)
     #[cfg(feature = "nogood-learning")]
     pub nogoods_hit: u64,
@@ -74,7 +170,45 @@ pub struct SolveStats {
     pub nogoods_recorded: u64,
 }
 
+impl SolveStats {
+    /// Fold `other` into `self`, as if both searches were one: counters add, `max_depth` is
+    /// the larger and `backtracked` is set if either backtracked.
+    pub fn merge(&mut self, other: &Self) {
+        self.nodes_visited += other.nodes_visited;
+        self.assignments += other.assignments;
+        self.max_depth = self.max_depth.max(other.max_depth);
+        self.backtracked |= other.backtracked;
+        self.degraded_cages += other.degraded_cages;
+        self.propagation_rounds += other.propagation_rounds;
+        #[cfg(feature = "nogood-learning")]
+        {
+            self.nogoods_hit += other.nogoods_hit;
+            self.nogoods_recorded += other.nogoods_recorded;
+        }
+    }
+}
+
+impl core::ops::Add for SolveStats {
+    type Output = Self;
+
+    /// Same as [`SolveStats::merge`].
+    fn add(mut self, other: Self) -> Self {
+        self.merge(&other);
+        self
+    }
+}
+
+impl core::ops::AddAssign for SolveStats {
+    fn add_assign(&mut self, other: Self) {
+        self.merge(&other);
+    }
+}
+
+/// Difficulty rating. String forms (`Display`/`FromStr`, and serde with the `serde`
+/// feature) are the lowercase variant names.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "lowercase"))]
 pub enum DifficultyTier {
     Easy,
     Normal,
@@ -83,7 +217,11 @@ pub enum DifficultyTier {
     Unreasonable,
 }
 
+/// Deduction strength used during search. String forms (`Display`/`FromStr`, and serde with
+/// the `serde` feature) are the lowercase variant names.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "lowercase"))]
 pub enum DeductionTier {
     None,
     Easy,
@@ -91,12 +229,237 @@ pub enum DeductionTier {
     Hard,
 }
 
-/// Solve and return the first solution (if any).
+impl DifficultyTier {
+    pub const ALL: [DifficultyTier; 5] = [
+        DifficultyTier::Easy,
+        DifficultyTier::Normal,
+        DifficultyTier::Hard,
+        DifficultyTier::Extreme,
+        DifficultyTier::Unreasonable,
+    ];
+
+    pub const fn name(self) -> &'static str {
+        match self {
+            DifficultyTier::Easy => "easy",
+            DifficultyTier::Normal => "normal",
+            DifficultyTier::Hard => "hard",
+            DifficultyTier::Extreme => "extreme",
+            DifficultyTier::Unreasonable => "unreasonable",
+        }
+    }
+}
+
+impl DeductionTier {
+    pub const ALL: [DeductionTier; 4] = [
+        DeductionTier::None,
+        DeductionTier::Easy,
+        DeductionTier::Normal,
+        DeductionTier::Hard,
+    ];
+
+    pub const fn name(self) -> &'static str {
+        match self {
+            DeductionTier::None => "none",
+            DeductionTier::Easy => "easy",
+            DeductionTier::Normal => "normal",
+            DeductionTier::Hard => "hard",
+        }
+    }
+}
+
+impl core::fmt::Display for DifficultyTier {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl core::fmt::Display for DeductionTier {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl core::str::FromStr for DifficultyTier {
+    type Err = ParseNameError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|tier| tier.name() == s)
+            .ok_or_else(|| ParseNameError {
+                kind: "difficulty tier",
+                value: s.to_string(),
+                expected: "easy, normal, hard, extreme, unreasonable",
+            })
+    }
+}
+
+impl core::str::FromStr for DeductionTier {
+    type Err = ParseNameError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|tier| tier.name() == s)
+            .ok_or_else(|| ParseNameError {
+                kind: "deduction tier",
+                value: s.to_string(),
+                expected: "none, easy, normal, hard",
+            })
+    }
+}
+
+/// Default for [`SearchOptions::max_tuples_per_cage`]; see
+/// [`kenken_core::limits::SEARCH_TUPLE_BUDGET`] for how it was chosen.
+pub const DEFAULT_MAX_TUPLES_PER_CAGE: usize = kenken_core::limits::SEARCH_TUPLE_BUDGET;
+
+/// Tunable work limits for the deducing search.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SearchOptions {
+    /// Maximum enumeration nodes spent on one cage deduction. Past this the deduction for
+    /// that cage degrades to per-candidate min/max bounds (recorded in
+    /// [`SolveStats::degraded_cages`]); the search stays correct, it just prunes less.
+    pub max_tuples_per_cage: usize,
+    /// Maximum propagation rounds per fixpoint (at the root and after each placement). When a
+    /// round still forces cells at the cap, propagation stops with the domains it has and the
+    /// search branches on; solutions stay correct, only nodes grow. Counted in
+    /// [`SolveStats::propagation_rounds`]. Default `u32::MAX`; 0 is treated as 1.
+    pub max_propagation_rounds: u32,
+    /// Store of full-domain cage enumerations to read and fill, shared by every solve given a
+    /// clone of the handle. Results and stats are the same with or without it. Default `None`.
+    pub tuple_cache: Option<TupleCacheHandle>,
+    /// Propagation rounds kept by
+    /// [`solve_one_with_propagation_log`](crate::solve_one_with_propagation_log); 0 keeps none.
+    /// Default [`DEFAULT_MAX_LOGGED_ROUNDS`](crate::propagation_log::DEFAULT_MAX_LOGGED_ROUNDS).
+    #[cfg(feature = "debug-snapshots")]
+    pub max_logged_rounds: usize,
+    /// Which searches, after root propagation, split the unfilled cells into components that
+    /// share no row, column or cage and search each on its own, multiplying the counts.
+    /// Default [`ComponentSplit::Counting`].
+    pub split_components: ComponentSplit,
+    /// Candidate-mask width the search runs on. Default [`SolverBackend::Auto`].
+    pub backend: SolverBackend,
+    /// Which searches on grids up to 4x4 are answered from the precomputed Latin square tables
+    /// of [`crate::small_n`] instead. Default [`SmallNTables::Counting`].
+    pub small_n_tables: SmallNTables,
+    /// Which solution the `solve_one*` entry points return. Counts ignore it. Default
+    /// [`SolveMode::LexMin`].
+    pub solve_mode: SolveMode,
+    /// Break ties in MRV branching (counts, and [`SolveMode::Mrv`] solves) between cells with
+    /// equally few candidates by the tuples left in each cell's cage, fewest first: a cell
+    /// whose cage has two surviving tuples is branched before one in a looser cage. Normal and
+    /// Hard propagation count the tuples; a cage they did not count ranks after every counted
+    /// one. Counts and [`SolveMode::LexMin`] solutions are unchanged, but an MRV solve of a
+    /// puzzle with several solutions may return a different one. Default `false`.
+    pub mrv_tuple_tiebreak: bool,
+    /// Node counts at which the deducing search pauses to show its state to a callback; see
+    /// [`SearchInspector`](crate::debug::SearchInspector). Default `None`.
+    #[cfg(feature = "debug-api")]
+    pub inspector: Option<crate::debug::SearchInspector>,
+}
+
+/// Which solution a first-solution search returns; see [`SearchOptions::solve_mode`].
+///
+/// On a unique puzzle both modes return the solution; they differ on puzzles with several.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SolveMode {
+    /// The lexicographically smallest solution grid, read row-major. The search branches on
+    /// the lowest-index open cell and tries its digits in ascending order; deductions only
+    /// drop digits no solution uses, so the first solution it reaches is the smallest, whatever
+    /// the [`DeductionTier`], backend or feature set. Strengthening propagation can change the
+    /// work done, never the answer.
+    #[default]
+    LexMin,
+    /// Whichever solution the performance-oriented search reaches first: it branches on the
+    /// cell with the fewest candidates (MRV) and, with `lcv-heuristic`, tries the least
+    /// constraining digit first. Often fewer nodes, but the solution may change whenever
+    /// deductions or heuristics do.
+    Mrv,
+}
+
+/// Candidate-mask representation behind a search; see [`SearchOptions::backend`].
+///
+/// Every backend gives the same solutions, counts and [`SolveStats`]; they differ only in
+/// speed. The choice is honored by the option-taking solve and count entry points and by
+/// [`solve_one_with_propagation_log`](crate::solve_one_with_propagation_log); the others
+/// always run [`SolverBackend::Auto`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SolverBackend {
+    /// The narrowest native word that holds `n` digits: `u32` up to 31x31, `u64` above.
+    #[default]
+    Auto,
+    /// A 256-bit mask on every grid, however small. Slower; it exists so the wide
+    /// representation can be checked against the native ones where both apply.
+    Wide,
+}
+
+/// When the deducing search splits into independent components; see
+/// [`SearchOptions::split_components`].
+///
+/// Counts are the same either way. A split search that reports a first solution splices it
+/// from each component's first. Under [`SolveMode::LexMin`] that is still the smallest
+/// solution; under [`SolveMode::Mrv`] it need not be the solution the whole-grid search finds
+/// first. Either way its [`SolveStats`] count the work of the component searches.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ComponentSplit {
+    /// Always search the whole grid at once.
+    Never,
+    /// Split searches for more than one solution (`limit > 1`): counts and uniqueness checks.
+    #[default]
+    Counting,
+    /// Split every search, including those that stop at the first solution.
+    Always,
+}
+
+/// Which searches filter the [`crate::small_n`] tables instead of searching; see
+/// [`SearchOptions::small_n_tables`].
+///
+/// Only [`count_solutions_up_to_with_deductions`] (with or without `_and_stats`),
+/// [`solve_one_with_deductions`] and their `_with_options` forms consult it, besides
+/// [`solve_other_than`], and only for grids up to [`SMALL_N_MAX`](crate::small_n::SMALL_N_MAX).
+/// Counts and solutions are the same either way, except that [`solve_other_than`] may pick
+/// another of several other solutions. A table answer runs no search, so the [`SolveStats`] it
+/// returns are all zero.
+///
+/// The tables are in lexicographic order, so under [`SolveMode::LexMin`] their first match is
+/// the solution the search would return, on any puzzle.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SmallNTables {
+    /// Always search.
+    Never,
+    /// Answer counts from the table.
+    #[default]
+    Counting,
+    /// Answer counts and first-solution searches from the table. Under [`SolveMode::Mrv`], a
+    /// puzzle with several solutions is still searched, because the table's first is not
+    /// necessarily that search's (see [the table order](crate::small_n#order)).
+    Always,
+}
+
+impl Default for SearchOptions {
+    fn default() -> Self {
+        Self {
+            max_tuples_per_cage: DEFAULT_MAX_TUPLES_PER_CAGE,
+            max_propagation_rounds: u32::MAX,
+            tuple_cache: None,
+            #[cfg(feature = "debug-snapshots")]
+            max_logged_rounds: crate::propagation_log::DEFAULT_MAX_LOGGED_ROUNDS,
+            split_components: ComponentSplit::default(),
+            backend: SolverBackend::default(),
+            small_n_tables: SmallNTables::default(),
+            solve_mode: SolveMode::default(),
+            mrv_tuple_tiebreak: false,
+            #[cfg(feature = "debug-api")]
+            inspector: None,
+        }
+    }
+}
+
+/// Solve and return the lexicographically smallest solution (if any); see
+/// [`SolveMode::LexMin`].
 #[instrument(skip(puzzle, rules), fields(n = puzzle.n, cages = puzzle.cages.len()))]
 pub fn solve_one(puzzle: &Puzzle, rules: Ruleset) -> Result<Option<Solution>, SolveError> {
-    let mut first = None;
-    let count = search(puzzle, rules, 1, &mut first)?;
-    Ok(if count == 0 { None } else { first })
+    Ok(solve_one_with_stats(puzzle, rules)?.0)
 }
 
 /// Solve and also return solver statistics for the search (nodes, assignments, depth).
@@ -106,7 +469,7 @@ pub fn solve_one_with_stats(
 ) -> Result<(Option<Solution>, SolveStats), SolveError> {
     let mut first = None;
     let mut stats = SolveStats::default();
-    let count = search_with_stats(puzzle, rules, 1, &mut first, &mut stats)?;
+    let count = search_with_stats(puzzle, rules, 1, &mut first, &mut stats, true)?;
     Ok((if count == 0 { None } else { first }, stats))
 }
 
@@ -117,12 +480,98 @@ pub fn solve_one_with_deductions(
     rules: Ruleset,
     tier: DeductionTier,
 ) -> Result<Option<Solution>, SolveError> {
-    let mut first = None;
+    Ok(solve_one_with_options(puzzle, rules, tier, SearchOptions::default())?.0)
+}
+
+/// Solve with a selectable deduction tier and explicit [`SearchOptions`], returning stats.
+///
+/// The solution returned is chosen by [`SearchOptions::solve_mode`].
+pub fn solve_one_with_options(
+    puzzle: &Puzzle,
+    rules: Ruleset,
+    tier: DeductionTier,
+    options: SearchOptions,
+) -> Result<(Option<Solution>, SolveStats), SolveError> {
     let mut stats = SolveStats::default();
-    let count = search_with_stats_deducing(puzzle, rules, tier, 1, &mut first, &mut stats)?;
+    if let Some((_, first)) = small_n_lookup(puzzle, rules, 1, true, &options)? {
+        return Ok((first, stats));
+    }
+    let first = solve_from_options(puzzle, rules, tier, &mut stats, options, &mut NoopObserver)?;
+    Ok((first, stats))
+}
+
+/// First-solution search of the deducing `solve_one*` entry points, in `options.solve_mode`.
+fn solve_from_options(
+    puzzle: &Puzzle,
+    rules: Ruleset,
+    tier: DeductionTier,
+    stats: &mut SolveStats,
+    options: SearchOptions,
+    observer: &mut dyn SearchObserver,
+) -> Result<Option<Solution>, SolveError> {
+    validate_for_search(puzzle, rules)?;
+    let lex_min = options.solve_mode == SolveMode::LexMin;
+    let mut first = None;
+    let count = with_backend_mask!(puzzle.n, options.backend, M => {
+        let mut state = State::<M>::with_options(puzzle, rules, options).with_lex_min(lex_min);
+        search_from_state(puzzle, rules, tier, 1, &mut first, stats, &mut state, observer)
+    })?;
     Ok(if count == 0 { None } else { first })
 }
 
+/// `solve_one_with_options` with `state.propagation_log` kept (see
+/// [`solve_one_with_propagation_log`](crate::solve_one_with_propagation_log)).
+#[cfg(feature = "debug-snapshots")]
+pub(crate) fn solve_one_logging_propagation(
+    puzzle: &Puzzle,
+    rules: Ruleset,
+    tier: DeductionTier,
+    options: SearchOptions,
+) -> Result<(Option<Solution>, PropagationLog), SolveError> {
+    validate_for_search(puzzle, rules)?;
+    let mut first = None;
+    let mut stats = SolveStats::default();
+    let max_rounds = options.max_logged_rounds;
+    let lex_min = options.solve_mode == SolveMode::LexMin;
+    with_backend_mask!(puzzle.n, options.backend, M => {
+        let mut state = State::<M>::with_options(puzzle, rules, options).with_lex_min(lex_min);
+        state.propagation_log = Some(PropagationLog::new(puzzle.n, max_rounds));
+        search_from_state(
+            puzzle,
+            rules,
+            tier,
+            1,
+            &mut first,
+            &mut stats,
+            &mut state,
+            &mut NoopObserver,
+        )?;
+        let log = state.propagation_log.take().expect("set before the search");
+        Ok((first, log))
+    })
+}
+
+/// Solve with a selectable deduction tier, reporting progress to `observer`.
+///
+/// Same search as [`solve_one_with_deductions`]; see [`SearchObserver`] for when each hook
+/// fires.
+pub fn solve_one_observed(
+    puzzle: &Puzzle,
+    rules: Ruleset,
+    tier: DeductionTier,
+    observer: &mut dyn SearchObserver,
+) -> Result<Option<Solution>, SolveError> {
+    let mut stats = SolveStats::default();
+    solve_from_options(
+        puzzle,
+        rules,
+        tier,
+        &mut stats,
+        SearchOptions::default(),
+        observer,
+    )
+}
+
 /// Count solutions up to `limit` (use `2` to check uniqueness).
 #[instrument(skip(puzzle, rules), fields(n = puzzle.n, limit))]
 pub fn count_solutions_up_to(
@@ -148,135 +597,1345 @@ pub fn count_solutions_up_to_with_deductions(
     if limit == 0 {
         return Ok(0);
     }
+    let options = SearchOptions::default();
+    if let Some((count, _)) = small_n_lookup(puzzle, rules, limit, false, &options)? {
+        return Ok(count);
+    }
     let mut stats = SolveStats::default();
     search_with_stats_deducing(puzzle, rules, tier, limit, &mut None, &mut stats)
 }
 
-fn search(
+/// Count solutions up to `limit` using a selectable deduction tier, also returning search stats.
+///
+/// Same search as `count_solutions_up_to_with_deductions`, small-n tables included (a table
+/// answer reports all-zero stats); the stats let callers (e.g. the generator) account for how
+/// much work uniqueness checks cost.
+pub fn count_solutions_up_to_with_deductions_and_stats(
     puzzle: &Puzzle,
     rules: Ruleset,
+    tier: DeductionTier,
     limit: u32,
-    first: &mut Option<Solution>,
-) -> Result<u32, SolveError> {
+) -> Result<(u32, SolveStats), SolveError> {
     let mut stats = SolveStats::default();
-    search_with_stats(puzzle, rules, limit, first, &mut stats)
+    if limit == 0 {
+        return Ok((0, stats));
+    }
+    let options = SearchOptions::default();
+    if let Some((count, _)) = small_n_lookup(puzzle, rules, limit, false, &options)? {
+        return Ok((count, stats));
+    }
+    let count = search_with_stats_deducing(puzzle, rules, tier, limit, &mut None, &mut stats)?;
+    Ok((count, stats))
 }
 
-fn search_with_stats(
+/// Count solutions up to `limit` with explicit [`SearchOptions`], also returning search stats.
+pub fn count_solutions_up_to_with_options(
     puzzle: &Puzzle,
     rules: Ruleset,
+    tier: DeductionTier,
     limit: u32,
-    first: &mut Option<Solution>,
-    stats: &mut SolveStats,
-) -> Result<u32, SolveError> {
-    puzzle.validate(rules)?;
+    options: SearchOptions,
+) -> Result<(u32, SolveStats), SolveError> {
+    let mut stats = SolveStats::default();
+    if limit == 0 {
+        return Ok((0, stats));
+    }
+    if let Some((count, _)) = small_n_lookup(puzzle, rules, limit, false, &options)? {
+        return Ok((count, stats));
+    }
+    let count = search_with_options(
+        puzzle,
+        rules,
+        tier,
+        limit,
+        &mut None,
+        &mut stats,
+        options,
+        &mut NoopObserver,
+    )?;
+    Ok((count, stats))
+}
 
-    let n = puzzle.n as usize;
-    let a = n * n;
+/// Count solutions up to `limit` given a grid `known` that probably still solves the puzzle,
+/// also returning search stats.
+///
+/// Meant for callers that re-check puzzles differing slightly from one already solved, like the
+/// minimizer. `known` is first checked with [`Puzzle::check_solution`]; if it passes it counts
+/// as the first solution and the search only looks for others, trying digits that diverge from
+/// `known` before the ones that follow it. If it fails, this is
+/// [`count_solutions_up_to_with_options`]. The count is the same either way.
+pub fn count_solutions_up_to_warm_start(
+    puzzle: &Puzzle,
+    rules: Ruleset,
+    tier: DeductionTier,
+    limit: u32,
+    known: &[u8],
+    options: SearchOptions,
+) -> Result<(u32, SolveStats), SolveError> {
+    validate_for_search(puzzle, rules)?;
+    if limit == 0 || puzzle.check_solution_with_rules(known, rules).is_err() {
+        return count_solutions_up_to_with_options(puzzle, rules, tier, limit, options);
+    }
+    let mut stats = SolveStats::default();
+    if limit == 1 {
+        return Ok((1, stats));
+    }
+    let others = with_backend_mask!(puzzle.n, options.backend, M => {
+        let mut state = State::<M>::with_options(puzzle, rules, options);
+        state.warm_grid = known.to_vec();
+        state.on_warm_path = true;
+        search_from_state(
+            puzzle,
+            rules,
+            tier,
+            limit - 1,
+            &mut None,
+            &mut stats,
+            &mut state,
+            &mut NoopObserver,
+        )
+    })?;
+    Ok((1 + others, stats))
+}
 
-    let mut cage_of_cell = vec![usize::MAX; a];
-    for (cage_idx, cage) in puzzle.cages.iter().enumerate() {
-        for cell in &cage.cells {
-            cage_of_cell[cell.0 as usize] = cage_idx;
-        }
+/// A solution of `puzzle` other than the grid `known`, searched for the way
+/// [`count_solutions_up_to_warm_start`] looks for a second solution, also returning search stats.
+/// `None` when there is none, so for a `known` that solves the puzzle this decides uniqueness
+/// and shows a witness when it fails. A `known` that fails [`Puzzle::check_solution`] is no
+/// solution, and then any solution is returned.
+///
+/// Unless [`SearchOptions::small_n_tables`] is `Never`, grids the tables cover are answered
+/// from them, at no search nodes.
+pub fn solve_other_than(
+    puzzle: &Puzzle,
+    rules: Ruleset,
+    tier: DeductionTier,
+    known: &[u8],
+    options: SearchOptions,
+) -> Result<(Option<Solution>, SolveStats), SolveError> {
+    validate_for_search(puzzle, rules)?;
+    if puzzle.check_solution_with_rules(known, rules).is_err() {
+        return solve_one_with_options(puzzle, rules, tier, options);
+    }
+    let mut stats = SolveStats::default();
+    if options.small_n_tables != SmallNTables::Never
+        && let Some(solutions) = crate::small_n::table_solutions(puzzle, rules)?
+    {
+        let other = solutions.into_iter().find(|&grid| grid != known);
+        let other = other.map(|grid| Solution {
+            n: puzzle.n,
+            grid: grid.to_vec(),
+        });
+        return Ok((other, stats));
     }
+    let mut other = None;
+    with_backend_mask!(puzzle.n, options.backend, M => {
+        let mut state = State::<M>::with_options(puzzle, rules, options);
+        state.warm_grid = known.to_vec();
+        state.on_warm_path = true;
+        search_from_state(
+            puzzle,
+            rules,
+            tier,
+            1,
+            &mut other,
+            &mut stats,
+            &mut state,
+            &mut NoopObserver,
+        )
+    })?;
+    Ok((other, stats))
+}
 
-    let mut state = State {
-        n: puzzle.n,
-        grid: vec![0; a],
-        row_mask: vec![0u64; n],
-        col_mask: vec![0u64; n],
-        cage_of_cell,
-        tuple_cache: HashMap::new(),
-        mrv_cache: MrvCache::new(puzzle.n),
-        #[cfg(feature = "nogood-learning")]
-        nogood_cache: Some(crate::nogood::NogoodCache::new(10000)),
-    };
+/// How a count orders its search; see [`count_solutions_up_to_with_strategy`].
+///
+/// The count is the same under every strategy; only the nodes spent reaching it differ.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum CountStrategy<'a> {
+    /// The search of [`count_solutions_up_to_with_options`].
+    #[default]
+    Default,
+    /// Diverge from a known solution grid (row-major) as early as possible, for uniqueness
+    /// checks of puzzles built from it, like the generator's and minimizer's. The grid is
+    /// checked with [`Puzzle::check_solution`] and, if it passes, counted without being
+    /// searched for. Along its path the search branches on the cell with the fewest
+    /// candidates, which is also the one with the fewest digits besides the known one, and
+    /// tries those digits before the known one, so a second solution sharing a long prefix
+    /// with it turns up before the walk back down to it. A grid that fails the check falls
+    /// back to [`CountStrategy::Default`].
+    DivergeFrom(&'a [u8]),
+}
 
-    let mut count = 0u32;
-    backtrack(
-        puzzle, rules, limit, first, &mut state, &mut count, 0, stats,
-    )?;
-    Ok(count)
+/// Count solutions up to `limit` under `strategy`, with explicit [`SearchOptions`], also
+/// returning search stats.
+///
+/// Grids the small-n tables answer are looked up under every strategy (see
+/// [`SearchOptions::small_n_tables`]), at no search nodes.
+pub fn count_solutions_up_to_with_strategy(
+    puzzle: &Puzzle,
+    rules: Ruleset,
+    tier: DeductionTier,
+    limit: u32,
+    strategy: CountStrategy<'_>,
+    options: SearchOptions,
+) -> Result<(u32, SolveStats), SolveError> {
+    match strategy {
+        CountStrategy::Default => {
+            count_solutions_up_to_with_options(puzzle, rules, tier, limit, options)
+        }
+        CountStrategy::DivergeFrom(known) => {
+            if limit > 0
+                && let Some((count, _)) = small_n_lookup(puzzle, rules, limit, false, &options)?
+            {
+                return Ok((count, SolveStats::default()));
+            }
+            count_solutions_up_to_warm_start(puzzle, rules, tier, limit, known, options)
+        }
+    }
 }
 
-fn search_with_stats_deducing(
+/// Count solutions up to `limit` while ignoring the arithmetic of the cages at the indices in
+/// `ignored_cages`.
+///
+/// An ignored cage still partitions the grid, but its op and target constrain nothing: its cells
+/// are bound only by the Latin rules. Used by clue-redundancy analysis to ask whether a clue is
+/// needed for uniqueness. Indices past the end of `puzzle.cages` are rejected with
+/// `CageIndexOutOfRange`.
+pub fn count_solutions_up_to_ignoring_cages(
     puzzle: &Puzzle,
     rules: Ruleset,
     tier: DeductionTier,
     limit: u32,
-    first: &mut Option<Solution>,
-    stats: &mut SolveStats,
+    ignored_cages: &[usize],
 ) -> Result<u32, SolveError> {
-    puzzle.validate(rules)?;
+    validate_for_search(puzzle, rules)?;
+    let free_cages = cage_index_flags(puzzle, ignored_cages)?;
+    if limit == 0 {
+        return Ok(0);
+    }
 
-    let n = puzzle.n as usize;
-    let a = n * n;
+    let mut stats = SolveStats::default();
+    with_mask!(puzzle.n, M => {
+        let mut state = State::<M>::for_puzzle(puzzle, rules).with_free_cages(puzzle, free_cages);
+        search_from_state(
+            puzzle,
+            rules,
+            tier,
+            limit,
+            &mut None,
+            &mut stats,
+            &mut state,
+            &mut NoopObserver,
+        )
+    })
+}
 
-    let mut cage_of_cell = vec![usize::MAX; a];
-    for (cage_idx, cage) in puzzle.cages.iter().enumerate() {
-        for cell in &cage.cells {
-            cage_of_cell[cell.0 as usize] = cage_idx;
-        }
-    }
+/// Find the lexicographically smallest solution while ignoring the arithmetic of the cages at
+/// the indices in `ignored_cages`, as in [`count_solutions_up_to_ignoring_cages`].
+pub fn solve_one_ignoring_cages(
+    puzzle: &Puzzle,
+    rules: Ruleset,
+    tier: DeductionTier,
+    ignored_cages: &[usize],
+) -> Result<Option<Solution>, SolveError> {
+    validate_for_search(puzzle, rules)?;
+    let free_cages = cage_index_flags(puzzle, ignored_cages)?;
 
-    let mut state = State {
-        n: puzzle.n,
-        grid: vec![0; a],
-        row_mask: vec![0u64; n],
-        col_mask: vec![0u64; n],
-        cage_of_cell,
-        tuple_cache: HashMap::new(),
-        mrv_cache: MrvCache::new(puzzle.n),
-        #[cfg(feature = "nogood-learning")]
-        nogood_cache: Some(crate::nogood::NogoodCache::new(10000)),
-    };
+    let mut first = None;
+    let mut stats = SolveStats::default();
+    let count = with_mask!(puzzle.n, M => {
+        let mut state = State::<M>::for_puzzle(puzzle, rules)
+            .with_free_cages(puzzle, free_cages)
+            .with_lex_min(true);
+        search_from_state(
+            puzzle,
+            rules,
+            tier,
+            1,
+            &mut first,
+            &mut stats,
+            &mut state,
+            &mut NoopObserver,
+        )
+    })?;
+    Ok(if count == 0 { None } else { first })
+}
 
-    let mut forced = Vec::new();
-    if tier != DeductionTier::None && !propagate(puzzle, rules, tier, &mut state, &mut forced)? {
+/// Count solutions up to `limit` with the ops of the cages at the indices in `hidden_cages`
+/// hidden: such a cage keeps its cells and target, and is met if any op it could carry
+/// reaches the target (see `hidden_op_candidates`). Indices past the end of `puzzle.cages`
+/// are rejected with `CageIndexOutOfRange`.
+pub(crate) fn count_solutions_up_to_hiding_ops(
+    puzzle: &Puzzle,
+    rules: Ruleset,
+    tier: DeductionTier,
+    limit: u32,
+    hidden_cages: &[usize],
+) -> Result<u32, SolveError> {
+    validate_for_search(puzzle, rules)?;
+    let hidden_ops = cage_index_flags(puzzle, hidden_cages)?;
+    if limit == 0 {
         return Ok(0);
     }
 
-    // Tier 2.2: After propagation, dirty cells are marked. Cache validity is preserved
-    // (choose_mrv_cell will check if cached cell is dirty and rescan if needed)
+    let mut stats = SolveStats::default();
+    with_mask!(puzzle.n, M => {
+        let mut state = State::<M>::for_puzzle(puzzle, rules).with_hidden_ops(hidden_ops);
+        search_from_state(
+            puzzle,
+            rules,
+            tier,
+            limit,
+            &mut None,
+            &mut stats,
+            &mut state,
+            &mut NoopObserver,
+        )
+    })
+}
 
-    let mut count = 0u32;
-    backtrack_deducing(
-        puzzle, rules, tier, limit, first, &mut state, &mut count, 0, stats,
-    )?;
-    Ok(count)
+/// Per cage of `puzzle`: whether its index is in `indices`.
+fn cage_index_flags(puzzle: &Puzzle, indices: &[usize]) -> Result<Vec<bool>, SolveError> {
+    let mut flags = vec![false; puzzle.cages.len()];
+    for &idx in indices {
+        let slot = flags.get_mut(idx).ok_or(CoreError::CageIndexOutOfRange {
+            index: idx,
+            len: puzzle.cages.len(),
+        })?;
+        *slot = true;
+    }
+    Ok(flags)
 }
 
-use std::collections::HashMap;
+/// Whether `puzzle` has no solution with the cages flagged in `free_cages` ignored, or `None`
+/// if `node_budget` search nodes were not enough to tell. Callers run `validate_for_search`
+/// (or a public entry point that does) first.
+pub(crate) fn unsat_ignoring_cages_within(
+    puzzle: &Puzzle,
+    rules: Ruleset,
+    tier: DeductionTier,
+    free_cages: Vec<bool>,
+    node_budget: u64,
+) -> Result<Option<bool>, SolveError> {
+    with_mask!(puzzle.n, M => {
+        let mut state = explicit_root_state::<M>(puzzle, rules).with_free_cages(puzzle, free_cages);
+        let feasible = tier == DeductionTier::None
+            || propagate(puzzle, rules, tier, &mut state, &mut Vec::new())?;
+        let mut search = ExplicitSearch::from_state(puzzle, rules, tier, state, feasible);
+        Ok(match search.next_solution(&mut SolveStats::default(), Some(node_budget))? {
+            SearchStep::Solution => Some(false),
+            SearchStep::Exhausted => Some(true),
+            SearchStep::OutOfBudget => None,
+        })
+    })
+}
 
-/// Cache key for memoizing enumerate_cage_tuples results.
-/// Key: (op_hash, target, cells_count, cells_hash, domain_state_hash)
-#[allow(dead_code)]
-type CacheTupleKey = (u8, u8, i32, usize, u64, u64);
+/// A solution of `puzzle` with `excluded = (cell, value)` struck from that cell's candidates,
+/// `Some(None)` if there is none, or `None` once `stats.nodes_visited` reaches `stop_at`.
+/// Nodes accumulate in `stats` across calls, so one `stop_at` bounds a series of them.
+/// Callers run `validate_for_search` first.
+pub(crate) fn solve_excluding_within(
+    puzzle: &Puzzle,
+    rules: Ruleset,
+    tier: DeductionTier,
+    excluded: Option<(usize, u8)>,
+    stats: &mut SolveStats,
+    stop_at: Option<u64>,
+) -> Result<Option<Option<Vec<u8>>>, SolveError> {
+    with_mask!(puzzle.n, M => {
+        let mut state = explicit_root_state::<M>(puzzle, rules);
+        if let Some((cell, value)) = excluded {
+            state.masks[2 * puzzle.n as usize + cell] &= !M::bit(value);
+        }
+        let feasible = tier == DeductionTier::None
+            || propagate(puzzle, rules, tier, &mut state, &mut Vec::new())?;
+        let mut search = ExplicitSearch::from_state(puzzle, rules, tier, state, feasible);
+        Ok(match search.next_solution(stats, stop_at)? {
+            SearchStep::Solution => Some(Some(search.state.grid.clone())),
+            SearchStep::Exhausted => Some(None),
+            SearchStep::OutOfBudget => None,
+        })
+    })
+}
 
-/// Cached result from enumerate_cage_tuples.
-#[derive(Clone)]
-#[allow(dead_code)]
-struct CachedTupleResult {
-    per_pos: Vec<u64>,
-    any_mask: u64,
+/// Entry check shared by every search: the grid must fit the solver's domain width for this
+/// build (see [`crate::validate_grid_size`]) before the puzzle itself is validated, so an
+/// oversized grid fails with `GridSizeTooLarge` instead of searching on truncated masks.
+pub(crate) fn validate_for_search(puzzle: &Puzzle, rules: Ruleset) -> Result<(), SolveError> {
+    crate::validate_grid_size(puzzle.n)?;
+    puzzle.validate(rules)?;
+    Ok(())
 }
 
-struct State {
-    n: u8,
-    grid: Vec<u8>,
-    row_mask: Vec<u64>, // Extended to u64 to support n <= 63
-    col_mask: Vec<u64>, // Extended to u64 to support n <= 63
-    cage_of_cell: Vec<usize>,
-    /// Memoization cache for enumerate_cage_tuples results.
-    /// Maps (cage_signature, domain_hash) -> (per_pos, any_mask).
-    /// Only used for n >= 4; cache skipped for tiny puzzles (n <= 3).
-    #[allow(dead_code)]
-    tuple_cache: HashMap<CacheTupleKey, CachedTupleResult>,
-    /// Incremental MRV cache for Tier 2.2 optimization.
-    /// Tracks minimum-remaining-value cell and invalidates selectively.
-    #[allow(dead_code)]
+/// One decision on the solver's explicit search stack, as stored in a [`SearchCheckpoint`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CheckpointFrame {
+    /// Row-major index of the cell branched on at this depth.
+    pub cell: u16,
+    /// Value currently placed in `cell`.
+    pub value: u8,
+    /// Candidates not yet tried at this depth (bit `v` set = value `v`).
+    pub remaining: u64,
+}
+
+/// Snapshot of an in-progress [`count_solutions_resumable`] run.
+///
+/// Only meaningful for the puzzle, rules and tier it was produced with.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SearchCheckpoint {
+    /// Decision stack from the root, outermost frame first.
+    pub frames: Vec<CheckpointFrame>,
+    /// Solutions counted so far.
+    pub count: u32,
+    /// Accumulated search statistics so far.
+    pub stats: SolveStats,
+}
+
+/// Result of one [`count_solutions_resumable`] segment.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ResumableOutcome {
+    /// The count finished (search exhausted or `limit` reached).
+    Complete { count: u32, stats: SolveStats },
+    /// The node budget ran out; pass the checkpoint back in to continue.
+    Suspended(SearchCheckpoint),
+}
+
+/// Count solutions up to `limit`, suspending after `checkpoint_every` search nodes.
+///
+/// Pass `None` to start a fresh count, or a checkpoint returned by a previous call to resume
+/// it. `checkpoint_every == 0` disables suspension. Uses an explicit-stack search with
+/// ascending value order, so totals match `count_solutions_up_to_with_deductions` while node
+/// statistics may differ.
+pub fn count_solutions_resumable(
+    puzzle: &Puzzle,
+    rules: Ruleset,
+    tier: DeductionTier,
+    limit: u32,
+    checkpoint: Option<SearchCheckpoint>,
+    checkpoint_every: u64,
+) -> Result<ResumableOutcome, SolveError> {
+    validate_for_search(puzzle, rules)?;
+    with_mask!(puzzle.n, M => {
+        let search = match &checkpoint {
+            Some(cp) => ExplicitSearch::<M>::resume(puzzle, rules, tier, &cp.frames)?,
+            None => ExplicitSearch::<M>::new(puzzle, rules, tier)?,
+        };
+        run_resumable(search, checkpoint, limit, checkpoint_every)
+    })
+}
+
+fn run_resumable<M: Mask>(
+    mut search: ExplicitSearch<'_, M>,
+    checkpoint: Option<SearchCheckpoint>,
+    limit: u32,
+    checkpoint_every: u64,
+) -> Result<ResumableOutcome, SolveError> {
+    let (mut count, mut stats) =
+        checkpoint.map_or((0, SolveStats::default()), |cp| (cp.count, cp.stats));
+    let stop_at =
+        (checkpoint_every > 0).then(|| stats.nodes_visited.saturating_add(checkpoint_every));
+    while count < limit {
+        match search.next_solution(&mut stats, stop_at)? {
+            SearchStep::Solution => count += 1,
+            SearchStep::Exhausted => break,
+            SearchStep::OutOfBudget => {
+                return Ok(ResumableOutcome::Suspended(SearchCheckpoint {
+                    frames: search.frames(),
+                    count,
+                    stats,
+                }));
+            }
+        }
+    }
+    Ok(ResumableOutcome::Complete { count, stats })
+}
+
+/// Up to `limit` solution grids (row-major), in the order the explicit-stack search of
+/// [`count_solutions_resumable`] finds them.
+pub(crate) fn solutions_up_to(
+    puzzle: &Puzzle,
+    rules: Ruleset,
+    tier: DeductionTier,
+    limit: u32,
+) -> Result<Vec<Vec<u8>>, SolveError> {
+    validate_for_search(puzzle, rules)?;
+    let mut grids = Vec::new();
+    with_mask!(puzzle.n, M => {
+        let mut search = ExplicitSearch::<M>::new(puzzle, rules, tier)?;
+        let mut stats = SolveStats::default();
+        while grids.len() < limit as usize {
+            match search.next_solution(&mut stats, None)? {
+                SearchStep::Solution => grids.push(search.state.grid.clone()),
+                SearchStep::Exhausted | SearchStep::OutOfBudget => break,
+            }
+        }
+    });
+    Ok(grids)
+}
+
+/// Count solutions up to `limit` on rayon's thread pool.
+///
+/// Propagates at the root, branches on the first MRV cell and searches the subtree under each
+/// of its candidates as a separate rayon task on a clone of the root state. Tasks share one
+/// atomic count and stop once it reaches `limit`; each may add at most one solution past that
+/// point, and the result is clamped to `limit`. The total matches
+/// `count_solutions_up_to_with_deductions`; the order in which solutions are found does not.
+#[cfg(feature = "parallel-rayon")]
+pub fn count_solutions_parallel(
+    puzzle: &Puzzle,
+    rules: Ruleset,
+    tier: DeductionTier,
+    limit: u32,
+) -> Result<u32, SolveError> {
+    validate_for_search(puzzle, rules)?;
+    if limit == 0 {
+        return Ok(0);
+    }
+    with_mask!(puzzle.n, M => count_parallel::<M>(puzzle, rules, tier, limit))
+}
+
+#[cfg(feature = "parallel-rayon")]
+fn count_parallel<M: Mask>(
+    puzzle: &Puzzle,
+    rules: Ruleset,
+    tier: DeductionTier,
+    limit: u32,
+) -> Result<u32, SolveError> {
+    use rayon::prelude::*;
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    const POLL_EVERY_NODES: u64 = 256;
+
+    let mut root = explicit_root_state::<M>(puzzle, rules);
+    if tier != DeductionTier::None && !propagate(puzzle, rules, tier, &mut root, &mut Vec::new())? {
+        return Ok(0);
+    }
+    let Some((cell, domain)) = choose_mrv_cell(&mut root) else {
+        // `choose_mrv_cell` also reports `None` for an empty domain.
+        return Ok(u32::from(!root.grid.contains(&0)));
+    };
+    let candidates: Vec<u8> = domain_iter(domain).filter(|&d| d != 0).collect();
+
+    let n = root.n as usize;
+    let found = AtomicU32::new(0);
+    candidates
+        .into_par_iter()
+        .try_for_each(|d| -> Result<(), SolveError> {
+            if found.load(Ordering::Relaxed) >= limit {
+                return Ok(());
+            }
+            let mut state = root.clone();
+            place(&mut state, cell / n, cell % n, d);
+            let feasible = cages_still_feasible(puzzle, rules, &state, cell)?
+                && (tier == DeductionTier::None
+                    || propagate(puzzle, rules, tier, &mut state, &mut Vec::new())?);
+            let mut search = ExplicitSearch::from_state(puzzle, rules, tier, state, feasible);
+            let mut stats = SolveStats::default();
+            loop {
+                // Check the shared count between solutions too, so a long barren subtree does not
+                // outlive a limit the other tasks already reached.
+                let stop_at = stats.nodes_visited + POLL_EVERY_NODES;
+                match search.next_solution(&mut stats, Some(stop_at))? {
+                    SearchStep::Solution => {
+                        if found.fetch_add(1, Ordering::Relaxed) + 1 >= limit {
+                            return Ok(());
+                        }
+                    }
+                    SearchStep::OutOfBudget => {
+                        if found.load(Ordering::Relaxed) >= limit {
+                            return Ok(());
+                        }
+                    }
+                    SearchStep::Exhausted => return Ok(()),
+                }
+            }
+        })?;
+    Ok(found.into_inner().min(limit))
+}
+
+/// Uniqueness checks across single-clue edits of one puzzle.
+///
+/// Puzzle editors change one clue at a time and re-check after each change. The checker keeps
+/// the per-cage tuple enumerations from earlier counts, keyed by cage shape, clue and the
+/// domains they were enumerated under, so a recount only re-enumerates the edited cage and
+/// the states the changed clue leads to. Those enumerations are only cached for n >= 6 and
+/// below `DeductionTier::Hard`; smaller grids and Hard still count correctly, from scratch.
+/// Nogoods learned by one count are not carried to the next, since loosening a clue can
+/// invalidate them.
+pub struct IncrementalChecker {
+    puzzle: Puzzle,
+    rules: Ruleset,
+    tier: DeductionTier,
+    tuples: TupleCaches,
+    last_count: Option<u32>,
+}
+
+/// Solution-count cap for every [`IncrementalChecker`] count.
+const INCREMENTAL_SOLUTION_CAP: u32 = 2;
+
+/// Tuple cache at the mask width `with_mask!` picks for the checker's grid.
+enum TupleCaches {
+    Narrow(HashMap<CacheTupleKey, CachedTupleResult<u32>>),
+    Wide(HashMap<CacheTupleKey, CachedTupleResult<u64>>),
+}
+
+impl IncrementalChecker {
+    /// Checker for a copy of `puzzle`; nothing is counted until [`Self::count`] or
+    /// [`Self::update_cage`].
+    pub fn new(puzzle: &Puzzle, rules: Ruleset, tier: DeductionTier) -> Result<Self, SolveError> {
+        validate_for_search(puzzle, rules)?;
+        let tuples = if puzzle.n <= <u32 as Mask>::MAX_N {
+            TupleCaches::Narrow(HashMap::new())
+        } else {
+            TupleCaches::Wide(HashMap::new())
+        };
+        Ok(Self {
+            puzzle: puzzle.clone(),
+            rules,
+            tier,
+            tuples,
+            last_count: None,
+        })
+    }
+
+    /// The puzzle with every edit so far applied.
+    pub fn puzzle(&self) -> &Puzzle {
+        &self.puzzle
+    }
+
+    /// Solutions of the current puzzle, capped at 2. Repeated calls reuse the last count.
+    pub fn count(&mut self) -> Result<u32, SolveError> {
+        if let Some(count) = self.last_count {
+            return Ok(count);
+        }
+        let count = match &mut self.tuples {
+            TupleCaches::Narrow(cache) => {
+                count_with_tuple_cache::<u32>(&self.puzzle, self.rules, self.tier, cache)?
+            }
+            TupleCaches::Wide(cache) => {
+                count_with_tuple_cache::<u64>(&self.puzzle, self.rules, self.tier, cache)?
+            }
+        };
+        self.last_count = Some(count);
+        Ok(count)
+    }
+
+    /// Replace cage `cage_idx`'s clue and recount solutions, capped at 2.
+    ///
+    /// Only the edited cage's cached tuples are dropped. An edit the ruleset rejects (e.g. a
+    /// three-cell subtraction under `sub_div_two_cell_only`) returns the validation error and
+    /// leaves the puzzle unchanged.
+    pub fn update_cage(
+        &mut self,
+        cage_idx: usize,
+        new_op: Op,
+        new_target: i64,
+    ) -> Result<u32, SolveError> {
+        let len = self.puzzle.cages.len();
+        let cage = self
+            .puzzle
+            .cages
+            .get_mut(cage_idx)
+            .ok_or(CoreError::CageIndexOutOfRange {
+                index: cage_idx,
+                len,
+            })?;
+        if (cage.op, cage.target) == (new_op, new_target) {
+            return self.count();
+        }
+        let old = (cage.op, cage.target);
+        (cage.op, cage.target) = (new_op, new_target);
+        if let Err(e) = self.puzzle.validate(self.rules) {
+            let cage = &mut self.puzzle.cages[cage_idx];
+            (cage.op, cage.target) = old;
+            return Err(e.into());
+        }
+
+        let cells: Vec<usize> = self.puzzle.cages[cage_idx]
+            .cells
+            .iter()
+            .map(|c| c.0 as usize)
+            .collect();
+        let shape = (cells.len(), cage_cells_hash(&cells));
+        match &mut self.tuples {
+            TupleCaches::Narrow(cache) => cache.retain(|key, _| (key.3, key.4) != shape),
+            TupleCaches::Wide(cache) => cache.retain(|key, _| (key.3, key.4) != shape),
+        }
+        self.last_count = None;
+        self.count()
+    }
+}
+
+fn count_with_tuple_cache<M: Mask>(
+    puzzle: &Puzzle,
+    rules: Ruleset,
+    tier: DeductionTier,
+    cache: &mut HashMap<CacheTupleKey, CachedTupleResult<M>>,
+) -> Result<u32, SolveError> {
+    let mut state = State::<M>::for_puzzle(puzzle, rules);
+    state.tuple_cache = std::mem::take(cache);
+    let mut stats = SolveStats::default();
+    let result = search_from_state(
+        puzzle,
+        rules,
+        tier,
+        INCREMENTAL_SOLUTION_CAP,
+        &mut None,
+        &mut stats,
+        &mut state,
+        &mut NoopObserver,
+    );
+    *cache = std::mem::take(&mut state.tuple_cache);
+    result
+}
+
+/// Run root deduction propagation to a fixpoint without searching.
+///
+/// Returns the row-major grid of forced placements (0 = undetermined), or `None` if
+/// propagation finds a contradiction. `DeductionTier::None` returns the empty grid.
+pub fn propagate_to_fixpoint(
+    puzzle: &Puzzle,
+    rules: Ruleset,
+    tier: DeductionTier,
+) -> Result<Option<Vec<u8>>, SolveError> {
+    validate_for_search(puzzle, rules)?;
+
+    with_mask!(puzzle.n, M => {
+        let mut state = State::<M>::for_puzzle(puzzle, rules);
+        let mut forced = Vec::new();
+        if tier != DeductionTier::None && !propagate(puzzle, rules, tier, &mut state, &mut forced)? {
+            return Ok(None);
+        }
+        Ok(Some(state.grid))
+    })
+}
+
+/// Per-cell digit support for `cage` given candidate `domains` (bit `v` set = digit `v`).
+///
+/// Enumerates every tuple satisfying the cage arithmetic and in-cage row/column
+/// distinctness. Returns one mask per cage cell (in `cage.cells` order) and their union.
+/// `domains` is indexed by row-major cell index of an `n`×`n` grid.
+pub fn cage_tuple_supports(n: u8, cage: &Cage, domains: &[u64]) -> (Vec<u64>, u64) {
+    let n = n as usize;
+    let cells: Vec<usize> = cage.cells.iter().map(|c| c.0 as usize).collect();
+    let coords: Vec<(usize, usize)> = cage
+        .coords(n as u8)
+        .map(|c| (c.row as usize, c.col as usize))
+        .collect();
+    let mut per_pos = vec![0u64; cells.len()];
+    let mut any_mask = 0u64;
+    let mut budget = usize::MAX;
+
+    #[cfg(not(feature = "alloc-bumpalo"))]
+    {
+        let mut chosen = Vec::with_capacity(cells.len());
+        enumerate_cage_tuples(
+            cage,
+            &cells,
+            &coords,
+            domains,
+            0,
+            &mut chosen,
+            &mut per_pos,
+            &mut any_mask,
+            &mut 0,
+            &mut budget,
+        );
+    }
+    #[cfg(feature = "alloc-bumpalo")]
+    {
+        let bump = Bump::new();
+        let mut chosen = bumpalo::collections::Vec::with_capacity_in(cells.len(), &bump);
+        enumerate_cage_tuples_bump(
+            cage,
+            &cells,
+            &coords,
+            domains,
+            0,
+            &mut chosen,
+            &mut per_pos,
+            &mut any_mask,
+            &mut 0,
+            &mut budget,
+        );
+    }
+
+    (per_pos, any_mask)
+}
+
+/// The [`crate::small_n`] table's answer to a count (`solving == false`) or first-solution
+/// search for up to `limit` solutions, when `options` route it there (see [`SmallNTables`]).
+fn small_n_lookup(
+    puzzle: &Puzzle,
+    rules: Ruleset,
+    limit: u32,
+    solving: bool,
+    options: &SearchOptions,
+) -> Result<Option<(u32, Option<Solution>)>, SolveError> {
+    let routed = match options.small_n_tables {
+        SmallNTables::Never => false,
+        SmallNTables::Counting => !solving,
+        SmallNTables::Always => true,
+    };
+    // The tables hold full Latin squares only.
+    if !routed || puzzle.n > SMALL_N_MAX || !rules.enforce_columns {
+        return Ok(None);
+    }
+    validate_for_search(puzzle, rules)?;
+    // The table's first match is the lexicographic minimum, so LexMin needs no second one.
+    let any_first = solving && options.solve_mode == SolveMode::LexMin;
+    let probe = if any_first { limit } else { limit.max(2) };
+    let Some((count, first)) = table_search(puzzle, probe) else {
+        return Ok(None);
+    };
+    if solving && count > 1 && !any_first {
+        return Ok(None);
+    }
+    let first = first.map(|grid| Solution {
+        n: puzzle.n,
+        grid: grid.to_vec(),
+    });
+    Ok(Some((count.min(limit), first)))
+}
+
+fn search(
+    puzzle: &Puzzle,
+    rules: Ruleset,
+    limit: u32,
+    first: &mut Option<Solution>,
+) -> Result<u32, SolveError> {
+    let mut stats = SolveStats::default();
+    search_with_stats(puzzle, rules, limit, first, &mut stats, false)
+}
+
+/// The non-deducing search; `lex_min` branches as [`SolveMode::LexMin`] does.
+fn search_with_stats(
+    puzzle: &Puzzle,
+    rules: Ruleset,
+    limit: u32,
+    first: &mut Option<Solution>,
+    stats: &mut SolveStats,
+    lex_min: bool,
+) -> Result<u32, SolveError> {
+    validate_for_search(puzzle, rules)?;
+
+    let mut count = 0u32;
+    with_mask!(puzzle.n, M => {
+        let mut state = State::<M>::for_puzzle(puzzle, rules).with_lex_min(lex_min);
+        backtrack(
+            puzzle, rules, limit, first, &mut state, &mut count, 0, stats,
+        )
+    })?;
+    Ok(count)
+}
+
+fn search_with_stats_deducing(
+    puzzle: &Puzzle,
+    rules: Ruleset,
+    tier: DeductionTier,
+    limit: u32,
+    first: &mut Option<Solution>,
+    stats: &mut SolveStats,
+) -> Result<u32, SolveError> {
+    search_with_options(
+        puzzle,
+        rules,
+        tier,
+        limit,
+        first,
+        stats,
+        SearchOptions::default(),
+        &mut NoopObserver,
+    )
+}
+
+#[allow(clippy::too_many_arguments)]
+fn search_with_options(
+    puzzle: &Puzzle,
+    rules: Ruleset,
+    tier: DeductionTier,
+    limit: u32,
+    first: &mut Option<Solution>,
+    stats: &mut SolveStats,
+    options: SearchOptions,
+    observer: &mut dyn SearchObserver,
+) -> Result<u32, SolveError> {
+    validate_for_search(puzzle, rules)?;
+    with_backend_mask!(puzzle.n, options.backend, M => {
+        let mut state = State::<M>::with_options(puzzle, rules, options);
+        search_from_state(puzzle, rules, tier, limit, first, stats, &mut state, observer)
+    })
+}
+
+/// Root propagation plus `backtrack_deducing` on a prepared, already-validated `state`.
+#[allow(clippy::too_many_arguments)]
+fn search_from_state<M: Mask>(
+    puzzle: &Puzzle,
+    rules: Ruleset,
+    tier: DeductionTier,
+    limit: u32,
+    first: &mut Option<Solution>,
+    stats: &mut SolveStats,
+    state: &mut State<M>,
+    observer: &mut dyn SearchObserver,
+) -> Result<u32, SolveError> {
+    let mut forced = Vec::new();
+    let mut count = 0u32;
+    if tier == DeductionTier::None || propagate(puzzle, rules, tier, state, &mut forced)? {
+        let split = match state.options.split_components {
+            ComponentSplit::Never => false,
+            ComponentSplit::Counting => limit > 1,
+            ComponentSplit::Always => true,
+        };
+        // A warm start skips one known grid, which is not a per-component condition.
+        let components = if split && state.warm_grid.is_empty() {
+            unfilled_components(puzzle, state)
+        } else {
+            Vec::new()
+        };
+        if components.len() > 1 {
+            count = search_components(
+                puzzle,
+                rules,
+                tier,
+                limit,
+                first,
+                stats,
+                state,
+                observer,
+                &components,
+            )?;
+        } else {
+            // Tier 2.2: After propagation, dirty cells are marked. Cache validity is preserved
+            // (choose_mrv_cell will check if cached cell is dirty and rescan if needed)
+            backtrack_deducing(
+                puzzle, rules, tier, limit, first, state, &mut count, 0, stats, observer,
+            )?;
+        }
+    }
+    stats.degraded_cages += state.degraded_cages;
+    stats.propagation_rounds += state.propagation_rounds;
+    Ok(count)
+}
+
+/// The unfilled cells of `state`, grouped into components joined by shared rows, columns and
+/// cages, smallest first.
+///
+/// Placements in one component change no domain or cage of another, so each can be searched
+/// alone and the solution counts multiplied.
+fn unfilled_components<M: Mask>(puzzle: &Puzzle, state: &State<M>) -> Vec<Vec<usize>> {
+    let n = state.n as usize;
+    let mut parent: Vec<usize> = (0..n * n).collect();
+    fn find(parent: &mut [usize], mut x: usize) -> usize {
+        while parent[x] != x {
+            parent[x] = parent[parent[x]];
+            x = parent[x];
+        }
+        x
+    }
+    let mut join = |cells: &mut dyn Iterator<Item = usize>| {
+        let Some(first) = cells.next() else {
+            return;
+        };
+        for cell in cells {
+            let (a, b) = (find(&mut parent, first), find(&mut parent, cell));
+            parent[a] = b;
+        }
+    };
+    let unfilled = |idx: &usize| state.grid[*idx] == 0;
+    for line in 0..n {
+        join(&mut (0..n).map(|k| line * n + k).filter(unfilled));
+        join(&mut (0..n).map(|k| k * n + line).filter(unfilled));
+    }
+    for cage in &puzzle.cages {
+        join(&mut cage.cells.iter().map(|c| c.0 as usize).filter(unfilled));
+    }
+
+    let mut by_root: HashMap<usize, Vec<usize>> = HashMap::new();
+    for idx in (0..n * n).filter(unfilled) {
+        by_root.entry(find(&mut parent, idx)).or_default().push(idx);
+    }
+    let mut components: Vec<Vec<usize>> = by_root.into_values().collect();
+    components.sort_by_key(|cells| (cells.len(), cells[0]));
+    components
+}
+
+/// Search each of `components` alone (see [`ComponentSplit`]), smallest first, stopping at the
+/// first with no solution. The count is the product of the component counts, capped at `limit`.
+#[allow(clippy::too_many_arguments)]
+fn search_components<M: Mask>(
+    puzzle: &Puzzle,
+    rules: Ruleset,
+    tier: DeductionTier,
+    limit: u32,
+    first: &mut Option<Solution>,
+    stats: &mut SolveStats,
+    state: &mut State<M>,
+    observer: &mut dyn SearchObserver,
+    components: &[Vec<usize>],
+) -> Result<u32, SolveError> {
+    let mut total = 1u32;
+    let mut spliced = state.grid.clone();
+    let mut inner = ComponentObserver(observer);
+    for cells in components {
+        state.scope = vec![false; state.grid.len()];
+        for &idx in cells {
+            state.scope[idx] = true;
+        }
+        state.mrv_cache.valid = false;
+        let mut part_first = None;
+        let mut part = 0u32;
+        let searched = backtrack_deducing(
+            puzzle,
+            rules,
+            tier,
+            limit,
+            &mut part_first,
+            state,
+            &mut part,
+            0,
+            stats,
+            &mut inner,
+        );
+        state.scope = Vec::new();
+        searched?;
+        if part == 0 {
+            return Ok(0);
+        }
+        total = total.saturating_mul(part).min(limit);
+        if let Some(part_first) = part_first {
+            for &idx in cells {
+                spliced[idx] = part_first.grid[idx];
+            }
+        }
+    }
+    for found in 1..=total {
+        observer.on_solution(found);
+    }
+    if first.is_none() {
+        *first = Some(Solution {
+            n: state.n,
+            grid: spliced,
+        });
+    }
+    Ok(total)
+}
+
+/// Forwards a component search's hooks, except `on_solution`: a component's solutions are not
+/// the puzzle's, so [`search_components`] reports those itself.
+struct ComponentObserver<'a>(&'a mut dyn SearchObserver);
+
+impl SearchObserver for ComponentObserver<'_> {
+    fn on_node(&mut self, depth: u32, nodes: u64) {
+        self.0.on_node(depth, nodes);
+    }
+
+    fn on_branch(&mut self, depth: u32, cell: usize, value: u8, nodes: u64) {
+        self.0.on_branch(depth, cell, value, nodes);
+    }
+
+    fn on_pruned(&mut self, depth: u32) {
+        self.0.on_pruned(depth);
+    }
+
+    fn on_backtrack(&mut self, depth: u32) {
+        self.0.on_backtrack(depth);
+    }
+}
+
+/// Outcome of advancing an [`ExplicitSearch`].
+enum SearchStep {
+    Solution,
+    Exhausted,
+    OutOfBudget,
+}
+
+struct SearchFrame<M> {
+    cell: usize,
+    value: u8,
+    remaining: M,
+    /// Cells placed by propagation after `value`, unwound before the next candidate.
+    forced: Vec<(usize, u8)>,
+}
+
+/// Fresh state for an [`ExplicitSearch`] root, before propagation.
+fn explicit_root_state<M: Mask>(puzzle: &Puzzle, rules: Ruleset) -> State<M> {
+    #[allow(unused_mut)]
+    let mut state = State::for_puzzle(puzzle, rules);
+    // Explicit search replays decisions; nogoods recorded against one path would be stale.
+    #[cfg(feature = "nogood-learning")]
+    {
+        state.nogood_cache = None;
+    }
+    state
+}
+
+/// Iterative counterpart of `backtrack_deducing` whose decision stack can be captured and
+/// replayed (checkpoints).
+struct ExplicitSearch<'a, M> {
+    puzzle: &'a Puzzle,
+    rules: Ruleset,
+    tier: DeductionTier,
+    state: State<M>,
+    stack: Vec<SearchFrame<M>>,
+    /// The state is consistent and the next step is to pick a branching cell.
+    at_node: bool,
+    done: bool,
+}
+
+impl<'a, M: Mask> ExplicitSearch<'a, M> {
+    /// Callers run `validate_for_search` first.
+    fn new(puzzle: &'a Puzzle, rules: Ruleset, tier: DeductionTier) -> Result<Self, SolveError> {
+        let mut state = explicit_root_state(puzzle, rules);
+        let mut root_forced = Vec::new();
+        let feasible = tier == DeductionTier::None
+            || propagate(puzzle, rules, tier, &mut state, &mut root_forced)?;
+        Ok(Self::from_state(puzzle, rules, tier, state, feasible))
+    }
+
+    /// Search below an already-propagated `state`; `feasible == false` yields nothing.
+    fn from_state(
+        puzzle: &'a Puzzle,
+        rules: Ruleset,
+        tier: DeductionTier,
+        state: State<M>,
+        feasible: bool,
+    ) -> Self {
+        Self {
+            puzzle,
+            rules,
+            tier,
+            state,
+            stack: Vec::new(),
+            at_node: feasible,
+            done: !feasible,
+        }
+    }
+
+    fn resume(
+        puzzle: &'a Puzzle,
+        rules: Ruleset,
+        tier: DeductionTier,
+        frames: &[CheckpointFrame],
+    ) -> Result<Self, SolveError> {
+        let mut search = Self::new(puzzle, rules, tier)?;
+        if search.done {
+            return if frames.is_empty() {
+                Ok(search)
+            } else {
+                Err(SolveError::InvalidCheckpoint {
+                    reason: "root is infeasible",
+                })
+            };
+        }
+
+        let n = search.state.n as usize;
+        for frame in frames {
+            let cell = frame.cell as usize;
+            if let Some(reason) = search.state.placement_conflict(cell, frame.value) {
+                return Err(SolveError::InvalidCheckpoint { reason });
+            }
+            if frame.remaining & !full_domain::<M>(search.state.n).to_u64() != 0 {
+                return Err(SolveError::InvalidCheckpoint {
+                    reason: "frame candidates outside 1..=n",
+                });
+            }
+
+            let (r, c) = (cell / n, cell % n);
+            place(&mut search.state, r, c, frame.value);
+            let mut forced = Vec::new();
+            let feasible = cages_still_feasible(puzzle, rules, &search.state, cell)?
+                && (tier == DeductionTier::None
+                    || propagate(puzzle, rules, tier, &mut search.state, &mut forced)?);
+            search.stack.push(SearchFrame {
+                cell,
+                value: frame.value,
+                remaining: M::from_u64(frame.remaining),
+                forced,
+            });
+            if !feasible {
+                return Err(SolveError::InvalidCheckpoint {
+                    reason: "replayed path is infeasible",
+                });
+            }
+        }
+        search.state.mrv_cache.valid = false;
+        // The checkpoint's stats already include degradations on the replayed path.
+        search.state.degraded_cages = 0;
+        search.state.propagation_rounds = 0;
+        Ok(search)
+    }
+
+    fn frames(&self) -> Vec<CheckpointFrame> {
+        self.stack
+            .iter()
+            .map(|f| CheckpointFrame {
+                cell: f.cell as u16,
+                value: f.value,
+                remaining: f.remaining.to_u64(),
+            })
+            .collect()
+    }
+
+    /// Advance to the next solution, or stop once `stats.nodes_visited` reaches `stop_at`.
+    ///
+    /// Suspension only happens at node boundaries, so the stack always describes a
+    /// consistent, feasible partial assignment when `OutOfBudget` is returned.
+    fn next_solution(
+        &mut self,
+        stats: &mut SolveStats,
+        stop_at: Option<u64>,
+    ) -> Result<SearchStep, SolveError> {
+        let step = self.advance(stats, stop_at);
+        stats.degraded_cages += std::mem::take(&mut self.state.degraded_cages);
+        stats.propagation_rounds += std::mem::take(&mut self.state.propagation_rounds);
+        step
+    }
+
+    fn advance(
+        &mut self,
+        stats: &mut SolveStats,
+        stop_at: Option<u64>,
+    ) -> Result<SearchStep, SolveError> {
+        loop {
+            if self.done {
+                return Ok(SearchStep::Exhausted);
+            }
+
+            if self.at_node {
+                if stop_at.is_some_and(|stop| stats.nodes_visited >= stop) {
+                    return Ok(SearchStep::OutOfBudget);
+                }
+                stats.nodes_visited += 1;
+                stats.max_depth = stats.max_depth.max(self.stack.len() as u32);
+                self.at_node = false;
+
+                match choose_mrv_cell(&mut self.state) {
+                    Some((cell, domain)) => self.stack.push(SearchFrame {
+                        cell,
+                        value: 0,
+                        remaining: domain & !M::bit(0),
+                        forced: Vec::new(),
+                    }),
+                    // `choose_mrv_cell` also reports `None` for an empty domain.
+                    None if !self.state.grid.contains(&0) => return Ok(SearchStep::Solution),
+                    None => {}
+                }
+            }
+
+            // Retreat: undo the current candidate at the top frame and try the next one.
+            let Some(top) = self.stack.last_mut() else {
+                self.done = true;
+                return Ok(SearchStep::Exhausted);
+            };
+            let n = self.state.n as usize;
+            if top.value != 0 {
+                for (idx, val) in top.forced.drain(..).rev() {
+                    unplace(&mut self.state, idx / n, idx % n, val);
+                }
+                unplace(&mut self.state, top.cell / n, top.cell % n, top.value);
+                top.value = 0;
+                stats.backtracked = true;
+            }
+            if top.remaining.is_empty() {
+                self.stack.pop();
+                continue;
+            }
+
+            let d = top.remaining.min_digit();
+            top.remaining = top.remaining.clear_lowest();
+            top.value = d;
+            place(&mut self.state, top.cell / n, top.cell % n, d);
+            stats.assignments += 1;
+
+            self.at_node = cages_still_feasible(self.puzzle, self.rules, &self.state, top.cell)?
+                && (self.tier == DeductionTier::None
+                    || propagate(
+                        self.puzzle,
+                        self.rules,
+                        self.tier,
+                        &mut self.state,
+                        &mut top.forced,
+                    )?);
+        }
+    }
+}
+
+use std::collections::HashMap;
+
+/// Cache key for memoizing enumerate_cage_tuples results.
+/// Key: (op_hash, target, cells_count, cells_hash, domain_state_hash)
+#[allow(dead_code)]
+type CacheTupleKey = (u8, u8, i64, usize, u64, u64);
+
+/// Cached result from enumerate_cage_tuples.
+#[derive(Clone)]
+#[allow(dead_code)]
+struct CachedTupleResult<M> {
+    per_pos: Vec<M>,
+    any_mask: M,
+    /// Tuples the enumeration found.
+    tuples: u32,
+}
+
+/// Search state over `M`-wide candidate masks (`u32` for n <= 31, see [`with_mask`]).
+#[derive(Clone)]
+pub(crate) struct State<M> {
+    n: u8,
+    grid: Vec<u8>,
+    /// Row masks, then column masks, then `static_mask`, in one allocation so a cell's
+    /// domain is read from a single buffer: `[rows: n | cols: n | static: n*n]`.
+    masks: Vec<M>,
+    /// `Ruleset::enforce_columns`. When off, `place` leaves the column masks empty, so every
+    /// reader of them (domains, conflicts, the SIMD batch) sees no column constraint.
+    enforce_columns: bool,
+    /// Per cell: index into `puzzle.cages`, `NO_CAGE` if uncovered.
+    cage_of_cell: Vec<u16>,
+    /// Memoization cache for enumerate_cage_tuples results.
+    /// Maps (cage_signature, domain_hash) -> (per_pos, any_mask).
+    /// Only used for n >= 4; cache skipped for tiny puzzles (n <= 3).
+    #[allow(dead_code)]
+    tuple_cache: HashMap<CacheTupleKey, CachedTupleResult<M>>,
+    /// Incremental MRV cache for Tier 2.2 optimization.
+    /// Tracks minimum-remaining-value cell and invalidates selectively.
+    #[allow(dead_code)]
     mrv_cache: MrvCache,
+    options: SearchOptions,
+    /// Cage deductions degraded by `options.max_tuples_per_cage`, drained into `SolveStats`.
+    degraded_cages: u64,
+    /// Propagation rounds run, drained into `SolveStats`.
+    propagation_rounds: u64,
+    /// Per cage: tuples that survived its last Normal or Hard deduction, [`TUPLES_UNKNOWN`]
+    /// where none counted them. Read by `choose_mrv_cell` under
+    /// [`SearchOptions::mrv_tuple_tiebreak`].
+    cage_tuples: Vec<u32>,
+    /// Per cage: arithmetic ignored (see `count_solutions_up_to_ignoring_cages`). Empty when
+    /// every cage is enforced.
+    free_cages: Vec<bool>,
+    /// Per cage: op hidden (see `count_solutions_up_to_hiding_ops`). Empty when every op is
+    /// known.
+    hidden_ops: Vec<bool>,
+    /// Known solution a warm-started count skips (see `count_solutions_up_to_warm_start`).
+    /// Empty when cold.
+    warm_grid: Vec<u8>,
+    /// Every placement so far agrees with `warm_grid`.
+    on_warm_path: bool,
+    /// Per cell: inside the component being searched (see `search_components`). Empty when
+    /// the whole grid is.
+    scope: Vec<bool>,
+    /// Branch as [`SolveMode::LexMin`]: lowest-index open cell, digits ascending.
+    lex_min: bool,
+    /// Scratch for `choose_mrv_cell`'s batched rescan: every cell's domain and its count.
+    #[cfg(feature = "simd-dispatch")]
+    batch_domains: Vec<M>,
+    #[cfg(feature = "simd-dispatch")]
+    batch_counts: Vec<u32>,
+    /// Rounds recorded by `propagate`, when a caller asked for them.
+    #[cfg(feature = "debug-snapshots")]
+    propagation_log: Option<PropagationLog>,
+    /// Decisions on the path to the current node, kept only while `options.inspector` is set.
+    #[cfg(feature = "debug-api")]
+    decisions: Vec<CheckpointFrame>,
     /// Phase 6.3: Nogood cache for Conflict-Driven Learning.
     /// Records failed partial assignments to prune equivalent search branches.
     #[cfg(feature = "nogood-learning")]
@@ -284,14 +1943,265 @@ struct State {
     nogood_cache: Option<crate::nogood::NogoodCache>,
 }
 
+/// Estimated peak heap bytes of one search on an `n` x `n` grid, tuple enumerations and caches
+/// aside: the search state's grid, masks and per-cell tables, a propagation round's domains,
+/// and for each level of recursion (at most one per cell) the digits left to try, plus the
+/// placements propagation forced on the way down.
+pub fn search_footprint(n: u8) -> usize {
+    use std::mem::size_of;
+    with_mask!(n, M => {
+        let (n, a) = (n as usize, n as usize * n as usize);
+        let mask = size_of::<M>();
+        // Grid, cage index and MRV dirty flag per cell, a tuple count per cage (at most one
+        // per cell); row, column and cell masks.
+        let state = size_of::<State<M>>()
+            + a * (size_of::<u8>() + size_of::<u16>() + size_of::<bool>() + size_of::<u32>())
+            + (2 * n + a) * mask;
+        #[cfg(feature = "simd-dispatch")]
+        let state = state + a * (mask + size_of::<u32>());
+        let recursion = a * n * size_of::<(u8, u32)>() + a * size_of::<(usize, u8)>();
+        state + a * mask + recursion
+    })
+}
+
+#[cfg(feature = "debug-api")]
+impl<M: Mask> crate::debug::InspectSource for State<M> {
+    fn grid(&self) -> &[u8] {
+        &self.grid
+    }
+
+    fn row_mask(&self, r: usize) -> u64 {
+        State::row_mask(self, r).to_u64()
+    }
+
+    fn col_mask(&self, c: usize) -> u64 {
+        State::col_mask(self, c).to_u64()
+    }
+
+    fn decisions(&self) -> &[CheckpointFrame] {
+        &self.decisions
+    }
+
+    fn tuple_cache_len(&self) -> usize {
+        self.tuple_cache.len()
+    }
+}
+
+/// Column `State::cell_lines` reports for every cell when columns are not Latin, so cage
+/// enumeration keeps digits apart along rows only and derives no column must-masks.
+pub(crate) const FREE_COLUMN: usize = usize::MAX;
+
+/// `State::cage_of_cell` entry for a cell no cage covers. `Puzzle::validate` rejects such
+/// puzzles, and `n <= 63` keeps every real cage index below it.
+const NO_CAGE: u16 = u16::MAX;
+
+/// `State::cage_tuples` entry for a cage whose surviving tuples were not counted: an Easy
+/// round, a free or hidden-op cage, a degraded enumeration, or a shortcut past one. Larger
+/// than any count, so the MRV tie-break ranks such cages after every counted one.
+const TUPLES_UNKNOWN: u32 = u32::MAX;
+
+impl<M: Mask> State<M> {
+    pub(crate) fn for_puzzle(puzzle: &Puzzle, rules: Ruleset) -> Self {
+        Self::with_options(puzzle, rules, SearchOptions::default())
+    }
+
+    fn with_options(puzzle: &Puzzle, rules: Ruleset, options: SearchOptions) -> Self {
+        let n = puzzle.n as usize;
+        let a = n * n;
+
+        let mut cage_of_cell = vec![NO_CAGE; a];
+        for (cage_idx, cage) in puzzle.cages.iter().enumerate() {
+            for cell in &cage.cells {
+                cage_of_cell[cell.0 as usize] = cage_idx as u16;
+            }
+        }
+
+        State {
+            n: puzzle.n,
+            grid: vec![0; a],
+            masks: vec![M::ZERO; 2 * n + a],
+            enforce_columns: rules.enforce_columns,
+            cage_of_cell,
+            tuple_cache: HashMap::new(),
+            mrv_cache: MrvCache::new(puzzle.n),
+            options,
+            degraded_cages: 0,
+            propagation_rounds: 0,
+            cage_tuples: vec![TUPLES_UNKNOWN; puzzle.cages.len()],
+            free_cages: Vec::new(),
+            hidden_ops: Vec::new(),
+            warm_grid: Vec::new(),
+            on_warm_path: false,
+            scope: Vec::new(),
+            lex_min: false,
+            #[cfg(feature = "simd-dispatch")]
+            batch_domains: vec![M::ZERO; a],
+            #[cfg(feature = "simd-dispatch")]
+            batch_counts: vec![0; a],
+            #[cfg(feature = "debug-snapshots")]
+            propagation_log: None,
+            #[cfg(feature = "debug-api")]
+            decisions: Vec::new(),
+            #[cfg(feature = "nogood-learning")]
+            nogood_cache: Some(crate::nogood::NogoodCache::new(10000)),
+        }
+        .with_free_cages(puzzle, Vec::new())
+    }
+
+    /// Branch as [`SolveMode::LexMin`] when `lex_min` is set.
+    fn with_lex_min(mut self, lex_min: bool) -> Self {
+        self.lex_min = lex_min;
+        self
+    }
+
+    /// Hide the ops of the cages flagged in `hidden_ops`.
+    fn with_hidden_ops(mut self, hidden_ops: Vec<bool>) -> Self {
+        self.hidden_ops = hidden_ops;
+        self
+    }
+
+    /// Set which cages are free and recompute the static masks to match.
+    fn with_free_cages(mut self, puzzle: &Puzzle, free_cages: Vec<bool>) -> Self {
+        self.free_cages = free_cages;
+        let n = self.n as usize;
+        let full = full_domain(self.n);
+        self.masks[2 * n..].fill(full);
+        for (cage_idx, cage) in puzzle.cages.iter().enumerate() {
+            if cage.op != Op::Eq || cage.cells.len() != 1 || self.cage_is_free(cage_idx) {
+                continue;
+            }
+            // `Puzzle::validate` rejects out-of-range targets; an empty mask keeps an
+            // unvalidated one from shifting out of range.
+            let pin = if (1..=self.n as i64).contains(&cage.target) {
+                M::bit(cage.target as u8)
+            } else {
+                M::ZERO
+            };
+            let idx = cage.cells[0].0 as usize;
+            let (r, c) = (idx / n, idx % n);
+            // The pinned digit can appear nowhere else in its row or column. Two pins that
+            // clash empty each other's mask, which the search reports as a dead end.
+            let statics = &mut self.masks[2 * n..];
+            let own = statics[idx] & pin;
+            for k in 0..n {
+                statics[r * n + k] &= !pin;
+                if self.enforce_columns {
+                    statics[k * n + c] &= !pin;
+                }
+            }
+            statics[idx] = own;
+        }
+        self
+    }
+
+    /// Digits placed in row `r`.
+    #[inline]
+    pub(crate) fn row_mask(&self, r: usize) -> M {
+        self.masks[r]
+    }
+
+    /// Digits placed in column `c`; always empty without `enforce_columns`.
+    #[inline]
+    pub(crate) fn col_mask(&self, c: usize) -> M {
+        self.masks[self.n as usize + c]
+    }
+
+    /// Why digit `d` cannot be placed in cell `idx` now, or `None` if it can: the cell must
+    /// exist and be empty, and `d` must be in its `domain_for_cell` (in `1..=n`, not yet in
+    /// its row or column, not ruled out by an `Eq` pin). The check every externally chosen
+    /// placement passes before `place`.
+    pub(crate) fn placement_conflict(&self, idx: usize, d: u8) -> Option<&'static str> {
+        let n = self.n as usize;
+        let (r, c) = (idx / n, idx % n);
+        if idx >= n * n {
+            Some("cell is out of range")
+        } else if self.grid[idx] != 0 {
+            Some("cell is already filled")
+        } else if d == 0 || d > self.n {
+            Some("digit is outside 1..=n")
+        } else if self.row_mask(r).has(d) {
+            Some("digit is already in the row")
+        } else if self.col_mask(c).has(d) {
+            Some("digit is already in the column")
+        } else if !self.static_mask(idx).has(d) {
+            Some("digit is ruled out by an Eq pin")
+        } else {
+            None
+        }
+    }
+
+    /// Row-major grid, 0 for an empty cell.
+    #[cfg(feature = "debug-api")]
+    pub(crate) fn grid(&self) -> &[u8] {
+        &self.grid
+    }
+
+    /// Candidates of cell `idx`: its digit if placed, `domain_for_cell` otherwise.
+    #[cfg(feature = "debug-api")]
+    pub(crate) fn cell_domain(&self, idx: usize) -> M {
+        let n = self.n as usize;
+        match self.grid[idx] {
+            0 => domain_for_cell(self, idx, idx / n, idx % n),
+            d => M::bit(d),
+        }
+    }
+
+    /// Per cell: digits allowed before any placement. `full_domain`, narrowed by the cell's
+    /// enforced singleton `Eq` cage and by the pins of its row and column. The base of both
+    /// `domain_for_cell` and `propagate`'s seeding, so the two cannot disagree about pins.
+    #[inline]
+    fn static_mask(&self, idx: usize) -> M {
+        self.masks[2 * self.n as usize + idx]
+    }
+
+    /// Row and column of cell `idx` for in-cage distinctness: the column is [`FREE_COLUMN`]
+    /// without `enforce_columns`.
+    #[inline]
+    fn cell_lines(&self, idx: usize) -> (usize, usize) {
+        let n = self.n as usize;
+        let col = if self.enforce_columns {
+            idx % n
+        } else {
+            FREE_COLUMN
+        };
+        (idx / n, col)
+    }
+
+    /// Cell `idx` is open to the current search: unfilled and in `scope`.
+    #[inline]
+    fn is_open(&self, idx: usize) -> bool {
+        self.grid[idx] == 0 && self.scope.get(idx).copied().unwrap_or(true)
+    }
+
+    /// `cage_tuples` of the cage covering cell `idx`.
+    #[inline]
+    fn cell_cage_tuples(&self, idx: usize) -> u32 {
+        let cage_idx = self.cage_of_cell[idx] as usize;
+        self.cage_tuples
+            .get(cage_idx)
+            .copied()
+            .unwrap_or(TUPLES_UNKNOWN)
+    }
+
+    #[inline]
+    fn cage_is_free(&self, cage_idx: usize) -> bool {
+        self.free_cages.get(cage_idx).copied().unwrap_or(false)
+    }
+
+    #[inline]
+    fn cage_op_is_hidden(&self, cage_idx: usize) -> bool {
+        self.hidden_ops.get(cage_idx).copied().unwrap_or(false)
+    }
+}
+
 /// Check if all cells in a cage are fully assigned (domain size == 1).
 /// This enables Tier 1.2 optimization: skip enumeration for fully-assigned cages.
 #[inline]
 #[allow(dead_code)]
-fn all_cells_fully_assigned(cells: &[usize], domains: &[u64]) -> bool {
+fn all_cells_fully_assigned<M: Mask>(cells: &[usize], domains: &[M]) -> bool {
     for &idx in cells {
         // Cell is fully assigned if exactly 1 bit is set (domain.popcount() == 1)
-        let popcount = domains[idx].count_ones();
+        let popcount = domains[idx].count();
         if popcount != 1 {
             return false;
         }
@@ -348,37 +2258,151 @@ impl MrvCache {
 
 /// Compute any_mask (union of valid values) from fully-assigned cage cells.
 /// Used by Tier 1.2 to avoid enumeration when all cells have exactly one value.
+/// Returns 0 when the assigned values do not satisfy the cage.
 #[inline]
 #[allow(dead_code)]
-fn compute_any_mask_from_assigned(cells: &[usize], domains: &[u64]) -> u64 {
-    let mut any_mask = 0u64;
+fn compute_any_mask_from_assigned<M: Mask>(cage: &Cage, cells: &[usize], domains: &[M]) -> M {
+    let values: SmallVec<[u8; INLINE_CAGE_CELLS]> =
+        cells.iter().map(|&idx| domains[idx].min_digit()).collect();
+    if !cage_tuple_satisfies(cage, &values) {
+        return M::ZERO;
+    }
+    let mut any_mask = M::ZERO;
     for &idx in cells {
         any_mask |= domains[idx];
     }
     any_mask
 }
 
+/// Cheap arithmetic pre-filter for `Mul` cages, run before tuple enumeration.
+///
+/// Every value is at least 1, so a candidate `v` survives only if it divides the target
+/// and the remaining factor `target / v` lies between the product of the other cells'
+/// domain minima and the product of their maxima. Divisibility is applied to the whole
+/// cage first so the bounds are computed from the tightened domains.
+#[inline]
+fn prefilter_mul_domains<M: Mask>(cage: &Cage, cells: &[usize], domains: &mut [M]) {
+    if cage.op != Op::Mul || cage.target <= 0 {
+        return;
+    }
+    let target = cage.target;
+
+    let union = cells.iter().fold(M::ZERO, |acc, &idx| acc | domains[idx]);
+    let mut divisors = M::ZERO;
+    for v in domain_iter(union) {
+        if target % v as i64 == 0 {
+            divisors |= M::bit(v);
+        }
+    }
+    for &idx in cells {
+        domains[idx] &= divisors;
+        if domains[idx].is_empty() {
+            return;
+        }
+    }
+
+    for (pos, &idx) in cells.iter().enumerate() {
+        let mut lo = 1i64;
+        let mut hi = 1i64;
+        for (other_pos, &other) in cells.iter().enumerate() {
+            if other_pos == pos {
+                continue;
+            }
+            let dom = domains[other];
+            lo = lo.saturating_mul(dom.min_digit() as i64);
+            hi = hi.saturating_mul(dom.max_digit() as i64);
+        }
+        let mut keep = M::ZERO;
+        for v in domain_iter(domains[idx]) {
+            let rest = target / v as i64;
+            if lo <= rest && rest <= hi {
+                keep |= M::bit(v);
+            }
+        }
+        domains[idx] &= keep;
+        if domains[idx].is_empty() {
+            return;
+        }
+    }
+}
+
+/// Bound-based fallback for a cage whose tuple enumeration ran past
+/// [`SearchOptions::max_tuples_per_cage`].
+///
+/// Keeps a candidate `v` only while the target stays within the min/max sum (or product)
+/// the other cells' domains can still reach, i.e. the `cage_feasible` interval test applied
+/// per candidate. n-ary Sub/Div cages have no such interval. Once every cell is down to one
+/// candidate the cage is checked outright, whatever its op: a degraded cage gets no other
+/// check from propagation, and cells it forces would otherwise reach a solution unchecked.
+fn bounds_filter_domains<M: Mask>(cage: &Cage, cells: &[usize], domains: &mut [M]) {
+    filter_by_bounds(cage, cells, domains);
+    if all_cells_fully_assigned(cells, domains)
+        && compute_any_mask_from_assigned(cage, cells, domains).is_empty()
+    {
+        for &idx in cells {
+            domains[idx] = M::ZERO;
+        }
+    }
+}
+
+fn filter_by_bounds<M: Mask>(cage: &Cage, cells: &[usize], domains: &mut [M]) {
+    match cage.op {
+        Op::Mul => prefilter_mul_domains(cage, cells, domains),
+        Op::Add => {
+            for (pos, &idx) in cells.iter().enumerate() {
+                let mut lo = 0i64;
+                let mut hi = 0i64;
+                for (other_pos, &other) in cells.iter().enumerate() {
+                    if other_pos == pos {
+                        continue;
+                    }
+                    // An empty domain is reported as a contradiction by `propagate`.
+                    let Some((mn, mx)) = domain_min_max(domains[other]) else {
+                        return;
+                    };
+                    lo += mn as i64;
+                    hi += mx as i64;
+                }
+                let mut keep = M::ZERO;
+                for v in domain_iter(domains[idx]) {
+                    let rest = cage.target - v as i64;
+                    if lo <= rest && rest <= hi {
+                        keep |= M::bit(v);
+                    }
+                }
+                domains[idx] &= keep;
+            }
+        }
+        Op::Sub | Op::Div | Op::Eq => {}
+    }
+}
+
+/// Simple hash of cell indices, the cage-shape part of a [`CacheTupleKey`].
+fn cage_cells_hash(cells: &[usize]) -> u64 {
+    cells.iter().fold(0u64, |hash, &cell| {
+        hash.wrapping_mul(31).wrapping_add(cell as u64)
+    })
+}
+
 /// Compute a cache key for a cage's tuple enumeration.
 /// Uses a hash of the cage's cells and the domain state for those cells.
 /// CRITICAL: Includes deduction tier to prevent cache mixing across different propagation contexts.
 #[inline]
 #[allow(dead_code)]
-fn compute_cache_key(
+fn compute_cache_key<M: Mask>(
     cage: &Cage,
     cells: &[usize],
-    domains: &[u64],
+    domains: &[M],
     tier: DeductionTier,
 ) -> CacheTupleKey {
-    // Simple hash of cell indices
-    let mut cells_hash = 0u64;
-    for &cell in cells.iter() {
-        cells_hash = cells_hash.wrapping_mul(31).wrapping_add(cell as u64);
-    }
+    let cells_hash = cage_cells_hash(cells);
 
     // Hash of domain state for cage cells
     let mut domain_hash = 0u64;
     for &cell in cells {
-        domain_hash = domain_hash.wrapping_mul(31).wrapping_add(domains[cell]);
+        domain_hash = domain_hash
+            .wrapping_mul(31)
+            .wrapping_add(domains[cell].to_u64());
     }
 
     // Use Op::Add as 0, Op::Sub as 1, Op::Div as 2, Op::Mul as 3, Op::Eq as 4
@@ -410,12 +2434,12 @@ fn compute_cache_key(
 
 #[allow(clippy::too_many_arguments)]
 #[instrument(skip(puzzle, rules, first, state, count, stats), fields(depth, n = state.n), level = "debug")]
-fn backtrack(
+fn backtrack<M: Mask>(
     puzzle: &Puzzle,
     rules: Ruleset,
     limit: u32,
     first: &mut Option<Solution>,
-    state: &mut State,
+    state: &mut State<M>,
     count: &mut u32,
     depth: u32,
     stats: &mut SolveStats,
@@ -450,7 +2474,11 @@ fn backtrack(
         }
     }
 
-    let Some((cell_idx, domain)) = choose_mrv_cell(puzzle, state)? else {
+    let Some((cell_idx, domain)) = choose_cell(state) else {
+        // `choose_cell` also reports `None` for an empty domain: dead end, not a solution.
+        if state.grid.contains(&0) {
+            return Ok(());
+        }
         // Solved
         *count += 1;
         if first.is_none() {
@@ -469,9 +2497,9 @@ fn backtrack(
     let mut tried = 0u32;
     #[cfg(feature = "nogood-learning")]
     let count_before = *count;
-    while mask != 0 {
-        let d = mask.trailing_zeros() as u8;
-        mask &= mask - 1;
+    while !mask.is_empty() {
+        let d = mask.min_digit();
+        mask = mask.clear_lowest();
         if d == 0 {
             continue;
         }
@@ -526,10 +2554,10 @@ fn backtrack(
 /// Used for LCV (Least Constraining Value) heuristic when lcv-heuristic feature is enabled.
 #[cfg(feature = "lcv-heuristic")]
 #[inline]
-fn measure_value_constrainingness(
+fn measure_value_constrainingness<M: Mask>(
     puzzle: &Puzzle,
     _rules: Ruleset,
-    state: &State,
+    state: &State<M>,
     cell_idx: usize,
     value: u8,
 ) -> u32 {
@@ -560,8 +2588,7 @@ fn measure_value_constrainingness(
     }
 
     // Cells in same cage also constrained
-    let cage_idx = state.cage_of_cell[cell_idx];
-    let cage = &puzzle.cages[cage_idx];
+    let cage = &puzzle.cages[state.cage_of_cell[cell_idx] as usize];
     for &cell in cage.cells.iter() {
         let idx = cell.0 as usize;
         if idx != cell_idx && (state.grid[idx] == 0) {
@@ -580,17 +2607,18 @@ fn measure_value_constrainingness(
 }
 
 #[allow(clippy::too_many_arguments)]
-#[instrument(skip(puzzle, rules, first, state, count, stats), fields(depth, tier = ?tier), level = "debug")]
-fn backtrack_deducing(
+#[instrument(skip(puzzle, rules, first, state, count, stats, observer), fields(depth, tier = ?tier), level = "debug")]
+fn backtrack_deducing<M: Mask>(
     puzzle: &Puzzle,
     rules: Ruleset,
     tier: DeductionTier,
     limit: u32,
     first: &mut Option<Solution>,
-    state: &mut State,
+    state: &mut State<M>,
     count: &mut u32,
     depth: u32,
     stats: &mut SolveStats,
+    observer: &mut dyn SearchObserver,
 ) -> Result<(), SolveError> {
     if *count >= limit {
         return Ok(());
@@ -598,9 +2626,22 @@ fn backtrack_deducing(
 
     stats.nodes_visited += 1;
     stats.max_depth = stats.max_depth.max(depth);
+    if (stats.nodes_visited - 1).is_multiple_of(OBSERVER_NODE_INTERVAL) {
+        observer.on_node(depth, stats.nodes_visited);
+    }
+    #[cfg(feature = "debug-api")]
+    if let Some(inspector) = &state.options.inspector {
+        crate::debug::inspect(inspector, puzzle, &*state, depth, stats.nodes_visited)?;
+    }
 
-    let Some((cell_idx, domain)) = choose_mrv_cell(puzzle, state)? else {
+    let Some((cell_idx, domain)) = choose_cell(state) else {
+        // `choose_cell` also reports `None` for an empty domain: dead end, not a solution.
+        let open = (0..state.grid.len()).any(|idx| state.is_open(idx));
+        if open || (state.on_warm_path && state.grid == state.warm_grid) {
+            return Ok(());
+        }
         *count += 1;
+        observer.on_solution(*count);
         if first.is_none() {
             *first = Some(Solution {
                 n: state.n,
@@ -614,14 +2655,16 @@ fn backtrack_deducing(
     let col = cell_idx % (state.n as usize);
 
     // Tier 2.3: LCV (Least Constraining Value) heuristic
-    // If enabled, score values and try least constraining first
+    // If enabled, score values and try least constraining first; LexMin keeps digits ascending.
     #[cfg(feature = "lcv-heuristic")]
-    let values_to_try = {
+    let values_to_try = if state.lex_min {
+        domain_digits(domain)
+    } else {
         let mut values = Vec::new();
         let mut mask = domain;
-        while mask != 0 {
-            let d = mask.trailing_zeros() as u8;
-            mask &= mask - 1;
+        while !mask.is_empty() {
+            let d = mask.min_digit();
+            mask = mask.clear_lowest();
             if d > 0 {
                 let score = measure_value_constrainingness(puzzle, rules, state, cell_idx, d);
                 values.push((d, score));
@@ -633,19 +2676,25 @@ fn backtrack_deducing(
     };
 
     #[cfg(not(feature = "lcv-heuristic"))]
-    let values_to_try = {
-        let mut values = Vec::new();
-        let mut mask = domain;
-        while mask != 0 {
-            let d = mask.trailing_zeros() as u8;
-            mask &= mask - 1;
-            if d > 0 {
-                values.push((d, 0u32)); // Dummy score, not used
-            }
-        }
-        values
-    };
+    let values_to_try = domain_digits(domain);
+
+    // Warm start: leave the known solution's digit for last, so a different solution turns up
+    // before the walk back down to the known one.
+    let mut values_to_try = values_to_try;
+    let on_warm_path = state.on_warm_path;
+    if on_warm_path
+        && let Some(pos) = values_to_try
+            .iter()
+            .position(|&(d, _)| d == state.warm_grid[cell_idx])
+    {
+        let known = values_to_try.remove(pos);
+        values_to_try.push(known);
+    }
 
+    #[cfg(feature = "debug-api")]
+    let mut untried = values_to_try
+        .iter()
+        .fold(0u64, |mask, &(d, _)| mask | 1 << d);
     let mut tried = 0u32;
     for (d, _score) in values_to_try {
         tried += 1;
@@ -655,6 +2704,16 @@ fn backtrack_deducing(
 
         place(state, row, col, d);
         stats.assignments += 1;
+        observer.on_branch(depth, cell_idx, d, stats.nodes_visited);
+        #[cfg(feature = "debug-api")]
+        if state.options.inspector.is_some() {
+            untried &= !(1 << d);
+            state.decisions.push(CheckpointFrame {
+                cell: cell_idx as u16,
+                value: d,
+                remaining: untried,
+            });
+        }
 
         let mut forced = Vec::new();
         let feasible = cages_still_feasible(puzzle, rules, state, cell_idx)?
@@ -668,6 +2727,7 @@ fn backtrack_deducing(
         // (choose_mrv_cell will check if cached cell is dirty and rescan if needed)
 
         if likely(feasible) {
+            state.on_warm_path = on_warm_path && d == state.warm_grid[cell_idx];
             backtrack_deducing(
                 puzzle,
                 rules,
@@ -678,7 +2738,11 @@ fn backtrack_deducing(
                 count,
                 depth + 1,
                 stats,
+                observer,
             )?;
+            state.on_warm_path = on_warm_path;
+        } else {
+            observer.on_pruned(depth);
         }
 
         for (idx, val) in forced.into_iter().rev() {
@@ -688,17 +2752,21 @@ fn backtrack_deducing(
         }
 
         unplace(state, row, col, d);
+        #[cfg(feature = "debug-api")]
+        state.decisions.pop();
 
         if *count >= limit {
             return Ok(());
         }
     }
 
+    observer.on_backtrack(depth);
     Ok(())
 }
 
 /// Result of tier-required classification.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct TierRequiredResult {
     /// Minimum deduction tier needed to solve without guessing.
     /// `None` means guessing (backtracking) was required.
@@ -716,6 +2784,11 @@ pub struct TierRequiredResult {
 /// Returns the minimum tier where the puzzle was solvable using only
 /// deductions (no guessing). If even Hard tier requires guessing,
 /// `tier_required` is `None`.
+///
+/// Best effort without [`Ruleset::enforce_columns`]: the same tiers run with their column
+/// deductions off, but the tiers (and [`classify_difficulty_from_tier`]'s node threshold)
+/// were calibrated on Latin puzzles. The labels order rows-only puzzles against each other
+/// and are not comparable with those of Latin ones.
 #[instrument(skip(puzzle, rules), fields(n = puzzle.n))]
 pub fn classify_tier_required(
     puzzle: &Puzzle,
@@ -807,193 +2880,723 @@ fn classify_difficulty_from_stats(stats: SolveStats) -> DifficultyTier {
     }
 }
 
-#[instrument(skip(puzzle, state), fields(n = state.n, cached = false), level = "debug")]
-fn choose_mrv_cell(puzzle: &Puzzle, state: &mut State) -> Result<Option<(usize, u64)>, SolveError> {
+/// Digits of `domain`, ascending, each with a dummy LCV score.
+fn domain_digits<M: Mask>(domain: M) -> Vec<(u8, u32)> {
+    let mut values = Vec::new();
+    let mut mask = domain;
+    while !mask.is_empty() {
+        let d = mask.min_digit();
+        mask = mask.clear_lowest();
+        if d > 0 {
+            values.push((d, 0u32)); // Dummy score, not used
+        }
+    }
+    values
+}
+
+/// The cell to branch on and its domain: the lowest-index open cell under `state.lex_min`,
+/// otherwise [`choose_mrv_cell`]'s. `None` when no cell is open or any open cell's domain is
+/// empty.
+fn choose_cell<M: Mask>(state: &mut State<M>) -> Option<(usize, M)> {
+    if !state.lex_min {
+        return choose_mrv_cell(state);
+    }
+    let n = state.n as usize;
+    let mut lowest = None;
+    for idx in 0..n * n {
+        if !state.is_open(idx) {
+            continue;
+        }
+        let dom = domain_for_cell(state, idx, idx / n, idx % n);
+        if dom.is_empty() {
+            return None;
+        }
+        lowest.get_or_insert((idx, dom));
+    }
+    lowest
+}
+
+#[instrument(skip(state), fields(n = state.n, cached = false), level = "debug")]
+fn choose_mrv_cell<M: Mask>(state: &mut State<M>) -> Option<(usize, M)> {
     let n = state.n as usize;
     let a = n * n;
 
     // Phase 2 optimization: use cache if still valid and no dirty cells
-    // When cache is valid, we can return the cached min_cell without rescanning
-    if state.mrv_cache.valid && !state.mrv_cache.has_dirty_cells() {
+    // When cache is valid, we can return the cached min_cell without rescanning.
+    // Tuple counts change without dirtying cells, so the tie-break always rescans.
+    let tiebreak = state.options.mrv_tuple_tiebreak;
+    if !tiebreak && state.mrv_cache.valid && !state.mrv_cache.has_dirty_cells() {
         // Cache hit: return cached result
         let min_idx = state.mrv_cache.min_cell;
-        if state.grid[min_idx] == 0 {
+        if state.is_open(min_idx) {
             // Cell still unfilled; use cached domain computation
             let row = min_idx / n;
             let col = min_idx % n;
-            if let Ok(dom) = domain_for_cell(puzzle, state, min_idx, row, col)
-                && popcount_u64(dom) > 0
-            {
-                return Ok(Some((min_idx, dom)));
+            let dom = domain_for_cell(state, min_idx, row, col);
+            if !dom.is_empty() {
+                return Some((min_idx, dom));
             }
         }
         // Cache miss (cell filled or domain empty): invalidate and rescan
     }
 
     // Cache miss or invalid: full rescan
-    let mut best: Option<(usize, u64, u32)> = None; // (idx, domain, popcnt)
+    // (idx, domain, popcnt, cage tuples); the tuples stay unknown without the tie-break.
+    let mut best: Option<(usize, M, u32, u32)> = None;
+
+    // One batched pass computes every domain; the scan still stops at the first singleton.
+    #[cfg(feature = "simd-dispatch")]
+    batch_domains(
+        n,
+        &state.masks,
+        &mut state.batch_domains,
+        &mut state.batch_counts,
+    );
 
     for idx in 0..a {
-        if state.grid[idx] != 0 {
+        if !state.is_open(idx) {
             continue;
         }
-        let row = idx / n;
-        let col = idx % n;
-        let dom = domain_for_cell(puzzle, state, idx, row, col)?;
-        let pop = popcount_u64(dom);
+        #[cfg(feature = "simd-dispatch")]
+        let (dom, pop) = (state.batch_domains[idx], state.batch_counts[idx]);
+        #[cfg(not(feature = "simd-dispatch"))]
+        let (dom, pop) = {
+            let dom = domain_for_cell(state, idx, idx / n, idx % n);
+            (dom, dom.count())
+        };
         if pop == 0 {
-            return Ok(None);
+            return None;
         }
+        let tuples = if tiebreak {
+            state.cell_cage_tuples(idx)
+        } else {
+            TUPLES_UNKNOWN
+        };
         match best {
-            None => best = Some((idx, dom, pop)),
-            Some((_, _, best_pop)) if pop < best_pop => best = Some((idx, dom, pop)),
+            None => best = Some((idx, dom, pop, tuples)),
+            Some((_, _, best_pop, best_tuples)) if (pop, tuples) < (best_pop, best_tuples) => {
+                best = Some((idx, dom, pop, tuples))
+            }
             _ => {}
         }
-        if best.is_some_and(|(_, _, p)| p == 1) {
+        if best.is_some_and(|(_, _, p, _)| p == 1) {
             break;
         }
-    }
-
-    // Update cache with new result before returning (Tier 2.2 optimization)
-    if let Some((idx, _dom, pop)) = best {
-        state.mrv_cache.min_cell = idx;
-        state.mrv_cache.min_count = pop;
-        state.mrv_cache.valid = true;
-        state.mrv_cache.reset_dirty();
-    }
-
-    Ok(best.map(|(idx, dom, _)| (idx, dom)))
-}
-
-fn popcount_u64(x: u64) -> u32 {
-    x.count_ones()
-}
+    }
+
+    // Update cache with new result before returning (Tier 2.2 optimization)
+    if let Some((idx, _dom, pop, _)) = best {
+        state.mrv_cache.min_cell = idx;
+        state.mrv_cache.min_count = pop;
+        state.mrv_cache.valid = true;
+        state.mrv_cache.reset_dirty();
+    }
+
+    best.map(|(idx, dom, _, _)| (idx, dom))
+}
+
+/// `domain_for_cell` of every cell into `out`, with counts, from `State::masks` of an `n` x `n`
+/// grid.
+#[cfg(feature = "simd-dispatch")]
+#[inline]
+fn batch_domains<M: Mask>(n: usize, masks: &[M], out: &mut [M], counts: &mut [u32]) {
+    let (lines, statics) = masks.split_at(2 * n);
+    let (rows, cols) = lines.split_at(n);
+    M::domains_batch(statics, rows, cols, out, counts);
+}
+
+#[inline]
+fn domain_for_cell<M: Mask>(state: &State<M>, idx: usize, row: usize, col: usize) -> M {
+    state.static_mask(idx) & !state.row_mask(row) & !state.col_mask(col)
+}
+
+fn cages_still_feasible<M: Mask>(
+    puzzle: &Puzzle,
+    rules: Ruleset,
+    state: &State<M>,
+    changed_cell: usize,
+) -> Result<bool, SolveError> {
+    let cage_idx = state.cage_of_cell[changed_cell] as usize;
+    if state.cage_is_free(cage_idx) {
+        return Ok(true);
+    }
+    let cage = &puzzle.cages[cage_idx];
+    if state.cage_op_is_hidden(cage_idx) {
+        for op in hidden_op_candidates(cage, state.n, rules) {
+            if cage_feasible(rules, state, &Cage { op, ..cage.clone() })? {
+                return Ok(true);
+            }
+        }
+        return Ok(false);
+    }
+    if !cage_feasible(rules, state, cage)? {
+        return Ok(false);
+    }
+    Ok(true)
+}
+
+/// Ops a cage whose op is hidden may carry. A one-cell cage keeps its own: its clue is its
+/// digit whatever the op. A larger one may carry any op `rules` allows at its size whose
+/// target range holds its target.
+fn hidden_op_candidates(cage: &Cage, n: u8, rules: Ruleset) -> SmallVec<[Op; 5]> {
+    let len = cage.cells.len();
+    if len == 1 {
+        return SmallVec::from_slice(&[cage.op]);
+    }
+    Op::ALL
+        .into_iter()
+        .filter(|&op| match op {
+            Op::Eq => false,
+            Op::Sub | Op::Div if rules.sub_div_two_cell_only && len != 2 => false,
+            _ => {
+                let (lo, hi) = target_bounds(op, len, n, false);
+                (lo..=hi).contains(&cage.target)
+            }
+        })
+        .collect()
+}
+
+/// Cage deduction for a cage whose op is hidden: every candidate op deduces from the same
+/// domains, and a digit survives wherever some op keeps it.
+fn apply_hidden_op_deduction<M: Mask>(
+    #[cfg(feature = "alloc-bumpalo")] bump: &Bump,
+    puzzle: &Puzzle,
+    rules: Ruleset,
+    state: &mut State<M>,
+    cage: &Cage,
+    tier: DeductionTier,
+    domains: &mut [M],
+) -> Result<(), SolveError> {
+    let mut kept = vec![M::ZERO; domains.len()];
+    for op in hidden_op_candidates(cage, state.n, rules) {
+        let variant = Cage { op, ..cage.clone() };
+        let mut trial = domains.to_vec();
+        #[cfg(feature = "alloc-bumpalo")]
+        apply_cage_deduction_with_bump(bump, puzzle, rules, state, &variant, tier, &mut trial)?;
+        #[cfg(not(feature = "alloc-bumpalo"))]
+        apply_cage_deduction(puzzle, rules, state, &variant, tier, &mut trial)?;
+        for (slot, dom) in kept.iter_mut().zip(trial) {
+            *slot |= dom;
+        }
+    }
+    domains.copy_from_slice(&kept);
+    Ok(())
+}
+
+#[instrument(skip(puzzle, rules, state, forced), fields(n = state.n, tier = ?tier, iterations = 0), level = "debug")]
+fn propagate<M: Mask>(
+    puzzle: &Puzzle,
+    rules: Ruleset,
+    tier: DeductionTier,
+    state: &mut State<M>,
+    forced: &mut Vec<(usize, u8)>,
+) -> Result<bool, SolveError> {
+    let n = state.n as usize;
+    let a = n * n;
+
+    #[cfg(feature = "alloc-bumpalo")]
+    let mut bump = Bump::new();
+
+    let mut domains = vec![M::ZERO; a];
+    let forced_from = forced.len();
+    let max_rounds = state.options.max_propagation_rounds.max(1);
+    let mut last_popcount = u64::MAX;
+    #[cfg(feature = "debug-snapshots")]
+    if let Some(log) = &mut state.propagation_log {
+        log.begin_call();
+    }
+
+    for round in 1..=max_rounds {
+        #[cfg(feature = "alloc-bumpalo")]
+        bump.reset();
+        state.propagation_rounds += 1;
+        #[cfg(feature = "debug-snapshots")]
+        let round_from = forced.len();
+
+        #[cfg(feature = "simd-dispatch")]
+        {
+            batch_domains(n, &state.masks, &mut domains, &mut state.batch_counts);
+            for (dom_slot, &digit) in domains.iter_mut().zip(&state.grid) {
+                if digit != 0 {
+                    *dom_slot = M::bit(digit);
+                }
+            }
+        }
+        #[cfg(not(feature = "simd-dispatch"))]
+        {
+            domains.fill(M::ZERO);
+            for (idx, dom_slot) in domains.iter_mut().enumerate() {
+                if state.grid[idx] != 0 {
+                    *dom_slot = M::bit(state.grid[idx]);
+                    continue;
+                }
+                let r = idx / n;
+                let c = idx % n;
+                *dom_slot = domain_for_cell(state, idx, r, c);
+            }
+        }
+
+        for (cage_idx, cage) in puzzle.cages.iter().enumerate() {
+            if state.cage_is_free(cage_idx) {
+                continue;
+            }
+            // Tier 2.2: Smarter dirty tracking - capture domain state before deduction
+            let cage_cells: Vec<usize> = cage.cells.iter().map(|c| c.0 as usize).collect();
+            let domain_before: Vec<M> = cage_cells.iter().map(|&idx| domains[idx]).collect();
+
+            let tuples = if state.cage_op_is_hidden(cage_idx) {
+                apply_hidden_op_deduction(
+                    #[cfg(feature = "alloc-bumpalo")]
+                    &bump,
+                    puzzle,
+                    rules,
+                    state,
+                    cage,
+                    tier,
+                    &mut domains,
+                )?;
+                TUPLES_UNKNOWN
+            } else {
+                #[cfg(feature = "alloc-bumpalo")]
+                let tuples = apply_cage_deduction_with_bump(
+                    &bump,
+                    puzzle,
+                    rules,
+                    state,
+                    cage,
+                    tier,
+                    &mut domains,
+                )?;
+
+                #[cfg(not(feature = "alloc-bumpalo"))]
+                let tuples = apply_cage_deduction(puzzle, rules, state, cage, tier, &mut domains)?;
+                tuples
+            };
+            state.cage_tuples[cage_idx] = match tier {
+                DeductionTier::Normal | DeductionTier::Hard => tuples,
+                _ => TUPLES_UNKNOWN,
+            };
+
+            // Tier 2.2: Only mark cells whose domains were actually reduced (smarter dirty tracking)
+            for (i, &idx) in cage_cells.iter().enumerate() {
+                let domain_after = domains[idx];
+                // Mark dirty only if domain was reduced (bits removed)
+                // Using: (before & ~after) != 0 means bits were removed
+                if !(domain_before[i] & !domain_after).is_empty() {
+                    state.mrv_cache.mark_dirty(idx);
+                }
+            }
+        }
+
+        // A filled cell's domain empties when its cage has no tuple left, e.g. when one round
+        // forced two of its cells to digits that are each supported but not together.
+        if domains.iter().any(|dom| dom.is_empty()) {
+            #[cfg(feature = "debug-snapshots")]
+            log_round(state, round, &domains, &[], true);
+            return Ok(false);
+        }
+
+        let mut any_forced = false;
+        for (idx, &dom) in domains.iter().enumerate() {
+            if state.grid[idx] != 0 {
+                continue;
+            }
+            if dom.count() == 1 {
+                let val = dom.min_digit();
+                let r = idx / n;
+                let c = idx % n;
+                // Domains are a snapshot from the start of the round, so two cells in
+                // one row/column can both be forced to the same digit: contradiction.
+                if (state.row_mask(r) | state.col_mask(c)).has(val) {
+                    #[cfg(feature = "debug-snapshots")]
+                    log_round(state, round, &domains, &forced[round_from..], true);
+                    return Ok(false);
+                }
+                place(state, r, c, val);
+                forced.push((idx, val));
+                any_forced = true;
+            }
+        }
+        #[cfg(feature = "debug-snapshots")]
+        log_round(state, round, &domains, &forced[round_from..], false);
 
-fn domain_for_cell(
-    puzzle: &Puzzle,
-    state: &State,
-    idx: usize,
-    row: usize,
-    col: usize,
-) -> Result<u64, CoreError> {
-    let n = state.n;
-    let mut dom = full_domain(n) & !state.row_mask[row] & !state.col_mask[col];
+        check_state_invariants(state, "propagate", None);
+        check_propagation_progress(&domains, &mut last_popcount, any_forced, round);
 
-    let cage = &puzzle.cages[state.cage_of_cell[idx]];
-    if cage.cells.len() == 1 && cage.op == Op::Eq {
-        if cage.target <= 0 || cage.target > n as i32 {
-            return Err(CoreError::EqTargetOutOfRange);
+        if !any_forced {
+            return Ok(true);
         }
-        dom &= 1u64 << (cage.target as u32);
     }
 
-    Ok(dom)
-}
-
-fn cages_still_feasible(
-    puzzle: &Puzzle,
-    rules: Ruleset,
-    state: &State,
-    changed_cell: usize,
-) -> Result<bool, SolveError> {
-    let cage_idx = state.cage_of_cell[changed_cell];
-    let cage = &puzzle.cages[cage_idx];
-    if !cage_feasible(puzzle, rules, state, cage)? {
-        return Ok(false);
+    // Capped with placements the next round would have checked: check their cages now, as
+    // the search does for its own placements.
+    for &(idx, _) in &forced[forced_from..] {
+        if !cages_still_feasible(puzzle, rules, state, idx)? {
+            return Ok(false);
+        }
     }
     Ok(true)
 }
 
-#[instrument(skip(puzzle, rules, state, forced), fields(n = state.n, tier = ?tier, iterations = 0), level = "debug")]
-fn propagate(
+/// `propagate` for at most `rounds` rounds, whatever `state.options` allows; placements it
+/// forces are appended to `forced` as usual.
+#[cfg(feature = "debug-api")]
+pub(crate) fn propagate_rounds<M: Mask>(
     puzzle: &Puzzle,
     rules: Ruleset,
     tier: DeductionTier,
-    state: &mut State,
+    state: &mut State<M>,
+    rounds: u32,
     forced: &mut Vec<(usize, u8)>,
 ) -> Result<bool, SolveError> {
-    let n = state.n as usize;
-    let a = n * n;
+    let cap = std::mem::replace(&mut state.options.max_propagation_rounds, rounds);
+    let feasible = propagate(puzzle, rules, tier, state, forced);
+    state.options.max_propagation_rounds = cap;
+    feasible
+}
 
-    #[cfg(feature = "alloc-bumpalo")]
-    let mut bump = Bump::new();
+/// Append a snapshot of `domains` to `state.propagation_log`, if one is being kept.
+#[cfg(feature = "debug-snapshots")]
+fn log_round<M: Mask>(
+    state: &mut State<M>,
+    round: u32,
+    domains: &[M],
+    forced: &[(usize, u8)],
+    contradiction: bool,
+) {
+    if let Some(log) = &mut state.propagation_log {
+        log.record(
+            round,
+            domains.iter().map(|d| d.to_u64()),
+            forced,
+            contradiction,
+        );
+    }
+}
 
-    let mut domains = vec![0u64; a];
+/// Panic if a propagation round forced cells without shrinking the total domain size below the
+/// previous round's: domains only narrow as cells are placed, so this means a deduction is
+/// oscillating.
+///
+/// Enabled by the `debug-invariants` feature; compiles to nothing otherwise.
+#[cfg(feature = "debug-invariants")]
+#[track_caller]
+fn check_propagation_progress<M: Mask>(
+    domains: &[M],
+    last_popcount: &mut u64,
+    any_forced: bool,
+    round: u32,
+) {
+    let popcount: u64 = domains.iter().map(|d| d.count() as u64).sum();
+    assert!(
+        !any_forced || popcount < *last_popcount,
+        "propagate round {round} forced cells without shrinking domains \
+         ({popcount} candidates, {} the round before)",
+        *last_popcount
+    );
+    *last_popcount = popcount;
+}
 
-    loop {
-        #[cfg(feature = "alloc-bumpalo")]
-        bump.reset();
+#[cfg(not(feature = "debug-invariants"))]
+#[inline(always)]
+fn check_propagation_progress<M: Mask>(
+    _domains: &[M],
+    _last_popcount: &mut u64,
+    _any_forced: bool,
+    _round: u32,
+) {
+}
 
-        domains.fill(0u64);
-        for (idx, dom_slot) in domains.iter_mut().enumerate() {
-            if state.grid[idx] != 0 {
-                *dom_slot = 1u64 << (state.grid[idx] as u32);
+/// Two-cell Sub/Div deduction, shared by the plain and `alloc-bumpalo` propagation paths.
+///
+/// Keeps each cell's values that have a partner in the other cell. At Hard tier, a value that
+/// every supporting pair places in some row (column) of the cage is removed from the rest of
+/// that line. A pair touches at most two rows and two columns, so the per-line masks live in
+/// locals instead of `n`-length scratch vectors; when both cells share a line, both of its
+/// slots receive the union of the pair's bits. Columns are left alone without
+/// `enforce_columns`. Returns the number of supporting pairs, or [`TUPLES_UNKNOWN`] when both
+/// cells were already assigned and no pair was enumerated.
+fn apply_sub_div_pair<M: Mask>(
+    n: usize,
+    cage: &Cage,
+    a_idx: usize,
+    b_idx: usize,
+    tier: DeductionTier,
+    enforce_columns: bool,
+    domains: &mut [M],
+) -> u32 {
+    let a_dom = domains[a_idx];
+    let b_dom = domains[b_idx];
+    let pair_ok = |av: u8, bv: u8| op_pair_satisfied(cage.op, cage.target, av, bv);
+
+    // TIER 1.2: If both cells are fully assigned, verify constraint directly
+    if tier != DeductionTier::Hard && a_dom.count() == 1 && b_dom.count() == 1 {
+        if !pair_ok(a_dom.min_digit(), b_dom.min_digit()) {
+            domains[a_idx] = M::ZERO;
+            domains[b_idx] = M::ZERO;
+        }
+        return TUPLES_UNKNOWN;
+    }
+
+    let (ra, ca) = (a_idx / n, a_idx % n);
+    let (rb, cb) = (b_idx / n, b_idx % n);
+    let mut a_ok = M::ZERO;
+    let mut b_ok = M::ZERO;
+    let mut pairs = 0;
+    let (mut must_ra, mut must_rb, mut must_ca, mut must_cb) =
+        (!M::ZERO, !M::ZERO, !M::ZERO, !M::ZERO);
+    for av in domain_iter(a_dom) {
+        for bv in domain_iter(b_dom) {
+            if !pair_ok(av, bv) {
                 continue;
             }
-            let r = idx / n;
-            let c = idx % n;
-            *dom_slot = full_domain(state.n) & !state.row_mask[r] & !state.col_mask[c];
+            pairs += 1;
+            let a_bit = M::bit(av);
+            let b_bit = M::bit(bv);
+            a_ok |= a_bit;
+            b_ok |= b_bit;
+
+            let (row_a, row_b) = if ra == rb {
+                (a_bit | b_bit, a_bit | b_bit)
+            } else {
+                (a_bit, b_bit)
+            };
+            let (col_a, col_b) = if ca == cb {
+                (a_bit | b_bit, a_bit | b_bit)
+            } else {
+                (a_bit, b_bit)
+            };
+            must_ra &= row_a;
+            must_rb &= row_b;
+            must_ca &= col_a;
+            must_cb &= col_b;
         }
+    }
 
-        for cage in &puzzle.cages {
-            // Tier 2.2: Smarter dirty tracking - capture domain state before deduction
-            let cage_cells: Vec<usize> = cage.cells.iter().map(|c| c.0 as usize).collect();
-            let domain_before: Vec<u64> = cage_cells.iter().map(|&idx| domains[idx]).collect();
+    domains[a_idx] &= a_ok;
+    domains[b_idx] &= b_ok;
 
-            #[cfg(feature = "alloc-bumpalo")]
-            apply_cage_deduction_with_bump(&bump, puzzle, rules, state, cage, tier, &mut domains)?;
+    if tier == DeductionTier::Hard && !a_ok.is_empty() {
+        for (r, must) in [(ra, must_ra), (rb, must_rb)] {
+            for c in 0..n {
+                let idx = r * n + c;
+                if idx != a_idx && idx != b_idx {
+                    domains[idx] &= !must;
+                }
+            }
+        }
+        let cols = if enforce_columns {
+            &[(ca, must_ca), (cb, must_cb)][..]
+        } else {
+            &[]
+        };
+        for &(c, must) in cols {
+            for r in 0..n {
+                let idx = r * n + c;
+                if idx != a_idx && idx != b_idx {
+                    domains[idx] &= !must;
+                }
+            }
+        }
+    }
+    pairs
+}
 
-            #[cfg(not(feature = "alloc-bumpalo"))]
-            apply_cage_deduction(puzzle, rules, state, cage, tier, &mut domains)?;
+/// Two-cell Add/Mul deduction, shared by the plain and `alloc-bumpalo` propagation paths.
+///
+/// Each value of the first cell has at most one partner (`target - v` or `target / v`), so
+/// the supports come from one pass over the first cell's domain instead of tuple
+/// enumeration. The result matches [`enumerate_cage_tuples`] (and, at Hard tier, its per-line
+/// must masks) bit for bit, including the rule that cells sharing a row or (with
+/// `enforce_columns`) a column differ, and the tuple count it returns;
+/// callers only take this path when the enumeration budget could not have run out.
+fn apply_add_mul_pair<M: Mask>(
+    n: usize,
+    cage: &Cage,
+    a_idx: usize,
+    b_idx: usize,
+    tier: DeductionTier,
+    enforce_columns: bool,
+    domains: &mut [M],
+) -> u32 {
+    // Easy tier keeps the union of both cells' supports, which can hold values the
+    // pre-filter would have removed, so it runs first exactly as on the generic path.
+    prefilter_mul_domains(cage, &[a_idx, b_idx], domains);
+    let a_dom = domains[a_idx];
+    let b_dom = domains[b_idx];
+    let (ra, ca) = (a_idx / n, a_idx % n);
+    let (rb, cb) = (b_idx / n, b_idx % n);
+    let shares_line = ra == rb || (enforce_columns && ca == cb);
+    let max_b = if b_dom.is_empty() {
+        0
+    } else {
+        b_dom.max_digit() as i64
+    };
 
-            // Tier 2.2: Only mark cells whose domains were actually reduced (smarter dirty tracking)
-            for (i, &idx) in cage_cells.iter().enumerate() {
-                let domain_after = domains[idx];
-                // Mark dirty only if domain was reduced (bits removed)
-                // Using: (before & ~after) != 0 means bits were removed
-                if (domain_before[i] & !domain_after) != 0 {
-                    state.mrv_cache.mark_dirty(idx);
+    let mut a_ok = M::ZERO;
+    let mut b_ok = M::ZERO;
+    let mut pairs = 0;
+    let (mut must_ra, mut must_rb, mut must_ca, mut must_cb) =
+        (!M::ZERO, !M::ZERO, !M::ZERO, !M::ZERO);
+    for av in domain_iter(a_dom) {
+        let partner = match cage.op {
+            Op::Add => cage.target - av as i64,
+            Op::Mul if cage.target % av as i64 == 0 => cage.target / av as i64,
+            _ => continue,
+        };
+        if !(1..=max_b).contains(&partner) {
+            continue;
+        }
+        let bv = partner as u8;
+        if !b_dom.has(bv) || (shares_line && bv == av) {
+            continue;
+        }
+        pairs += 1;
+        let a_bit = M::bit(av);
+        let b_bit = M::bit(bv);
+        a_ok |= a_bit;
+        b_ok |= b_bit;
+
+        if tier == DeductionTier::Hard {
+            let (row_a, row_b) = if ra == rb {
+                (a_bit | b_bit, a_bit | b_bit)
+            } else {
+                (a_bit, b_bit)
+            };
+            let (col_a, col_b) = if ca == cb {
+                (a_bit | b_bit, a_bit | b_bit)
+            } else {
+                (a_bit, b_bit)
+            };
+            must_ra &= row_a;
+            must_rb &= row_b;
+            must_ca &= col_a;
+            must_cb &= col_b;
+        }
+    }
+
+    if tier == DeductionTier::Easy {
+        domains[a_idx] &= a_ok | b_ok;
+        domains[b_idx] &= a_ok | b_ok;
+    } else {
+        domains[a_idx] &= a_ok;
+        domains[b_idx] &= b_ok;
+    }
+
+    if tier == DeductionTier::Hard && !a_ok.is_empty() {
+        for (r, must) in [(ra, must_ra), (rb, must_rb)] {
+            for c in 0..n {
+                let idx = r * n + c;
+                if idx != a_idx && idx != b_idx {
+                    domains[idx] &= !must;
                 }
             }
         }
-
-        for (idx, &dom) in domains.iter().enumerate() {
-            if state.grid[idx] == 0 && dom == 0 {
-                return Ok(false);
+        let cols = if enforce_columns {
+            &[(ca, must_ca), (cb, must_cb)][..]
+        } else {
+            &[]
+        };
+        for &(c, must) in cols {
+            for r in 0..n {
+                let idx = r * n + c;
+                if idx != a_idx && idx != b_idx {
+                    domains[idx] &= !must;
+                }
             }
         }
+    }
+    pairs
+}
+
+/// Deduce `cage` from `state.options.tuple_cache` when every cell's domain is still full, as
+/// the generic enumeration would, returning its tuple count. Returns `None`, leaving `domains`
+/// alone, when there is no cache, a domain is narrowed, or the enumeration would exceed the
+/// work cap.
+fn apply_shared_tuples<M: Mask>(
+    state: &State<M>,
+    cage: &Cage,
+    cells: &[usize],
+    tier: DeductionTier,
+    domains: &mut [M],
+) -> Option<u32> {
+    // The shared tables keep digits apart down columns too.
+    let cache = state
+        .options
+        .tuple_cache
+        .as_ref()
+        .filter(|_| state.enforce_columns)?;
+    let n = state.n as usize;
+    let full = M::full(state.n);
+    // Below 2x2 a full domain is already a placement, which the enumeration short-cuts.
+    if n < 2 || cells.iter().any(|&idx| domains[idx] != full) {
+        return None;
+    }
+    let tuples = cache.full_domain_tuples(state.n, cage, state.options.max_tuples_per_cage)?;
+
+    if tier == DeductionTier::Easy {
+        for &idx in cells {
+            domains[idx] &= M::from_u64(tuples.any);
+        }
+    } else {
+        for (pos, &idx) in cells.iter().enumerate() {
+            domains[idx] &= M::from_u64(tuples.per_pos[pos]);
+        }
+    }
 
-        let mut any_forced = false;
-        for (idx, &dom) in domains.iter().enumerate() {
-            if state.grid[idx] != 0 {
+    if tier == DeductionTier::Hard && tuples.any != 0 {
+        let row0 = cells.iter().map(|&idx| idx / n).min().unwrap_or(0);
+        let col0 = cells.iter().map(|&idx| idx % n).min().unwrap_or(0);
+        for (dr, &must) in tuples.must_row.iter().enumerate() {
+            let must = M::from_u64(must);
+            if must.is_empty() {
                 continue;
             }
-            if popcount_u64(dom) == 1 {
-                let val = dom.trailing_zeros() as u8;
-                let r = idx / n;
-                let c = idx % n;
-                place(state, r, c, val);
-                forced.push((idx, val));
-                any_forced = true;
+            for c in 0..n {
+                let idx = (row0 + dr) * n + c;
+                if !cells.contains(&idx) {
+                    domains[idx] &= !must;
+                }
             }
         }
-
-        if !any_forced {
-            return Ok(true);
+        for (dc, &must) in tuples.must_col.iter().enumerate() {
+            let must = M::from_u64(must);
+            if must.is_empty() {
+                continue;
+            }
+            for r in 0..n {
+                let idx = r * n + col0 + dc;
+                if !cells.contains(&idx) {
+                    domains[idx] &= !must;
+                }
+            }
         }
     }
+    Some(tuples.count)
+}
+
+/// Whether [`apply_add_mul_pair`] may stand in for enumerating `cage`: a two-cell Add/Mul cage
+/// whose enumeration (at most one root, `n` first-cell and `n * n` leaf nodes) fits the
+/// budget, so the generic path would not have degraded it.
+fn is_fast_add_mul_pair(cage: &Cage, n: usize, options: &SearchOptions) -> bool {
+    matches!(cage.op, Op::Add | Op::Mul)
+        && cage.cells.len() == 2
+        && options.max_tuples_per_cage > 1 + n + n * n
 }
 
+/// Narrow `domains` by `cage`'s arithmetic. Returns how many tuples of the cage survive the
+/// domains it started from, or [`TUPLES_UNKNOWN`] when no enumeration counted them (a
+/// fully-assigned cage at Easy/Normal, or one degraded by the work cap).
 #[cfg(not(feature = "alloc-bumpalo"))]
 #[instrument(skip(_puzzle, rules, state, cage, domains), fields(op = ?cage.op, cells = cage.cells.len()), level = "debug")]
-fn apply_cage_deduction(
+fn apply_cage_deduction<M: Mask>(
     _puzzle: &Puzzle,
     rules: Ruleset,
-    state: &mut State,
+    state: &mut State<M>,
     cage: &Cage,
     tier: DeductionTier,
-    domains: &mut [u64],
-) -> Result<(), SolveError> {
+    domains: &mut [M],
+) -> Result<u32, SolveError> {
     let n = state.n as usize;
     let a = n * n;
     let cells: Vec<usize> = cage.cells.iter().map(|c| c.0 as usize).collect();
@@ -1001,140 +3604,48 @@ fn apply_cage_deduction(
     match cage.op {
         Op::Eq => {
             let idx = cells[0];
-            domains[idx] &= 1u64 << (cage.target as u32);
-            return Ok(());
+            domains[idx] &= M::bit(cage.target as u8);
+            Ok(u32::from(!domains[idx].is_empty()))
         }
         Op::Sub | Op::Div if rules.sub_div_two_cell_only && cage.cells.len() != 2 => {
-            return Err(CoreError::SubDivMustBeTwoCell.into());
+            Err(CoreError::SubDivMustBeTwoCell.into())
         }
         Op::Sub | Op::Div if cage.cells.len() == 2 => {
-            let a_idx = cells[0];
-            let b_idx = cells[1];
-            let a_dom = domains[a_idx];
-            let b_dom = domains[b_idx];
-
-            // TIER 1.2: If both cells are fully assigned, verify constraint directly
-            if tier != DeductionTier::Hard
-                && domains[a_idx].count_ones() == 1
-                && domains[b_idx].count_ones() == 1
-            {
-                // Both cells have exactly one value; check constraint directly
-                let av = (a_dom.trailing_zeros() + 1) as u8;
-                let bv = (b_dom.trailing_zeros() + 1) as u8;
-                let ok = match cage.op {
-                    Op::Sub => (av as i32 - bv as i32).abs() == cage.target,
-                    Op::Div => {
-                        let (num, den) = if av >= bv { (av, bv) } else { (bv, av) };
-                        den != 0 && (num as i32) == (den as i32).saturating_mul(cage.target)
-                    }
-                    _ => false,
-                };
-                if ok {
-                    // Constraint satisfied; domains unchanged
-                } else {
-                    // Constraint violated; domains empty
-                    domains[a_idx] = 0u64;
-                    domains[b_idx] = 0u64;
-                }
-            } else {
-                // Standard enumeration (needed for Hard tier or when cells not fully assigned)
-                let mut a_ok = 0u64;
-                let mut b_ok = 0u64;
-                let mut found = false;
-                let coords = [(a_idx / n, a_idx % n), (b_idx / n, b_idx % n)];
-                let mut must_row: Vec<Option<u64>> = vec![None; n];
-                let mut must_col: Vec<Option<u64>> = vec![None; n];
-
-                for av in domain_iter(a_dom) {
-                    for bv in domain_iter(b_dom) {
-                        let ok = match cage.op {
-                            Op::Sub => (av as i32 - bv as i32).abs() == cage.target,
-                            Op::Div => {
-                                let (num, den) = if av >= bv { (av, bv) } else { (bv, av) };
-                                den != 0 && (num as i32) == (den as i32).saturating_mul(cage.target)
-                            }
-                            _ => false,
-                        };
-                        if ok {
-                            found = true;
-                            a_ok |= 1u64 << (av as u32);
-                            b_ok |= 1u64 << (bv as u32);
-
-                            if tier == DeductionTier::Hard {
-                                let pair = [av, bv];
-                                let mut row_bits = vec![0u64; n];
-                                let mut col_bits = vec![0u64; n];
-                                for (i, &(r, c)) in coords.iter().enumerate() {
-                                    row_bits[r] |= 1u64 << (pair[i] as u32);
-                                    col_bits[c] |= 1u64 << (pair[i] as u32);
-                                }
-                                for r in 0..n {
-                                    if row_bits[r] != 0 {
-                                        must_row[r] = Some(match must_row[r] {
-                                            None => row_bits[r],
-                                            Some(m) => m & row_bits[r],
-                                        });
-                                    }
-                                }
-                                for c in 0..n {
-                                    if col_bits[c] != 0 {
-                                        must_col[c] = Some(match must_col[c] {
-                                            None => col_bits[c],
-                                            Some(m) => m & col_bits[c],
-                                        });
-                                    }
-                                }
-                            }
-                        }
-                    }
-                }
-
-                domains[a_idx] &= a_ok;
-                domains[b_idx] &= b_ok;
-
-                if tier == DeductionTier::Hard && found {
-                    let mut in_cage = vec![false; a];
-                    in_cage[a_idx] = true;
-                    in_cage[b_idx] = true;
-                    for (r, maybe_must) in must_row.into_iter().enumerate() {
-                        let Some(must) = maybe_must else { continue };
-                        for c in 0..n {
-                            let idx = r * n + c;
-                            if !in_cage[idx] {
-                                domains[idx] &= !must;
-                            }
-                        }
-                    }
-                    for (c, maybe_must) in must_col.into_iter().enumerate() {
-                        let Some(must) = maybe_must else { continue };
-                        for r in 0..n {
-                            let idx = r * n + c;
-                            if !in_cage[idx] {
-                                domains[idx] &= !must;
-                            }
-                        }
-                    }
-                }
-            }
-            return Ok(());
+            let columns = state.enforce_columns;
+            Ok(apply_sub_div_pair(
+                n, cage, cells[0], cells[1], tier, columns, domains,
+            ))
+        }
+        Op::Add | Op::Mul if is_fast_add_mul_pair(cage, n, &state.options) => {
+            let columns = state.enforce_columns;
+            Ok(apply_add_mul_pair(
+                n, cage, cells[0], cells[1], tier, columns, domains,
+            ))
         }
-        Op::Add | Op::Mul => {
-            let coords: Vec<(usize, usize)> = cells.iter().map(|&idx| (idx / n, idx % n)).collect();
+        // Add/Mul, plus n-ary Sub/Div (only reachable when the ruleset allows them).
+        Op::Add | Op::Mul | Op::Sub | Op::Div => {
+            if let Some(tuples) = apply_shared_tuples(state, cage, &cells, tier, domains) {
+                return Ok(tuples);
+            }
+            prefilter_mul_domains(cage, &cells, domains);
+            let coords: Vec<(usize, usize)> =
+                cells.iter().map(|&idx| state.cell_lines(idx)).collect();
+            let mut budget = state.options.max_tuples_per_cage;
             let (per_pos, any_mask, must_row, must_col, found) = if tier == DeductionTier::Hard {
-                enumerate_cage_tuples_with_must(n, cage, &cells, &coords, domains)
+                enumerate_cage_tuples_with_must(n, cage, &cells, &coords, domains, &mut budget)
             } else {
                 // TIER 1.2: Skip enumeration if all cage cells are fully assigned.
                 // Only for Easy/Normal tiers (Hard tier needs full enumeration for constraint learning).
                 if tier != DeductionTier::Hard && all_cells_fully_assigned(&cells, domains) {
                     // All cells have exactly one value; skip enumeration and compute any_mask directly
-                    let any_mask = compute_any_mask_from_assigned(&cells, domains);
+                    let any_mask = compute_any_mask_from_assigned(cage, &cells, domains);
                     let per_pos = vec![any_mask; cells.len()];
                     (
                         per_pos,
                         any_mask,
-                        vec![0u64; n],
-                        vec![0u64; n],
-                        any_mask != 0,
+                        vec![M::ZERO; n],
+                        vec![M::ZERO; n],
+                        TUPLES_UNKNOWN,
                     )
                 } else if n >= 6 {
                     // TIER 1.1: Cache enumeration results (only for n >= 6)
@@ -1144,14 +3655,15 @@ fn apply_cage_deduction(
                         (
                             cached.per_pos.clone(),
                             cached.any_mask,
-                            vec![0u64; n],
-                            vec![0u64; n],
-                            cached.any_mask != 0,
+                            vec![M::ZERO; n],
+                            vec![M::ZERO; n],
+                            cached.tuples,
                         )
                     } else {
                         // Cache miss: compute and store
-                        let mut per_pos = vec![0u64; cells.len()];
-                        let mut any_mask = 0u64;
+                        let mut per_pos = vec![M::ZERO; cells.len()];
+                        let mut any_mask = M::ZERO;
+                        let mut tuples = 0;
                         enumerate_cage_tuples(
                             cage,
                             &cells,
@@ -1161,29 +3673,35 @@ fn apply_cage_deduction(
                             &mut Vec::new(),
                             &mut per_pos,
                             &mut any_mask,
+                            &mut tuples,
+                            &mut budget,
                         );
 
-                        // Store in cache before returning
-                        state.tuple_cache.insert(
-                            cache_key,
-                            CachedTupleResult {
-                                per_pos: per_pos.clone(),
-                                any_mask,
-                            },
-                        );
+                        // Store in cache before returning (cut-off enumerations are incomplete)
+                        if budget > 0 {
+                            state.tuple_cache.insert(
+                                cache_key,
+                                CachedTupleResult {
+                                    per_pos: per_pos.clone(),
+                                    any_mask,
+                                    tuples,
+                                },
+                            );
+                        }
 
                         (
                             per_pos,
                             any_mask,
-                            vec![0u64; n],
-                            vec![0u64; n],
-                            any_mask != 0,
+                            vec![M::ZERO; n],
+                            vec![M::ZERO; n],
+                            tuples,
                         )
                     }
                 } else {
                     // For small puzzles (n <= 5), skip cache and just compute
-                    let mut per_pos = vec![0u64; cells.len()];
-                    let mut any_mask = 0u64;
+                    let mut per_pos = vec![M::ZERO; cells.len()];
+                    let mut any_mask = M::ZERO;
+                    let mut tuples = 0;
                     enumerate_cage_tuples(
                         cage,
                         &cells,
@@ -1193,18 +3711,27 @@ fn apply_cage_deduction(
                         &mut Vec::new(),
                         &mut per_pos,
                         &mut any_mask,
+                        &mut tuples,
+                        &mut budget,
                     );
 
                     (
                         per_pos,
                         any_mask,
-                        vec![0u64; n],
-                        vec![0u64; n],
-                        any_mask != 0,
+                        vec![M::ZERO; n],
+                        vec![M::ZERO; n],
+                        tuples,
                     )
                 }
             };
 
+            if budget == 0 {
+                // Enumeration hit the work cap, so its supports are incomplete.
+                state.degraded_cages += 1;
+                bounds_filter_domains(cage, &cells, domains);
+                return Ok(TUPLES_UNKNOWN);
+            }
+
             if tier == DeductionTier::Easy {
                 for &idx in &cells {
                     domains[idx] &= any_mask;
@@ -1215,13 +3742,13 @@ fn apply_cage_deduction(
                 }
             }
 
-            if tier == DeductionTier::Hard && found {
+            if tier == DeductionTier::Hard && found > 0 {
                 let mut in_cage = vec![false; a];
                 for &idx in &cells {
                     in_cage[idx] = true;
                 }
                 for (r, must) in must_row.into_iter().enumerate() {
-                    if must == 0 {
+                    if must.is_empty() {
                         continue;
                     }
                     for c in 0..n {
@@ -1232,7 +3759,7 @@ fn apply_cage_deduction(
                     }
                 }
                 for (c, must) in must_col.into_iter().enumerate() {
-                    if must == 0 {
+                    if must.is_empty() {
                         continue;
                     }
                     for r in 0..n {
@@ -1243,25 +3770,23 @@ fn apply_cage_deduction(
                     }
                 }
             }
-            return Ok(());
+            Ok(found)
         }
-        _ => {}
     }
-
-    Ok(())
 }
 
+/// [`apply_cage_deduction`] with its scratch vectors in `bump`.
 #[cfg(feature = "alloc-bumpalo")]
 #[instrument(skip(bump, _puzzle, rules, state, cage, domains), fields(op = ?cage.op, cells = cage.cells.len()), level = "debug")]
-fn apply_cage_deduction_with_bump(
+fn apply_cage_deduction_with_bump<M: Mask>(
     bump: &Bump,
     _puzzle: &Puzzle,
     rules: Ruleset,
-    state: &mut State,
+    state: &mut State<M>,
     cage: &Cage,
     tier: DeductionTier,
-    domains: &mut [u64],
-) -> Result<(), SolveError> {
+    domains: &mut [M],
+) -> Result<u32, SolveError> {
     // Use bump-allocated temporary vectors to reduce per-iteration heap churn in propagation.
     let n = state.n as usize;
     let a = n * n;
@@ -1273,135 +3798,53 @@ fn apply_cage_deduction_with_bump(
     match cage.op {
         Op::Eq => {
             let idx = cells[0];
-            domains[idx] &= 1u64 << (cage.target as u32);
-            return Ok(());
+            domains[idx] &= M::bit(cage.target as u8);
+            Ok(u32::from(!domains[idx].is_empty()))
         }
         Op::Sub | Op::Div if rules.sub_div_two_cell_only && cage.cells.len() != 2 => {
-            return Err(CoreError::SubDivMustBeTwoCell.into());
+            Err(CoreError::SubDivMustBeTwoCell.into())
         }
         Op::Sub | Op::Div if cage.cells.len() == 2 => {
-            // Delegate to the existing implementation, but avoid allocating the `cells` Vec on the heap.
-            let a_idx = cells[0];
-            let b_idx = cells[1];
-            let a_dom = domains[a_idx];
-            let b_dom = domains[b_idx];
-            let mut a_ok = 0u64;
-            let mut b_ok = 0u64;
-            let mut found = false;
-            let mut must_row: bumpalo::collections::Vec<Option<u64>> =
-                bumpalo::collections::Vec::with_capacity_in(n, bump);
-            let mut must_col: bumpalo::collections::Vec<Option<u64>> =
-                bumpalo::collections::Vec::with_capacity_in(n, bump);
-            must_row.resize(n, None);
-            must_col.resize(n, None);
-            let coords = [(a_idx / n, a_idx % n), (b_idx / n, b_idx % n)];
-            for av in domain_iter(a_dom) {
-                for bv in domain_iter(b_dom) {
-                    let ok = match cage.op {
-                        Op::Sub => (av as i32 - bv as i32).abs() == cage.target,
-                        Op::Div => {
-                            let (num, den) = if av >= bv { (av, bv) } else { (bv, av) };
-                            den != 0 && (num as i32) == (den as i32).saturating_mul(cage.target)
-                        }
-                        _ => false,
-                    };
-                    if ok {
-                        found = true;
-                        a_ok |= 1u64 << (av as u32);
-                        b_ok |= 1u64 << (bv as u32);
-
-                        if tier == DeductionTier::Hard {
-                            let (ra, ca) = coords[0];
-                            let (rb, cb) = coords[1];
-                            let a_bit = 1u64 << (av as u32);
-                            let b_bit = 1u64 << (bv as u32);
-
-                            if ra == rb {
-                                let bits = a_bit | b_bit;
-                                must_row[ra] = Some(match must_row[ra] {
-                                    None => bits,
-                                    Some(m) => m & bits,
-                                });
-                            } else {
-                                must_row[ra] = Some(match must_row[ra] {
-                                    None => a_bit,
-                                    Some(m) => m & a_bit,
-                                });
-                                must_row[rb] = Some(match must_row[rb] {
-                                    None => b_bit,
-                                    Some(m) => m & b_bit,
-                                });
-                            }
-
-                            if ca == cb {
-                                let bits = a_bit | b_bit;
-                                must_col[ca] = Some(match must_col[ca] {
-                                    None => bits,
-                                    Some(m) => m & bits,
-                                });
-                            } else {
-                                must_col[ca] = Some(match must_col[ca] {
-                                    None => a_bit,
-                                    Some(m) => m & a_bit,
-                                });
-                                must_col[cb] = Some(match must_col[cb] {
-                                    None => b_bit,
-                                    Some(m) => m & b_bit,
-                                });
-                            }
-                        }
-                    }
-                }
-            }
-            domains[a_idx] &= a_ok;
-            domains[b_idx] &= b_ok;
-
-            if tier == DeductionTier::Hard && found {
-                for (r, maybe_must) in must_row.into_iter().enumerate() {
-                    let Some(must) = maybe_must else { continue };
-                    for c in 0..n {
-                        let idx = r * n + c;
-                        if idx != a_idx && idx != b_idx {
-                            domains[idx] &= !must;
-                        }
-                    }
-                }
-                for (c, maybe_must) in must_col.into_iter().enumerate() {
-                    let Some(must) = maybe_must else { continue };
-                    for r in 0..n {
-                        let idx = r * n + c;
-                        if idx != a_idx && idx != b_idx {
-                            domains[idx] &= !must;
-                        }
-                    }
-                }
-            }
-
-            return Ok(());
+            let columns = state.enforce_columns;
+            Ok(apply_sub_div_pair(
+                n, cage, cells[0], cells[1], tier, columns, domains,
+            ))
         }
-        Op::Add | Op::Mul => {
+        Op::Add | Op::Mul if is_fast_add_mul_pair(cage, n, &state.options) => {
+            let columns = state.enforce_columns;
+            Ok(apply_add_mul_pair(
+                n, cage, cells[0], cells[1], tier, columns, domains,
+            ))
+        }
+        // Add/Mul, plus n-ary Sub/Div (only reachable when the ruleset allows them).
+        Op::Add | Op::Mul | Op::Sub | Op::Div => {
+            if let Some(tuples) = apply_shared_tuples(state, cage, &cells, tier, domains) {
+                return Ok(tuples);
+            }
+            prefilter_mul_domains(cage, &cells, domains);
             let mut coords = bumpalo::collections::Vec::with_capacity_in(cells.len(), bump);
             for &idx in cells.iter() {
-                coords.push((idx / n, idx % n));
+                coords.push(state.cell_lines(idx));
             }
+            let mut budget = state.options.max_tuples_per_cage;
 
             if tier == DeductionTier::Hard {
                 let mut per_pos = bumpalo::collections::Vec::with_capacity_in(cells.len(), bump);
-                per_pos.resize(cells.len(), 0u64);
-                let mut any_mask = 0u64;
-                let mut must_row: bumpalo::collections::Vec<Option<u64>> =
+                per_pos.resize(cells.len(), M::ZERO);
+                let mut any_mask = M::ZERO;
+                let mut must_row: bumpalo::collections::Vec<Option<M>> =
                     bumpalo::collections::Vec::with_capacity_in(n, bump);
-                let mut must_col: bumpalo::collections::Vec<Option<u64>> =
+                let mut must_col: bumpalo::collections::Vec<Option<M>> =
                     bumpalo::collections::Vec::with_capacity_in(n, bump);
                 must_row.resize(n, None);
                 must_col.resize(n, None);
-                let mut found = false;
+                let mut found = 0;
 
                 let mut chosen = bumpalo::collections::Vec::with_capacity_in(cells.len(), bump);
                 let mut row_bits = bumpalo::collections::Vec::with_capacity_in(n, bump);
                 let mut col_bits = bumpalo::collections::Vec::with_capacity_in(n, bump);
-                row_bits.resize(n, 0u64);
-                col_bits.resize(n, 0u64);
+                row_bits.resize(n, M::ZERO);
+                col_bits.resize(n, M::ZERO);
 
                 enumerate_cage_tuples_collect_bump(
                     n,
@@ -1418,13 +3861,21 @@ fn apply_cage_deduction_with_bump(
                     &mut found,
                     &mut row_bits,
                     &mut col_bits,
+                    &mut budget,
                 );
 
+                if budget == 0 {
+                    // Enumeration hit the work cap, so its supports are incomplete.
+                    state.degraded_cages += 1;
+                    bounds_filter_domains(cage, &cells, domains);
+                    return Ok(TUPLES_UNKNOWN);
+                }
+
                 for (pos, &idx) in cells.iter().enumerate() {
                     domains[idx] &= per_pos[pos];
                 }
 
-                if found {
+                if found > 0 {
                     let mut in_cage = bumpalo::collections::Vec::with_capacity_in(a, bump);
                     in_cage.resize(a, false);
                     for &idx in &cells {
@@ -1433,7 +3884,7 @@ fn apply_cage_deduction_with_bump(
 
                     for (r, maybe_must) in must_row.into_iter().enumerate() {
                         let Some(must) = maybe_must else { continue };
-                        if must == 0 {
+                        if must.is_empty() {
                             continue;
                         }
                         for c in 0..n {
@@ -1445,7 +3896,7 @@ fn apply_cage_deduction_with_bump(
                     }
                     for (c, maybe_must) in must_col.into_iter().enumerate() {
                         let Some(must) = maybe_must else { continue };
-                        if must == 0 {
+                        if must.is_empty() {
                             continue;
                         }
                         for r in 0..n {
@@ -1457,13 +3908,14 @@ fn apply_cage_deduction_with_bump(
                     }
                 }
 
-                return Ok(());
+                return Ok(found);
             }
 
             // Easy/Normal tier: no "must" elimination needed.
             let mut per_pos = bumpalo::collections::Vec::with_capacity_in(cells.len(), bump);
-            per_pos.resize(cells.len(), 0u64);
-            let mut any_mask = 0u64;
+            per_pos.resize(cells.len(), M::ZERO);
+            let mut any_mask = M::ZERO;
+            let mut tuples = 0;
             let mut chosen = bumpalo::collections::Vec::with_capacity_in(cells.len(), bump);
             enumerate_cage_tuples_bump(
                 cage,
@@ -1474,8 +3926,17 @@ fn apply_cage_deduction_with_bump(
                 &mut chosen,
                 &mut per_pos,
                 &mut any_mask,
+                &mut tuples,
+                &mut budget,
             );
 
+            if budget == 0 {
+                // Enumeration hit the work cap, so its supports are incomplete.
+                state.degraded_cages += 1;
+                bounds_filter_domains(cage, &cells, domains);
+                return Ok(TUPLES_UNKNOWN);
+            }
+
             if tier == DeductionTier::Easy {
                 for &idx in &cells {
                     domains[idx] &= any_mask;
@@ -1485,32 +3946,35 @@ fn apply_cage_deduction_with_bump(
                     domains[idx] &= per_pos[pos];
                 }
             }
-
-            return Ok(());
+            Ok(tuples)
         }
-        _ => {}
     }
-
-    Ok(())
 }
 
 #[cfg(feature = "alloc-bumpalo")]
 #[allow(clippy::too_many_arguments)]
-fn enumerate_cage_tuples_bump(
+fn enumerate_cage_tuples_bump<M: Mask>(
     cage: &Cage,
     cells: &[usize],
     coords: &[(usize, usize)],
-    domains: &[u64],
+    domains: &[M],
     pos: usize,
     chosen: &mut bumpalo::collections::Vec<u8>,
-    per_pos: &mut [u64],
-    any_mask: &mut u64,
+    per_pos: &mut [M],
+    any_mask: &mut M,
+    found: &mut u32,
+    budget: &mut usize,
 ) {
+    if *budget == 0 {
+        return;
+    }
+    *budget -= 1;
     if pos == cells.len() {
         if cage_tuple_satisfies(cage, chosen) {
+            *found = found.saturating_add(1);
             for (i, &v) in chosen.iter().enumerate() {
-                per_pos[i] |= 1u64 << (v as u32);
-                *any_mask |= 1u64 << (v as u32);
+                per_pos[i] |= M::bit(v);
+                *any_mask |= M::bit(v);
             }
         }
         return;
@@ -1524,7 +3988,7 @@ fn enumerate_cage_tuples_bump(
         chosen.push(v);
 
         if cage.op == Op::Add {
-            let sum: i32 = chosen.iter().map(|&x| x as i32).sum();
+            let sum: i64 = chosen.iter().map(|&x| x as i64).sum();
             if sum <= cage.target {
                 enumerate_cage_tuples_bump(
                     cage,
@@ -1535,12 +3999,14 @@ fn enumerate_cage_tuples_bump(
                     chosen,
                     per_pos,
                     any_mask,
+                    found,
+                    budget,
                 );
             }
         } else if cage.op == Op::Mul {
-            let mut prod: i32 = 1;
+            let mut prod: i64 = 1;
             for &x in chosen.iter() {
-                prod = prod.saturating_mul(x as i32);
+                prod = prod.saturating_mul(x as i64);
             }
             if prod != 0 && cage.target % prod == 0 {
                 enumerate_cage_tuples_bump(
@@ -1552,6 +4018,8 @@ fn enumerate_cage_tuples_bump(
                     chosen,
                     per_pos,
                     any_mask,
+                    found,
+                    budget,
                 );
             }
         } else {
@@ -1564,6 +4032,8 @@ fn enumerate_cage_tuples_bump(
                 chosen,
                 per_pos,
                 any_mask,
+                found,
+                budget,
             );
         }
 
@@ -1573,38 +4043,45 @@ fn enumerate_cage_tuples_bump(
 
 #[cfg(feature = "alloc-bumpalo")]
 #[allow(clippy::too_many_arguments)]
-fn enumerate_cage_tuples_collect_bump(
+fn enumerate_cage_tuples_collect_bump<M: Mask>(
     n: usize,
     cage: &Cage,
     cells: &[usize],
     coords: &[(usize, usize)],
-    domains: &[u64],
+    domains: &[M],
     pos: usize,
     chosen: &mut bumpalo::collections::Vec<u8>,
-    per_pos: &mut [u64],
-    any_mask: &mut u64,
-    must_row: &mut [Option<u64>],
-    must_col: &mut [Option<u64>],
-    found: &mut bool,
-    row_bits: &mut [u64],
-    col_bits: &mut [u64],
+    per_pos: &mut [M],
+    any_mask: &mut M,
+    must_row: &mut [Option<M>],
+    must_col: &mut [Option<M>],
+    found: &mut u32,
+    row_bits: &mut [M],
+    col_bits: &mut [M],
+    budget: &mut usize,
 ) {
+    if *budget == 0 {
+        return;
+    }
+    *budget -= 1;
     if pos == cells.len() {
         if cage_tuple_satisfies(cage, chosen) {
-            *found = true;
+            *found = found.saturating_add(1);
             for (i, &v) in chosen.iter().enumerate() {
-                per_pos[i] |= 1u64 << (v as u32);
-                *any_mask |= 1u64 << (v as u32);
+                per_pos[i] |= M::bit(v);
+                *any_mask |= M::bit(v);
             }
 
-            row_bits.fill(0u64);
-            col_bits.fill(0u64);
+            row_bits.fill(M::ZERO);
+            col_bits.fill(M::ZERO);
             for (i, &(r, c)) in coords.iter().enumerate() {
-                row_bits[r] |= 1u64 << (chosen[i] as u32);
-                col_bits[c] |= 1u64 << (chosen[i] as u32);
+                row_bits[r] |= M::bit(chosen[i]);
+                if c != FREE_COLUMN {
+                    col_bits[c] |= M::bit(chosen[i]);
+                }
             }
             for r in 0..n {
-                if row_bits[r] != 0 {
+                if !row_bits[r].is_empty() {
                     must_row[r] = Some(match must_row[r] {
                         None => row_bits[r],
                         Some(m) => m & row_bits[r],
@@ -1612,7 +4089,7 @@ fn enumerate_cage_tuples_collect_bump(
                 }
             }
             for c in 0..n {
-                if col_bits[c] != 0 {
+                if !col_bits[c].is_empty() {
                     must_col[c] = Some(match must_col[c] {
                         None => col_bits[c],
                         Some(m) => m & col_bits[c],
@@ -1631,7 +4108,7 @@ fn enumerate_cage_tuples_collect_bump(
         chosen.push(v);
 
         if cage.op == Op::Add {
-            let sum: i32 = chosen.iter().map(|&x| x as i32).sum();
+            let sum: i64 = chosen.iter().map(|&x| x as i64).sum();
             if sum <= cage.target {
                 enumerate_cage_tuples_collect_bump(
                     n,
@@ -1648,12 +4125,13 @@ fn enumerate_cage_tuples_collect_bump(
                     found,
                     row_bits,
                     col_bits,
+                    budget,
                 );
             }
         } else if cage.op == Op::Mul {
-            let mut prod: i32 = 1;
+            let mut prod: i64 = 1;
             for &x in chosen.iter() {
-                prod = prod.saturating_mul(x as i32);
+                prod = prod.saturating_mul(x as i64);
             }
             if prod != 0 && cage.target % prod == 0 {
                 enumerate_cage_tuples_collect_bump(
@@ -1671,6 +4149,7 @@ fn enumerate_cage_tuples_collect_bump(
                     found,
                     row_bits,
                     col_bits,
+                    budget,
                 );
             }
         } else {
@@ -1689,6 +4168,7 @@ fn enumerate_cage_tuples_collect_bump(
                 found,
                 row_bits,
                 col_bits,
+                budget,
             );
         }
 
@@ -1698,46 +4178,56 @@ fn enumerate_cage_tuples_collect_bump(
 
 #[cfg(not(feature = "alloc-bumpalo"))]
 #[allow(clippy::too_many_arguments)]
-#[instrument(skip(cage, cells, coords, domains, chosen, per_pos, any_mask), fields(op = ?cage.op, pos, cells_len = cells.len()), level = "debug")]
-fn enumerate_cage_tuples(
+#[instrument(skip(cage, cells, coords, domains, chosen, per_pos, any_mask, found, budget), fields(op = ?cage.op, pos, cells_len = cells.len()), level = "debug")]
+fn enumerate_cage_tuples<M: Mask>(
     cage: &Cage,
     cells: &[usize],
     coords: &[(usize, usize)],
-    domains: &[u64],
+    domains: &[M],
     pos: usize,
     chosen: &mut Vec<u8>,
-    per_pos: &mut [u64],
-    any_mask: &mut u64,
+    per_pos: &mut [M],
+    any_mask: &mut M,
+    found: &mut u32,
+    budget: &mut usize,
 ) {
     // Phase 6.1 optimization: Use running sum/product instead of recomputing from scratch
     enumerate_cage_tuples_impl(
-        cage, cells, coords, domains, pos, chosen, per_pos, any_mask,
-        0i32, // running_sum (initialized to 0)
-        1i32, // running_prod (initialized to 1)
+        cage, cells, coords, domains, pos, chosen, per_pos, any_mask, found,
+        0i64, // running_sum (initialized to 0)
+        1i64, // running_prod (initialized to 1)
+        budget,
     );
 }
 
 #[cfg(not(feature = "alloc-bumpalo"))]
 #[inline]
 #[allow(clippy::too_many_arguments)]
-fn enumerate_cage_tuples_impl(
+fn enumerate_cage_tuples_impl<M: Mask>(
     cage: &Cage,
     cells: &[usize],
     coords: &[(usize, usize)],
-    domains: &[u64],
+    domains: &[M],
     pos: usize,
     chosen: &mut Vec<u8>,
-    per_pos: &mut [u64],
-    any_mask: &mut u64,
-    running_sum: i32,  // Phase 6.1: accumulated sum
-    running_prod: i32, // Phase 6.1: accumulated product
+    per_pos: &mut [M],
+    any_mask: &mut M,
+    found: &mut u32,
+    running_sum: i64,  // Phase 6.1: accumulated sum
+    running_prod: i64, // Phase 6.1: accumulated product
+    budget: &mut usize,
 ) {
+    if *budget == 0 {
+        return;
+    }
+    *budget -= 1;
     if pos == cells.len() {
         // Phase 6.1: Use running values instead of recomputing
         if cage_tuple_satisfies_with_values(cage, chosen, running_sum, running_prod) {
+            *found = found.saturating_add(1);
             for (i, &v) in chosen.iter().enumerate() {
-                per_pos[i] |= 1u64 << (v as u32);
-                *any_mask |= 1u64 << (v as u32);
+                per_pos[i] |= M::bit(v);
+                *any_mask |= M::bit(v);
             }
         }
         return;
@@ -1752,7 +4242,7 @@ fn enumerate_cage_tuples_impl(
 
         if cage.op == Op::Add {
             // Phase 6.1: Use running_sum + v instead of recomputing entire sum
-            let new_sum = running_sum + (v as i32);
+            let new_sum = running_sum + (v as i64);
             if new_sum <= cage.target {
                 enumerate_cage_tuples_impl(
                     cage,
@@ -1763,13 +4253,15 @@ fn enumerate_cage_tuples_impl(
                     chosen,
                     per_pos,
                     any_mask,
+                    found,
                     new_sum, // Pass incremental sum
                     1,       // product not used for Add
+                    budget,
                 );
             }
         } else if cage.op == Op::Mul {
             // Phase 6.1: Use running_prod * v instead of recomputing entire product
-            let new_prod = running_prod.saturating_mul(v as i32);
+            let new_prod = running_prod.saturating_mul(v as i64);
             if new_prod != 0 && cage.target % new_prod == 0 {
                 enumerate_cage_tuples_impl(
                     cage,
@@ -1780,8 +4272,10 @@ fn enumerate_cage_tuples_impl(
                     chosen,
                     per_pos,
                     any_mask,
+                    found,
                     0,        // sum not used for Mul
                     new_prod, // Pass incremental product
+                    budget,
                 );
             }
         } else {
@@ -1794,8 +4288,10 @@ fn enumerate_cage_tuples_impl(
                 chosen,
                 per_pos,
                 any_mask,
+                found,
                 running_sum, // Pass through for other operations
                 running_prod,
+                budget,
             );
         }
 
@@ -1806,7 +4302,7 @@ fn enumerate_cage_tuples_impl(
 /// Phase 6.1: Helper function that validates cage tuple using pre-computed running values
 #[cfg(not(feature = "alloc-bumpalo"))]
 #[inline]
-fn cage_tuple_satisfies_with_values(cage: &Cage, chosen: &[u8], sum: i32, prod: i32) -> bool {
+fn cage_tuple_satisfies_with_values(cage: &Cage, chosen: &[u8], sum: i64, prod: i64) -> bool {
     match cage.op {
         Op::Add => sum == cage.target,
         Op::Sub => {
@@ -1819,27 +4315,24 @@ fn cage_tuple_satisfies_with_values(cage: &Cage, chosen: &[u8], sum: i32, prod:
             // For division, we need to check all permutations
             cage_tuple_satisfies(cage, chosen)
         }
-        Op::Eq => {
-            // For Eq, all values must be the same
-            let first = chosen[0];
-            chosen.iter().all(|&v| v == first)
-        }
+        Op::Eq => cage_tuple_satisfies(cage, chosen),
     }
 }
 
 #[cfg(not(feature = "alloc-bumpalo"))]
-fn enumerate_cage_tuples_with_must(
+fn enumerate_cage_tuples_with_must<M: Mask>(
     n: usize,
     cage: &Cage,
     cells: &[usize],
     coords: &[(usize, usize)],
-    domains: &[u64],
-) -> (Vec<u64>, u64, Vec<u64>, Vec<u64>, bool) {
-    let mut per_pos = vec![0u64; cells.len()];
-    let mut any_mask = 0u64;
-    let mut must_row: Vec<Option<u64>> = vec![None; n];
-    let mut must_col: Vec<Option<u64>> = vec![None; n];
-    let mut found = false;
+    domains: &[M],
+    budget: &mut usize,
+) -> (Vec<M>, M, Vec<M>, Vec<M>, u32) {
+    let mut per_pos = vec![M::ZERO; cells.len()];
+    let mut any_mask = M::ZERO;
+    let mut must_row: Vec<Option<M>> = vec![None; n];
+    let mut must_col: Vec<Option<M>> = vec![None; n];
+    let mut found = 0;
 
     enumerate_cage_tuples_collect(
         n,
@@ -1854,74 +4347,84 @@ fn enumerate_cage_tuples_with_must(
         &mut must_row,
         &mut must_col,
         &mut found,
+        budget,
     );
 
-    let must_row = must_row.into_iter().map(|m| m.unwrap_or(0)).collect();
-    let must_col = must_col.into_iter().map(|m| m.unwrap_or(0)).collect();
+    let must_row = must_row.into_iter().map(|m| m.unwrap_or(M::ZERO)).collect();
+    let must_col = must_col.into_iter().map(|m| m.unwrap_or(M::ZERO)).collect();
     (per_pos, any_mask, must_row, must_col, found)
 }
 
 #[cfg(not(feature = "alloc-bumpalo"))]
 #[allow(clippy::too_many_arguments)]
-#[instrument(skip(cage, cells, coords, domains, chosen, per_pos, any_mask, must_row, must_col, found), fields(op = ?cage.op, pos, cells_len = cells.len()), level = "debug")]
-fn enumerate_cage_tuples_collect(
+#[instrument(skip(cage, cells, coords, domains, chosen, per_pos, any_mask, must_row, must_col, found, budget), fields(op = ?cage.op, pos, cells_len = cells.len()), level = "debug")]
+fn enumerate_cage_tuples_collect<M: Mask>(
     n: usize,
     cage: &Cage,
     cells: &[usize],
     coords: &[(usize, usize)],
-    domains: &[u64],
+    domains: &[M],
     pos: usize,
     chosen: &mut Vec<u8>,
-    per_pos: &mut [u64],
-    any_mask: &mut u64,
-    must_row: &mut [Option<u64>],
-    must_col: &mut [Option<u64>],
-    found: &mut bool,
+    per_pos: &mut [M],
+    any_mask: &mut M,
+    must_row: &mut [Option<M>],
+    must_col: &mut [Option<M>],
+    found: &mut u32,
+    budget: &mut usize,
 ) {
     // Phase 6.1 optimization: Use running sum/product instead of recomputing from scratch
     enumerate_cage_tuples_collect_impl(
         n, cage, cells, coords, domains, pos, chosen, per_pos, any_mask, must_row, must_col, found,
-        0i32, // running_sum (initialized to 0)
-        1i32, // running_prod (initialized to 1)
+        0i64, // running_sum (initialized to 0)
+        1i64, // running_prod (initialized to 1)
+        budget,
     );
 }
 
 #[cfg(not(feature = "alloc-bumpalo"))]
 #[inline]
 #[allow(clippy::too_many_arguments)]
-fn enumerate_cage_tuples_collect_impl(
+fn enumerate_cage_tuples_collect_impl<M: Mask>(
     n: usize,
     cage: &Cage,
     cells: &[usize],
     coords: &[(usize, usize)],
-    domains: &[u64],
+    domains: &[M],
     pos: usize,
     chosen: &mut Vec<u8>,
-    per_pos: &mut [u64],
-    any_mask: &mut u64,
-    must_row: &mut [Option<u64>],
-    must_col: &mut [Option<u64>],
-    found: &mut bool,
-    running_sum: i32,  // Phase 6.1: accumulated sum
-    running_prod: i32, // Phase 6.1: accumulated product
+    per_pos: &mut [M],
+    any_mask: &mut M,
+    must_row: &mut [Option<M>],
+    must_col: &mut [Option<M>],
+    found: &mut u32,
+    running_sum: i64,  // Phase 6.1: accumulated sum
+    running_prod: i64, // Phase 6.1: accumulated product
+    budget: &mut usize,
 ) {
+    if *budget == 0 {
+        return;
+    }
+    *budget -= 1;
     if pos == cells.len() {
         // Phase 6.1: Use running values instead of recomputing
         if cage_tuple_satisfies_with_values(cage, chosen, running_sum, running_prod) {
-            *found = true;
+            *found = found.saturating_add(1);
             for (i, &v) in chosen.iter().enumerate() {
-                per_pos[i] |= 1u64 << (v as u32);
-                *any_mask |= 1u64 << (v as u32);
+                per_pos[i] |= M::bit(v);
+                *any_mask |= M::bit(v);
             }
 
-            let mut row_bits = vec![0u64; n];
-            let mut col_bits = vec![0u64; n];
+            let mut row_bits = vec![M::ZERO; n];
+            let mut col_bits = vec![M::ZERO; n];
             for (i, &(r, c)) in coords.iter().enumerate() {
-                row_bits[r] |= 1u64 << (chosen[i] as u32);
-                col_bits[c] |= 1u64 << (chosen[i] as u32);
+                row_bits[r] |= M::bit(chosen[i]);
+                if c != FREE_COLUMN {
+                    col_bits[c] |= M::bit(chosen[i]);
+                }
             }
             for r in 0..n {
-                if row_bits[r] != 0 {
+                if !row_bits[r].is_empty() {
                     must_row[r] = Some(match must_row[r] {
                         None => row_bits[r],
                         Some(m) => m & row_bits[r],
@@ -1929,7 +4432,7 @@ fn enumerate_cage_tuples_collect_impl(
                 }
             }
             for c in 0..n {
-                if col_bits[c] != 0 {
+                if !col_bits[c].is_empty() {
                     must_col[c] = Some(match must_col[c] {
                         None => col_bits[c],
                         Some(m) => m & col_bits[c],
@@ -1949,7 +4452,7 @@ fn enumerate_cage_tuples_collect_impl(
 
         if cage.op == Op::Add {
             // Phase 6.1: Use running_sum + v instead of recomputing entire sum
-            let new_sum = running_sum + (v as i32);
+            let new_sum = running_sum + (v as i64);
             if new_sum <= cage.target {
                 enumerate_cage_tuples_collect_impl(
                     n,
@@ -1966,11 +4469,12 @@ fn enumerate_cage_tuples_collect_impl(
                     found,
                     new_sum, // Pass incremental sum
                     1,       // product not used for Add
+                    budget,
                 );
             }
         } else if cage.op == Op::Mul {
             // Phase 6.1: Use running_prod * v instead of recomputing entire product
-            let new_prod = running_prod.saturating_mul(v as i32);
+            let new_prod = running_prod.saturating_mul(v as i64);
             if new_prod != 0 && cage.target % new_prod == 0 {
                 enumerate_cage_tuples_collect_impl(
                     n,
@@ -1987,6 +4491,7 @@ fn enumerate_cage_tuples_collect_impl(
                     found,
                     0,        // sum not used for Mul
                     new_prod, // Pass incremental product
+                    budget,
                 );
             }
         } else {
@@ -2005,6 +4510,7 @@ fn enumerate_cage_tuples_collect_impl(
                 found,
                 running_sum, // Pass through for other operations
                 running_prod,
+                budget,
             );
         }
 
@@ -2012,34 +4518,36 @@ fn enumerate_cage_tuples_collect_impl(
     }
 }
 
-fn cage_tuple_satisfies(cage: &Cage, values: &[u8]) -> bool {
-    match cage.op {
-        Op::Add => values.iter().map(|&v| v as i32).sum::<i32>() == cage.target,
-        Op::Mul => values.iter().map(|&v| v as i32).product::<i32>() == cage.target,
-        _ => false,
-    }
+pub(crate) fn cage_tuple_satisfies(cage: &Cage, values: &[u8]) -> bool {
+    op_satisfied(cage.op, cage.target, values)
 }
 
-fn violates_in_cage_rowcol(coords: &[(usize, usize)], chosen: &[u8], pos: usize, v: u8) -> bool {
+/// Whether `v` at `pos` repeats an earlier `chosen` digit of the same row or column, where a
+/// [`FREE_COLUMN`] is shared with no other cell.
+pub(crate) fn violates_in_cage_rowcol(
+    coords: &[(usize, usize)],
+    chosen: &[u8],
+    pos: usize,
+    v: u8,
+) -> bool {
     let (r, c) = coords[pos];
     for (i, &prev) in chosen.iter().enumerate() {
         let (pr, pc) = coords[i];
-        if (pr == r || pc == c) && prev == v {
+        if (pr == r || (pc == c && c != FREE_COLUMN)) && prev == v {
             return true;
         }
     }
     false
 }
 
-#[instrument(skip(puzzle, rules, state, cage), fields(op = ?cage.op, cells = cage.cells.len()), level = "debug")]
-fn cage_feasible(
-    puzzle: &Puzzle,
+#[instrument(skip(rules, state, cage), fields(op = ?cage.op, cells = cage.cells.len()), level = "debug")]
+fn cage_feasible<M: Mask>(
     rules: Ruleset,
-    state: &State,
+    state: &State<M>,
     cage: &Cage,
 ) -> Result<bool, SolveError> {
     let n = state.n as usize;
-    let mut assigned: Vec<i32> = Vec::new();
+    let mut assigned: Vec<i64> = Vec::new();
     let mut unassigned: Vec<usize> = Vec::new();
 
     for cell in &cage.cells {
@@ -2048,7 +4556,7 @@ fn cage_feasible(
         if v == 0 {
             unassigned.push(idx);
         } else {
-            assigned.push(v as i32);
+            assigned.push(v as i64);
         }
     }
 
@@ -2074,68 +4582,59 @@ fn cage_feasible(
     }
 
     if unassigned.is_empty() {
-        return Ok(cage_satisfied(cage, &assigned));
+        return Ok(op_satisfied(cage.op, cage.target, &assigned));
     }
 
     match cage.op {
-        Op::Sub => {
-            // Two-cell only: check existence against remaining domain.
-            let (a_idx, b_idx) = (cage.cells[0].0 as usize, cage.cells[1].0 as usize);
-            Ok(two_cell_sub_feasible(
-                puzzle,
-                state,
-                a_idx,
-                b_idx,
-                cage.target,
-            )?)
+        Op::Sub | Op::Div if cage.cells.len() != 2 => {
+            Ok(n_ary_sub_div_feasible(state, cage, &assigned, &unassigned))
         }
-        Op::Div => {
+        Op::Sub | Op::Div => {
+            // Two-cell only: check existence against remaining domain.
             let (a_idx, b_idx) = (cage.cells[0].0 as usize, cage.cells[1].0 as usize);
-            Ok(two_cell_div_feasible(
-                puzzle,
-                state,
-                a_idx,
-                b_idx,
-                cage.target,
-            )?)
+            Ok(two_cell_feasible(state, cage, a_idx, b_idx))
         }
         Op::Add => {
-            let sum_assigned: i32 = assigned.iter().sum();
+            let sum_assigned: i64 = assigned.iter().sum();
             if sum_assigned > cage.target {
                 return Ok(false);
             }
-            let mut min_remaining = 0i32;
-            let mut max_remaining = 0i32;
+            let mut min_remaining = 0i64;
+            let mut max_remaining = 0i64;
             for &idx in &unassigned {
                 let row = idx / n;
                 let col = idx % n;
-                let dom = domain_for_cell(puzzle, state, idx, row, col)?;
-                let (mn, mx) =
-                    domain_min_max(dom).ok_or(SolveError::Core(CoreError::TargetMustBeNonZero))?;
-                min_remaining += mn as i32;
-                max_remaining += mx as i32;
+                let dom = domain_for_cell(state, idx, row, col);
+                // An empty domain leaves no completion for this cage.
+                let Some((mn, mx)) = domain_min_max(dom) else {
+                    return Ok(false);
+                };
+                min_remaining += mn as i64;
+                max_remaining += mx as i64;
             }
             let t = cage.target;
             Ok(sum_assigned + min_remaining <= t && t <= sum_assigned + max_remaining)
         }
         Op::Mul => {
-            let mut prod_assigned: i32 = 1;
+            let mut prod_assigned: i64 = 1;
             for &v in &assigned {
                 prod_assigned = prod_assigned.saturating_mul(v);
             }
             if prod_assigned == 0 || cage.target % prod_assigned != 0 {
                 return Ok(false);
             }
-            let mut min_prod: i32 = 1;
-            let mut max_prod: i32 = 1;
+            let mut min_prod: i64 = 1;
+            let mut max_prod: i64 = 1;
             for &idx in &unassigned {
                 let row = idx / n;
                 let col = idx % n;
-                let dom = domain_for_cell(puzzle, state, idx, row, col)?;
-                let (mn, mx) =
-                    domain_min_max(dom).ok_or(SolveError::Core(CoreError::TargetMustBeNonZero))?;
-                min_prod = min_prod.saturating_mul(mn as i32);
-                max_prod = max_prod.saturating_mul(mx as i32);
+                let dom = domain_for_cell(state, idx, row, col);
+                // An empty domain leaves no completion for this cage.
+                let Some((mn, mx)) = domain_min_max(dom) else {
+                    return Ok(false);
+                };
+                min_prod = min_prod.saturating_mul(mn as i64);
+                max_prod = max_prod.saturating_mul(mx as i64);
             }
             let t = cage.target;
             Ok(prod_assigned.saturating_mul(min_prod) <= t
@@ -2145,364 +4644,1298 @@ fn cage_feasible(
     }
 }
 
-fn cage_satisfied(cage: &Cage, values: &[i32]) -> bool {
-    match cage.op {
-        Op::Eq => values.len() == 1 && values[0] == cage.target,
-        Op::Add => values.iter().sum::<i32>() == cage.target,
-        Op::Mul => values.iter().product::<i32>() == cage.target,
-        Op::Sub => values.len() == 2 && (values[0] - values[1]).abs() == cage.target,
-        Op::Div => {
-            if values.len() != 2 {
-                return false;
+/// Upper bound on completions `n_ary_sub_div_feasible` will enumerate before giving up and
+/// reporting the cage as feasible (the full check still runs once the cage is filled).
+const N_ARY_FEASIBILITY_BUDGET: usize = 4096;
+
+/// Feasibility for Sub/Div cages with other than two cells (permissive rulesets only).
+///
+/// The largest value is only known once every cell is filled, so there is no cheap interval
+/// test; instead search for one completion from the current domains.
+fn n_ary_sub_div_feasible<M: Mask>(
+    state: &State<M>,
+    cage: &Cage,
+    assigned: &[i64],
+    unassigned: &[usize],
+) -> bool {
+    let n = state.n as usize;
+    let mut doms = Vec::with_capacity(unassigned.len());
+    let mut combos = 1usize;
+    for &idx in unassigned {
+        let dom = domain_for_cell(state, idx, idx / n, idx % n);
+        if dom.is_empty() {
+            return false;
+        }
+        combos = combos.saturating_mul(dom.count() as usize);
+        doms.push(dom);
+    }
+    if combos > N_ARY_FEASIBILITY_BUDGET {
+        return true;
+    }
+
+    fn rec<M: Mask>(cage: &Cage, doms: &[M], values: &mut Vec<i64>) -> bool {
+        let Some((&dom, rest)) = doms.split_first() else {
+            return op_satisfied(cage.op, cage.target, values);
+        };
+        for v in domain_iter(dom) {
+            values.push(v as i64);
+            let ok = rec(cage, rest, values);
+            values.pop();
+            if ok {
+                return true;
             }
-            let a = values[0].max(values[1]);
-            let b = values[0].min(values[1]);
-            b != 0 && a % b == 0 && a / b == cage.target
         }
+        false
     }
+
+    let mut values = assigned.to_vec();
+    rec(cage, &doms, &mut values)
 }
 
-fn two_cell_sub_feasible(
-    puzzle: &Puzzle,
-    state: &State,
-    a: usize,
-    b: usize,
-    target: i32,
-) -> Result<bool, CoreError> {
+/// Whether the two-cell `cage` on `a` and `b` still has a pair of values meeting its clue.
+fn two_cell_feasible<M: Mask>(state: &State<M>, cage: &Cage, a: usize, b: usize) -> bool {
     let n = state.n as usize;
-    let av = state.grid[a];
-    let bv = state.grid[b];
-    match (av, bv) {
-        (0, 0) => Ok(true),
+    let ok_pair = |x: u8, y: u8| op_pair_satisfied(cage.op, cage.target, x, y);
+    match (state.grid[a], state.grid[b]) {
+        (0, 0) => true,
         (x, 0) => {
-            let row = b / n;
-            let col = b % n;
-            let dom = domain_for_cell(puzzle, state, b, row, col)?;
-            Ok(domain_iter(dom).any(|y| (x as i32 - y as i32).abs() == target))
+            let dom = domain_for_cell(state, b, b / n, b % n);
+            domain_iter(dom).any(|y| ok_pair(x, y))
         }
         (0, y) => {
-            let row = a / n;
-            let col = a % n;
-            let dom = domain_for_cell(puzzle, state, a, row, col)?;
-            Ok(domain_iter(dom).any(|x| (x as i32 - y as i32).abs() == target))
+            let dom = domain_for_cell(state, a, a / n, a % n);
+            domain_iter(dom).any(|x| ok_pair(x, y))
+        }
+        (x, y) => ok_pair(x, y),
+    }
+}
+
+pub(crate) fn place<M: Mask>(state: &mut State<M>, row: usize, col: usize, d: u8) {
+    let idx = row * (state.n as usize) + col;
+    state.grid[idx] = d;
+    let n = state.n as usize;
+    state.masks[row] |= M::bit(d);
+    if state.enforce_columns {
+        state.masks[n + col] |= M::bit(d);
+    }
+
+    check_state_invariants(state, "place", Some(idx));
+}
+
+pub(crate) fn unplace<M: Mask>(state: &mut State<M>, row: usize, col: usize, d: u8) {
+    let idx = row * (state.n as usize) + col;
+    state.grid[idx] = 0;
+    let n = state.n as usize;
+    state.masks[row] &= !M::bit(d);
+    if state.enforce_columns {
+        state.masks[n + col] &= !M::bit(d);
+    }
+
+    // Tier 2.2: Invalidate MRV cache when domains change (unplace expands domains)
+    state.mrv_cache.valid = false;
+
+    check_state_invariants(state, "unplace", Some(idx));
+}
+
+/// Re-derive the Latin masks from the grid and panic if the incremental state diverged.
+///
+/// Enabled by the `debug-invariants` feature; compiles to nothing otherwise.
+#[cfg(feature = "debug-invariants")]
+#[track_caller]
+fn check_state_invariants<M: Mask>(state: &State<M>, op: &str, cell: Option<usize>) {
+    let n = state.n as usize;
+    let a = n * n;
+    let at = match cell {
+        Some(idx) => format!("after {op} at cell {idx} (r{}c{})", idx / n, idx % n),
+        None => format!("after {op}"),
+    };
+
+    assert_eq!(state.grid.len(), a, "grid length != n*n {at}");
+    assert_eq!(
+        state.cage_of_cell.len(),
+        a,
+        "cage_of_cell length != n*n {at}"
+    );
+    assert_eq!(
+        state.masks.len(),
+        2 * n + a,
+        "masks length != 2n + n*n {at}"
+    );
+
+    let mut row_mask = vec![M::ZERO; n];
+    let mut col_mask = vec![M::ZERO; n];
+    for (idx, &v) in state.grid.iter().enumerate() {
+        assert!(
+            state.cage_of_cell[idx] != NO_CAGE,
+            "cell {idx} is not covered by any cage {at}"
+        );
+        if v == 0 {
+            continue;
+        }
+        assert!(
+            v <= state.n,
+            "cell {idx} holds {v}, which exceeds n={} {at}",
+            state.n
+        );
+        row_mask[idx / n] |= M::bit(v);
+        if state.enforce_columns {
+            col_mask[idx % n] |= M::bit(v);
+        }
+    }
+
+    for (r, &want) in row_mask.iter().enumerate() {
+        assert_eq!(
+            state.row_mask(r),
+            want,
+            "row_mask[{r}] diverged from grid {at}"
+        );
+    }
+    for (c, &want) in col_mask.iter().enumerate() {
+        assert_eq!(
+            state.col_mask(c),
+            want,
+            "col_mask[{c}] diverged from grid {at}"
+        );
+    }
+}
+
+#[cfg(not(feature = "debug-invariants"))]
+#[inline(always)]
+fn check_state_invariants<M: Mask>(_state: &State<M>, _op: &str, _cell: Option<usize>) {}
+
+fn full_domain<M: Mask>(n: u8) -> M {
+    // bits 1..=n set; bit 0 is never a digit. `with_mask!` picks an `M` that holds n.
+    M::full(n)
+}
+
+fn domain_min_max<M: Mask>(dom: M) -> Option<(u8, u8)> {
+    if dom.is_empty() {
+        return None;
+    }
+    Some((dom.min_digit(), dom.max_digit()))
+}
+
+fn domain_iter<M: Mask>(dom: M) -> impl Iterator<Item = u8> {
+    let mut mask = dom;
+    core::iter::from_fn(move || {
+        if mask.is_empty() {
+            return None;
+        }
+        let bit = mask.min_digit();
+        mask = mask.clear_lowest();
+        Some(bit)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use kenken_core::format::sgt_desc::parse_keen_desc;
+
+    use super::*;
+
+    /// Solutions counted by the search, even on grids the [`crate::small_n`] tables answer.
+    fn searched_count(puzzle: &Puzzle, rules: Ruleset, tier: DeductionTier, limit: u32) -> u32 {
+        let options = SearchOptions {
+            small_n_tables: SmallNTables::Never,
+            ..SearchOptions::default()
+        };
+        count_solutions_up_to_with_options(puzzle, rules, tier, limit, options)
+            .unwrap()
+            .0
+    }
+
+    #[test]
+    fn tier_names_round_trip() {
+        for tier in DeductionTier::ALL {
+            assert_eq!(tier.to_string().parse::<DeductionTier>(), Ok(tier));
+        }
+        for tier in DifficultyTier::ALL {
+            assert_eq!(tier.to_string().parse::<DifficultyTier>(), Ok(tier));
+        }
+        assert_eq!(DifficultyTier::Unreasonable.to_string(), "unreasonable");
+        assert!("Hard".parse::<DeductionTier>().is_err());
+        assert!("none".parse::<DifficultyTier>().is_err());
+    }
+
+    #[test]
+    fn solution_accessors_and_checks() {
+        let rules = Ruleset::keen_baseline();
+        let puzzle = parse_keen_desc(2, "b__,a3a3").unwrap();
+        let solution = Solution {
+            n: 2,
+            grid: vec![1, 2, 2, 1],
+        };
+        assert_eq!(solution.row(1), &[2, 1]);
+        assert_eq!(solution.get(0, 1), 2);
+        assert!(solution.is_latin());
+        assert!(solution.validate_against(&puzzle, rules).is_ok());
+
+        let short = Solution {
+            n: 2,
+            grid: vec![1, 2, 2],
+        };
+        assert!(!short.is_latin());
+        assert!(matches!(
+            short.validate_against(&puzzle, rules),
+            Err(SolutionError::Violation(
+                CoreError::SolutionLengthMismatch { .. }
+            ))
+        ));
+
+        let wrong_size = Solution {
+            n: 1,
+            grid: vec![1],
+        };
+        assert!(wrong_size.is_latin());
+        assert!(matches!(
+            wrong_size.validate_against(&puzzle, rules),
+            Err(SolutionError::SizeMismatch {
+                solution: 1,
+                puzzle: 2
+            })
+        ));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn tiers_serialize_as_their_names() {
+        for tier in DeductionTier::ALL {
+            let json = serde_json::to_string(&tier).unwrap();
+            assert_eq!(json, format!("\"{tier}\""));
+            assert_eq!(serde_json::from_str::<DeductionTier>(&json).unwrap(), tier);
+        }
+        for tier in DifficultyTier::ALL {
+            let json = serde_json::to_string(&tier).unwrap();
+            assert_eq!(json, format!("\"{tier}\""));
+            assert_eq!(serde_json::from_str::<DifficultyTier>(&json).unwrap(), tier);
+        }
+        let op = serde_json::to_string(&Op::Div).unwrap();
+        assert_eq!(op, "\"div\"");
+    }
+
+    #[test]
+    fn counts_two_solutions_for_simple_2x2() {
+        let p = parse_keen_desc(2, "b__,a3a3").unwrap();
+        let count = count_solutions_up_to(&p, Ruleset::keen_baseline(), 2).unwrap();
+        assert_eq!(count, 2);
+    }
+
+    #[test]
+    fn stops_counting_at_limit() {
+        let p = parse_keen_desc(2, "b__,a3a3").unwrap();
+        let count = count_solutions_up_to(&p, Ruleset::keen_baseline(), 1).unwrap();
+        assert_eq!(count, 1);
+    }
+
+    #[test]
+    fn solve_one_returns_a_solution_when_one_exists() {
+        let p = parse_keen_desc(2, "b__,a3a3").unwrap();
+        let sol = solve_one(&p, Ruleset::keen_baseline()).unwrap().unwrap();
+        assert_eq!(sol.n, 2);
+        assert_eq!(sol.grid.len(), 4);
+    }
+
+    #[test]
+    fn solve_one_with_deductions_works() {
+        let p = parse_keen_desc(2, "b__,a3a3").unwrap();
+        let sol = solve_one_with_deductions(&p, Ruleset::keen_baseline(), DeductionTier::Hard)
+            .unwrap()
+            .unwrap();
+        assert_eq!(sol.n, 2);
+        assert_eq!(sol.grid.len(), 4);
+    }
+
+    #[cfg(feature = "debug-invariants")]
+    #[test]
+    #[should_panic(expected = "col_mask[0] diverged from grid after place at cell 1")]
+    fn debug_invariants_catch_stale_mask_bit() {
+        let p = parse_keen_desc(2, "b__,a3a3").unwrap();
+        let mut state = State::<u32>::for_puzzle(&p, Ruleset::keen_baseline());
+        place(&mut state, 0, 0, 1);
+        // Simulate a lost unplace: a stale bit for digit 2 stays in column 0 (masks[n + 0]).
+        state.masks[2] |= 1 << 2;
+        place(&mut state, 0, 1, 2);
+    }
+
+    #[cfg(feature = "debug-invariants")]
+    #[test]
+    fn debug_invariants_hold_during_search() {
+        let p = parse_keen_desc(2, "b__,a3a3").unwrap();
+        for tier in [DeductionTier::None, DeductionTier::Hard] {
+            assert_eq!(searched_count(&p, Ruleset::keen_baseline(), tier, 2), 2);
+        }
+    }
+
+    #[test]
+    fn mul_prefilter_drops_non_divisors_and_unreachable_factors() {
+        use kenken_core::CellId;
+
+        let full = full_domain(7);
+        let cage = Cage {
+            cells: [CellId(0), CellId(1), CellId(2)].into_iter().collect(),
+            op: Op::Mul,
+            target: 84,
+        };
+        let cells = [0usize, 1, 2];
+        let mut domains = vec![full; 3];
+        prefilter_mul_domains(&cage, &cells, &mut domains);
+        // Divisors of 84 in 1..=7 are 1, 2, 3, 4, 6, 7; the other two cells reach at most
+        // 7 * 7 = 49, so 1 (needing 84) is unreachable too.
+        let expected = (1u64 << 2) | (1 << 3) | (1 << 4) | (1 << 6) | (1 << 7);
+        assert_eq!(domains, vec![expected; 3]);
+
+        // Pinning one cell to 2 leaves 42 for the other two, which only 6 * 7 reaches.
+        let mut domains = vec![1u64 << 2, full, full];
+        prefilter_mul_domains(&cage, &cells, &mut domains);
+        assert_eq!(domains[0], 1u64 << 2);
+        assert_eq!(domains[1], (1u64 << 6) | (1 << 7));
+        assert_eq!(domains[2], (1u64 << 6) | (1 << 7));
+    }
+
+    #[test]
+    fn mul_prefilter_ignores_other_ops() {
+        use kenken_core::CellId;
+
+        let cage = Cage {
+            cells: [CellId(0), CellId(1)].into_iter().collect(),
+            op: Op::Add,
+            target: 5,
+        };
+        let mut domains = vec![full_domain::<u64>(5); 2];
+        prefilter_mul_domains(&cage, &[0, 1], &mut domains);
+        assert_eq!(domains, vec![full_domain::<u64>(5); 2]);
+    }
+
+    /// 5x5 cyclic square with the first two rows given and each remaining row an Add cage.
+    fn multi_solution_5x5() -> Puzzle {
+        use kenken_core::CellId;
+
+        let value = |r: usize, c: usize| ((r + c) % 5 + 1) as i64;
+        let mut cages = Vec::new();
+        for r in 0..2 {
+            for c in 0..5 {
+                cages.push(Cage {
+                    cells: [CellId((r * 5 + c) as u16)].into_iter().collect(),
+                    op: Op::Eq,
+                    target: value(r, c),
+                });
+            }
+        }
+        for r in 2..5 {
+            cages.push(Cage {
+                cells: (0..5).map(|c| CellId((r * 5 + c) as u16)).collect(),
+                op: Op::Add,
+                target: 15,
+            });
+        }
+        Puzzle { n: 5, cages }
+    }
+
+    #[test]
+    fn one_by_one_grid_is_rejected_by_every_entry_point() {
+        let p = Puzzle {
+            n: 1,
+            cages: vec![Cage {
+                cells: [kenken_core::CellId(0)].into_iter().collect(),
+                op: Op::Eq,
+                target: 1,
+            }],
+        };
+        let rules = Ruleset::keen_baseline();
+        let rejected = |r: Result<(), SolveError>| {
+            matches!(r, Err(SolveError::Core(CoreError::InvalidGridSize(1))))
+        };
+
+        assert!(rejected(solve_one(&p, rules).map(drop)));
+        assert!(rejected(solve_one_with_stats(&p, rules).map(drop)));
+        assert!(rejected(count_solutions_up_to(&p, rules, 2).map(drop)));
+        for tier in [
+            DeductionTier::None,
+            DeductionTier::Easy,
+            DeductionTier::Normal,
+            DeductionTier::Hard,
+        ] {
+            assert!(rejected(
+                solve_one_with_deductions(&p, rules, tier).map(drop)
+            ));
+            assert!(rejected(
+                count_solutions_up_to_with_deductions(&p, rules, tier, 2).map(drop)
+            ));
+        }
+        assert!(rejected(classify_tier_required(&p, rules).map(drop)));
+    }
+
+    #[test]
+    fn negative_targets_are_rejected_not_counted_as_unsolvable() {
+        // `b__,a3a3` with the top cage's target negated; the desc parser would reject it.
+        let mut p = parse_keen_desc(2, "b__,a3a3").unwrap();
+        let rules = Ruleset::keen_baseline();
+        for op in [Op::Add, Op::Mul, Op::Sub, Op::Div] {
+            p.cages[0].op = op;
+            p.cages[0].target = -3;
+            for tier in [DeductionTier::None, DeductionTier::Hard] {
+                assert!(matches!(
+                    count_solutions_up_to_with_deductions(&p, rules, tier, 2),
+                    Err(SolveError::Core(CoreError::TargetNegative {
+                        target: -3,
+                        ..
+                    }))
+                ));
+            }
+        }
+    }
+
+    #[test]
+    fn ignoring_cages_rejects_out_of_range_index() {
+        let p = parse_keen_desc(2, "b__,a3a3").unwrap();
+        let rules = Ruleset::keen_baseline();
+        assert!(matches!(
+            count_solutions_up_to_ignoring_cages(&p, rules, DeductionTier::Normal, 2, &[2]),
+            Err(SolveError::Core(CoreError::CageIndexOutOfRange {
+                index: 2,
+                len: 2
+            }))
+        ));
+        assert!(matches!(
+            solve_one_ignoring_cages(&p, rules, DeductionTier::Normal, &[0, 5]),
+            Err(SolveError::Core(CoreError::CageIndexOutOfRange {
+                index: 5,
+                len: 2
+            }))
+        ));
+        // Ignoring both `3+` pairs leaves the two 2x2 Latin squares.
+        assert_eq!(
+            count_solutions_up_to_ignoring_cages(&p, rules, DeductionTier::Normal, 10, &[0, 1])
+                .unwrap(),
+            2
+        );
+    }
+
+    #[test]
+    fn resumable_count_matches_uninterrupted_run_across_three_segments() {
+        let puzzle = multi_solution_5x5();
+        let rules = Ruleset::keen_baseline();
+
+        for tier in [DeductionTier::None, DeductionTier::Hard] {
+            let expected =
+                count_solutions_up_to_with_deductions(&puzzle, rules, tier, u32::MAX).unwrap();
+            let ResumableOutcome::Complete {
+                count: full,
+                stats: full_stats,
+            } = count_solutions_resumable(&puzzle, rules, tier, u32::MAX, None, 0).unwrap()
+            else {
+                panic!("budget 0 must never suspend");
+            };
+            assert!(full > 1);
+            assert_eq!(full, expected);
+
+            let every = full_stats.nodes_visited / 3 + 1;
+            let mut checkpoint = None;
+            let mut segments = 0;
+            let (count, stats) = loop {
+                segments += 1;
+                match count_solutions_resumable(
+                    &puzzle,
+                    rules,
+                    tier,
+                    u32::MAX,
+                    checkpoint.take(),
+                    every,
+                )
+                .unwrap()
+                {
+                    ResumableOutcome::Complete { count, stats } => break (count, stats),
+                    ResumableOutcome::Suspended(cp) => checkpoint = Some(cp),
+                }
+            };
+            assert_eq!(segments, 3);
+            assert_eq!(count, full);
+            assert_eq!(stats.nodes_visited, full_stats.nodes_visited);
+        }
+    }
+
+    #[test]
+    fn resume_rejects_inconsistent_checkpoint() {
+        let puzzle = multi_solution_5x5();
+        let checkpoint = SearchCheckpoint {
+            // Cell 0 is a given (Eq 1), so it is already filled after root propagation.
+            frames: vec![CheckpointFrame {
+                cell: 0,
+                value: 2,
+                remaining: 0,
+            }],
+            count: 0,
+            stats: SolveStats::default(),
+        };
+        let err = count_solutions_resumable(
+            &puzzle,
+            Ruleset::keen_baseline(),
+            DeductionTier::Hard,
+            u32::MAX,
+            Some(checkpoint),
+            0,
+        )
+        .unwrap_err();
+        assert!(matches!(err, SolveError::InvalidCheckpoint { .. }));
+    }
+
+    #[test]
+    fn solves_9x9_mul_cage_with_target_beyond_i32() {
+        use kenken_core::CellId;
+
+        // Cyclic Latin square; one 12-cell Mul cage over the large digits, givens elsewhere.
+        let n = 9u8;
+        let value = |r: usize, c: usize| ((r + c) % 9 + 1) as u8;
+        let cage_cells: Vec<(usize, usize)> = (4..9)
+            .map(|c| (0, c))
+            .chain((3..8).map(|c| (1, c)))
+            .chain([(2, 5), (2, 6)])
+            .collect();
+        let product: i64 = cage_cells
+            .iter()
+            .map(|&(r, c)| value(r, c) as i64)
+            .product();
+        assert!(product > i32::MAX as i64);
+
+        let mut cages = vec![Cage {
+            cells: cage_cells
+                .iter()
+                .map(|&(r, c)| CellId((r * 9 + c) as u16))
+                .collect(),
+            op: Op::Mul,
+            target: product,
+        }];
+        for r in 0..9 {
+            for c in 0..9 {
+                if !cage_cells.contains(&(r, c)) {
+                    cages.push(Cage {
+                        cells: [CellId((r * 9 + c) as u16)].into_iter().collect(),
+                        op: Op::Eq,
+                        target: value(r, c) as i64,
+                    });
+                }
+            }
+        }
+        let puzzle = Puzzle { n, cages };
+        let rules = Ruleset {
+            max_cage_size: 12,
+            ..Ruleset::keen_baseline()
+        };
+        puzzle.validate(rules).unwrap();
+
+        let sol = solve_one(&puzzle, rules).unwrap().unwrap();
+        for &(r, c) in &cage_cells {
+            assert_eq!(sol.grid[r * 9 + c], value(r, c));
+        }
+        assert_eq!(count_solutions_up_to(&puzzle, rules, 2).unwrap(), 1);
+    }
+
+    #[test]
+    fn two_cell_div_survives_both_cells_forced_in_one_round() {
+        // [1 2]  Div cage on the top row; the bottom-row givens force both cells at once.
+        // [2 1]
+        let cell = kenken_core::CellId;
+        let puzzle = Puzzle {
+            n: 2,
+            cages: vec![
+                Cage {
+                    cells: [cell(0), cell(1)].into_iter().collect(),
+                    op: Op::Div,
+                    target: 2,
+                },
+                Cage {
+                    cells: [cell(2)].into_iter().collect(),
+                    op: Op::Eq,
+                    target: 2,
+                },
+                Cage {
+                    cells: [cell(3)].into_iter().collect(),
+                    op: Op::Eq,
+                    target: 1,
+                },
+            ],
+        };
+        let rules = Ruleset::keen_baseline();
+        for tier in [DeductionTier::Easy, DeductionTier::Normal] {
+            let sol = solve_one_with_deductions(&puzzle, rules, tier)
+                .unwrap()
+                .unwrap_or_else(|| panic!("no solution at {tier:?}"));
+            assert_eq!(sol.grid, vec![1, 2, 2, 1]);
+        }
+    }
+
+    #[test]
+    fn empty_domain_under_tier_none_is_not_a_solution() {
+        // Givens 3 and 3 share the bottom row, so cell 8 ends up with no candidates. Without
+        // propagation, MRV hits that empty domain before reaching cell 8.
+        let cage = |cells: &[u16], op, target| Cage {
+            cells: cells.iter().copied().map(kenken_core::CellId).collect(),
+            op,
+            target,
+        };
+        let puzzle = Puzzle {
+            n: 3,
+            cages: vec![
+                cage(&[0, 1], Op::Add, 4),
+                cage(&[2], Op::Eq, 2),
+                cage(&[3, 4], Op::Add, 3),
+                cage(&[5, 8], Op::Add, 4),
+                cage(&[6], Op::Eq, 3),
+                cage(&[7], Op::Eq, 3),
+            ],
+        };
+        let rules = Ruleset::keen_baseline();
+        assert_eq!(count_solutions_up_to(&puzzle, rules, 10).unwrap(), 0);
+        assert_eq!(searched_count(&puzzle, rules, DeductionTier::None, 10), 0);
+        assert!(
+            solve_one_with_deductions(&puzzle, rules, DeductionTier::None)
+                .unwrap()
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn fully_assigned_add_cage_is_still_checked() {
+        // Latin propagation alone fills the grid with 1 and 2 in the `4+` cage (cells 5, 8).
+        let cage = |cells: &[u16], op, target| Cage {
+            cells: cells.iter().copied().map(kenken_core::CellId).collect(),
+            op,
+            target,
+        };
+        let puzzle = Puzzle {
+            n: 3,
+            cages: vec![
+                cage(&[0, 1, 3], Op::Mul, 4),
+                cage(&[2], Op::Eq, 3),
+                cage(&[4], Op::Eq, 3),
+                cage(&[5, 8], Op::Add, 4),
+                cage(&[6], Op::Eq, 3),
+                cage(&[7], Op::Eq, 1),
+            ],
+        };
+        let rules = Ruleset::keen_baseline();
+        for tier in [
+            DeductionTier::None,
+            DeductionTier::Easy,
+            DeductionTier::Normal,
+            DeductionTier::Hard,
+        ] {
+            assert_eq!(searched_count(&puzzle, rules, tier, 10), 0, "tier {tier:?}");
+        }
+        assert_eq!(
+            propagate_to_fixpoint(&puzzle, rules, DeductionTier::Normal).unwrap(),
+            None
+        );
+    }
+
+    #[test]
+    fn cells_forced_together_must_still_fit_their_cage() {
+        // One Hard round forces cells 0 and 5 of the `8+` cage (0, 1, 5) to 3 each, which
+        // its tuples support one at a time but not together (3 + 4 + 3).
+        let puzzle = parse_keen_desc(4, "aa_b__a_c__a__a,a8a3a8a10s2a5").unwrap();
+        let rules = Ruleset::keen_baseline();
+        for tier in [
+            DeductionTier::None,
+            DeductionTier::Easy,
+            DeductionTier::Normal,
+            DeductionTier::Hard,
+        ] {
+            assert_eq!(searched_count(&puzzle, rules, tier, 10), 1, "tier {tier:?}");
+        }
+    }
+
+    /// Build a 4x4 puzzle from cage labels and ops; targets are taken from `solution`.
+    fn puzzle_from_labels(solution: &[u8; 16], labels: &[usize; 16], ops: &[Op]) -> Puzzle {
+        let cages = ops
+            .iter()
+            .enumerate()
+            .map(|(cage_idx, &op)| {
+                let cells: Vec<usize> = (0..16).filter(|&i| labels[i] == cage_idx).collect();
+                let values: Vec<i64> = cells.iter().map(|&i| solution[i] as i64).collect();
+                Cage {
+                    cells: cells
+                        .iter()
+                        .map(|&i| kenken_core::CellId(i as u16))
+                        .collect(),
+                    op,
+                    target: op.apply(&values).unwrap(),
+                }
+            })
+            .collect();
+        Puzzle { n: 4, cages }
+    }
+
+    #[test]
+    fn n_ary_sub_div_cages_match_reference() {
+        #[rustfmt::skip]
+        let solution = [
+            4, 1, 2, 3,
+            2, 3, 4, 1,
+            1, 4, 3, 2,
+            3, 2, 1, 4,
+        ];
+        // 3-cell `1-` along the top row (4 - 1 - 2).
+        #[rustfmt::skip]
+        let sub_layout = [
+            0, 0, 0, 1,
+            2, 3, 1, 1,
+            2, 3, 4, 4,
+            5, 5, 6, 6,
+        ];
+        let sub_ops = [
+            Op::Sub,
+            Op::Add,
+            Op::Div,
+            Op::Sub,
+            Op::Mul,
+            Op::Sub,
+            Op::Div,
+        ];
+        // 3-cell L-shaped `2÷` (4 / 2 / 1).
+        #[rustfmt::skip]
+        let div_layout = [
+            0, 1, 1, 2,
+            0, 3, 1, 2,
+            4, 3, 5, 5,
+            4, 6, 6, 7,
+        ];
+        let div_ops = [
+            Op::Div,
+            Op::Div,
+            Op::Sub,
+            Op::Sub,
+            Op::Add,
+            Op::Sub,
+            Op::Div,
+            Op::Eq,
+        ];
+
+        let permissive = Ruleset {
+            sub_div_two_cell_only: false,
+            ..Ruleset::keen_baseline()
+        };
+        for (layout, ops) in [(&sub_layout, &sub_ops[..]), (&div_layout, &div_ops[..])] {
+            let puzzle = puzzle_from_labels(&solution, layout, ops);
+            assert!(puzzle.validate(Ruleset::keen_baseline()).is_err());
+            puzzle.validate(permissive).unwrap();
+
+            let expected = crate::reference::reference_count(&puzzle, permissive).unwrap();
+            assert!(expected >= 1);
+            assert_eq!(
+                count_solutions_up_to(&puzzle, permissive, 100).unwrap(),
+                expected
+            );
+            for tier in [
+                DeductionTier::None,
+                DeductionTier::Easy,
+                DeductionTier::Normal,
+                DeductionTier::Hard,
+            ] {
+                let count = searched_count(&puzzle, permissive, tier, 100);
+                assert_eq!(count, expected, "tier {tier:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn degraded_nary_sub_div_cages_are_checked_once_assigned() {
+        // Past the tuple cap, the 3-cell `1÷` falls back to bounds filtering, which has no
+        // interval for Sub/Div; Hard used to force its cells without ever checking it.
+        let puzzle = partial_puzzle(
+            3,
+            &[
+                (Op::Sub, 2, &[0, 3]),
+                (Op::Eq, 2, &[1]),
+                (Op::Sub, 1, &[2, 5]),
+                (Op::Eq, 2, &[6]),
+                (Op::Div, 1, &[4, 7, 8]),
+            ],
+        );
+        let permissive = Ruleset {
+            sub_div_two_cell_only: false,
+            ..Ruleset::keen_baseline()
+        };
+        let expected = crate::reference::reference_count(&puzzle, permissive).unwrap();
+        assert_eq!(expected, 1);
+        for max_tuples_per_cage in 1..=4 {
+            let options = SearchOptions {
+                max_tuples_per_cage,
+                small_n_tables: SmallNTables::Never,
+                ..SearchOptions::default()
+            };
+            for tier in [
+                DeductionTier::None,
+                DeductionTier::Easy,
+                DeductionTier::Normal,
+                DeductionTier::Hard,
+            ] {
+                let (count, _) = count_solutions_up_to_with_options(
+                    &puzzle,
+                    permissive,
+                    tier,
+                    100,
+                    options.clone(),
+                )
+                .unwrap();
+                assert_eq!(count, expected, "cap {max_tuples_per_cage} at {tier:?}");
+            }
+        }
+    }
+
+    /// Domains after one pass of cage deductions over an empty grid, through whichever
+    /// propagation path (plain or `alloc-bumpalo`) this build uses. The expectations below are
+    /// shared by both builds, so CI running this module under each feature set checks that
+    /// the two paths agree mask for mask.
+    fn one_round_domains(puzzle: &Puzzle, tier: DeductionTier) -> Vec<u64> {
+        let rules = Ruleset::keen_baseline();
+        let n = puzzle.n as usize;
+        let mut state = State::for_puzzle(puzzle, rules);
+        let mut domains = vec![full_domain(puzzle.n); n * n];
+        #[cfg(feature = "alloc-bumpalo")]
+        let bump = Bump::new();
+        for cage in &puzzle.cages {
+            #[cfg(feature = "alloc-bumpalo")]
+            apply_cage_deduction_with_bump(
+                &bump,
+                puzzle,
+                rules,
+                &mut state,
+                cage,
+                tier,
+                &mut domains,
+            )
+            .unwrap();
+            #[cfg(not(feature = "alloc-bumpalo"))]
+            apply_cage_deduction(puzzle, rules, &mut state, cage, tier, &mut domains).unwrap();
         }
-        (x, y) => Ok((x as i32 - y as i32).abs() == target),
+        domains
+    }
+
+    fn digits(ds: &[u8]) -> u64 {
+        ds.iter().fold(0, |m, &d| m | (1u64 << d))
     }
-}
 
-fn two_cell_div_feasible(
-    puzzle: &Puzzle,
-    state: &State,
-    a: usize,
-    b: usize,
-    target: i32,
-) -> Result<bool, CoreError> {
-    let n = state.n as usize;
-    let av = state.grid[a];
-    let bv = state.grid[b];
-    let ok_pair = |x: u8, y: u8| {
-        let (num, den) = if x >= y { (x, y) } else { (y, x) };
-        den != 0 && (num as i32) == (den as i32).saturating_mul(target)
-    };
-    match (av, bv) {
-        (0, 0) => Ok(true),
-        (x, 0) => {
-            let row = b / n;
-            let col = b % n;
-            let dom = domain_for_cell(puzzle, state, b, row, col)?;
-            Ok(domain_iter(dom).any(|y| ok_pair(x, y)))
+    /// Cages over the given cells only; uncovered cells keep their full domain.
+    fn partial_puzzle(n: u8, cages: &[(Op, i64, &[u16])]) -> Puzzle {
+        Puzzle {
+            n,
+            cages: cages
+                .iter()
+                .map(|&(op, target, cells)| Cage {
+                    cells: cells.iter().map(|&c| kenken_core::CellId(c)).collect(),
+                    op,
+                    target,
+                })
+                .collect(),
         }
-        (0, y) => {
-            let row = a / n;
-            let col = a % n;
-            let dom = domain_for_cell(puzzle, state, a, row, col)?;
-            Ok(domain_iter(dom).any(|x| ok_pair(x, y)))
+    }
+
+    #[test]
+    fn assigned_div_pair_is_checked_on_its_digits() {
+        // Bit `d` is digit `d`. Read as `d + 1`, 1 and 3 would pass `2/` as 2 and 4, and 2 and
+        // 4 would fail it as 3 and 5.
+        let puzzle = partial_puzzle(4, &[(Op::Div, 2, &[0, 1])]);
+        for (a, b, holds) in [(2, 4, true), (4, 2, true), (1, 3, false), (3, 1, false)] {
+            let mut domains = vec![u32::full(4); 16];
+            domains[0] = u32::bit(a);
+            domains[1] = u32::bit(b);
+            apply_sub_div_pair(
+                4,
+                &puzzle.cages[0],
+                0,
+                1,
+                DeductionTier::Normal,
+                true,
+                &mut domains,
+            );
+            let kept = (domains[0], domains[1]) == (u32::bit(a), u32::bit(b));
+            let emptied = domains[0].is_empty() && domains[1].is_empty();
+            assert!(if holds { kept } else { emptied }, "{a} and {b}");
         }
-        (x, y) => Ok(ok_pair(x, y)),
     }
-}
 
-fn place(state: &mut State, row: usize, col: usize, d: u8) {
-    let idx = row * (state.n as usize) + col;
-    state.grid[idx] = d;
-    state.row_mask[row] |= 1u64 << (d as u32);
-    state.col_mask[col] |= 1u64 << (d as u32);
-}
+    #[test]
+    fn sub_pair_sharing_a_row_clears_its_values_from_the_row() {
+        // `3-` on (0,0)-(0,1) of a 4x4: only {1,4} fits, and both values land in row 0.
+        let puzzle = partial_puzzle(4, &[(Op::Sub, 3, &[0, 1])]);
+        let mut expected = vec![digits(&[1, 2, 3, 4]); 16];
+        expected[0] = digits(&[1, 4]);
+        expected[1] = digits(&[1, 4]);
+        expected[2] = digits(&[2, 3]);
+        expected[3] = digits(&[2, 3]);
+        assert_eq!(one_round_domains(&puzzle, DeductionTier::Hard), expected);
+
+        expected[2] = digits(&[1, 2, 3, 4]);
+        expected[3] = digits(&[1, 2, 3, 4]);
+        assert_eq!(one_round_domains(&puzzle, DeductionTier::Normal), expected);
+    }
 
-fn unplace(state: &mut State, row: usize, col: usize, d: u8) {
-    let idx = row * (state.n as usize) + col;
-    state.grid[idx] = 0;
-    state.row_mask[row] &= !(1u64 << (d as u32));
-    state.col_mask[col] &= !(1u64 << (d as u32));
+    #[test]
+    fn forcing_one_digit_twice_in_a_row_in_one_round_is_a_contradiction() {
+        // Each L cage's `4*` fits only 1 in its corner and 2 on both arms, so the first Normal
+        // round forces cells 1 and 2, both in row 0, to 2. Both come from domains read before
+        // either was placed; the second must end the round as a contradiction rather than
+        // place a duplicate beside the first.
+        let puzzle = partial_puzzle(
+            3,
+            &[
+                (Op::Mul, 4, &[0, 1, 3]),
+                (Op::Mul, 4, &[2, 4, 5]),
+                (Op::Add, 6, &[6, 7, 8]),
+            ],
+        );
+        let rules = Ruleset::keen_baseline();
+        let mut state = State::<u32>::for_puzzle(&puzzle, rules);
+        let mut forced = Vec::new();
+        assert!(
+            !propagate(
+                &puzzle,
+                rules,
+                DeductionTier::Normal,
+                &mut state,
+                &mut forced
+            )
+            .unwrap()
+        );
+        assert_eq!(forced, [(0, 1), (1, 2)]);
+        assert_eq!(state.grid[2], 0);
+        assert_eq!(state.row_mask(0), u32::bit(1) | u32::bit(2));
 
-    // Tier 2.2: Invalidate MRV cache when domains change (unplace expands domains)
-    state.mrv_cache.valid = false;
-}
+        let count = count_solutions_up_to_with_deductions(&puzzle, rules, DeductionTier::Normal, 2)
+            .unwrap();
+        assert_eq!(count, 0);
+    }
 
-fn full_domain(n: u8) -> u64 {
-    // bits 1..=n set
-    if n >= 63 {
-        u64::MAX
-    } else {
-        ((1u64 << (n as u32 + 1)) - 1) & !1u64
+    #[test]
+    fn propagation_counts_surviving_tuples_for_the_mrv_tiebreak() {
+        // On an empty 4x4: `1-` on a row pair fits 6 ordered pairs, `6+` on a row triple the 6
+        // orders of {1,2,3}, `3+` on a row pair only (1,2) and (2,1), `5+` on a pair 4, and
+        // `7+` on a row triple the 6 orders of {1,2,4}. Nothing is forced at Normal.
+        let puzzle = partial_puzzle(
+            4,
+            &[
+                (Op::Sub, 1, &[0, 1]),
+                (Op::Add, 5, &[2, 3]),
+                (Op::Add, 6, &[8, 9, 10]),
+                (Op::Add, 3, &[4, 5]),
+                (Op::Add, 5, &[6, 7]),
+                (Op::Add, 5, &[11, 15]),
+                (Op::Add, 7, &[12, 13, 14]),
+            ],
+        );
+        let rules = Ruleset::keen_baseline();
+        let options = SearchOptions {
+            mrv_tuple_tiebreak: true,
+            ..SearchOptions::default()
+        };
+        let mut state = State::<u32>::with_options(&puzzle, rules, options);
+        let mut forced = Vec::new();
+        assert!(propagate(&puzzle, rules, DeductionTier::Easy, &mut state, &mut forced).unwrap());
+        assert_eq!(state.cage_tuples, vec![TUPLES_UNKNOWN; 7]);
+        // Every cell still has four candidates, so with nothing counted MRV takes the first.
+        assert_eq!(choose_mrv_cell(&mut state).map(|(idx, _)| idx), Some(0));
+
+        assert!(
+            propagate(
+                &puzzle,
+                rules,
+                DeductionTier::Normal,
+                &mut state,
+                &mut forced
+            )
+            .unwrap()
+        );
+        assert!(forced.is_empty());
+        assert_eq!(state.cage_tuples, vec![6, 4, 6, 2, 4, 4, 6]);
+        assert_eq!(choose_mrv_cell(&mut state).map(|(idx, _)| idx), Some(4));
+
+        state.options.mrv_tuple_tiebreak = false;
+        assert_eq!(choose_mrv_cell(&mut state).map(|(idx, _)| idx), Some(0));
     }
-}
 
-fn domain_min_max(dom: u64) -> Option<(u8, u8)> {
-    if dom == 0 {
-        return None;
+    #[test]
+    fn div_pair_sharing_a_column_clears_common_value_from_the_column() {
+        // `2÷` on (0,0)-(1,0) of a 4x4: pairs {1,2} and {2,4} both put a 2 in column 0.
+        let puzzle = partial_puzzle(4, &[(Op::Div, 2, &[0, 4])]);
+        let mut expected = vec![digits(&[1, 2, 3, 4]); 16];
+        expected[0] = digits(&[1, 2, 4]);
+        expected[4] = digits(&[1, 2, 4]);
+        expected[8] = digits(&[1, 3, 4]);
+        expected[12] = digits(&[1, 3, 4]);
+        assert_eq!(one_round_domains(&puzzle, DeductionTier::Hard), expected);
     }
-    let min = dom.trailing_zeros() as u8;
-    let max = (63 - dom.leading_zeros()) as u8;
-    Some((min, max))
-}
 
-fn domain_iter(dom: u64) -> impl Iterator<Item = u8> {
-    let mut mask = dom;
-    core::iter::from_fn(move || {
-        if mask == 0 {
-            return None;
+    #[test]
+    fn div_pair_with_single_support_clears_each_cells_column() {
+        // A given 4 at (0,1) leaves `2÷` on (0,0)-(0,1) with the single pair (2, 4): row 0
+        // loses both values, column 0 loses the 2 and column 1 loses the 4.
+        let puzzle = partial_puzzle(4, &[(Op::Eq, 4, &[1]), (Op::Div, 2, &[0, 1])]);
+        let mut expected = vec![digits(&[1, 2, 3, 4]); 16];
+        expected[0] = digits(&[2]);
+        expected[1] = digits(&[4]);
+        expected[2] = digits(&[1, 3]);
+        expected[3] = digits(&[1, 3]);
+        for idx in [4, 8, 12] {
+            expected[idx] = digits(&[1, 3, 4]);
         }
-        let bit = mask.trailing_zeros();
-        mask &= mask - 1;
-        Some(bit as u8)
-    })
-}
-
-#[cfg(test)]
-mod tests {
-    use kenken_core::format::sgt_desc::parse_keen_desc;
-
-    use super::*;
+        for idx in [5, 9, 13] {
+            expected[idx] = digits(&[1, 2, 3]);
+        }
+        assert_eq!(one_round_domains(&puzzle, DeductionTier::Hard), expected);
+    }
 
     #[test]
-    fn counts_two_solutions_for_simple_2x2() {
-        let p = parse_keen_desc(2, "b__,a3a3").unwrap();
-        let count = count_solutions_up_to(&p, Ruleset::keen_baseline(), 2).unwrap();
-        assert_eq!(count, 2);
+    fn sub_and_div_pairs_compose_within_one_round() {
+        // 6x6: `5-` on (2,1)-(2,2) clears {1,6} from row 2, then `5÷` on (0,4)-(1,4) clears
+        // {1,5} from column 4, including (2,4) which row 2 already narrowed.
+        let puzzle = partial_puzzle(6, &[(Op::Sub, 5, &[13, 14]), (Op::Div, 5, &[4, 10])]);
+        let full = digits(&[1, 2, 3, 4, 5, 6]);
+        let mut expected = vec![full; 36];
+        expected[13] = digits(&[1, 6]);
+        expected[14] = digits(&[1, 6]);
+        for idx in [12, 15, 17] {
+            expected[idx] = digits(&[2, 3, 4, 5]);
+        }
+        expected[16] = digits(&[2, 3, 4]);
+        expected[4] = digits(&[1, 5]);
+        expected[10] = digits(&[1, 5]);
+        for idx in [22, 28, 34] {
+            expected[idx] = digits(&[2, 3, 4, 6]);
+        }
+        assert_eq!(one_round_domains(&puzzle, DeductionTier::Hard), expected);
     }
 
-    #[test]
-    fn stops_counting_at_limit() {
-        let p = parse_keen_desc(2, "b__,a3a3").unwrap();
-        let count = count_solutions_up_to(&p, Ruleset::keen_baseline(), 1).unwrap();
-        assert_eq!(count, 1);
+    /// Two-cell Add/Mul deduction through the generic path: `prefilter_mul_domains`, then tuple
+    /// enumeration and the per-tier domain updates of `apply_cage_deduction`, with no cache,
+    /// assigned-cell shortcut or work cap.
+    #[cfg(not(feature = "alloc-bumpalo"))]
+    fn enumerated_pair_domains(
+        n: usize,
+        cage: &Cage,
+        tier: DeductionTier,
+        domains: &[u64],
+    ) -> Vec<u64> {
+        let mut domains = domains.to_vec();
+        let cells: Vec<usize> = cage.cells.iter().map(|c| c.0 as usize).collect();
+        let coords: Vec<(usize, usize)> = cells.iter().map(|&idx| (idx / n, idx % n)).collect();
+        prefilter_mul_domains(cage, &cells, &mut domains);
+        let mut budget = usize::MAX;
+        let (per_pos, any_mask, must_row, must_col, found) =
+            enumerate_cage_tuples_with_must(n, cage, &cells, &coords, &domains, &mut budget);
+        for (pos, &idx) in cells.iter().enumerate() {
+            domains[idx] &= if tier == DeductionTier::Easy {
+                any_mask
+            } else {
+                per_pos[pos]
+            };
+        }
+        if tier == DeductionTier::Hard && found > 0 {
+            for idx in 0..n * n {
+                if !cells.contains(&idx) {
+                    domains[idx] &= !(must_row[idx / n] | must_col[idx % n]);
+                }
+            }
+        }
+        domains
+    }
+
+    /// Every target and pair of cell domains for `n`, on a horizontal, a vertical and a
+    /// diagonal (no shared line) pair, at every propagating tier.
+    #[cfg(not(feature = "alloc-bumpalo"))]
+    fn check_add_mul_pairs_against_enumeration(n: usize) {
+        let full = full_domain::<u64>(n as u8);
+        let layouts = [[0u16, 1], [0, n as u16], [0, n as u16 + 1]];
+        for op in [Op::Add, Op::Mul] {
+            let max_target = if op == Op::Add { 2 * n } else { n * n };
+            for target in 0..=max_target as i64 + 1 {
+                for cells in layouts {
+                    let cage = Cage {
+                        cells: cells.iter().map(|&c| kenken_core::CellId(c)).collect(),
+                        op,
+                        target,
+                    };
+                    let (a_idx, b_idx) = (cells[0] as usize, cells[1] as usize);
+                    for a_dom in 0..1u64 << n {
+                        for b_dom in 0..1u64 << n {
+                            let mut domains = vec![full; n * n];
+                            domains[a_idx] = a_dom << 1;
+                            domains[b_idx] = b_dom << 1;
+                            for tier in [
+                                DeductionTier::Easy,
+                                DeductionTier::Normal,
+                                DeductionTier::Hard,
+                            ] {
+                                let mut fast = domains.clone();
+                                apply_add_mul_pair(n, &cage, a_idx, b_idx, tier, true, &mut fast);
+                                assert_eq!(
+                                    fast,
+                                    enumerated_pair_domains(n, &cage, tier, &domains),
+                                    "n={n} {op:?} {target} on {cells:?} at {tier:?}: \
+                                     {a_dom:#b} / {b_dom:#b}"
+                                );
+                            }
+                        }
+                    }
+                }
+            }
+        }
     }
 
+    #[cfg(not(feature = "alloc-bumpalo"))]
     #[test]
-    fn solve_one_returns_a_solution_when_one_exists() {
-        let p = parse_keen_desc(2, "b__,a3a3").unwrap();
-        let sol = solve_one(&p, Ruleset::keen_baseline()).unwrap().unwrap();
-        assert_eq!(sol.n, 2);
-        assert_eq!(sol.grid.len(), 4);
+    fn add_mul_pairs_match_enumeration_up_to_6() {
+        for n in 2..=6 {
+            check_add_mul_pairs_against_enumeration(n);
+        }
     }
 
+    #[cfg(not(feature = "alloc-bumpalo"))]
     #[test]
-    fn solve_one_with_deductions_works() {
-        let p = parse_keen_desc(2, "b__,a3a3").unwrap();
-        let sol = solve_one_with_deductions(&p, Ruleset::keen_baseline(), DeductionTier::Hard)
-            .unwrap()
-            .unwrap();
-        assert_eq!(sol.n, 2);
-        assert_eq!(sol.grid.len(), 4);
+    #[ignore] // ~3 min; run with: cargo test -p kenken-solver add_mul_pairs_match_enumeration_7_to_9 -- --ignored
+    fn add_mul_pairs_match_enumeration_7_to_9() {
+        for n in 7..=9 {
+            check_add_mul_pairs_against_enumeration(n);
+        }
     }
 }
 
 /// Kani formal verification harnesses for Latin constraint invariants.
 ///
-/// These proofs verify that the row_mask/col_mask representation correctly
-/// enforces Latin square constraints (no duplicate digits in rows or columns).
+/// These proofs verify that the row and column masks correctly enforce Latin square
+/// constraints (no duplicate digits in rows or columns). Each property is proved for `u64`
+/// masks and again for the `u32` masks `with_mask!` picks for n <= 31. Rows and columns are
+/// read through `State::row_mask` / `State::col_mask`, which index the `[rows | cols | static]`
+/// layout of `State::masks`.
 #[cfg(kani)]
 mod kani_verification {
     use super::*;
 
-    /// Proves full_domain(n) has exactly n bits set (bits 1..=n).
-    #[kani::proof]
-    fn full_domain_has_n_bits() {
-        let n: u8 = kani::any();
-        kani::assume(n >= 1 && n <= 30);
-
-        let dom = full_domain(n);
-        let count = dom.count_ones();
-
-        kani::assert(count == n as u32, "full_domain should have exactly n bits");
-
-        // Verify bit 0 is never set (digits are 1-indexed)
-        kani::assert((dom & 1) == 0, "bit 0 should never be set");
-
-        // Verify all bits 1..=n are set
-        for d in 1..=n {
-            kani::assert((dom & (1u32 << d)) != 0, "bit d should be set");
-        }
+    /// Search state of an `n` x `n` grid with nothing placed and no cages.
+    fn empty_state<M: Mask>(n: u8) -> State<M> {
+        State::for_puzzle(
+            &Puzzle {
+                n,
+                cages: Vec::new(),
+            },
+            Ruleset::keen_baseline(),
+        )
     }
 
-    /// Proves place() sets the digit bit in row_mask.
-    #[kani::proof]
-    fn place_sets_row_mask() {
+    /// Symbolic `(n, row, col, d)` with `2 <= n <= max_n`, the cell on the grid and `d` a digit.
+    fn any_placement(max_n: u8) -> (u8, usize, usize, u8) {
         let n: u8 = kani::any();
-        kani::assume(n >= 2 && n <= 9);
-
+        kani::assume(n >= 2 && n <= max_n);
         let row: usize = kani::any();
         let col: usize = kani::any();
         let d: u8 = kani::any();
         kani::assume(row < n as usize && col < n as usize);
         kani::assume(d >= 1 && d <= n);
-
-        let a = (n as usize) * (n as usize);
-        let mut state = State {
-            n,
-            grid: vec![0; a],
-            row_mask: vec![0u64; n as usize],
-            col_mask: vec![0u64; n as usize],
-            cage_of_cell: vec![0; a],
-            tuple_cache: HashMap::new(),
-            mrv_cache: MrvCache::new(n),
-            #[cfg(feature = "nogood-learning")]
-            nogood_cache: None,
-        };
-
-        place(&mut state, row, col, d);
-        let bit_after = state.row_mask[row] & (1u64 << d);
-
-        kani::assert(bit_after != 0, "place should set digit bit in row_mask");
+        (n, row, col, d)
     }
 
-    /// Proves place() sets the digit bit in col_mask.
-    #[kani::proof]
-    fn place_sets_col_mask() {
+    fn check_full_domain<M: Mask>(max_n: u8) {
         let n: u8 = kani::any();
-        kani::assume(n >= 2 && n <= 9);
-
-        let row: usize = kani::any();
-        let col: usize = kani::any();
-        let d: u8 = kani::any();
-        kani::assume(row < n as usize && col < n as usize);
-        kani::assume(d >= 1 && d <= n);
+        kani::assume(n >= 1 && n <= max_n);
 
-        let a = (n as usize) * (n as usize);
-        let mut state = State {
-            n,
-            grid: vec![0; a],
-            row_mask: vec![0u64; n as usize],
-            col_mask: vec![0u64; n as usize],
-            cage_of_cell: vec![0; a],
-            tuple_cache: HashMap::new(),
-            mrv_cache: MrvCache::new(n),
-            #[cfg(feature = "nogood-learning")]
-            nogood_cache: None,
-        };
+        let dom = full_domain::<M>(n);
+        kani::assert(
+            dom.count() == n as u32,
+            "full_domain should have exactly n bits",
+        );
 
-        place(&mut state, row, col, d);
-        let bit_after = state.col_mask[col] & (1u64 << d);
+        // Verify bit 0 is never set (digits are 1-indexed)
+        kani::assert(!dom.has(0), "bit 0 should never be set");
 
-        kani::assert(bit_after != 0, "place should set digit bit in col_mask");
+        // Verify all bits 1..=n are set
+        for d in 1..=n {
+            kani::assert(dom.has(d), "bit d should be set");
+        }
     }
 
-    /// Proves unplace() clears the digit bit in row_mask.
+    /// Proves full_domain(n) has exactly n bits set (bits 1..=n).
     #[kani::proof]
-    fn unplace_clears_row_mask() {
-        let n: u8 = kani::any();
-        kani::assume(n >= 2 && n <= 9);
+    fn full_domain_has_n_bits() {
+        check_full_domain::<u64>(32);
+    }
 
-        let row: usize = kani::any();
-        let col: usize = kani::any();
-        let d: u8 = kani::any();
-        kani::assume(row < n as usize && col < n as usize);
-        kani::assume(d >= 1 && d <= n);
+    #[kani::proof]
+    fn full_domain_has_n_bits_u32() {
+        check_full_domain::<u32>(31);
+    }
 
-        let a = (n as usize) * (n as usize);
-        let mut state = State {
-            n,
-            grid: vec![0; a],
-            row_mask: vec![0u64; n as usize],
-            col_mask: vec![0u64; n as usize],
-            cage_of_cell: vec![0; a],
-            tuple_cache: HashMap::new(),
-            mrv_cache: MrvCache::new(n),
-            #[cfg(feature = "nogood-learning")]
-            nogood_cache: None,
-        };
+    fn check_place_sets_masks<M: Mask>(max_n: u8) {
+        let (n, row, col, d) = any_placement(max_n);
+        let mut state = empty_state::<M>(n);
 
-        // Place then unplace
         place(&mut state, row, col, d);
-        unplace(&mut state, row, col, d);
 
-        let bit_after = state.row_mask[row] & (1u64 << d);
-        kani::assert(bit_after == 0, "unplace should clear digit bit in row_mask");
+        kani::assert(
+            state.row_mask(row).has(d),
+            "place should set digit bit in the row mask",
+        );
+        kani::assert(
+            state.col_mask(col).has(d),
+            "place should set digit bit in the column mask",
+        );
+        kani::assert(
+            state.grid[row * (n as usize) + col] == d,
+            "grid should contain placed digit",
+        );
     }
 
-    /// Proves unplace() clears the digit bit in col_mask.
+    /// Proves place() sets the digit bit in the row and column masks and the grid cell.
     #[kani::proof]
-    fn unplace_clears_col_mask() {
-        let n: u8 = kani::any();
-        kani::assume(n >= 2 && n <= 9);
+    fn place_sets_masks() {
+        check_place_sets_masks::<u64>(9);
+    }
 
-        let row: usize = kani::any();
-        let col: usize = kani::any();
-        let d: u8 = kani::any();
-        kani::assume(row < n as usize && col < n as usize);
-        kani::assume(d >= 1 && d <= n);
+    #[kani::proof]
+    fn place_sets_masks_u32() {
+        check_place_sets_masks::<u32>(9);
+    }
 
-        let a = (n as usize) * (n as usize);
-        let mut state = State {
-            n,
-            grid: vec![0; a],
-            row_mask: vec![0u64; n as usize],
-            col_mask: vec![0u64; n as usize],
-            cage_of_cell: vec![0; a],
-            tuple_cache: HashMap::new(),
-            mrv_cache: MrvCache::new(n),
-            #[cfg(feature = "nogood-learning")]
-            nogood_cache: None,
-        };
+    fn check_place_leaves_static_masks<M: Mask>(max_n: u8) {
+        let (n, row, col, d) = any_placement(max_n);
+        let mut state = empty_state::<M>(n);
+        let statics_before = state.masks[2 * n as usize..].to_vec();
 
-        // Place then unplace
         place(&mut state, row, col, d);
-        unplace(&mut state, row, col, d);
 
-        let bit_after = state.col_mask[col] & (1u64 << d);
-        kani::assert(bit_after == 0, "unplace should clear digit bit in col_mask");
+        kani::assert(
+            state.masks[2 * n as usize..] == statics_before[..],
+            "place should only touch the row and column slots of masks",
+        );
     }
 
-    /// Proves place/unplace roundtrip restores masks to original state.
+    /// Proves place() writes no further than the row and column slots of `State::masks`.
     #[kani::proof]
-    fn place_unplace_roundtrip() {
-        let n: u8 = kani::any();
-        kani::assume(n >= 2 && n <= 9);
+    fn place_leaves_static_masks() {
+        check_place_leaves_static_masks::<u64>(9);
+    }
 
-        let row: usize = kani::any();
-        let col: usize = kani::any();
-        let d: u8 = kani::any();
-        kani::assume(row < n as usize && col < n as usize);
-        kani::assume(d >= 1 && d <= n);
+    #[kani::proof]
+    fn place_leaves_static_masks_u32() {
+        check_place_leaves_static_masks::<u32>(9);
+    }
 
-        let a = (n as usize) * (n as usize);
-        let mut state = State {
-            n,
-            grid: vec![0; a],
-            row_mask: vec![0u64; n as usize],
-            col_mask: vec![0u64; n as usize],
-            cage_of_cell: vec![0; a],
-            tuple_cache: HashMap::new(),
-            mrv_cache: MrvCache::new(n),
-            #[cfg(feature = "nogood-learning")]
-            nogood_cache: None,
-        };
+    fn check_place_unplace_roundtrip<M: Mask>(max_n: u8) {
+        let (n, row, col, d) = any_placement(max_n);
+        let mut state = empty_state::<M>(n);
 
-        let row_before = state.row_mask[row];
-        let col_before = state.col_mask[col];
+        let masks_before = state.masks.clone();
         let grid_before = state.grid[row * (n as usize) + col];
 
+        // Place then unplace
         place(&mut state, row, col, d);
         unplace(&mut state, row, col, d);
 
         kani::assert(
-            state.row_mask[row] == row_before,
-            "row_mask should be restored after roundtrip",
+            !state.row_mask(row).has(d) && !state.col_mask(col).has(d),
+            "unplace should clear digit bit in the row and column masks",
         );
         kani::assert(
-            state.col_mask[col] == col_before,
-            "col_mask should be restored after roundtrip",
+            state.masks == masks_before,
+            "masks should be restored after roundtrip",
         );
         kani::assert(
             state.grid[row * (n as usize) + col] == grid_before,
@@ -2510,101 +5943,85 @@ mod kani_verification {
         );
     }
 
-    /// Proves domain computation excludes digits placed in the same row.
-    ///
-    /// Key Latin constraint: if digit d is placed in row r,
-    /// then domain for any other cell in row r must NOT include d.
+    /// Proves place/unplace roundtrip restores masks and grid to their original state.
     #[kani::proof]
-    fn domain_excludes_placed_in_row() {
-        let n: u8 = kani::any();
-        kani::assume(n >= 2 && n <= 9);
+    fn place_unplace_roundtrip() {
+        check_place_unplace_roundtrip::<u64>(9);
+    }
 
-        let row: usize = kani::any();
-        let col1: usize = kani::any();
-        let col2: usize = kani::any();
-        let d: u8 = kani::any();
-        kani::assume(row < n as usize);
-        kani::assume(col1 < n as usize && col2 < n as usize);
-        kani::assume(col1 != col2);
-        kani::assume(d >= 1 && d <= n);
+    #[kani::proof]
+    fn place_unplace_roundtrip_u32() {
+        check_place_unplace_roundtrip::<u32>(9);
+    }
 
-        let a = (n as usize) * (n as usize);
-        let mut state = State {
-            n,
-            grid: vec![0; a],
-            row_mask: vec![0u64; n as usize],
-            col_mask: vec![0u64; n as usize],
-            cage_of_cell: vec![0; a],
-            tuple_cache: HashMap::new(),
-            mrv_cache: MrvCache::new(n),
-            #[cfg(feature = "nogood-learning")]
-            nogood_cache: None,
-        };
+    /// Key Latin constraint: if digit d is placed in row r, then the domain of any other cell
+    /// in row r must NOT include d.
+    fn check_domain_excludes_placed_in_row<M: Mask>(max_n: u8) {
+        let (n, row, col1, d) = any_placement(max_n);
+        let col2: usize = kani::any();
+        kani::assume(col2 < n as usize && col1 != col2);
+        let mut state = empty_state::<M>(n);
 
         // Place digit d at (row, col1)
         place(&mut state, row, col1, d);
 
-        // Compute domain for cell (row, col2) - another cell in same row
-        let full = full_domain(n);
-        let domain = full & !state.row_mask[row] & !state.col_mask[col2];
+        // Domain for cell (row, col2) - another cell in same row
+        let idx = row * n as usize + col2;
+        let domain = domain_for_cell(&state, idx, row, col2);
 
-        // Domain should NOT include digit d
         kani::assert(
-            (domain & (1u64 << d)) == 0,
+            !domain.has(d),
             "domain should exclude digit placed in same row",
         );
     }
 
-    /// Proves domain computation excludes digits placed in the same column.
-    ///
-    /// Key Latin constraint: if digit d is placed in column c,
-    /// then domain for any other cell in column c must NOT include d.
+    /// Proves domain computation excludes digits placed in the same row.
     #[kani::proof]
-    fn domain_excludes_placed_in_col() {
-        let n: u8 = kani::any();
-        kani::assume(n >= 2 && n <= 9);
+    fn domain_excludes_placed_in_row() {
+        check_domain_excludes_placed_in_row::<u64>(9);
+    }
 
-        let col: usize = kani::any();
-        let row1: usize = kani::any();
-        let row2: usize = kani::any();
-        let d: u8 = kani::any();
-        kani::assume(col < n as usize);
-        kani::assume(row1 < n as usize && row2 < n as usize);
-        kani::assume(row1 != row2);
-        kani::assume(d >= 1 && d <= n);
+    #[kani::proof]
+    fn domain_excludes_placed_in_row_u32() {
+        check_domain_excludes_placed_in_row::<u32>(9);
+    }
 
-        let a = (n as usize) * (n as usize);
-        let mut state = State {
-            n,
-            grid: vec![0; a],
-            row_mask: vec![0u64; n as usize],
-            col_mask: vec![0u64; n as usize],
-            cage_of_cell: vec![0; a],
-            tuple_cache: HashMap::new(),
-            mrv_cache: MrvCache::new(n),
-            #[cfg(feature = "nogood-learning")]
-            nogood_cache: None,
-        };
+    /// Key Latin constraint: if digit d is placed in column c, then the domain of any other
+    /// cell in column c must NOT include d.
+    fn check_domain_excludes_placed_in_col<M: Mask>(max_n: u8) {
+        let (n, row1, col, d) = any_placement(max_n);
+        let row2: usize = kani::any();
+        kani::assume(row2 < n as usize && row1 != row2);
+        let mut state = empty_state::<M>(n);
 
         // Place digit d at (row1, col)
         place(&mut state, row1, col, d);
 
-        // Compute domain for cell (row2, col) - another cell in same column
-        let full = full_domain(n);
-        let domain = full & !state.row_mask[row2] & !state.col_mask[col];
+        // Domain for cell (row2, col) - another cell in same column
+        let idx = row2 * n as usize + col;
+        let domain = domain_for_cell(&state, idx, row2, col);
 
-        // Domain should NOT include digit d
         kani::assert(
-            (domain & (1u64 << d)) == 0,
+            !domain.has(d),
             "domain should exclude digit placed in same column",
         );
     }
 
-    /// Proves grid cell value matches placed digit.
+    /// Proves domain computation excludes digits placed in the same column.
     #[kani::proof]
-    fn place_sets_grid_value() {
-        let n: u8 = kani::any();
-        kani::assume(n >= 2 && n <= 9);
+    fn domain_excludes_placed_in_col() {
+        check_domain_excludes_placed_in_col::<u64>(9);
+    }
+
+    #[kani::proof]
+    fn domain_excludes_placed_in_col_u32() {
+        check_domain_excludes_placed_in_col::<u32>(9);
+    }
+
+    /// Proves place/unplace use the full u64 mask width (digits above 31 on large grids).
+    #[kani::proof]
+    fn place_unplace_roundtrip_wide_digits() {
+        let n: u8 = 32;
 
         let row: usize = kani::any();
         let col: usize = kani::any();
@@ -2612,22 +6029,18 @@ mod kani_verification {
         kani::assume(row < n as usize && col < n as usize);
         kani::assume(d >= 1 && d <= n);
 
-        let a = (n as usize) * (n as usize);
-        let mut state = State {
-            n,
-            grid: vec![0; a],
-            row_mask: vec![0u64; n as usize],
-            col_mask: vec![0u64; n as usize],
-            cage_of_cell: vec![0; a],
-            tuple_cache: HashMap::new(),
-            mrv_cache: MrvCache::new(n),
-            #[cfg(feature = "nogood-learning")]
-            nogood_cache: None,
-        };
+        let mut state = empty_state::<u64>(n);
 
         place(&mut state, row, col, d);
-        let idx = row * (n as usize) + col;
+        kani::assert(
+            state.row_mask(row) == 1u64 << d && state.col_mask(col) == 1u64 << d,
+            "place should set exactly the digit bit, including bits above 31",
+        );
 
-        kani::assert(state.grid[idx] == d, "grid should contain placed digit");
+        unplace(&mut state, row, col, d);
+        kani::assert(
+            state.row_mask(row) == 0 && state.col_mask(col) == 0,
+            "unplace should clear the digit bit, including bits above 31",
+        );
     }
 }
@@ -0,0 +1,176 @@
+//! Aggregates of [`SolveStats`] over many solves, for corpus analysis and benchmarks.
+
+use crate::SolveStats;
+
+/// Mean, median and 95th percentile of one counter.
+///
+/// Percentiles are nearest-rank on the sorted values: the `p`-th percentile of `len` values is
+/// the `ceil(p / 100 * len)`-th smallest, so the median of an even count is the lower middle.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Distribution {
+    pub mean: f64,
+    pub median: u64,
+    pub p95: u64,
+}
+
+impl Distribution {
+    /// Summarize `values`, sorting them in place; all zero when empty.
+    pub fn from_values(values: &mut [u64]) -> Self {
+        if values.is_empty() {
+            return Self::default();
+        }
+        values.sort_unstable();
+        let total: u128 = values.iter().map(|&v| v as u128).sum();
+        Self {
+            mean: total as f64 / values.len() as f64,
+            median: nearest_rank(values, 50),
+            p95: nearest_rank(values, 95),
+        }
+    }
+}
+
+/// The `percent`-th percentile of non-empty, sorted `values`, by nearest rank.
+fn nearest_rank(values: &[u64], percent: usize) -> u64 {
+    let rank = (percent * values.len()).div_ceil(100).max(1);
+    values[rank - 1]
+}
+
+/// Summary of a set of solves: their merged totals and the spread of nodes and assignments.
+///
+/// Built with `collect()` or [`StatsSummary::from_iter`] over [`SolveStats`].
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct StatsSummary {
+    /// Number of solves summarized.
+    pub count: usize,
+    /// Every solve merged with [`SolveStats::merge`].
+    pub total: SolveStats,
+    pub nodes: Distribution,
+    pub assignments: Distribution,
+}
+
+impl FromIterator<SolveStats> for StatsSummary {
+    fn from_iter<I: IntoIterator<Item = SolveStats>>(iter: I) -> Self {
+        let mut total = SolveStats::default();
+        let mut nodes = Vec::new();
+        let mut assignments = Vec::new();
+        for stats in iter {
+            total.merge(&stats);
+            nodes.push(stats.nodes_visited);
+            assignments.push(stats.assignments);
+        }
+        Self {
+            count: nodes.len(),
+            total,
+            nodes: Distribution::from_values(&mut nodes),
+            assignments: Distribution::from_values(&mut assignments),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // The update fills the `nogood-learning` fields when that feature is on.
+    #[allow(clippy::needless_update)]
+    fn stats(
+        nodes_visited: u64,
+        assignments: u64,
+        max_depth: u32,
+        backtracked: bool,
+    ) -> SolveStats {
+        SolveStats {
+            nodes_visited,
+            assignments,
+            max_depth,
+            backtracked,
+            degraded_cages: 1,
+            propagation_rounds: 2,
+            ..SolveStats::default()
+        }
+    }
+
+    #[test]
+    fn merge_sums_counters_maxes_depth_and_ors_backtracked() {
+        let mut a = stats(10, 4, 3, false);
+        a.merge(&stats(5, 7, 9, true));
+        assert_eq!(
+            a,
+            SolveStats {
+                degraded_cages: 2,
+                propagation_rounds: 4,
+                ..stats(15, 11, 9, true)
+            }
+        );
+        assert_eq!(stats(10, 4, 3, false) + stats(5, 7, 9, true), a);
+
+        let mut b = stats(1, 1, 1, false);
+        b += stats(1, 1, 0, false);
+        assert_eq!((b.max_depth, b.backtracked), (1, false));
+        assert_eq!(SolveStats::default() + a, a);
+    }
+
+    #[test]
+    fn percentiles_use_nearest_rank() {
+        // 1..=20: median is the 10th value, p95 the 19th.
+        let summary: StatsSummary = (1..=20).map(|v| stats(v, 2 * v, 0, false)).rev().collect();
+        assert_eq!(summary.count, 20);
+        assert_eq!(
+            summary.nodes,
+            Distribution {
+                mean: 10.5,
+                median: 10,
+                p95: 19,
+            }
+        );
+        assert_eq!(
+            summary.assignments,
+            Distribution {
+                mean: 21.0,
+                median: 20,
+                p95: 38,
+            }
+        );
+        assert_eq!(summary.total.nodes_visited, 210);
+
+        // Odd count: the true middle; a single large outlier only moves p95 at this size.
+        let summary: StatsSummary = [3, 1, 2, 1000, 5]
+            .into_iter()
+            .map(|v| stats(v, v, 0, false))
+            .collect();
+        assert_eq!((summary.nodes.median, summary.nodes.p95), (3, 1000));
+        assert_eq!(summary.nodes.mean, 202.2);
+    }
+
+    #[test]
+    fn single_and_empty_inputs() {
+        let one = StatsSummary::from_iter([stats(7, 3, 2, true)]);
+        assert_eq!(
+            one.nodes,
+            Distribution {
+                mean: 7.0,
+                median: 7,
+                p95: 7,
+            }
+        );
+        assert!(one.total.backtracked);
+
+        let empty = StatsSummary::from_iter(std::iter::empty());
+        assert_eq!(empty, StatsSummary::default());
+        assert_eq!(empty.count, 0);
+        assert_eq!(empty.nodes.mean, 0.0);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn summary_round_trips_through_json() {
+        let summary: StatsSummary = (1..=4).map(|v| stats(v, v, 1, v == 2)).collect();
+        let json = serde_json::to_string(&summary).unwrap();
+        assert_eq!(
+            serde_json::from_str::<StatsSummary>(&json).unwrap(),
+            summary
+        );
+    }
+}
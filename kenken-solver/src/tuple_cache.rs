@@ -0,0 +1,314 @@
+//! Tuple enumerations shared across solves.
+//!
+//! A cage deduction enumerates every digit tuple the cage admits under its cells' current
+//! domains. While those domains are still full, the result depends only on the grid size, the
+//! clue and the cage's shape, not on where the cage sits or on the rest of the puzzle, so a
+//! generator counting thousands of candidate puzzles keeps re-deriving the same tables. A
+//! [`TupleCacheHandle`] in [`SearchOptions::tuple_cache`](crate::SearchOptions::tuple_cache)
+//! keeps them for every solve it is passed to.
+//!
+//! Only full-domain enumerations are cached, and only ones that fit
+//! [`SearchOptions::max_tuples_per_cage`](crate::SearchOptions::max_tuples_per_cage), so a
+//! solve deduces exactly what it would without the cache: same results, same
+//! [`SolveStats`](crate::SolveStats).
+//!
+//! [`TupleCacheHandle::with_byte_limit`] bounds the store for memory-constrained callers: once
+//! it is full, new shapes are enumerated and used but not kept.
+
+use std::collections::HashMap;
+use std::mem::size_of;
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+
+use kenken_core::Cage;
+use kenken_core::rules::Op;
+
+use crate::solver::{cage_tuple_satisfies, violates_in_cage_rowcol};
+
+/// Grid size, op (as its discriminant), target and the cage's cells relative to its bounding
+/// box, in cage order.
+type ShapeKey = (u8, u8, i64, Vec<(u8, u8)>);
+
+/// What a full-domain enumeration of one cage shape found, relative to its bounding box.
+#[derive(Debug)]
+pub(crate) struct SharedTuples {
+    /// Digits each cage position takes in some tuple (bit `v` = digit `v`).
+    pub(crate) per_pos: Vec<u64>,
+    /// Union of `per_pos`; zero when the clue has no tuple at all.
+    pub(crate) any: u64,
+    /// Per bounding-box row: digits every tuple places in that row.
+    pub(crate) must_row: Vec<u64>,
+    /// Per bounding-box column: digits every tuple places in that column.
+    pub(crate) must_col: Vec<u64>,
+    /// Tuples found.
+    pub(crate) count: u32,
+    /// Enumeration nodes spent, on the solver's budget scale.
+    pub(crate) nodes: usize,
+    /// Whether the enumeration finished within its budget; if not, `nodes` is that budget and
+    /// the masks are partial.
+    complete: bool,
+}
+
+#[derive(Default)]
+struct Inner {
+    entries: Mutex<HashMap<ShapeKey, Arc<SharedTuples>>>,
+    hits: AtomicU64,
+    misses: AtomicU64,
+    /// [`entry_footprint`] summed over `entries`; only changed under their lock.
+    bytes: AtomicUsize,
+    /// Most bytes the entries may hold; `None` leaves the store unbounded.
+    byte_limit: Option<usize>,
+}
+
+/// Shared, thread-safe store of full-domain cage enumerations.
+///
+/// Clones share one store, so a generator can create a handle per run and hand it to every
+/// count. Two handles compare equal only if they share a store.
+#[derive(Clone, Default)]
+pub struct TupleCacheHandle {
+    inner: Arc<Inner>,
+}
+
+/// Lookup counters of a [`TupleCacheHandle`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TupleCacheStats {
+    pub hits: u64,
+    pub misses: u64,
+    /// Cage shapes stored.
+    pub entries: usize,
+    /// Estimated bytes the stored shapes hold.
+    pub bytes: usize,
+}
+
+impl TupleCacheStats {
+    /// Share of lookups answered from the store (0 before any lookup).
+    pub fn hit_rate(&self) -> f64 {
+        let lookups = self.hits + self.misses;
+        if lookups == 0 {
+            0.0
+        } else {
+            self.hits as f64 / lookups as f64
+        }
+    }
+}
+
+impl TupleCacheHandle {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// A store holding at most `limit` bytes of shapes, by estimate. A shape that would push
+    /// it past the limit is still enumerated and used, just not kept, so solves are unchanged
+    /// and only the hit rate suffers.
+    pub fn with_byte_limit(limit: usize) -> Self {
+        Self {
+            inner: Arc::new(Inner {
+                byte_limit: Some(limit),
+                ..Inner::default()
+            }),
+        }
+    }
+
+    /// Counters since the handle was created.
+    pub fn stats(&self) -> TupleCacheStats {
+        let entries = self.lock();
+        TupleCacheStats {
+            hits: self.inner.hits.load(Ordering::Relaxed),
+            misses: self.inner.misses.load(Ordering::Relaxed),
+            entries: entries.len(),
+            bytes: self.inner.bytes.load(Ordering::Relaxed),
+        }
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, HashMap<ShapeKey, Arc<SharedTuples>>> {
+        // A panic mid-insert leaves the map consistent, so a poisoned lock is still usable.
+        self.inner
+            .entries
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    /// Tuples of `cage` on an `n` x `n` grid with every cell's domain full, or `None` if the
+    /// enumeration takes `budget` nodes or more (the solver degrades such cages to bounds).
+    /// Misses are enumerated without holding the store's lock.
+    pub(crate) fn full_domain_tuples(
+        &self,
+        n: u8,
+        cage: &Cage,
+        budget: usize,
+    ) -> Option<Arc<SharedTuples>> {
+        let coords: Vec<(usize, usize)> = cage
+            .cells
+            .iter()
+            .map(|c| (c.0 as usize / n as usize, c.0 as usize % n as usize))
+            .collect();
+        let key = shape_key(n, cage, &coords);
+        let cached = self.lock().get(&key).cloned();
+        let tuples = match cached {
+            // An enumeration cut off at `nodes` still answers every budget up to `nodes`.
+            Some(tuples) if tuples.complete || budget <= tuples.nodes => {
+                self.inner.hits.fetch_add(1, Ordering::Relaxed);
+                tuples
+            }
+            _ => {
+                self.inner.misses.fetch_add(1, Ordering::Relaxed);
+                let tuples = Arc::new(enumerate_full(n, cage, &key.3, budget));
+                self.store(key, &tuples);
+                tuples
+            }
+        };
+        (tuples.complete && tuples.nodes < budget).then_some(tuples)
+    }
+
+    /// Keep `tuples` under `key` if the byte limit allows.
+    fn store(&self, key: ShapeKey, tuples: &Arc<SharedTuples>) {
+        let mut entries = self.lock();
+        let freed = match entries.get(&key) {
+            None => 0,
+            // Another solve may have stored a fuller enumeration meanwhile; keep that one.
+            Some(slot) if !slot.complete && (tuples.complete || tuples.nodes > slot.nodes) => {
+                entry_footprint(&key, slot)
+            }
+            Some(_) => return,
+        };
+        let bytes =
+            self.inner.bytes.load(Ordering::Relaxed) - freed + entry_footprint(&key, tuples);
+        if self.inner.byte_limit.is_none_or(|limit| bytes <= limit) {
+            self.inner.bytes.store(bytes, Ordering::Relaxed);
+            entries.insert(key, Arc::clone(tuples));
+        }
+    }
+}
+
+/// Estimated bytes one stored shape holds: its map slot, the key's cells, and the
+/// enumeration behind its `Arc` with the reference counts.
+fn entry_footprint(key: &ShapeKey, tuples: &SharedTuples) -> usize {
+    let masks = tuples.per_pos.len() + tuples.must_row.len() + tuples.must_col.len();
+    size_of::<(ShapeKey, Arc<SharedTuples>)>()
+        + key.3.len() * size_of::<(u8, u8)>()
+        + 2 * size_of::<usize>()
+        + size_of::<SharedTuples>()
+        + masks * size_of::<u64>()
+}
+
+impl core::fmt::Debug for TupleCacheHandle {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_tuple("TupleCacheHandle")
+            .field(&self.stats())
+            .finish()
+    }
+}
+
+impl PartialEq for TupleCacheHandle {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.inner, &other.inner)
+    }
+}
+
+impl Eq for TupleCacheHandle {}
+
+fn shape_key(n: u8, cage: &Cage, coords: &[(usize, usize)]) -> ShapeKey {
+    let row0 = coords.iter().map(|&(r, _)| r).min().unwrap_or(0);
+    let col0 = coords.iter().map(|&(_, c)| c).min().unwrap_or(0);
+    let shape = coords
+        .iter()
+        .map(|&(r, c)| ((r - row0) as u8, (c - col0) as u8))
+        .collect();
+    (n, cage.op as u8, cage.target, shape)
+}
+
+/// The solver's tuple enumeration over full domains: same order, pruning and node count. Every
+/// prefilter the solver applies first only shrinks the domains, so when this fits `budget`, the
+/// solver's own enumeration would have too.
+fn enumerate_full(n: u8, cage: &Cage, shape: &[(u8, u8)], budget: usize) -> SharedTuples {
+    let coords: Vec<(usize, usize)> = shape
+        .iter()
+        .map(|&(r, c)| (r as usize, c as usize))
+        .collect();
+    let rows = coords.iter().map(|&(r, _)| r + 1).max().unwrap_or(0);
+    let cols = coords.iter().map(|&(_, c)| c + 1).max().unwrap_or(0);
+    let mut walk = Walk {
+        n,
+        cage,
+        coords: &coords,
+        chosen: Vec::with_capacity(coords.len()),
+        budget,
+        tuples: SharedTuples {
+            per_pos: vec![0; coords.len()],
+            any: 0,
+            must_row: vec![u64::MAX; rows],
+            must_col: vec![u64::MAX; cols],
+            count: 0,
+            nodes: 0,
+            complete: false,
+        },
+    };
+    walk.visit(0, 1);
+    let mut tuples = walk.tuples;
+    tuples.complete = tuples.nodes < budget;
+    if tuples.any == 0 {
+        tuples.must_row.fill(0);
+        tuples.must_col.fill(0);
+    }
+    tuples
+}
+
+struct Walk<'a> {
+    n: u8,
+    cage: &'a Cage,
+    coords: &'a [(usize, usize)],
+    chosen: Vec<u8>,
+    budget: usize,
+    tuples: SharedTuples,
+}
+
+impl Walk<'_> {
+    fn visit(&mut self, sum: i64, prod: i64) {
+        if self.tuples.nodes == self.budget {
+            return;
+        }
+        self.tuples.nodes += 1;
+        let pos = self.chosen.len();
+        if pos == self.coords.len() {
+            if cage_tuple_satisfies(self.cage, &self.chosen) {
+                self.record();
+            }
+            return;
+        }
+        for v in 1..=self.n {
+            if violates_in_cage_rowcol(self.coords, &self.chosen, pos, v) {
+                continue;
+            }
+            let (sum, prod) = (sum + v as i64, prod.saturating_mul(v as i64));
+            let viable = match self.cage.op {
+                Op::Add => sum <= self.cage.target,
+                Op::Mul => self.cage.target % prod == 0,
+                Op::Sub | Op::Div | Op::Eq => true,
+            };
+            if viable {
+                self.chosen.push(v);
+                self.visit(sum, prod);
+                self.chosen.pop();
+            }
+        }
+    }
+
+    fn record(&mut self) {
+        let tuples = &mut self.tuples;
+        tuples.count = tuples.count.saturating_add(1);
+        let mut rows = vec![0u64; tuples.must_row.len()];
+        let mut cols = vec![0u64; tuples.must_col.len()];
+        for (pos, (&v, &(r, c))) in self.chosen.iter().zip(self.coords).enumerate() {
+            tuples.per_pos[pos] |= 1 << v;
+            tuples.any |= 1 << v;
+            rows[r] |= 1 << v;
+            cols[c] |= 1 << v;
+        }
+        for (must, bits) in tuples.must_row.iter_mut().zip(rows) {
+            *must &= bits;
+        }
+        for (must, bits) in tuples.must_col.iter_mut().zip(cols) {
+            *must &= bits;
+        }
+    }
+}
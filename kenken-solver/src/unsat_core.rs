@@ -0,0 +1,144 @@
+//! Unsat cores: which cages make an unsolvable puzzle unsolvable.
+//!
+//! A core is a set of cages whose clues, with the Latin rules, already admit no solution; every
+//! other cage's arithmetic is ignored (as in `count_solutions_up_to_ignoring_cages`). Cores are
+//! found by deletion: starting from every cage, each cage in index order is dropped for good
+//! if the rest stays unsolvable.
+
+use crate::solver::unsat_ignoring_cages_within;
+use crate::{DeductionTier, Puzzle, Ruleset, SolveError, count_solutions_up_to_with_deductions};
+
+/// Search nodes spent on each deletion check. A check that runs out keeps its cage in the
+/// core, so the core stays unsolvable but may not be minimal.
+pub const UNSAT_CORE_NODE_BUDGET: u64 = 100_000;
+
+/// Cage indices, ascending, of a small unsolvable subset of `puzzle`'s cages, or `None` if
+/// `puzzle` has a solution.
+///
+/// Deletion stops as soon as the core has at most `max_core` cages; pass 0 for a minimal core,
+/// one from which no cage can be dropped. Each deletion check searches at most
+/// [`UNSAT_CORE_NODE_BUDGET`] nodes. Deciding that `puzzle` is unsolvable in the first place
+/// is a full count and is not budgeted.
+pub fn find_unsat_core(
+    puzzle: &Puzzle,
+    rules: Ruleset,
+    tier: DeductionTier,
+    max_core: usize,
+) -> Result<Option<Vec<usize>>, SolveError> {
+    if count_solutions_up_to_with_deductions(puzzle, rules, tier, 1)? != 0 {
+        return Ok(None);
+    }
+
+    let mut free = vec![false; puzzle.cages.len()];
+    let mut core_len = free.len();
+    for idx in 0..free.len() {
+        if core_len <= max_core {
+            break;
+        }
+        free[idx] = true;
+        let still_unsat =
+            unsat_ignoring_cages_within(puzzle, rules, tier, free.clone(), UNSAT_CORE_NODE_BUDGET)?;
+        if still_unsat == Some(true) {
+            core_len -= 1;
+        } else {
+            free[idx] = false;
+        }
+    }
+    Ok(Some((0..free.len()).filter(|&idx| !free[idx]).collect()))
+}
+
+#[cfg(test)]
+mod tests {
+    use kenken_core::PuzzleBuilder;
+    use kenken_core::rules::Op;
+
+    use super::*;
+    use crate::reference::reference_count_ignoring;
+
+    const TIERS: [DeductionTier; 4] = [
+        DeductionTier::None,
+        DeductionTier::Easy,
+        DeductionTier::Normal,
+        DeductionTier::Hard,
+    ];
+
+    /// Sum dominoes over `(r + c) mod 4 + 1`, except that (2,2) and (2,3) are both given as 1
+    /// (cages 5 and 6).
+    fn clashing_givens() -> Puzzle {
+        PuzzleBuilder::new(4)
+            .cage(Op::Add, 3, &[(0, 0), (0, 1)])
+            .unwrap()
+            .cage(Op::Add, 7, &[(0, 2), (0, 3)])
+            .unwrap()
+            .cage(Op::Add, 5, &[(1, 0), (1, 1)])
+            .unwrap()
+            .cage(Op::Add, 5, &[(1, 2), (1, 3)])
+            .unwrap()
+            .cage(Op::Add, 7, &[(2, 0), (2, 1)])
+            .unwrap()
+            .cage(Op::Eq, 1, &[(2, 2)])
+            .unwrap()
+            .cage(Op::Eq, 1, &[(2, 3)])
+            .unwrap()
+            .cage(Op::Add, 5, &[(3, 0), (3, 1)])
+            .unwrap()
+            .cage(Op::Add, 5, &[(3, 2), (3, 3)])
+            .unwrap()
+            .build(Ruleset::keen_baseline())
+            .unwrap()
+    }
+
+    #[test]
+    fn finds_the_clashing_givens() {
+        let rules = Ruleset::keen_baseline();
+        let puzzle = clashing_givens();
+        for tier in TIERS {
+            let core = find_unsat_core(&puzzle, rules, tier, 0).unwrap();
+            assert_eq!(core, Some(vec![5, 6]), "{tier:?}");
+        }
+    }
+
+    #[test]
+    fn minimal_core_is_unsolvable_and_every_cage_is_needed() {
+        let rules = Ruleset::keen_baseline();
+        let puzzle = clashing_givens();
+        let core = find_unsat_core(&puzzle, rules, DeductionTier::Normal, 0)
+            .unwrap()
+            .unwrap();
+        let outside = |keep: &[usize]| -> Vec<usize> {
+            (0..puzzle.cages.len())
+                .filter(|idx| !keep.contains(idx))
+                .collect()
+        };
+
+        assert_eq!(
+            reference_count_ignoring(&puzzle, rules, &outside(&core)).unwrap(),
+            0
+        );
+        for dropped in &core {
+            let rest: Vec<usize> = core.iter().copied().filter(|c| c != dropped).collect();
+            assert!(reference_count_ignoring(&puzzle, rules, &outside(&rest)).unwrap() > 0);
+        }
+    }
+
+    #[test]
+    fn max_core_stops_deletion_early() {
+        let rules = Ruleset::keen_baseline();
+        let puzzle = clashing_givens();
+        let core = find_unsat_core(&puzzle, rules, DeductionTier::Normal, 5)
+            .unwrap()
+            .unwrap();
+        assert_eq!(core.len(), 5);
+        assert!(core.contains(&5) && core.contains(&6), "{core:?}");
+    }
+
+    #[test]
+    fn solvable_puzzle_has_no_core() {
+        let rules = Ruleset::keen_baseline();
+        let puzzle = kenken_core::format::sgt_desc::parse_keen_desc(2, "b__,a3a3").unwrap();
+        assert_eq!(
+            find_unsat_core(&puzzle, rules, DeductionTier::Normal, 0).unwrap(),
+            None
+        );
+    }
+}
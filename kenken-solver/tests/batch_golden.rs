@@ -0,0 +1,79 @@
+//! Batch APIs against the golden corpus.
+//!
+//! `solve_batch` and `classify_batch` must agree slot-for-slot with calling the
+//! single-puzzle functions in a loop, whether or not `parallel-rayon` is enabled.
+
+use kenken_core::Puzzle;
+use kenken_core::format::sgt_desc::parse_keen_desc;
+use kenken_core::rules::Ruleset;
+use kenken_solver::{
+    DeductionTier, classify_batch, classify_tier_required, solve_batch, solve_one_with_deductions,
+};
+
+mod common;
+use common::load_corpus;
+
+fn golden_puzzles(rules: Ruleset) -> Vec<Puzzle> {
+    load_corpus("golden.jsonl")
+        .into_iter()
+        .map(|def| parse_keen_desc(def.n, &def.desc).unwrap())
+        .filter(|p| p.validate(rules).is_ok())
+        .collect()
+}
+
+#[test]
+fn solve_batch_matches_serial_solves() {
+    let rules = Ruleset::keen_baseline();
+    let puzzles = golden_puzzles(rules);
+    assert!(!puzzles.is_empty());
+
+    for tier in [
+        DeductionTier::None,
+        DeductionTier::Easy,
+        DeductionTier::Normal,
+        DeductionTier::Hard,
+    ] {
+        let batch = solve_batch(&puzzles, rules, tier);
+        assert_eq!(batch.len(), puzzles.len());
+
+        for (i, (puzzle, got)) in puzzles.iter().zip(batch).enumerate() {
+            let expected = solve_one_with_deductions(puzzle, rules, tier).unwrap();
+            assert_eq!(got.unwrap(), expected, "puzzle #{i} at {tier:?}");
+        }
+    }
+}
+
+#[test]
+fn classify_batch_matches_serial_classification() {
+    let rules = Ruleset::keen_baseline();
+    let puzzles = golden_puzzles(rules);
+
+    let batch = classify_batch(&puzzles, rules);
+    assert_eq!(batch.len(), puzzles.len());
+
+    for (i, (puzzle, got)) in puzzles.iter().zip(batch).enumerate() {
+        let expected = classify_tier_required(puzzle, rules).unwrap();
+        assert_eq!(got.unwrap(), expected, "puzzle #{i}");
+    }
+}
+
+#[test]
+fn batch_reports_errors_per_slot() {
+    let rules = Ruleset::keen_baseline();
+    let good = parse_keen_desc(2, "_5,a1a2a2a1").unwrap();
+    let mut bad = good.clone();
+    bad.n = 200;
+
+    let results = solve_batch(&[good.clone(), bad, good], rules, DeductionTier::Normal);
+    assert_eq!(results.len(), 3);
+    assert!(matches!(results[0], Ok(Some(_))));
+    assert!(results[1].is_err());
+    assert!(matches!(results[2], Ok(Some(_))));
+}
+
+#[test]
+fn empty_batch_is_empty() {
+    let rules = Ruleset::keen_baseline();
+    assert!(solve_batch(&[], rules, DeductionTier::Normal).is_empty());
+    assert!(classify_batch(&[], rules).is_empty());
+}
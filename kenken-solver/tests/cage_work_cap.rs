@@ -0,0 +1,150 @@
+//! `SearchOptions::max_tuples_per_cage`: huge cages degrade to bounds filtering instead of
+//! stalling propagation, without changing any result.
+
+use std::time::{Duration, Instant};
+
+use kenken_core::format::sgt_desc::parse_keen_desc;
+use kenken_core::rules::{Op, Ruleset};
+use kenken_core::{Cage, CellId, Puzzle};
+use kenken_solver::{
+    DeductionTier, SearchOptions, count_solutions_up_to_with_deductions_and_stats,
+    count_solutions_up_to_with_options, solve_one_with_options,
+};
+
+mod common;
+use common::load_corpus;
+
+const TIERS: [DeductionTier; 4] = [
+    DeductionTier::None,
+    DeductionTier::Easy,
+    DeductionTier::Normal,
+    DeductionTier::Hard,
+];
+
+const N: usize = 12;
+
+fn big_cage_rules() -> Ruleset {
+    Ruleset {
+        max_cage_size: 12,
+        ..Ruleset::keen_baseline()
+    }
+}
+
+fn cyclic_solution() -> Vec<u8> {
+    (0..N * N)
+        .map(|idx| ((idx / N + idx % N) % N + 1) as u8)
+        .collect()
+}
+
+/// Rows of `ten_cell_add_12x12` covered by `×` dominoes instead of givens.
+const DOMINO_ROWS: std::ops::Range<usize> = 1..6;
+
+/// 12x12 whose first ten cells of row 0 form one `55+` cage; row 0's last two cells are a
+/// `23+` domino, rows 1-5 are tiled with horizontal `×` dominoes and every other cell is given.
+/// The givens pin only six values per column, so the first propagation round sees the big
+/// cage with six candidates per cell, which is far beyond any sensible enumeration budget.
+fn ten_cell_add_12x12() -> (Puzzle, Vec<u8>) {
+    let solution = cyclic_solution();
+    let sum = |cells: &[usize]| cells.iter().map(|&i| solution[i] as i64).sum();
+    let product = |cells: &[usize]| cells.iter().map(|&i| solution[i] as i64).product();
+    let cage = |cells: &[usize], op, target| Cage {
+        cells: cells.iter().map(|&i| CellId(i as u16)).collect(),
+        op,
+        target,
+    };
+
+    let big: Vec<usize> = (0..10).collect();
+    let tail = [10, 11];
+    let mut cages = vec![
+        cage(&big, Op::Add, sum(&big)),
+        cage(&tail, Op::Add, sum(&tail)),
+    ];
+    for row in DOMINO_ROWS {
+        for col in (0..N).step_by(2) {
+            let pair = [row * N + col, row * N + col + 1];
+            cages.push(cage(&pair, Op::Mul, product(&pair)));
+        }
+    }
+    for (idx, &value) in solution.iter().enumerate().skip(DOMINO_ROWS.end * N) {
+        cages.push(cage(&[idx], Op::Eq, value as i64));
+    }
+    (Puzzle { n: N as u8, cages }, solution)
+}
+
+#[test]
+fn ten_cell_add_cage_degrades_and_solves_quickly() {
+    let rules = big_cage_rules();
+    let (puzzle, solution) = ten_cell_add_12x12();
+    puzzle.validate(rules).unwrap();
+
+    for tier in TIERS {
+        let start = Instant::now();
+        let (sol, stats) =
+            solve_one_with_options(&puzzle, rules, tier, SearchOptions::default()).unwrap();
+        assert!(
+            start.elapsed() < Duration::from_secs(5),
+            "{tier:?} took {:?}",
+            start.elapsed()
+        );
+        assert_eq!(sol.unwrap().grid, solution, "{tier:?}");
+        if tier != DeductionTier::None {
+            assert!(stats.degraded_cages > 0, "{tier:?} never degraded");
+        }
+    }
+}
+
+#[test]
+fn ten_cell_add_cage_counts_exactly_one_solution() {
+    let rules = big_cage_rules();
+    let (puzzle, _) = ten_cell_add_12x12();
+
+    let (count, _) = count_solutions_up_to_with_options(
+        &puzzle,
+        rules,
+        DeductionTier::Hard,
+        2,
+        SearchOptions::default(),
+    )
+    .unwrap();
+    assert_eq!(count, 1);
+}
+
+#[test]
+fn golden_corpus_stays_under_default_cap() {
+    let rules = Ruleset::keen_baseline();
+
+    for def in load_corpus("golden.jsonl") {
+        let puzzle = parse_keen_desc(def.n, &def.desc).unwrap();
+        if puzzle.validate(rules).is_err() {
+            continue;
+        }
+        for tier in TIERS {
+            let (_, stats) =
+                count_solutions_up_to_with_deductions_and_stats(&puzzle, rules, tier, 2).unwrap();
+            assert_eq!(stats.degraded_cages, 0, "{} at {tier:?}", def.label);
+        }
+    }
+}
+
+#[test]
+fn tiny_cap_degrades_without_changing_counts() {
+    let rules = Ruleset::keen_baseline();
+    let tiny = SearchOptions {
+        max_tuples_per_cage: 4,
+        ..SearchOptions::default()
+    };
+
+    for def in load_corpus("golden.jsonl") {
+        let puzzle = parse_keen_desc(def.n, &def.desc).unwrap();
+        if puzzle.validate(rules).is_err() {
+            continue;
+        }
+        for tier in [DeductionTier::Easy, DeductionTier::Hard] {
+            let (expected, _) =
+                count_solutions_up_to_with_deductions_and_stats(&puzzle, rules, tier, 2).unwrap();
+            let (count, _) =
+                count_solutions_up_to_with_options(&puzzle, rules, tier, 2, tiny.clone()).unwrap();
+            assert_eq!(count, expected, "{} at {tier:?}", def.label);
+        }
+    }
+}
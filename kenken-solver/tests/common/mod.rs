@@ -0,0 +1,334 @@
+//! Shared helpers for integration tests.
+//!
+//! # Corpus files
+//!
+//! Puzzle corpora live under `tests/corpus/` as JSON lines: one object per line, with blank
+//! lines and lines starting with `#` ignored. Each object has the fields
+//!
+//! | field           | type             | meaning                                        |
+//! |-----------------|------------------|------------------------------------------------|
+//! | `label`         | string           | unique human-readable name                     |
+//! | `n`             | integer          | grid size                                      |
+//! | `desc`          | string           | SGT-desc puzzle string                         |
+//! | `solutions`     | integer          | expected solution count (1 = unique)           |
+//! | `difficulty`    | string or null   | `easy`/`normal`/`hard`/`extreme`/`unreasonable` |
+//! | `tier_required` | string or null   | `none`/`easy`/`normal`/`hard`; null = guessing |
+//! | `solution`      | int array / null | row-major known solution (unique puzzles only) |
+//! | `content_hash`  | string / null    | `Puzzle::content_hash` as 16 lowercase hex digits |
+//!
+//! The nullable fields may also be omitted.
+//!
+//! # Node-count baselines
+//!
+//! `tests/corpus/node_baselines.json` is a JSON array with one object per line, one per
+//! corpus puzzle and deduction tier, recording how a first-solution search went:
+//!
+//! | field            | type             | meaning                                     |
+//! |------------------|------------------|---------------------------------------------|
+//! | `label`          | string           | corpus label                                |
+//! | `tier`           | string           | `none`/`easy`/`normal`/`hard`                |
+//! | `nodes_visited`  | integer          | `SolveStats::nodes_visited`                 |
+//! | `assignments`    | integer          | `SolveStats::assignments`                   |
+//! | `first_solution` | int array / null | row-major first solution found (null = none) |
+
+// Each test binary compiles this module separately and uses a different subset of it.
+#![allow(dead_code)]
+
+use std::collections::HashSet;
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+use kenken_solver::{DeductionTier, DifficultyTier};
+use serde::{Deserialize, Serialize};
+
+/// A golden puzzle entry with full metadata.
+#[derive(Debug, Clone)]
+pub struct GoldenPuzzle {
+    /// Grid size.
+    pub n: u8,
+    /// SGT-desc format string.
+    pub desc: String,
+    /// Expected solution count (1 = unique).
+    pub solutions: u32,
+    /// Expected difficulty tier (None = unknown/any).
+    pub difficulty: Option<DifficultyTier>,
+    /// Expected minimum deduction tier (None = requires guessing).
+    pub tier_required: Option<DeductionTier>,
+    /// Known solution grid (row-major, None = not verified).
+    pub solution: Option<Vec<u8>>,
+    /// Pinned `Puzzle::content_hash` (None = not pinned).
+    pub content_hash: Option<u64>,
+    /// Human-readable description.
+    pub label: String,
+}
+
+/// A corpus entry that failed to load, with enough context to find and fix it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CorpusError {
+    pub origin: String,
+    /// 1-based line number.
+    pub line: usize,
+    pub text: String,
+    pub message: String,
+}
+
+impl fmt::Display for CorpusError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}:{}: {}\n    {}",
+            self.origin, self.line, self.message, self.text
+        )
+    }
+}
+
+impl std::error::Error for CorpusError {}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RawEntry {
+    label: String,
+    n: u8,
+    desc: String,
+    solutions: u32,
+    #[serde(default)]
+    difficulty: Option<String>,
+    #[serde(default)]
+    tier_required: Option<String>,
+    #[serde(default)]
+    solution: Option<Vec<u8>>,
+    #[serde(default)]
+    content_hash: Option<String>,
+}
+
+/// Path of a corpus file under `tests/corpus/`.
+pub fn corpus_path(name: &str) -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests")
+        .join("corpus")
+        .join(name)
+}
+
+/// Load `tests/corpus/<name>`, panicking with the offending line on any error.
+pub fn load_corpus(name: &str) -> Vec<GoldenPuzzle> {
+    let path = corpus_path(name);
+    let src = std::fs::read_to_string(&path)
+        .unwrap_or_else(|e| panic!("failed to read {}: {e}", path.display()));
+    parse_corpus(&src, &path.display().to_string()).unwrap_or_else(|e| panic!("{e}"))
+}
+
+/// Parse corpus text; `origin` is only used in error messages.
+pub fn parse_corpus(src: &str, origin: &str) -> Result<Vec<GoldenPuzzle>, CorpusError> {
+    let mut out = Vec::new();
+    let mut labels = HashSet::new();
+
+    for (idx, text) in src.lines().enumerate() {
+        let trimmed = text.trim();
+        if trimmed.is_empty() || trimmed.starts_with('#') {
+            continue;
+        }
+        let err = |message: String| CorpusError {
+            origin: origin.to_string(),
+            line: idx + 1,
+            text: trimmed.to_string(),
+            message,
+        };
+
+        let raw: RawEntry =
+            serde_json::from_str(trimmed).map_err(|e| err(format!("invalid entry: {e}")))?;
+        let entry = validate_entry(raw).map_err(err)?;
+        if !labels.insert(entry.label.clone()) {
+            return Err(err(format!("duplicate label '{}'", entry.label)));
+        }
+        out.push(entry);
+    }
+    Ok(out)
+}
+
+fn validate_entry(raw: RawEntry) -> Result<GoldenPuzzle, String> {
+    if raw.label.trim().is_empty() {
+        return Err("label must not be empty".to_string());
+    }
+    if !(2..=32).contains(&raw.n) {
+        return Err(format!("n must be in 2..=32, got {}", raw.n));
+    }
+    if raw.desc.is_empty() {
+        return Err("desc must not be empty".to_string());
+    }
+
+    let difficulty = raw
+        .difficulty
+        .as_deref()
+        .map(parse_difficulty)
+        .transpose()?;
+    let tier_required = raw
+        .tier_required
+        .as_deref()
+        .map(parse_deduction_tier)
+        .transpose()?;
+
+    if let Some(solution) = &raw.solution {
+        if raw.solutions != 1 {
+            return Err(format!(
+                "solution given but solutions = {} (only unique puzzles carry a solution)",
+                raw.solutions
+            ));
+        }
+        let expected_len = (raw.n as usize) * (raw.n as usize);
+        if solution.len() != expected_len {
+            return Err(format!(
+                "solution has {} cells, expected {} for n = {}",
+                solution.len(),
+                expected_len,
+                raw.n
+            ));
+        }
+        if let Some((pos, &v)) = solution
+            .iter()
+            .enumerate()
+            .find(|&(_, &v)| v == 0 || v > raw.n)
+        {
+            return Err(format!(
+                "solution cell {pos} has value {v}, expected 1..={}",
+                raw.n
+            ));
+        }
+    }
+
+    let content_hash = raw
+        .content_hash
+        .as_deref()
+        .map(|hex| {
+            if hex.len() != 16 || hex.bytes().any(|b| !matches!(b, b'0'..=b'9' | b'a'..=b'f')) {
+                return Err(format!(
+                    "content_hash must be 16 lowercase hex digits, got '{hex}'"
+                ));
+            }
+            Ok(u64::from_str_radix(hex, 16).expect("checked hex digits"))
+        })
+        .transpose()?;
+
+    Ok(GoldenPuzzle {
+        n: raw.n,
+        desc: raw.desc,
+        solutions: raw.solutions,
+        difficulty,
+        tier_required,
+        solution: raw.solution,
+        content_hash,
+        label: raw.label,
+    })
+}
+
+fn parse_difficulty(s: &str) -> Result<DifficultyTier, String> {
+    match s {
+        "easy" => Ok(DifficultyTier::Easy),
+        "normal" => Ok(DifficultyTier::Normal),
+        "hard" => Ok(DifficultyTier::Hard),
+        "extreme" => Ok(DifficultyTier::Extreme),
+        "unreasonable" => Ok(DifficultyTier::Unreasonable),
+        _ => Err(format!(
+            "unknown difficulty '{s}' (expected easy, normal, hard, extreme or unreasonable)"
+        )),
+    }
+}
+
+fn parse_deduction_tier(s: &str) -> Result<DeductionTier, String> {
+    match s {
+        "none" => Ok(DeductionTier::None),
+        "easy" => Ok(DeductionTier::Easy),
+        "normal" => Ok(DeductionTier::Normal),
+        "hard" => Ok(DeductionTier::Hard),
+        _ => Err(format!(
+            "unknown tier_required '{s}' (expected none, easy, normal, hard or null)"
+        )),
+    }
+}
+
+/// Lowercase corpus name of `tier`, as `tier_required` and the baselines spell it.
+pub fn deduction_tier_name(tier: DeductionTier) -> &'static str {
+    match tier {
+        DeductionTier::None => "none",
+        DeductionTier::Easy => "easy",
+        DeductionTier::Normal => "normal",
+        DeductionTier::Hard => "hard",
+    }
+}
+
+/// One entry of a node-count baseline file (see the [module docs](self)).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NodeBaseline {
+    pub label: String,
+    pub tier: DeductionTier,
+    pub nodes_visited: u64,
+    pub assignments: u64,
+    pub first_solution: Option<Vec<u8>>,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RawBaseline {
+    label: String,
+    tier: String,
+    nodes_visited: u64,
+    assignments: u64,
+    first_solution: Option<Vec<u8>>,
+}
+
+/// Load `tests/corpus/<name>` as node-count baselines, panicking on a malformed file or a
+/// repeated `(label, tier)`.
+pub fn load_node_baselines(name: &str) -> Vec<NodeBaseline> {
+    let path = corpus_path(name);
+    let src = std::fs::read_to_string(&path)
+        .unwrap_or_else(|e| panic!("failed to read {}: {e}", path.display()));
+    let raw: Vec<RawBaseline> = serde_json::from_str(&src)
+        .unwrap_or_else(|e| panic!("{}: invalid baselines: {e}", path.display()));
+    let mut seen = HashSet::new();
+    raw.into_iter()
+        .map(|raw| {
+            let tier = parse_deduction_tier(&raw.tier)
+                .unwrap_or_else(|e| panic!("{}: '{}': {e}", path.display(), raw.label));
+            assert!(
+                seen.insert((raw.label.clone(), raw.tier)),
+                "{}: duplicate baseline for '{}'",
+                path.display(),
+                raw.label
+            );
+            NodeBaseline {
+                label: raw.label,
+                tier,
+                nodes_visited: raw.nodes_visited,
+                assignments: raw.assignments,
+                first_solution: raw.first_solution,
+            }
+        })
+        .collect()
+}
+
+/// Write `baselines` to `tests/corpus/<name>`, one entry per line. The file is replaced in one
+/// rename, so an interrupted write leaves the old baselines in place.
+pub fn write_node_baselines(name: &str, baselines: &[NodeBaseline]) {
+    let lines: Vec<String> = baselines
+        .iter()
+        .map(|b| {
+            let raw = RawBaseline {
+                label: b.label.clone(),
+                tier: deduction_tier_name(b.tier).to_string(),
+                nodes_visited: b.nodes_visited,
+                assignments: b.assignments,
+                first_solution: b.first_solution.clone(),
+            };
+            format!(
+                "  {}",
+                serde_json::to_string(&raw).expect("baselines serialize")
+            )
+        })
+        .collect();
+    let text = format!("[\n{}\n]\n", lines.join(",\n"));
+
+    let path = corpus_path(name);
+    let tmp = path.with_extension("json.tmp");
+    std::fs::write(&tmp, text).unwrap_or_else(|e| panic!("failed to write {}: {e}", tmp.display()));
+    std::fs::rename(&tmp, &path)
+        .unwrap_or_else(|e| panic!("failed to replace {}: {e}", path.display()));
+}
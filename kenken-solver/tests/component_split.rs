@@ -0,0 +1,154 @@
+//! `SearchOptions::split_components`: searching independent components on their own changes no
+//! count, and multiplies rather than enumerates the solutions of block-diagonal puzzles.
+
+use kenken_core::format::sgt_desc::parse_keen_desc;
+use kenken_core::rules::{Op, Ruleset};
+use kenken_core::{Cage, CellId, Puzzle};
+use kenken_solver::{
+    ComponentSplit, DeductionTier, SearchOptions, count_solutions_up_to_with_options,
+    solve_one_with_options,
+};
+
+mod common;
+use common::load_corpus;
+
+const TIERS: [DeductionTier; 3] = [
+    DeductionTier::Easy,
+    DeductionTier::Normal,
+    DeductionTier::Hard,
+];
+
+fn with_split(split_components: ComponentSplit) -> SearchOptions {
+    SearchOptions {
+        split_components,
+        ..SearchOptions::default()
+    }
+}
+
+/// A 9x9 puzzle whose only open cells are the three diagonal 3x3 blocks, each a Latin square
+/// on its own three digits: every cell off the diagonal blocks is given, and each row of a
+/// diagonal block is one `Add` cage. `targets` overrides those cage targets, block by block.
+fn block_diagonal(targets: Option<[i64; 9]>) -> Puzzle {
+    const INNER: [[u8; 3]; 3] = [[0, 1, 2], [1, 2, 0], [2, 0, 1]];
+    let digit = |r: usize, c: usize| ((r / 3 + c / 3) % 3) as u8 * 3 + INNER[r % 3][c % 3] + 1;
+    let mut cages = Vec::new();
+    for r in 0..9 {
+        for c in (0..9).filter(|c| c / 3 != r / 3) {
+            cages.push(Cage {
+                cells: [CellId((r * 9 + c) as u16)].into_iter().collect(),
+                op: Op::Eq,
+                target: i64::from(digit(r, c)),
+            });
+        }
+        let c0 = r / 3 * 3;
+        let sum = (c0..c0 + 3).map(|c| i64::from(digit(r, c))).sum();
+        cages.push(Cage {
+            cells: (c0..c0 + 3).map(|c| CellId((r * 9 + c) as u16)).collect(),
+            op: Op::Add,
+            target: targets.map_or(sum, |t| t[r]),
+        });
+    }
+    Puzzle { n: 9, cages }
+}
+
+#[test]
+fn split_counts_match_unsplit_over_golden_corpus() {
+    let rules = Ruleset::keen_baseline();
+    for def in load_corpus("golden.jsonl") {
+        let puzzle = parse_keen_desc(def.n, &def.desc).unwrap();
+        if puzzle.validate(rules).is_err() {
+            continue;
+        }
+        for tier in TIERS {
+            for limit in [2, def.solutions.saturating_add(1)] {
+                let count = |split| {
+                    count_solutions_up_to_with_options(
+                        &puzzle,
+                        rules,
+                        tier,
+                        limit,
+                        with_split(split),
+                    )
+                    .unwrap()
+                    .0
+                };
+                let unsplit = count(ComponentSplit::Never);
+                let at = format!("{} at {tier:?}, limit {limit}", def.label);
+                assert_eq!(count(ComponentSplit::Counting), unsplit, "{at}");
+                assert_eq!(count(ComponentSplit::Always), unsplit, "{at}");
+            }
+        }
+    }
+}
+
+#[test]
+fn block_diagonal_counts_multiply() {
+    let rules = Ruleset::keen_baseline();
+    let puzzle = block_diagonal(None);
+    puzzle.validate(rules).unwrap();
+    // Twelve 3x3 Latin squares per block.
+    let (split, split_stats) = count_solutions_up_to_with_options(
+        &puzzle,
+        rules,
+        DeductionTier::Normal,
+        2000,
+        SearchOptions::default(),
+    )
+    .unwrap();
+    let (whole, whole_stats) = count_solutions_up_to_with_options(
+        &puzzle,
+        rules,
+        DeductionTier::Normal,
+        2000,
+        with_split(ComponentSplit::Never),
+    )
+    .unwrap();
+    assert_eq!((split, whole), (1728, 1728));
+    assert!(
+        split_stats.nodes_visited * 10 < whole_stats.nodes_visited,
+        "{split_stats:?} vs {whole_stats:?}"
+    );
+
+    // The product is capped at the limit, like any count.
+    let (capped, _) = count_solutions_up_to_with_options(
+        &puzzle,
+        rules,
+        DeductionTier::Normal,
+        100,
+        SearchOptions::default(),
+    )
+    .unwrap();
+    assert_eq!(capped, 100);
+}
+
+#[test]
+fn one_infeasible_block_zeroes_the_count() {
+    let rules = Ruleset::keen_baseline();
+    // Block 1's digits are 7..=9, so every row of it sums to 24; 23 and 25 keep the cages in
+    // bounds but leave the block without a solution.
+    let puzzle = block_diagonal(Some([6, 6, 6, 23, 25, 24, 15, 15, 15]));
+    for split in [ComponentSplit::Never, ComponentSplit::Always] {
+        let (count, _) = count_solutions_up_to_with_options(
+            &puzzle,
+            rules,
+            DeductionTier::Easy,
+            10,
+            with_split(split),
+        )
+        .unwrap();
+        assert_eq!(count, 0, "{split:?}");
+    }
+}
+
+#[test]
+fn always_splices_a_valid_first_solution() {
+    let rules = Ruleset::keen_baseline();
+    let puzzle = block_diagonal(None);
+    for tier in TIERS {
+        let (solution, _) =
+            solve_one_with_options(&puzzle, rules, tier, with_split(ComponentSplit::Always))
+                .unwrap();
+        let solution = solution.expect("block-diagonal puzzle is solvable");
+        puzzle.check_solution(&solution.grid).unwrap();
+    }
+}
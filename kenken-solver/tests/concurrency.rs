@@ -0,0 +1,80 @@
+//! Concurrent solves on shared puzzles.
+//!
+//! Many threads solving and counting the same golden puzzles must get exactly the answers a
+//! single thread gets. Any global mutable state leaking into the solver (a cache, a memo
+//! table, a reused scratch buffer) would show up here as a mismatch or a panic.
+
+use std::thread;
+
+use kenken_core::Puzzle;
+use kenken_core::format::sgt_desc::parse_keen_desc;
+use kenken_core::rules::Ruleset;
+use kenken_solver::{
+    DeductionTier, Solution, count_solutions_up_to_with_deductions, solve_one_with_deductions,
+};
+
+mod common;
+use common::load_corpus;
+
+const THREADS: usize = 16;
+const ITERATIONS: usize = 1_000;
+
+const TIERS: [DeductionTier; 4] = [
+    DeductionTier::None,
+    DeductionTier::Easy,
+    DeductionTier::Normal,
+    DeductionTier::Hard,
+];
+
+#[derive(Debug, PartialEq, Eq)]
+enum Answer {
+    Solved(Option<Solution>),
+    Counted(u32),
+}
+
+fn golden_puzzles(rules: Ruleset) -> Vec<Puzzle> {
+    load_corpus("golden.jsonl")
+        .into_iter()
+        .map(|def| parse_keen_desc(def.n, &def.desc).unwrap())
+        .filter(|p| p.validate(rules).is_ok())
+        .collect()
+}
+
+/// Job `i` of the mix: a solve or a count of one puzzle at one tier.
+fn run_job(puzzles: &[Puzzle], rules: Ruleset, i: usize) -> Answer {
+    let puzzle = &puzzles[i % puzzles.len()];
+    let tier = TIERS[(i / puzzles.len()) % TIERS.len()];
+    if (i / (puzzles.len() * TIERS.len())).is_multiple_of(2) {
+        Answer::Solved(solve_one_with_deductions(puzzle, rules, tier).unwrap())
+    } else {
+        Answer::Counted(count_solutions_up_to_with_deductions(puzzle, rules, tier, 2).unwrap())
+    }
+}
+
+#[test]
+fn concurrent_solves_match_single_threaded_answers() {
+    let rules = Ruleset::keen_baseline();
+    let puzzles = golden_puzzles(rules);
+    assert!(!puzzles.is_empty());
+
+    // One answer per distinct job; the mix repeats with this period.
+    let period = puzzles.len() * TIERS.len() * 2;
+    let expected: Vec<Answer> = (0..period).map(|i| run_job(&puzzles, rules, i)).collect();
+
+    thread::scope(|scope| {
+        for t in 0..THREADS {
+            let (puzzles, expected) = (&puzzles, &expected);
+            scope.spawn(move || {
+                // Offset each thread so different jobs run side by side.
+                for k in 0..ITERATIONS {
+                    let i = (t * 37 + k) % period;
+                    assert_eq!(
+                        run_job(puzzles, rules, i),
+                        expected[i],
+                        "thread {t}, iteration {k}"
+                    );
+                }
+            });
+        }
+    });
+}
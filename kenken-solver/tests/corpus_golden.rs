@@ -6,6 +6,9 @@
 //! - Known difficulty tier
 //! - Optional known solution for verification
 //!
+//! Entries are loaded from `tests/corpus/golden.jsonl`; see `tests/common/mod.rs` for the
+//! file format.
+//!
 //! # Organization
 //!
 //! Puzzles are grouped by grid size and difficulty:
@@ -15,560 +18,23 @@
 //! - **5x5**: Normal/Hard puzzles
 //! - **6x6**: Hard/Extreme puzzles
 
-use kenken_core::format::sgt_desc::parse_keen_desc;
-use kenken_core::rules::Ruleset;
+use kenken_core::format::sgt_desc::{
+    encode_keen_desc, encode_keen_desc_ext, parse_keen_desc, parse_keen_desc_ext,
+};
+use kenken_core::puzzle::coord;
+use kenken_core::rules::{Op, Ruleset};
+use kenken_core::{ClueStyle, PuzzleEditor};
 use kenken_solver::{
-    DeductionTier, DifficultyTier, classify_difficulty_from_tier, classify_tier_required,
-    count_solutions_up_to_with_deductions, solve_one_with_deductions,
+    DeductionTier, SearchOptions, SmallNTables, Solution, classify_difficulty_from_tier,
+    classify_tier_required, count_solutions_up_to_with_deductions,
+    count_solutions_up_to_with_options, solve_one, solve_one_with_deductions,
 };
 
-/// A golden puzzle entry with full metadata.
-#[derive(Debug, Clone)]
-struct GoldenPuzzle {
-    /// Grid size (2-9).
-    n: u8,
-    /// SGT-desc format string.
-    desc: &'static str,
-    /// Expected solution count (1 = unique).
-    solutions: u32,
-    /// Expected difficulty tier (None = unknown/any).
-    difficulty: Option<DifficultyTier>,
-    /// Expected minimum deduction tier (None = requires guessing).
-    tier_required: Option<DeductionTier>,
-    /// Known solution grid (row-major, None = not verified).
-    solution: Option<&'static [u8]>,
-    /// Human-readable description.
-    label: &'static str,
-}
+mod common;
+use common::{GoldenPuzzle, load_corpus};
 
 fn golden_corpus() -> Vec<GoldenPuzzle> {
-    vec![
-        // ============================================================
-        // 2x2 PUZZLES (Trivial - All Easy)
-        // ============================================================
-        GoldenPuzzle {
-            n: 2,
-            desc: "_5,a1a2a2a1",
-            solutions: 1,
-            difficulty: Some(DifficultyTier::Easy),
-            tier_required: Some(DeductionTier::Easy),
-            solution: Some(&[1, 2, 2, 1]),
-            label: "2x2 singleton grid [1,2;2,1]",
-        },
-        GoldenPuzzle {
-            n: 2,
-            desc: "_5,a2a1a1a2",
-            solutions: 1,
-            difficulty: Some(DifficultyTier::Easy),
-            tier_required: Some(DeductionTier::Easy),
-            solution: Some(&[2, 1, 1, 2]),
-            label: "2x2 singleton grid [2,1;1,2]",
-        },
-        GoldenPuzzle {
-            n: 2,
-            desc: "b__,a3a3",
-            solutions: 2,
-            difficulty: None,
-            tier_required: None,
-            solution: None,
-            label: "2x2 horizontal add-3 pairs (2 solutions)",
-        },
-        GoldenPuzzle {
-            n: 2,
-            desc: "__b,a3a3",
-            solutions: 2,
-            difficulty: None,
-            tier_required: None,
-            solution: None,
-            label: "2x2 vertical add-3 pairs (2 solutions)",
-        },
-        // ============================================================
-        // 3x3 PUZZLES (Easy/Normal)
-        // ============================================================
-        GoldenPuzzle {
-            n: 3,
-            desc: "_13,a1a2a3a2a3a1a3a1a2",
-            solutions: 1,
-            difficulty: Some(DifficultyTier::Easy),
-            tier_required: Some(DeductionTier::Easy),
-            solution: Some(&[1, 2, 3, 2, 3, 1, 3, 1, 2]),
-            label: "3x3 singleton grid A",
-        },
-        GoldenPuzzle {
-            n: 3,
-            desc: "_13,a1a3a2a3a2a1a2a1a3",
-            solutions: 1,
-            difficulty: Some(DifficultyTier::Easy),
-            tier_required: Some(DeductionTier::Easy),
-            solution: Some(&[1, 3, 2, 3, 2, 1, 2, 1, 3]),
-            label: "3x3 singleton grid B",
-        },
-        GoldenPuzzle {
-            n: 3,
-            desc: "_13,a2a1a3a1a3a2a3a2a1",
-            solutions: 1,
-            difficulty: Some(DifficultyTier::Easy),
-            tier_required: Some(DeductionTier::Easy),
-            solution: Some(&[2, 1, 3, 1, 3, 2, 3, 2, 1]),
-            label: "3x3 singleton grid C",
-        },
-        GoldenPuzzle {
-            n: 3,
-            desc: "_13,a2a3a1a3a1a2a1a2a3",
-            solutions: 1,
-            difficulty: Some(DifficultyTier::Easy),
-            tier_required: Some(DeductionTier::Easy),
-            solution: Some(&[2, 3, 1, 3, 1, 2, 1, 2, 3]),
-            label: "3x3 singleton grid D",
-        },
-        GoldenPuzzle {
-            n: 3,
-            desc: "_13,a3a1a2a1a2a3a2a3a1",
-            solutions: 1,
-            difficulty: Some(DifficultyTier::Easy),
-            tier_required: Some(DeductionTier::Easy),
-            solution: Some(&[3, 1, 2, 1, 2, 3, 2, 3, 1]),
-            label: "3x3 singleton grid E",
-        },
-        GoldenPuzzle {
-            n: 3,
-            desc: "_13,a3a2a1a2a1a3a1a3a2",
-            solutions: 1,
-            difficulty: Some(DifficultyTier::Easy),
-            tier_required: Some(DeductionTier::Easy),
-            solution: Some(&[3, 2, 1, 2, 1, 3, 1, 3, 2]),
-            label: "3x3 singleton grid F",
-        },
-        GoldenPuzzle {
-            n: 3,
-            desc: "f_6,a6a6a6",
-            solutions: 12,
-            difficulty: None,
-            tier_required: None,
-            solution: None,
-            label: "3x3 row cages (12 Latin squares)",
-        },
-        GoldenPuzzle {
-            n: 3,
-            desc: "_6f,a6a6a6",
-            solutions: 12,
-            difficulty: None,
-            tier_required: None,
-            solution: None,
-            label: "3x3 column cages (12 Latin squares)",
-        },
-        // ============================================================
-        // 4x4 PUZZLES (Easy/Normal/Hard)
-        // ============================================================
-        GoldenPuzzle {
-            n: 4,
-            desc: "_25,a1a2a3a4a2a1a4a3a3a4a1a2a4a3a2a1",
-            solutions: 1,
-            difficulty: Some(DifficultyTier::Easy),
-            tier_required: Some(DeductionTier::Easy),
-            solution: Some(&[1, 2, 3, 4, 2, 1, 4, 3, 3, 4, 1, 2, 4, 3, 2, 1]),
-            label: "4x4 singleton grid A",
-        },
-        GoldenPuzzle {
-            n: 4,
-            desc: "_25,a1a2a3a4a2a3a4a1a3a4a1a2a4a1a2a3",
-            solutions: 1,
-            difficulty: Some(DifficultyTier::Easy),
-            tier_required: Some(DeductionTier::Easy),
-            solution: Some(&[1, 2, 3, 4, 2, 3, 4, 1, 3, 4, 1, 2, 4, 1, 2, 3]),
-            label: "4x4 singleton grid B (cyclic)",
-        },
-        GoldenPuzzle {
-            n: 4,
-            desc: "_25,a1a3a2a4a3a1a4a2a2a4a1a3a4a2a3a1",
-            solutions: 1,
-            difficulty: Some(DifficultyTier::Easy),
-            tier_required: Some(DeductionTier::Easy),
-            solution: Some(&[1, 3, 2, 4, 3, 1, 4, 2, 2, 4, 1, 3, 4, 2, 3, 1]),
-            label: "4x4 singleton grid C",
-        },
-        GoldenPuzzle {
-            n: 4,
-            desc: "_25,a1a4a2a3a4a1a3a2a2a3a1a4a3a2a4a1",
-            solutions: 1,
-            difficulty: Some(DifficultyTier::Easy),
-            tier_required: Some(DeductionTier::Easy),
-            solution: Some(&[1, 4, 2, 3, 4, 1, 3, 2, 2, 3, 1, 4, 3, 2, 4, 1]),
-            label: "4x4 singleton grid D",
-        },
-        GoldenPuzzle {
-            n: 4,
-            desc: "_25,a2a1a4a3a1a2a3a4a4a3a2a1a3a4a1a2",
-            solutions: 1,
-            difficulty: Some(DifficultyTier::Easy),
-            tier_required: Some(DeductionTier::Easy),
-            solution: Some(&[2, 1, 4, 3, 1, 2, 3, 4, 4, 3, 2, 1, 3, 4, 1, 2]),
-            label: "4x4 singleton grid E",
-        },
-        GoldenPuzzle {
-            n: 4,
-            desc: "_25,a2a3a4a1a3a4a1a2a4a1a2a3a1a2a3a4",
-            solutions: 1,
-            difficulty: Some(DifficultyTier::Easy),
-            tier_required: Some(DeductionTier::Easy),
-            solution: Some(&[2, 3, 4, 1, 3, 4, 1, 2, 4, 1, 2, 3, 1, 2, 3, 4]),
-            label: "4x4 singleton grid F (cyclic)",
-        },
-        GoldenPuzzle {
-            n: 4,
-            desc: "_25,a3a1a4a2a1a3a2a4a4a2a1a3a2a4a3a1",
-            solutions: 1,
-            difficulty: Some(DifficultyTier::Easy),
-            tier_required: Some(DeductionTier::Easy),
-            solution: Some(&[3, 1, 4, 2, 1, 3, 2, 4, 4, 2, 1, 3, 2, 4, 3, 1]),
-            label: "4x4 singleton grid G",
-        },
-        GoldenPuzzle {
-            n: 4,
-            desc: "_25,a3a4a1a2a4a3a2a1a1a2a3a4a2a1a4a3",
-            solutions: 1,
-            difficulty: Some(DifficultyTier::Easy),
-            tier_required: Some(DeductionTier::Easy),
-            solution: Some(&[3, 4, 1, 2, 4, 3, 2, 1, 1, 2, 3, 4, 2, 1, 4, 3]),
-            label: "4x4 singleton grid H",
-        },
-        GoldenPuzzle {
-            n: 4,
-            desc: "_25,a4a1a2a3a1a4a3a2a2a3a4a1a3a2a1a4",
-            solutions: 1,
-            difficulty: Some(DifficultyTier::Easy),
-            tier_required: Some(DeductionTier::Easy),
-            solution: Some(&[4, 1, 2, 3, 1, 4, 3, 2, 2, 3, 4, 1, 3, 2, 1, 4]),
-            label: "4x4 singleton grid I",
-        },
-        GoldenPuzzle {
-            n: 4,
-            desc: "_25,a4a2a3a1a2a4a1a3a3a1a4a2a1a3a2a4",
-            solutions: 1,
-            difficulty: Some(DifficultyTier::Easy),
-            tier_required: Some(DeductionTier::Easy),
-            solution: Some(&[4, 2, 3, 1, 2, 4, 1, 3, 3, 1, 4, 2, 1, 3, 2, 4]),
-            label: "4x4 singleton grid J",
-        },
-        GoldenPuzzle {
-            n: 4,
-            desc: "_25,a4a3a2a1a3a2a1a4a2a1a4a3a1a4a3a2",
-            solutions: 1,
-            difficulty: Some(DifficultyTier::Easy),
-            tier_required: Some(DeductionTier::Easy),
-            solution: Some(&[4, 3, 2, 1, 3, 2, 1, 4, 2, 1, 4, 3, 1, 4, 3, 2]),
-            label: "4x4 singleton grid K (reverse cyclic)",
-        },
-        // ============================================================
-        // 5x5 PUZZLES (Easy/Normal/Hard)
-        // ============================================================
-        GoldenPuzzle {
-            n: 5,
-            desc: "_41,a1a2a3a4a5a2a3a4a5a1a3a4a5a1a2a4a5a1a2a3a5a1a2a3a4",
-            solutions: 1,
-            difficulty: Some(DifficultyTier::Easy),
-            tier_required: Some(DeductionTier::Easy),
-            solution: Some(&[
-                1, 2, 3, 4, 5, 2, 3, 4, 5, 1, 3, 4, 5, 1, 2, 4, 5, 1, 2, 3, 5, 1, 2, 3, 4,
-            ]),
-            label: "5x5 cyclic singleton grid",
-        },
-        GoldenPuzzle {
-            n: 5,
-            desc: "_41,a1a2a3a4a5a3a4a5a1a2a5a1a2a3a4a2a3a4a5a1a4a5a1a2a3",
-            solutions: 1,
-            difficulty: Some(DifficultyTier::Easy),
-            tier_required: Some(DeductionTier::Easy),
-            solution: Some(&[
-                1, 2, 3, 4, 5, 3, 4, 5, 1, 2, 5, 1, 2, 3, 4, 2, 3, 4, 5, 1, 4, 5, 1, 2, 3,
-            ]),
-            label: "5x5 double-step cyclic singleton",
-        },
-        GoldenPuzzle {
-            n: 5,
-            desc: "_41,a5a4a3a2a1a4a3a2a1a5a3a2a1a5a4a2a1a5a4a3a1a5a4a3a2",
-            solutions: 1,
-            difficulty: Some(DifficultyTier::Easy),
-            tier_required: Some(DeductionTier::Easy),
-            solution: Some(&[
-                5, 4, 3, 2, 1, 4, 3, 2, 1, 5, 3, 2, 1, 5, 4, 2, 1, 5, 4, 3, 1, 5, 4, 3, 2,
-            ]),
-            label: "5x5 reverse cyclic singleton",
-        },
-        GoldenPuzzle {
-            n: 5,
-            desc: "_41,a1a3a5a2a4a3a5a2a4a1a5a2a4a1a3a2a4a1a3a5a4a1a3a5a2",
-            solutions: 1,
-            difficulty: Some(DifficultyTier::Easy),
-            tier_required: Some(DeductionTier::Easy),
-            solution: Some(&[
-                1, 3, 5, 2, 4, 3, 5, 2, 4, 1, 5, 2, 4, 1, 3, 2, 4, 1, 3, 5, 4, 1, 3, 5, 2,
-            ]),
-            label: "5x5 +2 step cyclic singleton",
-        },
-        GoldenPuzzle {
-            n: 5,
-            desc: "_41,a1a2a3a4a5a5a1a2a3a4a4a5a1a2a3a3a4a5a1a2a2a3a4a5a1",
-            solutions: 1,
-            difficulty: Some(DifficultyTier::Easy),
-            tier_required: Some(DeductionTier::Easy),
-            solution: Some(&[
-                1, 2, 3, 4, 5, 5, 1, 2, 3, 4, 4, 5, 1, 2, 3, 3, 4, 5, 1, 2, 2, 3, 4, 5, 1,
-            ]),
-            label: "5x5 row-shift singleton",
-        },
-        // Note: 6x6 singleton puzzles require complex block encoding
-        // Omitted for now - the sgt-desc format is non-trivial for large grids
-        // ============================================================
-        // Additional 4x4 variety puzzles
-        // ============================================================
-        GoldenPuzzle {
-            n: 4,
-            desc: "_25,a1a3a4a2a3a1a2a4a4a2a1a3a2a4a3a1",
-            solutions: 1,
-            difficulty: Some(DifficultyTier::Easy),
-            tier_required: Some(DeductionTier::Easy),
-            solution: Some(&[1, 3, 4, 2, 3, 1, 2, 4, 4, 2, 1, 3, 2, 4, 3, 1]),
-            label: "4x4 singleton grid P",
-        },
-        GoldenPuzzle {
-            n: 4,
-            desc: "_25,a4a2a1a3a2a4a3a1a1a3a4a2a3a1a2a4",
-            solutions: 1,
-            difficulty: Some(DifficultyTier::Easy),
-            tier_required: Some(DeductionTier::Easy),
-            solution: Some(&[4, 2, 1, 3, 2, 4, 3, 1, 1, 3, 4, 2, 3, 1, 2, 4]),
-            label: "4x4 singleton grid Q",
-        },
-        GoldenPuzzle {
-            n: 4,
-            desc: "_25,a3a4a2a1a4a3a1a2a1a2a4a3a2a1a3a4",
-            solutions: 1,
-            difficulty: Some(DifficultyTier::Easy),
-            tier_required: Some(DeductionTier::Easy),
-            solution: Some(&[3, 4, 2, 1, 4, 3, 1, 2, 1, 2, 4, 3, 2, 1, 3, 4]),
-            label: "4x4 singleton grid R",
-        },
-        GoldenPuzzle {
-            n: 4,
-            desc: "_25,a2a3a1a4a3a2a4a1a4a1a3a2a1a4a2a3",
-            solutions: 1,
-            difficulty: Some(DifficultyTier::Easy),
-            tier_required: Some(DeductionTier::Easy),
-            solution: Some(&[2, 3, 1, 4, 3, 2, 4, 1, 4, 1, 3, 2, 1, 4, 2, 3]),
-            label: "4x4 singleton grid S",
-        },
-        GoldenPuzzle {
-            n: 4,
-            desc: "_25,a1a4a2a3a4a2a3a1a3a1a4a2a2a3a1a4",
-            solutions: 1,
-            difficulty: Some(DifficultyTier::Easy),
-            tier_required: Some(DeductionTier::Easy),
-            solution: Some(&[1, 4, 2, 3, 4, 2, 3, 1, 3, 1, 4, 2, 2, 3, 1, 4]),
-            label: "4x4 singleton grid T",
-        },
-        // ============================================================
-        // Additional 5x5 variety puzzles
-        // ============================================================
-        GoldenPuzzle {
-            n: 5,
-            desc: "_41,a1a5a4a3a2a5a4a3a2a1a4a3a2a1a5a3a2a1a5a4a2a1a5a4a3",
-            solutions: 1,
-            difficulty: Some(DifficultyTier::Easy),
-            tier_required: Some(DeductionTier::Easy),
-            solution: Some(&[
-                1, 5, 4, 3, 2, 5, 4, 3, 2, 1, 4, 3, 2, 1, 5, 3, 2, 1, 5, 4, 2, 1, 5, 4, 3,
-            ]),
-            label: "5x5 anti-diagonal singleton",
-        },
-        GoldenPuzzle {
-            n: 5,
-            desc: "_41,a2a1a5a4a3a1a5a4a3a2a5a4a3a2a1a4a3a2a1a5a3a2a1a5a4",
-            solutions: 1,
-            difficulty: Some(DifficultyTier::Easy),
-            tier_required: Some(DeductionTier::Easy),
-            solution: Some(&[
-                2, 1, 5, 4, 3, 1, 5, 4, 3, 2, 5, 4, 3, 2, 1, 4, 3, 2, 1, 5, 3, 2, 1, 5, 4,
-            ]),
-            label: "5x5 shifted anti-diagonal",
-        },
-        GoldenPuzzle {
-            n: 5,
-            desc: "_41,a3a1a4a2a5a1a4a2a5a3a4a2a5a3a1a2a5a3a1a4a5a3a1a4a2",
-            solutions: 1,
-            difficulty: Some(DifficultyTier::Easy),
-            tier_required: Some(DeductionTier::Easy),
-            solution: Some(&[
-                3, 1, 4, 2, 5, 1, 4, 2, 5, 3, 4, 2, 5, 3, 1, 2, 5, 3, 1, 4, 5, 3, 1, 4, 2,
-            ]),
-            label: "5x5 permuted singleton A",
-        },
-        GoldenPuzzle {
-            n: 5,
-            desc: "_41,a4a2a5a3a1a2a5a3a1a4a5a3a1a4a2a3a1a4a2a5a1a4a2a5a3",
-            solutions: 1,
-            difficulty: Some(DifficultyTier::Easy),
-            tier_required: Some(DeductionTier::Easy),
-            solution: Some(&[
-                4, 2, 5, 3, 1, 2, 5, 3, 1, 4, 5, 3, 1, 4, 2, 3, 1, 4, 2, 5, 1, 4, 2, 5, 3,
-            ]),
-            label: "5x5 permuted singleton B",
-        },
-        GoldenPuzzle {
-            n: 5,
-            desc: "_41,a5a3a1a4a2a3a1a4a2a5a1a4a2a5a3a4a2a5a3a1a2a5a3a1a4",
-            solutions: 1,
-            difficulty: Some(DifficultyTier::Easy),
-            tier_required: Some(DeductionTier::Easy),
-            solution: Some(&[
-                5, 3, 1, 4, 2, 3, 1, 4, 2, 5, 1, 4, 2, 5, 3, 4, 2, 5, 3, 1, 2, 5, 3, 1, 4,
-            ]),
-            label: "5x5 permuted singleton C",
-        },
-        // ============================================================
-        // Additional variety puzzles
-        // ============================================================
-        GoldenPuzzle {
-            n: 3,
-            desc: "_13,a1a2a3a3a1a2a2a3a1",
-            solutions: 1,
-            difficulty: Some(DifficultyTier::Easy),
-            tier_required: Some(DeductionTier::Easy),
-            solution: Some(&[1, 2, 3, 3, 1, 2, 2, 3, 1]),
-            label: "3x3 singleton grid G",
-        },
-        GoldenPuzzle {
-            n: 4,
-            desc: "_25,a1a2a4a3a3a4a2a1a4a3a1a2a2a1a3a4",
-            solutions: 1,
-            difficulty: Some(DifficultyTier::Easy),
-            tier_required: Some(DeductionTier::Easy),
-            solution: Some(&[1, 2, 4, 3, 3, 4, 2, 1, 4, 3, 1, 2, 2, 1, 3, 4]),
-            label: "4x4 singleton grid L",
-        },
-        GoldenPuzzle {
-            n: 4,
-            desc: "_25,a1a4a3a2a4a1a2a3a3a2a1a4a2a3a4a1",
-            solutions: 1,
-            difficulty: Some(DifficultyTier::Easy),
-            tier_required: Some(DeductionTier::Easy),
-            solution: Some(&[1, 4, 3, 2, 4, 1, 2, 3, 3, 2, 1, 4, 2, 3, 4, 1]),
-            label: "4x4 singleton grid M",
-        },
-        GoldenPuzzle {
-            n: 4,
-            desc: "_25,a2a4a1a3a4a2a3a1a1a3a2a4a3a1a4a2",
-            solutions: 1,
-            difficulty: Some(DifficultyTier::Easy),
-            tier_required: Some(DeductionTier::Easy),
-            solution: Some(&[2, 4, 1, 3, 4, 2, 3, 1, 1, 3, 2, 4, 3, 1, 4, 2]),
-            label: "4x4 singleton grid N",
-        },
-        GoldenPuzzle {
-            n: 4,
-            desc: "_25,a3a2a1a4a2a3a4a1a1a4a3a2a4a1a2a3",
-            solutions: 1,
-            difficulty: Some(DifficultyTier::Easy),
-            tier_required: Some(DeductionTier::Easy),
-            solution: Some(&[3, 2, 1, 4, 2, 3, 4, 1, 1, 4, 3, 2, 4, 1, 2, 3]),
-            label: "4x4 singleton grid O",
-        },
-        GoldenPuzzle {
-            n: 5,
-            desc: "_41,a1a4a2a5a3a4a2a5a3a1a2a5a3a1a4a5a3a1a4a2a3a1a4a2a5",
-            solutions: 1,
-            difficulty: Some(DifficultyTier::Easy),
-            tier_required: Some(DeductionTier::Easy),
-            solution: Some(&[
-                1, 4, 2, 5, 3, 4, 2, 5, 3, 1, 2, 5, 3, 1, 4, 5, 3, 1, 4, 2, 3, 1, 4, 2, 5,
-            ]),
-            label: "5x5 offset cyclic singleton",
-        },
-        GoldenPuzzle {
-            n: 5,
-            desc: "_41,a2a4a1a3a5a4a1a3a5a2a1a3a5a2a4a3a5a2a4a1a5a2a4a1a3",
-            solutions: 1,
-            difficulty: Some(DifficultyTier::Easy),
-            tier_required: Some(DeductionTier::Easy),
-            solution: Some(&[
-                2, 4, 1, 3, 5, 4, 1, 3, 5, 2, 1, 3, 5, 2, 4, 3, 5, 2, 4, 1, 5, 2, 4, 1, 3,
-            ]),
-            label: "5x5 offset-2 cyclic singleton",
-        },
-        GoldenPuzzle {
-            n: 5,
-            desc: "_41,a3a5a2a4a1a5a2a4a1a3a2a4a1a3a5a4a1a3a5a2a1a3a5a2a4",
-            solutions: 1,
-            difficulty: Some(DifficultyTier::Easy),
-            tier_required: Some(DeductionTier::Easy),
-            solution: Some(&[
-                3, 5, 2, 4, 1, 5, 2, 4, 1, 3, 2, 4, 1, 3, 5, 4, 1, 3, 5, 2, 1, 3, 5, 2, 4,
-            ]),
-            label: "5x5 offset-3 cyclic singleton",
-        },
-        // Additional 3x3 variations
-        GoldenPuzzle {
-            n: 3,
-            desc: "_13,a2a1a3a3a2a1a1a3a2",
-            solutions: 1,
-            difficulty: Some(DifficultyTier::Easy),
-            tier_required: Some(DeductionTier::Easy),
-            solution: Some(&[2, 1, 3, 3, 2, 1, 1, 3, 2]),
-            label: "3x3 singleton grid H",
-        },
-        GoldenPuzzle {
-            n: 3,
-            desc: "_13,a3a1a2a2a3a1a1a2a3",
-            solutions: 1,
-            difficulty: Some(DifficultyTier::Easy),
-            tier_required: Some(DeductionTier::Easy),
-            solution: Some(&[3, 1, 2, 2, 3, 1, 1, 2, 3]),
-            label: "3x3 singleton grid I",
-        },
-        GoldenPuzzle {
-            n: 3,
-            desc: "_13,a2a3a1a1a2a3a3a1a2",
-            solutions: 1,
-            difficulty: Some(DifficultyTier::Easy),
-            tier_required: Some(DeductionTier::Easy),
-            solution: Some(&[2, 3, 1, 1, 2, 3, 3, 1, 2]),
-            label: "3x3 singleton grid J",
-        },
-        GoldenPuzzle {
-            n: 3,
-            desc: "_13,a1a3a2a2a1a3a3a2a1",
-            solutions: 1,
-            difficulty: Some(DifficultyTier::Easy),
-            tier_required: Some(DeductionTier::Easy),
-            solution: Some(&[1, 3, 2, 2, 1, 3, 3, 2, 1]),
-            label: "3x3 singleton grid K",
-        },
-        // Exhaustive 3x3 rotation set
-        GoldenPuzzle {
-            n: 3,
-            desc: "_13,a3a2a1a1a3a2a2a1a3",
-            solutions: 1,
-            difficulty: Some(DifficultyTier::Easy),
-            tier_required: Some(DeductionTier::Easy),
-            solution: Some(&[3, 2, 1, 1, 3, 2, 2, 1, 3]),
-            label: "3x3 singleton grid L",
-        },
-        // ============================================================
-        // 6x6 PUZZLES (Test encoding - singleton cages)
-        // ============================================================
-        GoldenPuzzle {
-            n: 6,
-            desc: "_61,a1a2a3a4a5a6a2a3a4a5a6a1a3a4a5a6a1a2a4a5a6a1a2a3a5a6a1a2a3a4a6a1a2a3a4a5",
-            solutions: 1,
-            difficulty: Some(DifficultyTier::Easy),
-            tier_required: Some(DeductionTier::Easy),
-            solution: Some(&[
-                1, 2, 3, 4, 5, 6, 2, 3, 4, 5, 6, 1, 3, 4, 5, 6, 1, 2, 4, 5, 6, 1, 2, 3, 5, 6, 1, 2,
-                3, 4, 6, 1, 2, 3, 4, 5,
-            ]),
-            label: "6x6 cyclic singleton grid",
-        },
-    ]
+    load_corpus("golden.jsonl")
 }
 
 #[test]
@@ -576,7 +42,7 @@ fn golden_corpus_parse_and_validate() {
     let rules = Ruleset::keen_baseline();
 
     for puzzle_def in golden_corpus() {
-        let puzzle = parse_keen_desc(puzzle_def.n, puzzle_def.desc).unwrap_or_else(|e| {
+        let puzzle = parse_keen_desc(puzzle_def.n, &puzzle_def.desc).unwrap_or_else(|e| {
             panic!("Failed to parse '{}': {}", puzzle_def.label, e);
         });
 
@@ -586,12 +52,58 @@ fn golden_corpus_parse_and_validate() {
     }
 }
 
+/// Every entry pins its `Puzzle::content_hash`, which must never drift between releases.
+#[test]
+fn golden_corpus_content_hashes_are_pinned() {
+    for puzzle_def in golden_corpus() {
+        let mut puzzle = parse_keen_desc(puzzle_def.n, &puzzle_def.desc).unwrap();
+        let pinned = puzzle_def
+            .content_hash
+            .unwrap_or_else(|| panic!("'{}' has no content_hash", puzzle_def.label));
+        assert_eq!(
+            puzzle.content_hash(),
+            pinned,
+            "'{}': content hash drifted",
+            puzzle_def.label
+        );
+
+        puzzle.cages.reverse();
+        assert_eq!(puzzle.content_hash(), pinned, "'{}'", puzzle_def.label);
+    }
+}
+
+/// Both desc encoders reproduce every entry exactly: the strict one as the corpus's own desc,
+/// the extended one through its own parser.
+#[test]
+fn golden_corpus_round_trips_through_both_desc_formats() {
+    let rules = Ruleset::keen_baseline();
+
+    for puzzle_def in golden_corpus() {
+        let puzzle = parse_keen_desc(puzzle_def.n, &puzzle_def.desc).unwrap();
+        let strict = encode_keen_desc(&puzzle, rules).unwrap();
+        assert_eq!(
+            parse_keen_desc(puzzle_def.n, &strict).unwrap(),
+            puzzle,
+            "'{}': {strict}",
+            puzzle_def.label
+        );
+
+        let ext = encode_keen_desc_ext(&puzzle, rules).unwrap();
+        assert_eq!(
+            parse_keen_desc_ext(puzzle_def.n, &ext).unwrap(),
+            (puzzle, rules),
+            "'{}': {ext}",
+            puzzle_def.label
+        );
+    }
+}
+
 #[test]
 fn golden_corpus_solution_counts() {
     let rules = Ruleset::keen_baseline();
 
     for puzzle_def in golden_corpus() {
-        let puzzle = parse_keen_desc(puzzle_def.n, puzzle_def.desc).unwrap();
+        let puzzle = parse_keen_desc(puzzle_def.n, &puzzle_def.desc).unwrap();
 
         if puzzle.validate(rules).is_err() {
             continue;
@@ -619,7 +131,7 @@ fn golden_corpus_unique_puzzles_have_known_solutions() {
             continue;
         }
 
-        let puzzle = parse_keen_desc(puzzle_def.n, puzzle_def.desc).unwrap();
+        let puzzle = parse_keen_desc(puzzle_def.n, &puzzle_def.desc).unwrap();
 
         if puzzle.validate(rules).is_err() {
             continue;
@@ -628,8 +140,11 @@ fn golden_corpus_unique_puzzles_have_known_solutions() {
         let solution = solve_one_with_deductions(&puzzle, rules, DeductionTier::Hard)
             .unwrap()
             .unwrap();
+        solution
+            .validate_against(&puzzle, rules)
+            .unwrap_or_else(|e| panic!("'{}': {e}", puzzle_def.label));
 
-        let expected = puzzle_def.solution.unwrap();
+        let expected = puzzle_def.solution.as_deref().unwrap();
         assert_eq!(
             solution.grid.as_slice(),
             expected,
@@ -648,7 +163,7 @@ fn golden_corpus_difficulty_classification() {
             continue;
         }
 
-        let puzzle = parse_keen_desc(puzzle_def.n, puzzle_def.desc).unwrap();
+        let puzzle = parse_keen_desc(puzzle_def.n, &puzzle_def.desc).unwrap();
 
         if puzzle.validate(rules).is_err() {
             continue;
@@ -675,7 +190,7 @@ fn golden_corpus_tier_required() {
             continue;
         }
 
-        let puzzle = parse_keen_desc(puzzle_def.n, puzzle_def.desc).unwrap();
+        let puzzle = parse_keen_desc(puzzle_def.n, &puzzle_def.desc).unwrap();
 
         if puzzle.validate(rules).is_err() {
             continue;
@@ -692,6 +207,125 @@ fn golden_corpus_tier_required() {
     }
 }
 
+#[test]
+fn singleton_heavy_puzzles_settle_in_one_productive_round() {
+    // Turn the top-left pair of each singleton grid into a `+` domino. Every other cell is
+    // pinned, so the pins in its row and column already leave each domino cell a single
+    // candidate: the first round places everything and the second confirms the fixpoint.
+    let rules = Ruleset::keen_baseline();
+    let mut checked = 0;
+    for def in golden_corpus() {
+        let Some(solution) = &def.solution else {
+            continue;
+        };
+        let puzzle = parse_keen_desc(def.n, &def.desc).unwrap();
+        if puzzle.cages.iter().any(|c| c.cells.len() != 1) {
+            continue;
+        }
+        let mut editor = PuzzleEditor::new(puzzle, rules).unwrap();
+        let target = (solution[0] + solution[1]) as i64;
+        editor.merge_cages(0, 1, Op::Add, target).unwrap();
+        let puzzle = editor.into_puzzle();
+
+        for tier in [
+            DeductionTier::Easy,
+            DeductionTier::Normal,
+            DeductionTier::Hard,
+        ] {
+            // Rounds are counted below, so small grids must be searched, not looked up.
+            let options = SearchOptions {
+                small_n_tables: SmallNTables::Never,
+                ..SearchOptions::default()
+            };
+            let (count, stats) =
+                count_solutions_up_to_with_options(&puzzle, rules, tier, 2, options).unwrap();
+            assert_eq!(count, 1, "'{}' at {tier:?}", def.label);
+            assert_eq!(stats.propagation_rounds, 2, "'{}' at {tier:?}", def.label);
+        }
+        checked += 1;
+    }
+    assert!(checked > 0);
+}
+
+#[test]
+fn solved_grids_pass_checks_and_every_single_cell_change_fails() {
+    let rules = Ruleset::keen_baseline();
+
+    for puzzle_def in golden_corpus() {
+        let puzzle = parse_keen_desc(puzzle_def.n, &puzzle_def.desc).unwrap();
+        if puzzle.validate(rules).is_err() {
+            continue;
+        }
+        let Some(solution) = solve_one(&puzzle, rules).unwrap() else {
+            continue;
+        };
+        assert!(solution.is_latin(), "'{}'", puzzle_def.label);
+        solution
+            .validate_against(&puzzle, rules)
+            .unwrap_or_else(|e| panic!("'{}': {e}", puzzle_def.label));
+
+        // Every other digit in range breaks its row and column; 0 and n+1 are out of range.
+        for cell in 0..solution.grid.len() {
+            for value in 0..=puzzle.n + 1 {
+                if value == solution.grid[cell] {
+                    continue;
+                }
+                let mut grid = solution.grid.clone();
+                grid[cell] = value;
+                let mutated = Solution {
+                    n: solution.n,
+                    grid,
+                };
+                assert!(!mutated.is_latin(), "'{}' cell {cell}", puzzle_def.label);
+                assert!(
+                    mutated.validate_against(&puzzle, rules).is_err(),
+                    "'{}' cell {cell} = {value}",
+                    puzzle_def.label
+                );
+            }
+        }
+    }
+}
+
+#[test]
+fn clue_placements_anchor_on_min_cells_and_parse_back() {
+    for puzzle_def in golden_corpus() {
+        let puzzle = parse_keen_desc(puzzle_def.n, &puzzle_def.desc).unwrap();
+        for style in [ClueStyle::UNICODE, ClueStyle::ASCII] {
+            let placements = puzzle.clue_placements(&style);
+            assert_eq!(
+                placements.len(),
+                puzzle.cages.len(),
+                "'{}'",
+                puzzle_def.label
+            );
+
+            let mut previous = None;
+            for placement in &placements {
+                let cage = &puzzle.cages[placement.cage];
+                let min_cell = cage.anchor();
+                assert_eq!(
+                    placement.anchor,
+                    coord(puzzle.n, min_cell).unwrap(),
+                    "'{}' cage {}",
+                    puzzle_def.label,
+                    placement.cage
+                );
+                assert_eq!(
+                    style.parse(&placement.text),
+                    Some((cage.op, cage.target)),
+                    "'{}' clue {:?}",
+                    puzzle_def.label,
+                    placement.text
+                );
+                // Cages never share a cell, so anchors strictly increase.
+                assert!(previous < Some(min_cell), "'{}'", puzzle_def.label);
+                previous = Some(min_cell);
+            }
+        }
+    }
+}
+
 #[test]
 fn golden_corpus_covers_all_grid_sizes() {
     let corpus = golden_corpus();
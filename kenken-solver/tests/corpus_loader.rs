@@ -0,0 +1,120 @@
+//! Tests for the JSON-lines corpus loader in `tests/common`.
+
+mod common;
+use common::{CorpusError, parse_corpus};
+use kenken_solver::{DeductionTier, DifficultyTier};
+
+const VALID: &str = r#"{"label": "2x2 A", "n": 2, "desc": "_5,a1a2a2a1", "solutions": 1, "difficulty": "easy", "tier_required": "easy", "solution": [1, 2, 2, 1]}"#;
+
+fn parse_err(src: &str) -> CorpusError {
+    parse_corpus(src, "test.jsonl").expect_err("expected a corpus error")
+}
+
+#[test]
+fn parses_entries_and_skips_comments_and_blank_lines() {
+    let src = format!(
+        "# header\n\n{VALID}\n{}\n",
+        r#"{"label": "2x2 pairs", "n": 2, "desc": "b__,a3a3", "solutions": 2, "difficulty": null}"#
+    );
+    let corpus = parse_corpus(&src, "test.jsonl").unwrap();
+    assert_eq!(corpus.len(), 2);
+
+    assert_eq!(corpus[0].label, "2x2 A");
+    assert_eq!(corpus[0].difficulty, Some(DifficultyTier::Easy));
+    assert_eq!(corpus[0].tier_required, Some(DeductionTier::Easy));
+    assert_eq!(corpus[0].solution.as_deref(), Some(&[1, 2, 2, 1][..]));
+
+    assert_eq!(corpus[1].solutions, 2);
+    assert_eq!(corpus[1].difficulty, None);
+    assert_eq!(corpus[1].tier_required, None);
+    assert_eq!(corpus[1].solution, None);
+}
+
+#[test]
+fn rejects_wrong_solution_length() {
+    let line = VALID.replace("[1, 2, 2, 1]", "[1, 2, 2]");
+    let err = parse_err(&format!("# c\n{line}\n"));
+    assert_eq!(err.line, 2);
+    assert_eq!(err.text, line);
+    assert!(
+        err.message
+            .contains("solution has 3 cells, expected 4 for n = 2"),
+        "{err}"
+    );
+}
+
+#[test]
+fn rejects_out_of_range_solution_digit() {
+    let err = parse_err(&VALID.replace("[1, 2, 2, 1]", "[1, 2, 3, 1]"));
+    assert!(err.message.contains("solution cell 2 has value 3"), "{err}");
+}
+
+#[test]
+fn rejects_unknown_difficulty() {
+    let err = parse_err(&VALID.replace(r#""difficulty": "easy""#, r#""difficulty": "brutal""#));
+    assert_eq!(err.line, 1);
+    assert!(err.message.contains("unknown difficulty 'brutal'"), "{err}");
+}
+
+#[test]
+fn rejects_unknown_tier_required() {
+    let err =
+        parse_err(&VALID.replace(r#""tier_required": "easy""#, r#""tier_required": "expert""#));
+    assert!(
+        err.message.contains("unknown tier_required 'expert'"),
+        "{err}"
+    );
+}
+
+#[test]
+fn parses_and_checks_content_hashes() {
+    let line = VALID.replace('}', r#", "content_hash": "d8d7cbbd6d88c6aa"}"#);
+    let corpus = parse_corpus(&line, "test.jsonl").unwrap();
+    assert_eq!(corpus[0].content_hash, Some(0xd8d7_cbbd_6d88_c6aa));
+
+    let err = parse_err(&VALID.replace('}', r#", "content_hash": "0xD8D7"}"#));
+    assert!(
+        err.message
+            .contains("content_hash must be 16 lowercase hex digits, got '0xD8D7'"),
+        "{err}"
+    );
+}
+
+#[test]
+fn rejects_duplicate_labels() {
+    let err = parse_err(&format!("{VALID}\n{VALID}\n"));
+    assert_eq!(err.line, 2);
+    assert!(err.message.contains("duplicate label '2x2 A'"), "{err}");
+}
+
+#[test]
+fn rejects_malformed_json_and_unknown_fields() {
+    let err = parse_err("{\"label\": \"x\", \"n\": 2");
+    assert!(err.message.starts_with("invalid entry"), "{err}");
+
+    let err = parse_err(&VALID.replace(r#""n": 2"#, r#""n": 2, "size": 2"#));
+    assert!(err.message.contains("unknown field `size`"), "{err}");
+
+    let err = parse_err(&VALID.replace(r#""solutions": 1, "#, ""));
+    assert!(err.message.contains("missing field `solutions`"), "{err}");
+}
+
+#[test]
+fn rejects_solution_on_multi_solution_entry() {
+    let err = parse_err(&VALID.replace(r#""solutions": 1"#, r#""solutions": 2"#));
+    assert!(
+        err.message.contains("solution given but solutions = 2"),
+        "{err}"
+    );
+}
+
+#[test]
+fn error_display_points_at_offending_line() {
+    let err = parse_err(&format!("{VALID}\n\n{VALID}\n"));
+    let shown = err.to_string();
+    assert!(
+        shown.starts_with("test.jsonl:3: duplicate label"),
+        "{shown}"
+    );
+    assert!(shown.ends_with(VALID), "{shown}");
+}
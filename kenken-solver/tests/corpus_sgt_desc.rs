@@ -3,7 +3,9 @@
 //! Tests various puzzle sizes with known solution counts to verify
 //! solver correctness and determinism.
 
-use kenken_core::format::sgt_desc::{encode_keen_desc, parse_keen_desc};
+use kenken_core::format::sgt_desc::{
+    encode_keen_desc, encode_keen_desc_ext, parse_keen_desc, parse_keen_desc_ext,
+};
 use kenken_core::rules::Ruleset;
 use kenken_solver::{
     DeductionTier, count_solutions_up_to_with_deductions, solve_one_with_deductions,
@@ -114,6 +116,38 @@ fn sgt_desc_roundtrip_encoding() {
     }
 }
 
+#[test]
+fn corpus_round_trips_exactly_through_both_desc_formats() {
+    let rules = Ruleset::keen_baseline();
+
+    for (n, desc, _, name) in corpus() {
+        let puzzle = parse_keen_desc(n, desc).expect("parse failed");
+
+        let strict = encode_keen_desc(&puzzle, rules).expect("encode failed");
+        assert_eq!(
+            parse_keen_desc(n, &strict).expect("reparse failed"),
+            puzzle,
+            "Puzzle '{}': strict desc {}",
+            name,
+            strict
+        );
+
+        let ext = encode_keen_desc_ext(&puzzle, rules).expect("ext encode failed");
+        assert_eq!(
+            parse_keen_desc_ext(n, &ext).expect("ext reparse failed"),
+            (puzzle, rules),
+            "Puzzle '{}': ext desc {}",
+            name,
+            ext
+        );
+        // Same block structure; only the givens' clue letters differ.
+        assert_eq!(
+            ext.split_once(',').unwrap().0,
+            strict.split_once(',').unwrap().0
+        );
+    }
+}
+
 #[test]
 fn unique_puzzles_have_single_solution() {
     let rules = Ruleset::keen_baseline();
@@ -0,0 +1,144 @@
+//! `PuzzleEditor` round trips over the golden corpus.
+//!
+//! Every edit that succeeds is undone by its inverse, and the puzzle must come back
+//! byte-for-byte equal; every intermediate state must still pass `validate`. Edits that the
+//! ruleset rejects must leave the puzzle untouched.
+
+use kenken_core::format::sgt_desc::parse_keen_desc;
+use kenken_core::rules::{Op, Ruleset};
+use kenken_core::{CellId, Puzzle, PuzzleEditor};
+
+mod common;
+use common::load_corpus;
+
+fn golden_editors(rules: Ruleset) -> Vec<(String, PuzzleEditor)> {
+    load_corpus("golden.jsonl")
+        .into_iter()
+        .filter_map(|def| {
+            let puzzle = parse_keen_desc(def.n, &def.desc).unwrap();
+            PuzzleEditor::new(puzzle, rules)
+                .ok()
+                .map(|ed| (def.label, ed))
+        })
+        .collect()
+}
+
+fn assert_valid(ed: &PuzzleEditor, label: &str) {
+    ed.puzzle()
+        .validate(ed.rules())
+        .unwrap_or_else(|e| panic!("{label}: intermediate state invalid: {e}"));
+}
+
+fn all_cells(puzzle: &Puzzle) -> impl Iterator<Item = CellId> {
+    let a = puzzle.n as u16 * puzzle.n as u16;
+    (0..a).map(CellId)
+}
+
+#[test]
+fn set_clue_round_trips() {
+    let rules = Ruleset::keen_baseline();
+    for (label, mut ed) in golden_editors(rules) {
+        let original = ed.puzzle().clone();
+        for (idx, cage) in original.cages.iter().enumerate() {
+            let (op, target) = if cage.op == Op::Eq {
+                (Op::Eq, 1)
+            } else {
+                (Op::Mul, cage.target + 1)
+            };
+            ed.set_clue(idx, op, target).unwrap();
+            assert_valid(&ed, &label);
+            ed.set_clue(idx, cage.op, cage.target).unwrap();
+        }
+        assert_eq!(ed.puzzle(), &original, "{label}");
+    }
+}
+
+#[test]
+fn split_and_merge_round_trip() {
+    let rules = Ruleset::keen_baseline();
+    let mut splits = 0;
+    for (label, mut ed) in golden_editors(rules) {
+        let original = ed.puzzle().clone();
+        for (idx, cage) in original.cages.iter().enumerate() {
+            for &cell in &cage.cells {
+                let before = ed.clone();
+                let Ok(new) = ed.split_cage(idx, &[cell], Op::Eq, 1) else {
+                    assert_eq!(ed, before, "{label}: failed split changed the puzzle");
+                    continue;
+                };
+                splits += 1;
+                assert_valid(&ed, &label);
+                ed.merge_cages(idx, new, cage.op, cage.target).unwrap();
+                assert_eq!(
+                    ed.puzzle(),
+                    &original,
+                    "{label}: split {cell} from cage {idx}"
+                );
+            }
+        }
+    }
+    assert!(splits > 0);
+}
+
+#[test]
+fn move_cell_round_trips() {
+    let rules = Ruleset::keen_baseline();
+    let mut moves = 0;
+    for (label, mut ed) in golden_editors(rules) {
+        let original = ed.puzzle().clone();
+        for cell in all_cells(&original) {
+            let home = ed.cage_of(cell).unwrap();
+            for into in 0..original.cages.len() {
+                let before = ed.clone();
+                if ed.move_cell(cell, into).is_err() {
+                    assert_eq!(ed, before, "{label}: failed move changed the puzzle");
+                    continue;
+                }
+                moves += 1;
+                assert_valid(&ed, &label);
+                ed.move_cell(cell, home).unwrap();
+                assert_eq!(ed.puzzle(), &original, "{label}: move {cell} into {into}");
+            }
+        }
+    }
+    assert!(moves > 0);
+}
+
+#[test]
+fn edit_sequence_and_inverses_restore_original() {
+    let rules = Ruleset::keen_baseline();
+    for (label, mut ed) in golden_editors(rules) {
+        let original = ed.puzzle().clone();
+        let Some(idx) = original.cages.iter().position(|c| c.cells.len() >= 2) else {
+            continue;
+        };
+        let cage = original.cages[idx].clone();
+
+        // Re-clue, split off the first splittable cell and re-clue it, then unwind everything
+        // in reverse order.
+        ed.set_clue(idx, Op::Add, 99).unwrap();
+        assert_valid(&ed, &label);
+        let Some((cell, new)) = cage
+            .cells
+            .iter()
+            .find_map(|&cell| Some((cell, ed.split_cage(idx, &[cell], Op::Eq, 1).ok()?)))
+        else {
+            ed.set_clue(idx, cage.op, cage.target).unwrap();
+            assert_eq!(ed.puzzle(), &original, "{label}");
+            continue;
+        };
+        assert_valid(&ed, &label);
+        ed.set_clue(new, Op::Eq, 2).unwrap();
+        assert_valid(&ed, &label);
+
+        ed.set_clue(new, Op::Eq, 1).unwrap();
+        ed.merge_cages(idx, new, Op::Add, 99).unwrap();
+        assert_valid(&ed, &label);
+        ed.set_clue(idx, cage.op, cage.target).unwrap();
+        assert_eq!(
+            ed.puzzle(),
+            &original,
+            "{label}: split {cell} from cage {idx}"
+        );
+    }
+}
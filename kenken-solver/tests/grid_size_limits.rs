@@ -0,0 +1,140 @@
+//! Grid sizes at the edges of the solver's domain width.
+//!
+//! The search keeps candidates in `u32` masks up to n = 31 and `u64` masks above, so every
+//! entry point must either solve a grid correctly or reject it with `GridSizeTooLarge`; it must
+//! never search on truncated masks. 31 and 32 straddle the switch between the two widths.
+//! The limit depends on the build's features, so each case asks [`capabilities`] which
+//! outcome to expect.
+
+use kenken_core::rules::{Op, Ruleset};
+use kenken_core::{Cage, CellId, Puzzle};
+use kenken_solver::{
+    DeductionTier, SolveError, capabilities, classify_tier_required, count_solutions_resumable,
+    count_solutions_up_to, count_solutions_up_to_ignoring_cages,
+    count_solutions_up_to_with_deductions, propagate_to_fixpoint, solve_one,
+    solve_one_ignoring_cages, solve_one_with_deductions, solve_one_with_stats,
+};
+
+const BOUNDARY_SIZES: [u8; 4] = [31, 32, 63, 64];
+
+/// Cyclic Latin square `(row + col) % n + 1`, one given per cell.
+fn cyclic(n: u8) -> (Puzzle, Vec<u8>) {
+    let n_usize = n as usize;
+    let grid: Vec<u8> = (0..n_usize * n_usize)
+        .map(|idx| ((idx / n_usize + idx % n_usize) % n_usize + 1) as u8)
+        .collect();
+    let cages = grid
+        .iter()
+        .enumerate()
+        .map(|(idx, &v)| Cage {
+            cells: [CellId(idx as u16)].into_iter().collect(),
+            op: Op::Eq,
+            target: v as i64,
+        })
+        .collect();
+    (Puzzle { n, cages }, grid)
+}
+
+/// `Ok` when `n` is in range, otherwise the typed width error for exactly this `n`.
+fn expect_in_range<T: std::fmt::Debug>(
+    n: u8,
+    entry: &str,
+    result: Result<T, SolveError>,
+) -> Option<T> {
+    if n <= capabilities().max_grid_size {
+        Some(result.unwrap_or_else(|e| panic!("n={n} {entry}: {e}")))
+    } else {
+        match result {
+            Err(SolveError::GridSizeTooLarge { n: got, .. }) => assert_eq!(got, n, "{entry}"),
+            other => panic!("n={n} {entry}: expected GridSizeTooLarge, got {other:?}"),
+        }
+        None
+    }
+}
+
+#[test]
+fn solving_entry_points_respect_the_domain_width() {
+    let rules = Ruleset::keen_baseline();
+    for n in BOUNDARY_SIZES {
+        let (puzzle, grid) = cyclic(n);
+        let solved = [
+            expect_in_range(n, "solve_one", solve_one(&puzzle, rules)),
+            expect_in_range(
+                n,
+                "solve_one_with_stats",
+                solve_one_with_stats(&puzzle, rules).map(|(sol, _)| sol),
+            ),
+            expect_in_range(
+                n,
+                "solve_one_with_deductions",
+                solve_one_with_deductions(&puzzle, rules, DeductionTier::Normal),
+            ),
+            expect_in_range(
+                n,
+                "solve_one_ignoring_cages",
+                solve_one_ignoring_cages(&puzzle, rules, DeductionTier::Normal, &[]),
+            ),
+        ];
+        for sol in solved.into_iter().flatten() {
+            assert_eq!(sol.expect("cyclic grid is solvable").grid, grid, "n={n}");
+        }
+
+        if let Some(forced) = expect_in_range(
+            n,
+            "propagate_to_fixpoint",
+            propagate_to_fixpoint(&puzzle, rules, DeductionTier::Easy),
+        ) {
+            assert_eq!(forced, Some(grid.clone()), "n={n}");
+        }
+        if let Some(result) = expect_in_range(
+            n,
+            "classify_tier_required",
+            classify_tier_required(&puzzle, rules),
+        ) {
+            assert_eq!(result.tier_required, Some(DeductionTier::Easy), "n={n}");
+        }
+    }
+}
+
+#[test]
+fn counting_entry_points_respect_the_domain_width() {
+    let rules = Ruleset::keen_baseline();
+    for n in BOUNDARY_SIZES {
+        let (puzzle, _) = cyclic(n);
+        let counts = [
+            expect_in_range(
+                n,
+                "count_solutions_up_to",
+                count_solutions_up_to(&puzzle, rules, 2),
+            ),
+            expect_in_range(
+                n,
+                "count_solutions_up_to_with_deductions",
+                count_solutions_up_to_with_deductions(&puzzle, rules, DeductionTier::Easy, 2),
+            ),
+            expect_in_range(
+                n,
+                "count_solutions_up_to_ignoring_cages",
+                count_solutions_up_to_ignoring_cages(&puzzle, rules, DeductionTier::Easy, 2, &[]),
+            ),
+        ];
+        for count in counts.into_iter().flatten() {
+            assert_eq!(count, 1, "n={n}");
+        }
+
+        let resumable = expect_in_range(
+            n,
+            "count_solutions_resumable",
+            count_solutions_resumable(&puzzle, rules, DeductionTier::Easy, 2, None, 0),
+        );
+        if let Some(outcome) = resumable {
+            assert!(
+                matches!(
+                    outcome,
+                    kenken_solver::ResumableOutcome::Complete { count: 1, .. }
+                ),
+                "n={n}"
+            );
+        }
+    }
+}
@@ -0,0 +1,166 @@
+//! Hidden cage ops: a cage keeps its target and is met under any op it could carry.
+
+use std::collections::BTreeSet;
+
+use kenken_core::format::sgt_desc::parse_keen_desc;
+use kenken_core::rules::{Op, Ruleset};
+use kenken_core::{Cage, Puzzle, PuzzleBuilder};
+use kenken_solver::small_n::table_solutions;
+use kenken_solver::{DeductionTier, SolveError, max_hideable_ops, uniqueness_with_hidden_ops};
+
+mod common;
+use common::load_corpus;
+
+const TIERS: [DeductionTier; 4] = [
+    DeductionTier::None,
+    DeductionTier::Easy,
+    DeductionTier::Normal,
+    DeductionTier::Hard,
+];
+
+/// Unique 4x4 over `1234 / 4321 / 2143 / 3412` whose `3+` domino at (0,0)-(0,1) also reads
+/// as `3×`: with its op hidden, `3124 / 2431 / 1243 / 4312` solves it too.
+fn add_mul_ambiguity() -> Puzzle {
+    PuzzleBuilder::new(4)
+        .cage(Op::Add, 3, &[(0, 0), (0, 1)])
+        .unwrap()
+        .cage(Op::Add, 5, &[(0, 2), (1, 2)])
+        .unwrap()
+        .cage(Op::Eq, 4, &[(0, 3)])
+        .unwrap()
+        .cage(Op::Div, 2, &[(1, 0), (2, 0)])
+        .unwrap()
+        .cage(Op::Sub, 2, &[(1, 1), (2, 1)])
+        .unwrap()
+        .cage(Op::Eq, 1, &[(1, 3)])
+        .unwrap()
+        .cage(Op::Eq, 4, &[(2, 2)])
+        .unwrap()
+        .cage(Op::Eq, 3, &[(2, 3)])
+        .unwrap()
+        .cage(Op::Add, 7, &[(3, 0), (3, 1)])
+        .unwrap()
+        .cage(Op::Eq, 1, &[(3, 2)])
+        .unwrap()
+        .cage(Op::Eq, 2, &[(3, 3)])
+        .unwrap()
+        .build(Ruleset::keen_baseline())
+        .unwrap()
+}
+
+/// Solutions with the ops of `hidden` hidden, from the small-n tables: the union over every
+/// op the hidden cages could carry.
+fn table_count(puzzle: &Puzzle, rules: Ruleset, hidden: &[usize]) -> usize {
+    let mut variants = vec![puzzle.clone()];
+    for &idx in hidden {
+        let cage = &puzzle.cages[idx];
+        let ops: &[Op] = match cage.cells.len() {
+            1 => &[cage.op],
+            2 => &[Op::Add, Op::Mul, Op::Sub, Op::Div],
+            _ => &[Op::Add, Op::Mul],
+        };
+        variants = variants
+            .into_iter()
+            .flat_map(|variant| {
+                ops.iter().map(move |&op| {
+                    let mut variant = variant.clone();
+                    variant.cages[idx] = Cage { op, ..cage.clone() };
+                    variant
+                })
+            })
+            .collect();
+    }
+    let mut solutions = BTreeSet::new();
+    for variant in &variants {
+        for grid in table_solutions(variant, rules).unwrap().unwrap() {
+            solutions.insert(grid.to_vec());
+        }
+    }
+    solutions.len()
+}
+
+#[test]
+fn every_op_of_a_singleton_grid_is_hideable() {
+    let rules = Ruleset::keen_baseline();
+    let mut checked = 0;
+    for def in load_corpus("golden.jsonl") {
+        if !def.label.contains("singleton grid") {
+            continue;
+        }
+        let puzzle = parse_keen_desc(def.n, &def.desc).unwrap();
+        let all: Vec<usize> = (0..puzzle.cages.len()).collect();
+        for tier in TIERS {
+            assert_eq!(max_hideable_ops(&puzzle, rules, tier).unwrap(), all);
+            let count = uniqueness_with_hidden_ops(&puzzle, rules, &all, tier).unwrap();
+            assert_eq!(count, 1, "{} at {tier:?}", def.label);
+        }
+        checked += 1;
+    }
+    assert!(checked > 0);
+}
+
+#[test]
+fn an_add_mul_ambiguity_keeps_its_op_shown() {
+    let rules = Ruleset::keen_baseline();
+    let puzzle = add_mul_ambiguity();
+    for tier in TIERS {
+        assert_eq!(
+            uniqueness_with_hidden_ops(&puzzle, rules, &[], tier).unwrap(),
+            1
+        );
+        assert_eq!(
+            uniqueness_with_hidden_ops(&puzzle, rules, &[0], tier).unwrap(),
+            2,
+            "{tier:?}"
+        );
+        let hideable = max_hideable_ops(&puzzle, rules, tier).unwrap();
+        assert!(!hideable.contains(&0), "{tier:?}: {hideable:?}");
+        assert!(hideable.len() > 1, "{tier:?}: {hideable:?}");
+        assert_eq!(
+            uniqueness_with_hidden_ops(&puzzle, rules, &hideable, tier).unwrap(),
+            1
+        );
+        // Maximal: no cage left out can join.
+        for idx in (0..puzzle.cages.len()).filter(|idx| !hideable.contains(idx)) {
+            let mut more = hideable.clone();
+            more.push(idx);
+            let count = uniqueness_with_hidden_ops(&puzzle, rules, &more, tier).unwrap();
+            assert_eq!(count, 2, "{tier:?}: cage {idx}");
+        }
+    }
+}
+
+#[test]
+fn counts_match_the_tables_over_every_op() {
+    let rules = Ruleset::keen_baseline();
+    let puzzles = [
+        add_mul_ambiguity(),
+        parse_keen_desc(4, "a3_aaba__a__a_,a7a12m3m24a5d2").unwrap(),
+    ];
+    for puzzle in &puzzles {
+        let len = puzzle.cages.len();
+        let mut hidden_sets: Vec<Vec<usize>> = (0..len).map(|idx| vec![idx]).collect();
+        hidden_sets.extend((0..len).flat_map(|a| (a + 1..len).map(move |b| vec![a, b])));
+        hidden_sets.push((0..len).collect());
+        for hidden in &hidden_sets {
+            let expected = table_count(puzzle, rules, hidden).min(2) as u32;
+            for tier in TIERS {
+                let count = uniqueness_with_hidden_ops(puzzle, rules, hidden, tier).unwrap();
+                assert_eq!(count, expected, "{hidden:?} at {tier:?}");
+            }
+        }
+    }
+}
+
+#[test]
+fn out_of_range_indices_are_rejected() {
+    let puzzle = add_mul_ambiguity();
+    let err = uniqueness_with_hidden_ops(
+        &puzzle,
+        Ruleset::keen_baseline(),
+        &[0, 11],
+        DeductionTier::Normal,
+    )
+    .unwrap_err();
+    assert!(matches!(err, SolveError::Core(_)), "{err:?}");
+}
@@ -0,0 +1,98 @@
+//! Searching with some cages' arithmetic ignored: the ignored cages still cover their cells,
+//! which then obey only the Latin rules.
+
+use kenken_core::Puzzle;
+use kenken_core::format::sgt_desc::parse_keen_desc;
+use kenken_core::rules::Ruleset;
+use kenken_solver::{
+    DeductionTier, count_solutions_up_to_ignoring_cages, solve_one_ignoring_cages,
+};
+
+mod common;
+use common::load_corpus;
+
+const TIERS: [DeductionTier; 4] = [
+    DeductionTier::None,
+    DeductionTier::Easy,
+    DeductionTier::Normal,
+    DeductionTier::Hard,
+];
+
+fn golden(label: &str) -> (Puzzle, Vec<u8>) {
+    let def = load_corpus("golden.jsonl")
+        .into_iter()
+        .find(|def| def.label == label)
+        .unwrap();
+    assert_eq!(def.solutions, 1, "{label}");
+    (
+        parse_keen_desc(def.n, &def.desc).unwrap(),
+        def.solution.unwrap(),
+    )
+}
+
+/// Index of the cage covering cell `idx` in a grid of singleton cages.
+fn cage_at(puzzle: &Puzzle, idx: u16) -> usize {
+    puzzle
+        .cages
+        .iter()
+        .position(|cage| cage.cells[0].0 == idx)
+        .unwrap()
+}
+
+#[test]
+fn ignoring_every_cage_of_a_3x3_counts_all_latin_squares() {
+    let rules = Ruleset::keen_baseline();
+    let (puzzle, _) = golden("3x3 singleton grid A");
+    let all: Vec<usize> = (0..puzzle.cages.len()).collect();
+    for tier in TIERS {
+        let count = count_solutions_up_to_ignoring_cages(&puzzle, rules, tier, 100, &all);
+        assert_eq!(count.unwrap(), 12, "{tier:?}");
+        let solution = solve_one_ignoring_cages(&puzzle, rules, tier, &all).unwrap();
+        assert!(solution.unwrap().is_latin(), "{tier:?}");
+    }
+}
+
+#[test]
+fn ignoring_one_given_of_a_full_grid_keeps_it_unique() {
+    let rules = Ruleset::keen_baseline();
+    let (puzzle, grid) = golden("4x4 singleton grid A");
+    // The other fifteen givens leave only one digit for the free cell.
+    let ignored = [cage_at(&puzzle, 5)];
+    for tier in TIERS {
+        let count = count_solutions_up_to_ignoring_cages(&puzzle, rules, tier, 2, &ignored);
+        assert_eq!(count.unwrap(), 1, "{tier:?}");
+        let solution = solve_one_ignoring_cages(&puzzle, rules, tier, &ignored).unwrap();
+        assert_eq!(solution.unwrap().grid, grid, "{tier:?}");
+    }
+}
+
+#[test]
+fn ignoring_an_intercalate_lets_it_flip() {
+    let rules = Ruleset::keen_baseline();
+    let (puzzle, grid) = golden("4x4 singleton grid A");
+    // Rows 0-1, columns 0-1 hold 1 2 / 2 1: swapping the digits keeps every row and column
+    // Latin, so without these four givens the grid has a second solution.
+    assert_eq!([grid[0], grid[1], grid[4], grid[5]], [1, 2, 2, 1]);
+    let ignored: Vec<usize> = [0, 1, 4, 5].map(|idx| cage_at(&puzzle, idx)).to_vec();
+    for tier in TIERS {
+        let count = count_solutions_up_to_ignoring_cages(&puzzle, rules, tier, 10, &ignored);
+        assert_eq!(count.unwrap(), 2, "{tier:?}");
+        let solution = solve_one_ignoring_cages(&puzzle, rules, tier, &ignored)
+            .unwrap()
+            .unwrap();
+        assert!(solution.is_latin(), "{tier:?}");
+        assert_eq!(solution.grid[2..4], grid[2..4], "{tier:?}");
+        assert_eq!(solution.grid[6..], grid[6..], "{tier:?}");
+    }
+    // Each given alone is redundant; only the four together are essential.
+    for &cage in &ignored {
+        let count = count_solutions_up_to_ignoring_cages(
+            &puzzle,
+            rules,
+            DeductionTier::Normal,
+            10,
+            &[cage],
+        );
+        assert_eq!(count.unwrap(), 1);
+    }
+}
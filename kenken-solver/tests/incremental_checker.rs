@@ -0,0 +1,125 @@
+//! `IncrementalChecker` against from-scratch counts.
+//!
+//! Each scripted clue edit is applied through the checker and to a plain copy of the puzzle,
+//! and the checker's count must match `count_solutions_up_to_with_deductions` on the copy.
+
+use kenken_core::rules::{Op, Ruleset};
+use kenken_core::{CoreError, Puzzle, PuzzleBuilder};
+use kenken_solver::{
+    DeductionTier, IncrementalChecker, SolveError, count_solutions_up_to_with_deductions,
+};
+
+/// Cyclic Latin square `(row + col) % n + 1`, caged as horizontal sum dominoes with a trailing
+/// given per row when `n` is odd. Cage `k` of row `r` has index `r * ceil(n / 2) + k`.
+fn cyclic_dominoes(n: u8) -> Puzzle {
+    let value = |r: u8, c: u8| ((r + c) % n + 1) as i64;
+    let mut builder = PuzzleBuilder::new(n);
+    for r in 0..n {
+        for c in (0..n).step_by(2) {
+            builder = if c + 1 < n {
+                builder.cage(
+                    Op::Add,
+                    value(r, c) + value(r, c + 1),
+                    &[(r, c), (r, c + 1)],
+                )
+            } else {
+                builder.cage(Op::Eq, value(r, c), &[(r, c)])
+            }
+            .unwrap();
+        }
+    }
+    builder.build(Ruleset::keen_baseline()).unwrap()
+}
+
+/// Apply `edits` in order, checking every count against a fresh one; returns the counts.
+fn run_script(n: u8, tier: DeductionTier, edits: &[(usize, Op, i64)]) -> Vec<u32> {
+    let rules = Ruleset::keen_baseline();
+    let mut fresh = cyclic_dominoes(n);
+    let mut checker = IncrementalChecker::new(&fresh, rules, tier).unwrap();
+    let mut counts = vec![checker.count().unwrap()];
+    assert_eq!(
+        counts[0],
+        count_solutions_up_to_with_deductions(&fresh, rules, tier, 2).unwrap()
+    );
+    for (step, &(idx, op, target)) in edits.iter().enumerate() {
+        let got = checker.update_cage(idx, op, target).unwrap();
+        (fresh.cages[idx].op, fresh.cages[idx].target) = (op, target);
+        let want = count_solutions_up_to_with_deductions(&fresh, rules, tier, 2).unwrap();
+        assert_eq!(
+            got, want,
+            "n={n} {tier:?} step {step}: cage {idx} -> {op} {target}"
+        );
+        assert_eq!(checker.puzzle(), &fresh, "step {step}");
+        counts.push(got);
+    }
+    counts
+}
+
+#[test]
+fn scripted_5x5_edits_match_fresh_counts() {
+    // Row 0 is [1+2][3+4][5]; row 1 is [2+3][4+5][1].
+    let edits = [
+        (0, Op::Mul, 2),  // same pair, different clue
+        (2, Op::Eq, 4),   // row 0 loses its 5: unsat
+        (2, Op::Eq, 5),   // restored
+        (1, Op::Sub, 1),  // loosened
+        (4, Op::Add, 10), // no two distinct digits up to 5 sum to 10: unsat
+        (4, Op::Add, 9),  // restored
+        (1, Op::Add, 7),  // back to the original clue
+        (3, Op::Mul, 6),  // same pair, different clue
+    ];
+    for tier in [
+        DeductionTier::None,
+        DeductionTier::Easy,
+        DeductionTier::Normal,
+        DeductionTier::Hard,
+    ] {
+        let counts = run_script(5, tier, &edits);
+        assert!(counts.contains(&0), "{tier:?}: {counts:?}");
+        assert_eq!(
+            counts[3], counts[0],
+            "{tier:?}: restore undoes the unsat edit"
+        );
+        assert_eq!(
+            counts[6], counts[0],
+            "{tier:?}: restore undoes the unsat edit"
+        );
+    }
+}
+
+#[test]
+fn scripted_6x6_edits_match_fresh_counts() {
+    // n >= 6 below Hard, so counts after the first reuse cached tuples of untouched cages.
+    let edits = [
+        (0, Op::Sub, 1),
+        (1, Op::Add, 12),
+        (1, Op::Add, 7),
+        (0, Op::Add, 3),
+        (5, Op::Mul, 12),
+    ];
+    for tier in [DeductionTier::Easy, DeductionTier::Normal] {
+        let counts = run_script(6, tier, &edits);
+        assert!(counts.contains(&0), "{tier:?}: {counts:?}");
+        assert_eq!(counts[4], counts[0], "{tier:?}: {counts:?}");
+    }
+}
+
+#[test]
+fn rejected_edits_leave_the_puzzle_unchanged() {
+    let rules = Ruleset::keen_baseline();
+    let puzzle = cyclic_dominoes(5);
+    let mut checker = IncrementalChecker::new(&puzzle, rules, DeductionTier::Normal).unwrap();
+    let before = checker.count().unwrap();
+
+    let err = checker
+        .update_cage(puzzle.cages.len(), Op::Add, 3)
+        .unwrap_err();
+    assert!(matches!(
+        err,
+        SolveError::Core(CoreError::CageIndexOutOfRange { index: 15, len: 15 })
+    ));
+    // A two-cell cage cannot be an Eq clue.
+    assert!(checker.update_cage(0, Op::Eq, 3).is_err());
+    assert_eq!(checker.puzzle(), &puzzle);
+    assert_eq!(checker.count().unwrap(), before);
+}
@@ -0,0 +1,133 @@
+//! Cages of 7 and 8 cells, past the baseline's 6, under a ruleset that allows them: they
+//! validate, solve, count and round-trip through descs like any other cage.
+
+use kenken_core::format::sgt_desc::{
+    encode_keen_desc, encode_keen_desc_ext, parse_keen_desc_ext, parse_keen_desc_with_rules,
+};
+use kenken_core::limits::{SAT_TUPLE_THRESHOLD, SEARCH_TUPLE_BUDGET};
+use kenken_core::rules::{Op, Ruleset};
+use kenken_core::{Cage, CellId, CoreError, Puzzle};
+use kenken_solver::{
+    DeductionTier, count_solutions_up_to_with_deductions, solve_one_with_deductions,
+};
+
+const TIERS: [DeductionTier; 4] = [
+    DeductionTier::None,
+    DeductionTier::Easy,
+    DeductionTier::Normal,
+    DeductionTier::Hard,
+];
+
+const N: usize = 6;
+
+fn eight_cell_rules() -> Ruleset {
+    Ruleset {
+        max_cage_size: 8,
+        ..Ruleset::keen_baseline()
+    }
+}
+
+fn cyclic_solution() -> Vec<u8> {
+    (0..N * N)
+        .map(|idx| ((idx / N + idx % N) % N + 1) as u8)
+        .collect()
+}
+
+/// 6x6 over the cyclic square: row 0 plus (1,0) is a 7-cell `+` cage, the rest of row 1 plus
+/// (2,3)-(2,5) an 8-cell `×` cage, and every other cell is given.
+fn seven_and_eight_6x6() -> (Puzzle, Vec<u8>) {
+    let solution = cyclic_solution();
+    let cage = |cells: &[usize], op, target| Cage {
+        cells: cells.iter().map(|&i| CellId(i as u16)).collect(),
+        op,
+        target,
+    };
+    let seven: Vec<usize> = (0..=N).collect();
+    let eight: Vec<usize> = (N + 1..2 * N).chain(2 * N + 3..3 * N).collect();
+    let sum = seven.iter().map(|&i| solution[i] as i64).sum();
+    let product = eight.iter().map(|&i| solution[i] as i64).product();
+    let mut cages = vec![cage(&seven, Op::Add, sum), cage(&eight, Op::Mul, product)];
+    for (idx, &value) in solution.iter().enumerate() {
+        if !seven.contains(&idx) && !eight.contains(&idx) {
+            cages.push(cage(&[idx], Op::Eq, value as i64));
+        }
+    }
+    (Puzzle { n: N as u8, cages }, solution)
+}
+
+#[test]
+fn large_cages_validate_only_when_the_ruleset_allows_them() {
+    let (puzzle, _) = seven_and_eight_6x6();
+    assert_eq!(puzzle.cages[0].cells.len(), 7);
+    assert_eq!(puzzle.cages[1].cells.len(), 8);
+    puzzle.validate_full(eight_cell_rules()).unwrap();
+
+    let seven_max = Ruleset {
+        max_cage_size: 7,
+        ..Ruleset::keen_baseline()
+    };
+    for (rules, max) in [(Ruleset::keen_baseline(), 6), (seven_max, 7)] {
+        let err = puzzle.validate(rules).unwrap_err();
+        assert!(
+            matches!(err, CoreError::CageTooLarge { len, max: m } if len > max as usize && m == max),
+            "{err:?}"
+        );
+    }
+}
+
+#[test]
+fn large_cages_solve_and_count_at_every_tier() {
+    let rules = eight_cell_rules();
+    let (puzzle, solution) = seven_and_eight_6x6();
+    for tier in TIERS {
+        let found = solve_one_with_deductions(&puzzle, rules, tier)
+            .unwrap()
+            .unwrap();
+        assert_eq!(found.grid, solution, "{tier:?}");
+        let count = count_solutions_up_to_with_deductions(&puzzle, rules, tier, 2).unwrap();
+        assert_eq!(count, 1, "{tier:?}");
+    }
+}
+
+#[test]
+fn large_cages_round_trip_through_descs() {
+    let rules = eight_cell_rules();
+    let (puzzle, solution) = seven_and_eight_6x6();
+
+    let desc = encode_keen_desc(&puzzle, rules).unwrap();
+    let parsed = parse_keen_desc_with_rules(N as u8, &desc, rules).unwrap();
+    assert_eq!(encode_keen_desc(&parsed, rules).unwrap(), desc);
+    parsed.check_solution(&solution).unwrap();
+    assert!(parse_keen_desc_with_rules(N as u8, &desc, Ruleset::keen_baseline()).is_err());
+
+    let ext = encode_keen_desc_ext(&puzzle, rules).unwrap();
+    let (parsed, parsed_rules) = parse_keen_desc_ext(N as u8, &ext).unwrap();
+    assert_eq!(parsed_rules, rules);
+    parsed.check_solution(&solution).unwrap();
+}
+
+#[test]
+fn tractability_tracks_the_shared_budgets() {
+    let (puzzle, _) = seven_and_eight_6x6();
+    let (seven, eight) = (&puzzle.cages[0], &puzzle.cages[1]);
+    // 6! for row 0, then 5 for (1,0) under (0,0).
+    assert_eq!(seven.tuple_count_upper_bound(6), 3600);
+    assert!(!seven.is_tractable(6, SAT_TUPLE_THRESHOLD));
+    assert!(seven.is_tractable(6, SEARCH_TUPLE_BUDGET));
+    // 6 * 5 * 4 * 3 * 2 along row 1, then 5, 5 and 4 along row 2.
+    assert_eq!(eight.tuple_count_upper_bound(6), 72_000);
+    assert!(!eight.is_tractable(6, SEARCH_TUPLE_BUDGET));
+}
+
+#[cfg(feature = "sat-varisat")]
+#[test]
+fn sat_uniqueness_falls_back_past_the_allowlist_threshold() {
+    use kenken_solver::sat_cages::puzzle_uniqueness_via_sat;
+    use kenken_solver::sat_latin::SatUniqueness;
+
+    let (puzzle, _) = seven_and_eight_6x6();
+    assert_eq!(
+        puzzle_uniqueness_via_sat(&puzzle, eight_cell_rules()),
+        SatUniqueness::Unique
+    );
+}
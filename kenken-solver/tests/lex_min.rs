@@ -0,0 +1,157 @@
+//! `SolveMode::LexMin`: every `solve_one*` entry point returns the lexicographically smallest
+//! solution, whatever the deduction tier, search options or feature set of the build.
+
+use kenken_core::format::sgt_desc::parse_keen_desc;
+use kenken_core::rules::{Op, Ruleset};
+use kenken_core::{Cage, CellId, Puzzle};
+use kenken_solver::small_n::table_solutions;
+use kenken_solver::{
+    ComponentSplit, DeductionTier, SearchObserver, SearchOptions, SmallNTables, SolveMode,
+    SolverBackend, solve_one, solve_one_ignoring_cages, solve_one_observed,
+    solve_one_with_deductions, solve_one_with_options, solve_one_with_stats,
+};
+
+mod common;
+use common::load_corpus;
+
+struct Quiet;
+
+impl SearchObserver for Quiet {}
+
+/// Every option combination the first-solution search distinguishes, in LexMin.
+fn option_grid() -> Vec<SearchOptions> {
+    let mut grid = Vec::new();
+    for small_n_tables in [
+        SmallNTables::Never,
+        SmallNTables::Counting,
+        SmallNTables::Always,
+    ] {
+        for split_components in [
+            ComponentSplit::Never,
+            ComponentSplit::Counting,
+            ComponentSplit::Always,
+        ] {
+            for backend in [SolverBackend::Auto, SolverBackend::Wide] {
+                grid.push(SearchOptions {
+                    small_n_tables,
+                    split_components,
+                    backend,
+                    ..SearchOptions::default()
+                });
+            }
+        }
+    }
+    grid
+}
+
+/// Every LexMin entry point on `puzzle` returns `expected`.
+fn assert_lex_min(puzzle: &Puzzle, expected: &[u8], label: &str) {
+    let rules = Ruleset::keen_baseline();
+    let grid = |solution: Option<kenken_solver::Solution>| solution.map(|s| s.grid);
+    assert_eq!(
+        grid(solve_one(puzzle, rules).unwrap()).as_deref(),
+        Some(expected),
+        "{label}: solve_one"
+    );
+    assert_eq!(
+        grid(solve_one_with_stats(puzzle, rules).unwrap().0).as_deref(),
+        Some(expected),
+        "{label}: solve_one_with_stats"
+    );
+    for tier in DeductionTier::ALL {
+        assert_eq!(
+            grid(solve_one_with_deductions(puzzle, rules, tier).unwrap()).as_deref(),
+            Some(expected),
+            "{label}: solve_one_with_deductions at {tier:?}"
+        );
+        assert_eq!(
+            grid(solve_one_observed(puzzle, rules, tier, &mut Quiet).unwrap()).as_deref(),
+            Some(expected),
+            "{label}: solve_one_observed at {tier:?}"
+        );
+        assert_eq!(
+            grid(solve_one_ignoring_cages(puzzle, rules, tier, &[]).unwrap()).as_deref(),
+            Some(expected),
+            "{label}: solve_one_ignoring_cages at {tier:?}"
+        );
+        for options in option_grid() {
+            let (solution, _) = solve_one_with_options(puzzle, rules, tier, options.clone())
+                .unwrap_or_else(|e| panic!("{label} at {tier:?} with {options:?}: {e}"));
+            assert_eq!(
+                grid(solution).as_deref(),
+                Some(expected),
+                "{label}: {tier:?} with {options:?}"
+            );
+        }
+    }
+}
+
+#[test]
+fn multi_solution_corpus_entries_give_the_smallest_solution() {
+    let mut checked = 0;
+    for def in load_corpus("golden.jsonl") {
+        if def.solutions < 2 {
+            continue;
+        }
+        let puzzle = parse_keen_desc(def.n, &def.desc).unwrap();
+        // The tables list solutions in lexicographic order.
+        let solutions = table_solutions(&puzzle, Ruleset::keen_baseline())
+            .unwrap()
+            .expect("multi-solution corpus entries fit the tables");
+        assert_eq!(solutions.len() as u32, def.solutions, "{}", def.label);
+        assert_lex_min(&puzzle, solutions[0], &def.label);
+        checked += 1;
+    }
+    assert!(checked >= 4, "only {checked} multi-solution corpus entries");
+}
+
+#[test]
+fn ambiguous_4x4_puzzles_give_the_smallest_solution() {
+    for desc in ["a3_aaba__a3_,a7a12m3m24a8", "a3_ada__a__a_,a7a12m3a12a5"] {
+        let puzzle = parse_keen_desc(4, desc).unwrap();
+        let solutions = table_solutions(&puzzle, Ruleset::keen_baseline())
+            .unwrap()
+            .unwrap();
+        assert!(solutions.len() > 1, "{desc}");
+        assert_lex_min(&puzzle, solutions[0], desc);
+    }
+}
+
+/// One `Add` cage per row: every Latin square of order `n` solves it.
+fn row_cages(n: u8) -> Puzzle {
+    let target = (1..=i64::from(n)).sum();
+    let cages = (0..n)
+        .map(|row| Cage {
+            cells: (0..n).map(|col| CellId(u16::from(row * n + col))).collect(),
+            op: Op::Add,
+            target,
+        })
+        .collect();
+    Puzzle { n, cages }
+}
+
+#[test]
+fn larger_ambiguous_grids_agree_across_tiers_and_options() {
+    let rules = Ruleset::keen_baseline();
+    for n in [5, 6] {
+        let puzzle = row_cages(n);
+        let lex_min = solve_one(&puzzle, rules).unwrap().unwrap();
+        assert!(lex_min.validate_against(&puzzle, rules).is_ok());
+        // The smallest Latin square starts with 1..=n in its first row and column.
+        let first_row: Vec<u8> = (1..=n).collect();
+        assert_eq!(lex_min.row(0), first_row.as_slice());
+        assert!((0..n).all(|r| lex_min.get(r as usize, 0) == r + 1));
+        assert_lex_min(&puzzle, &lex_min.grid, &format!("{n}x{n} row cages"));
+
+        for tier in DeductionTier::ALL {
+            let mrv = SearchOptions {
+                solve_mode: SolveMode::Mrv,
+                ..SearchOptions::default()
+            };
+            let (other, _) = solve_one_with_options(&puzzle, rules, tier, mrv).unwrap();
+            let other = other.unwrap();
+            assert!(other.validate_against(&puzzle, rules).is_ok());
+            assert!(lex_min.grid <= other.grid, "{n}x{n} at {tier:?}");
+        }
+    }
+}
@@ -0,0 +1,62 @@
+//! `SearchOptions::mrv_tuple_tiebreak` only reorders branching: every count over the golden
+//! corpus is unchanged, and LexMin solves do not consult it at all.
+
+use kenken_core::format::sgt_desc::parse_keen_desc;
+use kenken_core::rules::Ruleset;
+use kenken_solver::{
+    DeductionTier, SearchOptions, SolveMode, count_solutions_up_to_with_options,
+    solve_one_with_options,
+};
+
+mod common;
+use common::load_corpus;
+
+const TIERS: [DeductionTier; 4] = [
+    DeductionTier::None,
+    DeductionTier::Easy,
+    DeductionTier::Normal,
+    DeductionTier::Hard,
+];
+
+fn tiebreak(solve_mode: SolveMode) -> SearchOptions {
+    SearchOptions {
+        mrv_tuple_tiebreak: true,
+        solve_mode,
+        ..SearchOptions::default()
+    }
+}
+
+#[test]
+fn tiebreak_keeps_counts_and_solutions() {
+    let rules = Ruleset::keen_baseline();
+    for def in load_corpus("golden.jsonl") {
+        let puzzle = parse_keen_desc(def.n, &def.desc).unwrap();
+        if puzzle.validate(rules).is_err() {
+            continue;
+        }
+        for tier in TIERS {
+            let at = format!("{} at {tier:?}", def.label);
+            let limit = def.solutions.saturating_add(1);
+            let (count, _) = count_solutions_up_to_with_options(
+                &puzzle,
+                rules,
+                tier,
+                limit,
+                tiebreak(SolveMode::Mrv),
+            )
+            .unwrap();
+            assert_eq!(count, def.solutions, "{at}");
+
+            let lex_min = solve_one_with_options(&puzzle, rules, tier, SearchOptions::default());
+            let with = solve_one_with_options(&puzzle, rules, tier, tiebreak(SolveMode::LexMin));
+            assert_eq!(with.unwrap(), lex_min.unwrap(), "{at}");
+
+            let (mrv, _) =
+                solve_one_with_options(&puzzle, rules, tier, tiebreak(SolveMode::Mrv)).unwrap();
+            assert_eq!(mrv.is_some(), def.solutions > 0, "{at}");
+            if let Some(mrv) = mrv {
+                assert!(mrv.validate_against(&puzzle, rules).is_ok(), "{at}");
+            }
+        }
+    }
+}
@@ -0,0 +1,113 @@
+//! Node-count regression gates for the `solver_regression` benchmark inputs.
+//!
+//! Timings are too noisy for CI, but search-node counts are deterministic. Each ceiling is
+//! roughly twice the count measured when the gate was added; tighten it when a change makes
+//! the solver prune harder, and treat a failure as an algorithmic regression.
+
+#[path = "../benches/regression_fixtures/mod.rs"]
+mod regression_fixtures;
+
+use kenken_core::rules::Ruleset;
+use kenken_solver::{
+    DeductionTier, cage_tuple_supports, count_solutions_up_to_with_deductions_and_stats,
+    propagate_to_fixpoint,
+};
+use regression_fixtures::{
+    TUPLE_GRID_N, five_cell_add_cage, large_cage_6x6, solve_fixtures, tuple_domain_sets,
+    twelve_solution_3x3,
+};
+
+/// `(fixture label, tier, max nodes to reach the first solution)`.
+const SOLVE_CEILINGS: &[(&str, DeductionTier, u64)] = &[
+    ("4x4_mixed", DeductionTier::None, 50),
+    ("4x4_mixed", DeductionTier::Easy, 10),
+    ("4x4_mixed", DeductionTier::Normal, 10),
+    ("4x4_mixed", DeductionTier::Hard, 10),
+    ("5x5_mixed", DeductionTier::None, 220),
+    ("5x5_mixed", DeductionTier::Easy, 12),
+    ("5x5_mixed", DeductionTier::Normal, 10),
+    ("5x5_mixed", DeductionTier::Hard, 10),
+    ("6x6_mixed", DeductionTier::None, 800),
+    ("6x6_mixed", DeductionTier::Easy, 35),
+    ("6x6_mixed", DeductionTier::Normal, 20),
+    ("6x6_mixed", DeductionTier::Hard, 10),
+    ("7x7_mul", DeductionTier::None, 540),
+    ("7x7_mul", DeductionTier::Easy, 8),
+    ("7x7_mul", DeductionTier::Normal, 8),
+    ("7x7_mul", DeductionTier::Hard, 4),
+];
+
+#[test]
+fn solve_fixtures_stay_under_node_ceilings() {
+    let rules = Ruleset::keen_baseline();
+    let fixtures = solve_fixtures();
+
+    for &(label, tier, ceiling) in SOLVE_CEILINGS {
+        let fixture = fixtures.iter().find(|f| f.label == label).unwrap();
+        let (count, stats) =
+            count_solutions_up_to_with_deductions_and_stats(&fixture.puzzle, rules, tier, 1)
+                .unwrap();
+        assert_eq!(count, 1, "{label} at {tier:?}: no solution found");
+        assert!(
+            stats.nodes_visited <= ceiling,
+            "{label} at {tier:?}: {} nodes exceeds ceiling {ceiling}",
+            stats.nodes_visited
+        );
+    }
+}
+
+#[test]
+fn twelve_solution_3x3_counts_all_latin_squares() {
+    let rules = Ruleset::keen_baseline();
+    let puzzle = twelve_solution_3x3();
+
+    for (tier, ceiling) in [(DeductionTier::None, 180), (DeductionTier::Hard, 45)] {
+        let (count, stats) =
+            count_solutions_up_to_with_deductions_and_stats(&puzzle, rules, tier, 100).unwrap();
+        assert_eq!(count, 12, "{tier:?}");
+        assert!(
+            stats.nodes_visited <= ceiling,
+            "3x3 count at {tier:?}: {} nodes exceeds ceiling {ceiling}",
+            stats.nodes_visited
+        );
+    }
+}
+
+#[test]
+fn large_cage_propagation_reaches_consistent_fixpoint() {
+    let rules = Ruleset::keen_baseline();
+    let puzzle = large_cage_6x6();
+
+    for tier in [
+        DeductionTier::Easy,
+        DeductionTier::Normal,
+        DeductionTier::Hard,
+    ] {
+        let grid = propagate_to_fixpoint(&puzzle, rules, tier)
+            .unwrap()
+            .unwrap_or_else(|| panic!("propagation at {tier:?} reported a contradiction"));
+        assert_eq!(grid.len(), 36);
+    }
+}
+
+#[test]
+fn five_cell_add_cage_supports_by_density() {
+    let cage = five_cell_add_cage();
+    let digits = |mask: u64| -> Vec<u32> { (1..=9).filter(|d| mask & (1u64 << d) != 0).collect() };
+
+    for (density, domains) in tuple_domain_sets() {
+        let (per_pos, any) = cage_tuple_supports(TUPLE_GRID_N, &cage, &domains);
+        assert_eq!(per_pos.len(), 5, "{density}");
+        match density {
+            "full" => assert_eq!(digits(any), (1..=9).collect::<Vec<_>>()),
+            "odd" => assert_eq!(digits(any), vec![1, 3, 5, 7, 9]),
+            "sparse" => {
+                // Row 0 and column 2 each hold {2, 5, 8} once (sum 15), so the total is
+                // 30 minus the shared corner, forcing the corner to 5.
+                assert_eq!(digits(per_pos[2]), vec![5]);
+                assert_eq!(digits(any), vec![2, 5, 8]);
+            }
+            other => panic!("unexpected density {other}"),
+        }
+    }
+}
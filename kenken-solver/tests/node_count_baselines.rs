@@ -0,0 +1,174 @@
+//! Exact search-behavior baselines over the golden corpus.
+//!
+//! For every corpus puzzle and deduction tier, a first-solution search must visit exactly the
+//! recorded number of nodes, make exactly the recorded assignments and find the recorded first
+//! solution. Unlike the ceilings in `node_ceilings.rs`, this catches any change to the search,
+//! including improvements and reordered branching, so a change that means to alter the search
+//! regenerates the file in the same commit:
+//!
+//! ```text
+//! UPDATE_BASELINES=1 cargo test -p kenken-solver --test node_count_baselines
+//! ```
+//!
+//! The searches run in [`SolveMode::Mrv`], the performance-oriented order whose first solution
+//! may move; `lcv-heuristic` and `nogood-learning` change its value order and pruning, so the
+//! baselines describe builds without them and the harness only runs there.
+#![cfg(not(any(feature = "lcv-heuristic", feature = "nogood-learning")))]
+
+use std::collections::{HashMap, HashSet};
+
+use kenken_core::format::sgt_desc::parse_keen_desc;
+use kenken_core::rules::Ruleset;
+use kenken_solver::{DeductionTier, SearchOptions, SolveMode, solve_one_with_options};
+
+mod common;
+use common::{
+    NodeBaseline, deduction_tier_name, load_corpus, load_node_baselines, write_node_baselines,
+};
+
+const BASELINES: &str = "node_baselines.json";
+
+const TIERS: [DeductionTier; 4] = [
+    DeductionTier::None,
+    DeductionTier::Easy,
+    DeductionTier::Normal,
+    DeductionTier::Hard,
+];
+
+/// Current search behavior on every valid corpus puzzle, in corpus then tier order.
+fn measure() -> Vec<NodeBaseline> {
+    let rules = Ruleset::keen_baseline();
+    let mut out = Vec::new();
+    for def in load_corpus("golden.jsonl") {
+        let puzzle = parse_keen_desc(def.n, &def.desc).unwrap();
+        if puzzle.validate(rules).is_err() {
+            continue;
+        }
+        for tier in TIERS {
+            let options = SearchOptions {
+                solve_mode: SolveMode::Mrv,
+                ..SearchOptions::default()
+            };
+            let (first, stats) = solve_one_with_options(&puzzle, rules, tier, options)
+                .unwrap_or_else(|e| panic!("{} at {tier:?}: {e}", def.label));
+            out.push(NodeBaseline {
+                label: def.label.clone(),
+                tier,
+                nodes_visited: stats.nodes_visited,
+                assignments: stats.assignments,
+                first_solution: first.map(|s| s.grid),
+            });
+        }
+    }
+    out
+}
+
+/// `expected -> actual (+x.x%)`, or just the value when unchanged.
+fn delta(expected: u64, actual: u64) -> String {
+    if expected == actual {
+        return actual.to_string();
+    }
+    let pct = if expected == 0 {
+        f64::INFINITY
+    } else {
+        (actual as f64 - expected as f64) * 100.0 / expected as f64
+    };
+    format!("{expected} -> {actual} ({pct:+.1}%)")
+}
+
+/// One line per difference between `expected` and `actual`, keyed by label and tier.
+fn differences(expected: &[NodeBaseline], actual: &[NodeBaseline]) -> Vec<String> {
+    let key = |b: &NodeBaseline| (b.label.clone(), deduction_tier_name(b.tier));
+    let recorded: HashMap<_, _> = expected.iter().map(|b| (key(b), b)).collect();
+    let mut report = Vec::new();
+    for now in actual {
+        let (label, tier) = key(now);
+        let Some(&was) = recorded.get(&key(now)) else {
+            report.push(format!("{label} @ {tier}: no baseline recorded"));
+            continue;
+        };
+        if (was.nodes_visited, was.assignments) != (now.nodes_visited, now.assignments) {
+            report.push(format!(
+                "{label} @ {tier}: nodes {}, assignments {}",
+                delta(was.nodes_visited, now.nodes_visited),
+                delta(was.assignments, now.assignments)
+            ));
+        }
+        if was.first_solution != now.first_solution {
+            report.push(format!(
+                "{label} @ {tier}: first solution {:?} -> {:?}",
+                was.first_solution, now.first_solution
+            ));
+        }
+    }
+    let measured: HashSet<_> = actual.iter().map(key).collect();
+    for was in expected.iter().filter(|b| !measured.contains(&key(b))) {
+        report.push(format!(
+            "{} @ {}: baseline for a puzzle no longer measured",
+            was.label,
+            deduction_tier_name(was.tier)
+        ));
+    }
+    report
+}
+
+#[test]
+fn node_counts_match_recorded_baselines() {
+    let actual = measure();
+    if std::env::var_os("UPDATE_BASELINES").is_some_and(|v| v == "1") {
+        write_node_baselines(BASELINES, &actual);
+        eprintln!(
+            "rewrote tests/corpus/{BASELINES} with {} entries",
+            actual.len()
+        );
+        return;
+    }
+
+    let report = differences(&load_node_baselines(BASELINES), &actual);
+    assert!(
+        report.is_empty(),
+        "search behavior differs from tests/corpus/{BASELINES} in {} place(s):\n  {}\n\
+         If the change is intended, rerun with UPDATE_BASELINES=1 and commit the file.",
+        report.len(),
+        report.join("\n  ")
+    );
+}
+
+#[test]
+fn differences_name_each_regression() {
+    let base = NodeBaseline {
+        label: "p".to_string(),
+        tier: DeductionTier::Easy,
+        nodes_visited: 10,
+        assignments: 20,
+        first_solution: Some(vec![1, 2, 2, 1]),
+    };
+    let recorded = std::slice::from_ref(&base);
+    assert!(differences(recorded, recorded).is_empty());
+
+    let slower = NodeBaseline {
+        nodes_visited: 15,
+        first_solution: Some(vec![2, 1, 1, 2]),
+        ..base.clone()
+    };
+    let report = differences(recorded, &[slower]);
+    assert_eq!(
+        report,
+        [
+            "p @ easy: nodes 10 -> 15 (+50.0%), assignments 20",
+            "p @ easy: first solution Some([1, 2, 2, 1]) -> Some([2, 1, 1, 2])",
+        ]
+    );
+
+    let other_tier = NodeBaseline {
+        tier: DeductionTier::Hard,
+        ..base.clone()
+    };
+    assert_eq!(
+        differences(recorded, &[other_tier]),
+        [
+            "p @ hard: no baseline recorded",
+            "p @ easy: baseline for a puzzle no longer measured",
+        ]
+    );
+}
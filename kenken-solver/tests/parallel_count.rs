@@ -0,0 +1,41 @@
+//! `count_solutions_parallel` agrees with the serial count and honours `limit`.
+#![cfg(feature = "parallel-rayon")]
+
+#[path = "../benches/regression_fixtures/mod.rs"]
+mod regression_fixtures;
+
+use kenken_core::rules::Ruleset;
+use kenken_solver::{
+    DeductionTier, count_solutions_parallel, count_solutions_up_to_with_deductions,
+};
+use regression_fixtures::twelve_solution_3x3;
+
+#[test]
+fn parallel_count_matches_serial_count() {
+    let rules = Ruleset::keen_baseline();
+    let puzzle = twelve_solution_3x3();
+    for tier in [
+        DeductionTier::None,
+        DeductionTier::Easy,
+        DeductionTier::Hard,
+    ] {
+        for (limit, expected) in [(100, 12), (5, 5), (1, 1), (0, 0)] {
+            let serial =
+                count_solutions_up_to_with_deductions(&puzzle, rules, tier, limit).unwrap();
+            let parallel = count_solutions_parallel(&puzzle, rules, tier, limit).unwrap();
+            assert_eq!(serial, expected, "serial {tier:?} limit={limit}");
+            assert_eq!(parallel, expected, "parallel {tier:?} limit={limit}");
+        }
+    }
+}
+
+#[test]
+fn parallel_count_handles_a_root_that_propagation_solves() {
+    // Singleton grid: Easy propagation fills every cell before any branching.
+    let puzzle =
+        kenken_core::format::sgt_desc::parse_keen_desc(4, "_25,a1a2a3a4a2a1a4a3a3a4a1a2a4a3a2a1")
+            .unwrap();
+    let count = count_solutions_parallel(&puzzle, Ruleset::keen_baseline(), DeductionTier::Easy, 2)
+        .unwrap();
+    assert_eq!(count, 1);
+}
@@ -0,0 +1,79 @@
+//! `SearchOptions::max_propagation_rounds`: cutting propagation short leaves more to the search
+//! without changing any result.
+
+use kenken_core::format::sgt_desc::parse_keen_desc;
+use kenken_core::rules::Ruleset;
+use kenken_solver::{
+    DeductionTier, SearchOptions, count_solutions_up_to_with_options, solve_one_with_options,
+};
+
+mod common;
+use common::load_corpus;
+
+const TIERS: [DeductionTier; 3] = [
+    DeductionTier::Easy,
+    DeductionTier::Normal,
+    DeductionTier::Hard,
+];
+
+#[test]
+fn easy_golden_puzzle_propagates_in_few_rounds() {
+    let rules = Ruleset::keen_baseline();
+    let def = load_corpus("golden.jsonl")
+        .into_iter()
+        .find(|def| def.label == "5x5 shifted anti-diagonal")
+        .unwrap();
+    assert_eq!(def.tier_required, Some(DeductionTier::Easy));
+    let puzzle = parse_keen_desc(def.n, &def.desc).unwrap();
+
+    let (solution, stats) = solve_one_with_options(
+        &puzzle,
+        rules,
+        DeductionTier::Easy,
+        SearchOptions::default(),
+    )
+    .unwrap();
+    assert_eq!(solution.unwrap().grid, def.solution.unwrap());
+    assert!(!stats.backtracked);
+    // One round places every given, the next confirms the fixpoint.
+    assert!(stats.propagation_rounds <= 2, "{stats:?}");
+}
+
+#[test]
+fn one_round_cap_keeps_solutions_and_counts() {
+    let rules = Ruleset::keen_baseline();
+    let one_round = SearchOptions {
+        max_propagation_rounds: 1,
+        ..SearchOptions::default()
+    };
+    let mut more_nodes = 0;
+
+    for def in load_corpus("golden.jsonl") {
+        let puzzle = parse_keen_desc(def.n, &def.desc).unwrap();
+        if puzzle.validate(rules).is_err() {
+            continue;
+        }
+        for tier in TIERS {
+            let default = SearchOptions::default();
+            let (solution, stats) = solve_one_with_options(&puzzle, rules, tier, default).unwrap();
+            let (capped, capped_stats) =
+                solve_one_with_options(&puzzle, rules, tier, one_round.clone()).unwrap();
+            let at = format!("{} at {tier:?}", def.label);
+            if def.solutions == 1 {
+                assert_eq!(capped, solution, "{at}");
+            }
+            if let Some(capped) = &capped {
+                assert!(capped.validate_against(&puzzle, rules).is_ok(), "{at}");
+            }
+            assert!(capped_stats.nodes_visited >= stats.nodes_visited, "{at}");
+            more_nodes += usize::from(capped_stats.nodes_visited > stats.nodes_visited);
+
+            let limit = def.solutions.saturating_add(1);
+            let (count, _) =
+                count_solutions_up_to_with_options(&puzzle, rules, tier, limit, one_round.clone())
+                    .unwrap();
+            assert_eq!(count, def.solutions, "{at}");
+        }
+    }
+    assert!(more_nodes > 0);
+}
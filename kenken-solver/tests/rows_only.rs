@@ -0,0 +1,162 @@
+//! `Ruleset::enforce_columns` off: the search, its deduction tiers and the SAT encoding count
+//! grids whose rows are permutations, agreeing with a brute-force pass over every grid.
+
+use kenken_core::rules::{Op, Ruleset};
+use kenken_core::{Puzzle, PuzzleBuilder};
+use kenken_solver::{
+    ComponentSplit, DeductionTier, SearchOptions, SmallNTables, SolverBackend,
+    count_solutions_up_to_with_deductions, count_solutions_up_to_with_options,
+    solve_one_with_deductions,
+};
+
+const TIERS: [DeductionTier; 4] = [
+    DeductionTier::None,
+    DeductionTier::Easy,
+    DeductionTier::Normal,
+    DeductionTier::Hard,
+];
+
+fn rows_only() -> Ruleset {
+    Ruleset {
+        enforce_columns: false,
+        ..Ruleset::keen_baseline()
+    }
+}
+
+/// One `6+` cage per row: every Latin square of order 3 solves it, and so does every grid
+/// of row permutations.
+fn row_cages() -> Puzzle {
+    PuzzleBuilder::new(3)
+        .cage(Op::Add, 6, &[(0, 0), (0, 1), (0, 2)])
+        .unwrap()
+        .cage(Op::Add, 6, &[(1, 0), (1, 1), (1, 2)])
+        .unwrap()
+        .cage(Op::Add, 6, &[(2, 0), (2, 1), (2, 2)])
+        .unwrap()
+        .build(rows_only())
+        .unwrap()
+}
+
+/// A `2+` down column 0 puts 1 in both its cells, which only a rows-only grid allows; rows 0
+/// and 1 then split {2, 3} and row 2 is free under its `6×`.
+fn column_repeat() -> Puzzle {
+    PuzzleBuilder::new(3)
+        .cage(Op::Add, 2, &[(0, 0), (1, 0)])
+        .unwrap()
+        .cage(Op::Add, 5, &[(0, 1), (0, 2)])
+        .unwrap()
+        .cage(Op::Add, 5, &[(1, 1), (1, 2)])
+        .unwrap()
+        .cage(Op::Mul, 6, &[(2, 0), (2, 1), (2, 2)])
+        .unwrap()
+        .build(rows_only())
+        .unwrap()
+}
+
+/// [`column_repeat`] with every other cell pinned, leaving one solution.
+fn unique_column_repeat() -> Puzzle {
+    let mut builder = PuzzleBuilder::new(3)
+        .cage(Op::Add, 2, &[(0, 0), (1, 0)])
+        .unwrap();
+    for (row, col, digit) in [(0, 1, 2), (0, 2, 3), (1, 1, 3), (1, 2, 2)] {
+        builder = builder.cage(Op::Eq, digit, &[(row, col)]).unwrap();
+    }
+    for (col, digit) in [(0, 2), (1, 1), (2, 3)] {
+        builder = builder.cage(Op::Eq, digit, &[(2, col)]).unwrap();
+    }
+    builder.build(rows_only()).unwrap()
+}
+
+/// Solutions of `puzzle` under `rules`, by checking all `n^(n*n)` grids.
+fn brute_force_count(puzzle: &Puzzle, rules: Ruleset) -> u32 {
+    let n = u32::from(puzzle.n);
+    let cells = (n * n) as usize;
+    let mut grid = vec![0u8; cells];
+    (0..n.pow(cells as u32))
+        .filter(|&code| {
+            let mut code = code;
+            for digit in &mut grid {
+                *digit = (code % n) as u8 + 1;
+                code /= n;
+            }
+            puzzle.check_solution_with_rules(&grid, rules).is_ok()
+        })
+        .count() as u32
+}
+
+#[test]
+fn counts_match_brute_force_at_every_tier() {
+    let rules = rows_only();
+    let latin = Ruleset::keen_baseline();
+    assert_eq!(brute_force_count(&row_cages(), latin), 12);
+    assert_eq!(brute_force_count(&column_repeat(), latin), 0);
+    for (puzzle, expected) in [
+        (row_cages(), 216),
+        (column_repeat(), 24),
+        (unique_column_repeat(), 1),
+    ] {
+        assert_eq!(brute_force_count(&puzzle, rules), expected);
+        for tier in TIERS {
+            assert_eq!(
+                count_solutions_up_to_with_deductions(&puzzle, rules, tier, 1_000).unwrap(),
+                expected,
+                "{tier:?}"
+            );
+            let solution = solve_one_with_deductions(&puzzle, rules, tier)
+                .unwrap()
+                .unwrap();
+            puzzle
+                .check_solution_with_rules(&solution.grid, rules)
+                .unwrap();
+        }
+    }
+}
+
+#[test]
+fn counts_hold_across_search_options() {
+    let rules = rows_only();
+    let variants = [
+        SearchOptions {
+            backend: SolverBackend::Wide,
+            ..SearchOptions::default()
+        },
+        SearchOptions {
+            split_components: ComponentSplit::Never,
+            ..SearchOptions::default()
+        },
+        SearchOptions {
+            small_n_tables: SmallNTables::Always,
+            ..SearchOptions::default()
+        },
+    ];
+    for (puzzle, expected) in [(row_cages(), 216), (column_repeat(), 24)] {
+        for options in &variants {
+            let (count, _) = count_solutions_up_to_with_options(
+                &puzzle,
+                rules,
+                DeductionTier::Hard,
+                1_000,
+                options.clone(),
+            )
+            .unwrap();
+            assert_eq!(count, expected, "{options:?}");
+        }
+    }
+}
+
+#[cfg(feature = "sat-varisat")]
+#[test]
+fn sat_encoding_drops_the_column_clauses() {
+    use kenken_solver::sat_cages::puzzle_uniqueness_via_sat;
+    use kenken_solver::sat_latin::SatUniqueness;
+
+    let rules = rows_only();
+    assert_eq!(
+        puzzle_uniqueness_via_sat(&unique_column_repeat(), rules),
+        SatUniqueness::Unique
+    );
+    assert_eq!(
+        puzzle_uniqueness_via_sat(&column_repeat(), rules),
+        SatUniqueness::Multiple
+    );
+}
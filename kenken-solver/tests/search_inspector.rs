@@ -0,0 +1,174 @@
+#![cfg(feature = "debug-api")]
+//! `SearchOptions::inspector`: breakpoints fire at exactly the requested node counts, show a
+//! state consistent with itself, can abort, and change nothing else about the search.
+
+use std::sync::{Arc, Mutex};
+
+use kenken_core::Puzzle;
+use kenken_core::format::sgt_desc::parse_keen_desc;
+use kenken_core::rules::Ruleset;
+use kenken_solver::debug::{InspectAction, SearchInspector, SearchView};
+use kenken_solver::{
+    CheckpointFrame, DeductionTier, DifficultyTier, SearchOptions, SolveError,
+    classify_difficulty_from_tier, classify_tier_required, count_solutions_up_to_with_options,
+    solve_one_with_options,
+};
+
+/// A generated Extreme 7x7 (seed 1): tens of thousands of nodes without deductions.
+const EXTREME_7X7: &str = "_aa__a_aa_a_5a_5b_4a_a_a_a__aa_3bb_b_4ab__ba_a,\
+                           s4a8m48a19s3a17m48a8s2a6m8m6s5a10s2m35s2a7d3s3a8";
+
+fn extreme() -> Puzzle {
+    parse_keen_desc(7, EXTREME_7X7).unwrap()
+}
+
+/// What one callback saw, copied out of the borrowed view.
+struct Stop {
+    nodes: u64,
+    depth: u32,
+    decisions: Vec<CheckpointFrame>,
+    grid: Vec<u8>,
+    rows: Vec<u64>,
+    cols: Vec<u64>,
+    dump: String,
+}
+
+impl Stop {
+    fn of(view: &SearchView<'_>) -> Self {
+        let n = view.puzzle().n as usize;
+        Self {
+            nodes: view.nodes(),
+            depth: view.depth(),
+            decisions: view.decisions().to_vec(),
+            grid: view.grid().to_vec(),
+            rows: (0..n).map(|r| view.row_mask(r)).collect(),
+            cols: (0..n).map(|c| view.col_mask(c)).collect(),
+            dump: view.to_string(),
+        }
+    }
+
+    /// The masks are the digits of the partial grid, and every decision is placed in it.
+    fn assert_consistent(&self, n: usize) {
+        let bit = |d: u8| if d == 0 { 0 } else { 1u64 << d };
+        for line in 0..n {
+            let row = (0..n).fold(0, |mask, c| mask | bit(self.grid[line * n + c]));
+            let col = (0..n).fold(0, |mask, r| mask | bit(self.grid[r * n + line]));
+            assert_eq!(self.rows[line], row, "node {} row {line}", self.nodes);
+            assert_eq!(self.cols[line], col, "node {} col {line}", self.nodes);
+        }
+        assert_eq!(
+            self.decisions.len(),
+            self.depth as usize,
+            "node {}",
+            self.nodes
+        );
+        let digits = ((1u64 << n) - 1) << 1;
+        for frame in &self.decisions {
+            assert_eq!(
+                self.grid[frame.cell as usize], frame.value,
+                "node {}",
+                self.nodes
+            );
+            assert_eq!(frame.remaining & bit(frame.value), 0, "node {}", self.nodes);
+            assert_eq!(frame.remaining & !digits, 0, "node {}", self.nodes);
+        }
+        assert!(
+            self.dump
+                .starts_with(&format!("node {} at depth {}", self.nodes, self.depth))
+        );
+    }
+}
+
+/// An inspector stopping at `breakpoints` that records each stop and answers `action`.
+fn recording(
+    breakpoints: &[u64],
+    action: InspectAction,
+) -> (SearchInspector, Arc<Mutex<Vec<Stop>>>) {
+    let stops = Arc::new(Mutex::new(Vec::new()));
+    let sink = Arc::clone(&stops);
+    let inspector = SearchInspector::new(breakpoints.iter().copied(), move |view| {
+        sink.lock().unwrap().push(Stop::of(view));
+        action
+    });
+    (inspector, stops)
+}
+
+fn inspected(inspector: SearchInspector) -> SearchOptions {
+    SearchOptions {
+        inspector: Some(inspector),
+        ..SearchOptions::default()
+    }
+}
+
+#[test]
+fn breakpoints_fire_at_exactly_the_requested_nodes() {
+    let puzzle = extreme();
+    let rules = Ruleset::keen_baseline();
+    let required = classify_tier_required(&puzzle, rules).unwrap();
+    assert_eq!(
+        classify_difficulty_from_tier(required),
+        DifficultyTier::Extreme
+    );
+    for tier in [DeductionTier::None, DeductionTier::Easy] {
+        let (solution, stats) =
+            solve_one_with_options(&puzzle, rules, tier, SearchOptions::default()).unwrap();
+        let total = stats.nodes_visited;
+        assert!(total > 20, "{tier:?} took {total} nodes");
+
+        // Out of order and repeated on purpose; the last is past the end of the search.
+        let breakpoints = [total / 2, 1, 2, total / 3, total, total / 2, total + 1];
+        let (inspector, stops) = recording(&breakpoints, InspectAction::Continue);
+        let (inspected_solution, inspected_stats) =
+            solve_one_with_options(&puzzle, rules, tier, inspected(inspector)).unwrap();
+        assert_eq!(inspected_solution, solution, "{tier:?}");
+        assert_eq!(inspected_stats, stats, "{tier:?}");
+
+        let stops = stops.lock().unwrap();
+        let fired: Vec<u64> = stops.iter().map(|stop| stop.nodes).collect();
+        assert_eq!(fired, [1, 2, total / 3, total / 2, total], "{tier:?}");
+        for stop in stops.iter() {
+            stop.assert_consistent(puzzle.n as usize);
+        }
+        assert!(stops.iter().any(|stop| stop.depth > 0), "{tier:?}");
+    }
+}
+
+#[test]
+fn abort_stops_the_search_at_its_breakpoint() {
+    let puzzle = extreme();
+    let (inspector, stops) = recording(&[10, 20], InspectAction::Abort);
+    let err = solve_one_with_options(
+        &puzzle,
+        Ruleset::keen_baseline(),
+        DeductionTier::None,
+        inspected(inspector),
+    )
+    .unwrap_err();
+    assert!(
+        matches!(err, SolveError::InspectorAbort { nodes: 10 }),
+        "{err:?}"
+    );
+    assert_eq!(stops.lock().unwrap().len(), 1);
+}
+
+#[test]
+fn counts_are_unchanged_by_inspection() {
+    let puzzle = extreme();
+    let rules = Ruleset::keen_baseline();
+    for tier in [DeductionTier::None, DeductionTier::Normal] {
+        let expected =
+            count_solutions_up_to_with_options(&puzzle, rules, tier, 2, SearchOptions::default())
+                .unwrap();
+        let breakpoints: Vec<u64> = (1..=expected.1.nodes_visited).step_by(97).collect();
+        let (inspector, stops) = recording(&breakpoints, InspectAction::Continue);
+        let counted =
+            count_solutions_up_to_with_options(&puzzle, rules, tier, 2, inspected(inspector))
+                .unwrap();
+        assert_eq!(counted, expected, "{tier:?}");
+        let stops = stops.lock().unwrap();
+        assert_eq!(stops.len(), breakpoints.len(), "{tier:?}");
+        for stop in stops.iter() {
+            stop.assert_consistent(puzzle.n as usize);
+        }
+    }
+}
@@ -0,0 +1,100 @@
+//! `SearchObserver` event sequences from `solve_one_observed`.
+
+#[path = "../benches/regression_fixtures/mod.rs"]
+mod regression_fixtures;
+
+use kenken_core::rules::Ruleset;
+use kenken_solver::{
+    DeductionTier, OBSERVER_NODE_INTERVAL, SearchObserver, SearchOptions, solve_one_observed,
+    solve_one_with_options,
+};
+use regression_fixtures::solve_fixtures;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Event {
+    Node(u32, u64),
+    Solution(u32),
+    Backtrack(u32),
+}
+
+#[derive(Default)]
+struct Recorder(Vec<Event>);
+
+impl SearchObserver for Recorder {
+    fn on_node(&mut self, depth: u32, nodes: u64) {
+        self.0.push(Event::Node(depth, nodes));
+    }
+    fn on_solution(&mut self, count: u32) {
+        self.0.push(Event::Solution(count));
+    }
+    fn on_backtrack(&mut self, depth: u32) {
+        self.0.push(Event::Backtrack(depth));
+    }
+}
+
+struct Silent;
+
+impl SearchObserver for Silent {}
+
+#[test]
+fn events_are_throttled_monotone_and_end_with_solution() {
+    let rules = Ruleset::keen_baseline();
+    let fixture = solve_fixtures()
+        .into_iter()
+        .find(|f| f.label == "6x6_mixed")
+        .unwrap();
+    let tier = DeductionTier::None;
+
+    let mut recorder = Recorder::default();
+    let sol = solve_one_observed(&fixture.puzzle, rules, tier, &mut recorder).unwrap();
+    let (expected, stats) =
+        solve_one_with_options(&fixture.puzzle, rules, tier, SearchOptions::default()).unwrap();
+    assert_eq!(sol, expected);
+    assert!(sol.is_some());
+
+    let events = recorder.0;
+    assert_eq!(events.first(), Some(&Event::Node(0, 1)));
+    assert_eq!(events.last(), Some(&Event::Solution(1)));
+    let backtracks: Vec<u32> = events
+        .iter()
+        .filter_map(|e| match *e {
+            Event::Backtrack(depth) => Some(depth),
+            _ => None,
+        })
+        .collect();
+    assert!(!backtracks.is_empty());
+    assert!(backtracks.iter().all(|&d| d <= stats.max_depth));
+
+    let nodes: Vec<u64> = events
+        .iter()
+        .filter_map(|e| match *e {
+            Event::Node(_, nodes) => Some(nodes),
+            _ => None,
+        })
+        .collect();
+    assert!(nodes.len() >= 2, "fixture too small to exercise throttling");
+    assert!(nodes.windows(2).all(|w| w[0] < w[1]), "{nodes:?}");
+    assert!(
+        nodes
+            .iter()
+            .all(|&k| (k - 1).is_multiple_of(OBSERVER_NODE_INTERVAL))
+    );
+    assert_eq!(
+        nodes.len() as u64,
+        (stats.nodes_visited - 1) / OBSERVER_NODE_INTERVAL + 1
+    );
+}
+
+#[test]
+fn default_methods_are_no_ops() {
+    let rules = Ruleset::keen_baseline();
+    for fixture in solve_fixtures() {
+        for tier in [DeductionTier::None, DeductionTier::Hard] {
+            let observed = solve_one_observed(&fixture.puzzle, rules, tier, &mut Silent).unwrap();
+            let (plain, _) =
+                solve_one_with_options(&fixture.puzzle, rules, tier, SearchOptions::default())
+                    .unwrap();
+            assert_eq!(observed, plain, "{} at {tier:?}", fixture.label);
+        }
+    }
+}
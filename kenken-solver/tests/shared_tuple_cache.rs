@@ -0,0 +1,106 @@
+//! `SearchOptions::tuple_cache`: a shared store of full-domain cage enumerations changes no
+//! result or statistic, and answers repeat lookups across solves, within its byte limit if it
+//! has one.
+
+use kenken_core::format::sgt_desc::parse_keen_desc;
+use kenken_core::rules::Ruleset;
+use kenken_solver::{
+    DeductionTier, SearchOptions, TupleCacheHandle, TupleCacheStats,
+    count_solutions_up_to_with_options, solve_one_with_options,
+};
+
+mod common;
+use common::load_corpus;
+
+const TIERS: [DeductionTier; 3] = [
+    DeductionTier::Easy,
+    DeductionTier::Normal,
+    DeductionTier::Hard,
+];
+
+/// Solve and count the golden corpus twice with `cache` and without, asserting the results
+/// agree, and return the store's counters.
+fn solve_corpus_with(plain: SearchOptions, cache: TupleCacheHandle) -> TupleCacheStats {
+    let rules = Ruleset::keen_baseline();
+    let cached = SearchOptions {
+        tuple_cache: Some(cache),
+        ..plain.clone()
+    };
+
+    // Two passes: the first mostly fills the store, the second mostly reads it.
+    for pass in 0..2 {
+        for def in load_corpus("golden.jsonl") {
+            let puzzle = parse_keen_desc(def.n, &def.desc).unwrap();
+            if puzzle.validate(rules).is_err() {
+                continue;
+            }
+            for tier in TIERS {
+                let at = format!("{} at {tier:?}, pass {pass}", def.label);
+                assert_eq!(
+                    solve_one_with_options(&puzzle, rules, tier, cached.clone()).unwrap(),
+                    solve_one_with_options(&puzzle, rules, tier, plain.clone()).unwrap(),
+                    "{at}"
+                );
+                let limit = def.solutions.saturating_add(1);
+                assert_eq!(
+                    count_solutions_up_to_with_options(&puzzle, rules, tier, limit, cached.clone())
+                        .unwrap(),
+                    count_solutions_up_to_with_options(&puzzle, rules, tier, limit, plain.clone())
+                        .unwrap(),
+                    "{at}"
+                );
+            }
+        }
+    }
+
+    cached.tuple_cache.unwrap().stats()
+}
+
+fn assert_cache_is_transparent(plain: SearchOptions) {
+    let stats = solve_corpus_with(plain, TupleCacheHandle::new());
+    assert!(stats.hits > 0 && stats.entries > 0, "{stats:?}");
+    assert!(stats.hit_rate() > 0.5, "{stats:?}");
+}
+
+#[test]
+fn cached_solves_match_uncached_over_golden_corpus() {
+    assert_cache_is_transparent(SearchOptions::default());
+}
+
+#[test]
+fn cached_solves_match_uncached_under_a_tiny_work_cap() {
+    // Enumerations past the cap are stored as cut off and never applied, so the cached solve
+    // degrades exactly the cages the uncached one does.
+    assert_cache_is_transparent(SearchOptions {
+        max_tuples_per_cage: 8,
+        ..SearchOptions::default()
+    });
+}
+
+#[test]
+fn clones_share_one_store() {
+    let handle = TupleCacheHandle::new();
+    assert_eq!(handle.clone(), handle);
+    assert_ne!(TupleCacheHandle::new(), handle);
+    assert_eq!(handle.stats().hit_rate(), 0.0);
+}
+
+#[test]
+fn a_byte_limited_store_stays_within_its_limit() {
+    let unbounded = solve_corpus_with(SearchOptions::default(), TupleCacheHandle::new());
+    // One byte short of every shape the corpus stores.
+    let limit = unbounded.bytes - 1;
+    let bounded = solve_corpus_with(
+        SearchOptions::default(),
+        TupleCacheHandle::with_byte_limit(limit),
+    );
+    assert!(bounded.bytes <= limit, "{bounded:?}");
+    assert!(bounded.entries < unbounded.entries, "{bounded:?}");
+    assert!(bounded.hits > 0, "{bounded:?}");
+
+    let empty = solve_corpus_with(
+        SearchOptions::default(),
+        TupleCacheHandle::with_byte_limit(0),
+    );
+    assert_eq!((empty.entries, empty.bytes, empty.hits), (0, 0, 0));
+}
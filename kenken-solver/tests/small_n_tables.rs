@@ -0,0 +1,125 @@
+//! `SearchOptions::small_n_tables`: answering grids up to 4x4 from the Latin square tables
+//! gives the search's counts and first solutions on every small golden puzzle.
+
+use kenken_core::format::sgt_desc::parse_keen_desc;
+use kenken_core::rules::Ruleset;
+use kenken_solver::small_n::{SMALL_N_MAX, table_solutions};
+use kenken_solver::{
+    DeductionTier, SearchOptions, SmallNTables, SolveMode, count_solutions_up_to_with_deductions,
+    count_solutions_up_to_with_deductions_and_stats, count_solutions_up_to_with_options,
+    solve_one_with_deductions, solve_one_with_options,
+};
+
+mod common;
+use common::load_corpus;
+
+fn tables(small_n_tables: SmallNTables) -> SearchOptions {
+    SearchOptions {
+        small_n_tables,
+        ..SearchOptions::default()
+    }
+}
+
+#[test]
+fn table_answers_match_the_search_over_golden_corpus() {
+    let rules = Ruleset::keen_baseline();
+    let mut checked = 0;
+    for def in load_corpus("golden.jsonl") {
+        if def.n > SMALL_N_MAX {
+            continue;
+        }
+        let puzzle = parse_keen_desc(def.n, &def.desc).unwrap();
+        let label = &def.label;
+
+        let solutions = table_solutions(&puzzle, rules).unwrap().unwrap();
+        assert_eq!(solutions.len() as u32, def.solutions, "{label}");
+        if let Some(solution) = &def.solution {
+            assert_eq!(solutions, [solution.as_slice()], "{label}");
+        }
+
+        for tier in DeductionTier::ALL {
+            for limit in [1, 2, 1000] {
+                let searched = count_solutions_up_to_with_options(
+                    &puzzle,
+                    rules,
+                    tier,
+                    limit,
+                    tables(SmallNTables::Never),
+                )
+                .unwrap();
+                let looked_up = count_solutions_up_to_with_options(
+                    &puzzle,
+                    rules,
+                    tier,
+                    limit,
+                    tables(SmallNTables::Counting),
+                )
+                .unwrap();
+                assert_eq!(
+                    looked_up.0, searched.0,
+                    "{label} at {tier:?}, limit {limit}"
+                );
+                assert_eq!(looked_up.0, def.solutions.min(limit), "{label}");
+                assert_eq!(looked_up.1.nodes_visited, 0, "{label}");
+                assert_eq!(
+                    count_solutions_up_to_with_deductions(&puzzle, rules, tier, limit).unwrap(),
+                    searched.0,
+                    "{label} at {tier:?}, limit {limit}"
+                );
+                assert_eq!(
+                    count_solutions_up_to_with_deductions_and_stats(&puzzle, rules, tier, limit)
+                        .unwrap(),
+                    looked_up,
+                    "{label} at {tier:?}, limit {limit}"
+                );
+            }
+
+            let (searched, _) =
+                solve_one_with_options(&puzzle, rules, tier, tables(SmallNTables::Never)).unwrap();
+            let (looked_up, _) =
+                solve_one_with_options(&puzzle, rules, tier, tables(SmallNTables::Always)).unwrap();
+            assert_eq!(looked_up, searched, "{label} at {tier:?}");
+            assert_eq!(
+                solve_one_with_deductions(&puzzle, rules, tier).unwrap(),
+                searched,
+                "{label} at {tier:?}"
+            );
+        }
+        checked += 1;
+    }
+    assert!(checked >= 30, "only {checked} small puzzles in the corpus");
+}
+
+#[test]
+fn ambiguous_puzzles_fall_back_to_the_search_under_mrv() {
+    let rules = Ruleset::keen_baseline();
+    // Row cages: the MRV search's first solution need not be the table's first.
+    let puzzle = parse_keen_desc(3, "f_6,a6a6a6").unwrap();
+    let tier = DeductionTier::Hard;
+    let mrv = |small_n_tables| SearchOptions {
+        solve_mode: SolveMode::Mrv,
+        ..tables(small_n_tables)
+    };
+    let (searched, searched_stats) =
+        solve_one_with_options(&puzzle, rules, tier, mrv(SmallNTables::Never)).unwrap();
+    let (always, always_stats) =
+        solve_one_with_options(&puzzle, rules, tier, mrv(SmallNTables::Always)).unwrap();
+    assert_eq!(always, searched);
+    assert_eq!(always_stats.nodes_visited, searched_stats.nodes_visited);
+    assert!(always_stats.nodes_visited > 0);
+}
+
+#[test]
+fn lex_min_answers_ambiguous_puzzles_from_the_table() {
+    let rules = Ruleset::keen_baseline();
+    let puzzle = parse_keen_desc(3, "f_6,a6a6a6").unwrap();
+    let tier = DeductionTier::Hard;
+    let (searched, searched_stats) =
+        solve_one_with_options(&puzzle, rules, tier, tables(SmallNTables::Never)).unwrap();
+    let (always, always_stats) =
+        solve_one_with_options(&puzzle, rules, tier, tables(SmallNTables::Always)).unwrap();
+    assert_eq!(always, searched);
+    assert_eq!(always.unwrap().grid, [1, 2, 3, 2, 3, 1, 3, 1, 2]);
+    assert!(searched_stats.nodes_visited > 0);
+    assert_eq!(always_stats.nodes_visited, 0);
+}
@@ -0,0 +1,197 @@
+//! `count_solutions_up_to_warm_start`: starting from a known solution gives the same counts as
+//! a cold count, and a grid that is not a solution changes nothing. The same holds for
+//! `CountStrategy::DivergeFrom`, which warm-starts grids the small-n tables do not answer, and
+//! `solve_other_than` returns a second solution exactly when the warm count finds one.
+
+use kenken_core::format::sgt_desc::parse_keen_desc;
+use kenken_core::rules::Ruleset;
+use kenken_solver::{
+    CountStrategy, DeductionTier, SearchOptions, SmallNTables, count_solutions_up_to_warm_start,
+    count_solutions_up_to_with_options, count_solutions_up_to_with_strategy,
+    solve_one_with_options, solve_other_than,
+};
+
+mod common;
+use common::load_corpus;
+
+const TIERS: [DeductionTier; 3] = [
+    DeductionTier::Easy,
+    DeductionTier::Normal,
+    DeductionTier::Hard,
+];
+
+#[test]
+fn warm_counts_match_cold_counts_over_golden_corpus() {
+    let rules = Ruleset::keen_baseline();
+    for def in load_corpus("golden.jsonl") {
+        let puzzle = parse_keen_desc(def.n, &def.desc).unwrap();
+        if puzzle.validate(rules).is_err() {
+            continue;
+        }
+        for tier in TIERS {
+            // Node counts are compared below, so small grids must be searched, not looked up.
+            let options = SearchOptions {
+                small_n_tables: SmallNTables::Never,
+                ..SearchOptions::default()
+            };
+            let (Some(known), _) =
+                solve_one_with_options(&puzzle, rules, tier, options.clone()).unwrap()
+            else {
+                continue;
+            };
+            for limit in 0..=def.solutions.saturating_add(1) {
+                let at = format!("{} at {tier:?}, limit {limit}", def.label);
+                let (cold, cold_stats) = count_solutions_up_to_with_options(
+                    &puzzle,
+                    rules,
+                    tier,
+                    limit,
+                    options.clone(),
+                )
+                .unwrap();
+                let (warm, warm_stats) = count_solutions_up_to_warm_start(
+                    &puzzle,
+                    rules,
+                    tier,
+                    limit,
+                    &known.grid,
+                    options.clone(),
+                )
+                .unwrap();
+                assert_eq!(warm, cold, "{at}");
+                // A unique puzzle's search space is walked in full either way.
+                if def.solutions == 1 && limit >= 2 {
+                    assert_eq!(warm_stats.nodes_visited, cold_stats.nodes_visited, "{at}");
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn non_solution_grid_falls_back_to_cold_count() {
+    let rules = Ruleset::keen_baseline();
+    let tier = DeductionTier::Hard;
+    let puzzle = parse_keen_desc(4, "_25,a1a2a3a4a2a1a4a3a3a4a1a2a4a3a2a1").unwrap();
+    let cold =
+        count_solutions_up_to_with_options(&puzzle, rules, tier, 2, SearchOptions::default())
+            .unwrap();
+    let mut wrong = vec![1, 2, 3, 4, 2, 1, 4, 3, 3, 4, 1, 2, 4, 3, 2, 1];
+    wrong.swap(0, 1);
+    for known in [&wrong[..], &wrong[..3], &[]] {
+        let warm = count_solutions_up_to_warm_start(
+            &puzzle,
+            rules,
+            tier,
+            2,
+            known,
+            SearchOptions::default(),
+        )
+        .unwrap();
+        assert_eq!(warm, cold, "{known:?}");
+    }
+}
+
+#[test]
+fn warm_start_finds_the_other_solution() {
+    let rules = Ruleset::keen_baseline();
+    let puzzle = parse_keen_desc(2, "b__,a3a3").unwrap();
+    for known in [[1, 2, 2, 1], [2, 1, 1, 2]] {
+        for tier in TIERS {
+            let (count, stats) = count_solutions_up_to_warm_start(
+                &puzzle,
+                rules,
+                tier,
+                2,
+                &known,
+                SearchOptions::default(),
+            )
+            .unwrap();
+            assert_eq!(count, 2, "{known:?} at {tier:?}");
+            assert!(stats.nodes_visited > 0);
+        }
+    }
+}
+
+#[test]
+fn diverging_counts_match_default_counts() {
+    let rules = Ruleset::keen_baseline();
+    for def in load_corpus("golden.jsonl") {
+        let puzzle = parse_keen_desc(def.n, &def.desc).unwrap();
+        if puzzle.validate(rules).is_err() {
+            continue;
+        }
+        for tier in TIERS {
+            let (Some(known), _) =
+                solve_one_with_options(&puzzle, rules, tier, SearchOptions::default()).unwrap()
+            else {
+                continue;
+            };
+            let count = |strategy| {
+                count_solutions_up_to_with_strategy(
+                    &puzzle,
+                    rules,
+                    tier,
+                    2,
+                    strategy,
+                    SearchOptions::default(),
+                )
+                .unwrap()
+            };
+            let (default, default_stats) = count(CountStrategy::Default);
+            let (diverging, diverging_stats) = count(CountStrategy::DivergeFrom(&known.grid));
+            let at = format!("{} at {tier:?}", def.label);
+            assert_eq!(diverging, default, "{at}");
+            // Grids the tables answer cost no nodes under either strategy.
+            if def.n <= 4 {
+                assert_eq!(diverging_stats.nodes_visited, 0, "{at}");
+                assert_eq!(default_stats.nodes_visited, 0, "{at}");
+            }
+        }
+    }
+}
+
+#[test]
+fn other_solutions_match_warm_counts() {
+    let rules = Ruleset::keen_baseline();
+    for def in load_corpus("golden.jsonl") {
+        let puzzle = parse_keen_desc(def.n, &def.desc).unwrap();
+        if puzzle.validate(rules).is_err() {
+            continue;
+        }
+        for tier in TIERS {
+            for small_n_tables in [SmallNTables::Never, SmallNTables::Counting] {
+                let options = SearchOptions {
+                    small_n_tables,
+                    ..SearchOptions::default()
+                };
+                let (Some(known), _) =
+                    solve_one_with_options(&puzzle, rules, tier, options.clone()).unwrap()
+                else {
+                    continue;
+                };
+                let at = format!("{} at {tier:?}, {small_n_tables:?}", def.label);
+                let (count, count_stats) = count_solutions_up_to_warm_start(
+                    &puzzle,
+                    rules,
+                    tier,
+                    2,
+                    &known.grid,
+                    options.clone(),
+                )
+                .unwrap();
+                let (other, stats) =
+                    solve_other_than(&puzzle, rules, tier, &known.grid, options).unwrap();
+                assert_eq!(other.is_some(), count == 2, "{at}");
+                if let Some(other) = other {
+                    assert_ne!(other.grid, known.grid, "{at}");
+                    assert!(puzzle.check_solution_with_rules(&other.grid, rules).is_ok());
+                }
+                // The same search, stopped at the same solution.
+                if small_n_tables == SmallNTables::Never {
+                    assert_eq!(stats.nodes_visited, count_stats.nodes_visited, "{at}");
+                }
+            }
+        }
+    }
+}
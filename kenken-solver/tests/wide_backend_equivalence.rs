@@ -0,0 +1,307 @@
+//! `SolverBackend::Wide` against the native masks: on every golden-corpus puzzle and 200
+//! generated ones, at every tier, the wide search must make the same propagation rounds, force
+//! the same cells in the same order, visit the same nodes and find the same solutions, warm
+//! started from a known solution as well as cold.
+//!
+//! The per-round comparison needs `debug-snapshots`; without it only the search results are
+//! compared.
+
+use kenken_core::arith::op_value;
+use kenken_core::format::sgt_desc::parse_keen_desc;
+use kenken_core::rules::{Op, Ruleset};
+use kenken_core::{Cage, CellId, Puzzle};
+use kenken_solver::{
+    DeductionTier, SearchOptions, SolverBackend, count_solutions_up_to_warm_start,
+    count_solutions_up_to_with_options, solve_one_with_options, solve_other_than,
+};
+
+mod common;
+use common::load_corpus;
+
+const TIERS: [DeductionTier; 4] = [
+    DeductionTier::None,
+    DeductionTier::Easy,
+    DeductionTier::Normal,
+    DeductionTier::Hard,
+];
+
+const GENERATED: u64 = 200;
+
+fn backend(backend: SolverBackend) -> SearchOptions {
+    SearchOptions {
+        backend,
+        ..SearchOptions::default()
+    }
+}
+
+/// SplitMix64, so the generated set is the same on every platform without an RNG crate.
+struct SplitMix(u64);
+
+impl SplitMix {
+    fn next(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    fn below(&mut self, bound: usize) -> usize {
+        (self.next() % bound as u64) as usize
+    }
+
+    fn shuffle<T>(&mut self, items: &mut [T]) {
+        for i in (1..items.len()).rev() {
+            items.swap(i, self.below(i + 1));
+        }
+    }
+}
+
+/// A puzzle over a permuted cyclic Latin square with cages of one to four cells grown from
+/// random seeds, clued from the square. Not necessarily unique.
+fn generated(seed: u64) -> Puzzle {
+    let mut rng = SplitMix(seed);
+    let n = 3 + rng.below(5);
+    let (mut rows, mut cols, mut digits): (Vec<usize>, Vec<usize>, Vec<u8>) =
+        ((0..n).collect(), (0..n).collect(), (1..=n as u8).collect());
+    rng.shuffle(&mut rows);
+    rng.shuffle(&mut cols);
+    rng.shuffle(&mut digits);
+    let solution: Vec<u8> = (0..n * n)
+        .map(|idx| digits[(rows[idx / n] + cols[idx % n]) % n])
+        .collect();
+
+    let mut order: Vec<usize> = (0..n * n).collect();
+    rng.shuffle(&mut order);
+    let mut taken = vec![false; n * n];
+    let mut cages = Vec::new();
+    for start in order {
+        if taken[start] {
+            continue;
+        }
+        taken[start] = true;
+        let mut cells = vec![start];
+        let size = 1 + rng.below(4);
+        while cells.len() < size {
+            let mut open: Vec<usize> = cells
+                .iter()
+                .flat_map(|&c| {
+                    let (r, col) = (c / n, c % n);
+                    [
+                        (r > 0).then(|| c - n),
+                        (r + 1 < n).then(|| c + n),
+                        (col > 0).then(|| c - 1),
+                        (col + 1 < n).then(|| c + 1),
+                    ]
+                })
+                .flatten()
+                .filter(|&c| !taken[c])
+                .collect();
+            open.dedup();
+            if open.is_empty() {
+                break;
+            }
+            let next = open[rng.below(open.len())];
+            taken[next] = true;
+            cells.push(next);
+        }
+
+        let values: Vec<u8> = cells.iter().map(|&c| solution[c]).collect();
+        let op = match values.len() {
+            1 => Op::Eq,
+            2 => {
+                let (hi, lo) = (values[0].max(values[1]), values[0].min(values[1]));
+                match rng.below(4) {
+                    0 => Op::Add,
+                    1 => Op::Mul,
+                    2 => Op::Sub,
+                    _ if hi % lo == 0 => Op::Div,
+                    _ => Op::Sub,
+                }
+            }
+            _ if rng.below(2) == 0 => Op::Add,
+            _ => Op::Mul,
+        };
+        cells.sort_unstable();
+        cages.push(Cage {
+            cells: cells.iter().map(|&c| CellId(c as u16)).collect(),
+            op,
+            target: op_value(op, &values).expect("clued from the square"),
+        });
+    }
+    Puzzle { n: n as u8, cages }
+}
+
+/// Every valid corpus puzzle, then the generated set, labelled.
+fn puzzles() -> Vec<(String, Puzzle)> {
+    let rules = Ruleset::keen_baseline();
+    let corpus = load_corpus("golden.jsonl")
+        .into_iter()
+        .map(|def| {
+            (
+                def.label.clone(),
+                parse_keen_desc(def.n, &def.desc).unwrap(),
+            )
+        })
+        .filter(|(_, puzzle)| puzzle.validate(rules).is_ok());
+    let generated = (0..GENERATED).map(|seed| (format!("generated #{seed}"), generated(seed)));
+    corpus.chain(generated).collect()
+}
+
+#[test]
+fn generated_puzzles_are_valid() {
+    let rules = Ruleset::keen_baseline();
+    for seed in 0..GENERATED {
+        let puzzle = generated(seed);
+        puzzle
+            .validate(rules)
+            .unwrap_or_else(|e| panic!("generated #{seed}: {e}"));
+    }
+}
+
+#[test]
+fn wide_search_matches_native_search() {
+    let rules = Ruleset::keen_baseline();
+    for (label, puzzle) in puzzles() {
+        for tier in TIERS {
+            let at = format!("{label} @ {tier:?}");
+            let (native, native_stats) =
+                solve_one_with_options(&puzzle, rules, tier, backend(SolverBackend::Auto)).unwrap();
+            let (wide, wide_stats) =
+                solve_one_with_options(&puzzle, rules, tier, backend(SolverBackend::Wide)).unwrap();
+            assert_eq!(wide, native, "{at}: first solution");
+            assert_eq!(wide_stats, native_stats, "{at}: solve stats");
+
+            let count = |b| {
+                count_solutions_up_to_with_options(&puzzle, rules, tier, 3, backend(b)).unwrap()
+            };
+            assert_eq!(
+                count(SolverBackend::Wide),
+                count(SolverBackend::Auto),
+                "{at}: count up to 3"
+            );
+
+            let Some(known) = native else {
+                continue;
+            };
+            let warm = |b| {
+                count_solutions_up_to_warm_start(&puzzle, rules, tier, 3, &known.grid, backend(b))
+                    .unwrap()
+            };
+            assert_eq!(
+                warm(SolverBackend::Wide),
+                warm(SolverBackend::Auto),
+                "{at}: warm count up to 3"
+            );
+            let other =
+                |b| solve_other_than(&puzzle, rules, tier, &known.grid, backend(b)).unwrap();
+            assert_eq!(
+                other(SolverBackend::Wide),
+                other(SolverBackend::Auto),
+                "{at}: other solution"
+            );
+        }
+    }
+}
+
+#[cfg(feature = "debug-snapshots")]
+mod rounds {
+    use kenken_solver::{PropagationLog, solve_one_with_propagation_log};
+
+    use super::*;
+
+    /// The first place two logs of the same solve part ways, described for a failure message.
+    fn first_divergence(native: &PropagationLog, wide: &PropagationLog) -> Option<String> {
+        for (i, (a, b)) in native.snapshots.iter().zip(&wide.snapshots).enumerate() {
+            let at = format!("snapshot {i} (call {}, round {})", a.call, a.round);
+            if (a.call, a.round) != (b.call, b.round) {
+                return Some(format!(
+                    "{at}: wide is at call {}, round {}",
+                    b.call, b.round
+                ));
+            }
+            let cells = native.n as usize * native.n as usize;
+            if let Some(cell) =
+                (0..cells).find(|&cell| native.domain(a, cell) != wide.domain(b, cell))
+            {
+                return Some(format!(
+                    "{at}: cell {cell} domain {:#b} native, {:#b} wide",
+                    native.domain(a, cell),
+                    wide.domain(b, cell)
+                ));
+            }
+            if a.forced != b.forced {
+                return Some(format!(
+                    "{at}: forced {:?} native, {:?} wide",
+                    a.forced, b.forced
+                ));
+            }
+            if a.contradiction != b.contradiction {
+                return Some(format!(
+                    "{at}: contradiction {} native, {} wide",
+                    a.contradiction, b.contradiction
+                ));
+            }
+        }
+        if (native.snapshots.len(), native.calls, native.dropped_rounds)
+            != (wide.snapshots.len(), wide.calls, wide.dropped_rounds)
+        {
+            return Some(format!(
+                "{} snapshots over {} calls native, {} over {} wide",
+                native.snapshots.len(),
+                native.calls,
+                wide.snapshots.len(),
+                wide.calls
+            ));
+        }
+        None
+    }
+
+    #[test]
+    fn wide_propagation_matches_native_round_by_round() {
+        let rules = Ruleset::keen_baseline();
+        for (label, puzzle) in puzzles() {
+            for tier in &TIERS[1..] {
+                let log = |b| {
+                    let options = SearchOptions {
+                        max_logged_rounds: usize::MAX,
+                        ..backend(b)
+                    };
+                    solve_one_with_propagation_log(&puzzle, rules, *tier, options).unwrap()
+                };
+                let (native_solution, native) = log(SolverBackend::Auto);
+                let (wide_solution, wide) = log(SolverBackend::Wide);
+                if let Some(divergence) = first_divergence(&native, &wide) {
+                    panic!("{label} @ {tier:?}: {divergence}");
+                }
+                assert_eq!(wide_solution, native_solution, "{label} @ {tier:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn divergences_name_the_first_differing_cell() {
+        let puzzle = parse_keen_desc(4, "a3_aaba__a__a_,a7a12m3m24a5d2").unwrap();
+        let options = SearchOptions {
+            max_logged_rounds: usize::MAX,
+            ..SearchOptions::default()
+        };
+        let (_, log) = solve_one_with_propagation_log(
+            &puzzle,
+            Ruleset::keen_baseline(),
+            DeductionTier::Normal,
+            options,
+        )
+        .unwrap();
+        assert_eq!(first_divergence(&log, &log), None);
+
+        let mut tampered = log.clone();
+        let width = tampered.bytes_per_cell();
+        tampered.snapshots[0].domains[5 * width] ^= 0b10;
+        let message = first_divergence(&log, &tampered).unwrap();
+        assert!(
+            message.starts_with("snapshot 0 (call 0, round 1): cell 5 domain"),
+            "{message}"
+        );
+    }
+}
@@ -255,6 +255,9 @@ fn z3_verify_golden_corpus() {
         // Verify the solution matches what the solver finds
         match solve_one_with_deductions(&puzzle, rules, DeductionTier::Hard) {
             Ok(Some(found_solution)) => {
+                found_solution
+                    .validate_against(&puzzle, rules)
+                    .unwrap_or_else(|e| panic!("{n}x{n}: {e}"));
                 assert_eq!(
                     found_solution.grid.as_slice(),
                     solution,
@@ -6,28 +6,16 @@
 
 #[cfg(feature = "gen")]
 use kenken_core::format::sgt_desc::encode_keen_desc;
-use kenken_core::format::sgt_desc::parse_keen_desc;
+use kenken_core::format::sgt_desc::{infer_keen_desc_size, parse_keen_desc};
 use kenken_core::rules::Ruleset;
-use kenken_solver::{count_solutions_up_to_with_deductions, solve_one_with_deductions};
-
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
-pub enum DeductionTier {
-    None,
-    Easy,
-    Normal,
-    Hard,
-}
-
-impl From<DeductionTier> for kenken_solver::DeductionTier {
-    fn from(t: DeductionTier) -> Self {
-        match t {
-            DeductionTier::None => kenken_solver::DeductionTier::None,
-            DeductionTier::Easy => kenken_solver::DeductionTier::Easy,
-            DeductionTier::Normal => kenken_solver::DeductionTier::Normal,
-            DeductionTier::Hard => kenken_solver::DeductionTier::Hard,
-        }
-    }
-}
+use kenken_core::{CellId, ClueStyle, clue_options};
+// The UDL `DeductionTier` enum is the solver's own type; UniFFI's converters are keyed on
+// this crate's tag, so no mirror enum is needed.
+pub use kenken_solver::{DeductionTier, DifficultyTier};
+use kenken_solver::{
+    cage_candidate_pairs, classify_difficulty_from_tier, classify_tier_required_dispatched,
+    count_solutions_up_to_with_deductions, max_hideable_ops, solve_one_with_deductions,
+};
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Grid {
@@ -41,10 +29,59 @@ pub struct Generated {
     pub solution: Grid,
 }
 
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DailyPuzzle {
+    pub desc: String,
+    pub solution: Grid,
+    pub difficulty: DifficultyTier,
+    pub difficulty_tolerance: u8,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DifficultyInfo {
+    pub difficulty: DifficultyTier,
+    pub tier_required: Option<DeductionTier>,
+    pub nodes_visited: u64,
+    pub backtracked: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MinimizedPuzzle {
+    pub desc: String,
+    pub cages_before: u32,
+    pub cages_after: u32,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CluePlacement {
+    pub cage: u32,
+    pub row: u8,
+    pub col: u8,
+    pub text: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CandidatePair {
+    pub first: u8,
+    pub second: u8,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Capabilities {
+    pub generate: bool,
+    pub solver_dlx: bool,
+    pub sat_varisat: bool,
+    pub verify: bool,
+    pub simd_dispatch: bool,
+    pub parallel_rayon: bool,
+    pub min_grid_size: u8,
+    pub max_grid_size: u8,
+    pub version: String,
+}
+
 pub fn solve_sgt_desc(n: u8, desc: String, tier: DeductionTier) -> Option<Grid> {
     let puzzle = parse_keen_desc(n, &desc).ok()?;
-    let solution =
-        solve_one_with_deductions(&puzzle, Ruleset::keen_baseline(), tier.into()).ok()?;
+    let solution = solve_one_with_deductions(&puzzle, Ruleset::keen_baseline(), tier).ok()?;
     let solution = solution?;
     Some(Grid {
         n: solution.n,
@@ -56,7 +93,7 @@ pub fn generate_sgt_desc(n: u8, seed: u64, tier: DeductionTier) -> Option<Genera
     #[cfg(feature = "gen")]
     {
         let cfg = kenken_gen::generator::GenerateConfig {
-            tier: tier.into(),
+            tier,
             ..kenken_gen::generator::GenerateConfig::keen_baseline(n, seed)
         };
         let g = kenken_gen::generator::generate(cfg).ok()?;
@@ -77,12 +114,388 @@ pub fn generate_sgt_desc(n: u8, seed: u64, tier: DeductionTier) -> Option<Genera
     }
 }
 
+/// [`kenken_gen::generate_daily_set`]: Easy, Normal and Hard puzzles for `date_seed`; requires
+/// `gen`.
+pub fn daily_set(date_seed: u64, n: u8) -> Option<Vec<DailyPuzzle>> {
+    #[cfg(feature = "gen")]
+    {
+        let set = kenken_gen::generate_daily_set(date_seed, n).ok()?;
+        set.into_iter()
+            .map(|g| {
+                Some(DailyPuzzle {
+                    desc: encode_keen_desc(&g.puzzle, Ruleset::keen_baseline()).ok()?,
+                    solution: Grid {
+                        n: g.puzzle.n,
+                        cells: g.solution,
+                    },
+                    difficulty: g.difficulty,
+                    difficulty_tolerance: g.difficulty_tolerance,
+                })
+            })
+            .collect()
+    }
+
+    #[cfg(not(feature = "gen"))]
+    {
+        let _ = (date_seed, n);
+        None
+    }
+}
+
 pub fn count_solutions_sgt_desc(n: u8, desc: String, tier: DeductionTier, limit: u32) -> u32 {
     let Ok(puzzle) = parse_keen_desc(n, &desc) else {
         return 0;
     };
-    count_solutions_up_to_with_deductions(&puzzle, Ruleset::keen_baseline(), tier.into(), limit)
+    count_solutions_up_to_with_deductions(&puzzle, Ruleset::keen_baseline(), tier, limit)
         .unwrap_or(0)
 }
 
+/// Difficulty from [`kenken_solver::classify_tier_required`], for puzzles with exactly one
+/// solution. The classifier stops at the first solution, so it would happily grade a puzzle
+/// with several (or call an unsolvable one a guess); like the golden corpus, those get no grade.
+pub fn classify_sgt_desc(n: u8, desc: String) -> Option<DifficultyInfo> {
+    let puzzle = parse_keen_desc(n, &desc).ok()?;
+    let rules = Ruleset::keen_baseline();
+    let result = classify_tier_required_dispatched(&puzzle, rules).ok()?;
+    if count_solutions_up_to_with_deductions(&puzzle, rules, DeductionTier::Hard, 2).ok()? != 1 {
+        return None;
+    }
+    Some(DifficultyInfo {
+        difficulty: classify_difficulty_from_tier(result),
+        tier_required: result.tier_required,
+        nodes_visited: result.stats.nodes_visited,
+        backtracked: result.stats.backtracked,
+    })
+}
+
+/// Cage merging from [`kenken_gen::minimize_puzzle`] with its baseline config; requires `gen`.
+pub fn minimize_sgt_desc(n: u8, desc: String, solution: Vec<u8>) -> Option<MinimizedPuzzle> {
+    #[cfg(feature = "gen")]
+    {
+        let puzzle = parse_keen_desc(n, &desc).ok()?;
+        let rules = Ruleset::keen_baseline();
+        // The minimizer derives merged targets from `solution`, so it must really solve the puzzle.
+        let solution = kenken_solver::Solution { n, grid: solution };
+        solution.validate_against(&puzzle, rules).ok()?;
+        let cfg = kenken_gen::MinimizeConfig::keen_baseline();
+        let m = kenken_gen::minimize_puzzle(puzzle, &solution.grid, cfg).ok()?;
+        Some(MinimizedPuzzle {
+            desc: encode_keen_desc(&m.puzzle, rules).ok()?,
+            cages_before: m.original_cage_count as u32,
+            cages_after: m.final_cage_count as u32,
+        })
+    }
+
+    #[cfg(not(feature = "gen"))]
+    {
+        let _ = (n, desc, solution);
+        None
+    }
+}
+
+pub fn clue_placements_sgt_desc(n: u8, desc: String) -> Option<Vec<CluePlacement>> {
+    let puzzle = parse_keen_desc(n, &desc).ok()?;
+    let placements = puzzle.clue_placements(&ClueStyle::UNICODE);
+    Some(
+        placements
+            .into_iter()
+            .map(|p| CluePlacement {
+                cage: p.cage as u32,
+                row: p.anchor.row,
+                col: p.anchor.col,
+                text: p.text,
+            })
+            .collect(),
+    )
+}
+
+/// [`kenken_core::clue_options`] under the baseline ruleset, as Unicode clue texts.
+pub fn clue_options_sgt(n: u8, cells: Vec<u16>, solution: Vec<u8>) -> Option<Vec<String>> {
+    let cells: Vec<CellId> = cells.into_iter().map(CellId).collect();
+    let options = clue_options(&cells, &solution, n, Ruleset::keen_baseline()).ok()?;
+    Some(
+        options
+            .into_iter()
+            .map(|(op, target)| ClueStyle::UNICODE.format(op, target))
+            .collect(),
+    )
+}
+
+/// [`kenken_solver::cage_candidate_pairs`] on a desc.
+pub fn candidate_pairs_sgt_desc(
+    n: u8,
+    desc: String,
+    partial: Vec<u8>,
+    cage_index: u32,
+) -> Option<Vec<CandidatePair>> {
+    let puzzle = parse_keen_desc(n, &desc).ok()?;
+    let pairs = cage_candidate_pairs(
+        &puzzle,
+        Ruleset::keen_baseline(),
+        &partial,
+        cage_index as usize,
+    )
+    .ok()?;
+    Some(
+        pairs
+            .into_iter()
+            .map(|(first, second)| CandidatePair { first, second })
+            .collect(),
+    )
+}
+
+/// [`kenken_solver::max_hideable_ops`] on a desc, at the Hard tier.
+pub fn hideable_ops_sgt_desc(n: u8, desc: String) -> Option<Vec<u32>> {
+    let puzzle = parse_keen_desc(n, &desc).ok()?;
+    let hideable = max_hideable_ops(&puzzle, Ruleset::keen_baseline(), DeductionTier::Hard).ok()?;
+    Some(hideable.into_iter().map(|idx| idx as u32).collect())
+}
+
+pub fn infer_size_sgt_desc(desc: String) -> Option<u8> {
+    infer_keen_desc_size(&desc)
+}
+
+/// The parse error for `desc` at `n`, or the size it seems to encode when that differs.
+pub fn desc_error_sgt_desc(n: u8, desc: String) -> Option<String> {
+    let err = parse_keen_desc(n, &desc).err()?;
+    Some(match infer_keen_desc_size(&desc) {
+        Some(m) if m != n => format!("desc appears to encode a {m}x{m} grid but n {n} was given"),
+        _ => err.to_string(),
+    })
+}
+
+/// Solver capabilities, plus whether this build links the generator. Feature flags report
+/// what `kenken-solver` was compiled with; the version is this crate's.
+pub fn capabilities() -> Capabilities {
+    let solver = kenken_solver::capabilities();
+    Capabilities {
+        generate: cfg!(feature = "gen"),
+        solver_dlx: solver.solver_dlx,
+        sat_varisat: solver.sat_varisat,
+        verify: solver.verify,
+        simd_dispatch: solver.simd_dispatch,
+        parallel_rayon: solver.parallel_rayon,
+        min_grid_size: solver.min_grid_size,
+        max_grid_size: solver.max_grid_size,
+        version: env!("CARGO_PKG_VERSION").to_string(),
+    }
+}
+
 uniffi::include_scaffolding!("keen");
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn generate_flag_matches_generate_sgt_desc() {
+        let caps = capabilities();
+        let generated = generate_sgt_desc(4, 7, DeductionTier::Normal);
+        assert_eq!(caps.generate, generated.is_some());
+    }
+
+    #[test]
+    fn daily_set_rises_in_difficulty() {
+        let set = daily_set(20261015, 4);
+        if !cfg!(feature = "gen") {
+            assert_eq!(set, None);
+            return;
+        }
+        let set = set.unwrap();
+        let difficulties: Vec<_> = set.iter().map(|p| p.difficulty).collect();
+        assert_eq!(
+            difficulties,
+            [
+                DifficultyTier::Easy,
+                DifficultyTier::Normal,
+                DifficultyTier::Hard
+            ]
+        );
+        for puzzle in &set {
+            assert_eq!(puzzle.difficulty_tolerance, 0);
+            let solved = solve_sgt_desc(4, puzzle.desc.clone(), DeductionTier::Hard).unwrap();
+            assert_eq!(solved, puzzle.solution);
+        }
+        assert_eq!(daily_set(20261015, 1), None);
+    }
+
+    #[test]
+    fn grid_range_matches_solver_validation() {
+        let caps = capabilities();
+        let solver = kenken_solver::capabilities();
+        assert_eq!(
+            (caps.min_grid_size, caps.max_grid_size),
+            (solver.min_grid_size, solver.max_grid_size)
+        );
+        for n in caps.min_grid_size..=caps.max_grid_size {
+            kenken_core::puzzle::validate_grid_size(n).unwrap();
+        }
+        assert!(kenken_core::puzzle::validate_grid_size(caps.min_grid_size - 1).is_err());
+    }
+
+    // Golden corpus descs (`kenken-solver/tests/corpus/golden.jsonl`).
+    const SINGLETON_4X4: &str = "_25,a1a2a3a4a2a1a4a3a3a4a1a2a4a3a2a1";
+    const SINGLETON_4X4_SOLUTION: [u8; 16] = [1, 2, 3, 4, 2, 1, 4, 3, 3, 4, 1, 2, 4, 3, 2, 1];
+    const TWO_SOLUTIONS_2X2: &str = "b__,a3a3";
+
+    #[test]
+    fn classify_reports_golden_tier() {
+        let info = classify_sgt_desc(4, SINGLETON_4X4.to_string()).unwrap();
+        assert_eq!(info.difficulty, DifficultyTier::Easy);
+        assert_eq!(info.tier_required, Some(DeductionTier::Easy));
+        assert!(!info.backtracked);
+    }
+
+    #[test]
+    fn classify_leaves_multi_solution_desc_ungraded() {
+        let desc = TWO_SOLUTIONS_2X2.to_string();
+        assert_eq!(
+            count_solutions_sgt_desc(2, desc.clone(), DeductionTier::Hard, 3),
+            2
+        );
+        assert_eq!(classify_sgt_desc(2, desc), None);
+    }
+
+    #[test]
+    fn classify_rejects_bad_and_unsolvable_descs() {
+        assert_eq!(classify_sgt_desc(4, "not a desc".to_string()), None);
+        // Two add-4 pairs over a 2x2 grid: each row would need a repeated digit.
+        assert_eq!(classify_sgt_desc(2, "b__,a4a4".to_string()), None);
+    }
+
+    #[test]
+    fn clue_placements_follow_desc_cages() {
+        let placements = clue_placements_sgt_desc(2, TWO_SOLUTIONS_2X2.to_string()).unwrap();
+        let expected = [(0, 0, 0, "3+"), (1, 1, 0, "3+")];
+        assert_eq!(placements.len(), expected.len());
+        for (p, (cage, row, col, text)) in placements.iter().zip(expected) {
+            assert_eq!(
+                (p.cage, p.row, p.col, p.text.as_str()),
+                (cage, row, col, text)
+            );
+        }
+
+        let placements = clue_placements_sgt_desc(4, SINGLETON_4X4.to_string()).unwrap();
+        let texts: Vec<u8> = placements.iter().map(|p| p.text.parse().unwrap()).collect();
+        assert_eq!(texts, SINGLETON_4X4_SOLUTION);
+        assert_eq!(clue_placements_sgt_desc(4, "not a desc".to_string()), None);
+    }
+
+    #[test]
+    fn clue_options_list_unicode_texts() {
+        let options = |cells: Vec<u16>| clue_options_sgt(4, cells, SINGLETON_4X4_SOLUTION.to_vec());
+        assert_eq!(options(vec![6]), Some(vec!["4".to_string()]));
+        assert_eq!(options(vec![0, 1]).unwrap(), ["3+", "2×", "1−", "2÷"]);
+        assert_eq!(options(vec![2, 3]).unwrap(), ["7+", "12×", "1−"]);
+        // Cells 0 and 5 touch only diagonally.
+        assert_eq!(options(vec![0, 5]), None);
+        assert_eq!(options(vec![0, 16]), None);
+    }
+
+    #[test]
+    fn candidate_pairs_follow_the_board() {
+        let desc = "a3_aaba__a__a_,a7a12m3m24a5d2".to_string();
+        let pairs = |partial: Vec<u8>, cage| {
+            candidate_pairs_sgt_desc(4, desc.clone(), partial, cage).map(|pairs| {
+                pairs
+                    .iter()
+                    .map(|p| (p.first, p.second))
+                    .collect::<Vec<_>>()
+            })
+        };
+        let mut partial = vec![1, 2, 3, 4, 4, 3, 2, 0];
+        partial.resize(16, 0);
+        // The 5+ cage on row 2, columns 1 and 2.
+        assert_eq!(pairs(partial.clone(), 4).unwrap(), [(1, 4), (4, 1)]);
+        partial[8] = 1;
+        partial[11] = 2;
+        assert_eq!(pairs(partial.clone(), 4).unwrap(), []);
+
+        // The three-cell 7+ cage, a short board and a bad desc.
+        assert_eq!(pairs(partial.clone(), 0), None);
+        assert_eq!(pairs(vec![0; 15], 4), None);
+        assert_eq!(
+            candidate_pairs_sgt_desc(4, "not a desc".to_string(), vec![0; 16], 0),
+            None
+        );
+    }
+
+    #[test]
+    fn hideable_ops_keep_the_puzzle_unique() {
+        let all: Vec<u32> = (0..16).collect();
+        assert_eq!(
+            hideable_ops_sgt_desc(4, SINGLETON_4X4.to_string()),
+            Some(all)
+        );
+
+        let desc = "a3_aaba__a__a_,a7a12m3m24a5d2";
+        let hideable = hideable_ops_sgt_desc(4, desc.to_string()).unwrap();
+        let puzzle = parse_keen_desc(4, desc).unwrap();
+        let hidden: Vec<usize> = hideable.iter().map(|&idx| idx as usize).collect();
+        let count = kenken_solver::uniqueness_with_hidden_ops(
+            &puzzle,
+            Ruleset::keen_baseline(),
+            &hidden,
+            DeductionTier::Hard,
+        );
+        assert_eq!(count.unwrap(), 1);
+
+        assert_eq!(
+            hideable_ops_sgt_desc(2, TWO_SOLUTIONS_2X2.to_string()),
+            Some(vec![])
+        );
+        assert_eq!(hideable_ops_sgt_desc(4, "not a desc".to_string()), None);
+    }
+
+    #[test]
+    fn desc_errors_name_the_encoded_size() {
+        assert_eq!(infer_size_sgt_desc(SINGLETON_4X4.to_string()), Some(4));
+        assert_eq!(infer_size_sgt_desc("not a desc".to_string()), None);
+        assert_eq!(desc_error_sgt_desc(4, SINGLETON_4X4.to_string()), None);
+        assert_eq!(
+            desc_error_sgt_desc(5, SINGLETON_4X4.to_string()).unwrap(),
+            "desc appears to encode a 4x4 grid but n 5 was given"
+        );
+        let err = desc_error_sgt_desc(2, "b__,a3".to_string()).unwrap();
+        assert!(err.contains("clue stream ends"), "{err}");
+    }
+
+    #[test]
+    fn minimize_round_trips_through_desc() {
+        let minimized = minimize_sgt_desc(
+            4,
+            SINGLETON_4X4.to_string(),
+            SINGLETON_4X4_SOLUTION.to_vec(),
+        );
+        if !cfg!(feature = "gen") {
+            assert_eq!(minimized, None);
+            return;
+        }
+        let minimized = minimized.unwrap();
+        assert_eq!(minimized.cages_before, 16);
+        assert!(minimized.cages_after < minimized.cages_before);
+
+        let tier = DeductionTier::Hard;
+        let rules = Ruleset::keen_baseline();
+        assert_eq!(
+            count_solutions_sgt_desc(4, minimized.desc.clone(), tier, 2),
+            1
+        );
+        let solved = solve_sgt_desc(4, minimized.desc.clone(), tier).unwrap();
+        assert_eq!(solved.cells, SINGLETON_4X4_SOLUTION);
+        let puzzle = parse_keen_desc(4, &minimized.desc).unwrap();
+        assert_eq!(puzzle.cages.len() as u32, minimized.cages_after);
+        puzzle.validate(rules).unwrap();
+    }
+
+    #[test]
+    fn minimize_rejects_a_wrong_solution() {
+        let mut wrong = SINGLETON_4X4_SOLUTION.to_vec();
+        wrong.swap(0, 1);
+        assert_eq!(minimize_sgt_desc(4, SINGLETON_4X4.to_string(), wrong), None);
+        assert_eq!(
+            minimize_sgt_desc(4, SINGLETON_4X4.to_string(), vec![1; 4]),
+            None
+        );
+    }
+}
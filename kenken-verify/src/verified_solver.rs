@@ -3,6 +3,7 @@
 //! This module contains implementations translated from Rocq/Coq formalization
 //! with correctness proofs. Each function has a corresponding theorem in rcoq/
 
+use kenken_core::arith;
 use kenken_core::rules::{Op, Ruleset};
 use kenken_core::{Cage, Puzzle};
 
@@ -70,54 +71,24 @@ fn verify_cage_constraint(_n: u8, cage: &Cage, solution: &[u8]) -> Result<(), St
     let op = cage.op;
 
     match op {
-        Op::Add => {
-            let sum: u32 = values.iter().map(|&v| v as u32).sum();
-            if sum != target as u32 {
-                return Err(format!("Cage ADD sum {} != target {}", sum, cage.target));
-            }
-        }
-        Op::Sub => {
-            if values.len() != 2 {
-                return Err("Subtract cage must have 2 cells".to_string());
-            }
-            let diff = (values[0] as i32 - values[1] as i32).abs();
-            if diff != target {
-                return Err(format!("Cage SUB diff {} != target {}", diff, cage.target));
-            }
+        Op::Sub if values.len() != 2 => {
+            return Err("Subtract cage must have 2 cells".to_string());
         }
-        Op::Mul => {
-            let product: u32 = values.iter().map(|&v| v as u32).product();
-            if product != target as u32 {
-                return Err(format!(
-                    "Cage MUL product {} != target {}",
-                    product, cage.target
-                ));
-            }
-        }
-        Op::Div => {
-            if values.len() != 2 {
-                return Err("Divide cage must have 2 cells".to_string());
-            }
-            if values[1] == 0 {
-                return Err("Divide by zero".to_string());
-            }
-            let quot = values[0] / values[1];
-            let rem = values[0] % values[1];
-            if rem != 0 || quot as i32 != target {
-                return Err(format!(
-                    "Cage DIV quotient {} or remainder {} invalid",
-                    quot, rem
-                ));
-            }
+        Op::Div if values.len() != 2 => {
+            return Err("Divide cage must have 2 cells".to_string());
         }
-        Op::Eq => {
-            if values.len() != 1 {
-                return Err("Eq cage must have exactly 1 cell".to_string());
-            }
-            if values[0] as i32 != target {
-                return Err(format!("Cage EQ value {} != target {}", values[0], target));
-            }
+        Op::Eq if values.len() != 1 => {
+            return Err("Eq cage must have exactly 1 cell".to_string());
         }
+        _ => {}
+    }
+    if !arith::op_satisfied(op, target, &values) {
+        return Err(format!(
+            "Cage {} values {:?} do not meet target {}",
+            op.name().to_uppercase(),
+            values,
+            target
+        ));
     }
 
     Ok(())
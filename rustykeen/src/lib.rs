@@ -0,0 +1,146 @@
+#![forbid(unsafe_code)]
+#![doc = include_str!("../README.md")]
+
+#[cfg(feature = "io")]
+pub mod snapshot;
+
+pub use kenken_core::format::sgt_desc::{
+    SgtDescError, encode_keen_desc, parse_keen_desc, parse_keen_desc_auto,
+};
+pub use kenken_core::rules::{Op, Ruleset};
+pub use kenken_core::{Cage, CellId, CoreError, Puzzle, PuzzleBuilder};
+#[cfg(feature = "gen")]
+pub use kenken_gen::{GenError, GeneratedPuzzleWithStats, MinimizeResult};
+#[cfg(feature = "sat")]
+pub use kenken_solver::sat_latin::SatUniqueness;
+pub use kenken_solver::{Capabilities, DeductionTier, DifficultyTier, Solution, SolveError};
+#[cfg(feature = "verify")]
+pub use kenken_verify::verify_solution;
+
+/// Deduction tier the wrappers propagate with: the strongest, so search branches least.
+pub const DEFAULT_TIER: DeductionTier = DeductionTier::Hard;
+
+/// What this build of the facade can do: `generate` is set with the `gen` feature,
+/// `sat_varisat` with `sat`, and the grid range is the solver's.
+///
+/// ```
+/// let caps = rustykeen::capabilities();
+/// assert_eq!(caps.generate, cfg!(feature = "gen"));
+/// ```
+pub fn capabilities() -> Capabilities {
+    #[cfg(feature = "gen")]
+    {
+        kenken_gen::capabilities()
+    }
+    #[cfg(not(feature = "gen"))]
+    {
+        kenken_solver::capabilities()
+    }
+}
+
+/// Solve `puzzle` under the baseline rules. `None` if it has no solution; with several, the
+/// lexicographically smallest.
+///
+/// ```
+/// let puzzle = rustykeen::parse_keen_desc(4, "a3_aaba__a__a_,a7a12m3m24a5d2")?;
+/// let solution = rustykeen::solve(&puzzle)?.expect("solvable");
+/// assert_eq!(solution.grid[..4], [1, 2, 3, 4]);
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+pub fn solve(puzzle: &Puzzle) -> Result<Option<Solution>, SolveError> {
+    kenken_solver::solve_one_with_deductions_dispatched(
+        puzzle,
+        Ruleset::keen_baseline(),
+        DEFAULT_TIER,
+    )
+}
+
+/// Solutions of `puzzle` under the baseline rules, counted up to `limit`.
+pub fn count_solutions(puzzle: &Puzzle, limit: u32) -> Result<u32, SolveError> {
+    kenken_solver::count_solutions_up_to_with_deductions_dispatched(
+        puzzle,
+        Ruleset::keen_baseline(),
+        DEFAULT_TIER,
+        limit,
+    )
+}
+
+/// Whether `puzzle` has exactly one solution under the baseline rules.
+///
+/// ```
+/// let unique = rustykeen::parse_keen_desc(4, "a3_aaba__a__a_,a7a12m3m24a5d2")?;
+/// assert!(rustykeen::is_unique(&unique)?);
+/// let ambiguous = rustykeen::parse_keen_desc(2, "b__,a3a3")?;
+/// assert!(!rustykeen::is_unique(&ambiguous)?);
+/// assert_eq!(rustykeen::count_solutions(&ambiguous, 10)?, 2);
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+pub fn is_unique(puzzle: &Puzzle) -> Result<bool, SolveError> {
+    Ok(count_solutions(puzzle, 2)? == 1)
+}
+
+/// Difficulty of `puzzle` under the baseline rules, graded by the deduction tier it needs.
+/// `None` unless it has exactly one solution, since the grade reads the first one found.
+pub fn classify(puzzle: &Puzzle) -> Result<Option<DifficultyTier>, SolveError> {
+    // The uniqueness count is the cheaper of the two, so an ambiguous puzzle skips grading.
+    if !is_unique(puzzle)? {
+        return Ok(None);
+    }
+    let result =
+        kenken_solver::classify_tier_required_dispatched(puzzle, Ruleset::keen_baseline())?;
+    Ok(Some(kenken_solver::classify_difficulty_from_tier(result)))
+}
+
+/// [`kenken_solver::sat_cages::puzzle_uniqueness_via_sat`] under the baseline rules: an
+/// independent check of [`is_unique`].
+#[cfg(feature = "sat")]
+pub fn uniqueness_via_sat(puzzle: &Puzzle) -> SatUniqueness {
+    kenken_solver::sat_cages::puzzle_uniqueness_via_sat(puzzle, Ruleset::keen_baseline())
+}
+
+/// Generate a unique `n` x `n` puzzle of `difficulty` from `seed`, under the baseline rules.
+/// The same arguments give the same puzzle.
+///
+/// ```
+/// use rustykeen::DifficultyTier;
+///
+/// let generated = rustykeen::generate(4, 7, DifficultyTier::Easy)?;
+/// assert_eq!(generated.difficulty, DifficultyTier::Easy);
+/// assert!(rustykeen::is_unique(&generated.puzzle)?);
+/// let desc = rustykeen::encode_keen_desc(&generated.puzzle, rustykeen::Ruleset::keen_baseline())?;
+/// let parsed = rustykeen::parse_keen_desc(4, &desc)?;
+/// assert_eq!(rustykeen::solve(&parsed)?.expect("unique").grid, generated.solution);
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+#[cfg(feature = "gen")]
+pub fn generate(
+    n: u8,
+    seed: u64,
+    difficulty: DifficultyTier,
+) -> Result<GeneratedPuzzleWithStats, GenError> {
+    kenken_gen::generate_with_stats(kenken_gen::GenerateConfig::with_difficulty(
+        n, seed, difficulty,
+    ))
+}
+
+/// Merge cages of `puzzle` while it stays unique, deriving merged targets from `solution`
+/// (row-major digits), with [`kenken_gen::MinimizeConfig::keen_baseline`]. A `solution` that
+/// does not solve `puzzle` fails with [`GenError::Core`].
+///
+/// ```
+/// use rustykeen::DifficultyTier;
+///
+/// let generated = rustykeen::generate(4, 7, DifficultyTier::Easy)?;
+/// let before = generated.puzzle.cages.len();
+/// let minimized = rustykeen::minimize(generated.puzzle, &generated.solution)?;
+/// assert!(minimized.puzzle.cages.len() <= before);
+/// assert!(rustykeen::is_unique(&minimized.puzzle)?);
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+#[cfg(feature = "gen")]
+pub fn minimize(puzzle: Puzzle, solution: &[u8]) -> Result<MinimizeResult, GenError> {
+    let config = kenken_gen::MinimizeConfig::keen_baseline();
+    puzzle.validate(config.rules)?;
+    puzzle.check_solution(solution)?;
+    kenken_gen::minimize_puzzle(puzzle, solution, config)
+}
@@ -0,0 +1,31 @@
+//! Versioned binary snapshots of a puzzle and its ruleset ([`kenken_io::rkyv_snapshot`]).
+
+use kenken_io::rkyv_snapshot::{decode_snapshot, encode_puzzle_v4};
+
+pub use kenken_io::error::IoError;
+
+use crate::{Puzzle, Ruleset};
+
+/// Encode `puzzle` and `rules` in the latest snapshot format.
+///
+/// ```
+/// use rustykeen::{Ruleset, snapshot};
+///
+/// let puzzle = rustykeen::parse_keen_desc(4, "a3_aaba__a__a_,a7a12m3m24a5d2")?;
+/// let bytes = snapshot::save(&puzzle, Ruleset::keen_baseline())?;
+/// assert_eq!(snapshot::load(&bytes)?, (puzzle, Ruleset::keen_baseline()));
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+pub fn save(puzzle: &Puzzle, rules: Ruleset) -> Result<Vec<u8>, IoError> {
+    encode_puzzle_v4(puzzle, rules)
+}
+
+/// Decode a snapshot of any version. Version 1 records no ruleset, so it loads under the
+/// baseline.
+pub fn load(bytes: &[u8]) -> Result<(Puzzle, Ruleset), IoError> {
+    let decoded = decode_snapshot(bytes)?;
+    Ok((
+        decoded.puzzle,
+        decoded.rules.unwrap_or_else(Ruleset::keen_baseline),
+    ))
+}
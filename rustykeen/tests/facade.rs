@@ -0,0 +1,171 @@
+//! Each wrapper against the subcrate call it stands for, so the facade cannot drift.
+
+use kenken_solver::{
+    classify_difficulty_from_tier, classify_tier_required, count_solutions_up_to_with_deductions,
+    solve_one_with_deductions,
+};
+use rustykeen::{DEFAULT_TIER, DeductionTier, Puzzle, Ruleset};
+
+/// Unique 4x4 over `1234 / 4321 / 2143 / 3412`.
+const UNIQUE_4X4: &str = "a3_aaba__a__a_,a7a12m3m24a5d2";
+/// Two horizontal `3+` dominoes: two solutions.
+const AMBIGUOUS_2X2: &str = "b__,a3a3";
+
+fn puzzles() -> Vec<Puzzle> {
+    [(4, UNIQUE_4X4), (2, AMBIGUOUS_2X2), (2, "_5,a1a2a2a1")]
+        .into_iter()
+        .map(|(n, desc)| rustykeen::parse_keen_desc(n, desc).unwrap())
+        .collect()
+}
+
+#[test]
+fn the_default_tier_is_hard() {
+    assert_eq!(DEFAULT_TIER, DeductionTier::Hard);
+}
+
+#[test]
+fn descs_round_trip() {
+    let rules = Ruleset::keen_baseline();
+    for puzzle in puzzles() {
+        let desc = rustykeen::encode_keen_desc(&puzzle, rules).unwrap();
+        assert_eq!(rustykeen::parse_keen_desc_auto(&desc).unwrap(), puzzle);
+    }
+}
+
+#[test]
+fn solve_and_count_match_the_solver() {
+    let rules = Ruleset::keen_baseline();
+    for puzzle in puzzles() {
+        let expected = solve_one_with_deductions(&puzzle, rules, DeductionTier::Hard).unwrap();
+        assert_eq!(rustykeen::solve(&puzzle).unwrap(), expected);
+        for limit in [1, 2, 10] {
+            let expected =
+                count_solutions_up_to_with_deductions(&puzzle, rules, DeductionTier::Hard, limit)
+                    .unwrap();
+            assert_eq!(
+                rustykeen::count_solutions(&puzzle, limit).unwrap(),
+                expected
+            );
+        }
+        let unique = count_solutions_up_to_with_deductions(&puzzle, rules, DeductionTier::Hard, 2)
+            .unwrap()
+            == 1;
+        assert_eq!(rustykeen::is_unique(&puzzle).unwrap(), unique);
+    }
+}
+
+#[test]
+fn classify_grades_unique_puzzles_only() {
+    let rules = Ruleset::keen_baseline();
+    for puzzle in puzzles() {
+        let expected = rustykeen::is_unique(&puzzle).unwrap().then(|| {
+            classify_difficulty_from_tier(classify_tier_required(&puzzle, rules).unwrap())
+        });
+        assert_eq!(rustykeen::classify(&puzzle).unwrap(), expected);
+    }
+    let ambiguous = rustykeen::parse_keen_desc(2, AMBIGUOUS_2X2).unwrap();
+    assert_eq!(rustykeen::classify(&ambiguous).unwrap(), None);
+}
+
+#[test]
+fn wrappers_reject_unsupported_grids() {
+    let one_by_one = Puzzle {
+        n: 1,
+        cages: Vec::new(),
+    };
+    assert!(rustykeen::solve(&one_by_one).is_err());
+    assert!(rustykeen::count_solutions(&one_by_one, 2).is_err());
+    assert!(rustykeen::classify(&one_by_one).is_err());
+}
+
+#[cfg(feature = "gen")]
+#[test]
+fn generate_and_minimize_match_the_generator() {
+    use kenken_gen::{GenerateConfig, MinimizeConfig, generate_with_stats, minimize_puzzle};
+    use rustykeen::DifficultyTier;
+
+    for (n, difficulty) in [(4, DifficultyTier::Easy), (5, DifficultyTier::Normal)] {
+        let generated = rustykeen::generate(n, 11, difficulty).unwrap();
+        let expected =
+            generate_with_stats(GenerateConfig::with_difficulty(n, 11, difficulty)).unwrap();
+        assert_eq!(generated.puzzle, expected.puzzle);
+        assert_eq!(generated.solution, expected.solution);
+        assert_eq!(generated.difficulty, difficulty);
+        assert!(rustykeen::is_unique(&generated.puzzle).unwrap());
+
+        let minimized = rustykeen::minimize(generated.puzzle.clone(), &generated.solution).unwrap();
+        let expected = minimize_puzzle(
+            generated.puzzle.clone(),
+            &generated.solution,
+            MinimizeConfig::keen_baseline(),
+        )
+        .unwrap();
+        assert_eq!(minimized.puzzle, expected.puzzle);
+        assert!(rustykeen::is_unique(&minimized.puzzle).unwrap());
+    }
+}
+
+#[cfg(feature = "gen")]
+#[test]
+fn minimize_rejects_a_wrong_solution() {
+    let puzzle = rustykeen::parse_keen_desc(4, UNIQUE_4X4).unwrap();
+    let mut solution = rustykeen::solve(&puzzle).unwrap().unwrap().grid;
+    solution.swap(0, 1);
+    assert!(matches!(
+        rustykeen::minimize(puzzle, &solution),
+        Err(rustykeen::GenError::Core(_))
+    ));
+}
+
+#[cfg(feature = "sat")]
+#[test]
+fn sat_agrees_with_is_unique() {
+    use rustykeen::SatUniqueness;
+
+    for puzzle in puzzles() {
+        let expected = if rustykeen::is_unique(&puzzle).unwrap() {
+            SatUniqueness::Unique
+        } else {
+            SatUniqueness::Multiple
+        };
+        assert_eq!(rustykeen::uniqueness_via_sat(&puzzle), expected);
+    }
+}
+
+#[cfg(feature = "verify")]
+#[test]
+fn verify_solution_accepts_the_solvers_answer() {
+    let puzzle = rustykeen::parse_keen_desc(4, UNIQUE_4X4).unwrap();
+    let mut grid = rustykeen::solve(&puzzle).unwrap().unwrap().grid;
+    assert!(rustykeen::verify_solution(&puzzle, &grid).is_ok());
+    grid.swap(0, 1);
+    assert!(rustykeen::verify_solution(&puzzle, &grid).is_err());
+}
+
+#[cfg(feature = "io")]
+#[test]
+fn snapshots_round_trip_through_every_version() {
+    use kenken_io::rkyv_snapshot::{encode_puzzle_v1, encode_puzzle_v4};
+    use rustykeen::snapshot;
+
+    let permissive = Ruleset {
+        sub_div_two_cell_only: false,
+        ..Ruleset::keen_baseline()
+    };
+    for puzzle in puzzles() {
+        let bytes = snapshot::save(&puzzle, permissive).unwrap();
+        assert_eq!(bytes, encode_puzzle_v4(&puzzle, permissive).unwrap());
+        assert_eq!(
+            snapshot::load(&bytes).unwrap(),
+            (puzzle.clone(), permissive)
+        );
+
+        // v1 has no ruleset and loads under the baseline.
+        let v1 = encode_puzzle_v1(&puzzle).unwrap();
+        assert_eq!(
+            snapshot::load(&v1).unwrap(),
+            (puzzle, Ruleset::keen_baseline())
+        );
+    }
+    assert!(snapshot::load(b"not a snapshot").is_err());
+}
@@ -0,0 +1,38 @@
+#![forbid(unsafe_code)]
+#![doc = include_str!("../README.md")]
+
+/// Facade items gated on a feature. Each example below compiles exactly when its feature is on:
+/// with it the block is built (not run), without it the block is a `compile_fail` test.
+///
+/// `generate` and `minimize` need `gen`:
+///
+#[cfg_attr(feature = "gen", doc = "```no_run")]
+#[cfg_attr(not(feature = "gen"), doc = "```compile_fail")]
+/// let generated = rustykeen::generate(4, 7, rustykeen::DifficultyTier::Easy).unwrap();
+/// let _ = rustykeen::minimize(generated.puzzle, &generated.solution);
+/// ```
+///
+/// `snapshot` needs `io`:
+///
+#[cfg_attr(feature = "io", doc = "```no_run")]
+#[cfg_attr(not(feature = "io"), doc = "```compile_fail")]
+/// let puzzle = rustykeen::parse_keen_desc(2, "b__,a3a3").unwrap();
+/// let _ = rustykeen::snapshot::save(&puzzle, rustykeen::Ruleset::keen_baseline());
+/// ```
+///
+/// `uniqueness_via_sat` needs `sat`:
+///
+#[cfg_attr(feature = "sat", doc = "```no_run")]
+#[cfg_attr(not(feature = "sat"), doc = "```compile_fail")]
+/// let puzzle = rustykeen::parse_keen_desc(2, "b__,a3a3").unwrap();
+/// let _: rustykeen::SatUniqueness = rustykeen::uniqueness_via_sat(&puzzle);
+/// ```
+///
+/// `verify_solution` needs `verify`:
+///
+#[cfg_attr(feature = "verify", doc = "```no_run")]
+#[cfg_attr(not(feature = "verify"), doc = "```compile_fail")]
+/// let puzzle = rustykeen::parse_keen_desc(2, "b__,a3a3").unwrap();
+/// let _ = rustykeen::verify_solution(&puzzle, &[1, 2, 2, 1]);
+/// ```
+pub mod feature_gates {}
@@ -0,0 +1,48 @@
+//! Shared helpers: the solver's golden corpus, read through its public JSON lines format.
+
+// Each test binary compiles this module separately and uses a different subset of it.
+#![allow(dead_code)]
+
+use std::path::Path;
+
+use kenken_core::Puzzle;
+use kenken_core::format::sgt_desc::parse_keen_desc;
+
+/// One line of `kenken-solver/tests/corpus/golden.jsonl`, the fields these tests read.
+pub struct CorpusPuzzle {
+    pub label: String,
+    pub n: u8,
+    pub desc: String,
+    pub solutions: u32,
+    pub solution: Option<Vec<u8>>,
+}
+
+impl CorpusPuzzle {
+    pub fn puzzle(&self) -> Puzzle {
+        parse_keen_desc(self.n, &self.desc).unwrap_or_else(|e| panic!("{}: {e}", self.label))
+    }
+}
+
+/// Every golden puzzle of at most `max_n` cells a side.
+pub fn golden_corpus(max_n: u8) -> Vec<CorpusPuzzle> {
+    let path =
+        Path::new(env!("CARGO_MANIFEST_DIR")).join("../kenken-solver/tests/corpus/golden.jsonl");
+    let text = std::fs::read_to_string(&path).unwrap();
+    text.lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(|line| {
+            let value: serde_json::Value = serde_json::from_str(line).unwrap();
+            CorpusPuzzle {
+                label: value["label"].as_str().unwrap().to_string(),
+                n: value["n"].as_u64().unwrap() as u8,
+                desc: value["desc"].as_str().unwrap().to_string(),
+                solutions: value["solutions"].as_u64().unwrap() as u32,
+                solution: value["solution"]
+                    .as_array()
+                    .map(|cells| cells.iter().map(|c| c.as_u64().unwrap() as u8).collect()),
+            }
+        })
+        .filter(|entry| entry.n <= max_n)
+        .collect()
+}
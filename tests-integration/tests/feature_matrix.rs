@@ -0,0 +1,97 @@
+//! What each crate reports about the features it was built with, and the rejection a missing
+//! feature takes at runtime: generation without `gen-dlx` fails with `DlxRequired` at every
+//! entry point, and grids past 31 are refused without `u64`.
+
+use kenken_core::rules::Op;
+use kenken_core::rules::Ruleset;
+use kenken_core::{Cage, CellId, Puzzle};
+use kenken_gen::{
+    GenAlgoVersion, GenError, GenerateConfig, generate, generate_daily_set, generate_versioned,
+    generate_with_stats, replay_attempt,
+};
+use smallvec::smallvec;
+
+/// `kenken-uniffi/gen` turns on `gen-dlx` too, so the generator can outrun the facade.
+const GEN_DLX: bool = cfg!(any(feature = "gen", feature = "uniffi-gen"));
+
+/// Only `sat` reaches `kenken-solver/sat-varisat`, directly and through `rustykeen/sat`; `verify`
+/// links kenken-verify without it.
+const SAT_VARISAT: bool = cfg!(feature = "sat");
+
+#[test]
+fn capability_reports_match_the_features_built() {
+    let solver = kenken_solver::capabilities();
+    assert!(!solver.generate);
+    assert_eq!(solver.sat_varisat, SAT_VARISAT);
+    assert_eq!(solver.max_grid_size > 31, cfg!(feature = "u64"));
+
+    let generator = kenken_gen::capabilities();
+    assert_eq!(generator.generate, GEN_DLX);
+    assert_eq!(generator.max_grid_size, solver.max_grid_size);
+
+    let facade = rustykeen::capabilities();
+    assert_eq!(facade.generate, cfg!(feature = "gen"));
+    assert_eq!(facade.sat_varisat, SAT_VARISAT);
+    assert_eq!(facade.max_grid_size, solver.max_grid_size);
+
+    assert_eq!(
+        kenken_uniffi::capabilities().generate,
+        cfg!(feature = "uniffi-gen")
+    );
+}
+
+#[test]
+fn generation_without_dlx_fails_at_every_entry_point() {
+    let config = GenerateConfig::keen_baseline(4, 7);
+    let results = [
+        ("generate", generate(config).err()),
+        ("generate_with_stats", generate_with_stats(config).err()),
+        ("replay_attempt", replay_attempt(&config, 0).err()),
+        (
+            "generate_versioned",
+            generate_versioned(config, GenAlgoVersion::V1).err(),
+        ),
+        ("generate_daily_set", generate_daily_set(20261015, 4).err()),
+    ];
+    for (entry, error) in results {
+        if GEN_DLX {
+            assert!(error.is_none(), "{entry}: {error:?}");
+        } else {
+            assert!(
+                matches!(error, Some(GenError::DlxRequired)),
+                "{entry}: {error:?}"
+            );
+        }
+    }
+}
+
+#[test]
+fn grids_past_31_need_u64() {
+    let n = 32u8;
+    assert_eq!(
+        kenken_core::puzzle::validate_grid_size(n).is_ok(),
+        cfg!(feature = "u64")
+    );
+
+    // Every cell its own `=` cage over the cyclic Latin square, so propagation alone solves it.
+    let grid: Vec<u8> = (0..n)
+        .flat_map(|r| (0..n).map(move |c| (r + c) % n + 1))
+        .collect();
+    let cages = grid
+        .iter()
+        .enumerate()
+        .map(|(i, &value)| Cage {
+            cells: smallvec![CellId(i as u16)],
+            op: Op::Eq,
+            target: i64::from(value),
+        })
+        .collect();
+    let puzzle = Puzzle { n, cages };
+    let solved = kenken_solver::solve_one_dispatched(&puzzle, Ruleset::keen_baseline());
+    if cfg!(feature = "u64") {
+        assert_eq!(solved.unwrap().unwrap().grid, grid);
+    } else {
+        assert!(solved.is_err());
+        assert!(rustykeen::solve(&puzzle).is_err());
+    }
+}
@@ -0,0 +1,101 @@
+//! generate → minimize → classify → snapshot → load → re-verify, each step through a public
+//! API and each result checked by the next crate along.
+#![cfg(feature = "gen")]
+
+use kenken_core::Puzzle;
+use kenken_core::rules::Ruleset;
+use kenken_gen::{GenerateConfig, MinimizeConfig, generate_with_stats, minimize_puzzle};
+use kenken_solver::{
+    DeductionTier, DifficultyTier, classify_difficulty_from_tier, classify_tier_required,
+    count_solutions_up_to_with_deductions, solve_one_with_deductions,
+};
+
+/// The puzzle as saved and loaded again, or as is without `io`.
+#[cfg(feature = "io")]
+fn through_snapshot(puzzle: &Puzzle, rules: Ruleset) -> Puzzle {
+    use kenken_io::rkyv_snapshot::{SnapshotVersion, decode_snapshot, encode_puzzle_v4};
+
+    let bytes = encode_puzzle_v4(puzzle, rules).unwrap();
+    let decoded = decode_snapshot(&bytes).unwrap();
+    assert_eq!(decoded.version, SnapshotVersion::V4);
+    assert_eq!(decoded.rules, Some(rules));
+    let facade = rustykeen::snapshot::save(puzzle, rules).unwrap();
+    assert_eq!(
+        rustykeen::snapshot::load(&facade).unwrap(),
+        (decoded.puzzle.clone(), rules)
+    );
+    decoded.puzzle
+}
+
+#[cfg(not(feature = "io"))]
+fn through_snapshot(puzzle: &Puzzle, _rules: Ruleset) -> Puzzle {
+    puzzle.clone()
+}
+
+#[test]
+fn generated_puzzles_survive_the_whole_pipeline() {
+    let rules = Ruleset::keen_baseline();
+    for (n, seed) in [(4, 1), (5, 2), (6, 3)] {
+        let label = format!("n={n} seed={seed}");
+        let generated = generate_with_stats(GenerateConfig::keen_baseline(n, seed)).unwrap();
+        generated
+            .puzzle
+            .check_solution(&generated.solution)
+            .unwrap();
+
+        let minimized = minimize_puzzle(
+            generated.puzzle.clone(),
+            &generated.solution,
+            MinimizeConfig::keen_baseline(),
+        )
+        .unwrap();
+        assert!(
+            minimized.final_cage_count <= minimized.original_cage_count,
+            "{label}"
+        );
+        let puzzle = minimized.puzzle;
+        assert_eq!(
+            count_solutions_up_to_with_deductions(&puzzle, rules, DeductionTier::Hard, 2).unwrap(),
+            1,
+            "{label}"
+        );
+
+        let difficulty =
+            classify_difficulty_from_tier(classify_tier_required(&puzzle, rules).unwrap());
+        assert_eq!(
+            rustykeen::classify(&puzzle).unwrap(),
+            Some(difficulty),
+            "{label}"
+        );
+
+        let loaded = through_snapshot(&puzzle, rules);
+        assert_eq!(loaded, puzzle, "{label}");
+        assert_eq!(loaded.content_hash(), puzzle.content_hash(), "{label}");
+
+        let solved = solve_one_with_deductions(&loaded, rules, DeductionTier::Hard)
+            .unwrap()
+            .unwrap();
+        assert_eq!(solved.grid, generated.solution, "{label}");
+        kenken_verify::verify_solution(&loaded, &solved.grid)
+            .unwrap_or_else(|e| panic!("{label}: {e}"));
+        #[cfg(feature = "verify")]
+        rustykeen::verify_solution(&loaded, &solved.grid).unwrap();
+    }
+}
+
+#[test]
+fn facade_generation_matches_the_generator() {
+    let generated = rustykeen::generate(4, 7, DifficultyTier::Easy).unwrap();
+    assert_eq!(generated.difficulty, DifficultyTier::Easy);
+    let direct =
+        generate_with_stats(GenerateConfig::with_difficulty(4, 7, DifficultyTier::Easy)).unwrap();
+    assert_eq!(direct.puzzle, generated.puzzle);
+    assert_eq!(direct.solution, generated.solution);
+
+    let minimized = rustykeen::minimize(generated.puzzle.clone(), &generated.solution).unwrap();
+    assert!(rustykeen::is_unique(&minimized.puzzle).unwrap());
+    minimized
+        .puzzle
+        .check_solution(&generated.solution)
+        .unwrap();
+}
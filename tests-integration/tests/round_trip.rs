@@ -0,0 +1,72 @@
+//! parse → solve → encode → parse over the golden corpus, under whatever features are built.
+
+use kenken_core::format::sgt_desc::{encode_keen_desc, parse_keen_desc};
+use kenken_core::rules::Ruleset;
+use kenken_solver::{
+    DeductionTier, count_solutions_up_to_with_deductions_dispatched, solve_one_with_deductions,
+};
+
+mod common;
+use common::golden_corpus;
+
+#[test]
+fn corpus_round_trips_through_solve_and_encode() {
+    let rules = Ruleset::keen_baseline();
+    let corpus = golden_corpus(6);
+    assert!(corpus.len() >= 50, "corpus shrank to {}", corpus.len());
+    for entry in &corpus {
+        let label = &entry.label;
+        let puzzle = entry.puzzle();
+
+        let count = count_solutions_up_to_with_deductions_dispatched(
+            &puzzle,
+            rules,
+            DeductionTier::Hard,
+            entry.solutions + 1,
+        )
+        .unwrap();
+        assert_eq!(count, entry.solutions, "{label}");
+        assert_eq!(
+            rustykeen::count_solutions(&puzzle, entry.solutions + 1).unwrap(),
+            entry.solutions,
+            "{label}"
+        );
+
+        if let Some(expected) = &entry.solution {
+            let solved = solve_one_with_deductions(&puzzle, rules, DeductionTier::Hard)
+                .unwrap()
+                .unwrap();
+            assert_eq!(&solved.grid, expected, "{label}");
+            assert_eq!(
+                rustykeen::solve(&puzzle).unwrap().unwrap().grid,
+                solved.grid,
+                "{label}"
+            );
+            kenken_verify::verify_solution(&puzzle, &solved.grid)
+                .unwrap_or_else(|e| panic!("{label}: {e}"));
+            puzzle.check_solution(&solved.grid).unwrap();
+        }
+
+        let desc = encode_keen_desc(&puzzle, rules).unwrap();
+        let reparsed = parse_keen_desc(entry.n, &desc).unwrap();
+        assert_eq!(reparsed, puzzle, "{label}");
+        assert_eq!(reparsed.content_hash(), puzzle.content_hash(), "{label}");
+    }
+}
+
+#[test]
+fn checker_rejects_what_the_solver_rejects() {
+    for entry in golden_corpus(6) {
+        let Some(mut grid) = entry.solution.clone() else {
+            continue;
+        };
+        let puzzle = entry.puzzle();
+        grid.swap(0, 1);
+        assert!(puzzle.check_solution(&grid).is_err(), "{}", entry.label);
+        assert!(
+            kenken_verify::verify_solution(&puzzle, &grid).is_err(),
+            "{}",
+            entry.label
+        );
+    }
+}
@@ -0,0 +1,47 @@
+//! SAT and CP uniqueness verdicts agree, on the corpus (unique and ambiguous puzzles) and, with
+//! `gen`, on fresh ones.
+#![cfg(feature = "sat")]
+
+use kenken_core::Puzzle;
+use kenken_core::rules::Ruleset;
+use kenken_solver::sat_cages::puzzle_uniqueness_via_sat;
+use kenken_solver::sat_latin::SatUniqueness;
+use kenken_solver::{DeductionTier, count_solutions_up_to_with_deductions};
+
+mod common;
+use common::golden_corpus;
+
+fn check_agreement(label: &str, puzzle: &Puzzle) {
+    let rules = Ruleset::keen_baseline();
+    let count =
+        count_solutions_up_to_with_deductions(puzzle, rules, DeductionTier::Hard, 2).unwrap();
+    let expected = match count {
+        0 => SatUniqueness::Unsat,
+        1 => SatUniqueness::Unique,
+        _ => SatUniqueness::Multiple,
+    };
+    assert_eq!(
+        puzzle_uniqueness_via_sat(puzzle, rules),
+        expected,
+        "{label}"
+    );
+    assert_eq!(rustykeen::uniqueness_via_sat(puzzle), expected, "{label}");
+}
+
+#[test]
+fn sat_agrees_with_cp_on_the_corpus() {
+    for entry in golden_corpus(6) {
+        check_agreement(&entry.label, &entry.puzzle());
+    }
+}
+
+#[cfg(feature = "gen")]
+#[test]
+fn sat_agrees_with_cp_on_generated_puzzles() {
+    use kenken_gen::{GenerateConfig, generate};
+
+    for seed in 0..4 {
+        let generated = generate(GenerateConfig::keen_baseline(5, seed)).unwrap();
+        check_agreement(&format!("seed={seed}"), &generated.puzzle);
+    }
+}
@@ -0,0 +1,101 @@
+//! The kenken-uniffi entry points against the crates they wrap, with and without `uniffi-gen`.
+
+use kenken_core::format::sgt_desc::parse_keen_desc;
+use kenken_core::rules::Ruleset;
+use kenken_solver::{
+    DeductionTier, classify_difficulty_from_tier, classify_tier_required,
+    count_solutions_up_to_with_deductions,
+};
+use kenken_uniffi::{
+    Grid, capabilities, classify_sgt_desc, count_solutions_sgt_desc, daily_set, generate_sgt_desc,
+    minimize_sgt_desc, solve_sgt_desc,
+};
+
+mod common;
+use common::golden_corpus;
+
+#[test]
+fn solve_count_and_classify_agree_with_the_solver() {
+    let rules = Ruleset::keen_baseline();
+    for entry in golden_corpus(5) {
+        let label = &entry.label;
+        let puzzle = entry.puzzle();
+        let count = count_solutions_sgt_desc(entry.n, entry.desc.clone(), DeductionTier::Hard, 13);
+        assert_eq!(count, entry.solutions, "{label}");
+
+        let grid = solve_sgt_desc(entry.n, entry.desc.clone(), DeductionTier::Hard).unwrap();
+        assert_eq!(grid.n, entry.n);
+        puzzle.check_solution(&grid.cells).unwrap();
+        if let Some(expected) = &entry.solution {
+            assert_eq!(&grid.cells, expected, "{label}");
+        }
+
+        let info = classify_sgt_desc(entry.n, entry.desc.clone());
+        if entry.solutions == 1 {
+            let result = classify_tier_required(&puzzle, rules).unwrap();
+            let info = info.unwrap();
+            assert_eq!(
+                info.difficulty,
+                classify_difficulty_from_tier(result),
+                "{label}"
+            );
+            assert_eq!(info.tier_required, result.tier_required, "{label}");
+        } else {
+            assert_eq!(info, None, "{label}");
+        }
+    }
+}
+
+#[test]
+fn malformed_descs_fail_softly() {
+    assert_eq!(
+        solve_sgt_desc(4, "not a desc".into(), DeductionTier::Hard),
+        None
+    );
+    assert_eq!(
+        count_solutions_sgt_desc(4, "b__,a3a3".into(), DeductionTier::Hard, 2),
+        0
+    );
+    assert_eq!(classify_sgt_desc(3, String::new()), None);
+}
+
+#[test]
+fn generation_entry_points_follow_the_gen_feature() {
+    let enabled = cfg!(feature = "uniffi-gen");
+    assert_eq!(capabilities().generate, enabled);
+
+    let generated = generate_sgt_desc(4, 7, DeductionTier::Normal);
+    assert_eq!(generated.is_some(), enabled);
+    let daily = daily_set(20261015, 4);
+    assert_eq!(daily.is_some(), enabled);
+    let Some(generated) = generated else {
+        assert_eq!(
+            minimize_sgt_desc(2, "_5,a1a2a2a1".into(), vec![1, 2, 2, 1]),
+            None
+        );
+        return;
+    };
+
+    let rules = Ruleset::keen_baseline();
+    let puzzle = parse_keen_desc(4, &generated.desc).unwrap();
+    assert_eq!(
+        count_solutions_up_to_with_deductions(&puzzle, rules, DeductionTier::Hard, 2).unwrap(),
+        1
+    );
+    assert_eq!(
+        solve_sgt_desc(4, generated.desc.clone(), DeductionTier::Hard),
+        Some(generated.solution.clone())
+    );
+    kenken_verify::verify_solution(&puzzle, &generated.solution.cells).unwrap();
+
+    let Grid { cells, .. } = generated.solution;
+    let minimized = minimize_sgt_desc(4, generated.desc, cells.clone()).unwrap();
+    assert!(minimized.cages_after <= minimized.cages_before);
+    let minimized = parse_keen_desc(4, &minimized.desc).unwrap();
+    minimized.check_solution(&cells).unwrap();
+
+    for slot in daily.unwrap() {
+        let puzzle = parse_keen_desc(4, &slot.desc).unwrap();
+        puzzle.check_solution(&slot.solution.cells).unwrap();
+    }
+}